@@ -3,19 +3,22 @@ pub(crate) use xitca_http::{
     error::BodyError,
 };
 
-#[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
+#[cfg(any(feature = "http2", feature = "http3"))]
 pub(crate) use xitca_http::body::BodySize;
 
 use core::{
-    fmt,
+    cmp, fmt,
     pin::Pin,
-    task::{Context, Poll},
+    task::{ready, Context, Poll},
 };
 
+use std::io;
+
 use futures_core::stream::Stream;
 use pin_project_lite::pin_project;
+use xitca_io::io::{AsyncRead, ReadBuf};
 
-use crate::bytes::Bytes;
+use crate::bytes::{Bytes, BytesMut};
 
 #[allow(clippy::large_enum_variant)]
 pub enum ResponseBody<'c> {
@@ -158,3 +161,99 @@ where
         self.body.size_hint()
     }
 }
+
+pin_project! {
+    /// stream body reading from [AsyncRead] type.
+    pub(crate) struct ReaderBody<R> {
+        #[pin]
+        reader: R,
+        buf: BytesMut,
+        remaining: Option<u64>,
+    }
+}
+
+impl<R> ReaderBody<R> {
+    // read chunk size of reader.
+    const CHUNK_SIZE: usize = 8 * 1024;
+
+    pub(crate) fn new(reader: R, len: Option<u64>) -> Self {
+        Self {
+            reader,
+            buf: BytesMut::new(),
+            remaining: len,
+        }
+    }
+}
+
+impl<R> Stream for ReaderBody<R>
+where
+    R: AsyncRead,
+{
+    type Item = Result<Bytes, BodyError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        let len = match *this.remaining {
+            Some(0) => return Poll::Ready(None),
+            Some(remaining) => cmp::min(remaining, Self::CHUNK_SIZE as u64) as usize,
+            None => Self::CHUNK_SIZE,
+        };
+
+        this.buf.resize(len, 0);
+        let mut buf = ReadBuf::new(&mut this.buf[..]);
+        ready!(this.reader.poll_read(cx, &mut buf))?;
+
+        let n = buf.filled().len();
+        if n == 0 {
+            // reader with known length ending early is an error.
+            let err = this
+                .remaining
+                .take()
+                .map(|_| Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()));
+            *this.remaining = Some(0);
+            return Poll::Ready(err);
+        }
+
+        if let Some(remaining) = this.remaining.as_mut() {
+            *remaining -= n as u64;
+        }
+
+        Poll::Ready(Some(Ok(this.buf.split_to(n).freeze())))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => {
+                let remaining = usize::try_from(remaining).unwrap_or(usize::MAX);
+                (remaining, Some(remaining))
+            }
+            None => (0, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::{executor::block_on, StreamExt};
+
+    use super::*;
+
+    #[test]
+    fn reader_body() {
+        let body = ReaderBody::new(&b"hello,world!"[..], Some(5));
+        assert_eq!(body.size_hint(), (5, Some(5)));
+        let chunks = block_on(body.collect::<Vec<_>>());
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].as_ref().unwrap().as_ref(), b"hello");
+
+        let body = ReaderBody::new(&b"hello"[..], Some(6));
+        let chunks = block_on(body.collect::<Vec<_>>());
+        assert!(chunks[1].is_err());
+
+        let body = ReaderBody::new(&b"hello"[..], None);
+        assert_eq!(body.size_hint(), (0, None));
+        let chunks = block_on(body.collect::<Vec<_>>());
+        assert_eq!(chunks.len(), 1);
+    }
+}
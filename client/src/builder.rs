@@ -1,6 +1,12 @@
 use core::{net::SocketAddr, time::Duration};
 
-use xitca_http::http::version::Version;
+use xitca_http::{
+    config::DEFAULT_READ_BUF_LIMIT,
+    http::{
+        header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+        version::Version,
+    },
+};
 
 use crate::{
     client::Client,
@@ -27,7 +33,11 @@ pub struct ClientBuilder {
     timeout_config: TimeoutConfig,
     local_addr: Option<SocketAddr>,
     max_http_version: Version,
+    default_headers: HeaderMap,
+    max_response_head_size: usize,
     service: HttpService,
+    #[cfg(feature = "http2")]
+    h2_keep_alive: crate::h2::proto::KeepAlive,
 }
 
 impl Default for ClientBuilder {
@@ -45,7 +55,11 @@ impl ClientBuilder {
             timeout_config: TimeoutConfig::new(),
             local_addr: None,
             max_http_version: max_http_version(),
+            default_headers: default_headers(),
+            max_response_head_size: DEFAULT_READ_BUF_LIMIT,
             service: base_service(),
+            #[cfg(feature = "http2")]
+            h2_keep_alive: None,
         }
     }
 
@@ -126,11 +140,46 @@ impl ClientBuilder {
 
     #[cfg(any(feature = "rustls", feature = "rustls-ring-crypto"))]
     /// enable rustls as tls connector.
+    ///
+    /// tls session resumption is enabled with an in memory cache of 256 sessions shared by all connections of
+    /// [Client]. See [ClientBuilder::rustls_with_config] for customizing it.
     pub fn rustls(mut self) -> Self {
         self.connector = connector::rustls::connect(self.alpn_from_version());
         self
     }
 
+    #[cfg(any(feature = "rustls", feature = "rustls-ring-crypto"))]
+    /// enable rustls as tls connector with given client config.
+    ///
+    /// The config is shared by all connections of [Client] and it's session resumption setting decides how tls
+    /// sessions(tickets and session ids) are cached and resumed. When config has no alpn protocol it's derived
+    /// from [ClientBuilder::set_max_http_version] like [ClientBuilder::rustls] does.
+    ///
+    /// # Note
+    /// early data(0-RTT) is not sent even if it's enabled in config as request is written after tls handshake is
+    /// finished.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_client::Client;
+    /// use xitca_tls::rustls::{client::Resumption, ClientConfig, RootCertStore};
+    ///
+    /// # fn config(root_certs: RootCertStore) {
+    /// let mut config = ClientConfig::builder()
+    ///     .with_root_certificates(root_certs)
+    ///     .with_no_client_auth();
+    ///
+    /// // cache up to 1024 sessions for resumption.
+    /// config.resumption = Resumption::in_memory_sessions(1024);
+    ///
+    /// let client = Client::builder().rustls_with_config(config).finish();
+    /// # }
+    /// ```
+    pub fn rustls_with_config(mut self, config: xitca_tls::rustls::ClientConfig) -> Self {
+        self.connector = connector::rustls::connect_with_config(config, self.alpn_from_version());
+        self
+    }
+
     #[cfg(any(feature = "openssl", feature = "rustls", feature = "rustls-ring-crypto"))]
     const fn alpn_from_version(&self) -> &[&[u8]] {
         match self.max_http_version {
@@ -282,8 +331,8 @@ impl ClientBuilder {
     ///
     /// # Note
     /// capacity is for concurrent opening sockets PER remote Domain.
-    /// capacity only applies to http/1 protocol.
-    /// http/2 always open one socket per remote domain.
+    /// http/2 open additional socket only when existing ones reached max concurrent streams allowed by server
+    /// and requests are dispatched to the least loaded socket.
     /// http/3 always open one socket for all remote domains.
     ///
     /// Default to 2
@@ -296,6 +345,16 @@ impl ClientBuilder {
         self
     }
 
+    /// Enable keep-alive ping for idle http/2 connections. Ping is sent every `interval` when connection has no
+    /// active stream and connection is closed and evicted from pool when pong is not received within `timeout`.
+    ///
+    /// Default to disabled.
+    #[cfg(feature = "http2")]
+    pub fn set_h2_keep_alive(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.h2_keep_alive = Some((interval, timeout));
+        self
+    }
+
     /// Set max http version client would be used.
     ///
     /// Default to the max version of http feature enabled within Cargo.toml
@@ -324,6 +383,65 @@ impl ClientBuilder {
         self
     }
 
+    /// Set a default header that would be added to every request sent by [Client].
+    ///
+    /// Existing default header with the same name would be replaced. Default headers never override headers
+    /// already present in request and can be overridden or removed per request with [RequestBuilder::header]
+    /// and [RequestBuilder::remove_header].
+    ///
+    /// By default [USER_AGENT] header is set with value: `xitca-client/<version>`. `Host` header is always
+    /// derived from request uri when it's not present.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_client::{
+    ///     http::header::{HeaderValue, ACCEPT, USER_AGENT},
+    ///     Client,
+    /// };
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let client = Client::builder()
+    ///     .default_header(ACCEPT, HeaderValue::from_static("application/json"))
+    ///     .default_header(USER_AGENT, HeaderValue::from_static("my-agent"))
+    ///     .finish();
+    ///
+    /// // request would be sent with default headers.
+    /// let req = client.get("http://localhost");
+    /// assert_eq!(req.headers().get(ACCEPT).unwrap(), "application/json");
+    ///
+    /// // default headers can be overridden or removed per request.
+    /// let req = client
+    ///     .get("http://localhost")
+    ///     .header(ACCEPT, HeaderValue::from_static("text/plain"))
+    ///     .remove_header(USER_AGENT);
+    /// assert_eq!(req.headers().get(ACCEPT).unwrap(), "text/plain");
+    /// assert!(!req.headers().contains_key(USER_AGENT));
+    /// # }
+    /// ```
+    ///
+    /// [RequestBuilder::header]: crate::RequestBuilder::header
+    /// [RequestBuilder::remove_header]: crate::RequestBuilder::remove_header
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Set max size in bytes of http/1 response head. Response with head larger than it would be rejected with
+    /// error and it's connection is closed.
+    ///
+    /// Default to [DEFAULT_READ_BUF_LIMIT]
+    pub fn set_max_response_head_size(mut self, size: usize) -> Self {
+        self.max_response_head_size = size;
+        self
+    }
+
+    /// Remove a default header by name. e.g: remove default [USER_AGENT] header.
+    pub fn remove_default_header(mut self, name: HeaderName) -> Self {
+        self.default_headers.remove(name);
+        self
+    }
+
     /// Finish the builder and construct [Client] instance.
     pub fn finish(self) -> Client {
         #[cfg(feature = "http3")]
@@ -432,14 +550,27 @@ impl ClientBuilder {
             timeout_config: self.timeout_config,
             max_http_version: self.max_http_version,
             local_addr: self.local_addr,
+            default_headers: self.default_headers,
+            max_response_head_size: self.max_response_head_size,
             date_service: DateTimeService::new(),
             service: self.service,
+            #[cfg(feature = "http2")]
+            h2_keep_alive: self.h2_keep_alive,
             #[cfg(feature = "http3")]
             h3_client,
         }
     }
 }
 
+fn default_headers() -> HeaderMap {
+    let mut headers = HeaderMap::with_capacity(1);
+    headers.insert(
+        USER_AGENT,
+        HeaderValue::from_static(concat!("xitca-client/", env!("CARGO_PKG_VERSION"))),
+    );
+    headers
+}
+
 pub(crate) fn version_check(version: Version) {
     match (max_http_version(), version) {
         (Version::HTTP_3, _) => {}
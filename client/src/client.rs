@@ -19,6 +19,7 @@ use crate::{
     resolver::ResolverService,
     service::HttpService,
     timeout::{Timeout, TimeoutConfig},
+    timing::{elapsed, Timing},
     tls::connector::Connector,
     uri::Uri,
 };
@@ -35,8 +36,12 @@ pub struct Client {
     pub(crate) timeout_config: TimeoutConfig,
     pub(crate) max_http_version: Version,
     pub(crate) local_addr: Option<SocketAddr>,
+    pub(crate) default_headers: http::HeaderMap,
+    pub(crate) max_response_head_size: usize,
     pub(crate) date_service: DateTimeService,
     pub(crate) service: HttpService,
+    #[cfg(feature = "http2")]
+    pub(crate) h2_keep_alive: crate::h2::proto::KeepAlive,
     #[cfg(feature = "http3")]
     pub(crate) h3_client: h3_quinn::quinn::Endpoint,
 }
@@ -273,10 +278,11 @@ impl Client {
         connect: &mut Connect<'_>,
         timer: &mut Pin<Box<Sleep>>,
         expected_version: Version,
+        timing: &mut Timing,
     ) -> Result<(ConnectionExclusive, Version), Error> {
         match connect.uri {
             Uri::Tcp(_) | Uri::Tls(_) => {
-                let conn = self.make_tcp(connect, timer).await?;
+                let conn = self.make_tcp(connect, timer, timing).await?;
 
                 if matches!(connect.uri, Uri::Tcp(_)) {
                     return Ok((conn, expected_version));
@@ -286,6 +292,8 @@ impl Client {
                     .as_mut()
                     .reset(Instant::now() + self.timeout_config.tls_connect_timeout);
 
+                let start = Instant::now();
+
                 let (conn, version) = self
                     .connector
                     .call((connect.hostname(), conn))
@@ -293,10 +301,12 @@ impl Client {
                     .await
                     .map_err(|_| TimeoutError::TlsHandshake)??;
 
+                elapsed(start, &mut timing.tls);
+
                 Ok((conn, version))
             }
             Uri::Unix(_) => self
-                .make_unix(connect, timer)
+                .make_unix(connect, timer, timing)
                 .await
                 .map(|conn| (conn, expected_version)),
         }
@@ -306,16 +316,21 @@ impl Client {
         &self,
         connect: &mut Connect<'_>,
         timer: &mut Pin<Box<Sleep>>,
+        timing: &mut Timing,
     ) -> Result<ConnectionExclusive, Error> {
+        let start = Instant::now();
+
         self.resolver
             .call(connect)
             .timeout(timer.as_mut())
             .await
             .map_err(|_| TimeoutError::Resolve)??;
 
-        timer
-            .as_mut()
-            .reset(Instant::now() + self.timeout_config.connect_timeout);
+        elapsed(start, &mut timing.dns);
+
+        let start = Instant::now();
+
+        timer.as_mut().reset(start + self.timeout_config.connect_timeout);
 
         let stream = self
             .make_tcp_inner(connect)
@@ -323,6 +338,8 @@ impl Client {
             .await
             .map_err(|_| TimeoutError::Connect)??;
 
+        elapsed(start, &mut timing.connect);
+
         // TODO: make nodelay configurable?
         let _ = stream.set_nodelay(true);
 
@@ -373,10 +390,11 @@ impl Client {
         &self,
         _connect: &Connect<'_>,
         timer: &mut Pin<Box<Sleep>>,
+        _timing: &mut Timing,
     ) -> Result<ConnectionExclusive, Error> {
-        timer
-            .as_mut()
-            .reset(Instant::now() + self.timeout_config.connect_timeout);
+        let _start = Instant::now();
+
+        timer.as_mut().reset(_start + self.timeout_config.connect_timeout);
 
         #[cfg(unix)]
         {
@@ -391,6 +409,8 @@ impl Client {
                 .await
                 .map_err(|_| TimeoutError::Connect)??;
 
+            elapsed(_start, &mut _timing.connect);
+
             Ok(Box::new(stream))
         }
 
@@ -2,7 +2,7 @@ use core::hash::{Hash, Hasher};
 
 use xitca_http::http::uri::{Authority, PathAndQuery};
 
-use super::{tls::TlsStream, uri::Uri};
+use super::{pool::shared::SharedConnection, tls::TlsStream, uri::Uri};
 
 #[cfg(feature = "http1")]
 /// A convince type alias for typing connection without interacting with pool.
@@ -39,6 +39,47 @@ impl From<crate::h3::Connection> for ConnectionShared {
     }
 }
 
+impl SharedConnection for ConnectionShared {
+    fn load(&self) -> usize {
+        match *self {
+            #[cfg(feature = "http2")]
+            Self::H2(ref conn) => conn.active_streams(),
+            #[cfg(feature = "http3")]
+            Self::H3(_) => 0,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        match *self {
+            #[cfg(feature = "http2")]
+            Self::H2(ref conn) => conn.is_full(),
+            #[cfg(feature = "http3")]
+            Self::H3(_) => false,
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        match *self {
+            #[cfg(feature = "http2")]
+            Self::H2(ref conn) => conn.is_closed(),
+            #[cfg(feature = "http3")]
+            Self::H3(_) => false,
+        }
+    }
+
+    fn is_same(&self, _other: &Self) -> bool {
+        match (self, _other) {
+            #[cfg(feature = "http2")]
+            (Self::H2(conn), Self::H2(other)) => conn.is_same(other),
+            // http/3 connection is never full and there is only one of it for every key.
+            #[cfg(feature = "http3")]
+            (Self::H3(_), Self::H3(_)) => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
 #[doc(hidden)]
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
 pub enum ConnectionKey {
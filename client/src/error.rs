@@ -4,7 +4,7 @@ use core::{convert::Infallible, fmt, str};
 
 use std::{error, io};
 
-use super::http::{uri, StatusCode};
+use super::http::{uri, HeaderValue, StatusCode};
 
 #[derive(Debug)]
 #[non_exhaustive]
@@ -171,12 +171,39 @@ impl From<ResolveError> for Error {
 #[derive(Debug)]
 pub enum ParseError {
     String(str::Utf8Error),
+    Charset(CharsetError),
     #[cfg(feature = "json")]
     Json(serde_json::Error),
     #[cfg(feature = "websocket")]
     WebSocket(http_ws::ProtocolError),
 }
 
+/// error type for response body can not be decoded with it's charset.
+#[derive(Debug)]
+pub enum CharsetError {
+    /// charset label is not known or not supported. non UTF-8 charsets require `charset` crate feature.
+    Unknown(Box<str>),
+    /// response body contains malformed byte sequence for charset.
+    Malformed(&'static str),
+}
+
+impl fmt::Display for CharsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(label) => write!(f, "charset {label} is not supported"),
+            Self::Malformed(name) => write!(f, "response body is not valid {name}"),
+        }
+    }
+}
+
+impl error::Error for CharsetError {}
+
+impl From<CharsetError> for Error {
+    fn from(e: CharsetError) -> Self {
+        Self::Parse(ParseError::Charset(e))
+    }
+}
+
 #[cfg(feature = "websocket")]
 impl From<http_ws::ProtocolError> for Error {
     fn from(e: http_ws::ProtocolError) -> Self {
@@ -285,6 +312,51 @@ impl From<ErrorResponse> for Error {
     }
 }
 
+/// error type for response body exceeding given size limit.
+#[derive(Debug)]
+pub struct PayloadLimitError {
+    pub limit: usize,
+}
+
+impl fmt::Display for PayloadLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "response body exceeds size limit of {} bytes", self.limit)
+    }
+}
+
+impl error::Error for PayloadLimitError {}
+
+impl From<PayloadLimitError> for Error {
+    fn from(e: PayloadLimitError) -> Self {
+        Self::Std(Box::new(e))
+    }
+}
+
+/// error type for response with unexpected content type.
+#[derive(Debug)]
+pub struct ContentTypeError {
+    pub expect: &'static str,
+    pub content_type: Option<HeaderValue>,
+}
+
+impl fmt::Display for ContentTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expecting response with content type {}, ", self.expect)?;
+        match self.content_type {
+            Some(ref ct) => write!(f, "got {ct:?} instead"),
+            None => f.write_str("got none instead"),
+        }
+    }
+}
+
+impl error::Error for ContentTypeError {}
+
+impl From<ContentTypeError> for Error {
+    fn from(e: ContentTypeError) -> Self {
+        Self::Std(Box::new(e))
+    }
+}
+
 #[derive(Debug)]
 pub enum FeatureError {
     Http1NotEnabled,
@@ -61,6 +61,10 @@ where
                     match xitca_unsafe_collection::bytes::read_buf(&mut *this.conn, &mut this.buf) {
                         Ok(n) => {
                             if n == 0 {
+                                // body delimited by connection close ends at eof.
+                                if this.decoder.is_upgrade() {
+                                    return Poll::Ready(None);
+                                }
                                 return Poll::Ready(Some(Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())));
                             }
                             break 'inner;
@@ -3,7 +3,11 @@ use core::{future::poll_fn, pin::Pin};
 use std::io;
 
 use futures_core::stream::Stream;
-use xitca_http::{body::BodySize, bytes::Buf, h1::proto::codec::TransferCoding};
+use xitca_http::{
+    body::BodySize,
+    bytes::Buf,
+    h1::proto::{codec::TransferCoding, error::ProtoError},
+};
 use xitca_io::io::{AsyncIo, Interest};
 
 use crate::{
@@ -11,10 +15,7 @@ use crate::{
     bytes::{Bytes, BytesMut},
     date::DateTimeHandle,
     h1::Error,
-    http::{
-        header::{HeaderValue, EXPECT, HOST},
-        Method, Request, Response, StatusCode,
-    },
+    http::{header::EXPECT, Request, Response, StatusCode},
 };
 
 use super::context::Context;
@@ -23,6 +24,7 @@ pub(crate) async fn send<S, B, E>(
     stream: &mut S,
     date: DateTimeHandle<'_>,
     req: &mut Request<B>,
+    max_head_size: usize,
 ) -> Result<(Response<()>, BytesMut, TransferCoding, bool), Error>
 where
     S: AsyncIo + Unpin,
@@ -31,27 +33,6 @@ where
 {
     let mut buf = BytesMut::new();
 
-    if !req.headers().contains_key(HOST) {
-        if let Some(host) = req.uri().host() {
-            buf.reserve(host.len() + 5);
-            buf.extend_from_slice(host.as_bytes());
-
-            if let Some(port) = req.uri().port() {
-                let port = port.as_str();
-                match port {
-                    "80" | "443" => {}
-                    _ => {
-                        buf.extend_from_slice(b":");
-                        buf.extend_from_slice(port.as_bytes());
-                    }
-                }
-            }
-
-            let val = HeaderValue::from_maybe_shared(buf.split().freeze()).unwrap();
-            req.headers_mut().insert(HOST, val);
-        }
-    }
-
     let mut is_expect = req.headers().contains_key(EXPECT);
 
     if is_expect {
@@ -72,13 +53,7 @@ where
     let mut ctx = Context::<128>::new(&date);
 
     // encode request head and return transfer encoding for request body
-    let encoder = ctx.encode_head(&mut buf, req)?;
-
-    // it's important to call set_head_method after encode_head. Context would remove http body it encodes/decodes
-    // for head http method.
-    if *req.method() == Method::HEAD {
-        ctx.set_head_method();
-    }
+    let encoder = ctx.encode_request_head(req, &mut buf)?;
 
     write_all_buf(stream, &mut buf).await?;
 
@@ -86,17 +61,13 @@ where
         flush(stream).await?;
 
         loop {
-            if let Some((res, mut decoder)) = try_read_response(stream, &mut buf, &mut ctx).await? {
+            if let Some((res, decoder)) = try_read_response(stream, &mut buf, &mut ctx, max_head_size).await? {
                 if res.status() == StatusCode::CONTINUE {
                     break;
                 }
 
                 let is_close = ctx.is_connection_closed();
 
-                if ctx.is_head_method() {
-                    decoder = TransferCoding::eof();
-                }
-
                 return Ok((res, buf, decoder, is_close));
             }
         }
@@ -117,7 +88,7 @@ where
 
     // read response head and get body decoder.
     loop {
-        if let Some((res, mut decoder)) = try_read_response(stream, &mut buf, &mut ctx).await? {
+        if let Some((res, decoder)) = try_read_response(stream, &mut buf, &mut ctx, max_head_size).await? {
             // check if server sent connection close header.
 
             // *. If send_body function produces error, Context has already set
@@ -127,10 +98,6 @@ where
 
             let is_close = ctx.is_connection_closed();
 
-            if ctx.is_head_method() {
-                decoder = TransferCoding::eof();
-            }
-
             return Ok((res, buf, decoder, is_close));
         }
     }
@@ -208,6 +175,7 @@ async fn try_read_response<S>(
     stream: &mut S,
     buf: &mut BytesMut,
     ctx: &mut Context<'_, '_, 128>,
+    max_head_size: usize,
 ) -> Result<Option<(Response<()>, TransferCoding)>, Error>
 where
     S: AsyncIo,
@@ -218,8 +186,12 @@ where
                 return if n == 0 {
                     Err(Error::from(io::Error::from(io::ErrorKind::UnexpectedEof)))
                 } else {
-                    ctx.decode_head(buf).map_err(Into::into)
-                }
+                    // limit is runtime configurable and checked here instead of through const generic param.
+                    match ctx.decode_response_head::<{ usize::MAX }>(buf)? {
+                        None if buf.len() >= max_head_size => Err(ProtoError::HeaderTooLarge.into()),
+                        res => Ok(res),
+                    }
+                };
             }
             Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
                 stream.ready(Interest::READABLE).await?;
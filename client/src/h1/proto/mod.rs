@@ -1,6 +1,4 @@
 mod context;
-mod decode;
 mod dispatcher;
-mod encode;
 
 pub(crate) use dispatcher::send;
@@ -11,6 +11,7 @@ use futures_core::stream::Stream;
 use crate::{
     body::BodyError,
     bytes::{Buf, Bytes, BytesMut},
+    h2::connection::StreamGuard,
 };
 
 type Tx = h2::SendStream<Bytes>;
@@ -24,14 +25,16 @@ pub struct ResponseBody {
     // TODO: use new type and import from xitca_http?
     pub(crate) tx: Tx,
     want_poll_cap: bool,
+    _guard: StreamGuard,
 }
 
 impl ResponseBody {
-    pub(super) fn new(tx: Tx, rx: RequestBody) -> Self {
+    pub(super) fn new(tx: Tx, rx: RequestBody, guard: StreamGuard) -> Self {
         Self {
             tx,
             rx,
             want_poll_cap: false,
+            _guard: guard,
         }
     }
 
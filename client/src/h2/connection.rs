@@ -0,0 +1,109 @@
+use core::{
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use std::sync::Arc;
+
+use h2::client::SendRequest;
+
+use crate::bytes::Bytes;
+
+/// shared http/2 connection. cloned connections send requests over the same socket.
+///
+/// Connection tracks it's in flight streams and liveness so that the connection pool can prefer less loaded
+/// connections and evict dead ones.
+#[derive(Clone)]
+pub struct Connection {
+    tx: SendRequest<Bytes>,
+    state: Arc<State>,
+}
+
+#[derive(Default)]
+pub(crate) struct State {
+    active_streams: AtomicUsize,
+    closed: AtomicBool,
+}
+
+impl State {
+    pub(crate) fn active_streams(&self) -> usize {
+        self.active_streams.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+    }
+}
+
+impl Connection {
+    pub(crate) fn new(tx: SendRequest<Bytes>) -> Self {
+        Self {
+            tx,
+            state: Arc::new(State::default()),
+        }
+    }
+
+    pub(crate) fn state(&self) -> Arc<State> {
+        self.state.clone()
+    }
+
+    /// count of streams opened by request and not finished yet. A stream is finished when request failed or
+    /// it's response body is dropped.
+    #[inline]
+    pub fn active_streams(&self) -> usize {
+        self.state.active_streams()
+    }
+
+    /// max count of concurrent streams allowed by server.
+    #[inline]
+    pub fn max_concurrent_streams(&self) -> usize {
+        self.tx.current_max_send_streams()
+    }
+
+    /// check if connection has reached max concurrent streams allowed by server. New stream opened on a full
+    /// connection is queued until an active stream is finished.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.active_streams() >= self.max_concurrent_streams()
+    }
+
+    /// check if connection is closed by either side or failed keep-alive ping.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.state.closed.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn stream_guard(&self) -> StreamGuard {
+        self.state.active_streams.fetch_add(1, Ordering::AcqRel);
+        StreamGuard(self.state.clone())
+    }
+
+    pub(crate) fn is_same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Deref for Connection {
+    type Target = SendRequest<Bytes>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.tx
+    }
+}
+
+impl DerefMut for Connection {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tx
+    }
+}
+
+// guard of active stream. stream is counted as finished when it's dropped.
+pub(crate) struct StreamGuard(Arc<State>);
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        self.0.active_streams.fetch_sub(1, Ordering::AcqRel);
+    }
+}
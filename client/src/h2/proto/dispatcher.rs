@@ -1,7 +1,8 @@
-use core::{cmp, future::poll_fn, pin::pin};
+use core::{cmp, future::poll_fn, pin::pin, time::Duration};
 
-use ::h2::{client, Reason};
+use ::h2::{client, Ping, PingPong, Reason};
 use futures_core::stream::Stream;
+use tracing::debug;
 use xitca_http::{
     date::DateTime,
     http::{
@@ -12,12 +13,14 @@ use xitca_http::{
     },
 };
 use xitca_io::io::{AsyncIo, PollIoAdapter};
+use xitca_unsafe_collection::futures::Select;
 
 use crate::{
     body::{BodyError, BodySize, ResponseBody},
     bytes::Bytes,
     date::DateTimeHandle,
-    h2::{body::ResponseBody as H2ResponseBody, Connection, Error},
+    h2::{body::ResponseBody as H2ResponseBody, connection::State, Connection, Error},
+    timeout::Timeout,
 };
 
 pub(crate) async fn send<B, E>(
@@ -87,6 +90,8 @@ where
 
     let is_head_method = *req.method() == Method::HEAD;
 
+    let guard = stream.stream_guard();
+
     let (fut, mut stream) = stream.send_request(req, end_of_stream)?;
 
     if !is_eof {
@@ -119,24 +124,58 @@ where
     let res = if is_head_method {
         res.map(|_| ResponseBody::Eof)
     } else {
-        res.map(|body| ResponseBody::H2(H2ResponseBody::new(stream, body.into())))
+        res.map(|body| ResponseBody::H2(H2ResponseBody::new(stream, body.into(), guard)))
     };
 
     Ok(res)
 }
 
-pub(crate) async fn handshake<S>(stream: S) -> Result<Connection, Error>
+/// keep-alive ping interval and timeout of idle connection.
+pub(crate) type KeepAlive = Option<(Duration, Duration)>;
+
+pub(crate) async fn handshake<S>(stream: S, keep_alive: KeepAlive) -> Result<Connection, Error>
 where
     S: AsyncIo + Send + 'static,
 {
-    let (conn, task) = client::Builder::new()
+    let (tx, mut task) = client::Builder::new()
         .enable_push(false)
         .handshake(PollIoAdapter(stream))
         .await?;
 
-    tokio::spawn(async {
-        task.await.expect("http2 connection failed");
+    let conn = Connection::new(tx);
+    let state = conn.state();
+    let ping = keep_alive.and_then(|(interval, timeout)| task.ping_pong().map(|ping| (ping, interval, timeout)));
+
+    tokio::spawn(async move {
+        match ping {
+            Some((ping, interval, timeout)) => {
+                // connection task is dropped when ping failed.
+                let _ = task.select(ping_idle(ping, interval, timeout, &state)).await;
+            }
+            None => {
+                let _ = task.await;
+            }
+        }
+        state.close();
     });
 
     Ok(conn)
 }
+
+// ping connection when it's idle and return when pong is not received in time.
+async fn ping_idle(mut ping: PingPong, interval: Duration, timeout: Duration, state: &State) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        // connection with active streams is checked by request and response timeout.
+        if state.active_streams() > 0 {
+            continue;
+        }
+
+        let mut timer = pin!(tokio::time::sleep(timeout));
+        if !matches!(ping.ping(Ping::opaque()).timeout(timer.as_mut()).await, Ok(Ok(_))) {
+            debug!("http2 connection keep-alive ping failed");
+            return;
+        }
+    }
+}
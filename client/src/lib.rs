@@ -41,6 +41,7 @@ mod resolver;
 mod response;
 mod service;
 mod timeout;
+mod timing;
 mod tls;
 mod tunnel;
 mod uri;
@@ -68,6 +69,7 @@ pub use self::request::RequestBuilder;
 pub use self::response::Response;
 pub use self::service::{HttpService, Service, ServiceRequest};
 pub use self::timeout::TimeoutConfig;
+pub use self::timing::Timing;
 pub use self::tls::{connector::Connector, TlsStream};
 
 // re-export http crate.
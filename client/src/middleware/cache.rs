@@ -0,0 +1,727 @@
+use core::{
+    future::poll_fn,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures_core::stream::Stream;
+
+use crate::{
+    body::{BodyError, ResponseBody},
+    bytes::{Bytes, BytesMut},
+    client::Client,
+    error::{Error, TimeoutError},
+    http::{
+        self,
+        header::{
+            HeaderMap, HeaderName, HeaderValue, AGE, AUTHORIZATION, CACHE_CONTROL, CONTENT_LENGTH, DATE, ETAG, EXPIRES,
+            IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RANGE, VARY,
+        },
+        Method, StatusCode, Version,
+    },
+    response::Response,
+    service::{Service, ServiceRequest},
+};
+
+const DEFAULT_MAX_ENTRY_SIZE: usize = 1024 * 1024;
+
+/// middleware caching http response according to `Cache-Control`, `Expires`, `ETag` and `Last-Modified` headers.
+///
+/// Only response to `GET` request is cached. A fresh cached response is served without sending request to server.
+/// A stale one with validator is revalidated with `If-None-Match` and/or `If-Modified-Since` request headers and
+/// served from cache when server responds with `304 Not Modified`. Successful response to unsafe request method
+/// invalidates cached response of the same uri.
+///
+/// Request with `Cache-Control: no-store`, `Range` or user provided conditional headers bypasses the cache.
+/// Response with `Cache-Control: no-store` or `Vary: *` is never stored.
+///
+/// By default the cache acts as a shared cache where `Cache-Control: private` response is not stored and
+/// `s-maxage` directive is honored. See [Cache::private] for changing this behavior.
+///
+/// # Examples
+/// ```rust
+/// use xitca_client::{
+///     middleware::{Cache, MemoryStore},
+///     ClientBuilder,
+/// };
+///
+/// // cache with default in memory store.
+/// let builder = ClientBuilder::new().middleware(Cache::new);
+///
+/// // private cache with custom store capacity.
+/// let builder = ClientBuilder::new()
+///     .middleware(|service| Cache::with_store(service, MemoryStore::with_capacity(1024)).private());
+/// ```
+pub struct Cache<S, St = MemoryStore> {
+    service: S,
+    store: St,
+    private: bool,
+    max_entry_size: usize,
+}
+
+impl<S> Cache<S> {
+    /// construct a new cache middleware with given http service type and default [MemoryStore].
+    pub fn new(service: S) -> Self {
+        Self::with_store(service, MemoryStore::new())
+    }
+}
+
+impl<S, St> Cache<S, St> {
+    /// construct a new cache middleware with given http service type and cache store.
+    pub fn with_store(service: S, store: St) -> Self {
+        Self {
+            service,
+            store,
+            private: false,
+            max_entry_size: DEFAULT_MAX_ENTRY_SIZE,
+        }
+    }
+
+    /// act as a private cache dedicated to single user. `Cache-Control: private` response is stored and
+    /// `s-maxage` directive is ignored.
+    pub fn private(mut self) -> Self {
+        self.private = true;
+        self
+    }
+
+    /// set max size of response body in bytes that can be stored. Response with larger body is passed through
+    /// without being cached.
+    ///
+    /// Default to 1 Mb.
+    pub fn max_entry_size(mut self, size: usize) -> Self {
+        self.max_entry_size = size;
+        self
+    }
+}
+
+impl<'r, 'c, S, St> Service<ServiceRequest<'r, 'c>> for Cache<S, St>
+where
+    S: for<'r2, 'c2> Service<ServiceRequest<'r2, 'c2>, Response = Response<'c2>, Error = Error> + Send + Sync,
+    St: CacheStore,
+{
+    type Response = Response<'c>;
+    type Error = Error;
+
+    async fn call(&self, req: ServiceRequest<'r, 'c>) -> Result<Self::Response, Self::Error> {
+        let ServiceRequest { req, client, timeout } = req;
+
+        if req.method() != Method::GET {
+            let key = req.uri().to_string();
+            let unsafe_method = !matches!(*req.method(), Method::HEAD | Method::OPTIONS | Method::TRACE);
+            let res = self.service.call(ServiceRequest { req, client, timeout }).await?;
+            if unsafe_method && (res.status().is_success() || res.status().is_redirection()) {
+                self.store.remove(&key);
+            }
+            return Ok(res);
+        }
+
+        let directives = Directives::from_headers(req.headers());
+        let headers = req.headers();
+
+        if directives.no_store
+            || headers.contains_key(RANGE)
+            || headers.contains_key(IF_NONE_MATCH)
+            || headers.contains_key(IF_MODIFIED_SINCE)
+        {
+            return self.service.call(ServiceRequest { req, client, timeout }).await;
+        }
+
+        let key = req.uri().to_string();
+        let now = SystemTime::now();
+
+        let cached = self.store.get(&key).filter(|cached| cached.vary_matches(req.headers()));
+
+        let mut validating = false;
+
+        if let Some(ref cached) = cached {
+            let age = cached.age(now);
+            // max-age request directive limits acceptable age of cached response.
+            let acceptable = match directives.max_age {
+                Some(max_age) => age <= max_age,
+                None => true,
+            };
+            if !directives.no_cache && acceptable && age < cached.lifetime {
+                return Ok(cached.to_response(age, client));
+            }
+
+            if let Some(etag) = cached.headers.get(ETAG) {
+                req.headers_mut().insert(IF_NONE_MATCH, etag.clone());
+                validating = true;
+            }
+
+            if let Some(last_modified) = cached.headers.get(LAST_MODIFIED) {
+                req.headers_mut().insert(IF_MODIFIED_SINCE, last_modified.clone());
+                validating = true;
+            }
+        }
+
+        let res = self.service.call(ServiceRequest { req, client, timeout }).await;
+
+        if validating {
+            // remove validators so request can be reused by caller as it was.
+            req.headers_mut().remove(IF_NONE_MATCH);
+            req.headers_mut().remove(IF_MODIFIED_SINCE);
+        }
+
+        let mut res = res?;
+
+        if let (true, Some(mut cached)) = (validating, cached) {
+            if res.status() == StatusCode::NOT_MODIFIED {
+                cached.refresh(res.headers(), now, self.private);
+                let res = cached.to_response(cached.age(now), client);
+                self.store.set(key, cached);
+                return Ok(res);
+            }
+        }
+
+        let directives = Directives::from_headers(res.headers());
+
+        if directives.no_store {
+            self.store.remove(&key);
+            return Ok(res);
+        }
+
+        if !self.is_storable(&directives, req.headers(), &res) {
+            return Ok(res);
+        }
+
+        let Some(vary) = vary(res.headers(), req.headers()) else {
+            return Ok(res);
+        };
+
+        let (parts, body) = res.res.into_parts();
+
+        let body = match buffer(body, self.max_entry_size, client.timeout_config.response_timeout).await? {
+            Ok(body) => {
+                let headers = parts.headers.clone();
+                let (date, lifetime) = freshness(&headers, &directives, now, self.private);
+                self.store.set(
+                    key,
+                    CachedResponse {
+                        status: parts.status,
+                        version: parts.version,
+                        headers,
+                        body: body.clone(),
+                        vary,
+                        date,
+                        lifetime,
+                    },
+                );
+                once(body)
+            }
+            Err(body) => body,
+        };
+
+        res.res = http::Response::from_parts(parts, body);
+
+        Ok(res)
+    }
+}
+
+impl<S, St> Cache<S, St> {
+    fn is_storable(&self, directives: &Directives, req: &HeaderMap, res: &Response<'_>) -> bool {
+        if !matches!(
+            res.status(),
+            StatusCode::OK
+                | StatusCode::NON_AUTHORITATIVE_INFORMATION
+                | StatusCode::NO_CONTENT
+                | StatusCode::MULTIPLE_CHOICES
+                | StatusCode::MOVED_PERMANENTLY
+                | StatusCode::PERMANENT_REDIRECT
+                | StatusCode::NOT_FOUND
+                | StatusCode::GONE
+        ) {
+            return false;
+        }
+
+        if !self.private
+            && (directives.private
+                || (req.contains_key(AUTHORIZATION)
+                    && !(directives.public || directives.must_revalidate || directives.s_max_age.is_some())))
+        {
+            return false;
+        }
+
+        // response without explicit freshness or validator is not useful to cache.
+        let headers = res.headers();
+        if directives.max_age.is_none()
+            && directives.s_max_age.is_none()
+            && !headers.contains_key(EXPIRES)
+            && !headers.contains_key(ETAG)
+            && !headers.contains_key(LAST_MODIFIED)
+        {
+            return false;
+        }
+
+        match headers
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok())
+        {
+            Some(len) => len <= self.max_entry_size,
+            None => true,
+        }
+    }
+}
+
+/// trait for storage of cached response. Used by [Cache] middleware.
+///
+/// Cached response is keyed by request uri.
+pub trait CacheStore: Send + Sync {
+    /// get cached response with given key.
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// store cached response with given key. existing one should be replaced.
+    fn set(&self, key: String, res: CachedResponse);
+
+    /// remove cached response with given key.
+    fn remove(&self, key: &str);
+}
+
+/// in memory [CacheStore] with limited capacity. The oldest response is evicted when store is full.
+pub struct MemoryStore {
+    capacity: usize,
+    map: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryStore {
+    /// construct a new store with capacity of 256 responses.
+    pub fn new() -> Self {
+        Self::with_capacity(256)
+    }
+
+    /// construct a new store with given capacity of responses.
+    ///
+    /// # Panics
+    /// When capacity is zero.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert_ne!(capacity, 0, "MemoryStore capacity must be greater than zero");
+        Self {
+            capacity,
+            map: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.map.lock().unwrap().get(key).cloned()
+    }
+
+    fn set(&self, key: String, res: CachedResponse) {
+        let mut map = self.map.lock().unwrap();
+        if map.len() >= self.capacity && !map.contains_key(&key) {
+            let oldest = map.iter().min_by_key(|(_, res)| res.date).map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                map.remove(&oldest);
+            }
+        }
+        map.insert(key, res);
+    }
+
+    fn remove(&self, key: &str) {
+        self.map.lock().unwrap().remove(key);
+    }
+}
+
+/// response stored by [CacheStore].
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    body: Bytes,
+    // request header values selected by Vary response header.
+    vary: Vec<(HeaderName, Option<HeaderValue>)>,
+    // time when response is generated by server.
+    date: SystemTime,
+    lifetime: Duration,
+}
+
+impl CachedResponse {
+    /// status code of cached response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// headers of cached response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// body of cached response.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// check if cached response is still fresh at given time.
+    pub fn is_fresh(&self, now: SystemTime) -> bool {
+        self.age(now) < self.lifetime
+    }
+
+    fn age(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.date).unwrap_or_default()
+    }
+
+    fn vary_matches(&self, headers: &HeaderMap) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| headers.get(name) == value.as_ref())
+    }
+
+    // update stored headers with 304 response headers and renew freshness.
+    fn refresh(&mut self, headers: &HeaderMap, now: SystemTime, private: bool) {
+        for name in headers.keys() {
+            if name == CONTENT_LENGTH {
+                continue;
+            }
+            self.headers.remove(name);
+            for value in headers.get_all(name) {
+                self.headers.append(name.clone(), value.clone());
+            }
+        }
+        let directives = Directives::from_headers(&self.headers);
+        (self.date, self.lifetime) = freshness(&self.headers, &directives, now, private);
+    }
+
+    fn to_response<'c>(&self, age: Duration, client: &'c Client) -> Response<'c> {
+        let mut res = http::Response::new(once(self.body.clone()));
+        *res.status_mut() = self.status;
+        *res.version_mut() = self.version;
+        *res.headers_mut() = self.headers.clone();
+        res.headers_mut().insert(AGE, HeaderValue::from(age.as_secs()));
+
+        let timeout = client.timeout_config.response_timeout;
+        Response::new(res, Box::pin(tokio::time::sleep(timeout)), timeout)
+    }
+}
+
+#[derive(Default)]
+struct Directives {
+    no_store: bool,
+    no_cache: bool,
+    private: bool,
+    public: bool,
+    must_revalidate: bool,
+    max_age: Option<Duration>,
+    s_max_age: Option<Duration>,
+}
+
+impl Directives {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let mut this = Self::default();
+
+        let directives = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','));
+
+        for directive in directives {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+
+            let secs = || value.and_then(|v| v.parse().ok()).map(Duration::from_secs);
+
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => this.no_store = true,
+                "no-cache" => this.no_cache = true,
+                "private" => this.private = true,
+                "public" => this.public = true,
+                "must-revalidate" | "proxy-revalidate" => this.must_revalidate = true,
+                // malformed max-age is treated as stale.
+                "max-age" => this.max_age = Some(secs().unwrap_or_default()),
+                "s-maxage" => this.s_max_age = Some(secs().unwrap_or_default()),
+                _ => {}
+            }
+        }
+
+        this
+    }
+}
+
+// calculate time of response generation and freshness lifetime of response.
+fn freshness(headers: &HeaderMap, directives: &Directives, now: SystemTime, private: bool) -> (SystemTime, Duration) {
+    let age = headers
+        .get(AGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or_default();
+
+    let date = now.checked_sub(age).unwrap_or(UNIX_EPOCH);
+
+    let lifetime = if directives.no_cache {
+        Duration::ZERO
+    } else if let (false, Some(s_max_age)) = (private, directives.s_max_age) {
+        s_max_age
+    } else if let Some(max_age) = directives.max_age {
+        max_age
+    } else if let Some(expires) = headers.get(EXPIRES) {
+        // invalid Expires header is treated as already expired.
+        http_date(Some(expires))
+            .and_then(|expires| expires.duration_since(http_date(headers.get(DATE)).unwrap_or(now)).ok())
+            .unwrap_or_default()
+    } else {
+        Duration::ZERO
+    };
+
+    (date, lifetime)
+}
+
+fn http_date(value: Option<&HeaderValue>) -> Option<SystemTime> {
+    value
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| httpdate::parse_http_date(v).ok())
+}
+
+// collect request header values selected by Vary response header. None when response can not be cached.
+fn vary(res: &HeaderMap, req: &HeaderMap) -> Option<Vec<(HeaderName, Option<HeaderValue>)>> {
+    let mut vary = Vec::new();
+    for name in res
+        .get_all(VARY)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if name == "*" {
+            return None;
+        }
+        let name = HeaderName::try_from(name).ok()?;
+        let value = req.get(&name).cloned();
+        vary.push((name, value));
+    }
+    Some(vary)
+}
+
+// buffer response body up to limit. When body exceeds the limit it's returned with the buffered part prepended.
+async fn buffer(
+    mut body: ResponseBody<'_>,
+    limit: usize,
+    timeout: Duration,
+) -> Result<Result<Bytes, ResponseBody<'_>>, Error> {
+    let mut buf = BytesMut::new();
+
+    let collect = async {
+        while let Some(chunk) = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await {
+            buf.extend_from_slice(&chunk?);
+            if buf.len() > limit {
+                return Ok(false);
+            }
+        }
+        Ok::<_, Error>(true)
+    };
+
+    match tokio::time::timeout(timeout, collect).await {
+        Ok(Ok(true)) => Ok(Ok(buf.freeze())),
+        Ok(Ok(false)) => Ok(Err(ResponseBody::Unknown(Box::pin(Prefixed {
+            prefix: Some(buf.freeze()),
+            body,
+        })))),
+        Ok(Err(e)) => {
+            body.destroy_on_drop();
+            Err(e)
+        }
+        Err(_) => {
+            body.destroy_on_drop();
+            Err(TimeoutError::Response.into())
+        }
+    }
+}
+
+fn once<'c>(bytes: Bytes) -> ResponseBody<'c> {
+    ResponseBody::Unknown(Box::pin(Prefixed {
+        prefix: Some(bytes),
+        body: ResponseBody::Eof,
+    }))
+}
+
+struct Prefixed<'c> {
+    prefix: Option<Bytes>,
+    body: ResponseBody<'c>,
+}
+
+impl Stream for Prefixed<'_> {
+    type Item = Result<Bytes, BodyError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match this.prefix.take() {
+            Some(bytes) if !bytes.is_empty() => Poll::Ready(Some(Ok(bytes))),
+            _ => Pin::new(&mut this.body).poll_next(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::{body::ResponseBody, Client};
+
+    use super::*;
+
+    struct Origin {
+        calls: Arc<AtomicUsize>,
+        cache_control: &'static str,
+    }
+
+    impl<'r, 'c> Service<ServiceRequest<'r, 'c>> for Origin {
+        type Response = Response<'c>;
+        type Error = Error;
+
+        async fn call(&self, req: ServiceRequest<'r, 'c>) -> Result<Self::Response, Self::Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+
+            let not_modified = req.req.headers().get(IF_NONE_MATCH).is_some_and(|v| v == "\"v1\"");
+
+            let mut res = if not_modified {
+                let mut res = http::Response::new(ResponseBody::Eof);
+                *res.status_mut() = StatusCode::NOT_MODIFIED;
+                res
+            } else {
+                http::Response::new(once(Bytes::from_static(b"hello")))
+            };
+            res.headers_mut()
+                .insert(CACHE_CONTROL, HeaderValue::from_static(self.cache_control));
+            res.headers_mut().insert(ETAG, HeaderValue::from_static("\"v1\""));
+
+            let timeout = req.client.timeout_config.response_timeout;
+            Ok(Response::new(res, Box::pin(tokio::time::sleep(timeout)), timeout))
+        }
+    }
+
+    fn build(cache_control: &'static str, private: bool) -> (Client, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let origin = Origin {
+            calls: calls.clone(),
+            cache_control,
+        };
+        let client = Client::builder()
+            .middleware(|_| {
+                let cache = Cache::new(origin);
+                if private {
+                    cache.private()
+                } else {
+                    cache
+                }
+            })
+            .finish();
+        (client, calls)
+    }
+
+    #[tokio::test]
+    async fn fresh() {
+        let (client, calls) = build("max-age=60", false);
+
+        for _ in 0..2 {
+            let res = client.get("http://localhost/").send().await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(res.string().await.unwrap(), "hello");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let res = client.get("http://localhost/").send().await.unwrap();
+        assert!(res.headers().contains_key(AGE));
+
+        // unsafe method invalidates cached response.
+        let _ = client.post("http://localhost/").send().await.unwrap();
+        let _ = client.get("http://localhost/").send().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn no_store() {
+        let (client, calls) = build("no-store", true);
+
+        for _ in 0..2 {
+            let res = client.get("http://localhost/").send().await.unwrap();
+            assert_eq!(res.string().await.unwrap(), "hello");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // request no-store bypasses cache.
+        let (client, calls) = build("max-age=60", false);
+        for _ in 0..2 {
+            let _ = client
+                .get("http://localhost/")
+                .header(CACHE_CONTROL, HeaderValue::from_static("no-store"))
+                .send()
+                .await
+                .unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn private() {
+        let (client, calls) = build("private, max-age=60", false);
+        for _ in 0..2 {
+            let _ = client.get("http://localhost/").send().await.unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let (client, calls) = build("private, max-age=60", true);
+        for _ in 0..2 {
+            let _ = client.get("http://localhost/").send().await.unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn revalidate() {
+        let (client, calls) = build("no-cache", false);
+
+        for _ in 0..3 {
+            let res = client.get("http://localhost/").send().await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(res.headers().get(ETAG).unwrap(), "\"v1\"");
+            assert_eq!(res.string().await.unwrap(), "hello");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn directives() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CACHE_CONTROL,
+            HeaderValue::from_static("Public, max-age=\"10\", s-maxage=20, no-cache=\"set-cookie\""),
+        );
+        let directives = Directives::from_headers(&headers);
+        assert!(directives.public);
+        assert!(directives.no_cache);
+        assert!(!directives.no_store);
+        assert_eq!(directives.max_age, Some(Duration::from_secs(10)));
+        assert_eq!(directives.s_max_age, Some(Duration::from_secs(20)));
+
+        let now = SystemTime::now();
+        let (_, lifetime) = freshness(&headers, &directives, now, false);
+        assert_eq!(lifetime, Duration::ZERO);
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=10, s-maxage=20"));
+        let directives = Directives::from_headers(&headers);
+        assert_eq!(freshness(&headers, &directives, now, false).1, Duration::from_secs(20));
+        assert_eq!(freshness(&headers, &directives, now, true).1, Duration::from_secs(10));
+    }
+}
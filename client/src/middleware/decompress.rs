@@ -31,9 +31,11 @@ where
     type Error = Error;
 
     async fn call(&self, req: ServiceRequest<'r, 'c>) -> Result<Self::Response, Self::Error> {
+        // respect accept-encoding header set by user.
         req.req
             .headers_mut()
-            .insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip, deflate, br"));
+            .entry(ACCEPT_ENCODING)
+            .or_insert(HeaderValue::from_static("gzip, deflate, br"));
 
         let mut res = self.service.call(req).await?;
 
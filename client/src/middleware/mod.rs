@@ -1,11 +1,24 @@
 //! middleware offer extended functionality to http client.
 
 mod redirect;
+mod sign;
+
+#[cfg(feature = "cache")]
+mod cache;
 
 #[cfg(feature = "compress")]
 mod decompress;
 
+#[cfg(feature = "sigv4")]
+mod sigv4;
+
+#[cfg(feature = "cache")]
+pub use cache::{Cache, CacheStore, CachedResponse, MemoryStore};
 #[cfg(feature = "compress")]
 pub use decompress::Decompress;
 
+#[cfg(feature = "sigv4")]
+pub use sigv4::SigV4;
+
 pub use redirect::FollowRedirect;
+pub use sign::{Sign, Signer};
@@ -0,0 +1,120 @@
+use core::{future::poll_fn, mem, pin::Pin};
+
+use futures_core::stream::Stream;
+use xitca_http::body::NONE_BODY_HINT;
+
+use crate::{
+    body::{BoxBody, Once},
+    bytes::{Bytes, BytesMut},
+    error::Error,
+    http::Request,
+    response::Response,
+    service::{Service, ServiceRequest},
+};
+
+/// trait for signing full http request before it's sent.
+///
+/// Signer observes and mutates method, uri, headers and extensions of request. Request body is buffered in memory
+/// and passed to signer as [Bytes] so it's hash can be part of the signature.
+///
+/// Trait is implemented for closure so simple signing logic can be expressed inline.
+///
+/// # Examples
+/// ```rust
+/// use xitca_client::{
+///     bytes::Bytes,
+///     error::Error,
+///     http::{header::HeaderValue, Request},
+///     middleware::Sign,
+///     ClientBuilder,
+/// };
+///
+/// let builder = ClientBuilder::new().middleware(|service| {
+///     Sign::new(service, |req: &mut Request<Bytes>| -> Result<(), Error> {
+///         let len = req.body().len().to_string();
+///         req.headers_mut().insert("x-body-length", HeaderValue::try_from(len).unwrap());
+///         Ok(())
+///     })
+/// });
+/// ```
+pub trait Signer: Send + Sync {
+    /// sign given request by adding signature to it's headers or uri.
+    fn sign(&self, req: &mut Request<Bytes>) -> Result<(), Error>;
+}
+
+impl<F> Signer for F
+where
+    F: Fn(&mut Request<Bytes>) -> Result<(), Error> + Send + Sync,
+{
+    #[inline]
+    fn sign(&self, req: &mut Request<Bytes>) -> Result<(), Error> {
+        (self)(req)
+    }
+}
+
+/// middleware signing request with given [Signer] before sending it.
+///
+/// Signing happens every time the middleware is called so requests re-sent by outer middlewares like
+/// [FollowRedirect](super::FollowRedirect) are signed again. Middlewares mutating request should be applied after
+/// this one with [ClientBuilder::middleware](crate::ClientBuilder::middleware) so their changes are visible to
+/// signer.
+pub struct Sign<S, Si> {
+    service: S,
+    signer: Si,
+}
+
+impl<S, Si> Sign<S, Si> {
+    /// construct a new signing middleware with given http service type and signer.
+    pub const fn new(service: S, signer: Si) -> Self {
+        Self { service, signer }
+    }
+}
+
+impl<'r, 'c, S, Si> Service<ServiceRequest<'r, 'c>> for Sign<S, Si>
+where
+    S: for<'r2, 'c2> Service<ServiceRequest<'r2, 'c2>, Response = Response<'c2>, Error = Error> + Send + Sync,
+    Si: Signer,
+{
+    type Response = Response<'c>;
+    type Error = Error;
+
+    async fn call(&self, req: ServiceRequest<'r, 'c>) -> Result<Self::Response, Self::Error> {
+        let ServiceRequest { req, client, timeout } = req;
+
+        let (parts, mut body) = mem::take(req).into_parts();
+        let is_none = body.size_hint() == NONE_BODY_HINT;
+
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await {
+            buf.extend_from_slice(&chunk?);
+        }
+
+        let mut signed = Request::from_parts(parts, buf.freeze());
+        self.signer.sign(&mut signed)?;
+
+        *req = signed.map(|body| {
+            // keep the absence of body so no content-length header is added to request.
+            if is_none && body.is_empty() {
+                BoxBody::default()
+            } else {
+                BoxBody::new(Once::new(body))
+            }
+        });
+
+        self.service.call(ServiceRequest { req, client, timeout }).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Client;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn build_sign_mw() {
+        let _ = Client::builder()
+            .middleware(|service| Sign::new(service, |_: &mut Request<Bytes>| Ok(())))
+            .finish();
+    }
+}
@@ -0,0 +1,370 @@
+use core::fmt::Write;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    bytes::Bytes,
+    error::{Error, InvalidUri},
+    http::{
+        header::{HeaderName, HeaderValue, AUTHORIZATION, CONNECTION, EXPECT, HOST, TRANSFER_ENCODING, USER_AGENT},
+        Request,
+    },
+};
+
+use super::sign::Signer;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+const X_AMZ_DATE: HeaderName = HeaderName::from_static("x-amz-date");
+const X_AMZ_CONTENT_SHA256: HeaderName = HeaderName::from_static("x-amz-content-sha256");
+const X_AMZ_SECURITY_TOKEN: HeaderName = HeaderName::from_static("x-amz-security-token");
+
+// unreserved characters of RFC 3986 are never encoded.
+const ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+const ENCODE_PATH_SET: &AsciiSet = &ENCODE_SET.remove(b'/');
+
+/// [Signer] implementing AWS Signature Version 4 with `Authorization` header.
+///
+/// Request is signed with it's method, path, query, headers and SHA256 hash of body. `Host` and `X-Amz-Date` headers
+/// are added to request and `X-Amz-Security-Token` header is added when session token is provided. For `s3` service
+/// `X-Amz-Content-Sha256` header is added and uri path is encoded once as S3 requires.
+///
+/// # Examples
+/// ```rust
+/// use xitca_client::{
+///     middleware::{SigV4, Sign},
+///     ClientBuilder,
+/// };
+///
+/// let signer = SigV4::new("access_key_id", "secret_access_key", "us-east-1", "s3");
+/// let builder = ClientBuilder::new().middleware(|service| Sign::new(service, signer));
+/// ```
+#[derive(Clone)]
+pub struct SigV4 {
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+    region: String,
+    service: String,
+    unsigned_payload: bool,
+}
+
+impl SigV4 {
+    /// construct a new signer with given credential, region and service name.
+    pub fn new(
+        access_key: impl Into<String>,
+        secret_key: impl Into<String>,
+        region: impl Into<String>,
+        service: impl Into<String>,
+    ) -> Self {
+        Self {
+            access_key: access_key.into(),
+            secret_key: secret_key.into(),
+            session_token: None,
+            region: region.into(),
+            service: service.into(),
+            unsigned_payload: false,
+        }
+    }
+
+    /// set session token of temporary security credential.
+    pub fn session_token(mut self, token: impl Into<String>) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
+
+    /// sign request with `UNSIGNED-PAYLOAD` in place of body hash. useful for skipping hashing of large body
+    /// when service accepts it.
+    pub fn unsigned_payload(mut self) -> Self {
+        self.unsigned_payload = true;
+        self
+    }
+
+    fn sign_at(&self, req: &mut Request<Bytes>, time: SystemTime) -> Result<(), Error> {
+        let (date, date_time) = format_time(time);
+
+        let payload_hash = if self.unsigned_payload {
+            String::from(UNSIGNED_PAYLOAD)
+        } else {
+            hex(&Sha256::digest(req.body()))
+        };
+
+        let is_s3 = self.service == "s3";
+
+        let headers = req.headers_mut();
+        headers.remove(AUTHORIZATION);
+        headers.insert(X_AMZ_DATE, HeaderValue::try_from(&date_time).map_err(std_err)?);
+        if is_s3 {
+            headers.insert(
+                X_AMZ_CONTENT_SHA256,
+                HeaderValue::try_from(&payload_hash).map_err(std_err)?,
+            );
+        }
+        if let Some(ref token) = self.session_token {
+            headers.insert(X_AMZ_SECURITY_TOKEN, HeaderValue::try_from(token).map_err(std_err)?);
+        }
+
+        if !req.headers().contains_key(HOST) {
+            let host = req.uri().authority().ok_or(InvalidUri::MissingAuthority)?;
+            let host = HeaderValue::try_from(host.as_str()).map_err(std_err)?;
+            req.headers_mut().insert(HOST, host);
+        }
+
+        let (canonical_request, signed_headers) = canonical_request(req, &payload_hash, !is_s3)?;
+
+        let scope = format!("{date}/{}/{}/aws4_request", self.region, self.service);
+        let string_to_sign = format!(
+            "{ALGORITHM}\n{date_time}\n{scope}\n{}",
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let key = hmac(format!("AWS4{}", self.secret_key).as_bytes(), date.as_bytes());
+        let key = hmac(&key, self.region.as_bytes());
+        let key = hmac(&key, self.service.as_bytes());
+        let key = hmac(&key, b"aws4_request");
+        let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            self.access_key
+        );
+        let mut authorization = HeaderValue::try_from(authorization).map_err(std_err)?;
+        authorization.set_sensitive(true);
+        req.headers_mut().insert(AUTHORIZATION, authorization);
+
+        Ok(())
+    }
+}
+
+impl Signer for SigV4 {
+    fn sign(&self, req: &mut Request<Bytes>) -> Result<(), Error> {
+        self.sign_at(req, SystemTime::now())
+    }
+}
+
+// headers excluded from signature as they can be altered by client or proxies.
+fn is_unsigned_header(name: &HeaderName) -> bool {
+    matches!(
+        *name,
+        AUTHORIZATION | USER_AGENT | EXPECT | CONNECTION | TRANSFER_ENCODING
+    ) || name.as_str() == "x-amzn-trace-id"
+}
+
+// return canonical request and signed header names.
+fn canonical_request(req: &Request<Bytes>, payload_hash: &str, double_encode: bool) -> Result<(String, String), Error> {
+    let mut canonical = String::new();
+
+    canonical.push_str(req.method().as_str());
+    canonical.push('\n');
+
+    let path = match req.uri().path() {
+        "" => "/",
+        path => path,
+    };
+    let path = percent_decode_str(path).decode_utf8_lossy();
+    let path = utf8_percent_encode(&path, ENCODE_PATH_SET).to_string();
+    if double_encode {
+        canonical.extend(utf8_percent_encode(&path, ENCODE_PATH_SET));
+    } else {
+        canonical.push_str(&path);
+    }
+    canonical.push('\n');
+
+    let mut query = req
+        .uri()
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (encode_query(key), encode_query(value))
+        })
+        .collect::<Vec<_>>();
+    query.sort();
+    for (i, (key, value)) in query.iter().enumerate() {
+        if i > 0 {
+            canonical.push('&');
+        }
+        let _ = write!(canonical, "{key}={value}");
+    }
+    canonical.push('\n');
+
+    let mut names = req
+        .headers()
+        .keys()
+        .filter(|name| !is_unsigned_header(name))
+        .collect::<Vec<_>>();
+    names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+    for name in names.iter() {
+        canonical.push_str(name.as_str());
+        canonical.push(':');
+        for (i, value) in req.headers().get_all(*name).iter().enumerate() {
+            if i > 0 {
+                canonical.push(',');
+            }
+            let value = value.to_str().map_err(std_err)?;
+            // trim and collapse sequential spaces.
+            for (i, word) in value.split_whitespace().enumerate() {
+                if i > 0 {
+                    canonical.push(' ');
+                }
+                canonical.push_str(word);
+            }
+        }
+        canonical.push('\n');
+    }
+    canonical.push('\n');
+
+    let signed_headers = names.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(";");
+    canonical.push_str(&signed_headers);
+    canonical.push('\n');
+    canonical.push_str(payload_hash);
+
+    Ok((canonical, signed_headers))
+}
+
+fn encode_query(value: &str) -> String {
+    let value = percent_decode_str(value).decode_utf8_lossy();
+    utf8_percent_encode(&value, ENCODE_SET).to_string()
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts key of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
+// format time to date(YYYYMMDD) and date time(YYYYMMDD'T'HHMMSS'Z') in UTC.
+fn format_time(time: SystemTime) -> (String, String) {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, secs) = (secs / 86400, secs % 86400);
+
+    // civil date from days since unix epoch. see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let date = format!("{year:04}{month:02}{day:02}");
+    let date_time = format!("{date}T{:02}{:02}{:02}Z", secs / 3600, secs % 3600 / 60, secs % 60);
+    (date, date_time)
+}
+
+fn std_err<E>(e: E) -> Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Error::Std(Box::new(e))
+}
+
+#[cfg(test)]
+mod test {
+    use core::time::Duration;
+
+    use crate::http::Method;
+
+    use super::*;
+
+    // test vectors from AWS Signature Version 4 test suite.
+    fn sign(mut req: Request<Bytes>) -> Request<Bytes> {
+        let signer = SigV4::new(
+            "AKIDEXAMPLE",
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "us-east-1",
+            "service",
+        );
+        // 2015-08-30T12:36:00Z
+        let time = UNIX_EPOCH + Duration::from_secs(1440938160);
+        signer.sign_at(&mut req, time).unwrap();
+        req
+    }
+
+    #[test]
+    fn date_time() {
+        let time = UNIX_EPOCH + Duration::from_secs(1440938160);
+        assert_eq!(
+            format_time(time),
+            (String::from("20150830"), String::from("20150830T123600Z"))
+        );
+
+        let time = UNIX_EPOCH + Duration::from_secs(951782400);
+        assert_eq!(
+            format_time(time),
+            (String::from("20000229"), String::from("20000229T000000Z"))
+        );
+    }
+
+    #[test]
+    fn get_vanilla() {
+        let req = Request::builder()
+            .uri("https://example.amazonaws.com/")
+            .body(Bytes::new())
+            .unwrap();
+
+        let req = sign(req);
+
+        assert_eq!(req.headers().get(HOST).unwrap(), "example.amazonaws.com");
+        assert_eq!(req.headers().get(X_AMZ_DATE).unwrap(), "20150830T123600Z");
+        assert_eq!(
+            req.headers().get(AUTHORIZATION).unwrap(),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+            SignedHeaders=host;x-amz-date, \
+            Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn get_vanilla_query_order() {
+        let req = Request::builder()
+            .uri("https://example.amazonaws.com/?Param2=value2&Param1=value1")
+            .body(Bytes::new())
+            .unwrap();
+
+        let req = sign(req);
+
+        assert_eq!(
+            req.headers().get(AUTHORIZATION).unwrap(),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+            SignedHeaders=host;x-amz-date, \
+            Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        );
+    }
+
+    #[test]
+    fn post_x_www_form_urlencoded() {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("https://example.amazonaws.com/")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(Bytes::from_static(b"Param1=value1"))
+            .unwrap();
+
+        let req = sign(req);
+
+        assert_eq!(
+            req.headers().get(AUTHORIZATION).unwrap(),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+            SignedHeaders=content-type;host;x-amz-date, \
+            Signature=ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a"
+        );
+    }
+}
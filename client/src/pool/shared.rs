@@ -7,43 +7,65 @@ use std::{
 
 use tokio::sync::Notify;
 
+// connection type that can be shared by concurrent requests.
+pub(crate) trait SharedConnection: Clone {
+    // count of in flight requests.
+    fn load(&self) -> usize;
+
+    // connection can not take more request without queuing it.
+    fn is_full(&self) -> bool;
+
+    fn is_closed(&self) -> bool;
+
+    fn is_same(&self, other: &Self) -> bool;
+}
+
 #[doc(hidden)]
 pub struct Pool<K, C> {
     conns: Mutex<HashMap<K, PooledConnection<C>>>,
+    cap: usize,
 }
 
 impl<K, C> Pool<K, C>
 where
     K: Eq + Hash + Clone,
-    C: Clone,
+    C: SharedConnection,
 {
-    pub(crate) fn with_capacity(_: usize) -> Self {
+    pub(crate) fn with_capacity(cap: usize) -> Self {
         Self {
             conns: Mutex::new(HashMap::new()),
+            cap,
         }
     }
 
-    pub(crate) async fn acquire(&self, key: impl Into<K>) -> AcquireOutput<'_, K, C>
-    where
-        C: Clone,
-    {
+    // acquire the least loaded connection. new connection is spawned when all connections are full and pool
+    // capacity is not reached. closed connections are evicted.
+    pub(crate) async fn acquire(&self, key: impl Into<K>) -> AcquireOutput<'_, K, C> {
         let key = key.into();
         loop {
             let notify = {
                 let mut conns = self.conns.lock().unwrap();
-                match conns.get(&key) {
-                    Some(PooledConnection::Conn(c)) => {
+                let pooled = conns.entry(key.clone()).or_insert_with(PooledConnection::new);
+                pooled.conns.retain(|c| !c.is_closed());
+
+                if let Some(c) = pooled.conns.iter().min_by_key(|c| c.load()) {
+                    // when new connection is spawning or capacity is reached the least loaded connection is used
+                    // even it's full and request would be queued by it.
+                    if !c.is_full() || pooled.spawning.is_some() || pooled.conns.len() >= self.cap {
                         return AcquireOutput::Conn(Conn {
                             pool: self,
                             key,
                             conn: c.clone(),
                             destroy_on_drop: false,
-                        })
+                        });
                     }
-                    Some(PooledConnection::Spawning(notify)) => notify.clone(),
+                }
+
+                match pooled.spawning {
+                    Some(ref notify) => notify.clone(),
                     None => {
                         let notify = Arc::new(Notify::new());
-                        conns.insert(key.clone(), PooledConnection::Spawning(notify.clone()));
+                        pooled.spawning = Some(notify.clone());
                         return AcquireOutput::Spawner(Spawner {
                             pool: self,
                             key,
@@ -58,14 +80,25 @@ where
     }
 }
 
-enum PooledConnection<C> {
-    Conn(C),
-    Spawning(Arc<Notify>),
+struct PooledConnection<C> {
+    conns: Vec<C>,
+    // notify of spawning connection.
+    spawning: Option<Arc<Notify>>,
+}
+
+impl<C> PooledConnection<C> {
+    fn new() -> Self {
+        Self {
+            conns: Vec::new(),
+            spawning: None,
+        }
+    }
 }
 
 pub(crate) enum AcquireOutput<'a, K, C>
 where
     K: Eq + Hash + Clone,
+    C: SharedConnection,
 {
     Conn(Conn<'a, K, C>),
     Spawner(Spawner<'a, K, C>),
@@ -74,6 +107,7 @@ where
 pub(crate) struct Conn<'a, K, C>
 where
     K: Eq + Hash + Clone,
+    C: SharedConnection,
 {
     pool: &'a Pool<K, C>,
     key: K,
@@ -84,6 +118,7 @@ where
 pub(crate) struct Spawner<'a, K, C>
 where
     K: Eq + Hash + Clone,
+    C: SharedConnection,
 {
     pool: &'a Pool<K, C>,
     key: K,
@@ -94,12 +129,16 @@ where
 impl<K, C> Drop for Conn<'_, K, C>
 where
     K: Eq + Hash + Clone,
+    C: SharedConnection,
 {
     fn drop(&mut self) {
         if self.destroy_on_drop {
             let mut conns = self.pool.conns.lock().unwrap();
-            if matches!(conns.get(&self.key), Some(PooledConnection::Conn(_))) {
-                conns.remove(&self.key);
+            if let Some(pooled) = conns.get_mut(&self.key) {
+                pooled.conns.retain(|c| !c.is_same(&self.conn));
+                if pooled.conns.is_empty() && pooled.spawning.is_none() {
+                    conns.remove(&self.key);
+                }
             }
         }
     }
@@ -108,10 +147,17 @@ where
 impl<K, C> Drop for Spawner<'_, K, C>
 where
     K: Eq + Hash + Clone,
+    C: SharedConnection,
 {
     fn drop(&mut self) {
         if !self.fulfilled {
-            self.pool.conns.lock().unwrap().remove(&self.key);
+            let mut conns = self.pool.conns.lock().unwrap();
+            if let Some(pooled) = conns.get_mut(&self.key) {
+                pooled.spawning = None;
+                if pooled.conns.is_empty() {
+                    conns.remove(&self.key);
+                }
+            }
         }
 
         self.notify.notify_waiters();
@@ -121,26 +167,106 @@ where
 impl<K, C> Spawner<'_, K, C>
 where
     K: Eq + Hash + Clone,
+    C: SharedConnection,
 {
     pub(crate) fn spawned(mut self, conn: C) {
         self.fulfilled = true;
-        if let Some(PooledConnection::Spawning(notify)) = self
-            .pool
-            .conns
-            .lock()
-            .unwrap()
-            .insert(self.key.clone(), PooledConnection::Conn(conn))
-        {
-            notify.notify_waiters();
-        }
+        let mut conns = self.pool.conns.lock().unwrap();
+        let pooled = conns.entry(self.key.clone()).or_insert_with(PooledConnection::new);
+        pooled.conns.push(conn);
+        pooled.spawning = None;
     }
 }
 
 impl<K, C> Conn<'_, K, C>
 where
     K: Eq + Hash + Clone,
+    C: SharedConnection,
 {
     pub(crate) fn destroy_on_drop(&mut self) {
         self.destroy_on_drop = true;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Dummy(Arc<(AtomicUsize, AtomicBool)>);
+
+    impl Dummy {
+        fn set_load(&self, load: usize) {
+            self.0 .0.store(load, Ordering::Relaxed);
+        }
+    }
+
+    impl SharedConnection for Dummy {
+        fn load(&self) -> usize {
+            self.0 .0.load(Ordering::Relaxed)
+        }
+
+        fn is_full(&self) -> bool {
+            self.load() >= 1
+        }
+
+        fn is_closed(&self) -> bool {
+            self.0 .1.load(Ordering::Relaxed)
+        }
+
+        fn is_same(&self, other: &Self) -> bool {
+            Arc::ptr_eq(&self.0, &other.0)
+        }
+    }
+
+    fn conn<'a>(out: AcquireOutput<'a, &'static str, Dummy>) -> Conn<'a, &'static str, Dummy> {
+        match out {
+            AcquireOutput::Conn(conn) => conn,
+            AcquireOutput::Spawner(_) => panic!("expecting connection"),
+        }
+    }
+
+    fn spawner<'a>(out: AcquireOutput<'a, &'static str, Dummy>) -> Spawner<'a, &'static str, Dummy> {
+        match out {
+            AcquireOutput::Spawner(spawner) => spawner,
+            AcquireOutput::Conn(_) => panic!("expecting spawner"),
+        }
+    }
+
+    #[test]
+    fn least_loaded() {
+        let pool = Pool::<&'static str, Dummy>::with_capacity(2);
+
+        let c1 = Dummy::default();
+        spawner(pool.acquire("a").now_or_panic()).spawned(c1.clone());
+        assert!(conn(pool.acquire("a").now_or_panic()).conn.is_same(&c1));
+
+        // full connection triggers spawning and it's still used while spawning.
+        c1.set_load(1);
+        let s = spawner(pool.acquire("a").now_or_panic());
+        assert!(conn(pool.acquire("a").now_or_panic()).conn.is_same(&c1));
+
+        let c2 = Dummy::default();
+        s.spawned(c2.clone());
+        assert!(conn(pool.acquire("a").now_or_panic()).conn.is_same(&c2));
+
+        // capacity reached and the least loaded connection is used.
+        c2.set_load(2);
+        assert!(conn(pool.acquire("a").now_or_panic()).conn.is_same(&c1));
+
+        // closed connection is evicted.
+        c1.0 .1.store(true, Ordering::Relaxed);
+        drop(spawner(pool.acquire("a").now_or_panic()));
+
+        c2.set_load(0);
+        let mut c = conn(pool.acquire("a").now_or_panic());
+        assert!(c.conn.is_same(&c2));
+        c.destroy_on_drop();
+        drop(c);
+        assert!(pool.conns.lock().unwrap().is_empty());
+    }
+}
@@ -1,15 +1,16 @@
 use core::time::Duration;
 
 use futures_core::Stream;
+use xitca_io::io::AsyncRead;
 
 use crate::{
-    body::{BodyError, BoxBody, Once},
+    body::{BodyError, BoxBody, Once, ReaderBody},
     bytes::Bytes,
     client::Client,
     error::Error,
     http::{
         self, const_header_value,
-        header::{HeaderMap, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE},
+        header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE},
         Extensions, Method, Version,
     },
     response::Response,
@@ -30,8 +31,20 @@ impl<'a> RequestBuilder<'a> {
         B: Stream<Item = Result<Bytes, E>> + Send + 'static,
         E: Into<BodyError>,
     {
+        let mut req = req.map(BoxBody::new);
+
+        // default headers are only added when request does not contain header with the same name.
+        let headers = req.headers_mut();
+        for name in client.default_headers.keys() {
+            if !headers.contains_key(name) {
+                for value in client.default_headers.get_all(name) {
+                    headers.append(name.clone(), value.clone());
+                }
+            }
+        }
+
         Self {
-            req: req.map(BoxBody::new),
+            req,
             err: Vec::new(),
             client,
             timeout: client.timeout_config.request_timeout,
@@ -54,6 +67,26 @@ impl<'a> RequestBuilder<'a> {
         self.req.headers_mut()
     }
 
+    /// Set a header of this request. Existing header with the same name(including default header set by
+    /// [ClientBuilder::default_header]) would be replaced.
+    ///
+    /// [ClientBuilder::default_header]: crate::builder::ClientBuilder::default_header
+    #[inline]
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers_mut().insert(name, value);
+        self
+    }
+
+    /// Remove a header of this request. Can be used to opt out default header set by
+    /// [ClientBuilder::default_header].
+    ///
+    /// [ClientBuilder::default_header]: crate::builder::ClientBuilder::default_header
+    #[inline]
+    pub fn remove_header(mut self, name: HeaderName) -> Self {
+        self.headers_mut().remove(name);
+        self
+    }
+
     /// Returns request's [Extensions].
     #[inline]
     pub fn extensions(&self) -> &Extensions {
@@ -162,6 +195,42 @@ impl<'a> RequestBuilder<'a> {
         self.map_body(body)
     }
 
+    /// Use [AsyncRead] type as streaming request body.
+    ///
+    /// When `len` is given request body would be sent with [CONTENT_LENGTH] header and reader must produce exactly
+    /// the same length of bytes. Otherwise request body would be sent with chunked transfer encoding for http/1.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_client::{error::Error, Client};
+    /// async fn upload(client: &Client) -> Result<(), Error> {
+    ///     // any type implementing AsyncRead can be used. e.g: tokio::fs::File.
+    ///     let reader: &'static [u8] = b"hello,world!";
+    ///
+    ///     // known length of body.
+    ///     let len = reader.len() as u64;
+    ///     client.post("http://localhost/upload").body_reader(reader, Some(len)).send().await?;
+    ///
+    ///     // unknown length of body.
+    ///     client.post("http://localhost/upload").body_reader(reader, None).send().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn body_reader<R>(mut self, reader: R, len: Option<u64>) -> RequestBuilder<'a>
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        match len {
+            Some(len) => {
+                self.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(len));
+            }
+            None => {
+                self.headers_mut().remove(CONTENT_LENGTH);
+            }
+        }
+        self.map_body(ReaderBody::new(reader, len))
+    }
+
     fn map_body<B, E>(mut self, b: B) -> RequestBuilder<'a>
     where
         B: Stream<Item = Result<Bytes, E>> + Send + 'static,
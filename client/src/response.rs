@@ -8,12 +8,16 @@ use core::{
 use futures_core::stream::Stream;
 use tokio::time::{Instant, Sleep};
 use tracing::debug;
-use xitca_http::{bytes::BytesMut, http};
+use xitca_http::{
+    bytes::{Bytes, BytesMut},
+    http::{self, header::CONTENT_TYPE, HeaderMap},
+};
 
 use crate::{
     body::ResponseBody,
-    error::{Error, TimeoutError},
+    error::{CharsetError, Error, PayloadLimitError, TimeoutError},
     timeout::Timeout,
+    timing::Timing,
 };
 
 const DEFAULT_PAYLOAD_LIMIT: usize = 1024 * 1024 * 8;
@@ -46,7 +50,7 @@ impl<const PAYLOAD_LIMIT: usize> fmt::Debug for Response<'_, PAYLOAD_LIMIT> {
 }
 
 impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
-    #[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
+    #[cfg(any(feature = "http1", feature = "http2", feature = "http3", feature = "cache"))]
     pub(crate) fn new(res: http::Response<ResponseBody<'a>>, timer: Pin<Box<Sleep>>, timeout: Duration) -> Self {
         Self { res, timer, timeout }
     }
@@ -61,6 +65,13 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
         &mut self.res
     }
 
+    /// Get timing metrics of connection setup and response head. See [Timing] for detail.
+    ///
+    /// Return None when response is not produced by client's http service. (e.g. constructed by middleware)
+    pub fn timing(&self) -> Option<Timing> {
+        self.res.extensions().get().copied()
+    }
+
     /// Set payload size limit in bytes. Payload size beyond limit would be discarded.
     ///
     /// Default to 8 Mb.
@@ -98,21 +109,62 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
         self.collect().await
     }
 
+    /// Collect response body as [Bytes] with given size limit in bytes. Response is consumed.
+    ///
+    /// Unlike [Response::limit] a response body exceeds the limit is not truncated and error is returned instead.
+    /// The limit applies together with the payload limit of response type.
+    pub async fn bytes_limited(self, limit: usize) -> Result<Bytes, Error> {
+        self.collect_with::<BytesMut>(Some(limit)).await.map(BytesMut::freeze)
+    }
+
+    /// Collect response body as String and decode it with the charset from `Content-Type` header. Response is
+    /// consumed.
+    ///
+    /// Body is decoded as UTF-8 when charset is absent. Decoding non UTF-8 charset requires `charset` crate
+    /// feature and error is returned for unknown charset or malformed body.
+    pub async fn text_with_charset(self) -> Result<String, Error> {
+        let charset = content_type(self.res.headers())
+            .and_then(|(_, charset)| charset)
+            .map(Box::<str>::from);
+        let bytes = self.collect::<BytesMut>().await?;
+        decode_text(charset.as_deref(), &bytes)
+    }
+
     #[cfg(feature = "json")]
     /// Collect response body as json object. Response is consumed.
     ///
     /// The output type must impl [serde::de::DeserializeOwned] trait.
+    ///
+    /// Response must have `Content-Type` header of `application/json` or `application/*+json` or
+    /// [crate::error::ContentTypeError] is returned without collecting the body.
     pub async fn json<T>(self) -> Result<T, Error>
     where
         T: serde::de::DeserializeOwned,
     {
         use xitca_http::bytes::Buf;
 
+        if !content_type(self.res.headers()).is_some_and(|(mime, _)| is_json(mime)) {
+            return Err(crate::error::ContentTypeError {
+                expect: "application/json",
+                content_type: self.res.headers().get(CONTENT_TYPE).cloned(),
+            }
+            .into());
+        }
+
         let bytes = self.collect::<BytesMut>().await?;
         Ok(serde_json::from_slice(bytes.chunk())?)
     }
 
+    #[inline]
     async fn collect<B>(self) -> Result<B, Error>
+    where
+        B: Collectable,
+    {
+        self.collect_with(None).await
+    }
+
+    // when strict limit is given body exceeds it would be treated as error instead of being truncated.
+    async fn collect_with<B>(self, strict: Option<usize>) -> Result<B, Error>
     where
         B: Collectable,
     {
@@ -121,13 +173,22 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
 
         let mut body = pin!(body);
 
-        let limit = res
+        let len = res
             .headers
             .get(http::header::CONTENT_LENGTH)
-            .and_then(|v| v.to_str().ok().and_then(|str| str.parse::<usize>().ok()))
-            .unwrap_or(PAYLOAD_LIMIT);
+            .and_then(|v| v.to_str().ok().and_then(|str| str.parse::<usize>().ok()));
 
-        let limit = std::cmp::min(limit, PAYLOAD_LIMIT);
+        // reject early when content length is known to exceed strict limit.
+        if let (Some(len), Some(max)) = (len, strict) {
+            if len > max {
+                body.destroy_on_drop();
+                return Err(PayloadLimitError { limit: max }.into());
+            }
+        }
+
+        let limit = std::cmp::min(len.unwrap_or(PAYLOAD_LIMIT), PAYLOAD_LIMIT);
+
+        let mut size = 0;
 
         // TODO: use a meaningful capacity.
         let mut b = B::with_capacity(1024);
@@ -146,6 +207,14 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
                         }
                     };
 
+                    if let Some(max) = strict {
+                        size += buf.len();
+                        if size > max {
+                            body.destroy_on_drop();
+                            return Err(PayloadLimitError { limit: max }.into());
+                        }
+                    }
+
                     b.try_extend_from_slice(&buf)?;
 
                     if buf.len() > limit {
@@ -177,6 +246,53 @@ impl<'a, const PAYLOAD_LIMIT: usize> Response<'a, PAYLOAD_LIMIT> {
     }
 }
 
+// parse Content-Type header into mime type and optional charset parameter.
+fn content_type(headers: &HeaderMap) -> Option<(&str, Option<&str>)> {
+    let mut params = headers.get(CONTENT_TYPE)?.to_str().ok()?.split(';');
+    let mime = params.next()?.trim();
+    let charset = params.find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"'))
+    });
+    Some((mime, charset))
+}
+
+#[cfg(feature = "json")]
+fn is_json(mime: &str) -> bool {
+    let Some((ty, sub_ty)) = mime.split_once('/') else {
+        return false;
+    };
+    ty.eq_ignore_ascii_case("application")
+        && (sub_ty.eq_ignore_ascii_case("json")
+            || sub_ty.len() > 5 && sub_ty[sub_ty.len() - 5..].eq_ignore_ascii_case("+json"))
+}
+
+fn decode_text(charset: Option<&str>, bytes: &[u8]) -> Result<String, Error> {
+    match charset {
+        None => Ok(std::str::from_utf8(bytes)?.to_owned()),
+        Some(label)
+            if ["utf-8", "utf8", "us-ascii"]
+                .iter()
+                .any(|l| l.eq_ignore_ascii_case(label)) =>
+        {
+            Ok(std::str::from_utf8(bytes)?.to_owned())
+        }
+        #[cfg(feature = "charset")]
+        Some(label) => {
+            let encoding = encoding_rs::Encoding::for_label(label.as_bytes())
+                .ok_or_else(|| CharsetError::Unknown(Box::from(label)))?;
+            encoding
+                .decode_without_bom_handling_and_without_replacement(bytes)
+                .map(|str| str.into_owned())
+                .ok_or_else(|| CharsetError::Malformed(encoding.name()).into())
+        }
+        #[cfg(not(feature = "charset"))]
+        Some(label) => Err(CharsetError::Unknown(Box::from(label)).into()),
+    }
+}
+
 trait Collectable {
     fn with_capacity(cap: usize) -> Self;
 
@@ -221,3 +337,53 @@ impl Collectable for String {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use xitca_http::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn content_type_parse() {
+        let mut headers = HeaderMap::new();
+        assert!(content_type(&headers).is_none());
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        assert_eq!(content_type(&headers), Some(("text/plain", None)));
+
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/html; foo=bar; Charset=\"ISO-8859-1\""),
+        );
+        assert_eq!(content_type(&headers), Some(("text/html", Some("ISO-8859-1"))));
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(decode_text(None, "hello".as_bytes()).unwrap(), "hello");
+        assert_eq!(decode_text(Some("UTF-8"), "你好".as_bytes()).unwrap(), "你好");
+        assert!(decode_text(Some("utf-8"), &[0xff]).is_err());
+
+        #[cfg(not(feature = "charset"))]
+        assert!(decode_text(Some("iso-8859-1"), &[0xe9]).is_err());
+
+        #[cfg(feature = "charset")]
+        {
+            assert_eq!(decode_text(Some("iso-8859-1"), &[0xe9]).unwrap(), "é");
+            assert!(decode_text(Some("shift_jis"), &[0x82]).is_err());
+            assert!(decode_text(Some("foo"), b"bar").is_err());
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_mime() {
+        assert!(is_json("application/json"));
+        assert!(is_json("Application/JSON"));
+        assert!(is_json("application/problem+json"));
+        assert!(!is_json("application/+json"));
+        assert!(!is_json("text/json"));
+        assert!(!is_json("text/plain"));
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     http::{Request, Version},
     pool::{exclusive, shared},
     response::Response,
+    timing::Timing,
     uri::Uri,
 };
 
@@ -96,6 +97,9 @@ pub(crate) fn base_service() -> HttpService {
 
             let mut connect = Connect::new(uri);
 
+            // timing metrics of connection setup and response head.
+            let mut timing = Timing::default();
+
             let _date = client.date_service.handle();
 
             loop {
@@ -103,6 +107,7 @@ pub(crate) fn base_service() -> HttpService {
                     Version::HTTP_2 | Version::HTTP_3 => match client.shared_pool.acquire(&connect.uri).await {
                         shared::AcquireOutput::Conn(mut _conn) => {
                             let mut _timer = Box::pin(tokio::time::sleep(timeout));
+                            let _start = tokio::time::Instant::now();
                             *req.version_mut() = version;
                             #[allow(unreachable_code)]
                             return match _conn.conn {
@@ -112,7 +117,9 @@ pub(crate) fn base_service() -> HttpService {
                                         .timeout(_timer.as_mut())
                                         .await
                                     {
-                                        Ok(Ok(res)) => {
+                                        Ok(Ok(mut res)) => {
+                                            timing.ttfb = _start.elapsed();
+                                            res.extensions_mut().insert(timing);
                                             let timeout = client.timeout_config.response_timeout;
                                             Ok(Response::new(res, _timer, timeout))
                                         }
@@ -128,11 +135,14 @@ pub(crate) fn base_service() -> HttpService {
                                 }
                                 #[cfg(feature = "http3")]
                                 crate::connection::ConnectionShared::H3(ref mut conn) => {
-                                    let res = crate::h3::proto::send(conn, _date, core::mem::take(req))
+                                    let mut res = crate::h3::proto::send(conn, _date, core::mem::take(req))
                                         .timeout(_timer.as_mut())
                                         .await
                                         .map_err(|_| TimeoutError::Request)??;
 
+                                    timing.ttfb = _start.elapsed();
+                                    res.extensions_mut().insert(timing);
+
                                     let timeout = client.timeout_config.response_timeout;
                                     Ok(Response::new(res, _timer, timeout))
                                 }
@@ -142,15 +152,20 @@ pub(crate) fn base_service() -> HttpService {
                             Version::HTTP_3 => {
                                 #[cfg(feature = "http3")]
                                 {
+                                    use crate::timing::elapsed;
+
                                     let mut timer = Box::pin(tokio::time::sleep(client.timeout_config.resolve_timeout));
+                                    let start = tokio::time::Instant::now();
 
                                     Service::call(&client.resolver, &mut connect)
                                         .timeout(timer.as_mut())
                                         .await
                                         .map_err(|_| TimeoutError::Resolve)??;
-                                    timer
-                                        .as_mut()
-                                        .reset(tokio::time::Instant::now() + client.timeout_config.connect_timeout);
+
+                                    elapsed(start, &mut timing.dns);
+
+                                    let start = tokio::time::Instant::now();
+                                    timer.as_mut().reset(start + client.timeout_config.connect_timeout);
 
                                     if let Ok(Ok(conn)) = crate::h3::proto::connect(
                                         &client.h3_client,
@@ -160,6 +175,7 @@ pub(crate) fn base_service() -> HttpService {
                                     .timeout(timer.as_mut())
                                     .await
                                     {
+                                        elapsed(start, &mut timing.connect);
                                         _spawner.spawned(conn.into());
                                     } else {
                                         #[cfg(feature = "http2")]
@@ -183,11 +199,12 @@ pub(crate) fn base_service() -> HttpService {
                                 #[cfg(feature = "http2")]
                                 {
                                     let mut timer = Box::pin(tokio::time::sleep(client.timeout_config.resolve_timeout));
-                                    let (conn, alpn_version) =
-                                        client.make_exclusive(&mut connect, &mut timer, Version::HTTP_2).await?;
+                                    let (conn, alpn_version) = client
+                                        .make_exclusive(&mut connect, &mut timer, Version::HTTP_2, &mut timing)
+                                        .await?;
 
                                     if alpn_version == Version::HTTP_2 {
-                                        let conn = crate::h2::proto::handshake(conn).await?;
+                                        let conn = crate::h2::proto::handshake(conn, client.h2_keep_alive).await?;
                                         _spawner.spawned(conn.into());
                                     } else {
                                         #[cfg(not(feature = "http1"))]
@@ -219,12 +236,16 @@ pub(crate) fn base_service() -> HttpService {
                             #[cfg(feature = "http1")]
                             {
                                 let mut timer = Box::pin(tokio::time::sleep(timeout));
-                                let res = crate::h1::proto::send(&mut *_conn, _date, req)
-                                    .timeout(timer.as_mut())
-                                    .await;
+                                let start = tokio::time::Instant::now();
+                                let res =
+                                    crate::h1::proto::send(&mut *_conn, _date, req, client.max_response_head_size)
+                                        .timeout(timer.as_mut())
+                                        .await;
 
                                 return match res {
-                                    Ok(Ok((res, buf, decoder, is_close))) => {
+                                    Ok(Ok((mut res, buf, decoder, is_close))) => {
+                                        timing.ttfb = start.elapsed();
+                                        res.extensions_mut().insert(timing);
                                         if is_close {
                                             _conn.destroy_on_drop();
                                         }
@@ -251,7 +272,9 @@ pub(crate) fn base_service() -> HttpService {
                         }
                         exclusive::AcquireOutput::Spawner(_spawner) => {
                             let mut timer = Box::pin(tokio::time::sleep(client.timeout_config.resolve_timeout));
-                            let (conn, _) = client.make_exclusive(&mut connect, &mut timer, version).await?;
+                            let (conn, _) = client
+                                .make_exclusive(&mut connect, &mut timer, version, &mut timing)
+                                .await?;
                             _spawner.spawned(conn);
                         }
                     },
@@ -0,0 +1,50 @@
+use core::time::Duration;
+
+use tokio::time::Instant;
+
+/// timing metrics of sending request and receiving response head. It can be accessed with [Response::timing] for
+/// performance debugging.
+///
+/// connection setup phases are absent when the request is sent over a connection reused from connection pool.
+///
+/// [Response::timing]: crate::Response::timing
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timing {
+    pub(crate) dns: Option<Duration>,
+    pub(crate) connect: Option<Duration>,
+    pub(crate) tls: Option<Duration>,
+    pub(crate) ttfb: Duration,
+}
+
+impl Timing {
+    /// duration of dns resolving.
+    pub fn dns(&self) -> Option<Duration> {
+        self.dns
+    }
+
+    /// duration of establishing tcp connection(or unix domain socket connection). For http/3 it's the duration of
+    /// quic handshake.
+    pub fn connect(&self) -> Option<Duration> {
+        self.connect
+    }
+
+    /// duration of tls handshake.
+    pub fn tls(&self) -> Option<Duration> {
+        self.tls
+    }
+
+    /// duration from request started being sent to response head received(time to first byte).
+    pub fn ttfb(&self) -> Duration {
+        self.ttfb
+    }
+
+    /// true when request is sent over a connection reused from connection pool.
+    pub fn is_reused(&self) -> bool {
+        self.connect.is_none()
+    }
+}
+
+// record elapsed time of a phase.
+pub(crate) fn elapsed(start: Instant, phase: &mut Option<Duration>) {
+    *phase = Some(start.elapsed());
+}
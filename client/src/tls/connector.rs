@@ -128,11 +128,17 @@ pub(crate) mod rustls {
 
         root_certs.extend(TLS_SERVER_ROOTS.iter().cloned());
 
-        let mut config = ClientConfig::builder()
+        let config = ClientConfig::builder()
             .with_root_certificates(root_certs)
             .with_no_client_auth();
 
-        config.alpn_protocols = protocols.iter().map(|p| p.to_vec()).collect();
+        connect_with_config(config, protocols)
+    }
+
+    pub(crate) fn connect_with_config(mut config: ClientConfig, protocols: &[&[u8]]) -> Connector {
+        if config.alpn_protocols.is_empty() {
+            config.alpn_protocols = protocols.iter().map(|p| p.to_vec()).collect();
+        }
 
         Box::new(TlsConnector(Arc::new(config)))
     }
@@ -1,6 +1,6 @@
 //! websocket request/response handling.
 
-pub use http_ws::Message;
+pub use http_ws::{websocket::WebSocketStream, Message};
 
 use core::{
     mem,
@@ -33,6 +33,8 @@ pub type WsRequest<'a> = TunnelRequest<'a, marker::WebSocket>;
 /// A unified websocket that can be used as both sender/receiver.
 ///
 /// * This type can not handle concurrent message which means send always block receive and vice versa.
+/// * This type is a raw websocket without protocol handling like ping/pong and close handshake. It can be
+///   wrapped by [WebSocketStream] for automatic handling of them.
 pub type WebSocket<'a> = Tunnel<WebSocketTunnel<'a>>;
 
 /// sender part of websocket connection.
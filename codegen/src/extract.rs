@@ -0,0 +1,122 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+    Data, DeriveInput, Error, Fields, GenericParam, Index, Lifetime, Type,
+};
+
+pub(crate) fn extract(input: DeriveInput) -> Result<TokenStream, Error> {
+    let ty_ident = &input.ident;
+
+    let Data::Struct(ref ty) = input.data else {
+        return Err(Error::new(ty_ident.span(), "expect Struct"));
+    };
+
+    // at most one lifetime param is allowed and it's bound to FromRequest trait's lifetime.
+    let mut lifetime = None;
+    for param in input.generics.params.iter() {
+        match param {
+            GenericParam::Lifetime(param) if lifetime.is_none() => lifetime = Some(param.lifetime.clone()),
+            param => {
+                return Err(Error::new(
+                    param.span(),
+                    "expect Struct with no generic param other than one lifetime",
+                ))
+            }
+        }
+    }
+
+    let with_lifetime = |lt: &str| {
+        let lt = Lifetime::new(lt, ty_ident.span());
+        let ty = lifetime
+            .as_ref()
+            .map(|_| quote! { #ty_ident<#lt> })
+            .unwrap_or(quote! { #ty_ident });
+        (lt, ty)
+    };
+
+    let (lt_a, self_ty) = with_lifetime("'__a");
+    let (lt_b, gat_ty) = with_lifetime("'__b");
+    let lt_f = Lifetime::new("'__f", ty_ident.span());
+
+    let req = quote! { ::xitca_web::WebContext<'__r, __C, __B> };
+
+    let mut bounds = Vec::new();
+    let mut fields = Vec::new();
+
+    for (idx, field) in ty.fields.iter().enumerate() {
+        let ty_a = replace_lifetime(&field.ty, lifetime.as_ref(), &lt_a);
+        let ty_f = replace_lifetime(&field.ty, lifetime.as_ref(), &lt_f);
+
+        bounds.push(quote! {
+            for<#lt_f> #ty_f: ::xitca_web::handler::FromRequest<#lt_f, #req, Error = ::xitca_web::error::Error<__C>>
+        });
+
+        let name = field
+            .ident
+            .as_ref()
+            .map(|ident| ident.to_string())
+            .unwrap_or_else(|| idx.to_string());
+
+        let value = quote! {
+            <#ty_a as ::xitca_web::handler::FromRequest<#lt_a, #req>>::from_request(ctx)
+                .await
+                .map_err(|e| ::xitca_web::error::ExtractError::new(#name, e))?
+        };
+
+        fields.push(match field.ident {
+            Some(ref ident) => quote! { #ident: #value },
+            None => {
+                let idx = Index::from(idx);
+                quote! { #idx: #value }
+            }
+        });
+    }
+
+    let construct = match ty.fields {
+        Fields::Unit => quote! { Self },
+        _ => quote! { Self { #(#fields),* } },
+    };
+
+    // silence unused variable warning for struct without field.
+    let ctx = format_ident!("{}", if fields.is_empty() { "_ctx" } else { "ctx" });
+
+    Ok(quote! {
+        impl<#lt_a, '__r, __C, __B> ::xitca_web::handler::FromRequest<#lt_a, #req> for #self_ty
+        where
+            __C: 'static,
+            #(#bounds,)*
+        {
+            type Type<#lt_b> = #gat_ty;
+            type Error = ::xitca_web::error::Error<__C>;
+
+            async fn from_request(#ctx: &#lt_a #req) -> Result<Self, Self::Error> {
+                Ok(#construct)
+            }
+        }
+    }
+    .into())
+}
+
+fn replace_lifetime(ty: &Type, from: Option<&Lifetime>, to: &Lifetime) -> Type {
+    struct Replace<'a> {
+        from: &'a Lifetime,
+        to: &'a Lifetime,
+    }
+
+    impl VisitMut for Replace<'_> {
+        fn visit_lifetime_mut(&mut self, lt: &mut Lifetime) {
+            if lt.ident == self.from.ident {
+                *lt = self.to.clone();
+            }
+            visit_mut::visit_lifetime_mut(self, lt)
+        }
+    }
+
+    let mut ty = ty.clone();
+    if let Some(from) = from {
+        Replace { from, to }.visit_type_mut(&mut ty);
+    }
+    ty
+}
@@ -1,4 +1,6 @@
 mod error;
+mod extract;
+mod pg_enum;
 mod route;
 mod service;
 mod state;
@@ -12,6 +14,20 @@ pub fn state_impl(item: TokenStream) -> TokenStream {
     state::state(item).unwrap_or_else(|e| e.to_compile_error().into())
 }
 
+#[proc_macro_derive(Extract)]
+pub fn extract_impl(item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item);
+    extract::extract(item).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+/// derive macro mapping rust enum to postgres enum type by label for `xitca-postgres`.
+/// See `xitca_postgres::PgEnum` for detail.
+#[proc_macro_derive(PgEnum, attributes(postgres))]
+pub fn pg_enum_impl(item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item);
+    pg_enum::pg_enum(item).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
 /// attribute macro for `xitca-web` application.
 ///
 /// # Pattern
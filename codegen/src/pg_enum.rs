@@ -0,0 +1,143 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Error, Fields, LitStr};
+
+pub(crate) fn pg_enum(input: DeriveInput) -> Result<TokenStream, Error> {
+    let ty_ident = &input.ident;
+
+    let Data::Enum(ref ty) = input.data else {
+        return Err(Error::new(ty_ident.span(), "expect Enum"));
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(ty_ident.span(), "generic enum is not supported"));
+    }
+
+    let name = rename(&input.attrs)?.unwrap_or_else(|| snake_case(&ty_ident.to_string()));
+
+    let mut idents = Vec::new();
+    let mut labels = Vec::new();
+
+    for variant in ty.variants.iter() {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(variant.ident.span(), "expect unit variant"));
+        }
+        let label = rename(&variant.attrs)?.unwrap_or_else(|| snake_case(&variant.ident.to_string()));
+        if labels.contains(&label) {
+            return Err(Error::new(variant.ident.span(), format!("duplicate label {label}")));
+        }
+        idents.push(&variant.ident);
+        labels.push(label);
+    }
+
+    let len = labels.len();
+
+    Ok(quote! {
+        impl #ty_ident {
+            #[doc(hidden)]
+            fn __pg_enum_accepts(ty: &::xitca_postgres::Type) -> bool {
+                if ty.name() != #name {
+                    return false;
+                }
+                match ty.kind() {
+                    ::xitca_postgres::types::Kind::Enum(variants) => {
+                        variants.len() == #len && variants.iter().all(|v| matches!(v.as_str(), #(#labels)|*))
+                    }
+                    _ => false,
+                }
+            }
+        }
+
+        impl<'a> ::xitca_postgres::FromSql<'a> for #ty_ident {
+            fn from_sql(
+                _: &::xitca_postgres::Type,
+                raw: &'a [u8],
+            ) -> Result<Self, Box<dyn ::std::error::Error + Sync + Send>> {
+                match ::core::str::from_utf8(raw)? {
+                    #(#labels => Ok(Self::#idents),)*
+                    s => Err(format!("invalid variant `{}` of enum type {}", s, #name).into()),
+                }
+            }
+
+            fn accepts(ty: &::xitca_postgres::Type) -> bool {
+                Self::__pg_enum_accepts(ty)
+            }
+        }
+
+        impl<'a> ::xitca_postgres::FromSqlExt<'a> for #ty_ident {
+            #[inline]
+            fn from_sql_nullable_ext(
+                ty: &::xitca_postgres::Type,
+                (range, buf): (&::core::ops::Range<usize>, &'a ::xitca_postgres::types::Bytes),
+            ) -> Result<Self, Box<dyn ::std::error::Error + Sync + Send>> {
+                <Self as ::xitca_postgres::FromSql>::from_sql_nullable(ty, buf.get(range.clone()))
+            }
+
+            #[inline]
+            fn accepts(ty: &::xitca_postgres::Type) -> bool {
+                Self::__pg_enum_accepts(ty)
+            }
+        }
+
+        impl ::xitca_postgres::ToSql for #ty_ident {
+            fn to_sql(
+                &self,
+                _: &::xitca_postgres::Type,
+                out: &mut ::xitca_postgres::types::BytesMut,
+            ) -> Result<::xitca_postgres::types::IsNull, Box<dyn ::std::error::Error + Sync + Send>> {
+                let label = match self {
+                    #(Self::#idents => #labels,)*
+                };
+                out.extend_from_slice(label.as_bytes());
+                Ok(::xitca_postgres::types::IsNull::No)
+            }
+
+            fn accepts(ty: &::xitca_postgres::Type) -> bool {
+                Self::__pg_enum_accepts(ty)
+            }
+
+            fn to_sql_checked(
+                &self,
+                ty: &::xitca_postgres::Type,
+                out: &mut ::xitca_postgres::types::BytesMut,
+            ) -> Result<::xitca_postgres::types::IsNull, Box<dyn ::std::error::Error + Sync + Send>> {
+                if !Self::__pg_enum_accepts(ty) {
+                    return Err(Box::new(::xitca_postgres::error::WrongType::new::<Self>(ty.clone())));
+                }
+                self.to_sql(ty, out)
+            }
+        }
+    }
+    .into())
+}
+
+// parse #[postgres(rename = "name")] attribute.
+fn rename(attrs: &[Attribute]) -> Result<Option<String>, Error> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("postgres")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported postgres attribute. expect rename = \"<name>\""))
+            }
+        })?;
+    }
+    Ok(name)
+}
+
+fn snake_case(ident: &str) -> String {
+    let mut s = String::with_capacity(ident.len());
+    for (i, c) in ident.char_indices() {
+        if c.is_uppercase() {
+            if i != 0 {
+                s.push('_');
+            }
+            s.extend(c.to_lowercase());
+        } else {
+            s.push(c);
+        }
+    }
+    s
+}
@@ -0,0 +1,132 @@
+use http::{
+    header::{HeaderMap, HeaderValue, ACCEPT_ENCODING},
+    request::Request,
+};
+
+/// content encoding of pre-compressed sibling file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    Br,
+    Gzip,
+}
+
+impl Encoding {
+    /// file extension appended to the path of original file.
+    pub(crate) const fn extension(self) -> &'static str {
+        match self {
+            Self::Br => "br",
+            Self::Gzip => "gz",
+        }
+    }
+
+    pub(crate) const fn header_value(self) -> HeaderValue {
+        match self {
+            Self::Br => HeaderValue::from_static("br"),
+            Self::Gzip => HeaderValue::from_static("gzip"),
+        }
+    }
+}
+
+/// enabled pre-compressed sibling file encodings.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Precompressed {
+    pub(crate) br: bool,
+    pub(crate) gzip: bool,
+}
+
+impl Precompressed {
+    pub(crate) const fn is_enabled(&self) -> bool {
+        self.br || self.gzip
+    }
+
+    /// enabled encodings acceptable by request's `Accept-Encoding` headers in order of preference.
+    /// br is preferred over gzip when they share the same quality value.
+    pub(crate) fn negotiate<Ext>(&self, req: &Request<Ext>) -> impl Iterator<Item = Encoding> {
+        let (br, gzip) = qualities(req.headers());
+
+        let br = (self.br && br > 0).then_some((Encoding::Br, br));
+        let gzip = (self.gzip && gzip > 0).then_some((Encoding::Gzip, gzip));
+
+        let order = match (br, gzip) {
+            (Some(br), Some(gzip)) if gzip.1 > br.1 => [Some(gzip), Some(br)],
+            _ => [br, gzip],
+        };
+
+        order.into_iter().flatten().map(|(enc, _)| enc)
+    }
+}
+
+// quality values of br and gzip in thousandths. encoding not acceptable has the value of 0.
+fn qualities(headers: &HeaderMap) -> (u16, u16) {
+    let (mut br, mut gzip, mut wildcard) = (None, None, None);
+
+    for (name, q) in headers
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(parse_coding)
+    {
+        if name.eq_ignore_ascii_case("br") {
+            br = Some(q);
+        } else if name.eq_ignore_ascii_case("gzip") || name.eq_ignore_ascii_case("x-gzip") {
+            gzip = Some(q);
+        } else if name == "*" {
+            wildcard = Some(q);
+        }
+    }
+
+    let wildcard = wildcard.unwrap_or(0);
+    (br.unwrap_or(wildcard), gzip.unwrap_or(wildcard))
+}
+
+fn parse_coding(coding: &str) -> Option<(&str, u16)> {
+    let mut params = coding.split(';');
+    let name = params.next()?.trim();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let q = params
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("q"))
+        .map(|(_, q)| parse_q(q.trim()))
+        .unwrap_or(Some(1000))?;
+
+    Some((name, q))
+}
+
+fn parse_q(q: &str) -> Option<u16> {
+    q.parse::<f32>()
+        .ok()
+        .filter(|q| (0.0..=1.0).contains(q))
+        .map(|q| (q * 1000.0) as u16)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn negotiate(precompressed: Precompressed, accept: &str) -> Vec<Encoding> {
+        let req = Request::builder().header(ACCEPT_ENCODING, accept).body(()).unwrap();
+        precompressed.negotiate(&req).collect()
+    }
+
+    #[test]
+    fn negotiate_order() {
+        let all = Precompressed { br: true, gzip: true };
+
+        assert_eq!(negotiate(all, "gzip, deflate, br"), [Encoding::Br, Encoding::Gzip]);
+        assert_eq!(negotiate(all, "br;q=0.5, gzip"), [Encoding::Gzip, Encoding::Br]);
+        assert_eq!(negotiate(all, "br;q=0, gzip;q=0.1"), [Encoding::Gzip]);
+        assert_eq!(negotiate(all, "*;q=0.2, gzip;q=0.5"), [Encoding::Gzip, Encoding::Br]);
+        assert_eq!(negotiate(all, "identity"), []);
+        assert_eq!(negotiate(all, "gzip;q=invalid, br"), [Encoding::Br]);
+
+        let gzip = Precompressed { br: false, gzip: true };
+
+        assert_eq!(negotiate(gzip, "br, gzip"), [Encoding::Gzip]);
+        assert_eq!(negotiate(gzip, "br"), []);
+    }
+}
@@ -7,23 +7,28 @@ pub mod runtime;
 mod buf;
 mod chunk;
 mod date;
+mod encoding;
 mod error;
 
 pub use self::{chunk::ChunkReader, error::ServeError};
 
 use std::{
-    io::SeekFrom,
+    io::{self, SeekFrom},
     path::{Component, Path, PathBuf},
 };
 
 use http::{
-    header::{HeaderValue, ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, LAST_MODIFIED, RANGE},
+    header::{
+        HeaderValue, ACCEPT_RANGES, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, LAST_MODIFIED,
+        RANGE, VARY,
+    },
     Method, Request, Response, StatusCode,
 };
 use mime_guess::mime;
 
 use self::{
     buf::buf_write_header,
+    encoding::{Encoding, Precompressed},
     runtime::{AsyncFs, ChunkRead, Meta},
 };
 
@@ -32,6 +37,7 @@ use self::{
 pub struct ServeDir<FS: AsyncFs = runtime::TokioFs> {
     chunk_size: usize,
     base_path: PathBuf,
+    precompressed: Precompressed,
     async_fs: FS,
 }
 
@@ -40,6 +46,7 @@ pub struct ServeDir<FS: AsyncFs = runtime::TokioFs> {
 pub struct ServeDir<FS: AsyncFs> {
     chunk_size: usize,
     base_path: PathBuf,
+    precompressed: Precompressed,
     async_fs: FS,
 }
 
@@ -66,6 +73,7 @@ impl<FS: AsyncFs> ServeDir<FS> {
         Self {
             chunk_size: 4096,
             base_path: path.into(),
+            precompressed: Precompressed::default(),
             async_fs,
         }
     }
@@ -78,6 +86,25 @@ impl<FS: AsyncFs> ServeDir<FS> {
         self
     }
 
+    /// serve pre-compressed sibling file with `.br` extension when request's `Accept-Encoding` header accepts br
+    /// encoding. For example request to `/app.js` is served with `app.js.br` file and `Content-Encoding: br` header
+    /// when the sibling file exists. `Content-Type` header is derived from the original file path and fall back to
+    /// original file when sibling file can not be found.
+    ///
+    /// When any pre-compressed encoding is enabled `Vary: Accept-Encoding` header is added to all successful
+    /// responses so caches would not serve encoded content to clients that do not accept it.
+    pub fn precompressed_br(&mut self) -> &mut Self {
+        self.precompressed.br = true;
+        self
+    }
+
+    /// serve pre-compressed sibling file with `.gz` extension when request's `Accept-Encoding` header accepts gzip
+    /// encoding. See [ServeDir::precompressed_br] for detail.
+    pub fn precompressed_gzip(&mut self) -> &mut Self {
+        self.precompressed.gzip = true;
+        self
+    }
+
     /// try to find a matching file from given input request and generate http response with stream
     /// reader of matched file.
     ///
@@ -106,54 +133,109 @@ impl<FS: AsyncFs> ServeDir<FS> {
             .first_raw()
             .unwrap_or_else(|| mime::APPLICATION_OCTET_STREAM.as_ref());
 
-        let mut file = self.async_fs.open(path).await?;
-
-        let modified = date::mod_date_check(req, &mut file)?;
-
-        let mut res = Response::new(());
+        let (file, encoding) = self.open(req, path).await?;
 
-        let mut size = file.len();
+        let mut res = serve_file(req, file, HeaderValue::from_static(ct), self.chunk_size).await?;
 
-        if let Some(range) = req
-            .headers()
-            .get(RANGE)
-            .and_then(|h| h.to_str().ok())
-            .and_then(|range| http_range_header::parse_range_header(range).ok())
-            .map(|range| range.validate(size))
-        {
-            let (start, end) = range
-                .map_err(|_| ServeError::RangeNotSatisfied(size))?
-                .pop()
-                .expect("http_range_header produced empty range")
-                .into_inner();
-
-            file.seek(SeekFrom::Start(start)).await?;
+        if self.precompressed.is_enabled() {
+            res.headers_mut()
+                .append(VARY, HeaderValue::from_static("accept-encoding"));
+            if let Some(encoding) = encoding {
+                res.headers_mut().insert(CONTENT_ENCODING, encoding.header_value());
+            }
+        }
 
-            *res.status_mut() = StatusCode::PARTIAL_CONTENT;
-            let val = buf_write_header!(0, "bytes {start}-{end}/{size}");
-            res.headers_mut().insert(CONTENT_RANGE, val);
+        Ok(res)
+    }
 
-            size = end - start + 1;
+    // open pre-compressed sibling file acceptable by request and fall back to the original file.
+    async fn open<Ext>(&self, req: &Request<Ext>, path: PathBuf) -> Result<(FS::File, Option<Encoding>), ServeError> {
+        for encoding in self.precompressed.negotiate(req) {
+            let mut sibling = path.clone().into_os_string();
+            sibling.push(".");
+            sibling.push(encoding.extension());
+
+            match self.async_fs.open(sibling.into()).await {
+                Ok(file) => return Ok((file, Some(encoding))),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
         }
 
-        res.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(ct));
-        res.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(size));
-        res.headers_mut()
-            .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        let file = self.async_fs.open(path).await?;
+        Ok((file, None))
+    }
+}
 
-        if let Some(modified) = modified {
-            let val = date::date_to_header(modified);
-            res.headers_mut().insert(LAST_MODIFIED, val);
-        }
+/// generate http response with stream reader of given opened file and content type. useful for serving file
+/// outside of [ServeDir].
+///
+/// `Range`, `If-Modified-Since` and `If-Unmodified-Since` headers of request are honored. Response of `HEAD`
+/// request method has empty body. request method is not checked and it's caller's responsibility.
+///
+/// # Examples
+/// ```rust
+/// # use http_file::{runtime::{ChunkRead, Meta}, serve_file};
+/// # use http::{HeaderValue, Request};
+/// async fn serve<F: ChunkRead + Meta>(req: &Request<()>, file: F) {
+///     let ct = HeaderValue::from_static("text/plain");
+///     let res = serve_file(req, file, ct, 4096).await;
+/// }
+/// ```
+pub async fn serve_file<F, Ext>(
+    req: &Request<Ext>,
+    mut file: F,
+    content_type: HeaderValue,
+    chunk_size: usize,
+) -> Result<Response<ChunkReader<F>>, ServeError>
+where
+    F: ChunkRead + Meta,
+{
+    let modified = date::mod_date_check(req, &mut file)?;
+
+    let mut res = Response::new(());
+
+    let mut size = file.len();
+
+    if let Some(range) = req
+        .headers()
+        .get(RANGE)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|range| http_range_header::parse_range_header(range).ok())
+        .map(|range| range.validate(size))
+    {
+        let (start, end) = range
+            .map_err(|_| ServeError::RangeNotSatisfied(size))?
+            .pop()
+            .expect("http_range_header produced empty range")
+            .into_inner();
+
+        file.seek(SeekFrom::Start(start)).await?;
+
+        *res.status_mut() = StatusCode::PARTIAL_CONTENT;
+        let val = buf_write_header!(0, "bytes {start}-{end}/{size}");
+        res.headers_mut().insert(CONTENT_RANGE, val);
+
+        size = end - start + 1;
+    }
 
-        let stream = if matches!(*req.method(), Method::HEAD) {
-            ChunkReader::empty()
-        } else {
-            ChunkReader::reader(file, size, self.chunk_size)
-        };
+    res.headers_mut().insert(CONTENT_TYPE, content_type);
+    res.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(size));
+    res.headers_mut()
+        .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
 
-        Ok(res.map(|_| stream))
+    if let Some(modified) = modified {
+        let val = date::date_to_header(modified);
+        res.headers_mut().insert(LAST_MODIFIED, val);
     }
+
+    let stream = if matches!(*req.method(), Method::HEAD) {
+        ChunkReader::empty()
+    } else {
+        ChunkReader::reader(file, size, chunk_size)
+    };
+
+    Ok(res.map(|_| stream))
 }
 
 impl<FS: AsyncFs> ServeDir<FS> {
@@ -277,6 +359,68 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn precompressed() {
+        let mut dir = ServeDir::new("sample");
+        dir.precompressed_br().precompressed_gzip();
+
+        let req = Request::builder()
+            .uri("/test.txt")
+            .header("accept-encoding", "br, gzip")
+            .body(())
+            .unwrap();
+        let res = dir.serve(&req).await.unwrap();
+        assert_eq!(
+            res.headers().get(CONTENT_TYPE).unwrap(),
+            HeaderValue::from_static("text/plain")
+        );
+        assert_eq!(
+            res.headers().get(CONTENT_ENCODING).unwrap(),
+            HeaderValue::from_static("gzip")
+        );
+        assert_eq!(
+            res.headers().get(VARY).unwrap(),
+            HeaderValue::from_static("accept-encoding")
+        );
+        let len = std::fs::metadata("sample/test.txt.gz").unwrap().len();
+        assert_eq!(res.headers().get(CONTENT_LENGTH).unwrap(), HeaderValue::from(len));
+
+        // br sibling file does not exist and fall back to original file.
+        let req = Request::builder()
+            .uri("/test.txt")
+            .header("accept-encoding", "br")
+            .body(())
+            .unwrap();
+        let res = dir.serve(&req).await.unwrap();
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(
+            res.headers().get(VARY).unwrap(),
+            HeaderValue::from_static("accept-encoding")
+        );
+        assert_eq!(
+            res.headers().get(CONTENT_LENGTH).unwrap(),
+            HeaderValue::from("hello, world!".len())
+        );
+
+        let req = Request::builder()
+            .uri("/test.txt")
+            .header("accept-encoding", "gzip;q=0")
+            .body(())
+            .unwrap();
+        let res = dir.serve(&req).await.unwrap();
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+
+        // pre-compressed serving is opt-in.
+        let req = Request::builder()
+            .uri("/test.txt")
+            .header("accept-encoding", "gzip")
+            .body(())
+            .unwrap();
+        let res = ServeDir::new("sample").serve(&req).await.unwrap();
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert!(res.headers().get(VARY).is_none());
+    }
+
     #[tokio::test]
     async fn body_size_hint() {
         let dir = ServeDir::new("sample");
@@ -351,6 +495,20 @@ mod test {
         assert_eq!("llo, world!", res);
     }
 
+    #[tokio::test]
+    async fn serve_file_content_type() {
+        let file = runtime::TokioFs.open("sample/test.txt".into()).await.unwrap();
+        let req = Request::builder().header("range", "bytes=7-").body(()).unwrap();
+        let ct = HeaderValue::from_static("application/octet-stream");
+        let res = serve_file(&req, file, ct.clone(), 4096).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), ct);
+        assert_eq!(
+            res.headers().get(CONTENT_RANGE).unwrap(),
+            HeaderValue::from_static("bytes 7-12/13")
+        );
+    }
+
     #[tokio::test]
     async fn ranged() {
         test_range(ServeDir::new("sample")).await;
@@ -54,7 +54,7 @@ pub trait ChunkRead: Sized {
 }
 
 #[cfg(feature = "tokio")]
-pub(crate) use tokio_impl::TokioFs;
+pub use tokio_impl::TokioFs;
 
 #[cfg(feature = "tokio")]
 mod tokio_impl {
@@ -65,6 +65,7 @@ mod tokio_impl {
 
     use super::*;
 
+    /// async file system backed by tokio's blocking thread pool.
     #[derive(Clone)]
     pub struct TokioFs;
 
@@ -130,7 +131,7 @@ mod tokio_impl {
 }
 
 #[cfg(feature = "tokio-uring")]
-pub(crate) use tokio_uring_impl::TokioUringFs;
+pub use tokio_uring_impl::TokioUringFs;
 
 #[cfg(feature = "tokio-uring")]
 mod tokio_uring_impl {
@@ -138,6 +139,7 @@ mod tokio_uring_impl {
 
     use super::*;
 
+    /// async file system backed by tokio-uring.
     #[derive(Clone)]
     pub struct TokioUringFs;
 
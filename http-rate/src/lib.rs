@@ -45,6 +45,14 @@ impl RateLimit {
             .or_else(|| maybe_x_real_ip(headers))
             .or_else(|| maybe_forwarded(headers))
             .unwrap_or_else(|| addr.ip());
+        self.rate_limit_ip(addr)
+    }
+
+    /// Rate limit with given client [IpAddr] as is. Useful when client address is already resolved from
+    /// trusted source and headers of [Request] should not be looked into.
+    ///
+    /// [Request]: http::Request
+    pub fn rate_limit_ip(&self, addr: IpAddr) -> Result<RateSnapshot, TooManyRequests> {
         self.limit.check_key(&addr).map_err(TooManyRequests::from)
     }
 }
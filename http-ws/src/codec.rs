@@ -170,12 +170,13 @@ impl Codec {
                     Err(ProtocolError::ContinuationNotStarted)
                 }
                 OpCode::Continue => {
+                    let payload = payload.unwrap_or_else(Bytes::new);
                     if finished {
                         self.flags.remove(Flags::CONTINUATION);
+                        Ok(Some(Message::Continuation(Item::Last(payload))))
+                    } else {
+                        Ok(Some(Message::Continuation(Item::Continue(payload))))
                     }
-                    Ok(Some(Message::Continuation(Item::Continue(
-                        payload.unwrap_or_else(Bytes::new),
-                    ))))
                 }
                 OpCode::Binary if !finished => {
                     self.try_start_continue()?;
@@ -16,6 +16,7 @@ pub enum ProtocolError {
     ContinuationNotStarted,
     ContinuationStarted,
     ContinuationFragment(OpCode),
+    InvalidUtf8,
     Closed,
 }
 
@@ -31,6 +32,7 @@ impl fmt::Display for ProtocolError {
             Self::ContinuationNotStarted => f.write_str("Continuation is not started."),
             Self::ContinuationStarted => f.write_str("Received new continuation but it is already started."),
             Self::ContinuationFragment(ref code) => write!(f, "Unknown continuation fragment with OpCode: {code}."),
+            Self::InvalidUtf8 => f.write_str("Text message is not valid utf-8."),
             Self::Closed => f.write_str("Connection already closed."),
         }
     }
@@ -38,7 +38,7 @@ extern crate alloc;
 use http::{
     header::{
         HeaderMap, HeaderName, HeaderValue, ALLOW, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY,
-        SEC_WEBSOCKET_VERSION, UPGRADE,
+        SEC_WEBSOCKET_PROTOCOL, SEC_WEBSOCKET_VERSION, UPGRADE,
     },
     request::Request,
     response::{Builder, Response},
@@ -130,6 +130,77 @@ pub fn handshake(method: &Method, headers: &HeaderMap) -> Result<Builder, Handsh
     Ok(builder)
 }
 
+/// Verify HTTP/1.1 WebSocket handshake request and create handshake response with negotiated subprotocol.
+///
+/// See [negotiate_protocol] for how subprotocol is selected. `Sec-WebSocket-Protocol` header is added to response
+/// when a subprotocol is selected.
+///
+/// # Examples
+/// ```rust
+/// use http::{header, Method, Request};
+/// use http_ws::handshake_with_protocols;
+///
+/// let request = Request::get("/")
+///     .header(header::UPGRADE, "websocket")
+///     .header(header::CONNECTION, "upgrade")
+///     .header(header::SEC_WEBSOCKET_VERSION, "13")
+///     .header(header::SEC_WEBSOCKET_KEY, "dGhlIHNhbXBsZSBub25jZQ==")
+///     .header(header::SEC_WEBSOCKET_PROTOCOL, "chat, superchat")
+///     .body(())
+///     .unwrap();
+///
+/// let (builder, protocol) = handshake_with_protocols(request.method(), request.headers(), &["superchat"]).unwrap();
+/// assert_eq!(protocol, Some("superchat"));
+///
+/// let response = builder.body(()).unwrap();
+/// assert_eq!(response.headers()[header::SEC_WEBSOCKET_PROTOCOL], "superchat");
+/// assert_eq!(response.headers()[header::SEC_WEBSOCKET_ACCEPT], "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+/// ```
+pub fn handshake_with_protocols<'a>(
+    method: &Method,
+    headers: &HeaderMap,
+    protocols: &[&'a str],
+) -> Result<(Builder, Option<&'a str>), HandshakeError> {
+    let mut builder = handshake(method, headers)?;
+    let protocol = negotiate_protocol(headers, protocols);
+    if let Some(protocol) = protocol {
+        builder = builder.header(SEC_WEBSOCKET_PROTOCOL, protocol);
+    }
+    Ok((builder, protocol))
+}
+
+/// Select subprotocol from `Sec-WebSocket-Protocol` request headers.
+///
+/// The first one of given protocols(in order of server preference) offered by client is selected. Protocol names
+/// are compared case sensitively. `None` is returned when client offers no subprotocol or none of them is supported.
+pub fn negotiate_protocol<'a>(headers: &HeaderMap, protocols: &[&'a str]) -> Option<&'a str> {
+    let offered = || {
+        headers
+            .get_all(SEC_WEBSOCKET_PROTOCOL)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+    };
+
+    protocols
+        .iter()
+        .copied()
+        .find(|protocol| offered().any(|offer| offer == *protocol))
+}
+
+/// Compute `Sec-WebSocket-Accept` header value from `Sec-WebSocket-Key` header value.
+///
+/// # Examples
+/// ```rust
+/// let value = http_ws::accept_key(b"dGhlIHNhbXBsZSBub25jZQ==");
+/// assert_eq!(value, "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+/// ```
+pub fn accept_key(key: &[u8]) -> HeaderValue {
+    // key is known to be header value safe ascii
+    HeaderValue::from_bytes(&hash_key(key)).unwrap()
+}
+
 /// Verify HTTP/2 WebSocket handshake request and create handshake response.
 pub fn handshake_h2(method: &Method, headers: &HeaderMap) -> Result<Builder, HandshakeError> {
     // Check for method
@@ -183,17 +254,11 @@ fn verify_handshake<'a>(method: &'a Method, headers: &'a HeaderMap) -> Result<&'
 ///
 /// This function returns handshake `http::response::Builder`, ready to send to peer.
 fn handshake_response(key: &[u8]) -> Builder {
-    let key = hash_key(key);
-
     Response::builder()
         .status(StatusCode::SWITCHING_PROTOCOLS)
         .header(UPGRADE, WEBSOCKET)
         .header(CONNECTION, UPGRADE_VALUE)
-        .header(
-            SEC_WEBSOCKET_ACCEPT,
-            // key is known to be header value safe ascii
-            HeaderValue::from_bytes(&key).unwrap(),
-        )
+        .header(SEC_WEBSOCKET_ACCEPT, accept_key(key))
 }
 
 // check supported version
@@ -213,7 +278,10 @@ fn ws_version_check(headers: &HeaderMap) -> Result<(), HandshakeError> {
 pub mod stream;
 
 #[cfg(feature = "stream")]
-pub use self::stream::{RequestStream, ResponseSender, ResponseStream, ResponseWeakSender, WsError};
+pub mod websocket;
+
+#[cfg(feature = "stream")]
+pub use self::stream::{RequestStream, ResponseSender, ResponseSink, ResponseStream, ResponseWeakSender, WsError};
 
 #[cfg(feature = "stream")]
 pub type WsOutput<B> = (RequestStream<B>, Response<ResponseStream>, ResponseSender);
@@ -364,6 +432,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_negotiate_protocol() {
+        let req = Request::builder()
+            .header(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static("chat,  v1.json"))
+            .header(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static("v2.json"))
+            .body(())
+            .unwrap();
+
+        assert_eq!(negotiate_protocol(req.headers(), &["v2.json", "chat"]), Some("v2.json"));
+        assert_eq!(negotiate_protocol(req.headers(), &["v1.json"]), Some("v1.json"));
+        assert_eq!(negotiate_protocol(req.headers(), &["Chat", "v3.json"]), None);
+        assert_eq!(negotiate_protocol(req.headers(), &[]), None);
+        assert_eq!(negotiate_protocol(&HeaderMap::new(), &["chat"]), None);
+    }
+
     #[test]
     fn test_ws_error_http_response() {
         let res = Builder::from(HandshakeError::GetMethodRequired).body(()).unwrap();
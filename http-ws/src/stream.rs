@@ -11,8 +11,9 @@ use std::{error, io, sync::Mutex};
 
 use bytes::{Bytes, BytesMut};
 use futures_core::stream::Stream;
+use futures_sink::Sink;
 use pin_project_lite::pin_project;
-use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::mpsc::{channel, error::TrySendError, OwnedPermit, Receiver, Sender};
 
 use super::{
     codec::{Codec, Message},
@@ -248,6 +249,82 @@ impl ResponseSender {
     }
 }
 
+/// [Sink] version of [ResponseSender]. Encode [Message] into [Bytes] and send it to [ResponseStream].
+pub struct ResponseSink {
+    sender: ResponseSender,
+    permit: Option<OwnedPermit<Item>>,
+    reserve: Option<ReserveFuture>,
+}
+
+type ReserveFuture = Pin<Box<dyn Future<Output = Result<OwnedPermit<Item>, ProtocolError>> + Send>>;
+
+impl ResponseSink {
+    pub fn new(sender: ResponseSender) -> Self {
+        Self {
+            sender,
+            permit: None,
+            reserve: None,
+        }
+    }
+
+    /// acquire inner [ResponseSender].
+    pub fn into_inner(self) -> ResponseSender {
+        self.sender
+    }
+}
+
+impl Sink<Message> for ResponseSink {
+    type Error = ProtocolError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+
+        if this.permit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+
+        let reserve = match this.reserve {
+            Some(ref mut reserve) => reserve,
+            None => match this.sender.inner.tx.clone().try_reserve_owned() {
+                Ok(permit) => {
+                    this.permit = Some(permit);
+                    return Poll::Ready(Ok(()));
+                }
+                Err(TrySendError::Full(tx)) => this.reserve.insert(Box::pin(async {
+                    tx.reserve_owned().await.map_err(|_| ProtocolError::Closed)
+                })),
+                Err(TrySendError::Closed(_)) => return Poll::Ready(Err(ProtocolError::Closed)),
+            },
+        };
+
+        let res = ready!(reserve.as_mut().poll(cx));
+        this.reserve = None;
+        this.permit = Some(res?);
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        let permit = this
+            .permit
+            .take()
+            .expect("Sink::poll_ready must be called before Sink::start_send");
+        let buf = this.sender.inner.encode(item)?;
+        permit.send(Ok(buf));
+        Ok(())
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
 /// [Weak] version of [ResponseSender].
 #[derive(Debug)]
 pub struct ResponseWeakSender {
@@ -279,16 +356,18 @@ impl _ResponseSender {
     // the bytes is encoded binary websocket message ready to be sent to client.
     async fn send(&self, msg: Message) -> Result<(), ProtocolError> {
         let permit = self.tx.reserve().await.map_err(|_| ProtocolError::Closed)?;
-        let buf = {
-            let mut encoder = self.encoder.lock().unwrap();
-            let Encoder { codec, buf } = &mut *encoder;
-            codec.encode(msg, buf)?;
-            buf.split().freeze()
-        };
+        let buf = self.encode(msg)?;
         permit.send(Ok(buf));
         Ok(())
     }
 
+    fn encode(&self, msg: Message) -> Result<Bytes, ProtocolError> {
+        let mut encoder = self.encoder.lock().unwrap();
+        let Encoder { codec, buf } = &mut *encoder;
+        codec.encode(msg, buf)?;
+        Ok(buf.split().freeze())
+    }
+
     // send error to response stream. it would produce Err(io::Error) when succeed where
     // the error is a representation of io error to the stream consumer. in most cases
     // the consumer observing the error should close the stream and the tcp connection
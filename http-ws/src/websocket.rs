@@ -0,0 +1,675 @@
+//! high level websocket type handling protocol details for both server and client side.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use std::sync::Mutex;
+
+use bytes::{Bytes, BytesMut};
+use futures_core::stream::Stream;
+use futures_sink::Sink;
+use tokio::time::{sleep, Sleep};
+
+use super::{
+    codec::{Item, Message},
+    error::ProtocolError,
+    proto::{CloseCode, CloseReason},
+    stream::{RequestStream, ResponseSender, ResponseSink, WsError},
+};
+
+/// A websocket connection on top of a transport type that can send and receive [Message].
+///
+/// Compared to raw transport it offers:
+/// - automatic [Message::Pong] reply to [Message::Ping] from peer.
+/// - close handshake. [Message::Close] from peer is replied and stream ends after the reply is sent.
+///   [Message::Close] sent to peer would wait for peer's reply in a grace period before stream ends.
+/// - fragmented messages are reassembled into whole [Message::Text] or [Message::Binary] with a max
+///   message size limit. text messages are checked for valid utf-8.
+///
+/// Only [Message::Text], [Message::Binary], [Message::Pong] and [Message::Close] are yielded by the
+/// [Stream] impl. Any message type can be sent with the [Sink] impl.
+///
+/// Transport type `T` must impl both [Stream] and [Sink] traits. For server side [ServerTransport]
+/// can be used and for client side `xitca-client`'s websocket type can be used directly.
+///
+/// # Examples
+/// ```rust
+/// # use std::future::poll_fn;
+/// # use std::pin::Pin;
+/// # use futures_core::Stream;
+/// # use futures_sink::Sink;
+/// use http_ws::{websocket::WebSocketStream, Message, ProtocolError};
+///
+/// async fn echo<T>(mut ws: WebSocketStream<T>) -> Result<(), ProtocolError>
+/// where
+///     T: Stream<Item = Result<Message, ProtocolError>> + Sink<Message, Error = ProtocolError> + Unpin,
+/// {
+///     let mut ws = Pin::new(&mut ws);
+///
+///     while let Some(msg) = poll_fn(|cx| ws.as_mut().poll_next(cx)).await {
+///         match msg? {
+///             msg @ (Message::Text(_) | Message::Binary(_)) => {
+///                 poll_fn(|cx| ws.as_mut().poll_ready(cx)).await?;
+///                 ws.as_mut().start_send(msg)?;
+///                 poll_fn(|cx| ws.as_mut().poll_flush(cx)).await?;
+///             }
+///             // close handshake is handled by WebSocketStream. stream ends afterwards.
+///             _ => {}
+///         }
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub struct WebSocketStream<T> {
+    inner: Mutex<Inner<T>>,
+}
+
+impl<T, E> WebSocketStream<T>
+where
+    T: Stream<Item = Result<Message, E>> + Sink<Message, Error = E> + Unpin,
+    E: From<ProtocolError>,
+{
+    pub fn new(transport: T) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                transport,
+                state: State::Open,
+                pong: None,
+                close: None,
+                flush: false,
+                frag: None,
+                max_message_size: 1024 * 1024,
+                close_grace_period: Duration::from_secs(5),
+            }),
+        }
+    }
+
+    /// Set max size of message. Fragmented message is limited by the combined size of all fragments.
+    ///
+    /// By default max size is set to 1MB.
+    pub fn max_message_size(mut self, size: usize) -> Self {
+        self.inner.get_mut().unwrap().max_message_size = size;
+        self
+    }
+
+    /// Set duration of waiting for peer's close reply after [Message::Close] is sent. Stream ends when
+    /// the duration elapsed.
+    ///
+    /// By default grace period is set to 5 seconds.
+    pub fn close_grace_period(mut self, dur: Duration) -> Self {
+        self.inner.get_mut().unwrap().close_grace_period = dur;
+        self
+    }
+
+    /// Split into a sink and reader pair that can be used for concurrent read/write message.
+    ///
+    /// Reader must be polled to drive the close handshake and the automatic pong replies.
+    #[inline]
+    pub fn split(&self) -> (WebSocketSink<'_, T>, WebSocketReader<'_, T>) {
+        (WebSocketSink(self), WebSocketReader(self))
+    }
+
+    /// acquire inner transport type.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner().unwrap().transport
+    }
+}
+
+/// sender part of [WebSocketStream].
+/// [Sink] trait is used to asynchronously send message.
+pub struct WebSocketSink<'a, T>(&'a WebSocketStream<T>);
+
+/// receiver part of [WebSocketStream].
+/// [Stream] trait is used to asynchronously receive message.
+pub struct WebSocketReader<'a, T>(&'a WebSocketStream<T>);
+
+impl<T, E> Stream for WebSocketStream<T>
+where
+    T: Stream<Item = Result<Message, E>> + Sink<Message, Error = E> + Unpin,
+    E: From<ProtocolError>,
+{
+    type Item = Result<Message, E>;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().inner.get_mut().unwrap().poll_next(cx)
+    }
+}
+
+impl<T, E> Stream for WebSocketReader<'_, T>
+where
+    T: Stream<Item = Result<Message, E>> + Sink<Message, Error = E> + Unpin,
+    E: From<ProtocolError>,
+{
+    type Item = Result<Message, E>;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.inner.lock().unwrap().poll_next(cx)
+    }
+}
+
+impl<T, E> Sink<Message> for WebSocketStream<T>
+where
+    T: Stream<Item = Result<Message, E>> + Sink<Message, Error = E> + Unpin,
+    E: From<ProtocolError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().inner.get_mut().unwrap().poll_ready(cx)
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+        self.get_mut().inner.get_mut().unwrap().start_send(item)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().inner.get_mut().unwrap().poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().inner.get_mut().unwrap().poll_close(cx)
+    }
+}
+
+impl<T, E> Sink<Message> for WebSocketSink<'_, T>
+where
+    T: Stream<Item = Result<Message, E>> + Sink<Message, Error = E> + Unpin,
+    E: From<ProtocolError>,
+{
+    type Error = E;
+
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.inner.lock().unwrap().poll_ready(cx)
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+        self.0.inner.lock().unwrap().start_send(item)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.inner.lock().unwrap().poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.inner.lock().unwrap().poll_close(cx)
+    }
+}
+
+enum State {
+    Open,
+    // close message sent and waiting for peer's reply until timer fires.
+    CloseSent(Pin<Box<Sleep>>),
+    // close message is queued as reply to peer's close or error. stream ends after it's sent.
+    Closing,
+    Closed,
+}
+
+struct Inner<T> {
+    transport: T,
+    state: State,
+    // latest ping payload from peer waiting to be replied.
+    pong: Option<Bytes>,
+    // close message waiting to be sent.
+    close: Option<Option<CloseReason>>,
+    flush: bool,
+    // fragmented message in reassembling. bool is true when it's a text message.
+    frag: Option<(bool, BytesMut)>,
+    max_message_size: usize,
+    close_grace_period: Duration,
+}
+
+impl<T, E> Inner<T>
+where
+    T: Stream<Item = Result<Message, E>> + Sink<Message, Error = E> + Unpin,
+    E: From<ProtocolError>,
+{
+    // send queued control messages to peer.
+    fn poll_send_queued(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), E>> {
+        while self.pong.is_some() || self.close.is_some() {
+            ready!(Pin::new(&mut self.transport).poll_ready(cx))?;
+            let msg = match self.pong.take() {
+                Some(pong) => Message::Pong(pong),
+                None => Message::Close(self.close.take().unwrap()),
+            };
+            Pin::new(&mut self.transport).start_send(msg)?;
+            self.flush = true;
+        }
+
+        if self.flush {
+            ready!(Pin::new(&mut self.transport).poll_flush(cx))?;
+            self.flush = false;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    // queue close message with given code and end stream after it's sent.
+    fn close_with(&mut self, code: CloseCode) {
+        self.pong = None;
+        if matches!(self.state, State::Open) {
+            self.close = Some(Some(code.into()));
+        }
+        self.state = State::Closing;
+    }
+
+    fn on_error(&mut self, e: ProtocolError) -> E {
+        let code = match e {
+            ProtocolError::Overflow => CloseCode::Size,
+            ProtocolError::InvalidUtf8 => CloseCode::Invalid,
+            _ => CloseCode::Protocol,
+        };
+        self.close_with(code);
+        E::from(e)
+    }
+
+    fn check_size(&mut self, size: usize) -> Result<(), E> {
+        if size > self.max_message_size {
+            return Err(self.on_error(ProtocolError::Overflow));
+        }
+        Ok(())
+    }
+
+    fn on_data(&mut self, text: bool, bytes: Bytes) -> Result<Message, E> {
+        if text {
+            if core::str::from_utf8(&bytes).is_err() {
+                return Err(self.on_error(ProtocolError::InvalidUtf8));
+            }
+            Ok(Message::Text(bytes))
+        } else {
+            Ok(Message::Binary(bytes))
+        }
+    }
+
+    fn on_continuation(&mut self, item: Item) -> Result<Option<Message>, E> {
+        match item {
+            Item::FirstText(_) | Item::FirstBinary(_) if self.frag.is_some() => {
+                Err(self.on_error(ProtocolError::ContinuationStarted))
+            }
+            Item::FirstText(bytes) => {
+                self.check_size(bytes.len())?;
+                self.frag = Some((true, BytesMut::from(bytes.as_ref())));
+                Ok(None)
+            }
+            Item::FirstBinary(bytes) => {
+                self.check_size(bytes.len())?;
+                self.frag = Some((false, BytesMut::from(bytes.as_ref())));
+                Ok(None)
+            }
+            Item::Continue(_) | Item::Last(_) if self.frag.is_none() => {
+                Err(self.on_error(ProtocolError::ContinuationNotStarted))
+            }
+            Item::Continue(bytes) => {
+                let size = self.frag.as_ref().unwrap().1.len() + bytes.len();
+                self.check_size(size)?;
+                self.frag.as_mut().unwrap().1.extend_from_slice(&bytes);
+                Ok(None)
+            }
+            Item::Last(bytes) => {
+                let size = self.frag.as_ref().unwrap().1.len() + bytes.len();
+                self.check_size(size)?;
+                let (text, mut buf) = self.frag.take().unwrap();
+                buf.extend_from_slice(&bytes);
+                self.on_data(text, buf.freeze()).map(Some)
+            }
+        }
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Message, E>>> {
+        // pending send of queued message does not block receiving.
+        if let Poll::Ready(Err(e)) = self.poll_send_queued(cx) {
+            self.state = State::Closed;
+            return Poll::Ready(Some(Err(e)));
+        }
+
+        match self.state {
+            State::Open => {}
+            State::CloseSent(ref mut timer) => {
+                if timer.as_mut().poll(cx).is_ready() {
+                    self.state = State::Closed;
+                    return Poll::Ready(None);
+                }
+            }
+            State::Closing => {
+                // queued close message must be sent before stream ends.
+                if self.close.is_some() || self.flush {
+                    return Poll::Pending;
+                }
+                self.state = State::Closed;
+                return Poll::Ready(None);
+            }
+            State::Closed => return Poll::Ready(None),
+        }
+
+        loop {
+            let msg = match ready!(Pin::new(&mut self.transport).poll_next(cx)) {
+                Some(Ok(msg)) => msg,
+                Some(Err(e)) => {
+                    self.state = State::Closed;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                None => {
+                    self.state = State::Closed;
+                    return Poll::Ready(None);
+                }
+            };
+
+            let res = match msg {
+                Message::Ping(ping) => {
+                    if matches!(self.state, State::Open) {
+                        self.pong = Some(ping);
+                        // try to reply right away. pending send would be resumed by next poll.
+                        if let Poll::Ready(Err(e)) = self.poll_send_queued(cx) {
+                            self.state = State::Closed;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                    }
+                    continue;
+                }
+                Message::Nop => continue,
+                Message::Pong(pong) => Ok(Message::Pong(pong)),
+                Message::Text(_) | Message::Binary(_) if self.frag.is_some() => {
+                    Err(self.on_error(ProtocolError::ContinuationStarted))
+                }
+                Message::Text(bytes) => self.check_size(bytes.len()).and_then(|_| self.on_data(true, bytes)),
+                Message::Binary(bytes) => self.check_size(bytes.len()).and_then(|_| self.on_data(false, bytes)),
+                Message::Continuation(item) => match self.on_continuation(item) {
+                    Ok(Some(msg)) => Ok(msg),
+                    Ok(None) => continue,
+                    Err(e) => Err(e),
+                },
+                Message::Close(reason) => {
+                    match self.state {
+                        // reply with the same close code from peer.
+                        State::Open => {
+                            self.close = Some(reason.as_ref().map(|reason| reason.code.into()));
+                            self.pong = None;
+                            self.state = State::Closing;
+                        }
+                        // close handshake is finished.
+                        _ => self.state = State::Closed,
+                    }
+                    // try to reply right away. pending send would be resumed by next poll.
+                    let _ = self.poll_send_queued(cx);
+                    Ok(Message::Close(reason))
+                }
+            };
+
+            return Poll::Ready(Some(res));
+        }
+    }
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), E>> {
+        ready!(self.poll_send_queued(cx))?;
+        Pin::new(&mut self.transport).poll_ready(cx)
+    }
+
+    fn start_send(&mut self, item: Message) -> Result<(), E> {
+        if !matches!(self.state, State::Open) {
+            return Err(E::from(ProtocolError::Closed));
+        }
+
+        if matches!(item, Message::Close(_)) {
+            self.pong = None;
+            self.state = State::CloseSent(Box::pin(sleep(self.close_grace_period)));
+        }
+
+        Pin::new(&mut self.transport).start_send(item)
+    }
+
+    fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), E>> {
+        ready!(self.poll_send_queued(cx))?;
+        Pin::new(&mut self.transport).poll_flush(cx)
+    }
+
+    // send close message to peer when it's not sent yet. transport is not closed as peer's close reply
+    // is expected to be received afterwards.
+    fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), E>> {
+        if matches!(self.state, State::Open) {
+            ready!(self.poll_ready(cx))?;
+            self.start_send(Message::Close(None))?;
+        }
+        self.poll_flush(cx)
+    }
+}
+
+/// Transport type for server side [WebSocketStream]. It's constructed from the output of [ws] function.
+///
+/// [ws]: crate::ws
+pub struct ServerTransport<B> {
+    stream: RequestStream<B>,
+    sink: ResponseSink,
+}
+
+impl<B> ServerTransport<B> {
+    pub fn new(stream: RequestStream<B>, sender: ResponseSender) -> Self {
+        Self {
+            stream,
+            sink: ResponseSink::new(sender),
+        }
+    }
+}
+
+impl<B, T, E> Stream for ServerTransport<B>
+where
+    B: Stream<Item = Result<T, E>> + Unpin,
+    T: AsRef<[u8]>,
+{
+    type Item = Result<Message, WsError<E>>;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().stream).poll_next(cx)
+    }
+}
+
+impl<B, T, E> Sink<Message> for ServerTransport<B>
+where
+    B: Stream<Item = Result<T, E>> + Unpin,
+{
+    type Error = WsError<E>;
+
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.get_mut().sink).poll_ready(cx).map_err(Into::into)
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+        Pin::new(&mut self.get_mut().sink).start_send(item).map_err(Into::into)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.get_mut().sink).poll_flush(cx).map_err(Into::into)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.get_mut().sink).poll_close(cx).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::future::poll_fn;
+
+    use bytes::BytesMut;
+    use futures_util::{stream, StreamExt};
+
+    use crate::codec::Codec;
+
+    use super::*;
+
+    type Body = Pin<Box<dyn Stream<Item = Result<Bytes, ()>>>>;
+
+    type Ws = WebSocketStream<ServerTransport<Body>>;
+
+    // encode messages as client would and construct a server side websocket with them as input.
+    // when keep_open is true the input does not end after all messages are received.
+    fn server(msgs: Vec<Message>, keep_open: bool) -> (Ws, crate::stream::ResponseStream) {
+        let mut codec = Codec::new().client_mode();
+        let mut buf = BytesMut::new();
+        for msg in msgs {
+            codec.encode(msg, &mut buf).unwrap();
+        }
+        let body = stream::iter([Ok(buf.freeze())]);
+        let body: Body = if keep_open {
+            Box::pin(body.chain(stream::pending()))
+        } else {
+            Box::pin(body)
+        };
+        let req_stream = RequestStream::new(body);
+        let (res_stream, tx) = req_stream.response_stream();
+        (WebSocketStream::new(ServerTransport::new(req_stream, tx)), res_stream)
+    }
+
+    // decode messages sent by server as client would.
+    async fn responses(mut res_stream: crate::stream::ResponseStream) -> Vec<Message> {
+        let mut codec = Codec::new().client_mode();
+        let mut buf = BytesMut::new();
+        let mut msgs = Vec::new();
+        while let Some(bytes) = poll_fn(|cx| Pin::new(&mut res_stream).poll_next(cx)).await {
+            buf.extend_from_slice(&bytes.unwrap());
+            while let Some(msg) = codec.decode(&mut buf).unwrap() {
+                msgs.push(msg);
+            }
+        }
+        msgs
+    }
+
+    async fn next(ws: &mut Ws) -> Option<Result<Message, WsError<()>>> {
+        poll_fn(|cx| Pin::new(&mut *ws).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn ping_and_close() {
+        let (mut ws, res_stream) = server(
+            vec![
+                Message::Ping(Bytes::from_static(b"ping")),
+                Message::Text(Bytes::from_static(b"hello")),
+                Message::Close(Some(CloseCode::Normal.into())),
+            ],
+            false,
+        );
+
+        assert_eq!(
+            next(&mut ws).await.unwrap().unwrap(),
+            Message::Text(Bytes::from_static(b"hello"))
+        );
+        assert_eq!(
+            next(&mut ws).await.unwrap().unwrap(),
+            Message::Close(Some(CloseCode::Normal.into()))
+        );
+        assert!(next(&mut ws).await.is_none());
+
+        let (mut sink, _) = ws.split();
+        assert!(Pin::new(&mut sink).start_send(Message::Text(Bytes::new())).is_err());
+
+        drop(ws);
+
+        assert_eq!(
+            responses(res_stream).await,
+            [
+                Message::Pong(Bytes::from_static(b"ping")),
+                Message::Close(Some(CloseCode::Normal.into()))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reassemble() {
+        let (ws, res_stream) = server(
+            vec![
+                Message::Continuation(Item::FirstText(Bytes::from_static(b"hel"))),
+                Message::Ping(Bytes::new()),
+                Message::Continuation(Item::Continue(Bytes::from_static(b"l"))),
+                Message::Continuation(Item::Last(Bytes::from_static(b"o"))),
+                Message::Continuation(Item::FirstBinary(Bytes::from_static(b"0123"))),
+                Message::Continuation(Item::Last(Bytes::from_static(b"45678"))),
+            ],
+            false,
+        );
+
+        let mut ws = ws.max_message_size(8);
+
+        assert_eq!(
+            next(&mut ws).await.unwrap().unwrap(),
+            Message::Text(Bytes::from_static(b"hello"))
+        );
+        assert!(matches!(
+            next(&mut ws).await.unwrap(),
+            Err(WsError::Protocol(ProtocolError::Overflow))
+        ));
+        assert!(next(&mut ws).await.is_none());
+
+        drop(ws);
+
+        assert_eq!(
+            responses(res_stream).await,
+            [
+                Message::Pong(Bytes::new()),
+                Message::Close(Some(CloseCode::Size.into()))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_utf8() {
+        let (mut ws, res_stream) = server(vec![Message::Text(Bytes::from_static(&[0xff, 0xfe]))], false);
+
+        assert!(matches!(
+            next(&mut ws).await.unwrap(),
+            Err(WsError::Protocol(ProtocolError::InvalidUtf8))
+        ));
+        assert!(next(&mut ws).await.is_none());
+
+        drop(ws);
+
+        assert_eq!(
+            responses(res_stream).await,
+            [Message::Close(Some(CloseCode::Invalid.into()))]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn close_grace_period() {
+        // peer never replies to close message and keeps connection open.
+        let (ws, res_stream) = server(vec![Message::Text(Bytes::from_static(b"hello"))], true);
+
+        let mut ws = ws.close_grace_period(Duration::from_secs(1));
+
+        {
+            let (mut sink, _) = ws.split();
+            poll_fn(|cx| Pin::new(&mut sink).poll_close(cx)).await.unwrap();
+        }
+
+        // message received after close sent is still yielded.
+        assert_eq!(
+            next(&mut ws).await.unwrap().unwrap(),
+            Message::Text(Bytes::from_static(b"hello"))
+        );
+
+        let now = tokio::time::Instant::now();
+        assert!(next(&mut ws).await.is_none());
+        assert!(now.elapsed() >= Duration::from_secs(1));
+
+        drop(ws);
+
+        assert_eq!(responses(res_stream).await, [Message::Close(None)]);
+    }
+}
@@ -5,6 +5,9 @@
 //!
 //! When implementing customized body type please reference [none_body_hint] and [exact_body_hint]
 //! for contract of inferring body size with [Stream::size_hint] trait method.
+//!
+//! Response body can yield [flush_hint] to make dispatcher flush buffered bytes to client at semantic boundaries
+//! of the body.
 
 use core::{
     convert::Infallible,
@@ -38,6 +41,43 @@ pub const fn exact_body_hint(size: usize) -> (usize, Option<usize>) {
     (size, Some(size))
 }
 
+/// an empty [Bytes] yielded by response body is a hint for flushing. On receiving it the http dispatcher writes
+/// buffered response bytes to io before polling the body again instead of waiting for write buffer threshold.
+///
+/// It's useful for streaming response like server sent events or long polling where each message should reach client
+/// as soon as it's produced.
+///
+/// # Examples
+/// ```rust
+/// # use core::{convert::Infallible, pin::Pin, task::{Context, Poll}};
+/// # use futures_core::stream::Stream;
+/// use xitca_http::{body::flush_hint, bytes::Bytes};
+///
+/// // body of server sent events.
+/// struct Events {
+///     events: Vec<&'static str>,
+///     flush: bool,
+/// }
+///
+/// impl Stream for Events {
+///     type Item = Result<Bytes, Infallible>;
+///
+///     fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+///         let this = self.get_mut();
+///         // yield flush hint after every event.
+///         if core::mem::take(&mut this.flush) {
+///             return Poll::Ready(Some(Ok(flush_hint())));
+///         }
+///         let event = this.events.pop().map(|event| Ok(Bytes::from_static(event.as_bytes())));
+///         this.flush = event.is_some();
+///         Poll::Ready(event)
+///     }
+/// }
+/// ```
+pub const fn flush_hint() -> Bytes {
+    Bytes::new()
+}
+
 /// A unified request body type for different http protocols.
 /// This enables one service type to handle multiple http protocols.
 #[derive(Default)]
@@ -1,4 +1,6 @@
-use core::{fmt, marker::PhantomData};
+use core::marker::PhantomData;
+
+use std::error;
 
 use xitca_io::net;
 use xitca_service::Service;
@@ -195,23 +197,23 @@ impl<V, St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WR
     }
 }
 
-type Error = Box<dyn fmt::Debug>;
+type Error = Box<dyn error::Error + Send + Sync>;
 
 impl<FA, S, E, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIMIT: usize>
     Service<Result<S, E>>
     for HttpServiceBuilder<marker::Http, net::Stream, FA, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>
 where
     FA: Service,
-    FA::Error: fmt::Debug + 'static,
-    E: fmt::Debug + 'static,
+    FA::Error: Into<Error>,
+    E: Into<Error>,
 {
     type Response =
         HttpService<net::Stream, S, RequestBody, FA::Response, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>;
     type Error = Error;
 
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
-        let service = res.map_err(|e| Box::new(e) as Error)?;
-        let tls_acceptor = self.tls_factory.call(()).await.map_err(|e| Box::new(e) as Error)?;
+        let service = res.map_err(Into::into)?;
+        let tls_acceptor = self.tls_factory.call(()).await.map_err(Into::into)?;
         Ok(HttpService::new(self.config, service, tls_acceptor))
     }
 }
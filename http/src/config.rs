@@ -1,6 +1,12 @@
 //! Configuration for http service middlewares.
 
-use core::time::Duration;
+use core::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::http::StatusCode;
 
 /// The default maximum read buffer size. If the head gets this big and
 /// a message is still not complete, a `TooLarge` error is triggered.
@@ -19,6 +25,373 @@ pub const DEFAULT_WRITE_BUF_LIMIT: usize = 8192 + 4096 * 100;
 /// 64 chosen for no particular reason.
 pub const DEFAULT_HEADER_LIMIT: usize = 64;
 
+/// The default maximum buffered request body size in bytes. When request body chunks are not
+/// consumed by service and buffered bytes get this big reading from Io stream would be paused.
+pub const DEFAULT_REQUEST_BODY_BUFFER_SIZE: usize = 32_768;
+
+/// The default max size in bytes of response body a http/2 stream can send before yielding to other streams
+/// on the same connection.
+pub const DEFAULT_H2_FAIRNESS_WINDOW: usize = 65_536;
+
+/// Policy for handling ambiguous http/1 request framing which can be exploited for request smuggling when
+/// xitca-http is deployed behind proxies with different interpretation of request body length.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum SmugglingPolicy {
+    /// Reject request with conflicting `Content-Length` and/or `Transfer-Encoding` headers that would result
+    /// in conflicting body decoder. Tolerate the rest for compatibility.
+    #[default]
+    Lenient,
+    /// Additionally reject request with:
+    /// - both `Content-Length` and `Transfer-Encoding` headers. (including `Content-Length: 0`)
+    /// - multiple `Content-Length` headers with differing values or non digit value.
+    /// - `Transfer-Encoding` header where `chunked` is not the final coding or appears more than once.
+    /// - chunked body with chunk extension containing control characters.
+    Strict,
+}
+
+/// Policy for handling http/1 request with `Expect: 100-continue` header.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ExpectPolicy {
+    /// `100 Continue` is sent when service starts reading request body.
+    #[default]
+    Continue,
+    /// Reject request with `413 Payload Too Large` when it's `Content-Length` is larger than given bytes.
+    PayloadTooLarge(u64),
+    /// Reject request with `417 Expectation Failed` when it's `Content-Length` is larger than given bytes.
+    ExpectationFailed(u64),
+}
+
+impl ExpectPolicy {
+    /// Return status code for rejecting request with given `Content-Length`. None when request is allowed.
+    pub const fn reject(&self, len: u64) -> Option<StatusCode> {
+        match *self {
+            Self::PayloadTooLarge(max) if len > max => Some(StatusCode::PAYLOAD_TOO_LARGE),
+            Self::ExpectationFailed(max) if len > max => Some(StatusCode::EXPECTATION_FAILED),
+            _ => None,
+        }
+    }
+}
+
+/// Casing of header names in http/1 response head.
+///
+/// Header names are case insensitive by spec and [HeaderMap](crate::http::HeaderMap) stores them in lower case.
+/// Some legacy clients only recognize specific casing and this policy is for working with them. Http/2 and
+/// http/3 require lower case names and are not affected.
+///
+/// Exact casing of specific names can be set per response with
+/// [HeaderCaseMap](crate::h1::proto::header::HeaderCaseMap) extension which takes precedence over this policy.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum HeaderCase {
+    /// names are written in lower case. e.g: `content-type`.
+    #[default]
+    Lower,
+    /// first letter and every letter after `-` of names are written in upper case. e.g: `Content-Type`.
+    Title,
+}
+
+/// Headers added to every response by http/1 and http/2 dispatchers at protocol layer. Header already present in
+/// response produced by service is not overridden so it can be customized per response.
+///
+/// By default only `Date` header is added.
+///
+/// # Examples
+/// ```rust
+/// use xitca_http::config::{HttpServiceConfig, ResponseHeaders};
+///
+/// const HEADERS: ResponseHeaders = ResponseHeaders::new()
+///     // stop emitting Date header.
+///     .disable_date()
+///     // emit Server header with given name.
+///     .server("xitca")
+///     // emit a fixed set of headers.
+///     .headers(&[("x-content-type-options", "nosniff"), ("x-frame-options", "DENY")]);
+///
+/// let config = HttpServiceConfig::new().response_headers(HEADERS);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ResponseHeaders {
+    pub(crate) date: bool,
+    pub(crate) server: Option<&'static str>,
+    pub(crate) headers: &'static [(&'static str, &'static str)],
+}
+
+impl Default for ResponseHeaders {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResponseHeaders {
+    /// Max count of fixed headers set with [ResponseHeaders::headers].
+    pub const MAX_HEADERS: usize = 64;
+
+    /// Construct with `Date` header enabled, no `Server` header and no fixed header.
+    pub const fn new() -> Self {
+        Self {
+            date: true,
+            server: None,
+            headers: &[],
+        }
+    }
+
+    /// Stop adding `Date` header to response.
+    pub const fn disable_date(mut self) -> Self {
+        self.date = false;
+        self
+    }
+
+    /// Add `Server` header with given value to response.
+    ///
+    /// # Panics
+    /// When value contains character not allowed in header value.
+    pub const fn server(mut self, value: &'static str) -> Self {
+        assert!(is_valid_value(value), "invalid Server header value");
+        self.server = Some(value);
+        self
+    }
+
+    /// Add given fixed set of headers to response. Header names must be in lower case.
+    ///
+    /// # Panics
+    /// - When header name or value contains character not allowed.
+    /// - When header is `Date` or `Server` which are set with dedicated methods.
+    /// - When header is one of `Connection`, `Content-Length`, `Transfer-Encoding`, `Upgrade`, `Trailer` and
+    ///   `Keep-Alive` which are managed by dispatchers.
+    /// - When count of headers exceeds [ResponseHeaders::MAX_HEADERS].
+    pub const fn headers(mut self, headers: &'static [(&'static str, &'static str)]) -> Self {
+        assert!(headers.len() <= Self::MAX_HEADERS, "too many fixed response headers");
+        let mut i = 0;
+        while i < headers.len() {
+            let (name, value) = headers[i];
+            assert!(is_valid_name(name), "invalid fixed response header name");
+            assert!(!is_reserved_name(name), "fixed response header name is reserved");
+            assert!(is_valid_value(value), "invalid fixed response header value");
+            i += 1;
+        }
+        self.headers = headers;
+        self
+    }
+}
+
+// lower case token characters. see https://www.rfc-editor.org/rfc/rfc9110#section-5.6.2
+const fn is_valid_name(name: &str) -> bool {
+    let name = name.as_bytes();
+    if name.is_empty() {
+        return false;
+    }
+    let mut i = 0;
+    while i < name.len() {
+        match name[i] {
+            b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'!'
+            | b'#'
+            | b'$'
+            | b'%'
+            | b'&'
+            | b'\''
+            | b'*'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~' => {}
+            _ => return false,
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn is_valid_value(value: &str) -> bool {
+    let value = value.as_bytes();
+    let mut i = 0;
+    while i < value.len() {
+        match value[i] {
+            b'\t' | b' '..=b'~' => {}
+            _ => return false,
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn is_reserved_name(name: &str) -> bool {
+    const RESERVED: [&str; 8] = [
+        "date",
+        "server",
+        "connection",
+        "content-length",
+        "transfer-encoding",
+        "upgrade",
+        "trailer",
+        "keep-alive",
+    ];
+
+    let mut i = 0;
+    while i < RESERVED.len() {
+        let reserved = RESERVED[i].as_bytes();
+        let name = name.as_bytes();
+        if reserved.len() == name.len() {
+            let mut j = 0;
+            while j < name.len() && reserved[j] == name[j] {
+                j += 1;
+            }
+            if j == name.len() {
+                return true;
+            }
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Limits of http/1 request head. Request exceeding any of the limits is rejected with
+/// `431 Request Header Fields Too Large` and the connection is closed.
+///
+/// Max count of header fields is bounded by both [HeaderLimits::max_header_count] and the const generic param
+/// set with [HttpServiceConfig::max_request_headers]. The smaller one takes effect.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct HeaderLimits {
+    pub(crate) request_line: usize,
+    pub(crate) header_count: usize,
+    pub(crate) header_name: usize,
+    pub(crate) header_value: usize,
+}
+
+impl Default for HeaderLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeaderLimits {
+    /// Construct limits with all of them unbounded. Request head is still bounded by read buffer limit and
+    /// [HttpServiceConfig::max_request_headers]. Use builder methods to opt-in tighter limits.
+    pub const fn new() -> Self {
+        Self {
+            request_line: usize::MAX,
+            header_count: usize::MAX,
+            header_name: usize::MAX,
+            header_value: usize::MAX,
+        }
+    }
+
+    /// Define max size in bytes of request line. e.g: `GET /path?query HTTP/1.1`
+    pub const fn max_request_line(mut self, size: usize) -> Self {
+        self.request_line = size;
+        self
+    }
+
+    /// Define max number of header fields.
+    pub const fn max_header_count(mut self, count: usize) -> Self {
+        self.header_count = count;
+        self
+    }
+
+    /// Define max size in bytes of a single header name.
+    pub const fn max_header_name(mut self, size: usize) -> Self {
+        self.header_name = size;
+        self
+    }
+
+    /// Define max size in bytes of a single header value.
+    pub const fn max_header_value(mut self, size: usize) -> Self {
+        self.header_value = size;
+        self
+    }
+}
+
+/// Reason of http/1 request rejected for exceeding limit of request head.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HeaderLimitExceeded {
+    /// total size of request head exceeds read buffer limit. See [HttpServiceConfig::max_read_buf_size].
+    HeadSize,
+    /// See [HeaderLimits::max_request_line].
+    RequestLine,
+    /// See [HeaderLimits::max_header_count].
+    HeaderCount,
+    /// See [HeaderLimits::max_header_name].
+    HeaderName,
+    /// See [HeaderLimits::max_header_value].
+    HeaderValue,
+}
+
+impl HeaderLimitExceeded {
+    pub(crate) const fn as_str(&self) -> &'static str {
+        match *self {
+            Self::HeadSize => "request head too large",
+            Self::RequestLine => "request line too long",
+            Self::HeaderCount => "too many header fields",
+            Self::HeaderName => "header name too long",
+            Self::HeaderValue => "header value too long",
+        }
+    }
+}
+
+impl fmt::Display for HeaderLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Counters of http/1 requests rejected for exceeding limit of request head. Counted per
+/// [HeaderLimitExceeded] reason.
+///
+/// # Examples
+/// ```rust
+/// use xitca_http::config::{HeaderLimitExceeded, HeaderLimitMetrics, HttpServiceConfig};
+///
+/// static METRICS: HeaderLimitMetrics = HeaderLimitMetrics::new();
+///
+/// let config = HttpServiceConfig::new().header_limit_metrics(&METRICS);
+///
+/// // read counter from anywhere. e.g: metrics exporter.
+/// assert_eq!(METRICS.get(HeaderLimitExceeded::RequestLine), 0);
+/// ```
+#[derive(Debug, Default)]
+pub struct HeaderLimitMetrics {
+    head_size: AtomicU64,
+    request_line: AtomicU64,
+    header_count: AtomicU64,
+    header_name: AtomicU64,
+    header_value: AtomicU64,
+}
+
+impl HeaderLimitMetrics {
+    pub const fn new() -> Self {
+        Self {
+            head_size: AtomicU64::new(0),
+            request_line: AtomicU64::new(0),
+            header_count: AtomicU64::new(0),
+            header_name: AtomicU64::new(0),
+            header_value: AtomicU64::new(0),
+        }
+    }
+
+    /// Get number of requests rejected for given reason.
+    pub fn get(&self, reason: HeaderLimitExceeded) -> u64 {
+        self.counter(reason).load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record(&self, reason: HeaderLimitExceeded) {
+        self.counter(reason).fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn counter(&self, reason: HeaderLimitExceeded) -> &AtomicU64 {
+        match reason {
+            HeaderLimitExceeded::HeadSize => &self.head_size,
+            HeaderLimitExceeded::RequestLine => &self.request_line,
+            HeaderLimitExceeded::HeaderCount => &self.header_count,
+            HeaderLimitExceeded::HeaderName => &self.header_name,
+            HeaderLimitExceeded::HeaderValue => &self.header_value,
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct HttpServiceConfig<
     const HEADER_LIMIT: usize = DEFAULT_HEADER_LIMIT,
@@ -30,6 +403,18 @@ pub struct HttpServiceConfig<
     pub(crate) request_head_timeout: Duration,
     pub(crate) tls_accept_timeout: Duration,
     pub(crate) peek_protocol: bool,
+    pub(crate) request_body_buffer_size: usize,
+    pub(crate) max_requests_per_connection: usize,
+    pub(crate) smuggling_policy: SmugglingPolicy,
+    pub(crate) expect_policy: ExpectPolicy,
+    pub(crate) h2_fairness_window: usize,
+    pub(crate) header_limits: HeaderLimits,
+    pub(crate) header_limit_metrics: Option<&'static HeaderLimitMetrics>,
+    pub(crate) header_case: HeaderCase,
+    pub(crate) request_timing: bool,
+    pub(crate) disconnect_signal: bool,
+    pub(crate) informational_responses: bool,
+    pub(crate) response_headers: ResponseHeaders,
 }
 
 impl Default for HttpServiceConfig {
@@ -46,6 +431,18 @@ impl HttpServiceConfig {
             request_head_timeout: Duration::from_secs(5),
             tls_accept_timeout: Duration::from_secs(3),
             peek_protocol: false,
+            request_body_buffer_size: DEFAULT_REQUEST_BODY_BUFFER_SIZE,
+            max_requests_per_connection: usize::MAX,
+            smuggling_policy: SmugglingPolicy::Lenient,
+            expect_policy: ExpectPolicy::Continue,
+            h2_fairness_window: DEFAULT_H2_FAIRNESS_WINDOW,
+            header_limits: HeaderLimits::new(),
+            header_limit_metrics: None,
+            header_case: HeaderCase::Lower,
+            request_timing: false,
+            disconnect_signal: false,
+            informational_responses: false,
+            response_headers: ResponseHeaders::new(),
         }
     }
 }
@@ -128,6 +525,143 @@ impl<const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIM
         self
     }
 
+    /// Define max size in bytes of request body chunks buffered for a connection.
+    ///
+    /// When service is slow at consuming request body and buffered chunks get this big the connection
+    /// would stop reading from Io stream until service catches up. This bounds memory usage per connection
+    /// for large uploads. The effective memory usage can be larger by at most the size of read buffer.
+    ///
+    /// See [DEFAULT_REQUEST_BODY_BUFFER_SIZE] for default value.
+    ///
+    /// # Panics
+    /// When size is 0.
+    pub fn request_body_buffer_size(mut self, size: usize) -> Self {
+        assert_ne!(size, 0, "request body buffer size must be greater than 0");
+        self.request_body_buffer_size = size;
+        self
+    }
+
+    /// Define max number of requests served by a single connection.
+    ///
+    /// For Http/1 the response of last request would be sent with `Connection: close` header and the
+    /// connection is closed afterwards. For Http/2 a graceful shutdown is initiated after the last stream
+    /// is accepted and in flight streams are finished before connection closes.
+    ///
+    /// This is useful for spreading load of long lived connections across multiple servers behind a load
+    /// balancer. Default to unlimited.
+    ///
+    /// # Panics
+    /// When num is 0.
+    pub fn max_requests_per_connection(mut self, num: usize) -> Self {
+        assert_ne!(num, 0, "max requests per connection must be greater than 0");
+        self.max_requests_per_connection = num;
+        self
+    }
+
+    /// Define policy of http/1 request framing strictness. Config is applied per listener when
+    /// multiple services are bound to different listeners.
+    ///
+    /// Rejected request is responded with `400 Bad Request` and the connection is closed.
+    ///
+    /// See [SmugglingPolicy] for detail. Default to [SmugglingPolicy::Lenient].
+    pub fn smuggling_policy(mut self, policy: SmugglingPolicy) -> Self {
+        self.smuggling_policy = policy;
+        self
+    }
+
+    /// Define policy of http/1 request with `Expect: 100-continue` header. Request advertising `Content-Length`
+    /// over the limit of policy is rejected before service is called and it's body is never read. The connection
+    /// is closed after rejection.
+    ///
+    /// See [ExpectPolicy] for detail. Default to [ExpectPolicy::Continue].
+    pub fn expect_policy(mut self, policy: ExpectPolicy) -> Self {
+        self.expect_policy = policy;
+        self
+    }
+
+    /// Define max size in bytes of response body a http/2 stream can send before yielding to other streams on
+    /// the same connection.
+    ///
+    /// Flow control capacity is reserved per stream in chunks no larger than the window and after the window is
+    /// used up the stream gives other streams a chance to make progress. This prevents one large streaming
+    /// response from starving the rest streams of a connection. Smaller window gives better fairness at the cost
+    /// of throughput of single stream.
+    ///
+    /// See [DEFAULT_H2_FAIRNESS_WINDOW] for default value.
+    ///
+    /// # Panics
+    /// When size is 0.
+    pub fn h2_fairness_window(mut self, size: usize) -> Self {
+        assert_ne!(size, 0, "h2_fairness_window must be non zero");
+        self.h2_fairness_window = size;
+        self
+    }
+
+    /// Define limits of http/1 request head. Request exceeding limit is rejected with
+    /// `431 Request Header Fields Too Large` and a plain text body describing the reason.
+    ///
+    /// See [HeaderLimits] for detail. Default to [HeaderLimits::new].
+    pub fn header_limits(mut self, limits: HeaderLimits) -> Self {
+        self.header_limits = limits;
+        self
+    }
+
+    /// Define counters of http/1 requests rejected for exceeding limit of request head.
+    ///
+    /// See [HeaderLimitMetrics] for detail.
+    pub fn header_limit_metrics(mut self, metrics: &'static HeaderLimitMetrics) -> Self {
+        self.header_limit_metrics = Some(metrics);
+        self
+    }
+
+    /// Define casing of header names in http/1 response head.
+    ///
+    /// See [HeaderCase] for detail. Default to [HeaderCase::Lower].
+    pub fn header_case(mut self, case: HeaderCase) -> Self {
+        self.header_case = case;
+        self
+    }
+
+    /// Enable per request timing and body size instrumentation of http/1 connection. A [RequestTiming] is inserted
+    /// into request extensions.
+    ///
+    /// See [timing](crate::timing) module for detail. Default to disabled.
+    ///
+    /// [RequestTiming]: crate::timing::RequestTiming
+    pub fn request_timing(mut self) -> Self {
+        self.request_timing = true;
+        self
+    }
+
+    /// Enable peer disconnect signal of http/1 connection. A [Disconnect] is inserted into request extensions.
+    ///
+    /// See [disconnect](crate::disconnect) module for detail. Default to disabled.
+    ///
+    /// [Disconnect]: crate::disconnect::Disconnect
+    pub fn disconnect_signal(mut self) -> Self {
+        self.disconnect_signal = true;
+        self
+    }
+
+    /// Enable informational(1xx) responses of http/1.1 connection. An [Informational] is inserted into request
+    /// extensions.
+    ///
+    /// See [informational](crate::informational) module for detail. Default to disabled.
+    ///
+    /// [Informational]: crate::informational::Informational
+    pub fn informational_responses(mut self) -> Self {
+        self.informational_responses = true;
+        self
+    }
+
+    /// Define headers added to every http/1 and http/2 response.
+    ///
+    /// See [ResponseHeaders] for detail. Default to [ResponseHeaders::new].
+    pub fn response_headers(mut self, headers: ResponseHeaders) -> Self {
+        self.response_headers = headers;
+        self
+    }
+
     #[doc(hidden)]
     /// A shortcut for mutating const generic params.
     pub fn mutate_const_generic<
@@ -143,6 +677,18 @@ impl<const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIM
             request_head_timeout: self.request_head_timeout,
             tls_accept_timeout: self.tls_accept_timeout,
             peek_protocol: self.peek_protocol,
+            request_body_buffer_size: self.request_body_buffer_size,
+            max_requests_per_connection: self.max_requests_per_connection,
+            smuggling_policy: self.smuggling_policy,
+            expect_policy: self.expect_policy,
+            h2_fairness_window: self.h2_fairness_window,
+            header_limits: self.header_limits,
+            header_limit_metrics: self.header_limit_metrics,
+            header_case: self.header_case,
+            request_timing: self.request_timing,
+            disconnect_signal: self.disconnect_signal,
+            informational_responses: self.informational_responses,
+            response_headers: self.response_headers,
         }
     }
 }
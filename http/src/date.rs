@@ -14,6 +14,11 @@ use tokio::{
     time::{interval, Instant},
 };
 
+use crate::{
+    bytes::BytesMut,
+    http::header::{HeaderValue, InvalidHeaderValue},
+};
+
 /// Trait for getting current date/time.
 ///
 /// This is usually used by a low resolution of timer to reduce frequent syscall to OS.
@@ -27,9 +32,23 @@ pub trait DateTime {
         F: FnOnce(&[u8]) -> O;
 
     fn now(&self) -> Instant;
+
+    /// write `date` header line to buffer. line is in form of `\r\ndate: <http date>`.
+    #[inline]
+    fn write_date_header(&self, buf: &mut BytesMut) {
+        buf.reserve(Self::DATE_VALUE_LENGTH + DATE_HEADER_PREFIX.len());
+        buf.extend_from_slice(DATE_HEADER_PREFIX);
+        self.with_date(|slice| buf.extend_from_slice(slice));
+    }
 }
 
-/// Struct with Date update periodically at 500 milliseconds interval.
+/// Struct with Date update periodically at given interval. Default to 500 milliseconds.
+///
+/// Date is shared with reference through [DateTimeService::get] and it can be utilized by any component
+/// running on the same thread. e.g: http client, proxy or logger.
+///
+/// # Panics
+/// Construct DateTimeService outside of tokio [LocalSet](tokio::task::LocalSet) would cause panic.
 pub struct DateTimeService {
     state: Rc<RefCell<DateTimeState>>,
     handle: JoinHandle<()>,
@@ -50,13 +69,22 @@ impl Default for DateTimeService {
 
 impl DateTimeService {
     pub fn new() -> Self {
+        Self::with_interval(Duration::from_millis(500))
+    }
+
+    /// construct a new DateTimeService with given update interval. smaller interval offers more precise
+    /// date time at the cost of more frequent syscall.
+    ///
+    /// # Panics
+    /// panic when interval is zero.
+    pub fn with_interval(dur: Duration) -> Self {
         // shared date and timer for Date and update async task.
         let state = Rc::new(RefCell::new(DateTimeState::new()));
         let state_clone = Rc::clone(&state);
-        // spawn an async task sleep for 1 sec and update date in a loop.
+        // spawn an async task sleep for interval and update date in a loop.
         // handle is used to stop the task on Date drop.
         let handle = tokio::task::spawn_local(async move {
-            let mut interval = interval(Duration::from_millis(500));
+            let mut interval = interval(dur);
             let state = &*state_clone;
             loop {
                 let _ = interval.tick().await;
@@ -73,16 +101,22 @@ impl DateTimeService {
     }
 }
 
-pub(crate) type DateTimeHandle = RefCell<DateTimeState>;
+/// shared handle of date time updated by [DateTimeService].
+pub type DateTimeHandle = RefCell<DateTimeState>;
 
 /// The length of byte representation of [HttpDate].
 pub const DATE_VALUE_LENGTH: usize = 29;
 
+const DATE_HEADER_PREFIX: &[u8; 8] = b"\r\ndate: ";
+const DATE_HEADER_LENGTH: usize = DATE_HEADER_PREFIX.len() + DATE_VALUE_LENGTH;
+
 /// struct contains byte representation of [HttpDate] and [Instant].
 #[derive(Copy, Clone)]
 pub struct DateTimeState {
     pub date: [u8; DATE_VALUE_LENGTH],
     pub now: Instant,
+    // pre-rendered date header line.
+    header: [u8; DATE_HEADER_LENGTH],
 }
 
 impl Default for DateTimeState {
@@ -96,8 +130,11 @@ impl DateTimeState {
         let mut date = Self {
             date: [0; DATE_VALUE_LENGTH],
             now: Instant::now(),
+            header: [0; DATE_HEADER_LENGTH],
         };
         let _ = write!(date, "{}", HttpDate::from(SystemTime::now()));
+        date.header[..DATE_HEADER_PREFIX.len()].copy_from_slice(DATE_HEADER_PREFIX);
+        date.header[DATE_HEADER_PREFIX.len()..].copy_from_slice(&date.date);
         date
     }
 }
@@ -127,6 +164,11 @@ impl DateTime for DateTimeHandle {
     fn now(&self) -> Instant {
         self.borrow().now
     }
+
+    #[inline]
+    fn write_date_header(&self, buf: &mut BytesMut) {
+        buf.extend_from_slice(&self.borrow().header);
+    }
 }
 
 /// Time handler powered by plain OS system time. useful for testing purpose.
@@ -149,3 +191,52 @@ impl DateTime for SystemTimeDateTimeHandler {
         Instant::now()
     }
 }
+
+/// pre-rendered `server` header line for writing into response buffer. line is in form of `\r\nserver: <value>`.
+#[derive(Clone, Debug)]
+pub struct ServerHeader(Box<[u8]>);
+
+impl ServerHeader {
+    /// construct from header value. error when value is not a valid header value.
+    pub fn new(value: &str) -> Result<Self, InvalidHeaderValue> {
+        let value = HeaderValue::from_str(value)?;
+        let mut line = Vec::with_capacity(value.len() + 10);
+        line.extend_from_slice(b"\r\nserver: ");
+        line.extend_from_slice(value.as_bytes());
+        Ok(Self(line.into_boxed_slice()))
+    }
+
+    /// write `server` header line to buffer.
+    #[inline]
+    pub fn write(&self, buf: &mut BytesMut) {
+        buf.extend_from_slice(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn date_header() {
+        let handle = DateTimeHandle::new(DateTimeState::new());
+
+        let mut buf = BytesMut::new();
+        handle.write_date_header(&mut buf);
+        assert_eq!(buf.len(), DATE_HEADER_LENGTH);
+        assert!(buf.starts_with(b"\r\ndate: "));
+        handle.with_date(|date| assert!(buf.ends_with(date)));
+
+        let mut buf = BytesMut::new();
+        SystemTimeDateTimeHandler.write_date_header(&mut buf);
+        assert_eq!(buf.len(), DATE_HEADER_LENGTH);
+    }
+
+    #[test]
+    fn server_header() {
+        let mut buf = BytesMut::new();
+        ServerHeader::new("xitca").unwrap().write(&mut buf);
+        assert_eq!(&buf[..], b"\r\nserver: xitca");
+        assert!(ServerHeader::new("xitca\r\n").is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! request scoped signal of peer closing connection.
+//!
+//! When enabled with [HttpServiceConfig::disconnect_signal] a [Disconnect] is inserted into request extensions by
+//! http/1 dispatcher. After request body is fully read the dispatcher keeps watching the connection while service
+//! is processing the request and triggers the signal when peer closes it. Long running service (SSE, long-polling,
+//! expensive query, etc) can observe the signal and abort it's work early.
+//!
+//! Peer shutting down it's write half of connection after sending request is observed as disconnect as well.
+//!
+//! # Examples
+//! ```rust
+//! use std::convert::Infallible;
+//!
+//! use xitca_http::{
+//!     disconnect::Disconnect,
+//!     http::{Request, RequestExt, Response},
+//! };
+//!
+//! async fn handler(req: Request<RequestExt<()>>) -> Result<Response<&'static str>, Infallible> {
+//!     // signal is absent when it's not enabled or protocol is not http/1.
+//!     let disconnect = req.extensions().get::<Disconnect>().cloned().unwrap_or_default();
+//!
+//!     for _ in 0..16 {
+//!         // stop expensive work early when peer is gone. Disconnect::wait can be used for racing with
+//!         // other async tasks.
+//!         if disconnect.is_disconnected() {
+//!             return Ok(Response::new(""));
+//!         }
+//!         // expensive work.
+//!     }
+//!
+//!     Ok(Response::new("done"))
+//! }
+//! ```
+//!
+//! [HttpServiceConfig::disconnect_signal]: crate::config::HttpServiceConfig::disconnect_signal
+
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use std::sync::Arc;
+
+use tokio::sync::Notify;
+
+/// shared handle of peer disconnect signal. See [module](self) level doc for detail.
+///
+/// [Disconnect::default] constructs a signal that is never triggered.
+#[derive(Clone, Default)]
+pub struct Disconnect(Arc<Inner>);
+
+#[derive(Default)]
+struct Inner {
+    disconnected: AtomicBool,
+    notify: Notify,
+}
+
+impl Disconnect {
+    /// check if peer has closed the connection.
+    #[inline]
+    pub fn is_disconnected(&self) -> bool {
+        self.0.disconnected.load(Ordering::Acquire)
+    }
+
+    /// wait for peer closing the connection. resolve immediately when it's already closed.
+    pub async fn wait(&self) {
+        let notified = self.0.notify.notified();
+        if self.is_disconnected() {
+            return;
+        }
+        notified.await
+    }
+
+    pub(crate) fn trigger(&self) {
+        self.0.disconnected.store(true, Ordering::Release);
+        self.0.notify.notify_waiters();
+    }
+}
+
+impl fmt::Debug for Disconnect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Disconnect")
+            .field("disconnected", &self.is_disconnected())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn trigger() {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(async {
+                let disconnect = Disconnect::default();
+                assert!(!disconnect.is_disconnected());
+
+                let waiter = disconnect.clone();
+                let handle = tokio::spawn(async move { waiter.wait().await });
+                tokio::task::yield_now().await;
+                assert!(!handle.is_finished());
+
+                disconnect.trigger();
+                assert!(disconnect.is_disconnected());
+                handle.await.unwrap();
+
+                // triggered signal resolves immediately.
+                disconnect.wait().await;
+            });
+    }
+}
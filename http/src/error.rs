@@ -86,3 +86,55 @@ impl<S, B> From<Infallible> for HttpServiceError<S, B> {
 
 /// Default Request/Response body error.
 pub type BodyError = Box<dyn Error + Send + Sync>;
+
+/// error type when request body is terminated before it's finished. Typically caused by client disconnecting in
+/// the middle of uploading.
+///
+/// http/1 request body emits it as inner error of [std::io::Error] and the bytes received before the error are
+/// still yielded in order. Consumer can make use of them for resumable upload where client continues uploading
+/// from [BodyIncomplete::received] offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyIncomplete {
+    received: u64,
+    expected: Option<u64>,
+}
+
+impl BodyIncomplete {
+    pub const fn new(received: u64, expected: Option<u64>) -> Self {
+        Self { received, expected }
+    }
+
+    /// count of body bytes received before termination.
+    #[inline]
+    pub const fn received(&self) -> u64 {
+        self.received
+    }
+
+    /// count of body bytes expected from `Content-Length` header. None when body is chunked encoded.
+    #[inline]
+    pub const fn expected(&self) -> Option<u64> {
+        self.expected
+    }
+
+    /// try to find [BodyIncomplete] from given error. Either it's the error itself or the inner error of
+    /// [std::io::Error].
+    pub fn find<'a>(e: &'a (dyn Error + 'static)) -> Option<&'a Self> {
+        e.downcast_ref::<Self>().or_else(|| {
+            e.downcast_ref::<std::io::Error>()
+                .and_then(|e| e.get_ref())
+                .and_then(|e| e.downcast_ref::<Self>())
+        })
+    }
+}
+
+impl fmt::Display for BodyIncomplete {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "request body is incomplete. received {} bytes", self.received)?;
+        if let Some(expected) = self.expected {
+            write!(f, " of expected {expected} bytes")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for BodyIncomplete {}
@@ -12,9 +12,6 @@ use futures_core::stream::Stream;
 
 use crate::bytes::Bytes;
 
-/// max buffer size 32k
-pub(crate) const MAX_BUFFER_SIZE: usize = 32_768;
-
 #[derive(Clone, Debug)]
 enum RequestBodyInner {
     Some(Rc<RefCell<Inner>>),
@@ -24,10 +21,10 @@ enum RequestBodyInner {
 }
 
 impl RequestBodyInner {
-    fn new(eof: bool) -> Self {
+    fn new(eof: bool, limit: usize) -> Self {
         match eof {
             true => Self::None,
-            false => Self::Some(Default::default()),
+            false => Self::Some(Rc::new(RefCell::new(Inner::new(limit)))),
         }
     }
 }
@@ -40,14 +37,15 @@ pub struct RequestBody(RequestBodyInner);
 
 impl Default for RequestBody {
     fn default() -> Self {
-        Self(RequestBodyInner::new(true))
+        Self(RequestBodyInner::None)
     }
 }
 
 impl RequestBody {
     // an async spsc channel where RequestBodySender used to push data and popped from RequestBody.
-    pub(super) fn channel(eof: bool) -> (RequestBodySender, Self) {
-        let inner = RequestBodyInner::new(eof);
+    // limit is the max bytes buffered in channel before sender enters backpressure state.
+    pub(super) fn channel(eof: bool, limit: usize) -> (RequestBodySender, Self) {
+        let inner = RequestBodyInner::new(eof, limit);
         (RequestBodySender(inner.clone()), RequestBody(inner))
     }
 
@@ -139,6 +137,11 @@ impl RequestBodySender {
         }
     }
 
+    // true when buffered data reaches the limit and sender should stop feeding more data.
+    pub(super) fn is_full(&mut self) -> bool {
+        self.try_inner().is_some_and(|inner| inner.backpressure())
+    }
+
     pub(super) fn ready(&mut self) -> impl Future<Output = io::Result<()>> + '_ {
         self.ready_with(|inner| !inner.backpressure())
     }
@@ -173,10 +176,11 @@ impl RequestBodySender {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct Inner {
     eof: bool,
     len: usize,
+    limit: usize,
     err: Option<io::Error>,
     items: VecDeque<Bytes>,
     task: Option<Waker>,
@@ -184,6 +188,18 @@ struct Inner {
 }
 
 impl Inner {
+    fn new(limit: usize) -> Self {
+        Self {
+            eof: false,
+            len: 0,
+            limit,
+            err: None,
+            items: VecDeque::new(),
+            task: None,
+            io_task: None,
+        }
+    }
+
     /// Wake up future waiting for payload data to be available.
     fn wake(&mut self) {
         if let Some(waker) = self.task.take() {
@@ -236,7 +252,7 @@ impl Inner {
     }
 
     fn backpressure(&self) -> bool {
-        self.len >= MAX_BUFFER_SIZE
+        self.len >= self.limit
     }
 
     fn poll_next_unpin(&mut self, cx: &Context<'_>) -> Poll<Option<io::Result<Bytes>>> {
@@ -254,3 +270,31 @@ impl Inner {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    #[test]
+    fn backpressure() {
+        let (mut tx, mut body) = RequestBody::channel(false, 4);
+
+        tx.feed_data(Bytes::from_static(b"996"));
+        assert!(!tx.is_full());
+        tx.ready().now_or_panic().unwrap();
+
+        tx.feed_data(Bytes::from_static(b"251"));
+        assert!(tx.is_full());
+
+        let chunk = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).now_or_panic();
+        assert_eq!(chunk.unwrap().unwrap().as_ref(), b"996");
+        assert!(!tx.is_full());
+        tx.ready().now_or_panic().unwrap();
+
+        drop(body);
+        assert!(!tx.is_full());
+        assert!(tx.ready().now_or_panic().is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use core::fmt;
+use std::error;
 
 use xitca_service::Service;
 
@@ -47,21 +47,21 @@ impl<St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE
     }
 }
 
-type Error = Box<dyn fmt::Debug>;
+type Error = Box<dyn error::Error + Send + Sync>;
 
 impl<St, FA, S, E, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIMIT: usize>
     Service<Result<S, E>> for HttpServiceBuilder<marker::Http1, St, FA, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>
 where
     FA: Service,
-    FA::Error: fmt::Debug + 'static,
-    E: fmt::Debug + 'static,
+    FA::Error: Into<Error>,
+    E: Into<Error>,
 {
     type Response = H1Service<St, S, FA::Response, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>;
     type Error = Error;
 
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
-        let service = res.map_err(|e| Box::new(e) as Error)?;
-        let tls_acceptor = self.tls_factory.call(()).await.map_err(|e| Box::new(e) as Error)?;
+        let service = res.map_err(Into::into)?;
+        let tls_acceptor = self.tls_factory.call(()).await.map_err(Into::into)?;
         Ok(H1Service::new(self.config, service, tls_acceptor))
     }
 }
@@ -72,15 +72,15 @@ impl<St, FA, S, E, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const
     for HttpServiceBuilder<marker::Http1Uring, St, FA, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>
 where
     FA: Service,
-    FA::Error: fmt::Debug + 'static,
-    E: fmt::Debug + 'static,
+    FA::Error: Into<Error>,
+    E: Into<Error>,
 {
     type Response = super::service::H1UringService<S, FA::Response, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>;
     type Error = Error;
 
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
-        let service = res.map_err(|e| Box::new(e) as Error)?;
-        let tls_acceptor = self.tls_factory.call(()).await.map_err(|e| Box::new(e) as Error)?;
+        let service = res.map_err(Into::into)?;
+        let tls_acceptor = self.tls_factory.call(()).await.map_err(Into::into)?;
         Ok(super::service::H1UringService::new(self.config, service, tls_acceptor))
     }
 }
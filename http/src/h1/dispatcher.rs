@@ -7,7 +7,7 @@ use core::{
     time::Duration,
 };
 
-use std::io;
+use std::{io, time::Instant};
 
 use futures_core::stream::Stream;
 use tracing::trace;
@@ -18,18 +18,22 @@ use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 use crate::{
     body::NoneBody,
     bytes::{Bytes, EitherBuf},
-    config::HttpServiceConfig,
+    config::{HeaderLimitExceeded, HttpServiceConfig},
     date::DateTime,
+    disconnect::Disconnect,
+    error::BodyIncomplete,
     h1::{
         body::{RequestBody, RequestBodySender},
         error::Error,
     },
     http::{
         response::{Parts, Response},
-        StatusCode,
+        StatusCode, Version,
     },
+    informational::{Informational, InformationalReceiver},
+    timing::RequestTiming,
     util::{
-        buffered::{BufferedIo, ListWriteBuf, ReadBuf, WriteBuf},
+        buffered::{BufInterest, BufferedIo, ListWriteBuf, ReadBuf, WriteBuf},
         timer::{KeepAlive, Timeout},
     },
 };
@@ -88,6 +92,11 @@ struct Dispatcher<'a, St, S, ReqB, W, D, const HEADER_LIMIT: usize, const READ_B
     timer: Timer<'a>,
     ctx: Context<'a, D, HEADER_LIMIT>,
     service: &'a S,
+    body_buf_limit: usize,
+    req_remaining: usize,
+    request_timing: bool,
+    disconnect_signal: bool,
+    informational_responses: bool,
     _phantom: PhantomData<ReqB>,
 }
 
@@ -175,11 +184,24 @@ where
         date: &'a D,
         write_buf: W,
     ) -> Self {
+        let mut ctx = Context::with_addr(addr, date);
+        ctx.set_smuggling_policy(config.smuggling_policy);
+        ctx.set_expect_policy(config.expect_policy);
+        ctx.set_header_limits(config.header_limits);
+        ctx.set_header_limit_metrics(config.header_limit_metrics);
+        ctx.set_header_case(config.header_case);
+        ctx.set_response_headers(config.response_headers);
+
         Self {
             io: BufferedIo::new(io, write_buf),
             timer: Timer::new(timer, config.keep_alive_timeout, config.request_head_timeout),
-            ctx: Context::with_addr(addr, date),
+            ctx,
             service,
+            body_buf_limit: config.request_body_buffer_size,
+            req_remaining: config.max_requests_per_connection,
+            request_timing: config.request_timing,
+            disconnect_signal: config.disconnect_signal,
+            informational_responses: config.informational_responses,
             _phantom: PhantomData,
         }
     }
@@ -193,10 +215,16 @@ where
                     return Ok(());
                 }
                 Err(Error::RequestTimeout) => self.request_error(|| status_only(StatusCode::REQUEST_TIMEOUT)),
-                Err(Error::Proto(ProtoError::HeaderTooLarge)) => {
-                    self.request_error(|| status_only(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE))
+                Err(Error::Proto(ProtoError::HeaderTooLarge)) => self
+                    .ctx
+                    .encode_header_limit_error(HeaderLimitExceeded::HeadSize, &mut self.io.write_buf),
+                Err(Error::Proto(ProtoError::HeaderLimit(reason))) => {
+                    self.ctx.encode_header_limit_error(reason, &mut self.io.write_buf)
+                }
+                Err(Error::Proto(e)) => {
+                    trace!(target: "h1_dispatcher", "Request rejected with protocol error: {e:?}");
+                    self.request_error(|| status_only(StatusCode::BAD_REQUEST))
                 }
-                Err(Error::Proto(_)) => self.request_error(|| status_only(StatusCode::BAD_REQUEST)),
                 Err(e) => return Err(e),
             }
 
@@ -217,16 +245,46 @@ where
             .await
             .map_err(|_| self.timer.map_to_err())??;
 
-        while let Some((req, decoder)) = self.ctx.decode_head::<READ_BUF_LIMIT>(&mut self.io.read_buf)? {
+        // start time of reading request head. pipelined request starts after previous response is written.
+        let mut start = self.request_timing.then(Instant::now);
+
+        while let Some((mut req, decoder)) = self.ctx.decode_head::<READ_BUF_LIMIT>(&mut self.io.read_buf)? {
             self.timer.reset_state();
+            self.count_request();
+
+            // reject request before it's body is read. the connection is closed afterwards.
+            if let Some(status) = self.ctx.expect_rejection(&decoder) {
+                self.request_error(|| status_only(status));
+                break;
+            }
 
-            let (mut body_reader, body) = BodyReader::from_coding(decoder);
+            let timing = start.map(|start| {
+                let timing = RequestTiming::new(start);
+                timing.head_decoded();
+                req.extensions_mut().insert(timing.clone());
+                timing
+            });
+
+            let disconnect = self.disconnect_signal.then(|| {
+                let disconnect = Disconnect::default();
+                req.extensions_mut().insert(disconnect.clone());
+                disconnect
+            });
+
+            // informational response is not understood by http/1.0 client.
+            let mut informational = (self.informational_responses && req.version() == Version::HTTP_11).then(|| {
+                let (info, rx) = Informational::channel();
+                req.extensions_mut().insert(info);
+                rx
+            });
+
+            let (mut body_reader, body) = BodyReader::from_coding(decoder, self.body_buf_limit, timing.clone());
             let req = req.map(|ext| ext.map_body(|_| ReqB::from(body)));
 
             let (parts, body) = match self
                 .service
                 .call(req)
-                .select(self.request_body_handler(&mut body_reader))
+                .select(self.request_body_handler(&mut body_reader, disconnect.as_ref(), informational.as_mut()))
                 .await
             {
                 SelectOutput::A(Ok(res)) => res.into_parts(),
@@ -235,20 +293,43 @@ where
                 SelectOutput::B(Ok(i)) => match i {},
             };
 
+            // encode informational responses sent right before service returns. receiver is dropped afterwards so
+            // sending after final response is rejected.
+            if let Some(mut rx) = informational.take() {
+                while let Some(res) = rx.try_recv() {
+                    self.ctx.encode_informational(res, &mut self.io.write_buf);
+                }
+            }
+
+            if let Some(ref timing) = timing {
+                timing.handler_done();
+            }
+
             let encoder = &mut self.encode_head(parts, &body)?;
             let mut body = pin!(body);
 
+            // flush hint from body. body is not polled until buffered bytes are written to io.
+            let mut flush = false;
+
             loop {
+                flush &= self.io.write_buf.want_write_io();
+
                 match self
-                    .try_poll_body(body.as_mut())
+                    .try_poll_body(body.as_mut(), flush)
                     .select(self.io_ready(&mut body_reader))
                     .await
                 {
-                    SelectOutput::A(Some(Ok(bytes))) => encoder.encode(bytes, &mut self.io.write_buf),
+                    SelectOutput::A(Some(Ok(bytes))) if bytes.is_empty() => flush = true,
+                    SelectOutput::A(Some(Ok(bytes))) => {
+                        if let Some(ref timing) = timing {
+                            timing.add_response_body_bytes(bytes.len());
+                        }
+                        encoder.encode(bytes, &mut self.io.write_buf)
+                    }
                     SelectOutput::B(Ok(ready)) => {
                         if ready.is_readable() {
                             if let Err(e) = self.io.try_read() {
-                                body_reader.feed_error(e);
+                                body_reader.feed_incomplete(e);
                             }
                         }
                         if ready.is_writable() {
@@ -257,6 +338,9 @@ where
                     }
                     SelectOutput::A(None) => {
                         encoder.encode_eof(&mut self.io.write_buf);
+                        if let Some(ref timing) = timing {
+                            timing.body_written();
+                        }
                         break;
                     }
                     SelectOutput::B(Err(e)) => return Err(e.into()),
@@ -264,24 +348,65 @@ where
                 }
             }
 
-            if !body_reader.decoder.is_eof() {
+            if !body_reader.decoder.is_eof() || self.req_remaining == 0 {
                 self.ctx.set_close();
                 break;
             }
+
+            if start.is_some() {
+                start = Some(Instant::now());
+            }
         }
 
         Ok(())
     }
 
+    // count served request and mark connection as closing when the last one allowed is reached.
+    // the close state must be set before encoding response head so `Connection: close` header is written.
+    fn count_request(&mut self) {
+        self.req_remaining -= 1;
+        if self.req_remaining == 0 {
+            self.ctx.set_close();
+        }
+    }
+
     fn encode_head(&mut self, parts: Parts, body: &impl Stream) -> Result<TransferCoding, ProtoError> {
         self.ctx.encode_head(parts, body, &mut self.io.write_buf)
     }
 
     // an associated future of self.service that runs until service is resolved or error produced.
-    async fn request_body_handler(&mut self, body_reader: &mut BodyReader) -> Result<Infallible, Error<S::Error, BE>> {
+    async fn request_body_handler(
+        &mut self,
+        body_reader: &mut BodyReader,
+        disconnect: Option<&Disconnect>,
+        informational: Option<&mut InformationalReceiver>,
+    ) -> Result<Infallible, Error<S::Error, BE>> {
+        let Some(rx) = informational else {
+            return self.read_request_body(body_reader, disconnect).await;
+        };
+
+        loop {
+            match self.read_request_body(body_reader, disconnect).select(rx.recv()).await {
+                SelectOutput::A(res) => return res,
+                // reading request body is interrupted and resumed after informational response is written.
+                SelectOutput::B(res) => {
+                    self.ctx.encode_informational(res, &mut self.io.write_buf);
+                    self.io.drain_write().await?;
+                }
+            }
+        }
+    }
+
+    async fn read_request_body(
+        &mut self,
+        body_reader: &mut BodyReader,
+        disconnect: Option<&Disconnect>,
+    ) -> Result<Infallible, Error<S::Error, BE>> {
         if self.ctx.is_expect_header() {
             // wait for service future to start polling RequestBody.
             if body_reader.wait_for_poll().await.is_ok() {
+                // continue is only encoded once when reading is interrupted by informational response.
+                self.ctx.remove_expect_header();
                 // encode continue as service future want a body.
                 self.io.write_buf.write_buf_static(CONTINUE);
                 // use drain write to make sure continue is sent to client.
@@ -289,14 +414,40 @@ where
             }
         }
 
-        loop {
-            body_reader.ready(&mut self.io.read_buf).await;
-            self.io.read().await?;
+        while body_reader.decode(&mut self.io.read_buf).await {
+            if let Err(e) = self.io.read().await {
+                // peer is gone in the middle of request body. service is kept running so it can observe the
+                // incomplete body and make use of the part already received.
+                body_reader.feed_incomplete(e);
+                if let Some(disconnect) = disconnect {
+                    disconnect.trigger();
+                }
+                self.ctx.set_close();
+                break;
+            }
         }
+
+        if let (Some(disconnect), true) = (disconnect, body_reader.decoder.is_eof()) {
+            // keep reading io for observing peer closing connection. read bytes are left in read buffer for
+            // decoding next request and reading is stopped when it's full.
+            while self.io.read_buf.want_write_buf() {
+                if self.io.read().await.is_err() {
+                    disconnect.trigger();
+                    self.ctx.set_close();
+                    break;
+                }
+            }
+        }
+
+        pending().await
     }
 
-    fn try_poll_body<'b>(&self, mut body: Pin<&'b mut ResB>) -> impl Future<Output = Option<Result<Bytes, BE>>> + 'b {
-        let want_buf = self.io.write_buf.want_write_buf();
+    fn try_poll_body<'b>(
+        &self,
+        mut body: Pin<&'b mut ResB>,
+        flush: bool,
+    ) -> impl Future<Output = Option<Result<Bytes, BE>>> + 'b {
+        let want_buf = !flush && self.io.write_buf.want_write_buf();
         async move {
             if want_buf {
                 poll_fn(|cx| body.as_mut().poll_next(cx)).await
@@ -336,28 +487,68 @@ where
 pub(super) struct BodyReader {
     pub(super) decoder: TransferCoding,
     tx: RequestBodySender,
+    timing: Option<RequestTiming>,
+    received: u64,
 }
 
 impl BodyReader {
-    pub(super) fn from_coding(decoder: TransferCoding) -> (Self, RequestBody) {
-        let (tx, body) = RequestBody::channel(decoder.is_eof());
-        let body_reader = BodyReader { decoder, tx };
+    pub(super) fn from_coding(
+        decoder: TransferCoding,
+        limit: usize,
+        timing: Option<RequestTiming>,
+    ) -> (Self, RequestBody) {
+        let (tx, body) = RequestBody::channel(decoder.is_eof(), limit);
+        if decoder.is_eof() {
+            if let Some(ref timing) = timing {
+                timing.body_read();
+            }
+        }
+        let body_reader = BodyReader {
+            decoder,
+            tx,
+            timing,
+            received: 0,
+        };
         (body_reader, body)
     }
 
     // dispatcher MUST call this method before do any io reading.
     // a none ready state means the body consumer either is in backpressure or don't expect body.
     pub(super) async fn ready<const READ_BUF_LIMIT: usize>(&mut self, read_buf: &mut ReadBuf<READ_BUF_LIMIT>) {
+        if !self.decode(read_buf).await {
+            pending().await
+        }
+    }
+
+    // decode request body from read buffer. return true when more data is needed from io and false when body is
+    // finished or corrupted.
+    async fn decode<const READ_BUF_LIMIT: usize>(&mut self, read_buf: &mut ReadBuf<READ_BUF_LIMIT>) -> bool {
         loop {
             match self.decoder.decode(&mut *read_buf) {
-                ChunkResult::Ok(bytes) => self.tx.feed_data(bytes),
+                ChunkResult::Ok(bytes) => {
+                    if let Some(ref timing) = self.timing {
+                        timing.add_request_body_bytes(bytes.len());
+                    }
+                    self.received += bytes.len() as u64;
+                    self.tx.feed_data(bytes);
+                    // stop decoding when body buffer is full. read buffer is left untouched and io
+                    // reading is paused until body consumer drains the buffer.
+                    if self.tx.is_full() && self.tx.ready().await.is_err() {
+                        self.decoder.set_corrupted();
+                    }
+                }
                 ChunkResult::InsufficientData => match self.tx.ready().await {
-                    Ok(_) => return,
+                    Ok(_) => return true,
                     // service future drop RequestBody so marker decoder to corrupted.
                     Err(_) => self.decoder.set_corrupted(),
                 },
-                ChunkResult::OnEof => self.tx.feed_eof(),
-                ChunkResult::AlreadyEof | ChunkResult::Corrupted => pending().await,
+                ChunkResult::OnEof => {
+                    if let Some(ref timing) = self.timing {
+                        timing.body_read();
+                    }
+                    self.tx.feed_eof()
+                }
+                ChunkResult::AlreadyEof | ChunkResult::Corrupted => return false,
                 ChunkResult::Err(e) => self.feed_error(e),
             }
         }
@@ -367,10 +558,29 @@ impl BodyReader {
     #[cold]
     #[inline(never)]
     pub(super) fn feed_error(&mut self, e: io::Error) {
-        self.tx.feed_error(e);
+        // peer closing it's write half is the end of tunneled request body rather than an error.
+        if self.decoder.is_upgrade() && e.kind() == io::ErrorKind::UnexpectedEof {
+            self.tx.feed_eof();
+        } else {
+            self.tx.feed_error(e);
+        }
         self.decoder.set_corrupted();
     }
 
+    // feed io error to body sender when peer is gone before request body is finished. error is tagged with
+    // BodyIncomplete for body consumer to tell how much of the body is received.
+    #[cold]
+    #[inline(never)]
+    pub(super) fn feed_incomplete(&mut self, e: io::Error) {
+        let expected = match self.decoder {
+            TransferCoding::Length(rem) => Some(self.received + rem),
+            TransferCoding::Upgrade => return self.feed_error(e),
+            _ => None,
+        };
+        let e = io::Error::new(e.kind(), BodyIncomplete::new(self.received, expected));
+        self.feed_error(e);
+    }
+
     // wait for service start to consume RequestBody.
     pub(super) async fn wait_for_poll(&mut self) -> io::Result<()> {
         // IMPORTANT: service future drop RequestBody so marker decoder to corrupted.
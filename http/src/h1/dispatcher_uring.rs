@@ -25,7 +25,7 @@ use xitca_unsafe_collection::futures::SelectOutput;
 use crate::{
     body::NoneBody,
     bytes::Bytes,
-    config::HttpServiceConfig,
+    config::{HeaderLimitExceeded, HttpServiceConfig},
     date::DateTime,
     h1::{body::RequestBody, error::Error},
     http::{response::Response, StatusCode},
@@ -53,6 +53,7 @@ pub(super) struct Dispatcher<'a, Io, S, ReqB, D, const H_LIMIT: usize, const R_L
     read_buf: BufOwned,
     write_buf: BufOwned,
     notify: Notify<BufOwned>,
+    req_remaining: usize,
     _phantom: PhantomData<ReqB>,
 }
 
@@ -119,14 +120,23 @@ where
         service: &'a S,
         date: &'a D,
     ) -> Self {
+        let mut ctx = Context::<_, H_LIMIT>::with_addr(addr, date);
+        ctx.set_smuggling_policy(config.smuggling_policy);
+        ctx.set_expect_policy(config.expect_policy);
+        ctx.set_header_limits(config.header_limits);
+        ctx.set_header_limit_metrics(config.header_limit_metrics);
+        ctx.set_header_case(config.header_case);
+        ctx.set_response_headers(config.response_headers);
+
         Self {
             io: Rc::new(io),
             timer: Timer::new(timer, config.keep_alive_timeout, config.request_head_timeout),
-            ctx: Context::<_, H_LIMIT>::with_addr(addr, date),
+            ctx,
             service,
             read_buf: BufOwned::new(),
             write_buf: BufOwned::new(),
             notify: Notify::new(),
+            req_remaining: config.max_requests_per_connection,
             _phantom: PhantomData,
         }
     }
@@ -140,8 +150,11 @@ where
                     return Ok(());
                 }
                 Err(Error::RequestTimeout) => self.request_error(|| status_only(StatusCode::REQUEST_TIMEOUT)),
-                Err(Error::Proto(ProtoError::HeaderTooLarge)) => {
-                    self.request_error(|| status_only(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE))
+                Err(Error::Proto(ProtoError::HeaderTooLarge)) => self
+                    .ctx
+                    .encode_header_limit_error(HeaderLimitExceeded::HeadSize, &mut *self.write_buf),
+                Err(Error::Proto(ProtoError::HeaderLimit(reason))) => {
+                    self.ctx.encode_header_limit_error(reason, &mut *self.write_buf)
                 }
                 Err(Error::Proto(_)) => self.request_error(|| status_only(StatusCode::BAD_REQUEST)),
                 Err(e) => return Err(e),
@@ -173,6 +186,18 @@ where
         while let Some((req, decoder)) = self.ctx.decode_head::<R_LIMIT>(&mut self.read_buf)? {
             self.timer.reset_state();
 
+            // mark connection as closing before encoding response head when the last allowed request is reached.
+            self.req_remaining -= 1;
+            if self.req_remaining == 0 {
+                self.ctx.set_close();
+            }
+
+            // reject request before it's body is read. the connection is closed afterwards.
+            if let Some(status) = self.ctx.expect_rejection(&decoder) {
+                self.request_error(|| status_only(status));
+                break;
+            }
+
             let (waiter, body) = if decoder.is_eof() {
                 (None, RequestBody::default())
             } else {
@@ -213,8 +238,11 @@ where
 
                         match res {
                             SelectOutput::A(Some(Ok(bytes))) => {
-                                encoder.encode(bytes, buf);
-                                continue;
+                                // empty bytes is a flush hint. write buffered bytes to io before polling body again.
+                                if !bytes.is_empty() || buf.is_empty() {
+                                    encoder.encode(bytes, buf);
+                                    continue;
+                                }
                             }
                             SelectOutput::A(Some(Err(e))) => return self.on_body_error(e).await,
                             SelectOutput::A(None) => break encoder.encode_eof(buf),
@@ -235,6 +263,10 @@ where
                     }
                 }
             }
+
+            if self.req_remaining == 0 {
+                break;
+            }
         }
 
         Ok(())
@@ -1,4 +1,30 @@
 //! http/1 specific module for types and protocol utilities.
+//!
+//! # CONNECT tunneling
+//! Request with `CONNECT` method has it's body decoded as raw bytes sent by client after the request head.
+//! When service responds with a `2xx` status code the response body is written to client as raw bytes
+//! too and the connection becomes a bidirectional tunnel, which is useful for building forward proxy.
+//! The connection is closed when response body ends.
+//! ```rust
+//! use xitca_http::{
+//!     body::ResponseBody,
+//!     h1::RequestBody,
+//!     http::{Method, Request, RequestExt, Response, StatusCode},
+//! };
+//!
+//! async fn service(req: Request<RequestExt<RequestBody>>) -> Result<Response<ResponseBody>, ()> {
+//!     if req.method() != Method::CONNECT {
+//!         let mut res = Response::new(ResponseBody::none());
+//!         *res.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+//!         return Ok(res);
+//!     }
+//!
+//!     // request body is the stream of bytes from client. in a real forward proxy it would be written to
+//!     // the connection of upstream server addressed by request uri and the bytes read from upstream would
+//!     // be the response body. here the bytes are echoed back to client.
+//!     Ok(Response::new(ResponseBody::box_stream(req.into_body())))
+//! }
+//! ```
 
 pub mod proto;
 
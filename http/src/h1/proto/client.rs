@@ -0,0 +1,313 @@
+//! client side codec of Http/1.x. encode request head and decode response head with the same
+//! [Context] used by server side dispatcher.
+
+use core::mem::MaybeUninit;
+
+use futures_core::stream::Stream;
+use httparse::{ParserConfig, Status};
+
+use crate::{
+    body::BodySize,
+    bytes::{Buf, BytesMut},
+    date::DateTime,
+    http::{
+        header::{HeaderMap, HeaderValue, CONTENT_LENGTH, HOST},
+        intern::intern,
+        Method, Request, Response, StatusCode, Uri, Version,
+    },
+};
+
+use super::{buf_write::H1BufWrite, codec::TransferCoding, context::Context, error::ProtoError, header::HeaderIndex};
+
+impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS>
+where
+    D: DateTime,
+{
+    /// encode request head to buffer and generate request body encoder.
+    ///
+    /// Context state is reset and request method is recorded for decoding the following response with
+    /// [Context::decode_response_head]. `host` header is generated from request uri when it's not presented.
+    /// Headers of request are drained into buffer.
+    ///
+    /// After encoding [Context::is_connection_closed] returns true when request asks for closing connection.
+    pub fn encode_request_head<B, W>(&mut self, req: &mut Request<B>, buf: &mut W) -> Result<TransferCoding, ProtoError>
+    where
+        B: Stream,
+        W: H1BufWrite,
+    {
+        buf.write_buf_head(|buf| self.encode_request_head_inner(req, buf))
+    }
+
+    fn encode_request_head_inner<B>(
+        &mut self,
+        req: &mut Request<B>,
+        buf: &mut BytesMut,
+    ) -> Result<TransferCoding, ProtoError>
+    where
+        B: Stream,
+    {
+        self.reset();
+
+        let version = match req.version() {
+            Version::HTTP_11 => b" HTTP/1.1",
+            Version::HTTP_10 => {
+                self.set_close();
+                b" HTTP/1.0"
+            }
+            _ => return Err(ProtoError::Version),
+        };
+
+        let uri = req.uri();
+
+        // CONNECT request use authority form as request target.
+        let target = match *req.method() {
+            Method::CONNECT => {
+                self.set_connect_method();
+                uri.authority().map(|a| a.as_str()).ok_or(ProtoError::Uri)?
+            }
+            _ => uri.path_and_query().map(|p| p.as_str()).unwrap_or("/"),
+        };
+
+        let is_head = req.method() == Method::HEAD;
+
+        let method = req.method().as_str().as_bytes();
+        let target = target.as_bytes();
+
+        buf.reserve(method.len() + 1 + target.len() + version.len());
+        buf.extend_from_slice(method);
+        buf.extend_from_slice(b" ");
+        buf.extend_from_slice(target);
+        buf.extend_from_slice(version);
+
+        if !req.headers().contains_key(HOST) {
+            if let Some(host) = host(uri) {
+                req.headers_mut().insert(HOST, host?);
+            }
+        }
+
+        let size = BodySize::from_stream(req.body());
+
+        let res = self.encode_headers(req.headers_mut(), size, buf, false);
+
+        // record HEAD method after encoding headers where it's used for response specific body removal.
+        if is_head {
+            self.set_head_method();
+        }
+
+        res
+    }
+}
+
+// generate host header value from uri. default port of http and https is omitted.
+fn host(uri: &Uri) -> Option<Result<HeaderValue, ProtoError>> {
+    let host = uri.host()?;
+    let value = match uri.port() {
+        Some(port) if !matches!(port.as_str(), "80" | "443") => HeaderValue::try_from(format!("{host}:{port}")),
+        // host is repeated for every request of a connection and interned.
+        _ => intern(host),
+    };
+    Some(value.map_err(|_| ProtoError::HeaderValue))
+}
+
+impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
+    /// decode response head from buffer and generate response body decoder.
+    ///
+    /// Must be called after [Context::encode_request_head] of the request the response belongs to. Body
+    /// decoder is decided by request method, response status and headers:
+    /// - response to `HEAD` request and response with `1xx`, `204` or `304` status has no body.
+    /// - `101` response and `2xx` response to `CONNECT` request are decoded as [TransferCoding::Upgrade]
+    ///   where connection is handed over to other protocol.
+    /// - response without `content-length` and `transfer-encoding` header is delimited by connection close.
+    ///   It's decoded as [TransferCoding::Upgrade] and connection is marked as closed.
+    ///
+    /// After decoding [Context::is_connection_closed] returns true when connection can not be reused for
+    /// next request.
+    pub fn decode_response_head<const READ_BUF_LIMIT: usize>(
+        &mut self,
+        buf: &mut BytesMut,
+    ) -> Result<Option<(Response<()>, TransferCoding)>, ProtoError> {
+        let mut headers = [const { MaybeUninit::uninit() }; MAX_HEADERS];
+        let mut parsed = httparse::Response::new(&mut []);
+
+        match ParserConfig::default()
+            .allow_spaces_after_header_name_in_responses(true)
+            .parse_response_with_uninit_headers(&mut parsed, buf.as_ref(), &mut headers)?
+        {
+            Status::Complete(len) => {
+                let version = if parsed.version.unwrap() == 1 {
+                    Version::HTTP_11
+                } else {
+                    self.set_close();
+                    Version::HTTP_10
+                };
+
+                let status = StatusCode::from_u16(parsed.code.unwrap())?;
+
+                let mut header_idx = [const { MaybeUninit::uninit() }; MAX_HEADERS];
+                let header_idx_slice = HeaderIndex::record(&mut header_idx, buf, parsed.headers);
+                let headers_len = parsed.headers.len();
+
+                let slice = buf.split_to(len).freeze();
+
+                let mut headers = self.take_headers();
+                headers.reserve(headers_len);
+
+                let mut decoder = TransferCoding::eof();
+
+                for idx in header_idx_slice {
+                    self.try_write_header(&mut headers, &mut decoder, idx, &slice, version)?;
+                }
+
+                if status == StatusCode::SWITCHING_PROTOCOLS || (self.is_connect_method() && status.is_success()) {
+                    decoder = TransferCoding::upgrade();
+                } else if self.is_head_method()
+                    || status.is_informational()
+                    || status == StatusCode::NO_CONTENT
+                    || status == StatusCode::NOT_MODIFIED
+                {
+                    decoder = TransferCoding::eof();
+                } else if matches!(decoder, TransferCoding::Eof) && !has_zero_length(&headers) {
+                    self.set_close();
+                    decoder = TransferCoding::upgrade();
+                }
+
+                let mut res = Response::new(());
+
+                *res.version_mut() = version;
+                *res.status_mut() = status;
+                *res.headers_mut() = headers;
+                *res.extensions_mut() = self.take_extensions();
+
+                Ok(Some((res, decoder)))
+            }
+            Status::Partial => {
+                if buf.remaining() >= READ_BUF_LIMIT {
+                    Err(ProtoError::HeaderTooLarge)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+}
+
+// content-length: 0 leaves decoder in eof state which is not a close delimited body.
+fn has_zero_length(headers: &HeaderMap) -> bool {
+    headers.get(CONTENT_LENGTH).is_some_and(|v| v.as_bytes() == b"0")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        body::{BoxBody, Once},
+        bytes::Bytes,
+        date::SystemTimeDateTimeHandler,
+        http::header::CONNECTION,
+    };
+
+    use super::*;
+
+    fn encode(
+        ctx: &mut Context<'_, SystemTimeDateTimeHandler, 8>,
+        mut req: Request<BoxBody>,
+    ) -> (BytesMut, TransferCoding) {
+        let mut buf = BytesMut::new();
+        let encoder = ctx.encode_request_head(&mut req, &mut buf).unwrap();
+        (buf, encoder)
+    }
+
+    fn decode(ctx: &mut Context<'_, SystemTimeDateTimeHandler, 8>, res: &[u8]) -> (Response<()>, TransferCoding) {
+        let mut buf = BytesMut::from(res);
+        ctx.decode_response_head::<4096>(&mut buf).unwrap().unwrap()
+    }
+
+    #[test]
+    fn request_head() {
+        let mut ctx = Context::<_, 8>::new(&SystemTimeDateTimeHandler);
+
+        let mut req = Request::new(BoxBody::new(Once::new(Bytes::from_static(b"996"))));
+        *req.method_mut() = Method::POST;
+        *req.uri_mut() = "http://example.com/foo?bar=1".parse().unwrap();
+
+        let (buf, encoder) = encode(&mut ctx, req);
+
+        let mut headers = [httparse::EMPTY_HEADER; 8];
+        let mut req = httparse::Request::new(&mut headers);
+        assert!(req.parse(&buf).unwrap().is_complete());
+        assert_eq!(req.method, Some("POST"));
+        assert_eq!(req.path, Some("/foo?bar=1"));
+
+        let host = req.headers.iter().find(|h| h.name == "host").unwrap();
+        assert_eq!(host.value, b"example.com");
+        let len = req.headers.iter().find(|h| h.name == "content-length").unwrap();
+        assert_eq!(len.value, b"3");
+
+        assert_eq!(encoder, TransferCoding::length(3));
+        assert!(!ctx.is_connection_closed());
+
+        let mut req = Request::new(BoxBody::default());
+        *req.method_mut() = Method::CONNECT;
+        *req.uri_mut() = "example.com:443".parse().unwrap();
+        req.headers_mut().insert(CONNECTION, HeaderValue::from_static("close"));
+
+        let (buf, _) = encode(&mut ctx, req);
+        assert!(buf.starts_with(b"CONNECT example.com:443 HTTP/1.1"));
+        assert!(ctx.is_connection_closed());
+    }
+
+    #[test]
+    fn response_head() {
+        let mut ctx = Context::<_, 8>::new(&SystemTimeDateTimeHandler);
+
+        let mut req = Request::new(BoxBody::default());
+        *req.uri_mut() = "/".parse().unwrap();
+        encode(&mut ctx, req);
+
+        let (res, decoder) = decode(&mut ctx, b"HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\n");
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(decoder, TransferCoding::length(5));
+        assert!(!ctx.is_connection_closed());
+
+        let (_, decoder) = decode(&mut ctx, b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n");
+        assert_eq!(decoder, TransferCoding::decode_chunked());
+
+        let (_, decoder) = decode(&mut ctx, b"HTTP/1.1 204 No Content\r\n\r\n");
+        assert!(decoder.is_eof());
+        assert!(!ctx.is_connection_closed());
+
+        let (_, decoder) = decode(&mut ctx, b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n");
+        assert!(decoder.is_eof());
+        assert!(!ctx.is_connection_closed());
+
+        let (_, decoder) = decode(&mut ctx, b"HTTP/1.1 200 OK\r\n\r\n");
+        assert!(decoder.is_upgrade());
+        assert!(ctx.is_connection_closed());
+
+        let mut req = Request::new(BoxBody::default());
+        *req.method_mut() = Method::HEAD;
+        *req.uri_mut() = "/".parse().unwrap();
+        encode(&mut ctx, req);
+        assert!(!ctx.is_connection_closed());
+
+        let (_, decoder) = decode(&mut ctx, b"HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\n");
+        assert!(decoder.is_eof());
+
+        let mut req = Request::new(BoxBody::default());
+        *req.uri_mut() = "/".parse().unwrap();
+        encode(&mut ctx, req);
+
+        let (_, decoder) = decode(&mut ctx, b"HTTP/1.0 200 OK\r\ncontent-length: 5\r\n\r\n");
+        assert_eq!(decoder, TransferCoding::length(5));
+        assert!(ctx.is_connection_closed());
+
+        let mut req = Request::new(BoxBody::default());
+        *req.uri_mut() = "/".parse().unwrap();
+        encode(&mut ctx, req);
+
+        let (_, _) = decode(
+            &mut ctx,
+            b"HTTP/1.0 200 OK\r\nconnection: keep-alive\r\ncontent-length: 5\r\n\r\n",
+        );
+        assert!(!ctx.is_connection_closed());
+    }
+}
@@ -17,8 +17,9 @@ pub enum TransferCoding {
     Corrupted,
     /// Coder used when a Content-Length header is passed with a positive integer.
     Length(u64),
-    /// Decoder used when Transfer-Encoding is `chunked`.
-    DecodeChunked(ChunkedState, u64),
+    /// Decoder used when Transfer-Encoding is `chunked`. The bool flag indicates strict validation of chunk
+    /// extension.
+    DecodeChunked(ChunkedState, u64, bool),
     /// Encoder for when Transfer-Encoding includes `chunked`.
     EncodeChunked,
     /// Upgrade type coder that pass through body as is without transforming.
@@ -38,7 +39,13 @@ impl TransferCoding {
 
     #[inline]
     pub const fn decode_chunked() -> Self {
-        Self::DecodeChunked(ChunkedState::Size, 0)
+        Self::DecodeChunked(ChunkedState::Size, 0, false)
+    }
+
+    /// Chunked decoder rejecting chunk extension containing control characters.
+    #[inline]
+    pub const fn decode_chunked_strict() -> Self {
+        Self::DecodeChunked(ChunkedState::Size, 0, true)
     }
 
     #[inline]
@@ -114,6 +121,18 @@ impl ChunkedState {
         }
     }
 
+    pub fn step_strict(
+        &mut self,
+        body: &mut BytesMut,
+        size: &mut u64,
+        buf: &mut Option<Bytes>,
+    ) -> io::Result<Option<Self>> {
+        match *self {
+            Self::Extension => Self::read_extension_strict(body),
+            _ => self.step(body, size, buf),
+        }
+    }
+
     fn read_size(rdr: &mut BytesMut, size: &mut u64) -> io::Result<Option<Self>> {
         macro_rules! or_overflow {
             ($e:expr) => (
@@ -179,6 +198,18 @@ impl ChunkedState {
         }
     }
 
+    fn read_extension_strict(rdr: &mut BytesMut) -> io::Result<Option<Self>> {
+        match byte!(rdr) {
+            b'\r' => Ok(Some(Self::SizeLf)),
+            b'\t' => Ok(Some(Self::Extension)),
+            b if b.is_ascii_control() => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid chunk extension contains control character",
+            )),
+            _ => Ok(Some(Self::Extension)),
+        }
+    }
+
     fn read_size_lf(rdr: &mut BytesMut, size: &u64) -> io::Result<Option<Self>> {
         match byte!(rdr) {
             b'\n' if *size > 0 => Ok(Some(Self::Body)),
@@ -323,7 +354,7 @@ impl TransferCoding {
             // ChunkResult::AlreadyEof if decode is called again.
             // This multi stage behaviour is depended on by the caller to know the exact timing of
             // when eof happens. (Expensive one time operations can be happening at Eof)
-            Self::Length(0) | Self::DecodeChunked(ChunkedState::End, _) => {
+            Self::Length(0) | Self::DecodeChunked(ChunkedState::End, ..) => {
                 *self = Self::Eof;
                 ChunkResult::OnEof
             }
@@ -332,11 +363,16 @@ impl TransferCoding {
             ref _this if src.is_empty() => ChunkResult::InsufficientData,
             Self::Length(ref mut rem) => ChunkResult::Ok(bounded_split(rem, src)),
             Self::Upgrade => ChunkResult::Ok(src.split().freeze()),
-            Self::DecodeChunked(ref mut state, ref mut size) => {
+            Self::DecodeChunked(ref mut state, ref mut size, strict) => {
                 loop {
                     let mut buf = None;
                     // advances the chunked state
-                    *state = match state.step(src, size, &mut buf) {
+                    let res = if strict {
+                        state.step_strict(src, size, &mut buf)
+                    } else {
+                        state.step(src, size, &mut buf)
+                    };
+                    *state = match res {
                         Ok(Some(state)) => state,
                         Ok(None) => return ChunkResult::InsufficientData,
                         Err(e) => return ChunkResult::Err(e),
@@ -519,6 +555,28 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_read_chunked_strict_extension() {
+        let body = "3;ext=\"a\tb\"\r\nfoo\r\n0\r\n\r\n";
+
+        match TransferCoding::decode_chunked_strict().decode(&mut BytesMut::from(body)) {
+            ChunkResult::Ok(buf) => assert_eq!(buf, "foo"),
+            state => panic!("{}", state),
+        }
+
+        let body = "3;ext\x00\r\nfoo\r\n0\r\n\r\n";
+
+        match TransferCoding::decode_chunked().decode(&mut BytesMut::from(body)) {
+            ChunkResult::Ok(buf) => assert_eq!(buf, "foo"),
+            state => panic!("{}", state),
+        }
+
+        match TransferCoding::decode_chunked_strict().decode(&mut BytesMut::from(body)) {
+            ChunkResult::Err(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            state => panic!("{}", state),
+        }
+    }
+
     #[test]
     fn test_read_chunked_after_eof() {
         let mock_buf = &mut BytesMut::from("10\r\n1234567890abcdef\r\n0\r\n\r\n");
@@ -567,6 +625,19 @@ mod test {
         assert_eq!(dst.buf(), b"7\r\nfoo bar\r\nD\r\nbaz quux herp\r\n0\r\n\r\n");
     }
 
+    #[test]
+    fn encode_chunked_flush_hint() {
+        let mut encoder = TransferCoding::encode_chunked();
+        let dst = &mut WriteBuf::<1024>::default();
+
+        encoder.encode(Bytes::from("foo"), dst);
+        // flush hint must not be encoded as the last chunk.
+        encoder.encode(crate::body::flush_hint(), dst);
+        encoder.encode(Bytes::from("bar"), dst);
+
+        assert_eq!(dst.buf(), b"3\r\nfoo\r\n3\r\nbar\r\n");
+    }
+
     #[test]
     fn encode_length() {
         let max_len = 8;
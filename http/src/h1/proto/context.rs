@@ -1,6 +1,11 @@
 use core::{mem, net::SocketAddr};
 
-use crate::http::{header::HeaderMap, Extensions};
+use crate::{
+    config::{ExpectPolicy, HeaderCase, HeaderLimitMetrics, HeaderLimits, ResponseHeaders, SmugglingPolicy},
+    http::{header::HeaderMap, Extensions, StatusCode},
+};
+
+use super::codec::TransferCoding;
 
 /// Context is connection specific struct contain states for processing.
 pub struct Context<'a, D, const HEADER_LIMIT: usize> {
@@ -11,6 +16,12 @@ pub struct Context<'a, D, const HEADER_LIMIT: usize> {
     // http extensions reused by next request.
     exts: Extensions,
     date: &'a D,
+    policy: SmugglingPolicy,
+    expect_policy: ExpectPolicy,
+    header_limits: HeaderLimits,
+    header_limit_metrics: Option<&'static HeaderLimitMetrics>,
+    header_case: HeaderCase,
+    response_headers: ResponseHeaders,
 }
 
 // A set of state for current request that are used after request's ownership is passed
@@ -64,9 +75,90 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
             header: None,
             exts: Extensions::new(),
             date,
+            policy: SmugglingPolicy::Lenient,
+            expect_policy: ExpectPolicy::Continue,
+            header_limits: HeaderLimits::new(),
+            header_limit_metrics: None,
+            header_case: HeaderCase::Lower,
+            response_headers: ResponseHeaders::new(),
+        }
+    }
+
+    /// Set [SmugglingPolicy] of request framing strictness for Context.
+    #[inline]
+    pub fn set_smuggling_policy(&mut self, policy: SmugglingPolicy) {
+        self.policy = policy;
+    }
+
+    /// Return true when Context is using [SmugglingPolicy::Strict].
+    #[inline]
+    pub fn is_strict(&self) -> bool {
+        self.policy == SmugglingPolicy::Strict
+    }
+
+    /// Set [ExpectPolicy] of request with `Expect: 100-continue` header for Context.
+    #[inline]
+    pub fn set_expect_policy(&mut self, policy: ExpectPolicy) {
+        self.expect_policy = policy;
+    }
+
+    /// Return status code for rejecting current request according to [ExpectPolicy] and it's body decoder.
+    /// None when request does not have expect header or it's allowed by policy.
+    pub fn expect_rejection(&self, decoder: &TransferCoding) -> Option<StatusCode> {
+        match *decoder {
+            TransferCoding::Length(len) if self.is_expect_header() => self.expect_policy.reject(len),
+            _ => None,
         }
     }
 
+    /// Set [HeaderLimits] of request head for Context.
+    #[inline]
+    pub fn set_header_limits(&mut self, limits: HeaderLimits) {
+        self.header_limits = limits;
+    }
+
+    /// Get [HeaderLimits] of request head from Context.
+    #[inline]
+    pub fn header_limits(&self) -> &HeaderLimits {
+        &self.header_limits
+    }
+
+    /// Get [HeaderLimitMetrics] of Context.
+    #[inline]
+    pub fn header_limit_metrics(&self) -> Option<&'static HeaderLimitMetrics> {
+        self.header_limit_metrics
+    }
+
+    /// Set [HeaderLimitMetrics] counting request rejected for exceeding [HeaderLimits] for Context.
+    #[inline]
+    pub fn set_header_limit_metrics(&mut self, metrics: Option<&'static HeaderLimitMetrics>) {
+        self.header_limit_metrics = metrics;
+    }
+
+    /// Get [HeaderCase] of response head from Context.
+    #[inline]
+    pub fn header_case(&self) -> HeaderCase {
+        self.header_case
+    }
+
+    /// Set [HeaderCase] of response head for Context.
+    #[inline]
+    pub fn set_header_case(&mut self, case: HeaderCase) {
+        self.header_case = case;
+    }
+
+    /// Get [ResponseHeaders] added to response head from Context.
+    #[inline]
+    pub fn response_headers(&self) -> &ResponseHeaders {
+        &self.response_headers
+    }
+
+    /// Set [ResponseHeaders] added to response head for Context.
+    #[inline]
+    pub fn set_response_headers(&mut self, headers: ResponseHeaders) {
+        self.response_headers = headers;
+    }
+
     /// Get Date type from Context.
     #[inline]
     pub fn date(&self) -> &D {
@@ -113,6 +205,12 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
         self.state.insert(ContextState::EXPECT)
     }
 
+    /// Remove Context's EXPECT header state.
+    #[inline]
+    pub fn remove_expect_header(&mut self) {
+        self.state.remove(ContextState::EXPECT)
+    }
+
     /// Set Context's state to CONNECT method received.
     #[inline]
     pub fn set_connect_method(&mut self) {
@@ -10,6 +10,8 @@ use crate::{
     },
 };
 
+use crate::config::HeaderLimitExceeded;
+
 use super::{
     codec::TransferCoding,
     context::Context,
@@ -28,11 +30,18 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
         let mut req = httparse::Request::new(&mut []);
         let mut headers = [const { MaybeUninit::uninit() }; MAX_HEADERS];
 
-        match req.parse_with_uninit_headers(buf, &mut headers)? {
+        let status = req.parse_with_uninit_headers(buf, &mut headers).map_err(|e| match e {
+            httparse::Error::TooManyHeaders => ProtoError::HeaderLimit(HeaderLimitExceeded::HeaderCount),
+            e => e.into(),
+        })?;
+
+        match status {
             Status::Complete(len) => {
                 // Important: reset context state for new request.
                 self.reset();
 
+                self.check_header_limits(&req, buf)?;
+
                 let method = Method::from_bytes(req.method.unwrap().as_bytes())?;
 
                 // default body decoder from method.
@@ -77,6 +86,10 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
                 let mut headers = self.take_headers();
                 headers.reserve(headers_len);
 
+                if self.is_strict() {
+                    check_framing(header_idx_slice, &slice)?;
+                }
+
                 // write headers to headermap and update request states.
                 for idx in header_idx_slice {
                     self.try_write_header(&mut headers, &mut decoder, idx, &slice, version)?;
@@ -99,6 +112,8 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
             Status::Partial => {
                 if buf.remaining() >= READ_BUF_LIMIT {
                     Err(ProtoError::HeaderTooLarge)
+                } else if self.is_request_line_overflow(buf) {
+                    Err(ProtoError::HeaderLimit(HeaderLimitExceeded::RequestLine))
                 } else {
                     Ok(None)
                 }
@@ -106,6 +121,38 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
         }
     }
 
+    fn check_header_limits(&self, req: &httparse::Request<'_, '_>, buf: &[u8]) -> Result<(), ProtoError> {
+        let limits = self.header_limits();
+
+        // request line ends after path with a space and http version. e.g: " HTTP/1.1"
+        let path = req.path.unwrap();
+        let line_len = path.as_ptr() as usize - buf.as_ptr() as usize + path.len() + 9;
+        if line_len > limits.request_line {
+            return Err(ProtoError::HeaderLimit(HeaderLimitExceeded::RequestLine));
+        }
+
+        if req.headers.len() > limits.header_count {
+            return Err(ProtoError::HeaderLimit(HeaderLimitExceeded::HeaderCount));
+        }
+
+        for header in req.headers.iter() {
+            if header.name.len() > limits.header_name {
+                return Err(ProtoError::HeaderLimit(HeaderLimitExceeded::HeaderName));
+            }
+            if header.value.len() > limits.header_value {
+                return Err(ProtoError::HeaderLimit(HeaderLimitExceeded::HeaderValue));
+            }
+        }
+
+        Ok(())
+    }
+
+    // reject partial request early when it's request line is not finished within limit.
+    fn is_request_line_overflow(&self, buf: &[u8]) -> bool {
+        let limit = self.header_limits().request_line;
+        buf.len() > limit && !buf[..=limit].contains(&b'\n')
+    }
+
     pub fn try_write_header(
         &mut self,
         headers: &mut HeaderMap,
@@ -125,7 +172,12 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
                 for val in value.to_str().map_err(|_| ProtoError::HeaderValue)?.split(',') {
                     let val = val.trim();
                     if val.eq_ignore_ascii_case("chunked") {
-                        decoder.try_set(TransferCoding::decode_chunked())?;
+                        let chunked = if self.is_strict() {
+                            TransferCoding::decode_chunked_strict()
+                        } else {
+                            TransferCoding::decode_chunked()
+                        };
+                        decoder.try_set(chunked)?;
                     }
                 }
             }
@@ -167,8 +219,60 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
     }
 }
 
+// strict check of request framing headers. See SmugglingPolicy::Strict for detail.
+fn check_framing(indices: &[HeaderIndex], slice: &Bytes) -> Result<(), ProtoError> {
+    let mut content_length = None;
+    let mut transfer_encoding = false;
+    let mut chunked = 0;
+    let mut last_chunked = false;
+
+    for idx in indices {
+        let name = &slice[idx.name.0..idx.name.1];
+        let value = &slice[idx.value.0..idx.value.1];
+
+        if name.eq_ignore_ascii_case(b"content-length") {
+            if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+                return Err(ProtoError::HeaderValue);
+            }
+            match content_length {
+                Some(len) if len != value => return Err(ProtoError::ContentLengthMismatch),
+                _ => content_length = Some(value),
+            }
+        } else if name.eq_ignore_ascii_case(b"transfer-encoding") {
+            transfer_encoding = true;
+            let value = core::str::from_utf8(value).map_err(|_| ProtoError::HeaderValue)?;
+            for val in value.split(',') {
+                let val = val.trim();
+                if val.is_empty() {
+                    continue;
+                }
+                last_chunked = val.eq_ignore_ascii_case("chunked");
+                if last_chunked {
+                    chunked += 1;
+                }
+            }
+        }
+    }
+
+    if transfer_encoding {
+        if content_length.is_some() {
+            return Err(ProtoError::ContentLengthWithTransferEncoding);
+        }
+        if chunked != 1 || !last_chunked {
+            return Err(ProtoError::TransferEncodingNotChunked);
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
+    use crate::{
+        config::{ExpectPolicy, HeaderLimits, SmugglingPolicy},
+        http::StatusCode,
+    };
+
     use super::*;
 
     #[test]
@@ -311,4 +415,132 @@ mod test {
             "transfer coding is not decoded to chunked"
         );
     }
+
+    #[test]
+    fn smuggling_policy() {
+        fn decode(head: &[u8], policy: SmugglingPolicy) -> Result<TransferCoding, ProtoError> {
+            let mut ctx = Context::<_, 8>::new(&());
+            ctx.set_smuggling_policy(policy);
+            let mut buf = BytesMut::from(head);
+            ctx.decode_head::<128>(&mut buf).map(|res| res.unwrap().1)
+        }
+
+        let cl_te = b"POST / HTTP/1.1\r\nContent-Length: 0\r\nTransfer-Encoding: chunked\r\n\r\n";
+        assert!(matches!(
+            decode(cl_te, SmugglingPolicy::Lenient),
+            Ok(TransferCoding::DecodeChunked(_, _, false))
+        ));
+        assert!(matches!(
+            decode(cl_te, SmugglingPolicy::Strict),
+            Err(ProtoError::ContentLengthWithTransferEncoding)
+        ));
+
+        let cl_cl = b"POST / HTTP/1.1\r\nContent-Length: 0\r\nContent-Length: 5\r\n\r\n";
+        assert_eq!(
+            decode(cl_cl, SmugglingPolicy::Lenient).unwrap(),
+            TransferCoding::length(5)
+        );
+        assert!(matches!(
+            decode(cl_cl, SmugglingPolicy::Strict),
+            Err(ProtoError::ContentLengthMismatch)
+        ));
+
+        let cl = b"POST / HTTP/1.1\r\nContent-Length: +5\r\n\r\n";
+        assert!(matches!(
+            decode(cl, SmugglingPolicy::Strict),
+            Err(ProtoError::HeaderValue)
+        ));
+
+        let te = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked, gzip\r\n\r\n";
+        assert!(matches!(
+            decode(te, SmugglingPolicy::Strict),
+            Err(ProtoError::TransferEncodingNotChunked)
+        ));
+
+        let te = b"POST / HTTP/1.1\r\nTransfer-Encoding: gzip\r\nTransfer-Encoding: chunked\r\n\r\n";
+        assert!(matches!(
+            decode(te, SmugglingPolicy::Strict),
+            Ok(TransferCoding::DecodeChunked(_, _, true))
+        ));
+    }
+
+    #[test]
+    fn expect_policy() {
+        fn rejection(head: &[u8], policy: ExpectPolicy) -> Option<StatusCode> {
+            let mut ctx = Context::<_, 8>::new(&());
+            ctx.set_expect_policy(policy);
+            let mut buf = BytesMut::from(head);
+            let (_, decoder) = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+            ctx.expect_rejection(&decoder)
+        }
+
+        let expect = b"POST / HTTP/1.1\r\nExpect: 100-continue\r\nContent-Length: 5\r\n\r\n";
+        assert_eq!(rejection(expect, ExpectPolicy::Continue), None);
+        assert_eq!(rejection(expect, ExpectPolicy::PayloadTooLarge(5)), None);
+        assert_eq!(
+            rejection(expect, ExpectPolicy::PayloadTooLarge(4)),
+            Some(StatusCode::PAYLOAD_TOO_LARGE)
+        );
+        assert_eq!(
+            rejection(expect, ExpectPolicy::ExpectationFailed(4)),
+            Some(StatusCode::EXPECTATION_FAILED)
+        );
+
+        let no_expect = b"POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\n";
+        assert_eq!(rejection(no_expect, ExpectPolicy::PayloadTooLarge(4)), None);
+
+        let chunked = b"POST / HTTP/1.1\r\nExpect: 100-continue\r\nTransfer-Encoding: chunked\r\n\r\n";
+        assert_eq!(rejection(chunked, ExpectPolicy::PayloadTooLarge(4)), None);
+    }
+
+    #[test]
+    fn header_limits() {
+        fn decode(head: &[u8], limits: HeaderLimits) -> Result<(), ProtoError> {
+            let mut ctx = Context::<_, 2>::new(&());
+            ctx.set_header_limits(limits);
+            let mut buf = BytesMut::from(head);
+            ctx.decode_head::<128>(&mut buf).map(|res| assert!(res.is_some()))
+        }
+
+        fn exceeded(res: Result<(), ProtoError>) -> HeaderLimitExceeded {
+            match res {
+                Err(ProtoError::HeaderLimit(reason)) => reason,
+                res => panic!("unexpected result: {res:?}"),
+            }
+        }
+
+        let head = b"GET /foo HTTP/1.1\r\nHost: a\r\n\r\n";
+        assert!(decode(head, HeaderLimits::new()).is_ok());
+        assert!(decode(head, HeaderLimits::new().max_request_line(17)).is_ok());
+        assert_eq!(
+            exceeded(decode(head, HeaderLimits::new().max_request_line(16))),
+            HeaderLimitExceeded::RequestLine
+        );
+        assert_eq!(
+            exceeded(decode(head, HeaderLimits::new().max_header_name(3))),
+            HeaderLimitExceeded::HeaderName
+        );
+        assert_eq!(
+            exceeded(decode(head, HeaderLimits::new().max_header_value(0))),
+            HeaderLimitExceeded::HeaderValue
+        );
+        assert_eq!(
+            exceeded(decode(head, HeaderLimits::new().max_header_count(0))),
+            HeaderLimitExceeded::HeaderCount
+        );
+
+        // header count bounded by const generic param.
+        let head = b"GET / HTTP/1.1\r\nA: a\r\nB: b\r\nC: c\r\n\r\n";
+        assert_eq!(
+            exceeded(decode(head, HeaderLimits::new())),
+            HeaderLimitExceeded::HeaderCount
+        );
+
+        // partial request line is rejected early.
+        let partial = b"GET /foooooooooooooooooooo";
+        assert_eq!(
+            exceeded(decode(partial, HeaderLimits::new().max_request_line(16))),
+            HeaderLimitExceeded::RequestLine
+        );
+    }
 }
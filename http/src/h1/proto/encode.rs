@@ -1,18 +1,29 @@
 use futures_core::stream::Stream;
-use tracing::{debug, error, warn};
+use tracing::{debug, error, trace};
 
 use crate::{
-    body::BodySize,
+    body::{BodySize, Once},
     bytes::{Bytes, BytesMut},
+    config::{HeaderCase, HeaderLimitExceeded, ResponseHeaders},
     date::DateTime,
     http::{
-        header::{HeaderMap, CONNECTION, CONTENT_LENGTH, DATE, SET_COOKIE, TE, TRANSFER_ENCODING, UPGRADE},
-        response::Parts,
+        const_header_value::TEXT_UTF8,
+        header::{
+            HeaderMap, CONNECTION, CONTENT_LENGTH, CONTENT_TYPE, DATE, SERVER, SET_COOKIE, TE, TRANSFER_ENCODING,
+            UPGRADE,
+        },
+        response::{Parts, Response},
         StatusCode, Version,
     },
 };
 
-use super::{buf_write::H1BufWrite, codec::TransferCoding, context::Context, error::ProtoError, header};
+use super::{
+    buf_write::H1BufWrite,
+    codec::TransferCoding,
+    context::Context,
+    error::ProtoError,
+    header::{self, HeaderCaseMap},
+};
 
 pub const CONTINUE: &[u8; 25] = b"HTTP/1.1 100 Continue\r\n\r\n";
 
@@ -23,12 +34,78 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS>
 where
     D: DateTime,
 {
-    pub fn encode_head<B, W>(&mut self, parts: Parts, body: &B, buf: &mut W) -> Result<TransferCoding, ProtoError>
+    pub fn encode_head<B, W>(&mut self, mut parts: Parts, body: &B, buf: &mut W) -> Result<TransferCoding, ProtoError>
     where
         B: Stream,
         W: H1BufWrite,
     {
-        buf.write_buf_head(|buf| self.encode_head_inner(parts, body, buf))
+        let case_map = parts.extensions.remove::<HeaderCaseMap>();
+        buf.write_buf_head(|buf| {
+            let start = buf.len();
+            let encoding = self.encode_head_inner(parts, body, buf)?;
+            if case_map.is_some() || self.header_case() != HeaderCase::Lower {
+                header::recase_names(&mut buf[start..], self.header_case(), case_map.as_ref());
+            }
+            Ok(encoding)
+        })
+    }
+
+    /// Encode `431 Request Header Fields Too Large` response with plain text body describing given reason.
+    /// Context is set to close state and the rejection is counted when [HeaderLimitMetrics] is set.
+    ///
+    /// [HeaderLimitMetrics]: crate::config::HeaderLimitMetrics
+    #[cold]
+    #[inline(never)]
+    pub fn encode_header_limit_error<W>(&mut self, reason: HeaderLimitExceeded, buf: &mut W)
+    where
+        W: H1BufWrite,
+    {
+        if let Some(metrics) = self.header_limit_metrics() {
+            metrics.record(reason);
+        }
+
+        self.set_close();
+
+        let bytes = Bytes::from_static(reason.as_str().as_bytes());
+        let body = Once::new(bytes.clone());
+
+        let mut res = Response::new(());
+        *res.status_mut() = StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE;
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        let (parts, _) = res.into_parts();
+
+        let mut encoder = self
+            .encode_head(parts, &body, buf)
+            .expect("header limit error must be correct");
+        encoder.encode(bytes, buf);
+        encoder.encode_eof(buf);
+    }
+
+    /// Encode informational(1xx) response head. Headers are written as is without adding any default one.
+    pub fn encode_informational<W>(&mut self, res: Response<()>, buf: &mut W)
+    where
+        W: H1BufWrite,
+    {
+        let (parts, _) = res.into_parts();
+        let case_map = parts.extensions.get::<HeaderCaseMap>();
+        let _ = buf.write_buf_head(|buf| {
+            let start = buf.len();
+            encode_version_status_reason(buf, Version::HTTP_11, parts.status);
+            for (name, value) in parts.headers.iter() {
+                let name = name.as_str().as_bytes();
+                let value = value.as_bytes();
+                buf.reserve(name.len() + value.len() + 4);
+                buf.extend_from_slice(b"\r\n");
+                buf.extend_from_slice(name);
+                buf.extend_from_slice(b": ");
+                buf.extend_from_slice(value);
+            }
+            buf.extend_from_slice(b"\r\n\r\n");
+            if case_map.is_some() || self.header_case() != HeaderCase::Lower {
+                header::recase_names(&mut buf[start..], self.header_case(), case_map);
+            }
+            Ok::<_, ProtoError>(())
+        });
     }
 
     fn encode_head_inner<B>(&mut self, parts: Parts, body: &B, buf: &mut BytesMut) -> Result<TransferCoding, ProtoError>
@@ -43,6 +120,17 @@ where
             ..
         } = parts;
 
+        let size = BodySize::from_stream(body);
+
+        if let Some(encoding) = self.try_encode_template(&headers, version, status, size, buf) {
+            // put header map and extension back to cache.
+            headers.clear();
+            self.replace_headers(headers);
+            extensions.clear();
+            self.replace_extensions(extensions);
+            return Ok(encoding);
+        }
+
         // decide if content-length or transfer-encoding header would be skipped.
         let skip_ct_te = match status {
             StatusCode::SWITCHING_PROTOCOLS => true,
@@ -59,19 +147,80 @@ where
         // encode version, status code and reason
         encode_version_status_reason(buf, version, status);
 
-        let size = BodySize::from_stream(body);
+        // 2xx response to CONNECT request turns connection into a tunnel where response body
+        // is passed through as is.
+        let is_tunnel = self.is_connect_method() && status.is_success();
 
-        self.encode_headers(&mut headers, size, buf, skip_ct_te).inspect(|_| {
-            // put header map back to cache.
-            self.replace_headers(headers);
+        self.encode_headers(&mut headers, size, buf, skip_ct_te)
+            .map(|encoding| if is_tunnel { TransferCoding::upgrade() } else { encoding })
+            .inspect(|_| {
+                // put header map back to cache.
+                self.replace_headers(headers);
 
-            // put extension back to cache;
-            extensions.clear();
-            self.replace_extensions(extensions);
-        })
+                // put extension back to cache;
+                extensions.clear();
+                self.replace_extensions(extensions);
+            })
+    }
+
+    // fast path for common small response with only a content-type header and sized body. pre-computed head
+    // template is written directly to buf and generic header iteration is bypassed.
+    fn try_encode_template(
+        &mut self,
+        headers: &HeaderMap,
+        version: Version,
+        status: StatusCode,
+        size: BodySize,
+        buf: &mut BytesMut,
+    ) -> Option<TransferCoding> {
+        let BodySize::Sized(size) = size else { return None };
+
+        if version != Version::HTTP_11 || headers.len() != 1 || self.is_head_method() || self.is_connect_method() {
+            return None;
+        }
+
+        // template only carries the default date header.
+        if *self.response_headers() != ResponseHeaders::new() {
+            return None;
+        }
+
+        let content_type = headers.get(CONTENT_TYPE)?.as_bytes();
+        let (_, _, head) = TEMPLATES
+            .iter()
+            .find(|(s, ct, _)| *s == status && *ct == content_type)?;
+
+        buf.extend_from_slice(head);
+        write_length_header(buf, size);
+
+        if self.is_connection_closed() {
+            buf.extend_from_slice(CLOSE_HEADER);
+        }
+
+        self.date().write_date_header(buf);
+        buf.extend_from_slice(b"\r\n\r\n");
+
+        Some(TransferCoding::length(size as u64))
     }
 }
 
+macro_rules! template {
+    ($status: ident, $code: literal, $reason: literal, $content_type: literal) => {
+        (
+            StatusCode::$status,
+            $content_type.as_bytes(),
+            concat!("HTTP/1.1 ", $code, " ", $reason, "\r\ncontent-type: ", $content_type).as_bytes(),
+        )
+    };
+}
+
+// pre-computed status line and content-type header of common responses.
+const TEMPLATES: &[(StatusCode, &[u8], &[u8])] = &[
+    template!(OK, "200", "OK", "text/plain; charset=utf-8"),
+    template!(OK, "200", "OK", "text/html; charset=utf-8"),
+    template!(OK, "200", "OK", "application/json"),
+    template!(NOT_FOUND, "404", "Not Found", "text/plain; charset=utf-8"),
+];
+
 #[inline]
 fn encode_version_status_reason(buf: &mut BytesMut, version: Version, status: StatusCode) {
     // encode version, status code and reason
@@ -113,7 +262,18 @@ where
         buf: &mut BytesMut,
         mut skip_ct_te: bool,
     ) -> Result<TransferCoding, ProtoError> {
-        let mut skip_date = false;
+        let response_headers = *self.response_headers();
+
+        let mut skip_date = !response_headers.date;
+        let mut skip_server = response_headers.server.is_none();
+
+        // bit mask of fixed headers already present in header map.
+        let mut skip_fixed = 0u64;
+        for (i, (name, _)) in response_headers.headers.iter().enumerate() {
+            if headers.contains_key(*name) {
+                skip_fixed |= 1 << i;
+            }
+        }
 
         // use the shortest header name as default
         let mut name = TE;
@@ -139,6 +299,7 @@ where
                 }
                 UPGRADE => encoding = TransferCoding::upgrade(),
                 DATE => skip_date = true,
+                SERVER => skip_server = true,
                 CONTENT_LENGTH => {
                     debug_assert!(!skip_ct_te, "CONTENT_LENGTH header can not be set");
                     let value = header::parse_content_length(&value)?;
@@ -203,9 +364,19 @@ where
 
         // set date header if there is not any.
         if !skip_date {
-            buf.reserve(D::DATE_VALUE_LENGTH + 12);
-            buf.extend_from_slice(b"\r\ndate: ");
-            self.date().with_date(|slice| buf.extend_from_slice(slice));
+            self.date().write_date_header(buf);
+        }
+
+        if !skip_server {
+            if let Some(server) = response_headers.server {
+                write_header(buf, SERVER.as_str(), server);
+            }
+        }
+
+        for (i, (name, value)) in response_headers.headers.iter().enumerate() {
+            if skip_fixed & (1 << i) == 0 {
+                write_header(buf, name, value);
+            }
         }
 
         buf.extend_from_slice(b"\r\n\r\n");
@@ -214,6 +385,14 @@ where
     }
 }
 
+fn write_header(buf: &mut BytesMut, name: &str, value: &str) {
+    buf.reserve(name.len() + value.len() + 4);
+    buf.extend_from_slice(b"\r\n");
+    buf.extend_from_slice(name.as_bytes());
+    buf.extend_from_slice(b": ");
+    buf.extend_from_slice(value.as_bytes());
+}
+
 const CHUNKED_HEADER: &[u8; 28] = b"\r\ntransfer-encoding: chunked";
 const CLOSE_HEADER: &[u8; 19] = b"\r\nconnection: close";
 
@@ -233,7 +412,8 @@ fn try_remove_body(buf: &mut BytesMut, skip_ct_te: bool, size: BodySize, encodin
         _ => {}
     }
 
-    warn!("response to HEAD request should not bearing body. It will been dropped without polling.");
+    // routing HEAD request to GET service is expected. (see util::service::route)
+    trace!("response to HEAD request should not bearing body. It will been dropped without polling.");
 }
 
 fn write_length_header(buf: &mut BytesMut, size: usize) {
@@ -313,4 +493,181 @@ mod test {
         assert_eq!(header[1].name, "set-cookie");
         assert_eq!(header[1].value, b"bar=bar");
     }
+
+    #[test]
+    fn template() {
+        use crate::http::const_header_value::{TEXT_HTML_UTF8, TEXT_UTF8};
+
+        let encode = |res: Response<BoxBody>, generic: bool| {
+            let mut ctx = Context::<_, 64>::new(&SystemTimeDateTimeHandler);
+            let (mut parts, body) = res.into_parts();
+            let mut buf = BytesMut::new();
+            let encoding = if generic {
+                let size = BodySize::from_stream(&body);
+                encode_version_status_reason(&mut buf, parts.version, parts.status);
+                ctx.encode_headers(&mut parts.headers, size, &mut buf, false).unwrap()
+            } else {
+                ctx.encode_head(parts, &body, &mut buf).unwrap()
+            };
+            (buf, encoding)
+        };
+
+        for (status, value) in [
+            (StatusCode::OK, TEXT_UTF8),
+            (StatusCode::OK, TEXT_HTML_UTF8),
+            (StatusCode::NOT_FOUND, TEXT_UTF8),
+        ] {
+            let res = || {
+                let mut res = Response::new(BoxBody::new(Once::new(Bytes::from_static(b"996"))));
+                *res.status_mut() = status;
+                res.headers_mut().insert(CONTENT_TYPE, value.clone());
+                res
+            };
+
+            // date header is excluded from comparing as it's time sensitive.
+            let head = |buf: &BytesMut| {
+                core::str::from_utf8(buf)
+                    .unwrap()
+                    .lines()
+                    .filter(|line| !line.starts_with("date: "))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+
+            let (buf, encoding) = encode(res(), false);
+            let (buf2, encoding2) = encode(res(), true);
+            assert_eq!(head(&buf), head(&buf2));
+            assert_eq!(encoding, encoding2);
+            assert!(TEMPLATES.iter().any(|(_, _, head)| buf.starts_with(head)));
+        }
+    }
+
+    #[test]
+    fn connect_tunnel() {
+        let mut ctx = Context::<_, 64>::new(&SystemTimeDateTimeHandler);
+
+        let mut buf = BytesMut::from("CONNECT example.com:443 HTTP/1.1\r\nhost: example.com:443\r\n\r\n");
+        let (_, decoder) = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        assert!(decoder.is_upgrade());
+
+        let (parts, body) = Response::new(BoxBody::new(Once::new(Bytes::new()))).into_parts();
+
+        let mut buf = BytesMut::new();
+        let encoder = ctx.encode_head(parts, &body, &mut buf).unwrap();
+        assert!(encoder.is_upgrade());
+
+        let mut header = [httparse::EMPTY_HEADER; 8];
+        let mut res = httparse::Response::new(&mut header);
+
+        let httparse::Status::Complete(_) = res.parse(buf.as_ref()).unwrap() else {
+            panic!("failed to parse response")
+        };
+
+        assert!(header
+            .iter()
+            .all(|h| h.name != "content-length" && h.name != "transfer-encoding"));
+    }
+
+    #[test]
+    fn header_case() {
+        let encode = |case: HeaderCase, map: Option<HeaderCaseMap>| {
+            let mut ctx = Context::<_, 64>::new(&SystemTimeDateTimeHandler);
+            ctx.set_header_case(case);
+
+            let mut res = Response::new(BoxBody::new(Once::new(Bytes::from_static(b"996"))));
+            res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+            if let Some(map) = map {
+                res.extensions_mut().insert(map);
+            }
+
+            let (parts, body) = res.into_parts();
+            let mut buf = BytesMut::new();
+            ctx.encode_head(parts, &body, &mut buf).unwrap();
+
+            let mut header = [httparse::EMPTY_HEADER; 8];
+            let mut res = httparse::Response::new(&mut header);
+            let httparse::Status::Complete(_) = res.parse(buf.as_ref()).unwrap() else {
+                panic!("failed to parse response")
+            };
+            header
+                .iter()
+                .map(|h| h.name.to_owned())
+                .filter(|n| !n.is_empty())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            encode(HeaderCase::Lower, None),
+            ["content-type", "content-length", "date"]
+        );
+        assert_eq!(
+            encode(HeaderCase::Title, None),
+            ["Content-Type", "Content-Length", "Date"]
+        );
+        assert_eq!(
+            encode(HeaderCase::Title, Some(HeaderCaseMap::new().name("CONTENT-type"))),
+            ["CONTENT-type", "Content-Length", "Date"]
+        );
+    }
+
+    #[test]
+    fn response_headers() {
+        let encode = |config: ResponseHeaders, res: Response<BoxBody>| {
+            let mut ctx = Context::<_, 64>::new(&SystemTimeDateTimeHandler);
+            ctx.set_response_headers(config);
+
+            let (parts, body) = res.into_parts();
+            let mut buf = BytesMut::new();
+            ctx.encode_head(parts, &body, &mut buf).unwrap();
+
+            let mut header = [httparse::EMPTY_HEADER; 8];
+            let mut res = httparse::Response::new(&mut header);
+            let httparse::Status::Complete(_) = res.parse(buf.as_ref()).unwrap() else {
+                panic!("failed to parse response")
+            };
+            header
+                .iter()
+                .filter(|h| !h.name.is_empty())
+                .map(|h| (h.name.to_owned(), String::from_utf8(h.value.to_vec()).unwrap()))
+                .filter(|(name, _)| name != "content-length")
+                .collect::<Vec<_>>()
+        };
+
+        let res = || {
+            let mut res = Response::new(BoxBody::new(Once::new(Bytes::from_static(b"996"))));
+            res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+            res
+        };
+
+        let config = ResponseHeaders::new()
+            .disable_date()
+            .server("xitca")
+            .headers(&[("x-frame-options", "DENY"), ("x-content-type-options", "nosniff")]);
+
+        assert_eq!(
+            encode(config, res()),
+            [
+                ("content-type".to_owned(), "text/plain; charset=utf-8".to_owned()),
+                ("server".to_owned(), "xitca".to_owned()),
+                ("x-frame-options".to_owned(), "DENY".to_owned()),
+                ("x-content-type-options".to_owned(), "nosniff".to_owned()),
+            ]
+        );
+
+        // headers set by response take precedence.
+        let mut res = res();
+        res.headers_mut().insert(SERVER, HeaderValue::from_static("foo"));
+        res.headers_mut()
+            .insert("x-frame-options", HeaderValue::from_static("SAMEORIGIN"));
+
+        assert_eq!(
+            encode(config, res),
+            [
+                ("content-type".to_owned(), "text/plain; charset=utf-8".to_owned()),
+                ("server".to_owned(), "foo".to_owned()),
+                ("x-frame-options".to_owned(), "SAMEORIGIN".to_owned()),
+                ("x-content-type-options".to_owned(), "nosniff".to_owned()),
+            ]
+        );
+    }
 }
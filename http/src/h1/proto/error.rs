@@ -1,5 +1,7 @@
 use httparse::Error as HttparseError;
 
+use crate::config::HeaderLimitExceeded;
+
 #[derive(Debug)]
 pub enum ProtoError {
     HeaderName,
@@ -11,6 +13,14 @@ pub enum ProtoError {
     Status,
     Token,
     Version,
+    /// request has both Content-Length and Transfer-Encoding headers.
+    ContentLengthWithTransferEncoding,
+    /// request has multiple Content-Length headers with differing values.
+    ContentLengthMismatch,
+    /// request has Transfer-Encoding header where chunked is not the final coding.
+    TransferEncodingNotChunked,
+    /// request head exceeds limit of [HeaderLimits](crate::config::HeaderLimits).
+    HeaderLimit(HeaderLimitExceeded),
 }
 
 impl From<HttparseError> for ProtoError {
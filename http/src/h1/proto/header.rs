@@ -6,7 +6,7 @@ use httparse::Header;
 
 use super::error::ProtoError;
 
-use crate::http::header::HeaderValue;
+use crate::{config::HeaderCase, http::header::HeaderValue};
 
 #[derive(Clone, Copy)]
 pub struct HeaderIndex {
@@ -37,3 +37,89 @@ pub(super) fn parse_content_length(val: &HeaderValue) -> Result<u64, ProtoError>
         .and_then(|v| v.parse().ok())
         .ok_or(ProtoError::HeaderValue)
 }
+
+/// Exact casing of header names in http/1 response head. Inserted to response extensions and takes precedence
+/// over [HeaderCase] policy of service.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_http::{h1::proto::header::HeaderCaseMap, http::Response};
+/// let mut res = Response::new(());
+/// res.extensions_mut()
+///     .insert(HeaderCaseMap::new().name("WWW-Authenticate").name("X-API-Key"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HeaderCaseMap(Vec<Box<str>>);
+
+impl HeaderCaseMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add header name with it's casing. names are matched case insensitively.
+    pub fn name(mut self, name: &str) -> Self {
+        self.0.retain(|n| !n.eq_ignore_ascii_case(name));
+        self.0.push(Box::from(name));
+        self
+    }
+
+    fn get(&self, name: &[u8]) -> Option<&[u8]> {
+        self.0
+            .iter()
+            .map(|n| n.as_bytes())
+            .find(|n| n.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Rewrite casing of header names of encoded response head in place. The status line is skipped.
+pub(super) fn recase_names(head: &mut [u8], case: HeaderCase, map: Option<&HeaderCaseMap>) {
+    let mut lines = head.split_mut(|b| *b == b'\n').skip(1);
+
+    for line in &mut lines {
+        let Some(colon) = line.iter().position(|b| *b == b':') else {
+            continue;
+        };
+        let name = &mut line[..colon];
+
+        if let Some(cased) = map.and_then(|map| map.get(name)) {
+            name.copy_from_slice(cased);
+            continue;
+        }
+
+        if case == HeaderCase::Title {
+            let mut upper = true;
+            for b in name.iter_mut() {
+                if upper {
+                    b.make_ascii_uppercase();
+                }
+                upper = *b == b'-';
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recase() {
+        let head =
+            b"HTTP/1.1 200 OK\r\ncontent-type: text/plain\r\nwww-authenticate: basic\r\ncontent-length: 0\r\n\r\n";
+
+        let mut buf = head.to_vec();
+        recase_names(&mut buf, HeaderCase::Title, None);
+        assert_eq!(
+            buf,
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nWww-Authenticate: basic\r\nContent-Length: 0\r\n\r\n"
+        );
+
+        let map = HeaderCaseMap::new().name("WWW-Authenticate");
+        let mut buf = head.to_vec();
+        recase_names(&mut buf, HeaderCase::Lower, Some(&map));
+        assert_eq!(
+            buf,
+            b"HTTP/1.1 200 OK\r\ncontent-type: text/plain\r\nWWW-Authenticate: basic\r\ncontent-length: 0\r\n\r\n"
+        );
+    }
+}
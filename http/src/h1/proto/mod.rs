@@ -1,6 +1,7 @@
 //! protocol module of Http/1.x
 //! aiming to be correct and fast with only safe code.
 
+mod client;
 mod decode;
 
 pub mod buf_write;
@@ -1,4 +1,4 @@
-use core::fmt;
+use std::error;
 
 use xitca_service::Service;
 
@@ -6,7 +6,7 @@ use crate::builder::{marker, HttpServiceBuilder};
 
 use super::service::H2Service;
 
-type Error = Box<dyn fmt::Debug>;
+type Error = Box<dyn error::Error + Send + Sync>;
 
 #[cfg(feature = "io-uring")]
 impl<St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIMIT: usize>
@@ -38,15 +38,15 @@ impl<St, FA, S, E, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const
     Service<Result<S, E>> for HttpServiceBuilder<marker::Http2, St, FA, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>
 where
     FA: Service,
-    FA::Error: fmt::Debug + 'static,
-    E: fmt::Debug + 'static,
+    FA::Error: Into<Error>,
+    E: Into<Error>,
 {
     type Response = H2Service<St, S, FA::Response, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>;
     type Error = Error;
 
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
-        let service = res.map_err(|e| Box::new(e) as Error)?;
-        let tls_acceptor = self.tls_factory.call(()).await.map_err(|e| Box::new(e) as Error)?;
+        let service = res.map_err(Into::into)?;
+        let tls_acceptor = self.tls_factory.call(()).await.map_err(Into::into)?;
         Ok(H2Service::new(self.config, service, tls_acceptor))
     }
 }
@@ -57,15 +57,15 @@ impl<St, FA, S, E, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const
     for HttpServiceBuilder<marker::Http2Uring, St, FA, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>
 where
     FA: Service,
-    FA::Error: fmt::Debug + 'static,
-    E: fmt::Debug + 'static,
+    FA::Error: Into<Error>,
+    E: Into<Error>,
 {
     type Response = super::service::H2UringService<S, FA::Response, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>;
     type Error = Error;
 
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
-        let service = res.map_err(|e| Box::new(e) as Error)?;
-        let tls_acceptor = self.tls_factory.call(()).await.map_err(|e| Box::new(e) as Error)?;
+        let service = res.map_err(Into::into)?;
+        let tls_acceptor = self.tls_factory.call(()).await.map_err(Into::into)?;
         Ok(super::service::H2UringService::new(self.config, service, tls_acceptor))
     }
 }
@@ -21,12 +21,13 @@ use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 use crate::{
     body::BodySize,
     bytes::Bytes,
+    config::ResponseHeaders,
     date::{DateTime, DateTimeHandle},
     error::HttpServiceError,
     h2::{body::RequestBody, error::Error},
     http::{
-        header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRAILER},
-        Extension, Request, RequestExt, Response, Version,
+        header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, SERVER, TRAILER},
+        Extension, Method, Request, RequestExt, Response, Version,
     },
     util::{futures::Queue, timer::KeepAlive},
 };
@@ -37,8 +38,11 @@ pub(crate) struct Dispatcher<'a, TlsSt, S, ReqB> {
     addr: SocketAddr,
     keep_alive: Pin<&'a mut KeepAlive>,
     ka_dur: Duration,
+    max_req: usize,
+    fairness_window: usize,
     service: &'a S,
     date: &'a DateTimeHandle,
+    response_headers: ResponseHeaders,
     _req_body: PhantomData<ReqB>,
 }
 
@@ -53,21 +57,28 @@ where
     TlsSt: AsyncRead + AsyncWrite + Unpin,
     ReqB: From<RequestBody>,
 {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         io: &'a mut Connection<TlsSt, Bytes>,
         addr: SocketAddr,
         keep_alive: Pin<&'a mut KeepAlive>,
         ka_dur: Duration,
+        max_req: usize,
+        fairness_window: usize,
         service: &'a S,
         date: &'a DateTimeHandle,
+        response_headers: ResponseHeaders,
     ) -> Self {
         Self {
             io,
             addr,
             keep_alive,
             ka_dur,
+            max_req,
+            fairness_window,
             service,
             date,
+            response_headers,
             _req_body: PhantomData,
         }
     }
@@ -78,8 +89,11 @@ where
             addr,
             mut keep_alive,
             ka_dur,
+            mut max_req,
+            fairness_window,
             service,
             date,
+            response_headers,
             ..
         } = self;
 
@@ -103,6 +117,8 @@ where
         loop {
             match io.accept().select(try_poll_queue(&mut queue, &mut ping_pong)).await {
                 SelectOutput::A(Some(Ok((req, tx)))) => {
+                    let is_head = req.method() == Method::HEAD;
+
                     // Convert http::Request body type to crate::h2::Body
                     // and reconstruct as HttpRequest.
                     let req = req.map(|body| {
@@ -112,8 +128,15 @@ where
 
                     queue.push(async move {
                         let fut = service.call(req);
-                        h2_handler(fut, tx, date).await
+                        h2_handler(fut, tx, date, response_headers, fairness_window, is_head).await
                     });
+
+                    // stop accepting new streams after the last allowed one. in flight streams are
+                    // finished before connection closes.
+                    max_req = max_req.saturating_sub(1);
+                    if max_req == 0 {
+                        io.graceful_shutdown();
+                    }
                 }
                 SelectOutput::B(SelectOutput::A(_)) => io.graceful_shutdown(),
                 SelectOutput::B(SelectOutput::B(Ok(_))) => {
@@ -218,6 +241,9 @@ async fn h2_handler<Fut, B, SE, BE>(
     fut: Fut,
     mut tx: SendResponse<Bytes>,
     date: &DateTimeHandle,
+    response_headers: ResponseHeaders,
+    fairness_window: usize,
+    is_head: bool,
 ) -> Result<ConnectionState, Error<SE, BE>>
 where
     Fut: Future<Output = Result<Response<B>, SE>>,
@@ -250,6 +276,9 @@ where
         }
     };
 
+    // response to HEAD request has no body. it's dropped without polling while content-length header is kept.
+    let is_eof = is_eof || is_head;
+
     let mut trailers = HeaderMap::with_capacity(0);
 
     while let Some(value) = res.headers_mut().remove(TRAILER) {
@@ -258,11 +287,24 @@ where
         trailers.append(name, value);
     }
 
-    if !res.headers().contains_key(DATE) {
+    if response_headers.date && !res.headers().contains_key(DATE) {
         let date = date.with_date(HeaderValue::from_bytes).unwrap();
         res.headers_mut().insert(DATE, date);
     }
 
+    if let Some(server) = response_headers.server {
+        if !res.headers().contains_key(SERVER) {
+            res.headers_mut().insert(SERVER, HeaderValue::from_static(server));
+        }
+    }
+
+    for &(name, value) in response_headers.headers {
+        if !res.headers().contains_key(name) {
+            res.headers_mut()
+                .insert(HeaderName::from_static(name), HeaderValue::from_static(value));
+        }
+    }
+
     // check response header to determine if user want connection be closed.
     let state = res
         .headers_mut()
@@ -280,13 +322,30 @@ where
     if !is_eof {
         let mut body = pin!(body);
 
+        // remaining bytes current stream can send before yielding to other streams.
+        let mut budget = fairness_window;
+
         while let Some(res) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
             let mut chunk = res.map_err(Error::Body)?;
 
+            // empty chunk is a flush hint. yield to connection so queued data frames are written to io before
+            // polling body again.
+            if chunk.is_empty() {
+                yield_now().await;
+                continue;
+            }
+
             while !chunk.is_empty() {
+                if budget == 0 {
+                    yield_now().await;
+                    budget = fairness_window;
+                }
+
                 let len = chunk.len();
 
-                stream.reserve_capacity(cmp::min(len, CHUNK_SIZE));
+                // reserve no more than the budget so connection level flow control window is not hogged by
+                // current stream.
+                stream.reserve_capacity(cmp::min(cmp::min(len, CHUNK_SIZE), budget));
 
                 let cap = poll_fn(|cx| stream.poll_capacity(cx))
                     .await
@@ -295,6 +354,8 @@ where
                 // Split chuck to writeable size and send to client.
                 let bytes = chunk.split_to(cmp::min(cap, len));
 
+                budget = budget.saturating_sub(bytes.len());
+
                 stream.send_data(bytes, false)?;
             }
         }
@@ -306,3 +367,18 @@ where
 }
 
 const CHUNK_SIZE: usize = 16_384;
+
+// give other futures polled by dispatcher a chance to make progress.
+async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
@@ -71,8 +71,11 @@ where
             addr,
             timer,
             self.config.keep_alive_timeout,
+            self.config.max_requests_per_connection,
+            self.config.h2_fairness_window,
             &self.service,
             self.date.get(),
+            self.config.response_headers,
         );
 
         dispatcher.run().await?;
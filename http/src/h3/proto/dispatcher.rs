@@ -19,7 +19,7 @@ use crate::{
     bytes::Bytes,
     error::HttpServiceError,
     h3::{body::RequestBody, error::Error},
-    http::{Extension, Request, RequestExt, Response},
+    http::{Extension, Method, Request, RequestExt, Response},
     util::futures::Queue,
 };
 
@@ -66,6 +66,8 @@ where
                 SelectOutput::A(Ok(Some((req, stream)))) => {
                     let (tx, rx) = stream.split();
 
+                    let is_head = req.method() == Method::HEAD;
+
                     // Reconstruct Request to attach crate body type.
                     let req = req.map(|_| {
                         let body = ReqB::from(RequestBody(rx));
@@ -74,7 +76,7 @@ where
 
                     queue.push(async move {
                         let fut = self.service.call(req);
-                        h3_handler(fut, tx).await
+                        h3_handler(fut, tx, is_head).await
                     });
                 }
                 SelectOutput::A(Ok(None)) => break,
@@ -96,6 +98,7 @@ where
 async fn h3_handler<'a, Fut, C, ResB, SE, BE>(
     fut: Fut,
     mut stream: RequestStream<C, Bytes>,
+    is_head: bool,
 ) -> Result<(), Error<SE, BE>>
 where
     Fut: Future<Output = Result<Response<ResB>, SE>> + 'a,
@@ -106,11 +109,14 @@ where
     let res = Response::from_parts(parts, ());
     stream.send_response(res).await?;
 
-    let mut body = pin!(body);
+    // response to HEAD request has no body. it's dropped without polling.
+    if !is_head {
+        let mut body = pin!(body);
 
-    while let Some(res) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
-        let bytes = res.map_err(Error::Body)?;
-        stream.send_data(bytes).await?;
+        while let Some(res) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            let bytes = res.map_err(Error::Body)?;
+            stream.send_data(bytes).await?;
+        }
     }
 
     stream.finish().await?;
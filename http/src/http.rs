@@ -53,6 +53,8 @@ pub mod const_header_name {
     const_name!((PROTOCOL, "protocol"));
 }
 
+pub mod intern;
+
 /// helper trait for converting a [Request] to [Response].
 /// This is a memory optimization for re-use heap allocation and pass down the context data
 /// inside [Extensions] from request to response.
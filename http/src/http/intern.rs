@@ -0,0 +1,165 @@
+//! interning of frequently repeated header values.
+//!
+//! Well known values (common content types, connection tokens, encodings, etc) are resolved to
+//! [HeaderValue::from_static] without allocation. Other values are copied into [Bytes] once and cached in a
+//! bounded thread local table. Cloning a cached value is a reference count bump of the shared [Bytes] so
+//! repeated values of responses produced on the same thread do not allocate.
+//!
+//! # Examples
+//! ```rust
+//! use xitca_http::http::{header::CONTENT_LANGUAGE, intern, Response};
+//!
+//! let mut res = Response::new(());
+//!
+//! // "text/plain; charset=utf-8" is a well known value and not allocated.
+//! res.headers_mut().insert("content-type", intern::intern("text/plain; charset=utf-8").unwrap());
+//! // "zh-CN" is allocated once and shared by every response on current thread afterwards.
+//! res.headers_mut().insert(CONTENT_LANGUAGE, intern::intern("zh-CN").unwrap());
+//! ```
+
+use core::cell::RefCell;
+
+use std::collections::HashMap;
+
+use crate::bytes::Bytes;
+
+use super::header::{HeaderValue, InvalidHeaderValue};
+
+/// max count of values cached on a thread. values beyond the limit are allocated on every call.
+pub const MAX_ENTRIES: usize = 256;
+
+/// max byte length of value that can be cached. longer values are treated as unique and allocated on every call.
+pub const MAX_VALUE_LEN: usize = 128;
+
+/// resolve given str to header value. see [module](self) level doc for detail.
+///
+/// # Errors
+/// when given str contains bytes not allowed in header value.
+pub fn intern(value: &str) -> Result<HeaderValue, InvalidHeaderValue> {
+    if let Some(value) = well_known(value) {
+        return Ok(value);
+    }
+
+    if value.len() > MAX_VALUE_LEN {
+        return HeaderValue::from_str(value);
+    }
+
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+
+        if let Some(value) = cache.get(value) {
+            return Ok(value.clone());
+        }
+
+        let val = HeaderValue::from_maybe_shared(Bytes::copy_from_slice(value.as_bytes()))?;
+
+        if cache.len() < MAX_ENTRIES {
+            cache.insert(Box::from(value), val.clone());
+        }
+
+        Ok(val)
+    })
+}
+
+thread_local! {
+    static CACHE: RefCell<HashMap<Box<str>, HeaderValue>> = RefCell::new(HashMap::new());
+}
+
+macro_rules! well_known {
+    ($($value: literal),* $(,)?) => {
+        fn well_known(value: &str) -> Option<HeaderValue> {
+            match value {
+                $($value => Some(HeaderValue::from_static($value)),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+well_known!(
+    // content type.
+    "text/plain",
+    "text/plain; charset=utf-8",
+    "text/html",
+    "text/html; charset=utf-8",
+    "text/css",
+    "text/css; charset=utf-8",
+    "text/csv",
+    "text/csv; charset=utf-8",
+    "text/javascript",
+    "text/javascript; charset=utf-8",
+    "text/event-stream",
+    "text/xml",
+    "application/json",
+    "application/json; charset=utf-8",
+    "application/javascript",
+    "application/xml",
+    "application/x-www-form-urlencoded",
+    "application/octet-stream",
+    "application/pdf",
+    "application/wasm",
+    "application/grpc",
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/svg+xml",
+    "image/x-icon",
+    "multipart/form-data",
+    // connection and transfer tokens.
+    "close",
+    "keep-alive",
+    "upgrade",
+    "websocket",
+    "chunked",
+    "100-continue",
+    // content encoding.
+    "gzip",
+    "br",
+    "deflate",
+    "zstd",
+    "identity",
+    // misc.
+    "bytes",
+    "none",
+    "no-cache",
+    "no-store",
+    "nosniff",
+    "*",
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn well_known_value() {
+        let value = intern("application/json").unwrap();
+        assert_eq!(value, "application/json");
+        assert!(CACHE.with(|cache| cache.borrow().is_empty()));
+    }
+
+    #[test]
+    fn shared() {
+        let a = intern("zh-CN").unwrap();
+        let b = intern("zh-CN").unwrap();
+        assert_eq!(a, "zh-CN");
+        assert_eq!(a.as_bytes().as_ptr(), b.as_bytes().as_ptr());
+
+        let long = "a".repeat(MAX_VALUE_LEN + 1);
+        let a = intern(&long).unwrap();
+        let b = intern(&long).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a.as_bytes().as_ptr(), b.as_bytes().as_ptr());
+
+        assert!(intern("foo\r\nbar").is_err());
+    }
+
+    #[test]
+    fn bounded() {
+        for i in 0..MAX_ENTRIES + 8 {
+            intern(&i.to_string()).unwrap();
+        }
+        assert_eq!(CACHE.with(|cache| cache.borrow().len()), MAX_ENTRIES);
+    }
+}
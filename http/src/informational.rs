@@ -0,0 +1,137 @@
+//! request scoped side channel for sending informational(1xx) responses ahead of final response.
+//!
+//! When enabled with [HttpServiceConfig::informational_responses] an [Informational] is inserted into request
+//! extensions of http/1.1 request by http/1 dispatcher. Service can send interim responses like
+//! `102 Processing` and `103 Early Hints` through it while processing the request and the dispatcher encodes them
+//! to connection in order before the final response. Informational response sent after final response is
+//! produced is dropped.
+//!
+//! `100 Continue` is managed by dispatcher according to request's `Expect` header and `101 Switching Protocols`
+//! is a final response for upgrading connection. Neither of them can be sent through [Informational].
+//!
+//! # Examples
+//! ```rust
+//! use std::convert::Infallible;
+//!
+//! use xitca_http::{
+//!     http::{header::LINK, HeaderValue, Request, RequestExt, Response, StatusCode},
+//!     informational::Informational,
+//! };
+//!
+//! async fn handler(req: Request<RequestExt<()>>) -> Result<Response<&'static str>, Infallible> {
+//!     // handle is absent when it's not enabled or protocol is not http/1.1.
+//!     let info = req.extensions().get::<Informational>().cloned().unwrap_or_default();
+//!
+//!     // hint client to preload resource while final response is being produced.
+//!     let mut hints = Response::new(());
+//!     *hints.status_mut() = StatusCode::from_u16(103).unwrap();
+//!     hints
+//!         .headers_mut()
+//!         .insert(LINK, HeaderValue::from_static("</style.css>; rel=preload; as=style"));
+//!     info.send(hints);
+//!
+//!     // expensive work.
+//!
+//!     Ok(Response::new("done"))
+//! }
+//! ```
+//!
+//! [HttpServiceConfig::informational_responses]: crate::config::HttpServiceConfig::informational_responses
+
+use core::fmt;
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::http::{Response, StatusCode};
+
+/// shared handle of informational response side channel. See [module](self) level doc for detail.
+///
+/// [Informational::default] constructs a handle that drops every response sent through it.
+#[derive(Clone, Default)]
+pub struct Informational(Option<UnboundedSender<Response<()>>>);
+
+impl Informational {
+    pub(crate) fn channel() -> (Self, InformationalReceiver) {
+        let (tx, rx) = unbounded_channel();
+        (Self(Some(tx)), InformationalReceiver(rx))
+    }
+
+    /// send informational response to client ahead of final response.
+    ///
+    /// Return false when the response is dropped. This happens when:
+    /// - status code of response is not in 102..=199 range.
+    /// - handle is not enabled or protocol does not support it.
+    /// - final response of request is already produced.
+    pub fn send(&self, res: Response<()>) -> bool {
+        if !is_informational(res.status()) {
+            return false;
+        }
+        self.0.as_ref().is_some_and(|tx| tx.send(res).is_ok())
+    }
+
+    /// check if handle is connected to a dispatcher that accepts informational response.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.0.as_ref().is_some_and(|tx| !tx.is_closed())
+    }
+}
+
+impl fmt::Debug for Informational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Informational")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+pub(crate) struct InformationalReceiver(UnboundedReceiver<Response<()>>);
+
+impl InformationalReceiver {
+    pub(crate) async fn recv(&mut self) -> Response<()> {
+        match self.0.recv().await {
+            Some(res) => res,
+            // every handle is dropped and no more response would be sent.
+            None => core::future::pending().await,
+        }
+    }
+
+    pub(crate) fn try_recv(&mut self) -> Option<Response<()>> {
+        self.0.try_recv().ok()
+    }
+}
+
+fn is_informational(status: StatusCode) -> bool {
+    status.is_informational() && status != StatusCode::CONTINUE && status != StatusCode::SWITCHING_PROTOCOLS
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn send() {
+        fn res(status: StatusCode) -> Response<()> {
+            let mut res = Response::new(());
+            *res.status_mut() = status;
+            res
+        }
+
+        let info = Informational::default();
+        assert!(!info.is_enabled());
+        assert!(!info.send(res(StatusCode::PROCESSING)));
+
+        let (info, mut rx) = Informational::channel();
+        assert!(info.is_enabled());
+        assert!(info.send(res(StatusCode::PROCESSING)));
+        assert_eq!(rx.try_recv().unwrap().status(), StatusCode::PROCESSING);
+
+        for status in [StatusCode::CONTINUE, StatusCode::SWITCHING_PROTOCOLS, StatusCode::OK] {
+            assert!(!info.send(res(status)));
+        }
+        assert!(rx.try_recv().is_none());
+
+        drop(rx);
+        assert!(!info.is_enabled());
+        assert!(!info.send(res(StatusCode::PROCESSING)));
+    }
+}
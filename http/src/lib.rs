@@ -53,12 +53,22 @@ pub mod util;
 
 #[cfg(feature = "runtime")]
 pub mod date;
+#[cfg(feature = "runtime")]
+pub mod disconnect;
 #[cfg(feature = "http1")]
 pub mod h1;
 #[cfg(feature = "http2")]
 pub mod h2;
 #[cfg(feature = "http3")]
 pub mod h3;
+#[cfg(feature = "runtime")]
+pub mod informational;
+#[cfg(feature = "runtime")]
+pub mod timing;
+#[cfg(feature = "runtime")]
+pub mod upgrade;
+#[cfg(feature = "ws")]
+pub mod ws;
 
 /// re-export bytes crate as module.
 pub use xitca_io::bytes;
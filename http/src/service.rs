@@ -140,8 +140,11 @@ where
                             _addr,
                             timer.as_mut(),
                             self.config.keep_alive_timeout,
+                            self.config.max_requests_per_connection,
+                            self.config.h2_fairness_window,
                             &self.service,
                             self.date.get(),
+                            self.config.response_headers,
                         )
                         .run()
                         .await
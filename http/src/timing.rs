@@ -0,0 +1,213 @@
+//! per request timing and body size instrumentation.
+//!
+//! When enabled with [HttpServiceConfig::request_timing] a [RequestTiming] is inserted into request extensions by
+//! http/1 dispatcher. It's a shared handle updated by dispatcher while the request is processed so logging and
+//! metrics middlewares can report accurate phase timings without wrapping request and response body themselves.
+//! A clone of it can be moved into response extensions by middleware for consumers of response.
+//!
+//! Phases recorded:
+//! - request read: from the time bytes of request head are read from io until request body is fully read.
+//! - handler: from the time request head is decoded until service returns response.
+//! - response write: from the time service returns response until response body is fully written to write
+//!   buffer.
+//!
+//! Phases finished after middleware returns response(response write and possibly request read when body is
+//! not consumed by service) must be observed after response is sent. e.g: from a [Drop] implementation of
+//! response body or a deferred task holding a clone of [RequestTiming].
+//!
+//! # Examples
+//! ```rust
+//! use std::convert::Infallible;
+//!
+//! use xitca_http::{
+//!     http::{Request, RequestExt, Response},
+//!     timing::RequestTiming,
+//! };
+//! use xitca_service::{fn_service, Service, ServiceExt};
+//!
+//! async fn handler(_: Request<RequestExt<()>>) -> Result<Response<()>, Infallible> {
+//!     Ok(Response::new(()))
+//! }
+//!
+//! // a middleware function reading timing from request extensions.
+//! async fn middleware<S>(service: &S, req: Request<RequestExt<()>>) -> Result<Response<()>, Infallible>
+//! where
+//!     S: Service<Request<RequestExt<()>>, Response = Response<()>, Error = Infallible>,
+//! {
+//!     let timing = req.extensions().get::<RequestTiming>().cloned();
+//!     let mut res = service.call(req).await?;
+//!     if let Some(timing) = timing {
+//!         // handler phase is finished after dispatcher receives response from service. elapsed time of
+//!         // middleware call is a close approximation.
+//!         println!("request body bytes read: {}", timing.request_body_bytes());
+//!         // attach timing to response for later consumers.
+//!         res.extensions_mut().insert(timing);
+//!     }
+//!     Ok(res)
+//! }
+//!
+//! let service = fn_service(handler).enclosed_fn(middleware);
+//! ```
+//!
+//! [HttpServiceConfig::request_timing]: crate::config::HttpServiceConfig::request_timing
+
+use core::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use std::{sync::Arc, time::Instant};
+
+/// shared handle of per request timing and body size. See [module](self) level doc for detail.
+#[derive(Clone)]
+pub struct RequestTiming(Arc<Inner>);
+
+struct Inner {
+    start: Instant,
+    head_decoded: AtomicU64,
+    body_read: AtomicU64,
+    handler_done: AtomicU64,
+    body_written: AtomicU64,
+    request_body_bytes: AtomicU64,
+    response_body_bytes: AtomicU64,
+}
+
+// sentinel value of unrecorded time point.
+const UNSET: u64 = u64::MAX;
+
+impl RequestTiming {
+    pub(crate) fn new(start: Instant) -> Self {
+        Self(Arc::new(Inner {
+            start,
+            head_decoded: AtomicU64::new(UNSET),
+            body_read: AtomicU64::new(UNSET),
+            handler_done: AtomicU64::new(UNSET),
+            body_written: AtomicU64::new(UNSET),
+            request_body_bytes: AtomicU64::new(0),
+            response_body_bytes: AtomicU64::new(0),
+        }))
+    }
+
+    /// duration of reading request. from the time bytes of request head are read from io until request body is
+    /// fully read.
+    /// `None` when request body is not fully read yet.
+    pub fn request_read(&self) -> Option<Duration> {
+        self.load(&self.0.body_read).map(Duration::from_nanos)
+    }
+
+    /// duration of service handling request. from the time request head is decoded until service returns
+    /// response. `None` when service is not finished yet.
+    pub fn handler(&self) -> Option<Duration> {
+        self.span(&self.0.head_decoded, &self.0.handler_done)
+    }
+
+    /// duration of writing response. from the time service returns response until response body is fully
+    /// written to write buffer. `None` when response is not fully written yet.
+    pub fn response_write(&self) -> Option<Duration> {
+        self.span(&self.0.handler_done, &self.0.body_written)
+    }
+
+    /// number of request body bytes read so far.
+    pub fn request_body_bytes(&self) -> u64 {
+        self.0.request_body_bytes.load(Ordering::Relaxed)
+    }
+
+    /// number of response body bytes written so far.
+    pub fn response_body_bytes(&self) -> u64 {
+        self.0.response_body_bytes.load(Ordering::Relaxed)
+    }
+
+    /// check if response body is fully written.
+    pub fn is_complete(&self) -> bool {
+        self.load(&self.0.body_written).is_some()
+    }
+
+    pub(crate) fn head_decoded(&self) {
+        self.mark(&self.0.head_decoded);
+    }
+
+    pub(crate) fn body_read(&self) {
+        self.mark(&self.0.body_read);
+    }
+
+    pub(crate) fn handler_done(&self) {
+        self.mark(&self.0.handler_done);
+    }
+
+    pub(crate) fn body_written(&self) {
+        self.mark(&self.0.body_written);
+    }
+
+    pub(crate) fn add_request_body_bytes(&self, n: usize) {
+        self.0.request_body_bytes.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_response_body_bytes(&self, n: usize) {
+        self.0.response_body_bytes.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    // record time point as nanos elapsed from start. time point can only be recorded once.
+    fn mark(&self, point: &AtomicU64) {
+        let nanos = u64::try_from(self.0.start.elapsed().as_nanos()).unwrap_or(UNSET - 1);
+        let _ = point.compare_exchange(UNSET, nanos, Ordering::Relaxed, Ordering::Relaxed);
+    }
+
+    fn load(&self, point: &AtomicU64) -> Option<u64> {
+        match point.load(Ordering::Relaxed) {
+            UNSET => None,
+            nanos => Some(nanos),
+        }
+    }
+
+    fn span(&self, from: &AtomicU64, to: &AtomicU64) -> Option<Duration> {
+        let from = self.load(from)?;
+        let to = self.load(to)?;
+        Some(Duration::from_nanos(to.saturating_sub(from)))
+    }
+}
+
+impl fmt::Debug for RequestTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestTiming")
+            .field("request_read", &self.request_read())
+            .field("handler", &self.handler())
+            .field("response_write", &self.response_write())
+            .field("request_body_bytes", &self.request_body_bytes())
+            .field("response_body_bytes", &self.response_body_bytes())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn phases() {
+        let timing = RequestTiming::new(Instant::now());
+        assert!(timing.handler().is_none());
+        assert!(timing.response_write().is_none());
+
+        timing.head_decoded();
+        timing.add_request_body_bytes(3);
+        timing.body_read();
+        timing.handler_done();
+        assert!(timing.handler().is_some());
+        assert!(timing.request_read().is_some());
+        assert!(!timing.is_complete());
+
+        let handler = timing.handler();
+        // time point is only recorded once.
+        timing.handler_done();
+        assert_eq!(timing.handler(), handler);
+
+        timing.add_response_body_bytes(5);
+        timing.add_response_body_bytes(5);
+        timing.body_written();
+        assert!(timing.is_complete());
+        assert!(timing.response_write().is_some());
+        assert_eq!(timing.request_body_bytes(), 3);
+        assert_eq!(timing.response_body_bytes(), 10);
+    }
+}
@@ -0,0 +1,396 @@
+//! connection upgrade for tunneling arbitrary protocols.
+//!
+//! After responding with `101 Switching Protocols` to upgrade request or `2xx` status code to `CONNECT` request
+//! the request body becomes the raw bytes sent by client and the response body is written to client as is.
+//! [upgrade] bridges these two halves into a single [UpgradeIo] type implementing [AsyncIo] and
+//! [AsyncRead]/[AsyncWrite] traits so it can be used like the underlying connection.
+//!
+//! [AsyncIo] is only implemented when request body type is thread safe. (e.g. http/2 request body)
+//!
+//! # Examples
+//! ```rust
+//! use std::{convert::Infallible, future::poll_fn, pin::Pin};
+//!
+//! use xitca_http::{
+//!     body::ResponseBody,
+//!     h1::RequestBody,
+//!     http::{Method, Request, RequestExt, Response, StatusCode},
+//!     upgrade::upgrade,
+//! };
+//! use xitca_io::io::{AsyncRead, AsyncWrite, ReadBuf};
+//!
+//! async fn service(req: Request<RequestExt<RequestBody>>) -> Result<Response<ResponseBody>, Infallible> {
+//!     if req.method() != Method::CONNECT {
+//!         let mut res = Response::new(ResponseBody::none());
+//!         *res.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+//!         return Ok(res);
+//!     }
+//!
+//!     let (mut io, body) = upgrade(req.into_body());
+//!
+//!     // take over the connection in a separate task and echo bytes back to client. io can be used with any
+//!     // protocol implementation built on top of AsyncRead/AsyncWrite traits.
+//!     tokio::task::spawn_local(async move {
+//!         let mut buf = [0; 1024];
+//!         loop {
+//!             let mut read_buf = ReadBuf::new(&mut buf);
+//!             match poll_fn(|cx| Pin::new(&mut io).poll_read(cx, &mut read_buf)).await {
+//!                 Ok(_) if !read_buf.filled().is_empty() => {}
+//!                 _ => break,
+//!             }
+//!             let n = read_buf.filled().len();
+//!             if poll_fn(|cx| Pin::new(&mut io).poll_write(cx, &buf[..n])).await.is_err() {
+//!                 break;
+//!             }
+//!         }
+//!     });
+//!
+//!     // response body must be returned for bytes written to io to be sent to client.
+//!     Ok(Response::new(ResponseBody::box_stream(body)))
+//! }
+//! ```
+
+use core::{
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::{ready, Context, Poll, Waker},
+};
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use futures_core::stream::Stream;
+use xitca_io::io::{AsyncIo, AsyncRead, AsyncWrite, Interest, ReadBuf, Ready};
+
+use crate::{
+    bytes::{Buf, Bytes, BytesMut},
+    error::BodyError,
+};
+
+// max bytes buffered by UpgradeIo before response body takes them.
+const WRITE_BUF_LIMIT: usize = 64 * 1024;
+
+/// split request body of upgraded connection into [UpgradeIo] and [UpgradeBody].
+///
+/// [UpgradeBody] must be used as response body. It ends when [UpgradeIo] is shutdown or dropped.
+pub fn upgrade<B, E>(body: B) -> (UpgradeIo<B>, UpgradeBody)
+where
+    B: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: Into<BodyError>,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        buf: BytesMut::new(),
+        closed: false,
+        dropped: false,
+        read_waker: None,
+        write_waker: None,
+    }));
+
+    let io = UpgradeIo {
+        body,
+        read_buf: Bytes::new(),
+        read_state: ReadState::Open,
+        shared: shared.clone(),
+    };
+
+    (io, UpgradeBody { shared })
+}
+
+struct Shared {
+    // bytes written by UpgradeIo and not yet taken by UpgradeBody.
+    buf: BytesMut,
+    // write half of UpgradeIo is shutdown.
+    closed: bool,
+    // UpgradeBody is dropped and no more bytes can be sent to client.
+    dropped: bool,
+    read_waker: Option<Waker>,
+    write_waker: Option<Waker>,
+}
+
+enum ReadState {
+    Open,
+    Eof,
+    Error(io::Error),
+}
+
+/// IO type of upgraded connection. See [module](self) level doc for detail.
+pub struct UpgradeIo<B> {
+    body: B,
+    read_buf: Bytes,
+    read_state: ReadState,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl<B, E> UpgradeIo<B>
+where
+    B: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: Into<BodyError>,
+{
+    fn poll_read_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        while self.read_buf.is_empty() && matches!(self.read_state, ReadState::Open) {
+            match ready!(Pin::new(&mut self.body).poll_next(cx)) {
+                Some(Ok(bytes)) => self.read_buf = bytes,
+                Some(Err(e)) => self.read_state = ReadState::Error(io::Error::other(e.into())),
+                None => self.read_state = ReadState::Eof,
+            }
+        }
+        Poll::Ready(())
+    }
+
+    fn poll_write_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut shared = self.shared.lock().unwrap();
+        if shared.dropped || shared.closed || shared.buf.len() < WRITE_BUF_LIMIT {
+            Poll::Ready(())
+        } else {
+            shared.write_waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    fn shutdown(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.closed = true;
+        if let Some(waker) = shared.read_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<B> io::Read for UpgradeIo<B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.read_buf.is_empty() {
+            let len = buf.len().min(self.read_buf.len());
+            buf[..len].copy_from_slice(&self.read_buf[..len]);
+            self.read_buf.advance(len);
+            return Ok(len);
+        }
+
+        match self.read_state {
+            ReadState::Open => Err(io::ErrorKind::WouldBlock.into()),
+            ReadState::Eof => Ok(0),
+            ReadState::Error(_) => match std::mem::replace(&mut self.read_state, ReadState::Eof) {
+                ReadState::Error(e) => Err(e),
+                _ => unreachable!(),
+            },
+        }
+    }
+}
+
+impl<B> io::Write for UpgradeIo<B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if shared.dropped || shared.closed {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+
+        let len = buf.len().min(WRITE_BUF_LIMIT.saturating_sub(shared.buf.len()));
+        if len == 0 && !buf.is_empty() {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+
+        shared.buf.extend_from_slice(&buf[..len]);
+        if let Some(waker) = shared.read_waker.take() {
+            waker.wake();
+        }
+
+        Ok(len)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<B, E> AsyncIo for UpgradeIo<B>
+where
+    B: Stream<Item = Result<Bytes, E>> + Send + Unpin,
+    E: Into<BodyError>,
+{
+    #[inline]
+    fn ready(&mut self, interest: Interest) -> impl Future<Output = io::Result<Ready>> + Send {
+        poll_fn(move |cx| self.poll_ready(interest, cx))
+    }
+
+    fn poll_ready(&mut self, interest: Interest, cx: &mut Context<'_>) -> Poll<io::Result<Ready>> {
+        let mut ready = Ready::EMPTY;
+
+        if interest.is_readable() && self.poll_read_ready(cx).is_ready() {
+            ready |= Ready::READABLE;
+        }
+
+        if interest.is_writable() && self.poll_write_ready(cx).is_ready() {
+            ready |= Ready::WRITABLE;
+        }
+
+        if ready.is_empty() {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(ready))
+        }
+    }
+
+    #[inline]
+    fn is_vectored_write(&self) -> bool {
+        false
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().shutdown();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<B, E> AsyncRead for UpgradeIo<B>
+where
+    B: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: Into<BodyError>,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_read_ready(cx));
+        let n = io::Read::read(this, buf.initialize_unfilled())?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<B, E> AsyncWrite for UpgradeIo<B>
+where
+    B: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: Into<BodyError>,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_ready(cx));
+        Poll::Ready(io::Write::write(this, buf))
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().shutdown();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<B> Drop for UpgradeIo<B> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.closed = true;
+        if let Some(waker) = shared.read_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// response body of upgraded connection producing bytes written to [UpgradeIo].
+pub struct UpgradeBody {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Stream for UpgradeBody {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if !shared.buf.is_empty() {
+            let bytes = shared.buf.split().freeze();
+            if let Some(waker) = shared.write_waker.take() {
+                waker.wake();
+            }
+            return Poll::Ready(Some(Ok(bytes)));
+        }
+
+        if shared.closed {
+            return Poll::Ready(None);
+        }
+
+        shared.read_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for UpgradeBody {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.dropped = true;
+        if let Some(waker) = shared.write_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::convert::Infallible;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    struct Iter<I>(I);
+
+    impl<I> Stream for Iter<I>
+    where
+        I: Iterator<Item = Result<Bytes, Infallible>> + Unpin,
+    {
+        type Item = I::Item;
+
+        fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.get_mut().0.next())
+        }
+    }
+
+    #[test]
+    fn upgrade_io() {
+        let body = Iter([Ok(Bytes::from_static(b"hello")), Ok(Bytes::from_static(b"world"))].into_iter());
+        let (mut io, mut res_body) = upgrade(body);
+
+        let mut buf = [0; 8];
+        let ready = io
+            .ready(Interest::READABLE | Interest::WRITABLE)
+            .now_or_panic()
+            .unwrap();
+        assert!(ready.is_readable() && ready.is_writable());
+
+        assert_eq!(io::Read::read(&mut io, &mut buf).unwrap(), 5);
+        assert_eq!(&buf[..5], b"hello");
+        io.ready(Interest::READABLE).now_or_panic().unwrap();
+        assert_eq!(io::Read::read(&mut io, &mut buf).unwrap(), 5);
+        assert_eq!(&buf[..5], b"world");
+        io.ready(Interest::READABLE).now_or_panic().unwrap();
+        assert_eq!(io::Read::read(&mut io, &mut buf).unwrap(), 0);
+
+        assert_eq!(io::Write::write(&mut io, b"996").unwrap(), 3);
+        let bytes = poll_fn(|cx| Pin::new(&mut res_body).poll_next(cx))
+            .now_or_panic()
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes, "996");
+
+        let big = vec![0; WRITE_BUF_LIMIT + 1];
+        assert_eq!(io::Write::write(&mut io, &big).unwrap(), WRITE_BUF_LIMIT);
+        assert_eq!(
+            io::Write::write(&mut io, &big).unwrap_err().kind(),
+            io::ErrorKind::WouldBlock
+        );
+
+        drop(io);
+        let bytes = poll_fn(|cx| Pin::new(&mut res_body).poll_next(cx))
+            .now_or_panic()
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes.len(), WRITE_BUF_LIMIT);
+        assert!(poll_fn(|cx| Pin::new(&mut res_body).poll_next(cx))
+            .now_or_panic()
+            .is_none());
+    }
+}
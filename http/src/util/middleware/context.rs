@@ -1,6 +1,8 @@
 //! middleware for adding typed state to service request.
 
-use core::{fmt, future::Future};
+use core::future::Future;
+
+use std::error;
 
 use xitca_service::Service;
 
@@ -96,22 +98,22 @@ where
     }
 }
 
-type Error = Box<dyn fmt::Debug>;
+type Error = Box<dyn error::Error + Send + Sync>;
 
 impl<CF, Fut, C, CErr, S, E> Service<Result<S, E>> for ContextBuilder<CF>
 where
     CF: Fn() -> Fut,
     Fut: Future<Output = Result<C, CErr>>,
     C: 'static,
-    CErr: fmt::Debug + 'static,
-    E: fmt::Debug + 'static,
+    CErr: Into<Error>,
+    E: Into<Error>,
 {
     type Response = service::ContextService<C, S>;
     type Error = Error;
 
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
-        let service = res.map_err(|e| Box::new(e) as Error)?;
-        let state = (self.builder)().await.map_err(|e| Box::new(e) as Error)?;
+        let service = res.map_err(Into::into)?;
+        let state = (self.builder)().await.map_err(Into::into)?;
         Ok(service::ContextService { service, state })
     }
 }
@@ -1,4 +1,9 @@
 //! routing [`Service`] with given [`Method`] that support a wide range of http method including custom ones.
+//!
+//! # Automatic HEAD handling
+//! [`Method::HEAD`] request is routed to the [`Service`] of [`Method::GET`] when there is no explicit HEAD
+//! route. Response body is dropped by dispatcher while `content-length` header is kept. Register a route with
+//! [`head`] to override it.
 
 use core::{fmt, marker::PhantomData};
 
@@ -140,7 +145,7 @@ pub struct RouteService<R, N, const M: usize> {
 impl<R, N, Req, E, const M: usize> Service<Req> for RouteService<R, N, M>
 where
     R: Service<Req, Error = E>,
-    N: Service<Req, Response = R::Response, Error = RouterError<E>>,
+    N: Service<Req, Response = R::Response, Error = RouterError<E>> + RouteMethods,
     Req: BorrowReq<Method>,
 {
     type Response = R::Response;
@@ -148,7 +153,8 @@ where
 
     #[inline]
     async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
-        if self.methods.contains(req.borrow()) {
+        let method = req.borrow();
+        if self.methods.contains(method) || self.is_head_fallback(method) {
             self.route.call(req).await.map_err(RouterError::Service)
         } else {
             self.next
@@ -159,15 +165,54 @@ where
     }
 }
 
+impl<R, N, const M: usize> RouteService<R, N, M>
+where
+    N: RouteMethods,
+{
+    // HEAD request falls back to GET route when no route in the chain handles it explicitly.
+    fn is_head_fallback(&self, method: &Method) -> bool {
+        method == Method::HEAD && self.methods.contains(&Method::GET) && !self.next.contains(&Method::HEAD)
+    }
+}
+
 #[cold]
 #[inline(never)]
 fn try_append_allowed<E>(mut e: RouterError<E>, methods: &[Method]) -> RouterError<E> {
     if let RouterError::NotAllowed(ref mut e) = e {
-        e.0.extend_from_slice(methods);
+        for method in methods {
+            if !e.0.contains(method) {
+                e.0.push(method.clone());
+            }
+        }
+        // GET route implies HEAD. an explicit HEAD route in chain would be deduplicated.
+        if methods.contains(&Method::GET) && !e.0.contains(&Method::HEAD) {
+            e.0.push(Method::HEAD);
+        }
     }
     e
 }
 
+/// trait for looking up [Method] registered in a chain of [RouteService].
+#[doc(hidden)]
+pub trait RouteMethods {
+    fn contains(&self, method: &Method) -> bool;
+}
+
+impl<R, N, const M: usize> RouteMethods for RouteService<R, N, M>
+where
+    N: RouteMethods,
+{
+    fn contains(&self, method: &Method) -> bool {
+        self.methods.contains(method) || self.next.contains(method)
+    }
+}
+
+impl<R> RouteMethods for MethodNotAllowedService<R> {
+    fn contains(&self, _: &Method) -> bool {
+        false
+    }
+}
+
 impl<R, N, const M: usize> ReadyService for RouteService<R, N, M> {
     type Ready = ();
 
@@ -347,14 +392,16 @@ mod test {
 
         let allowed = e.allowed_methods();
 
-        assert_eq!(allowed.len(), 5);
+        assert_eq!(allowed.len(), 6);
         // strict allowed method order does not matter.
         // as long as the test can produce deterministic prediction it's fine.
         assert_eq!(allowed[0], Method::GET);
-        assert_eq!(allowed[1], Method::OPTIONS);
-        assert_eq!(allowed[2], Method::TRACE);
-        assert_eq!(allowed[3], Method::POST);
-        assert_eq!(allowed[4], Method::PUT);
+        // implied by GET route.
+        assert_eq!(allowed[1], Method::HEAD);
+        assert_eq!(allowed[2], Method::OPTIONS);
+        assert_eq!(allowed[3], Method::TRACE);
+        assert_eq!(allowed[4], Method::POST);
+        assert_eq!(allowed[5], Method::PUT);
 
         let mut req = Request::new(RequestBody::None);
         *req.method_mut() = Method::PUT;
@@ -367,6 +414,66 @@ mod test {
         assert_eq!(res.status().as_u16(), 200);
     }
 
+    #[test]
+    fn route_head_fallback() {
+        async fn head(_: Request<RequestBody>) -> Result<Response<ResponseBody>, Infallible> {
+            let mut res = Response::new(ResponseBody::none());
+            *res.status_mut() = crate::http::StatusCode::NO_CONTENT;
+            Ok(res)
+        }
+
+        fn head_req() -> Request<RequestBody> {
+            let mut req = Request::new(RequestBody::None);
+            *req.method_mut() = Method::HEAD;
+            req
+        }
+
+        // HEAD falls back to GET route.
+        let service = post(fn_service(index))
+            .get(fn_service(index))
+            .call(())
+            .now_or_panic()
+            .ok()
+            .unwrap();
+        let res = service.call(head_req()).now_or_panic().ok().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+
+        // explicit HEAD route overrides fallback regardless of it's position in chain.
+        let service = get(fn_service(index))
+            .head(fn_service(head))
+            .call(())
+            .now_or_panic()
+            .ok()
+            .unwrap();
+        let res = service.call(head_req()).now_or_panic().ok().unwrap();
+        assert_eq!(res.status().as_u16(), 204);
+
+        let service = Route::new([Method::HEAD])
+            .route(fn_service(head))
+            .get(fn_service(index))
+            .call(())
+            .now_or_panic()
+            .ok()
+            .unwrap();
+        let res = service.call(head_req()).now_or_panic().ok().unwrap();
+        assert_eq!(res.status().as_u16(), 204);
+
+        // explicit HEAD route is not duplicated with the one implied by GET route.
+        let mut req = Request::new(RequestBody::None);
+        *req.method_mut() = Method::DELETE;
+        let RouterError::NotAllowed(e) = service.call(req).now_or_panic().err().unwrap() else {
+            panic!("route does not return error on unallowed method request");
+        };
+        assert_eq!(e.allowed_methods(), &[Method::GET, Method::HEAD]);
+
+        // no fallback without GET route.
+        let service = post(fn_service(index)).call(()).now_or_panic().ok().unwrap();
+        let RouterError::NotAllowed(e) = service.call(head_req()).now_or_panic().err().unwrap() else {
+            panic!("HEAD request must not be allowed without GET route");
+        };
+        assert_eq!(e.allowed_methods(), &[Method::POST]);
+    }
+
     #[test]
     fn route_accept_crate_request() {
         get(fn_service(|_: Request<()>| async {
@@ -0,0 +1,128 @@
+//! websocket handshake utilities for accepting websocket connection on top of raw http service.
+//!
+//! Handshake is done with [accept] and the connection is taken over by websocket protocol implementation after
+//! `101 Switching Protocols`(or `200 OK` for http/2) response is sent. With [upgrade](crate::upgrade::upgrade) the
+//! request body can be turned into an io type for websocket libraries built on top of `AsyncRead`/`AsyncWrite`.
+//! Alternatively [http_ws] crate types can be used directly with request body as websocket message stream.
+//!
+//! # Examples
+//! ```rust
+//! use std::convert::Infallible;
+//!
+//! use xitca_http::{
+//!     body::ResponseBody,
+//!     h1::RequestBody,
+//!     http::{Request, RequestExt, Response},
+//!     upgrade::upgrade,
+//!     ws,
+//! };
+//!
+//! async fn service(req: Request<RequestExt<RequestBody>>) -> Result<Response<ResponseBody>, Infallible> {
+//!     // verify handshake and negotiate subprotocol. reject request with error response on failure.
+//!     let (res, _protocol) = match ws::accept(&req, &["chat"]) {
+//!         Ok(res) => res,
+//!         Err(e) => return Ok(ws::error_response(e).map(|_| ResponseBody::none())),
+//!     };
+//!
+//!     // io can be handed over to websocket protocol implementation.
+//!     let (_io, body) = upgrade(req.into_body());
+//!
+//!     Ok(res.map(|_| ResponseBody::box_stream(body)))
+//! }
+//! ```
+
+pub use http_ws::{accept_key, handshake, handshake_h2, handshake_with_protocols, negotiate_protocol, HandshakeError};
+
+use crate::http::{header::SEC_WEBSOCKET_PROTOCOL, response::Builder, Request, Response, Version};
+
+/// verify websocket handshake request and construct handshake response with negotiated subprotocol.
+///
+/// Both http/1.1 upgrade and http/2 extended `CONNECT` requests are supported. Subprotocol is selected by
+/// [negotiate_protocol] from given protocols in order of server preference and is added to response as
+/// `Sec-WebSocket-Protocol` header.
+pub fn accept<'a, B>(
+    req: &Request<B>,
+    protocols: &[&'a str],
+) -> Result<(Response<()>, Option<&'a str>), HandshakeError> {
+    let (builder, protocol) = match req.version() {
+        Version::HTTP_2 => {
+            let mut builder = handshake_h2(req.method(), req.headers())?;
+            let protocol = negotiate_protocol(req.headers(), protocols);
+            if let Some(protocol) = protocol {
+                builder = builder.header(SEC_WEBSOCKET_PROTOCOL, protocol);
+            }
+            (builder, protocol)
+        }
+        _ => handshake_with_protocols(req.method(), req.headers(), protocols)?,
+    };
+
+    let res = builder
+        .body(())
+        .expect("handshake function failed to generate correct Response Builder");
+
+    Ok((res, protocol))
+}
+
+/// construct error response of failed websocket handshake.
+///
+/// `405 Method Not Allowed` is returned for http/1.1 handshake request with wrong method and
+/// `400 Bad Request` for others.
+pub fn error_response(e: HandshakeError) -> Response<()> {
+    Builder::from(e)
+        .body(())
+        .expect("HandshakeError failed to generate correct Response Builder")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::http::{
+        header::{HeaderValue, CONNECTION, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION, UPGRADE},
+        Method, StatusCode,
+    };
+
+    use super::*;
+
+    #[test]
+    fn accept_h1() {
+        let req = Request::builder()
+            .header(UPGRADE, HeaderValue::from_static("websocket"))
+            .header(CONNECTION, HeaderValue::from_static("upgrade"))
+            .header(SEC_WEBSOCKET_VERSION, HeaderValue::from_static("13"))
+            .header(SEC_WEBSOCKET_KEY, HeaderValue::from_static("dGhlIHNhbXBsZSBub25jZQ=="))
+            .header(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static("chat"))
+            .body(())
+            .unwrap();
+
+        let (res, protocol) = accept(&req, &["chat"]).unwrap();
+        assert_eq!(protocol, Some("chat"));
+        assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+        assert_eq!(res.headers().get(SEC_WEBSOCKET_PROTOCOL).unwrap(), "chat");
+
+        let (res, protocol) = accept(&req, &[]).unwrap();
+        assert!(protocol.is_none());
+        assert!(!res.headers().contains_key(SEC_WEBSOCKET_PROTOCOL));
+
+        let req = Request::builder().method(Method::POST).body(()).unwrap();
+        let e = accept(&req, &[]).unwrap_err();
+        assert_eq!(error_response(e).status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[test]
+    fn accept_h2() {
+        let req = Request::builder()
+            .method(Method::CONNECT)
+            .version(Version::HTTP_2)
+            .header(SEC_WEBSOCKET_VERSION, HeaderValue::from_static("13"))
+            .header(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static("chat"))
+            .body(())
+            .unwrap();
+
+        let (res, protocol) = accept(&req, &["chat"]).unwrap();
+        assert_eq!(protocol, Some("chat"));
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = Request::builder().version(Version::HTTP_2).body(()).unwrap();
+        let e = accept(&req, &[]).unwrap_err();
+        assert_eq!(error_response(e).status(), StatusCode::BAD_REQUEST);
+    }
+}
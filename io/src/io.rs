@@ -12,6 +12,8 @@ use core::{
 
 use std::io;
 
+use crate::bytes::{Buf, BytesMut};
+
 /// A wrapper trait for an [AsyncRead]/[AsyncWrite] tokio type with additional methods.
 pub trait AsyncIo: io::Read + io::Write + Unpin {
     /// asynchronously wait for the IO type and return it's state as [Ready].
@@ -251,3 +253,197 @@ where
         self.0.read(buf)
     }
 }
+
+/// adapter type for transforming a type impl [AsyncRead] and [AsyncWrite] traits to a type impl [AsyncIo] trait.
+///
+/// This enables third party io types like tls streams from other crates or simulated sockets of testing
+/// frameworks to be used with xitca's drivers. It's the reverse of [PollIoAdapter].
+///
+/// Poll based io types do not expose readiness without doing the actual io. The adapter performs read ahead
+/// and buffered write internally to fit them into the readiness model of [AsyncIo]:
+/// - readable readiness is reported when bytes are read into internal buffer.
+/// - writable readiness is reported when internally buffered bytes are written and flushed to io.
+///
+/// # Example
+/// ```rust
+/// use xitca_io::io::{AsyncIo, AsyncIoAdapter, AsyncRead, AsyncWrite};
+///
+/// fn adapt<Io>(io: Io) -> impl AsyncIo
+/// where
+///     Io: AsyncRead + AsyncWrite + Unpin + Send,
+/// {
+///     AsyncIoAdapter::new(io)
+/// }
+/// ```
+pub struct AsyncIoAdapter<T> {
+    io: T,
+    read_buf: BytesMut,
+    read_state: ReadState,
+    write_buf: BytesMut,
+    want_flush: bool,
+}
+
+enum ReadState {
+    Open,
+    Eof,
+    Error(io::Error),
+}
+
+const ADAPTER_READ_SIZE: usize = 4096;
+const ADAPTER_WRITE_LIMIT: usize = 65536;
+
+impl<T> AsyncIoAdapter<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    pub fn new(io: T) -> Self {
+        Self {
+            io,
+            read_buf: BytesMut::new(),
+            read_state: ReadState::Open,
+            write_buf: BytesMut::new(),
+            want_flush: false,
+        }
+    }
+
+    /// get a reference of the wrapped io type.
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    /// get a mutable reference of the wrapped io type. read from or write to it directly would mess up the
+    /// internal buffers of adapter.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// take the wrapped io type. bytes buffered in adapter are dropped.
+    pub fn into_inner(self) -> T {
+        self.io
+    }
+
+    fn poll_read_ahead(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if !self.read_buf.is_empty() || !matches!(self.read_state, ReadState::Open) {
+            return Poll::Ready(());
+        }
+
+        self.read_buf.resize(ADAPTER_READ_SIZE, 0);
+        let mut buf = ReadBuf::new(&mut self.read_buf);
+        let res = Pin::new(&mut self.io).poll_read(cx, &mut buf);
+        let filled = buf.filled().len();
+        self.read_buf.truncate(filled);
+
+        match ready!(res) {
+            Ok(_) if filled == 0 => self.read_state = ReadState::Eof,
+            Ok(_) => {}
+            Err(e) => self.read_state = ReadState::Error(e),
+        }
+
+        Poll::Ready(())
+    }
+
+    fn poll_drain_write(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.write_buf.is_empty() {
+            match ready!(Pin::new(&mut self.io).poll_write(cx, &self.write_buf))? {
+                0 => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                n => self.write_buf.advance(n),
+            }
+        }
+
+        if self.want_flush {
+            ready!(Pin::new(&mut self.io).poll_flush(cx))?;
+            self.want_flush = false;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> AsyncIo for AsyncIoAdapter<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    #[inline]
+    fn ready(&mut self, interest: Interest) -> impl Future<Output = io::Result<Ready>> + Send {
+        core::future::poll_fn(move |cx| AsyncIo::poll_ready(self, interest, cx))
+    }
+
+    fn poll_ready(&mut self, interest: Interest, cx: &mut Context<'_>) -> Poll<io::Result<Ready>> {
+        let mut ready = Ready::EMPTY;
+
+        if interest.is_readable() && self.poll_read_ahead(cx).is_ready() {
+            ready |= Ready::READABLE;
+        }
+
+        // write error is reported as writable readiness and surfaced from following io::Write call.
+        if interest.is_writable() {
+            match self.poll_drain_write(cx) {
+                Poll::Ready(Ok(_)) => ready |= Ready::WRITABLE,
+                Poll::Ready(Err(e)) => {
+                    self.write_buf.clear();
+                    self.want_flush = false;
+                    return Poll::Ready(Err(e));
+                }
+                Poll::Pending => {}
+            }
+        }
+
+        if ready.is_empty() {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(ready))
+        }
+    }
+
+    #[inline]
+    fn is_vectored_write(&self) -> bool {
+        false
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain_write(cx))?;
+        Pin::new(&mut this.io).poll_shutdown(cx)
+    }
+}
+
+impl<T> io::Read for AsyncIoAdapter<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.read_buf.is_empty() {
+            let len = buf.len().min(self.read_buf.len());
+            buf[..len].copy_from_slice(&self.read_buf[..len]);
+            self.read_buf.advance(len);
+            return Ok(len);
+        }
+
+        match self.read_state {
+            ReadState::Open => Err(io::ErrorKind::WouldBlock.into()),
+            ReadState::Eof => Ok(0),
+            ReadState::Error(_) => match core::mem::replace(&mut self.read_state, ReadState::Eof) {
+                ReadState::Error(e) => Err(e),
+                _ => unreachable!(),
+            },
+        }
+    }
+}
+
+impl<T> io::Write for AsyncIoAdapter<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(ADAPTER_WRITE_LIMIT.saturating_sub(self.write_buf.len()));
+        if len == 0 && !buf.is_empty() {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        self.write_buf.extend_from_slice(&buf[..len]);
+        self.want_flush = true;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // buffered bytes are written and flushed when polling for writable readiness.
+        if self.want_flush {
+            Err(io::ErrorKind::WouldBlock.into())
+        } else {
+            Ok(())
+        }
+    }
+}
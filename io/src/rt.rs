@@ -0,0 +1,70 @@
+//! runtime abstraction for spawning task and timer.
+//!
+//! Io types of this crate are runtime agnostic through [AsyncIo](crate::io::AsyncIo) trait. [Executor] trait covers
+//! the rest of runtime dependencies so downstream crates can be driven by alternative async runtimes when user
+//! provides both io and executor types.
+//!
+//! # Examples
+//! ```rust
+//! use std::{future::Future, pin::Pin, time::Instant};
+//!
+//! use xitca_io::rt::Executor;
+//!
+//! // executor forwarding to an alternative runtime.
+//! struct MyExecutor;
+//!
+//! impl Executor for MyExecutor {
+//!     fn spawn(&self, fut: Pin<Box<dyn Future<Output = ()> + Send>>) {
+//!         // other_runtime::spawn(fut).detach();
+//!         # drop(fut);
+//!     }
+//!
+//!     fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+//!         // Box::pin(other_runtime::Timer::at(deadline))
+//!         # let _ = deadline;
+//!         # Box::pin(async {})
+//!     }
+//! }
+//! ```
+
+use core::{future::Future, pin::Pin, time::Duration};
+
+use std::time::Instant;
+
+/// boxed future used by [Executor] trait.
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// trait for spawning task and producing timer on an async runtime.
+///
+/// Trait is object safe and can be shared as `Arc<dyn Executor>`.
+pub trait Executor: Send + Sync {
+    /// spawn a detached task.
+    fn spawn(&self, fut: BoxFuture<()>);
+
+    /// produce a timer future that resolves at given deadline.
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<()>;
+
+    /// produce a timer future that resolves after given duration.
+    fn sleep(&self, dur: Duration) -> BoxFuture<()> {
+        self.sleep_until(Instant::now() + dur)
+    }
+}
+
+/// [Executor] backed by [tokio](https://docs.rs/tokio) runtime. Spawning and timer must happen inside the context of
+/// a tokio runtime.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokioExecutor;
+
+#[cfg(feature = "runtime")]
+impl Executor for TokioExecutor {
+    #[inline]
+    fn spawn(&self, fut: BoxFuture<()>) {
+        tokio::spawn(fut);
+    }
+
+    #[inline]
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<()> {
+        Box::pin(tokio::time::sleep_until(deadline.into()))
+    }
+}
@@ -3,11 +3,17 @@ use std::{collections::HashMap, sync::Mutex};
 use postgres_types::{Oid, Type};
 use xitca_unsafe_collection::no_hash::NoHashBuilder;
 
-use super::{driver::DriverTx, statement::Statement};
+use super::{
+    driver::{DriverStats, DriverTx},
+    statement::{Statement, StatementCache},
+};
 
 pub struct Client {
     pub(crate) tx: DriverTx,
     cached_typeinfo: Mutex<CachedTypeInfo>,
+    pub(crate) cached_statements: StatementCache,
+    #[cfg(feature = "tracing")]
+    pub(crate) slow_query: Option<core::time::Duration>,
 }
 
 /// A cache of type info and prepared statements for fetching type info
@@ -24,6 +30,9 @@ struct CachedTypeInfo {
     /// Corresponds to [TYPEINFO_QUERY](prepare::TYPEINFO_COMPOSITE_QUERY) (or
     /// its fallback).
     typeinfo_enum: Option<Statement>,
+    /// A statement for getting OID of a type from its name.
+    /// Corresponds to [TYPEINFO_NAME_QUERY](prepare::TYPEINFO_NAME_QUERY).
+    typeinfo_name: Option<Statement>,
     /// Cache of types already looked up.
     types: HashMap<Oid, Type, NoHashBuilder>,
 }
@@ -36,8 +45,12 @@ impl Client {
                 typeinfo: None,
                 typeinfo_composite: None,
                 typeinfo_enum: None,
+                typeinfo_name: None,
                 types: HashMap::default(),
             }),
+            cached_statements: StatementCache::default(),
+            #[cfg(feature = "tracing")]
+            slow_query: None,
         }
     }
 
@@ -47,6 +60,13 @@ impl Client {
         self.tx.is_closed()
     }
 
+    /// runtime statistics of connection's driver. None when it's not enabled by [Config::driver_stats].
+    ///
+    /// [Config::driver_stats]: crate::Config::driver_stats
+    pub fn stats(&self) -> Option<&DriverStats> {
+        self.tx.stats()
+    }
+
     pub fn typeinfo(&self) -> Option<Statement> {
         self.cached_typeinfo.lock().unwrap().typeinfo.clone()
     }
@@ -71,6 +91,14 @@ impl Client {
         self.cached_typeinfo.lock().unwrap().typeinfo_enum = Some(statement.clone());
     }
 
+    pub fn typeinfo_name(&self) -> Option<Statement> {
+        self.cached_typeinfo.lock().unwrap().typeinfo_name.clone()
+    }
+
+    pub fn set_typeinfo_name(&self, statement: &Statement) {
+        self.cached_typeinfo.lock().unwrap().typeinfo_name = Some(statement.clone());
+    }
+
     pub fn type_(&self, oid: Oid) -> Option<Type> {
         self.cached_typeinfo.lock().unwrap().types.get(&oid).cloned()
     }
@@ -88,12 +116,13 @@ impl Drop for Client {
     fn drop(&mut self) {
         // convert leaked statements to guarded statements.
         // this is to cancel the statement on client go away.
-        let (type_info, typeinfo_composite, typeinfo_enum) = {
+        let (type_info, typeinfo_composite, typeinfo_enum, typeinfo_name) = {
             let cache = self.cached_typeinfo.get_mut().unwrap();
             (
                 cache.typeinfo.take(),
                 cache.typeinfo_composite.take(),
                 cache.typeinfo_enum.take(),
+                cache.typeinfo_name.take(),
             )
         };
 
@@ -108,5 +137,13 @@ impl Drop for Client {
         if let Some(stmt) = typeinfo_enum {
             drop(stmt.into_guarded(&*self));
         }
+
+        if let Some(stmt) = typeinfo_name {
+            drop(stmt.into_guarded(&*self));
+        }
+
+        for (_, stmt) in self.cached_statements.take() {
+            drop(stmt.into_guarded(&*self));
+        }
     }
 }
@@ -1,12 +1,13 @@
 use core::fmt;
 
-use super::Type;
+use super::{statement::Format, Type};
 
 /// Information about a column of a query.
 #[derive(Clone)]
 pub struct Column {
     name: Box<str>,
     r#type: Type,
+    format: Format,
 }
 
 impl Column {
@@ -14,9 +15,15 @@ impl Column {
         Column {
             name: Box::from(name),
             r#type,
+            format: Format::Binary,
         }
     }
 
+    pub(crate) fn with_format(mut self, format: Format) -> Column {
+        self.format = format;
+        self
+    }
+
     /// Returns the name of the column.
     pub fn name(&self) -> &str {
         &self.name
@@ -26,6 +33,11 @@ impl Column {
     pub fn r#type(&self) -> &Type {
         &self.r#type
     }
+
+    /// Returns the format of the column's value in result rows.
+    pub fn format(&self) -> Format {
+        self.format
+    }
 }
 
 impl fmt::Debug for Column {
@@ -33,6 +45,7 @@ impl fmt::Debug for Column {
         fmt.debug_struct("Column")
             .field("name", &self.name)
             .field("type", &self.r#type)
+            .field("format", &self.format)
             .finish()
     }
 }
@@ -2,9 +2,12 @@ pub(crate) mod codec;
 pub(crate) mod generic;
 
 mod connect;
+mod stats;
 
 pub(crate) use generic::DriverTx;
 
+pub use stats::DriverStats;
+
 #[cfg(feature = "tls")]
 mod tls;
 
@@ -14,7 +17,7 @@ pub(crate) mod quic;
 use core::{
     future::{Future, IntoFuture},
     net::SocketAddr,
-    pin::Pin,
+    pin::{pin, Pin},
 };
 
 use postgres_protocol::message::backend;
@@ -23,7 +26,14 @@ use xitca_io::{
     net::TcpStream,
 };
 
-use super::{client::Client, config::Config, error::Error, iter::AsyncLendingIterator};
+use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
+
+use super::{
+    client::Client,
+    config::{AfterConnect, Config},
+    error::{DriverDownReceiving, Error},
+    iter::AsyncLendingIterator,
+};
 
 use self::generic::GenericDriver;
 
@@ -38,7 +48,7 @@ pub(super) async fn connect(cfg: &mut Config) -> Result<(Client, Driver), Error>
     let hosts = cfg.get_hosts().to_vec();
     for host in hosts {
         match self::connect::connect_host(host, cfg).await {
-            Ok((tx, drv)) => return Ok((Client::new(tx), drv)),
+            Ok((tx, drv)) => return after_connect(Client::new(tx), drv, cfg).await,
             Err(e) => err = Some(e),
         }
     }
@@ -51,7 +61,46 @@ where
     Io: AsyncIo + Send + 'static,
 {
     let (tx, drv) = self::connect::connect_io(io, cfg).await?;
-    Ok((Client::new(tx), drv))
+    after_connect(Client::new(tx), drv, cfg).await
+}
+
+// execute statements and hooks added by Config::after_connect. driver is polled concurrently as it's not spawned
+// yet. server sent messages not belonging to any query are dropped in the mean time.
+#[cfg_attr(not(feature = "tracing"), allow(unused_mut))]
+async fn after_connect(mut cli: Client, mut drv: Driver, cfg: &Config) -> Result<(Client, Driver), Error> {
+    #[cfg(feature = "tracing")]
+    {
+        cli.slow_query = cfg.slow_query;
+    }
+
+    if cfg.after_connect.is_empty() {
+        return Ok((cli, drv));
+    }
+
+    {
+        let mut fut = pin!(async {
+            for after in cfg.after_connect.iter() {
+                match after {
+                    AfterConnect::Sql(sql) => {
+                        cli.execute_simple(sql).await?;
+                    }
+                    AfterConnect::Hook(hook) => hook(&cli).await?,
+                }
+            }
+            Ok::<_, Error>(())
+        });
+
+        loop {
+            match fut.as_mut().select(drv.try_next()).await {
+                SelectOutput::A(res) => break res?,
+                SelectOutput::B(Ok(Some(_))) => {}
+                SelectOutput::B(Ok(None)) => return Err(DriverDownReceiving.into()),
+                SelectOutput::B(Err(e)) => return Err(e),
+            }
+        }
+    }
+
+    Ok((cli, drv))
 }
 
 async fn dns_resolve(host: &str, ports: &[u16]) -> Result<Vec<SocketAddr>, Error> {
@@ -106,7 +155,10 @@ pub enum Driver {
 }
 
 impl AsyncLendingIterator for Driver {
-    type Ok<'i> = backend::Message where Self: 'i;
+    type Ok<'i>
+        = backend::Message
+    where
+        Self: 'i;
     type Err = Error;
 
     #[inline]
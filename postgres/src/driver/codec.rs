@@ -5,17 +5,40 @@ use core::{
 
 use postgres_protocol::message::backend;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
-use xitca_io::bytes::BytesMut;
+use xitca_io::bytes::{BufMut, BytesMut};
 
 use crate::error::{DriverDownReceiving, Error};
 
 pub(super) fn request_pair(msg_count: usize) -> (ResponseSender, Response) {
+    _request_pair(msg_count, true)
+}
+
+// request pair of query without it's own sync message. see ResponseSender::send for detail.
+pub(super) fn request_pair_unsync() -> (ResponseSender, Response) {
+    _request_pair(1, false)
+}
+
+// sender of the ReadyForQuery responding to a shared sync message. it belongs to no query.
+pub(super) fn shared_sync_sender() -> ResponseSender {
+    let (mut tx, _) = request_pair(1);
+    tx.shared_sync = true;
+    tx
+}
+
+fn _request_pair(msg_count: usize, sync: bool) -> (ResponseSender, Response) {
     let (tx, rx) = unbounded_channel();
     (
-        ResponseSender { tx, msg_count },
+        ResponseSender {
+            tx,
+            msg_count,
+            sync,
+            shared_sync: false,
+        },
         Response {
             rx,
             buf: BytesMut::new(),
+            #[cfg(feature = "tracing")]
+            trace: None,
         },
     )
 }
@@ -23,9 +46,16 @@ pub(super) fn request_pair(msg_count: usize) -> (ResponseSender, Response) {
 pub struct Response {
     rx: ResponseReceiver,
     buf: BytesMut,
+    #[cfg(feature = "tracing")]
+    trace: Option<Box<crate::trace::QueryTrace>>,
 }
 
 impl Response {
+    #[cfg(feature = "tracing")]
+    pub(crate) fn set_trace(&mut self, trace: crate::trace::QueryTrace) {
+        self.trace = Some(Box::new(trace));
+    }
+
     pub(crate) fn recv(&mut self) -> impl Future<Output = Result<backend::Message, Error>> + Send + '_ {
         poll_fn(|cx| {
             if self.buf.is_empty() {
@@ -34,10 +64,15 @@ impl Response {
 
             let res = match backend::Message::parse(&mut self.buf)?.expect("must not parse message from empty buffer.")
             {
-                backend::Message::ErrorResponse(_body) => Err(Error::todo()),
+                backend::Message::ErrorResponse(body) => Err(Error::db(&body)),
                 msg => Ok(msg),
             };
 
+            #[cfg(feature = "tracing")]
+            if let Some(trace) = self.trace.as_mut() {
+                trace.on_message(&res);
+            }
+
             Poll::Ready(res)
         })
     }
@@ -47,6 +82,8 @@ impl Response {
 pub(crate) struct ResponseSender {
     tx: UnboundedSender<BytesMut>,
     msg_count: usize,
+    sync: bool,
+    shared_sync: bool,
 }
 
 pub(super) enum SenderState {
@@ -55,12 +92,26 @@ pub(super) enum SenderState {
 }
 
 impl ResponseSender {
+    pub(super) fn is_sync(&self) -> bool {
+        self.sync
+    }
+
+    pub(super) fn is_shared_sync(&self) -> bool {
+        self.shared_sync
+    }
+
     pub(super) fn send(&mut self, msg: BytesMut, complete: bool) -> SenderState {
         debug_assert!(self.msg_count > 0);
 
         let _ = self.tx.send(msg);
 
         if complete {
+            // query without it's own sync message shares ReadyForQuery with other queries and it's response is
+            // completed by CommandComplete, EmptyQueryResponse or ErrorResponse. a ReadyForQuery is generated
+            // so it can be consumed the same way as sync query.
+            if !self.sync {
+                let _ = self.tx.send(ready_for_query());
+            }
             self.msg_count -= 1;
         }
 
@@ -70,6 +121,26 @@ impl ResponseSender {
             SenderState::Continue
         }
     }
+
+    // query without it's own sync message is skipped by server when prior query sharing the same sync failed.
+    pub(super) fn abort(self) {
+        let _ = self.tx.send(pipeline_aborted());
+        let _ = self.tx.send(ready_for_query());
+    }
+}
+
+fn ready_for_query() -> BytesMut {
+    BytesMut::from(&b"Z\0\0\0\x05I"[..])
+}
+
+fn pipeline_aborted() -> BytesMut {
+    const FIELDS: &[u8] =
+        b"SERROR\0VERROR\0C25P02\0Mquery is skipped because prior query sharing the same sync failed\0\0";
+    let mut buf = BytesMut::with_capacity(FIELDS.len() + 5);
+    buf.put_u8(backend::ERROR_RESPONSE_TAG);
+    buf.put_i32(FIELDS.len() as i32 + 4);
+    buf.extend_from_slice(FIELDS);
+    buf
 }
 
 // TODO: remove this lint.
@@ -79,10 +150,13 @@ pub(super) type ResponseReceiver = UnboundedReceiver<BytesMut>;
 pub enum ResponseMessage {
     Normal { buf: BytesMut, complete: bool },
     Async(backend::Message),
+    // ReadyForQuery is received by query without it's own sync message. it means the query is skipped by server.
+    Aborted,
 }
 
 impl ResponseMessage {
-    pub(crate) fn try_from_buf(buf: &mut BytesMut) -> Result<Option<Self>, Error> {
+    // sync indicates if the query the response belongs to has it's own sync message.
+    pub(crate) fn try_from_buf(buf: &mut BytesMut, sync: bool) -> Result<Option<Self>, Error> {
         let mut idx = 0;
         let mut complete = false;
 
@@ -109,9 +183,24 @@ impl ResponseMessage {
 
                     break;
                 }
+                backend::READY_FOR_QUERY_TAG if !sync => {
+                    if idx == 0 {
+                        return Ok(Some(ResponseMessage::Aborted));
+                    }
+
+                    break;
+                }
                 tag => {
                     idx += len;
-                    if matches!(tag, backend::READY_FOR_QUERY_TAG) {
+                    if sync {
+                        if matches!(tag, backend::READY_FOR_QUERY_TAG) {
+                            complete = true;
+                            break;
+                        }
+                    } else if matches!(
+                        tag,
+                        backend::COMMAND_COMPLETE_TAG | backend::EMPTY_QUERY_RESPONSE_TAG | backend::ERROR_RESPONSE_TAG
+                    ) {
                         complete = true;
                         break;
                     }
@@ -129,3 +218,72 @@ impl ResponseMessage {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::error::SqlState;
+
+    use super::*;
+
+    fn message(tag: u8, body: &[u8]) -> BytesMut {
+        let mut buf = BytesMut::new();
+        buf.put_u8(tag);
+        buf.put_i32(body.len() as i32 + 4);
+        buf.extend_from_slice(body);
+        buf
+    }
+
+    #[test]
+    fn unsync() {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&message(backend::BIND_COMPLETE_TAG, b""));
+        buf.extend_from_slice(&message(backend::COMMAND_COMPLETE_TAG, b"SELECT 0\0"));
+        buf.extend_from_slice(&ready_for_query());
+
+        // query without sync message is completed by CommandComplete.
+        let Some(ResponseMessage::Normal { buf: msg, complete }) =
+            ResponseMessage::try_from_buf(&mut buf, false).unwrap()
+        else {
+            panic!("unexpected response message")
+        };
+        assert!(complete);
+
+        let (mut tx, mut rx) = request_pair_unsync();
+        assert!(matches!(tx.send(msg, complete), SenderState::Finish));
+        assert!(matches!(
+            rx.recv().now_or_panic().unwrap(),
+            backend::Message::BindComplete
+        ));
+        assert!(matches!(
+            rx.recv().now_or_panic().unwrap(),
+            backend::Message::CommandComplete(_)
+        ));
+        assert!(matches!(
+            rx.recv().now_or_panic().unwrap(),
+            backend::Message::ReadyForQuery(_)
+        ));
+
+        // ReadyForQuery of shared sync is not consumed by query without sync message.
+        assert!(matches!(
+            ResponseMessage::try_from_buf(&mut buf, false).unwrap(),
+            Some(ResponseMessage::Aborted)
+        ));
+        let (tx, mut rx) = request_pair_unsync();
+        tx.abort();
+        let e = rx.recv().now_or_panic().err().unwrap();
+        assert_eq!(e.code(), Some(&SqlState::IN_FAILED_SQL_TRANSACTION));
+        assert!(matches!(
+            rx.recv().now_or_panic().unwrap(),
+            backend::Message::ReadyForQuery(_)
+        ));
+
+        let Some(ResponseMessage::Normal { complete, .. }) = ResponseMessage::try_from_buf(&mut buf, true).unwrap()
+        else {
+            panic!("unexpected response message")
+        };
+        assert!(complete);
+        assert!(buf.is_empty());
+    }
+}
@@ -78,7 +78,7 @@ pub(super) async fn connect_host(host: Host, cfg: &mut Config) -> Result<(Driver
         match cfg.get_ssl_mode() {
             SslMode::Disable => Ok(false),
             mode => match (query_tls_availability(io).await?, mode) {
-                (false, SslMode::Require) => Err(Error::todo()),
+                (false, SslMode::Require | SslMode::VerifyCa | SslMode::VerifyFull) => Err(Error::todo()),
                 (bool, _) => Ok(bool),
             },
         }
@@ -91,14 +91,18 @@ pub(super) async fn connect_host(host: Host, cfg: &mut Config) -> Result<(Driver
                 #[cfg(feature = "tls")]
                 {
                     let io = super::tls::connect_tls(io, host, cfg).await?;
-                    prepare_driver(io, cfg).await.map(|(tx, drv)| (tx, Driver::Tls(drv)))
+                    prepare_driver(io, cfg, Some(host))
+                        .await
+                        .map(|(tx, drv)| (tx, Driver::Tls(drv)))
                 }
                 #[cfg(not(feature = "tls"))]
                 {
                     Err(crate::error::FeatureError::Tls.into())
                 }
             } else {
-                prepare_driver(io, cfg).await.map(|(tx, drv)| (tx, Driver::Tcp(drv)))
+                prepare_driver(io, cfg, Some(host))
+                    .await
+                    .map(|(tx, drv)| (tx, Driver::Tcp(drv)))
             }
         }
         Host::Unix(ref _host) => {
@@ -110,7 +114,7 @@ pub(super) async fn connect_host(host: Host, cfg: &mut Config) -> Result<(Driver
                     {
                         let host = _host.to_string_lossy();
                         let io = super::tls::connect_tls(io, host.as_ref(), cfg).await?;
-                        prepare_driver(io, cfg)
+                        prepare_driver(io, cfg, None)
                             .await
                             .map(|(tx, drv)| (tx, Driver::UnixTls(drv)))
                     }
@@ -119,7 +123,9 @@ pub(super) async fn connect_host(host: Host, cfg: &mut Config) -> Result<(Driver
                         Err(crate::error::FeatureError::Tls.into())
                     }
                 } else {
-                    prepare_driver(io, cfg).await.map(|(tx, drv)| (tx, Driver::Unix(drv)))
+                    prepare_driver(io, cfg, None)
+                        .await
+                        .map(|(tx, drv)| (tx, Driver::Unix(drv)))
                 }
             }
 
@@ -131,8 +137,10 @@ pub(super) async fn connect_host(host: Host, cfg: &mut Config) -> Result<(Driver
         Host::Quic(ref _host) => {
             #[cfg(feature = "quic")]
             {
-                let io = super::quic::connect_quic(_host, cfg.get_ports()).await?;
-                prepare_driver(io, cfg).await.map(|(tx, drv)| (tx, Driver::Quic(drv)))
+                let io = super::quic::connect_quic(_host, cfg).await?;
+                prepare_driver(io, cfg, Some(_host))
+                    .await
+                    .map(|(tx, drv)| (tx, Driver::Quic(drv)))
             }
             #[cfg(not(feature = "quic"))]
             {
@@ -148,16 +156,22 @@ pub(super) async fn connect_io<Io>(io: Io, cfg: &mut Config) -> Result<(DriverTx
 where
     Io: AsyncIo + Send + 'static,
 {
-    prepare_driver(Box::new(io) as _, cfg)
+    prepare_driver(Box::new(io) as _, cfg, None)
         .await
         .map(|(tx, drv)| (tx, Driver::Dynamic(drv)))
 }
 
-async fn prepare_driver<Io>(io: Io, cfg: &mut Config) -> Result<(DriverTx, GenericDriver<Io>), Error>
+// host is the name of remote server used for authentication. it's absent when connecting through unix socket or
+// user provided io.
+async fn prepare_driver<Io>(
+    io: Io,
+    cfg: &mut Config,
+    host: Option<&str>,
+) -> Result<(DriverTx, GenericDriver<Io>), Error>
 where
     Io: AsyncIo + Send + 'static,
 {
-    let (mut drv, tx) = GenericDriver::new(io);
-    prepare_session(&mut drv, cfg).await?;
+    let (mut drv, tx) = GenericDriver::new(io, cfg);
+    prepare_session(&mut drv, cfg, host).await?;
     Ok((tx, drv))
 }
@@ -9,17 +9,27 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use postgres_protocol::message::backend;
-use tokio::sync::Notify;
+use postgres_protocol::message::{backend, frontend};
+use tokio::{sync::Notify, time::Instant};
 use xitca_io::{
     bytes::{Buf, BufRead, BytesMut},
     io::{AsyncIo, Interest},
+    rt::Executor,
 };
 use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
 
-use crate::error::{DriverDown, Error};
+use crate::{
+    config::Config,
+    error::{DriverDown, Error, Overloaded},
+    pipeline::AutoPipeline,
+};
 
-use super::codec::{Response, ResponseMessage, ResponseSender, SenderState};
+use super::{
+    codec::{
+        request_pair, request_pair_unsync, shared_sync_sender, Response, ResponseMessage, ResponseSender, SenderState,
+    },
+    stats::DriverStats,
+};
 
 type PagedBytesMut = xitca_unsafe_collection::bytes::PagedBytesMut<4096>;
 
@@ -47,6 +57,30 @@ impl DriverTx {
     pub(crate) fn send_multi_with<F>(&self, func: F, msg_count: usize) -> Result<Response, Error>
     where
         F: FnOnce(&mut BytesMut) -> Result<(), Error>,
+    {
+        self._send_with(|buf, _| func(buf), msg_count, false)
+    }
+
+    // send extended query. when shared sync is enabled by auto pipelining mode func is called with false as sync
+    // argument and the query must be encoded without sync message.
+    pub(crate) fn send_query_with<F>(&self, func: F) -> Result<Response, Error>
+    where
+        F: FnOnce(&mut BytesMut, bool) -> Result<(), Error>,
+    {
+        self._send_with(func, 1, true)
+    }
+
+    pub(crate) fn set_auto_pipeline(&self, auto: Option<AutoPipeline>) {
+        self.0.guarded.lock().unwrap().auto = auto;
+    }
+
+    pub(crate) fn stats(&self) -> Option<&DriverStats> {
+        self.0.stats.as_ref()
+    }
+
+    fn _send_with<F>(&self, func: F, msg_count: usize, query: bool) -> Result<Response, Error>
+    where
+        F: FnOnce(&mut BytesMut, bool) -> Result<(), Error>,
     {
         let mut inner = self.0.guarded.lock().unwrap();
 
@@ -54,11 +88,41 @@ impl DriverTx {
             return Err(DriverDown.into());
         }
 
+        if inner.in_flight >= inner.max_queued {
+            if let Some(stats) = self.0.stats.as_ref() {
+                stats.on_overloaded();
+            }
+            return Err(Overloaded.into());
+        }
+
+        let shared_sync = query && inner.auto.is_some_and(|auto| auto.shared_sync);
+
+        if !shared_sync {
+            // request after queries without sync message must not be skipped by server when one of them failed.
+            inner.sync();
+        }
+
         let len = inner.buf.len();
 
-        func(&mut inner.buf).inspect_err(|_| inner.buf.truncate(len))?;
-        let (tx, rx) = super::codec::request_pair(msg_count);
+        func(&mut inner.buf, !shared_sync).inspect_err(|_| inner.buf.truncate(len))?;
+
+        let (tx, rx) = if shared_sync {
+            inner.unsynced += 1;
+            request_pair_unsync()
+        } else {
+            request_pair(msg_count)
+        };
         inner.res.push_back(tx);
+        inner.in_flight += 1;
+
+        if let Some(stats) = self.0.stats.as_ref() {
+            // position of the end of request in all bytes ever written to io.
+            let end = inner.written + inner.buf.len() as u64;
+            inner.pending.push_back((end, Instant::now()));
+            stats.set_in_flight(inner.in_flight);
+            stats.set_queued(inner.pending.len());
+        }
+
         self.0.notify.notify_one();
 
         Ok(rx)
@@ -68,12 +132,59 @@ impl DriverTx {
 pub(crate) struct SharedState {
     guarded: Mutex<State>,
     notify: Notify,
+    stats: Option<DriverStats>,
 }
 
 struct State {
     closed: bool,
     buf: BytesMut,
     res: VecDeque<ResponseSender>,
+    auto: Option<AutoPipeline>,
+    // count of encoded queries without sync message since last sync.
+    unsynced: usize,
+    // count of requests waiting for response and the limit of it.
+    in_flight: usize,
+    max_queued: usize,
+    // count of all bytes written to io.
+    written: u64,
+    // end position and enqueue time of requests not yet written to io. only tracked when stats is enabled.
+    pending: VecDeque<(u64, Instant)>,
+}
+
+impl State {
+    fn sync(&mut self) {
+        if self.unsynced > 0 {
+            self.unsynced = 0;
+            frontend::sync(&mut self.buf);
+            // shared sync is responded with a ReadyForQuery that belongs to no query.
+            self.res.push_back(shared_sync_sender());
+        }
+    }
+
+    fn on_written(&mut self, n: usize, stats: Option<&DriverStats>) {
+        self.written += n as u64;
+
+        if let Some(stats) = stats {
+            let now = Instant::now();
+            while let Some(&(end, enqueued)) = self.pending.front() {
+                if end > self.written {
+                    break;
+                }
+                self.pending.pop_front();
+                stats.on_written(now - enqueued);
+            }
+            stats.set_queued(self.pending.len());
+        }
+    }
+
+    fn on_finish(&mut self, res: &ResponseSender, stats: Option<&DriverStats>) {
+        if !res.is_shared_sync() {
+            self.in_flight -= 1;
+            if let Some(stats) = stats {
+                stats.set_in_flight(self.in_flight);
+            }
+        }
+    }
 }
 
 pub struct GenericDriver<Io> {
@@ -82,6 +193,7 @@ pub struct GenericDriver<Io> {
     pub(crate) state: DriverState,
     pub(crate) shared_state: Arc<SharedState>,
     write_state: WriteState,
+    executor: Option<Arc<dyn Executor>>,
 }
 
 // in case driver is dropped without closing the shared state
@@ -93,6 +205,8 @@ impl<Io> Drop for GenericDriver<Io> {
 
 enum WriteState {
     Waiting,
+    // auto pipelining mode is collecting requests until deadline.
+    Batching(Instant),
     WantWrite,
     WantFlush,
 }
@@ -106,14 +220,21 @@ impl<Io> GenericDriver<Io>
 where
     Io: AsyncIo + Send,
 {
-    pub(crate) fn new(io: Io) -> (Self, DriverTx) {
+    pub(crate) fn new(io: Io, cfg: &Config) -> (Self, DriverTx) {
         let state = Arc::new(SharedState {
             guarded: Mutex::new(State {
                 closed: false,
                 buf: BytesMut::new(),
                 res: VecDeque::new(),
+                auto: None,
+                unsynced: 0,
+                in_flight: 0,
+                max_queued: cfg.max_queued.unwrap_or(usize::MAX),
+                written: 0,
+                pending: VecDeque::new(),
             }),
             notify: Notify::new(),
+            stats: cfg.stats.then(DriverStats::default),
         });
 
         (
@@ -123,13 +244,14 @@ where
                 state: DriverState::Running,
                 shared_state: state.clone(),
                 write_state: WriteState::Waiting,
+                executor: cfg.executor.as_ref().map(|exec| exec.0.clone()),
             },
             DriverTx(state),
         )
     }
 
     fn want_write(&self) -> bool {
-        !matches!(self.write_state, WriteState::Waiting)
+        matches!(self.write_state, WriteState::WantWrite | WriteState::WantFlush)
     }
 
     pub(crate) async fn send(&mut self, msg: BytesMut) -> Result<(), Error> {
@@ -162,19 +284,43 @@ where
 
             let ready = match self.state {
                 DriverState::Running => 'inner: loop {
+                    let deadline = match self.write_state {
+                        WriteState::Batching(deadline) => Some(deadline),
+                        _ => None,
+                    };
+
                     match self
                         .shared_state
                         .notify
                         .notified()
                         .select(self.io.ready(interest))
+                        .select(batch_deadline(deadline, self.executor.as_deref()))
                         .await
                     {
-                        SelectOutput::A(_) => {
+                        SelectOutput::A(SelectOutput::A(_)) => {
+                            if let WriteState::Waiting = self.write_state {
+                                let auto = self.shared_state.guarded.lock().unwrap().auto;
+                                if let Some(auto) = auto {
+                                    // collect concurrent requests and write them with one flush.
+                                    self.write_state = WriteState::Batching(Instant::now() + auto.window);
+                                    continue 'inner;
+                                }
+                            }
+
+                            if let WriteState::Batching(_) = self.write_state {
+                                continue 'inner;
+                            }
+
+                            self.write_state = WriteState::WantWrite;
+                            interest = interest.add(Interest::WRITABLE);
+                            continue 'inner;
+                        }
+                        SelectOutput::A(SelectOutput::B(ready)) => break ready?,
+                        SelectOutput::B(_) => {
                             self.write_state = WriteState::WantWrite;
                             interest = interest.add(Interest::WRITABLE);
                             continue 'inner;
                         }
-                        SelectOutput::B(ready) => break ready?,
                     }
                 },
                 DriverState::Closing(ref mut e) => {
@@ -201,6 +347,10 @@ where
                     // no future Interest::WRITABLE should be passed to AsyncIo::ready method.
                     let mut inner = self.shared_state.guarded.lock().unwrap();
                     inner.buf.clear();
+                    inner.pending.clear();
+                    if let Some(stats) = self.shared_state.stats.as_ref() {
+                        stats.set_queued(0);
+                    }
                     // close shared state early so driver tx can observe the shutdown in first hand
                     inner.closed = true;
                     self.write_state = WriteState::Waiting;
@@ -241,6 +391,9 @@ where
                 WriteState::WantWrite => {
                     let mut inner = self.shared_state.guarded.lock().unwrap();
 
+                    // queries without sync message are written with a shared sync at the end.
+                    inner.sync();
+
                     if inner.closed {
                         if matches!(self.state, DriverState::Running) {
                             self.state = DriverState::Closing(None);
@@ -256,6 +409,7 @@ where
                         Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
                         Ok(n) => {
                             inner.buf.advance(n);
+                            inner.on_written(n, self.shared_state.stats.as_ref());
 
                             if inner.buf.is_empty() {
                                 self.write_state = WriteState::WantFlush;
@@ -265,7 +419,9 @@ where
                         Err(e) => return Err(e),
                     }
                 }
-                WriteState::Waiting => unreachable!("try_write must be called when WriteState is waiting"),
+                WriteState::Waiting | WriteState::Batching(_) => {
+                    unreachable!("try_write must be called when WriteState is waiting")
+                }
             }
         }
 
@@ -273,21 +429,40 @@ where
     }
 
     fn try_decode(&mut self) -> Result<Option<backend::Message>, Error> {
-        while let Some(res) = ResponseMessage::try_from_buf(self.read_buf.get_mut())? {
+        let mut inner = self.shared_state.guarded.lock().unwrap();
+        loop {
+            let sync = !matches!(inner.res.front(), Some(res) if !res.is_sync());
+            let Some(res) = ResponseMessage::try_from_buf(self.read_buf.get_mut(), sync)? else {
+                return Ok(None);
+            };
             match res {
                 ResponseMessage::Normal { buf, complete } => {
-                    let mut inner = self.shared_state.guarded.lock().unwrap();
                     let front = inner.res.front_mut().expect("server respond out of bound");
                     match front.send(buf, complete) {
                         SenderState::Finish => {
-                            inner.res.pop_front();
+                            let res = inner.res.pop_front().unwrap();
+                            inner.on_finish(&res, self.shared_state.stats.as_ref());
                         }
                         SenderState::Continue => {}
                     }
                 }
+                ResponseMessage::Aborted => {
+                    // ReadyForQuery is left in buffer and received by the shared sync.
+                    let res = inner.res.pop_front().expect("server respond out of bound");
+                    inner.on_finish(&res, self.shared_state.stats.as_ref());
+                    res.abort();
+                }
                 ResponseMessage::Async(msg) => return Ok(Some(msg)),
             }
         }
-        Ok(None)
+    }
+}
+
+// resolve when batching deadline of auto pipelining mode is reached. never resolve when there is no deadline.
+async fn batch_deadline(deadline: Option<Instant>, executor: Option<&dyn Executor>) {
+    match (deadline, executor) {
+        (Some(deadline), Some(exec)) => exec.sleep_until(deadline.into_std()).await,
+        (Some(deadline), None) => tokio::time::sleep_until(deadline).await,
+        (None, _) => core::future::pending().await,
     }
 }
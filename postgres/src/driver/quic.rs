@@ -16,7 +16,7 @@ use xitca_io::{
     io::{AsyncIo, Interest, Ready},
 };
 
-use crate::error::Error;
+use crate::{config::Config, error::Error};
 
 pub(crate) const QUIC_ALPN: &[u8] = b"quic";
 
@@ -217,11 +217,11 @@ impl io::Write for QuicStream {
 
 #[cold]
 #[inline(never)]
-pub(crate) async fn _connect_quic(host: &str, ports: &[u16]) -> Result<Connection, Error> {
-    let addrs = super::dns_resolve(host, ports).await?;
+pub(crate) async fn _connect_quic(host: &str, cfg: &Config) -> Result<Connection, Error> {
+    let addrs = super::dns_resolve(host, cfg.get_ports()).await?;
     let mut endpoint = Endpoint::client("0.0.0.0:0".parse().unwrap())?;
 
-    let cfg = super::tls::dangerous_config(vec![QUIC_ALPN.to_vec()]);
+    let cfg = super::tls::dangerous_config(vec![QUIC_ALPN.to_vec()], cfg)?;
     let cfg = QuicClientConfig::try_from(cfg).unwrap();
     endpoint.set_default_client_config(ClientConfig::new(Arc::new(cfg)));
 
@@ -242,8 +242,8 @@ pub(crate) async fn _connect_quic(host: &str, ports: &[u16]) -> Result<Connectio
 
 #[cold]
 #[inline(never)]
-pub(crate) async fn connect_quic(host: &str, ports: &[u16]) -> Result<QuicStream, Error> {
-    let conn = _connect_quic(host, ports).await?;
+pub(crate) async fn connect_quic(host: &str, cfg: &Config) -> Result<QuicStream, Error> {
+    let conn = _connect_quic(host, cfg).await?;
     let stream = conn.open_bi().await.map_err(|_| Error::todo())?;
     Ok(stream.into())
 }
@@ -0,0 +1,109 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use std::sync::Arc;
+
+/// shared handle of runtime statistics of a connection's [Driver](crate::Driver). enabled by
+/// [Config::driver_stats](crate::Config::driver_stats) and obtained from [Client::stats](crate::Client::stats).
+///
+/// Handle is cheap to clone and can outlive the client and driver. Every value is a lossy snapshot as the driver
+/// updates them concurrently.
+///
+/// # Examples
+/// ```rust
+/// use xitca_postgres::Client;
+///
+/// fn report(client: &Client) {
+///     if let Some(stats) = client.stats() {
+///         println!("in flight: {}, queued: {}", stats.in_flight(), stats.queued());
+///         if let Some(avg) = stats.queue_wait_avg() {
+///             println!("average queue wait: {avg:?}");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct DriverStats(Arc<Stats>);
+
+impl fmt::Debug for DriverStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DriverStats")
+            .field("in_flight", &self.in_flight())
+            .field("queued", &self.queued())
+            .field("written", &self.written())
+            .field("queue_wait_total", &self.queue_wait_total())
+            .field("queue_wait_max", &self.queue_wait_max())
+            .field("overloaded", &self.overloaded())
+            .finish()
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    in_flight: AtomicUsize,
+    queued: AtomicUsize,
+    written: AtomicU64,
+    queue_wait_nanos: AtomicU64,
+    queue_wait_max_nanos: AtomicU64,
+    overloaded: AtomicU64,
+}
+
+impl DriverStats {
+    /// count of requests sent to driver and waiting for their responses to finish.
+    pub fn in_flight(&self) -> usize {
+        self.0.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// count of requests sent to driver and not yet written to connection's io.
+    pub fn queued(&self) -> usize {
+        self.0.queued.load(Ordering::Relaxed)
+    }
+
+    /// total count of requests written to connection's io.
+    pub fn written(&self) -> u64 {
+        self.0.written.load(Ordering::Relaxed)
+    }
+
+    /// total time requests spent in driver's queue before written to connection's io.
+    pub fn queue_wait_total(&self) -> Duration {
+        Duration::from_nanos(self.0.queue_wait_nanos.load(Ordering::Relaxed))
+    }
+
+    /// average time a request spent in driver's queue. None when no request has been written.
+    pub fn queue_wait_avg(&self) -> Option<Duration> {
+        let written = u32::try_from(self.written()).unwrap_or(u32::MAX);
+        (written > 0).then(|| self.queue_wait_total() / written)
+    }
+
+    /// longest time a request spent in driver's queue.
+    pub fn queue_wait_max(&self) -> Duration {
+        Duration::from_nanos(self.0.queue_wait_max_nanos.load(Ordering::Relaxed))
+    }
+
+    /// total count of requests rejected with [Overloaded](crate::error::Overloaded) error.
+    pub fn overloaded(&self) -> u64 {
+        self.0.overloaded.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn set_in_flight(&self, in_flight: usize) {
+        self.0.in_flight.store(in_flight, Ordering::Relaxed);
+    }
+
+    pub(super) fn set_queued(&self, queued: usize) {
+        self.0.queued.store(queued, Ordering::Relaxed);
+    }
+
+    pub(super) fn on_written(&self, wait: Duration) {
+        let nanos = u64::try_from(wait.as_nanos()).unwrap_or(u64::MAX);
+        self.0.written.fetch_add(1, Ordering::Relaxed);
+        self.0.queue_wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.0.queue_wait_max_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    pub(super) fn on_overloaded(&self) {
+        self.0.overloaded.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -1,16 +1,22 @@
-use std::sync::Arc;
+use std::{io, path::Path, sync::Arc};
 
 use sha2::{Digest, Sha256};
 use xitca_io::io::AsyncIo;
 use xitca_tls::rustls::{
     self,
-    client::danger::HandshakeSignatureValid,
+    client::{
+        danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        WantsClientCert, WebPkiServerVerifier,
+    },
     crypto::{verify_tls12_signature, verify_tls13_signature},
-    pki_types::{CertificateDer, ServerName, UnixTime},
-    ClientConfig, ClientConnection, DigitallySignedStruct, RootCertStore, TlsStream,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+    CertificateError, ClientConfig, ClientConnection, ConfigBuilder, DigitallySignedStruct, RootCertStore, TlsStream,
 };
 
-use crate::{config::Config, error::Error};
+use crate::{
+    config::{Config, SslMode},
+    error::Error,
+};
 
 pub(super) async fn connect_tls<Io>(
     io: Io,
@@ -21,7 +27,16 @@ where
     Io: AsyncIo,
 {
     let name = ServerName::try_from(host).map_err(|_| Error::todo())?.to_owned();
-    let config = dangerous_config(Vec::new());
+    let config = match cfg.tls_cert_verifier {
+        Some(ref verifier) => verified_config(verifier.0.clone(), cfg)?,
+        None => match (cfg.get_ssl_mode(), cfg.get_ssl_root_cert()) {
+            (SslMode::VerifyFull, path) => verified_config(webpki_verifier(path)?, cfg)?,
+            (SslMode::VerifyCa, path) | (SslMode::Require, path @ Some(_)) => {
+                verified_config(Arc::new(SkipHostnameVerification(webpki_verifier(path)?)), cfg)?
+            }
+            _ => dangerous_config(Vec::new(), cfg)?,
+        },
+    };
     let config = Arc::new(config);
     let session = ClientConnection::new(config, name).map_err(|_| Error::todo())?;
 
@@ -39,19 +54,115 @@ where
     Ok(stream)
 }
 
-pub(crate) fn dangerous_config(alpn: Vec<Vec<u8>>) -> xitca_tls::rustls::ClientConfig {
+fn verified_config(verifier: Arc<dyn ServerCertVerifier>, cfg: &Config) -> Result<ClientConfig, Error> {
+    let builder = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier);
+    client_auth(builder, cfg)
+}
+
+// load client certificate and private key from PEM files for tls client authentication when they are configured.
+fn client_auth(builder: ConfigBuilder<ClientConfig, WantsClientCert>, cfg: &Config) -> Result<ClientConfig, Error> {
+    match (cfg.get_ssl_cert(), cfg.get_ssl_key()) {
+        (Some(cert), Some(key)) => {
+            let certs = CertificateDer::pem_file_iter(cert)
+                .map_err(invalid_data)?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(invalid_data)?;
+            let key = PrivateKeyDer::from_pem_file(key).map_err(invalid_data)?;
+            builder
+                .with_client_auth_cert(certs, key)
+                .map_err(|e| invalid_data(e).into())
+        }
+        (None, None) => Ok(builder.with_no_client_auth()),
+        _ => Err(invalid_data("ssl_cert and ssl_key must be set together").into()),
+    }
+}
+
+// load root certificates from PEM file. bundled root certificates are used when path is absent or "system".
+fn webpki_verifier(path: Option<&Path>) -> Result<Arc<WebPkiServerVerifier>, Error> {
     let mut root_store = RootCertStore::empty();
 
-    root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    match path {
+        Some(path) if path != Path::new("system") => {
+            for cert in CertificateDer::pem_file_iter(path).map_err(invalid_data)? {
+                root_store.add(cert.map_err(invalid_data)?).map_err(invalid_data)?;
+            }
+        }
+        _ => root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+    }
+
+    WebPkiServerVerifier::builder_with_provider(
+        Arc::new(root_store),
+        Arc::new(rustls::crypto::ring::default_provider()),
+    )
+    .build()
+    .map_err(|e| invalid_data(e).into())
+}
+
+fn invalid_data<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+// verify server certificate is signed by trusted certificate authority without checking host name.
+#[derive(Debug)]
+struct SkipHostnameVerification(Arc<WebPkiServerVerifier>);
+
+impl ServerCertVerifier for SkipHostnameVerification {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp, now)
+        {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            res => res,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}
 
-    let mut cfg = ClientConfig::builder()
-        .with_root_certificates(root_store)
-        .with_no_client_auth();
-    cfg.alpn_protocols = alpn;
+pub(crate) fn dangerous_config(alpn: Vec<Vec<u8>>, cfg: &Config) -> Result<ClientConfig, Error> {
+    let builder = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(SkipServerVerification::new());
 
-    cfg.dangerous().set_certificate_verifier(SkipServerVerification::new());
+    let mut config = client_auth(builder, cfg)?;
+    config.alpn_protocols = alpn;
 
-    cfg
+    Ok(config)
 }
 
 #[derive(Debug)]
@@ -63,7 +174,7 @@ impl SkipServerVerification {
     }
 }
 
-impl rustls::client::danger::ServerCertVerifier for SkipServerVerification {
+impl ServerCertVerifier for SkipServerVerification {
     fn verify_server_cert(
         &self,
         _end_entity: &CertificateDer<'_>,
@@ -71,8 +182,8 @@ impl rustls::client::danger::ServerCertVerifier for SkipServerVerification {
         _server_name: &ServerName<'_>,
         _ocsp: &[u8],
         _now: UnixTime,
-    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
-        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
     }
 
     fn verify_tls12_signature(
@@ -9,8 +9,18 @@ use core::{
 
 use std::{error, io};
 
+use postgres_protocol::message::backend;
+
 use super::from_sql::FromSqlError;
 
+mod db;
+mod sql_state;
+
+pub use self::{
+    db::{DbError, ErrorPosition},
+    sql_state::SqlState,
+};
+
 /// public facing error type. providing basic format and display based error handling.
 ///
 /// for typed based error handling runtime type cast is needed with the help of other
@@ -36,11 +46,69 @@ impl Error {
         Self(Box::new(UnexpectedMessage))
     }
 
+    /// check if error is caused by connection reaching it's limit of in flight requests. see [Overloaded] for detail.
+    pub fn is_overloaded(&self) -> bool {
+        self.0.is::<Overloaded>()
+    }
+
     #[cold]
     #[inline(never)]
     pub(crate) fn is_driver_down(&self) -> bool {
         self.0.is::<DriverDown>()
     }
+
+    pub(crate) fn db(body: &backend::ErrorResponseBody) -> Self {
+        match DbError::parse(body) {
+            Ok(e) => e.into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// get the error sent by database server if there is one.
+    pub fn as_db_error(&self) -> Option<&DbError> {
+        self.0
+            .downcast_ref::<DbError>()
+            .or_else(|| self.0.downcast_ref::<AuthenticationError>()?.db_error())
+    }
+
+    /// get the SQLSTATE code of error sent by database server if there is one.
+    pub fn code(&self) -> Option<&SqlState> {
+        self.as_db_error().map(DbError::code)
+    }
+
+    /// check if error is a unique constraint violation.
+    pub fn is_unique_violation(&self) -> bool {
+        self.is_code(SqlState::UNIQUE_VIOLATION)
+    }
+
+    /// check if error is a foreign key constraint violation.
+    pub fn is_foreign_key_violation(&self) -> bool {
+        self.is_code(SqlState::FOREIGN_KEY_VIOLATION)
+    }
+
+    /// check if error is a not null constraint violation.
+    pub fn is_not_null_violation(&self) -> bool {
+        self.is_code(SqlState::NOT_NULL_VIOLATION)
+    }
+
+    /// check if error is a check constraint violation.
+    pub fn is_check_violation(&self) -> bool {
+        self.is_code(SqlState::CHECK_VIOLATION)
+    }
+
+    /// check if error is a serialization failure where retrying the transaction may succeed.
+    pub fn is_serialization_failure(&self) -> bool {
+        self.is_code(SqlState::T_R_SERIALIZATION_FAILURE)
+    }
+
+    /// check if error is a deadlock where retrying the transaction may succeed.
+    pub fn is_deadlock_detected(&self) -> bool {
+        self.is_code(SqlState::T_R_DEADLOCK_DETECTED)
+    }
+
+    fn is_code(&self, code: SqlState) -> bool {
+        self.code() == Some(&code)
+    }
 }
 
 impl Deref for Error {
@@ -130,6 +198,31 @@ impl From<DriverDownReceiving> for Error {
     }
 }
 
+/// error indicate [Client]'s connection has reached it's limit of in flight requests set by
+/// [Config::max_queued_requests].
+///
+/// database query related to this error has not been sent to database and it's safe to retry operation after
+/// in flight requests are finished.
+///
+/// [Client]: crate::client::Client
+/// [Config::max_queued_requests]: crate::Config::max_queued_requests
+#[derive(Debug)]
+pub struct Overloaded;
+
+impl fmt::Display for Overloaded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Connection has too many requests in flight.")
+    }
+}
+
+impl error::Error for Overloaded {}
+
+impl From<Overloaded> for Error {
+    fn from(e: Overloaded) -> Self {
+        Self(Box::new(e))
+    }
+}
+
 pub struct InvalidColumnIndex(pub String);
 
 impl fmt::Debug for InvalidColumnIndex {
@@ -158,6 +251,12 @@ impl From<Infallible> for Error {
     }
 }
 
+impl From<DbError> for Error {
+    fn from(e: DbError) -> Self {
+        Self(Box::new(e))
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(e: io::Error) -> Self {
         Self(Box::new(e))
@@ -170,26 +269,89 @@ impl From<FromSqlError> for Error {
     }
 }
 
-/// error happens when library user failed to provide valid authentication info to database server.
+/// error happens when authentication with database server failed.
+///
+/// # Example
+/// ```rust
+/// use xitca_postgres::error::{AuthenticationError, Error};
+///
+/// fn is_wrong_password(e: &Error) -> bool {
+///     matches!(e.downcast_ref::<AuthenticationError>(), Some(AuthenticationError::WrongPassWord(_)))
+/// }
+/// ```
+#[non_exhaustive]
 #[derive(Debug)]
 pub enum AuthenticationError {
+    /// user name is required by server but not provided.
     MissingUserName,
+    /// password is required by server but not provided.
     MissingPassWord,
-    WrongPassWord,
+    /// server rejected the password. (SQLSTATE 28P01)
+    WrongPassWord(DbError),
+    /// server rejected the authorization. (SQLSTATE 28000)
+    /// it includes rejection from `pg_hba.conf` and failure from external authentication backends like LDAP,
+    /// RADIUS and Kerberos.
+    InvalidAuthorization(DbError),
+    /// other error sent by server during authentication.
+    Server(DbError),
+    /// authentication method requested by server is not supported.
+    UnsupportedMethod(&'static str),
+    /// none of the SASL mechanisms offered by server is supported.
+    UnsupportedSaslMechanism,
+    /// server requires SASL channel binding but tls server end point is not available.
+    ChannelBindingUnavailable,
+    /// error from GSSAPI security context.
+    Gssapi(Box<dyn error::Error + Send + Sync>),
+}
+
+impl AuthenticationError {
+    /// get the error sent by database server if there is one.
+    pub fn db_error(&self) -> Option<&DbError> {
+        match *self {
+            Self::WrongPassWord(ref e) | Self::InvalidAuthorization(ref e) | Self::Server(ref e) => Some(e),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn from_response(body: &backend::ErrorResponseBody) -> Error {
+        match DbError::parse(body) {
+            Ok(e) => match *e.code() {
+                SqlState::INVALID_PASSWORD => Self::WrongPassWord(e),
+                SqlState::INVALID_AUTHORIZATION_SPECIFICATION => Self::InvalidAuthorization(e),
+                _ => Self::Server(e),
+            }
+            .into(),
+            Err(e) => e.into(),
+        }
+    }
 }
 
 impl fmt::Display for AuthenticationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
-            Self::MissingUserName => f.write_str("username is missing")?,
-            Self::MissingPassWord => f.write_str("password is missing")?,
-            Self::WrongPassWord => f.write_str("password is wrong")?,
+            Self::MissingUserName => f.write_str("username is missing for authentication"),
+            Self::MissingPassWord => f.write_str("password is missing for authentication"),
+            Self::WrongPassWord(ref e) => write!(f, "password is wrong for authentication: {e}"),
+            Self::InvalidAuthorization(ref e) => write!(f, "authorization is rejected: {e}"),
+            Self::Server(ref e) => write!(f, "authentication failed: {e}"),
+            Self::UnsupportedMethod(method) => write!(f, "authentication method {method} is not supported"),
+            Self::UnsupportedSaslMechanism => f.write_str("none of SASL mechanisms offered by server is supported"),
+            Self::ChannelBindingUnavailable => {
+                f.write_str("server requires channel binding but tls server end point is not available")
+            }
+            Self::Gssapi(ref e) => write!(f, "gssapi authentication failed: {e}"),
         }
-        f.write_str(" for authentication")
     }
 }
 
-impl error::Error for AuthenticationError {}
+impl error::Error for AuthenticationError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Self::Gssapi(ref e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
 
 impl From<AuthenticationError> for Error {
     fn from(e: AuthenticationError) -> Self {
@@ -202,6 +364,7 @@ impl From<AuthenticationError> for Error {
 pub enum FeatureError {
     Tls,
     Quic,
+    Gssapi,
 }
 
 impl fmt::Display for FeatureError {
@@ -209,6 +372,7 @@ impl fmt::Display for FeatureError {
         match *self {
             Self::Tls => f.write_str("tls")?,
             Self::Quic => f.write_str("quic")?,
+            Self::Gssapi => f.write_str("gssapi")?,
         }
         f.write_str(" feature is not enabled")
     }
@@ -222,6 +386,37 @@ impl From<FeatureError> for Error {
     }
 }
 
+/// error of schema migration. see [migrate](crate::migrate) module for detail.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum MigrateError {
+    /// sql of applied migration is changed after it's applied.
+    Modified { version: i64, name: String },
+    /// applied migration is not found in local migrations.
+    Missing { version: i64, name: String },
+}
+
+impl fmt::Display for MigrateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Modified { version, ref name } => {
+                write!(f, "migration {version} {name} is modified after it's applied")
+            }
+            Self::Missing { version, ref name } => {
+                write!(f, "applied migration {version} {name} is missing from local migrations")
+            }
+        }
+    }
+}
+
+impl error::Error for MigrateError {}
+
+impl From<MigrateError> for Error {
+    fn from(e: MigrateError) -> Self {
+        Self(Box::new(e))
+    }
+}
+
 #[derive(Debug)]
 pub struct UnexpectedMessage;
 
@@ -0,0 +1,232 @@
+use core::fmt;
+
+use std::{error, io};
+
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::message::backend::ErrorResponseBody;
+
+use super::SqlState;
+
+/// error sent by database server.
+///
+/// # Examples
+/// ```rust
+/// use xitca_postgres::error::{Error, SqlState};
+///
+/// fn violated_constraint(e: &Error) -> Option<&str> {
+///     let e = e.as_db_error()?;
+///     (*e.code() == SqlState::UNIQUE_VIOLATION).then(|| e.constraint()).flatten()
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct DbError {
+    severity: Box<str>,
+    code: SqlState,
+    message: Box<str>,
+    detail: Option<Box<str>>,
+    hint: Option<Box<str>>,
+    position: Option<ErrorPosition>,
+    where_: Option<Box<str>>,
+    schema: Option<Box<str>>,
+    table: Option<Box<str>>,
+    column: Option<Box<str>>,
+    datatype: Option<Box<str>>,
+    constraint: Option<Box<str>>,
+    file: Option<Box<str>>,
+    line: Option<u32>,
+    routine: Option<Box<str>>,
+}
+
+/// position of error in query string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorPosition {
+    /// position in the original query string sent by client. Counted in characters starting from 1.
+    Original(u32),
+    /// position in an internally generated query.
+    Internal {
+        /// position in the internal query. Counted in characters starting from 1.
+        position: u32,
+        /// the internal query.
+        query: Box<str>,
+    },
+}
+
+impl DbError {
+    pub(crate) fn parse(body: &ErrorResponseBody) -> io::Result<Self> {
+        let mut severity = None;
+        let mut code = None;
+        let mut message = None;
+        let mut detail = None;
+        let mut hint = None;
+        let mut normal_position = None;
+        let mut internal_position = None;
+        let mut internal_query = None;
+        let mut where_ = None;
+        let mut schema = None;
+        let mut table = None;
+        let mut column = None;
+        let mut datatype = None;
+        let mut constraint = None;
+        let mut file = None;
+        let mut line = None;
+        let mut routine = None;
+
+        let mut fields = body.fields();
+        while let Some(field) = fields.next()? {
+            let value = String::from_utf8_lossy(field.value_bytes());
+            match field.type_() {
+                // localized severity is used only when non localized one is absent.
+                b'S' => severity = severity.or_else(|| Some(Box::from(value))),
+                b'V' => severity = Some(Box::from(value)),
+                b'C' => code = Some(SqlState::from_code(&value).ok_or_else(|| invalid("C"))?),
+                b'M' => message = Some(Box::from(value)),
+                b'D' => detail = Some(Box::from(value)),
+                b'H' => hint = Some(Box::from(value)),
+                b'P' => normal_position = Some(value.parse().map_err(|_| invalid("P"))?),
+                b'p' => internal_position = Some(value.parse().map_err(|_| invalid("p"))?),
+                b'q' => internal_query = Some(Box::from(value)),
+                b'W' => where_ = Some(Box::from(value)),
+                b's' => schema = Some(Box::from(value)),
+                b't' => table = Some(Box::from(value)),
+                b'c' => column = Some(Box::from(value)),
+                b'd' => datatype = Some(Box::from(value)),
+                b'n' => constraint = Some(Box::from(value)),
+                b'F' => file = Some(Box::from(value)),
+                b'L' => line = Some(value.parse().map_err(|_| invalid("L"))?),
+                b'R' => routine = Some(Box::from(value)),
+                _ => {}
+            }
+        }
+
+        let position = match (normal_position, internal_position) {
+            (Some(position), _) => Some(ErrorPosition::Original(position)),
+            (None, Some(position)) => Some(ErrorPosition::Internal {
+                position,
+                query: internal_query.ok_or_else(|| missing("q"))?,
+            }),
+            (None, None) => None,
+        };
+
+        Ok(Self {
+            severity: severity.ok_or_else(|| missing("S"))?,
+            code: code.ok_or_else(|| missing("C"))?,
+            message: message.ok_or_else(|| missing("M"))?,
+            detail,
+            hint,
+            position,
+            where_,
+            schema,
+            table,
+            column,
+            datatype,
+            constraint,
+            file,
+            line,
+            routine,
+        })
+    }
+
+    /// severity of error. e.g: `ERROR`, `FATAL`, `PANIC`.
+    pub fn severity(&self) -> &str {
+        &self.severity
+    }
+
+    /// SQLSTATE code of error.
+    pub fn code(&self) -> &SqlState {
+        &self.code
+    }
+
+    /// primary human-readable error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// optional secondary message carrying more detail about the error.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    /// optional suggestion what to do about the error.
+    pub fn hint(&self) -> Option<&str> {
+        self.hint.as_deref()
+    }
+
+    /// optional position of error in query string.
+    pub fn position(&self) -> Option<&ErrorPosition> {
+        self.position.as_ref()
+    }
+
+    /// context in which the error occurred. e.g: call stack traceback of active procedural language functions.
+    pub fn where_(&self) -> Option<&str> {
+        self.where_.as_deref()
+    }
+
+    /// name of the schema associated with the error.
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// name of the table associated with the error.
+    pub fn table(&self) -> Option<&str> {
+        self.table.as_deref()
+    }
+
+    /// name of the column associated with the error.
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    /// name of the data type associated with the error.
+    pub fn datatype(&self) -> Option<&str> {
+        self.datatype.as_deref()
+    }
+
+    /// name of the constraint associated with the error.
+    pub fn constraint(&self) -> Option<&str> {
+        self.constraint.as_deref()
+    }
+
+    /// file name of the source code location where the error was reported.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// line number of the source code location where the error was reported.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// name of the source code routine reporting the error.
+    pub fn routine(&self) -> Option<&str> {
+        self.routine.as_deref()
+    }
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} (SQLSTATE {})", self.severity, self.message, self.code)?;
+        if let Some(ref detail) = self.detail {
+            write!(f, "\nDETAIL: {detail}")?;
+        }
+        if let Some(ref hint) = self.hint {
+            write!(f, "\nHINT: {hint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for DbError {}
+
+fn missing(field: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("error response field {field} is missing"),
+    )
+}
+
+fn invalid(field: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("error response field {field} is invalid"),
+    )
+}
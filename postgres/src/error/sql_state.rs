@@ -0,0 +1,119 @@
+use core::{fmt, str};
+
+/// SQLSTATE error code sent by database server.
+///
+/// Constants of common error codes are offered for matching. See [PostgreSQL document] for the full list.
+///
+/// # Examples
+/// ```rust
+/// use xitca_postgres::error::SqlState;
+///
+/// fn retryable(code: &SqlState) -> bool {
+///     matches!(*code, SqlState::T_R_SERIALIZATION_FAILURE | SqlState::T_R_DEADLOCK_DETECTED)
+/// }
+/// ```
+///
+/// [PostgreSQL document]: https://www.postgresql.org/docs/current/errcodes-appendix.html
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SqlState([u8; 5]);
+
+impl SqlState {
+    /// construct from raw error code. return `None` when code is not 5 ascii alphanumeric characters.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = <[u8; 5]>::try_from(code.as_bytes()).ok()?;
+        code.iter().all(u8::is_ascii_alphanumeric).then_some(Self(code))
+    }
+
+    /// the error code in string form.
+    pub fn code(&self) -> &str {
+        str::from_utf8(&self.0).expect("SqlState must be constructed from ascii")
+    }
+
+    /// the error class which is the first two characters of error code.
+    pub fn class(&self) -> &str {
+        &self.code()[..2]
+    }
+
+    pub const SUCCESSFUL_COMPLETION: Self = Self(*b"00000");
+    pub const WARNING: Self = Self(*b"01000");
+    pub const NO_DATA: Self = Self(*b"02000");
+    pub const CONNECTION_EXCEPTION: Self = Self(*b"08000");
+    pub const CONNECTION_DOES_NOT_EXIST: Self = Self(*b"08003");
+    pub const CONNECTION_FAILURE: Self = Self(*b"08006");
+    pub const PROTOCOL_VIOLATION: Self = Self(*b"08P01");
+    pub const FEATURE_NOT_SUPPORTED: Self = Self(*b"0A000");
+    pub const CARDINALITY_VIOLATION: Self = Self(*b"21000");
+    pub const DATA_EXCEPTION: Self = Self(*b"22000");
+    pub const STRING_DATA_RIGHT_TRUNCATION: Self = Self(*b"22001");
+    pub const NUMERIC_VALUE_OUT_OF_RANGE: Self = Self(*b"22003");
+    pub const NULL_VALUE_NOT_ALLOWED: Self = Self(*b"22004");
+    pub const INVALID_DATETIME_FORMAT: Self = Self(*b"22007");
+    pub const DATETIME_FIELD_OVERFLOW: Self = Self(*b"22008");
+    pub const DIVISION_BY_ZERO: Self = Self(*b"22012");
+    pub const INVALID_PARAMETER_VALUE: Self = Self(*b"22023");
+    pub const INVALID_TEXT_REPRESENTATION: Self = Self(*b"22P02");
+    pub const INTEGRITY_CONSTRAINT_VIOLATION: Self = Self(*b"23000");
+    pub const RESTRICT_VIOLATION: Self = Self(*b"23001");
+    pub const NOT_NULL_VIOLATION: Self = Self(*b"23502");
+    pub const FOREIGN_KEY_VIOLATION: Self = Self(*b"23503");
+    pub const UNIQUE_VIOLATION: Self = Self(*b"23505");
+    pub const CHECK_VIOLATION: Self = Self(*b"23514");
+    pub const EXCLUSION_VIOLATION: Self = Self(*b"23P01");
+    pub const INVALID_CURSOR_STATE: Self = Self(*b"24000");
+    pub const INVALID_TRANSACTION_STATE: Self = Self(*b"25000");
+    pub const ACTIVE_SQL_TRANSACTION: Self = Self(*b"25001");
+    pub const READ_ONLY_SQL_TRANSACTION: Self = Self(*b"25006");
+    pub const NO_ACTIVE_SQL_TRANSACTION: Self = Self(*b"25P01");
+    pub const IN_FAILED_SQL_TRANSACTION: Self = Self(*b"25P02");
+    pub const IDLE_IN_TRANSACTION_SESSION_TIMEOUT: Self = Self(*b"25P03");
+    pub const INVALID_SQL_STATEMENT_NAME: Self = Self(*b"26000");
+    pub const INVALID_AUTHORIZATION_SPECIFICATION: Self = Self(*b"28000");
+    pub const INVALID_PASSWORD: Self = Self(*b"28P01");
+    pub const INVALID_CATALOG_NAME: Self = Self(*b"3D000");
+    pub const INVALID_SCHEMA_NAME: Self = Self(*b"3F000");
+    pub const TRANSACTION_ROLLBACK: Self = Self(*b"40000");
+    pub const T_R_SERIALIZATION_FAILURE: Self = Self(*b"40001");
+    pub const T_R_STATEMENT_COMPLETION_UNKNOWN: Self = Self(*b"40003");
+    pub const T_R_DEADLOCK_DETECTED: Self = Self(*b"40P01");
+    pub const SYNTAX_ERROR_OR_ACCESS_RULE_VIOLATION: Self = Self(*b"42000");
+    pub const INSUFFICIENT_PRIVILEGE: Self = Self(*b"42501");
+    pub const SYNTAX_ERROR: Self = Self(*b"42601");
+    pub const UNDEFINED_COLUMN: Self = Self(*b"42703");
+    pub const UNDEFINED_FUNCTION: Self = Self(*b"42883");
+    pub const UNDEFINED_TABLE: Self = Self(*b"42P01");
+    pub const UNDEFINED_PARAMETER: Self = Self(*b"42P02");
+    pub const UNDEFINED_OBJECT: Self = Self(*b"42704");
+    pub const DUPLICATE_COLUMN: Self = Self(*b"42701");
+    pub const DUPLICATE_OBJECT: Self = Self(*b"42710");
+    pub const DUPLICATE_TABLE: Self = Self(*b"42P07");
+    pub const AMBIGUOUS_COLUMN: Self = Self(*b"42702");
+    pub const DATATYPE_MISMATCH: Self = Self(*b"42804");
+    pub const INSUFFICIENT_RESOURCES: Self = Self(*b"53000");
+    pub const DISK_FULL: Self = Self(*b"53100");
+    pub const OUT_OF_MEMORY: Self = Self(*b"53200");
+    pub const TOO_MANY_CONNECTIONS: Self = Self(*b"53300");
+    pub const PROGRAM_LIMIT_EXCEEDED: Self = Self(*b"54000");
+    pub const OBJECT_NOT_IN_PREREQUISITE_STATE: Self = Self(*b"55000");
+    pub const OBJECT_IN_USE: Self = Self(*b"55006");
+    pub const LOCK_NOT_AVAILABLE: Self = Self(*b"55P03");
+    pub const OPERATOR_INTERVENTION: Self = Self(*b"57000");
+    pub const QUERY_CANCELED: Self = Self(*b"57014");
+    pub const ADMIN_SHUTDOWN: Self = Self(*b"57P01");
+    pub const CRASH_SHUTDOWN: Self = Self(*b"57P02");
+    pub const CANNOT_CONNECT_NOW: Self = Self(*b"57P03");
+    pub const SYSTEM_ERROR: Self = Self(*b"58000");
+    pub const IO_ERROR: Self = Self(*b"58030");
+    pub const INTERNAL_ERROR: Self = Self(*b"XX000");
+}
+
+impl fmt::Debug for SqlState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SqlState").field(&self.code()).finish()
+    }
+}
+
+impl fmt::Display for SqlState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
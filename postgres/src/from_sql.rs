@@ -87,6 +87,52 @@ default_impl!(i64);
 default_impl!(f32);
 default_impl!(f64);
 
+#[cfg(feature = "chrono")]
+mod chrono_impl {
+    use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+    use super::*;
+
+    default_impl!(NaiveDateTime);
+    default_impl!(NaiveDate);
+    default_impl!(NaiveTime);
+    default_impl!(DateTime<Utc>);
+    default_impl!(DateTime<Local>);
+    default_impl!(DateTime<FixedOffset>);
+}
+
+#[cfg(feature = "time")]
+mod time_impl {
+    use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+    use super::*;
+
+    default_impl!(PrimitiveDateTime);
+    default_impl!(OffsetDateTime);
+    default_impl!(Date);
+    default_impl!(Time);
+}
+
+#[cfg(feature = "rust_decimal")]
+default_impl!(rust_decimal::Decimal);
+
+#[cfg(feature = "uuid")]
+impl<'a> FromSqlExt<'a> for uuid::Uuid {
+    fn from_sql_nullable_ext(ty: &Type, (range, buf): (&Range<usize>, &'a Bytes)) -> Result<Self, FromSqlError> {
+        match buf.get(range.clone()) {
+            Some(raw) => <[u8; 16]>::try_from(raw)
+                .map(uuid::Uuid::from_bytes)
+                .map_err(|_| "invalid uuid length".into()),
+            None => <Self as FromSql>::from_sql_null(ty),
+        }
+    }
+
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::UUID
+    }
+}
+
 impl<'a> FromSqlExt<'a> for BytesStr {
     fn from_sql_nullable_ext(ty: &Type, (range, buf): (&Range<usize>, &'a Bytes)) -> Result<Self, FromSqlError> {
         // copy/paste from postgres-protocol dependency.
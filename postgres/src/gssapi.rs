@@ -0,0 +1,82 @@
+//! Kerberos/GSSAPI authentication.
+//!
+//! xitca-postgres drives the GSSAPI token exchange with database server and delegate the security context
+//! establishment to a user provided [Gssapi] implementation. It can be a binding to system GSSAPI library or
+//! a pure rust Kerberos implementation.
+//!
+//! # Examples
+//! ```rust
+//! use std::{error::Error, sync::Arc};
+//!
+//! use xitca_postgres::{
+//!     gssapi::{Gssapi, GssapiContext},
+//!     Config,
+//! };
+//!
+//! struct MyGssapi;
+//!
+//! impl Gssapi for MyGssapi {
+//!     fn init_context(&self, target: &str) -> Result<Box<dyn GssapiContext>, Box<dyn Error + Send + Sync>> {
+//!         // target is in form of "service@host". e.g: "postgres@db.example.com"
+//!         Ok(Box::new(MyContext))
+//!     }
+//! }
+//!
+//! struct MyContext;
+//!
+//! impl GssapiContext for MyContext {
+//!     fn step(&mut self, token: Option<&[u8]>) -> Result<Option<Vec<u8>>, Box<dyn Error + Send + Sync>> {
+//!         todo!("feed token from server to security context and return the output token")
+//!     }
+//! }
+//!
+//! let mut cfg = Config::try_from("postgres://postgres@localhost/postgres?krbsrvname=postgres").unwrap();
+//! cfg.gssapi(Arc::new(MyGssapi));
+//! ```
+
+use std::{error, sync::Arc};
+
+use crate::{
+    config::Config,
+    error::{AuthenticationError, Error},
+};
+
+/// provider of GSSAPI client side security context.
+pub trait Gssapi: Send + Sync {
+    /// initiate a security context for given target service principal in form of `service@host`.
+    fn init_context(&self, target: &str) -> Result<Box<dyn GssapiContext>, Box<dyn error::Error + Send + Sync>>;
+}
+
+/// client side GSSAPI security context.
+pub trait GssapiContext: Send {
+    /// advance security context with token received from server. token is `None` for the initial step.
+    ///
+    /// output token is sent to server when returned `Some`. return `None` when context is established and no
+    /// more token needs to be sent.
+    fn step(&mut self, token: Option<&[u8]>) -> Result<Option<Vec<u8>>, Box<dyn error::Error + Send + Sync>>;
+}
+
+// gssapi provider compared by identity.
+#[derive(Clone)]
+pub(crate) struct GssapiProvider(pub(crate) Arc<dyn Gssapi>);
+
+impl PartialEq for GssapiProvider {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for GssapiProvider {}
+
+pub(crate) fn init_context(cfg: &Config, host: Option<&str>) -> Result<Box<dyn GssapiContext>, Error> {
+    let provider = cfg
+        .gssapi
+        .as_ref()
+        .ok_or_else(|| AuthenticationError::Gssapi("gssapi provider is not configured".into()))?;
+    let host = host.ok_or_else(|| AuthenticationError::Gssapi("host name is required for gssapi".into()))?;
+    let target = format!("{}@{host}", cfg.get_krb_srvname());
+    provider
+        .0
+        .init_context(&target)
+        .map_err(|e| AuthenticationError::Gssapi(e).into())
+}
@@ -0,0 +1,212 @@
+//! large object support with server side lo functions.
+//!
+//! Large object is stored in `pg_largeobject` system table and referenced by it's [Oid]. Content of it is
+//! accessed through a descriptor opened by [Client::lo_open] and the descriptor is only valid within the
+//! transaction it's opened. Large object API must be used inside a transaction.
+//!
+//! # Examples
+//! ```rust
+//! use std::io::SeekFrom;
+//!
+//! use xitca_postgres::{large_object::Mode, Client, Error};
+//!
+//! async fn large_object(client: &mut Client) -> Result<(), Error> {
+//!     let tx = client.transaction().await?;
+//!
+//!     // create a new large object with server assigned oid.
+//!     let oid = tx.lo_create(0).await?;
+//!
+//!     let mut lo = tx.lo_open(oid, Mode::ReadWrite).await?;
+//!     lo.write(b"such large much object").await?;
+//!     lo.seek(SeekFrom::Start(5)).await?;
+//!     assert_eq!(lo.read(5).await?, b"large");
+//!     lo.close().await?;
+//!
+//!     tx.commit().await
+//! }
+//! ```
+
+use core::fmt;
+
+use std::io::SeekFrom;
+
+use postgres_types::Oid;
+
+use crate::{client::Client, error::Error, iter::AsyncLendingIterator, transaction::Transaction, FromSql, ToSql, Type};
+
+// flags of lo_open function. see libpq-fs.h
+const INV_WRITE: i32 = 0x0002_0000;
+const INV_READ: i32 = 0x0004_0000;
+
+/// access mode of opened large object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// read only. content is a snapshot as of the start of current transaction.
+    Read,
+    /// write only. reading is not allowed.
+    Write,
+    /// read and write. reading sees content written by current transaction.
+    ReadWrite,
+}
+
+impl Mode {
+    const fn flag(self) -> i32 {
+        match self {
+            Self::Read => INV_READ,
+            Self::Write => INV_WRITE,
+            Self::ReadWrite => INV_READ | INV_WRITE,
+        }
+    }
+}
+
+impl Client {
+    /// create a new large object and return it's oid. When given oid is 0 server assigns an unused oid.
+    ///
+    /// See [module](crate::large_object) level doc for detail.
+    pub async fn lo_create(&self, oid: Oid) -> Result<Oid, Error> {
+        lo_call(self, "SELECT pg_catalog.lo_create($1)", &[(&oid, Type::OID)]).await
+    }
+
+    /// open large object with given oid and access mode.
+    ///
+    /// See [module](crate::large_object) level doc for detail.
+    pub async fn lo_open(&self, oid: Oid, mode: Mode) -> Result<LargeObject<'_>, Error> {
+        let fd = lo_call(
+            self,
+            "SELECT pg_catalog.lo_open($1, $2)",
+            &[(&oid, Type::OID), (&mode.flag(), Type::INT4)],
+        )
+        .await?;
+        Ok(LargeObject { client: self, fd })
+    }
+
+    /// remove large object with given oid from database.
+    pub async fn lo_unlink(&self, oid: Oid) -> Result<(), Error> {
+        lo_call::<i32>(self, "SELECT pg_catalog.lo_unlink($1)", &[(&oid, Type::OID)])
+            .await
+            .map(|_| ())
+    }
+}
+
+impl Transaction<'_> {
+    /// [Client::lo_create] for transaction.
+    #[inline]
+    pub async fn lo_create(&self, oid: Oid) -> Result<Oid, Error> {
+        self.client().lo_create(oid).await
+    }
+
+    /// [Client::lo_open] for transaction.
+    #[inline]
+    pub async fn lo_open(&self, oid: Oid, mode: Mode) -> Result<LargeObject<'_>, Error> {
+        self.client().lo_open(oid, mode).await
+    }
+
+    /// [Client::lo_unlink] for transaction.
+    #[inline]
+    pub async fn lo_unlink(&self, oid: Oid) -> Result<(), Error> {
+        self.client().lo_unlink(oid).await
+    }
+}
+
+/// descriptor of opened large object. produced by [Client::lo_open].
+///
+/// Every operation is a round trip to database. Prefer reading and writing in large chunks.
+///
+/// Descriptor is closed by database at the end of transaction. Call [LargeObject::close] for closing it early.
+pub struct LargeObject<'a> {
+    client: &'a Client,
+    fd: i32,
+}
+
+impl fmt::Debug for LargeObject<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LargeObject").field("fd", &self.fd).finish()
+    }
+}
+
+impl LargeObject<'_> {
+    /// read at most `len` bytes from current position and advance position by the count of bytes read.
+    /// Returned bytes are shorter than `len` when reaching the end of large object.
+    pub async fn read(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+        let len = i32::try_from(len).unwrap_or(i32::MAX);
+        self.call(
+            "SELECT pg_catalog.loread($1, $2)",
+            &[(&self.fd, Type::INT4), (&len, Type::INT4)],
+        )
+        .await
+    }
+
+    /// write bytes to current position and advance position by the count of bytes written.
+    /// Return the count of bytes written.
+    pub async fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.call::<i32>(
+            "SELECT pg_catalog.lowrite($1, $2)",
+            &[(&self.fd, Type::INT4), (&buf, Type::BYTEA)],
+        )
+        .await
+        .map(|n| n as usize)
+    }
+
+    /// change current position and return the new position from the start of large object.
+    pub async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        // whence values are the same as SEEK_SET, SEEK_CUR and SEEK_END.
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (i64::try_from(offset).unwrap_or(i64::MAX), 0i32),
+            SeekFrom::Current(offset) => (offset, 1),
+            SeekFrom::End(offset) => (offset, 2),
+        };
+        self.call::<i64>(
+            "SELECT pg_catalog.lo_lseek64($1, $2, $3)",
+            &[(&self.fd, Type::INT4), (&offset, Type::INT8), (&whence, Type::INT4)],
+        )
+        .await
+        .map(|pos| pos as u64)
+    }
+
+    /// return current position from the start of large object.
+    pub async fn tell(&mut self) -> Result<u64, Error> {
+        self.call::<i64>("SELECT pg_catalog.lo_tell64($1)", &[(&self.fd, Type::INT4)])
+            .await
+            .map(|pos| pos as u64)
+    }
+
+    /// truncate or extend large object to given length. Extended part is filled with zero bytes.
+    pub async fn truncate(&mut self, len: u64) -> Result<(), Error> {
+        let len = i64::try_from(len).unwrap_or(i64::MAX);
+        self.call::<i32>(
+            "SELECT pg_catalog.lo_truncate64($1, $2)",
+            &[(&self.fd, Type::INT4), (&len, Type::INT8)],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// close descriptor of large object.
+    pub async fn close(self) -> Result<(), Error> {
+        self.call::<i32>("SELECT pg_catalog.lo_close($1)", &[(&self.fd, Type::INT4)])
+            .await
+            .map(|_| ())
+    }
+
+    async fn call<T>(&self, stmt: &str, params: &[(&(dyn ToSql + Sync), Type)]) -> Result<T, Error>
+    where
+        T: for<'r> FromSql<'r>,
+    {
+        lo_call(self.client, stmt, params).await
+    }
+}
+
+// lo functions return a single row with single column.
+async fn lo_call<T>(client: &Client, stmt: &str, params: &[(&(dyn ToSql + Sync), Type)]) -> Result<T, Error>
+where
+    T: for<'r> FromSql<'r>,
+{
+    let mut stream = client.query_typed(stmt, params).await?;
+    let res = match stream.try_next().await? {
+        Some(row) => row.try_get_raw(0)?,
+        None => return Err(Error::unexpected()),
+    };
+    // drain the stream until ReadyForQuery.
+    while stream.try_next().await?.is_some() {}
+    Ok(res)
+}
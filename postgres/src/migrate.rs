@@ -0,0 +1,367 @@
+//! minimal schema migration running over [Client] and [SharedClient].
+//!
+//! Migrations are plain sql strings with a version number. Sql files can be embedded into binary with
+//! [include_str]. Applied migrations are tracked in a table of database together with the checksum of their sql so
+//! modification of applied migration can be detected.
+//!
+//! Every migration is executed as a single simple query batch together with the insert of it's tracking record.
+//! Postgres runs such batch in an implicit transaction so a failed migration leaves no trace. Migration sql must not
+//! manage transaction by itself.
+//!
+//! # Examples
+//! ```rust
+//! use xitca_postgres::{migrate::{Migration, Migrator}, Client, Error};
+//!
+//! async fn migrate(client: &Client) -> Result<(), Error> {
+//!     let migrator = Migrator::new()
+//!         .migration(Migration::new(1, "create_users", "CREATE TABLE users (id SERIAL PRIMARY KEY, name TEXT)"))
+//!         // sql file relative to current source file.
+//!         // .migration(Migration::new(2, "add_age", include_str!("../migrations/0002_add_age.sql")))
+//!         .migration(Migration::new(2, "add_age", "ALTER TABLE users ADD COLUMN age INT"));
+//!
+//!     // error when applied migrations are modified or missing.
+//!     migrator.verify(client).await?;
+//!
+//!     for status in migrator.list(client).await? {
+//!         println!("{} {} {:?}", status.version(), status.name(), status.state());
+//!     }
+//!
+//!     // apply pending migrations in order of version.
+//!     let applied = migrator.apply(client).await?;
+//!     println!("applied migrations: {applied:?}");
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use core::{fmt::Write, future::Future};
+
+use std::borrow::Cow;
+
+use super::{
+    client::Client,
+    error::{Error, MigrateError},
+    iter::AsyncLendingIterator,
+    pool::SharedClient,
+    query::SimpleResultSets,
+};
+
+/// client type migration can be running on.
+pub trait MigrateClient: sealed::Sealed {
+    #[doc(hidden)]
+    fn batch(&self, stmt: &str) -> impl Future<Output = Result<SimpleResultSets, Error>> + Send;
+}
+
+impl MigrateClient for Client {
+    fn batch(&self, stmt: &str) -> impl Future<Output = Result<SimpleResultSets, Error>> + Send {
+        let res = self.query_simple_sets(stmt);
+        async { res }
+    }
+}
+
+impl MigrateClient for SharedClient {
+    async fn batch(&self, stmt: &str) -> Result<SimpleResultSets, Error> {
+        self.query_simple_sets(stmt).await
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::Client {}
+    impl Sealed for crate::SharedClient {}
+}
+
+/// a single migration with version, name and sql.
+#[derive(Clone, Debug)]
+pub struct Migration {
+    version: i64,
+    name: Cow<'static, str>,
+    sql: Cow<'static, str>,
+}
+
+impl Migration {
+    /// construct a new migration. version determines the order of applying.
+    pub fn new(version: i64, name: impl Into<Cow<'static, str>>, sql: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            version,
+            name: name.into(),
+            sql: sql.into(),
+        }
+    }
+
+    /// version of migration.
+    pub fn version(&self) -> i64 {
+        self.version
+    }
+
+    /// name of migration.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// sql of migration.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// checksum of migration's sql in hex string form. checksum is stable across platforms and compiler versions.
+    pub fn checksum(&self) -> String {
+        // 64 bit FNV-1a
+        let hash = self.sql.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100_0000_01b3)
+        });
+        format!("{hash:016x}")
+    }
+}
+
+/// state of migration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationState {
+    /// migration is applied.
+    Applied,
+    /// migration is not applied yet.
+    Pending,
+    /// migration is applied but it's sql is modified afterwards.
+    Modified,
+    /// migration is applied but it's missing from local migrations.
+    Missing,
+}
+
+/// status of migration produced by [Migrator::list].
+#[derive(Clone, Debug)]
+pub struct MigrationStatus {
+    version: i64,
+    name: String,
+    state: MigrationState,
+}
+
+impl MigrationStatus {
+    /// version of migration.
+    pub fn version(&self) -> i64 {
+        self.version
+    }
+
+    /// name of migration.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// state of migration.
+    pub fn state(&self) -> MigrationState {
+        self.state
+    }
+}
+
+/// collection of migrations and the runner of them.
+pub struct Migrator {
+    migrations: Vec<Migration>,
+    table: Cow<'static, str>,
+}
+
+impl Default for Migrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Migrator {
+    /// construct an empty migrator with `_xitca_migrations` as tracking table.
+    pub fn new() -> Self {
+        Self {
+            migrations: Vec::new(),
+            table: Cow::Borrowed("_xitca_migrations"),
+        }
+    }
+
+    /// set name of tracking table. name can be schema qualified. e.g: `my_schema.migrations`
+    ///
+    /// # Panics
+    /// panic when name is empty or contains characters other than ascii alphanumeric, `_` and `.`.
+    pub fn table(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        let name = name.into();
+        assert!(
+            !name.is_empty()
+                && name
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'.'),
+            "{name} is not a valid migration table name"
+        );
+        self.table = name;
+        self
+    }
+
+    /// add migration to migrator.
+    ///
+    /// # Panics
+    /// panic when migration with the same version is already added.
+    pub fn migration(mut self, migration: Migration) -> Self {
+        let idx = match self.migrations.binary_search_by_key(&migration.version, |m| m.version) {
+            Ok(_) => panic!("migration version {} already exists", migration.version),
+            Err(idx) => idx,
+        };
+        self.migrations.insert(idx, migration);
+        self
+    }
+
+    /// list status of local and applied migrations in order of version.
+    pub async fn list<C>(&self, client: &C) -> Result<Vec<MigrationStatus>, Error>
+    where
+        C: MigrateClient,
+    {
+        let applied = self.applied(client).await?;
+        Ok(self.status(applied))
+    }
+
+    /// verify applied migrations are not modified or missing from local migrations.
+    pub async fn verify<C>(&self, client: &C) -> Result<(), Error>
+    where
+        C: MigrateClient,
+    {
+        self.list(client).await.and_then(|list| check(&list))
+    }
+
+    /// apply pending migrations in order of version and return the versions applied. applied migrations are verified
+    /// before applying and a session level advisory lock is held while applying to prevent concurrent runners.
+    pub async fn apply<C>(&self, client: &C) -> Result<Vec<i64>, Error>
+    where
+        C: MigrateClient,
+    {
+        let lock = format!("hashtext({})", quote(&self.table));
+        drain(client.batch(&format!("SELECT pg_advisory_lock({lock})")).await?).await?;
+
+        let res = self._apply(client).await;
+
+        let unlock = drain(client.batch(&format!("SELECT pg_advisory_unlock({lock})")).await?).await;
+
+        res.and_then(|applied| unlock.map(|_| applied))
+    }
+
+    async fn _apply<C>(&self, client: &C) -> Result<Vec<i64>, Error>
+    where
+        C: MigrateClient,
+    {
+        let list = self.list(client).await?;
+        check(&list)?;
+
+        let mut applied = Vec::new();
+
+        for status in list.iter().filter(|s| s.state == MigrationState::Pending) {
+            let migration = self
+                .migrations
+                .iter()
+                .find(|m| m.version == status.version)
+                .expect("pending migration must be local");
+
+            let mut batch = String::from(migration.sql());
+            // new line prevents trailing comment of migration sql from commenting out the insert.
+            write!(
+                batch,
+                "\n;\nINSERT INTO {} (version, name, checksum) VALUES ({}, {}, {})",
+                self.table,
+                migration.version,
+                quote(migration.name()),
+                quote(&migration.checksum())
+            )
+            .unwrap();
+
+            drain(client.batch(&batch).await?).await?;
+
+            applied.push(migration.version);
+        }
+
+        Ok(applied)
+    }
+
+    async fn applied<C>(&self, client: &C) -> Result<Vec<(i64, String, String)>, Error>
+    where
+        C: MigrateClient,
+    {
+        let stmt = format!(
+            "CREATE TABLE IF NOT EXISTS {table} (\
+                version BIGINT PRIMARY KEY, \
+                name TEXT NOT NULL, \
+                checksum TEXT NOT NULL, \
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT now()\
+            );\
+            SELECT version, name, checksum FROM {table} ORDER BY version",
+            table = self.table
+        );
+
+        let mut sets = client.batch(&stmt).await?;
+        let mut applied = Vec::new();
+
+        while let Some(mut set) = sets.try_next().await? {
+            while let Some(row) = set.try_next().await? {
+                let version = row
+                    .try_get(0)?
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(Error::unexpected)?;
+                let name = row.try_get(1)?.unwrap_or_default().to_owned();
+                let checksum = row.try_get(2)?.unwrap_or_default().to_owned();
+                applied.push((version, name, checksum));
+            }
+        }
+
+        Ok(applied)
+    }
+
+    fn status(&self, applied: Vec<(i64, String, String)>) -> Vec<MigrationStatus> {
+        let mut list = self
+            .migrations
+            .iter()
+            .map(|m| {
+                let state = match applied.iter().find(|(v, ..)| *v == m.version) {
+                    Some((.., checksum)) if *checksum == m.checksum() => MigrationState::Applied,
+                    Some(_) => MigrationState::Modified,
+                    None => MigrationState::Pending,
+                };
+                MigrationStatus {
+                    version: m.version,
+                    name: m.name().to_owned(),
+                    state,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        list.extend(
+            applied
+                .into_iter()
+                .filter(|(v, ..)| !self.migrations.iter().any(|m| m.version == *v))
+                .map(|(version, name, _)| MigrationStatus {
+                    version,
+                    name,
+                    state: MigrationState::Missing,
+                }),
+        );
+
+        list.sort_by_key(|s| s.version);
+        list
+    }
+}
+
+fn check(list: &[MigrationStatus]) -> Result<(), Error> {
+    list.iter().try_for_each(|s| match s.state {
+        MigrationState::Modified => Err(MigrateError::Modified {
+            version: s.version,
+            name: s.name.clone(),
+        }
+        .into()),
+        MigrationState::Missing => Err(MigrateError::Missing {
+            version: s.version,
+            name: s.name.clone(),
+        }
+        .into()),
+        MigrationState::Applied | MigrationState::Pending => Ok(()),
+    })
+}
+
+async fn drain(mut sets: SimpleResultSets) -> Result<(), Error> {
+    while sets.try_next().await?.is_some() {}
+    Ok(())
+}
+
+// quote string as sql literal.
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
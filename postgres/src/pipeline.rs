@@ -1,4 +1,7 @@
-use core::ops::{Deref, DerefMut, Range};
+use core::{
+    ops::{Deref, DerefMut, Range},
+    time::Duration,
+};
 
 use postgres_protocol::message::{backend, frontend};
 use xitca_io::bytes::BytesMut;
@@ -297,6 +300,73 @@ impl Client {
     }
 }
 
+/// configuration of auto pipelining mode. see [Client::set_auto_pipeline] for detail.
+#[derive(Clone, Copy, Debug)]
+pub struct AutoPipeline {
+    pub(crate) window: Duration,
+    pub(crate) shared_sync: bool,
+}
+
+impl AutoPipeline {
+    /// construct auto pipelining mode with given batching window. requests sent within the window after the
+    /// first one are written to database together with one flush.
+    ///
+    /// a short window (tens to hundreds of microseconds) is recommended as every request is delayed by it.
+    pub const fn new(window: Duration) -> Self {
+        Self {
+            window,
+            shared_sync: false,
+        }
+    }
+
+    /// share one sync message between concurrent queries of the same batch instead of one sync per query.
+    ///
+    /// with shared sync database server sees the queries as one implicit transaction like [Pipeline::unsync].
+    /// when one of the queries failed effects of the batch are rolled back and the following queries of it are
+    /// skipped by server and return error with [SqlState::IN_FAILED_SQL_TRANSACTION] code. query that is not part
+    /// of a transaction and tolerates failure caused by other queries can use it for potential performance gain.
+    ///
+    /// only [Client::query], [Client::execute] and their variants share sync message. other requests are always
+    /// sent with their own sync.
+    ///
+    /// [SqlState::IN_FAILED_SQL_TRANSACTION]: crate::error::SqlState::IN_FAILED_SQL_TRANSACTION
+    pub const fn shared_sync(mut self) -> Self {
+        self.shared_sync = true;
+        self
+    }
+}
+
+impl Client {
+    /// enable auto pipelining mode with [AutoPipeline] or disable it with [Option::None]. disabled by default.
+    ///
+    /// in auto pipelining mode concurrent requests on the same client are coalesced into one write to socket
+    /// without manual [Pipeline] building. it improves throughput of highly concurrent workload using a shared
+    /// client at the cost of added latency of batching window.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use xitca_postgres::{pipeline::AutoPipeline, Client, Error};
+    ///
+    /// async fn concurrent(client: &Client) -> Result<(), Error> {
+    ///     client.set_auto_pipeline(Some(AutoPipeline::new(Duration::from_micros(50)).shared_sync()));
+    ///
+    ///     let stmt = client.prepare("SELECT 1", &[]).await?;
+    ///
+    ///     // concurrent queries are written to database together.
+    ///     let (a, b) = tokio::join!(client.execute(stmt.as_ref(), &[]), client.execute(stmt.as_ref(), &[]));
+    ///     a?;
+    ///     b?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_auto_pipeline(&self, auto: Option<AutoPipeline>) {
+        self.tx.set_auto_pipeline(auto);
+    }
+}
+
 /// streaming response of pipeline.
 /// impl [AsyncLendingIterator] trait and can be collected asynchronously.
 pub struct PipelineStream<'a> {
@@ -341,7 +411,10 @@ impl<'a> Columns<'a> {
 }
 
 impl<'a> AsyncLendingIterator for PipelineStream<'a> {
-    type Ok<'i> = PipelineItem<'i> where Self: 'i;
+    type Ok<'i>
+        = PipelineItem<'i>
+    where
+        Self: 'i;
     type Err = Error;
 
     async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
@@ -405,7 +478,10 @@ impl PipelineItem<'_> {
 }
 
 impl AsyncLendingIterator for PipelineItem<'_> {
-    type Ok<'i> = Row<'i> where Self: 'i;
+    type Ok<'i>
+        = Row<'i>
+    where
+        Self: 'i;
     type Err = Error;
 
     async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
@@ -15,16 +15,97 @@ use crate::{
     driver::codec::Response,
     error::Error,
     iter::AsyncLendingIterator,
-    statement::{Statement, StatementGuarded},
+    statement::{Description, Statement, StatementGuarded, StatementNamed},
     Type,
 };
 
 type BoxedFuture<'a> = Pin<Box<dyn Future<Output = Result<Type, Error>> + Send + 'a>>;
 
 impl Client {
+    /// Prepare a named statement on current connection. The statement is closed when returned guard is dropped.
+    ///
+    /// Named statement is parsed once and reusable for the lifetime of it. For queries executed only once
+    /// consider [Client::query_unnamed] which uses the unnamed statement and keeps no resource on database.
     pub async fn prepare(&self, query: &str, types: &[Type]) -> Result<StatementGuarded<&'_ Self>, Error> {
         self._prepare(query, types).await.map(|stmt| stmt.into_guarded(self))
     }
+
+    /// Prepare a [StatementNamed] on current connection. The prepared [Statement] is cached and live as long as
+    /// the connection. Following calls with the same [StatementNamed] would return the cached statement without
+    /// preparing it again.
+    pub async fn prepare_named(&self, stmt: &StatementNamed) -> Result<Statement, Error> {
+        if let Some(stmt) = self.cached_statements.get(stmt) {
+            return Ok(stmt);
+        }
+
+        let prepared = self._prepare(stmt.stmt(), stmt.types()).await?;
+        let (prepared, duplicate) = self.cached_statements.insert(stmt, prepared);
+
+        if let Some(duplicate) = duplicate {
+            drop(duplicate.into_guarded(self));
+        }
+
+        Ok(prepared)
+    }
+
+    /// Remove a [StatementNamed] prepared by [Client::prepare_named] from cache and close it on current
+    /// connection. Return false when the statement is not prepared on current connection.
+    ///
+    /// Following calls with the same [StatementNamed] would prepare it again. This is useful for freeing database
+    /// resources of rarely used statements. See [Client::query_unnamed] for one-shot queries that don't need a
+    /// named statement at all.
+    pub fn close_named(&self, stmt: &StatementNamed) -> bool {
+        match self.cached_statements.remove(stmt) {
+            Some(stmt) => {
+                drop(stmt.into_guarded(self));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Describe a query without preparing a named statement or executing it. The query is parsed by database as
+    /// unnamed statement and the types of it's parameters and result columns are returned.
+    ///
+    /// This can be used for validating sql queries and inspecting their metadata. e.g: at the start of application.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_postgres::{Client, Error, Type};
+    ///
+    /// async fn validate(cli: &Client) -> Result<(), Error> {
+    ///     let desc = cli.describe("SELECT id, name FROM users WHERE id = $1", &[]).await?;
+    ///     assert_eq!(desc.params(), &[Type::INT4]);
+    ///     assert_eq!(desc.columns()[1].name(), "name");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn describe(&self, query: &str, types: &[Type]) -> Result<Description, Error> {
+        let mut res = self.send_prepare("", query, types)?;
+
+        match res.recv().await? {
+            backend::Message::ParseComplete => {}
+            _ => return Err(Error::unexpected()),
+        }
+
+        let (params, columns) = self.recv_description(&mut res).await?;
+
+        Ok(Description::new(params, columns))
+    }
+
+    /// Describe a prepared [Statement] from database. Unlike [Statement::params] and [Statement::columns] which
+    /// are collected when statement is prepared the metadata is queried from database again.
+    pub async fn describe_statement(&self, stmt: &Statement) -> Result<Description, Error> {
+        let mut res = self.tx.send_with(|buf| {
+            frontend::describe(b'S', stmt.name(), buf)?;
+            frontend::sync(buf);
+            Ok(())
+        })?;
+
+        let (params, columns) = self.recv_description(&mut res).await?;
+
+        Ok(Description::new(params, columns))
+    }
 }
 
 impl Client {
@@ -43,6 +124,12 @@ impl Client {
             _ => return Err(Error::unexpected()),
         }
 
+        let (parameters, columns) = self.recv_description(&mut res).await?;
+
+        Ok(Statement::new(name, parameters, columns))
+    }
+
+    async fn recv_description(&self, res: &mut Response) -> Result<(Vec<Type>, Vec<Column>), Error> {
         let parameter_description = match res.recv().await? {
             backend::Message::ParameterDescription(body) => body,
             _ => return Err(Error::unexpected()),
@@ -71,12 +158,18 @@ impl Client {
             }
         }
 
-        Ok(Statement::new(name, parameters, columns))
+        Ok((parameters, columns))
     }
 
+    /// Get [Type] of given OID. Built-in types are returned as is. User defined types(enum, composite, domain,
+    /// array and range of them) are looked up from database catalog and cached for the lifetime of [Client].
+    ///
+    /// Statement preparing calls this method for every parameter and column type so user defined types work with
+    /// [FromSql](crate::FromSql) and [ToSql](crate::ToSql) implementations out of the box. Cached types can be
+    /// cleared with [Client::clear_type_cache] when they are altered in database.
     // get type is called recursively so a boxed future is needed.
     #[inline(never)]
-    fn get_type(&self, oid: Oid) -> BoxedFuture<'_> {
+    pub fn get_type(&self, oid: Oid) -> BoxedFuture<'_> {
         Box::pin(async move {
             if let Some(ty) = Type::from_oid(oid).or_else(|| self.type_(oid)) {
                 return Ok(ty);
@@ -123,6 +216,32 @@ impl Client {
         })
     }
 
+    /// Get [Type] of given type name. The name is resolved with database's `search_path` and can be schema
+    /// qualified. See [Client::get_type] for detail.
+    ///
+    /// This is useful for preparing statement with explicit parameter types of user defined types.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_postgres::{Client, Error};
+    ///
+    /// async fn prepare(cli: &Client) -> Result<(), Error> {
+    ///     // CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy');
+    ///     let mood = cli.get_type_by_name("mood").await?;
+    ///     let _stmt = cli.prepare("SELECT $1", &[mood]).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_type_by_name(&self, name: &str) -> Result<Type, Error> {
+        let stmt = self.typeinfo_name_statement().await?;
+
+        let mut rows = self.query_raw(&stmt, &[&name]).await?;
+        let row = rows.try_next().await?.ok_or_else(Error::unexpected)?;
+        let oid = row.try_get::<Oid>(0)?;
+
+        self.get_type(oid).await
+    }
+
     #[inline(never)]
     async fn get_enum_variants(&self, oid: Oid) -> Result<Vec<String>, Error> {
         let stmt = self.typeinfo_enum_statement().await?;
@@ -210,6 +329,19 @@ impl Client {
         Ok(stmt)
     }
 
+    #[inline(never)]
+    async fn typeinfo_name_statement(&self) -> Result<Statement, Error> {
+        if let Some(stmt) = self.typeinfo_name() {
+            return Ok(stmt);
+        }
+
+        let stmt = self._prepare(TYPEINFO_NAME_QUERY, &[]).await?;
+
+        self.set_typeinfo_name(&stmt);
+
+        Ok(stmt)
+    }
+
     fn send_prepare(&self, name: &str, query: &str, types: &[Type]) -> Result<Response, Error> {
         if types.is_empty() {
             debug!("preparing query {}: {}", name, query);
@@ -267,3 +399,5 @@ AND NOT attisdropped
 AND attnum > 0
 ORDER BY attnum
 ";
+
+const TYPEINFO_NAME_QUERY: &str = "SELECT $1::text::regtype::oid";
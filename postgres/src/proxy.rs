@@ -1,18 +1,48 @@
 //! transparent proxy serves as a sample implementation of server side traffic forwarder
 //! between a xitca-postgres Client with `quic` feature enabled and the postgres database
+//!
+//! # Client authentication
+//! With [Proxy::with_client_auth] client must present a certificate signed by given certificate authority. Client
+//! certificate can be configured with [Config::ssl_cert] and [Config::ssl_key]. Clients are identified by SHA-256
+//! fingerprint of their certificate and [Proxy::acl] can be used to restrict the databases and users a client can
+//! access.
+//!
+//! # Examples
+//! ```rust
+//! use xitca_postgres::proxy::{Acl, Proxy};
+//!
+//! # async fn run() -> Result<(), xitca_postgres::proxy::Error> {
+//! Proxy::with_client_auth("cert.pem", "key.pem", "client_ca.pem")
+//!     // fingerprint can be obtained with: openssl x509 -in client.pem -noout -fingerprint -sha256
+//!     .acl(
+//!         "AB:CD:...",
+//!         Acl::new().database("app").user("app_user").user("app_reader"),
+//!     )
+//!     .run()
+//!     .await
+//! # }
+//! ```
+//!
+//! [Config::ssl_cert]: crate::Config::ssl_cert
+//! [Config::ssl_key]: crate::Config::ssl_key
 
 use core::net::SocketAddr;
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     error, fs,
     io::{self, Read, Write},
     path::Path,
     sync::Arc,
 };
 
-use quinn::{crypto::rustls::QuicServerConfig, Endpoint, Incoming, ServerConfig};
-use tracing::error;
+use quinn::{
+    crypto::rustls::QuicServerConfig,
+    rustls::{pki_types::CertificateDer, server::WebPkiClientVerifier, RootCertStore},
+    Connection, Endpoint, Incoming, RecvStream, SendStream, ServerConfig,
+};
+use sha2::{Digest, Sha256};
+use tracing::{error, info, warn};
 use xitca_io::{
     io::{AsyncIo, Interest},
     net::TcpStream,
@@ -29,6 +59,7 @@ pub struct Proxy {
     upstream_addr: SocketAddr,
     listen_addr: SocketAddr,
     white_list: Option<HashSet<SocketAddr>>,
+    acl: HashMap<String, Acl>,
 }
 
 impl Proxy {
@@ -38,6 +69,7 @@ impl Proxy {
             upstream_addr: SocketAddr::from(([127, 0, 0, 1], 5432)),
             listen_addr: SocketAddr::from(([0, 0, 0, 0], 5433)),
             white_list: None,
+            acl: HashMap::new(),
         }
     }
 
@@ -47,6 +79,13 @@ impl Proxy {
         Self::new(cfg_from_cert(cert, key))
     }
 
+    /// construct a proxy with given single key/cert pair and require client to present a certificate signed by
+    /// certificate authority from given client_ca.
+    /// key/cert/client_ca must be pem format.
+    pub fn with_client_auth(cert: impl AsRef<Path>, key: impl AsRef<Path>, client_ca: impl AsRef<Path>) -> Self {
+        Self::new(cfg_from_client_auth(cert, key, client_ca))
+    }
+
     /// construct a proxy with given [quinn::ServerConfig]
     pub fn with_config(cfg: ServerConfig) -> Self {
         Self::new(Ok(cfg))
@@ -73,6 +112,16 @@ impl Proxy {
         self
     }
 
+    /// set access control for client identified by SHA-256 fingerprint of it's certificate. fingerprint is in hex
+    /// form and can be separated by `:`.
+    ///
+    /// once set client not in the acl list would be rejected from proxy. It's meant to be used together with
+    /// [Proxy::with_client_auth] or a [quinn::ServerConfig] that verifies client certificate.
+    pub fn acl(mut self, fingerprint: impl AsRef<str>, acl: Acl) -> Self {
+        self.acl.insert(normalize_fingerprint(fingerprint.as_ref()), acl);
+        self
+    }
+
     /// start the proxy.
     pub async fn run(self) -> Result<(), Error> {
         let cfg = self.cfg?;
@@ -80,15 +129,23 @@ impl Proxy {
         let listener = Endpoint::server(cfg, self.listen_addr)?;
 
         let addr = self.upstream_addr;
+        let acl = Arc::new(self.acl);
+
         while let Some(conn) = listener.accept().await {
             if let Some(list) = self.white_list.as_ref() {
                 if !list.contains(&conn.remote_address()) {
+                    warn!(
+                        "Proxy rejected connection from {} not in white list",
+                        conn.remote_address()
+                    );
                     continue;
                 }
             }
+            let acl = acl.clone();
             tokio::spawn(async move {
-                if let Err(e) = listen_task(conn, addr).await {
-                    error!("Proxy listen error: {e}");
+                let remote = conn.remote_address();
+                if let Err(e) = listen_task(conn, addr, &acl).await {
+                    error!("Proxy listen error from {remote}: {e}");
                 }
             });
         }
@@ -97,6 +154,42 @@ impl Proxy {
     }
 }
 
+/// access control of a client. By default client can access all databases with all users.
+#[derive(Clone, Debug, Default)]
+pub struct Acl {
+    databases: Option<HashSet<String>>,
+    users: Option<HashSet<String>>,
+}
+
+impl Acl {
+    /// construct an acl allowing all databases and users.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add database client is allowed to access. once set database not in the list would be rejected.
+    pub fn database(mut self, name: impl Into<String>) -> Self {
+        self.databases.get_or_insert_with(HashSet::new).insert(name.into());
+        self
+    }
+
+    /// add user client is allowed to login as. once set user not in the list would be rejected.
+    pub fn user(mut self, name: impl Into<String>) -> Self {
+        self.users.get_or_insert_with(HashSet::new).insert(name.into());
+        self
+    }
+
+    fn is_allowed(&self, user: &str, database: &str) -> bool {
+        fn allow(list: &Option<HashSet<String>>, name: &str) -> bool {
+            match list {
+                Some(list) => list.contains(name),
+                None => true,
+            }
+        }
+        allow(&self.users, user) && allow(&self.databases, database)
+    }
+}
+
 fn cfg_from_cert(cert: impl AsRef<Path>, key: impl AsRef<Path>) -> Result<ServerConfig, Error> {
     let cert = fs::read(cert)?;
     let key = fs::read(key)?;
@@ -116,11 +209,45 @@ fn cfg_from_cert(cert: impl AsRef<Path>, key: impl AsRef<Path>) -> Result<Server
     Ok(ServerConfig::with_crypto(Arc::new(config)))
 }
 
-async fn listen_task(conn: Incoming, addr: SocketAddr) -> Result<(), Error> {
+fn cfg_from_client_auth(
+    cert: impl AsRef<Path>,
+    key: impl AsRef<Path>,
+    client_ca: impl AsRef<Path>,
+) -> Result<ServerConfig, Error> {
+    let cert = fs::read(cert)?;
+    let key = fs::read(key)?;
+    let client_ca = fs::read(client_ca)?;
+
+    let key = rustls_pemfile::pkcs8_private_keys(&mut &*key)
+        .next()
+        .ok_or("private key not found")??;
+    let key = quinn::rustls::pki_types::PrivateKeyDer::from(key);
+
+    let cert = rustls_pemfile::certs(&mut &*cert).collect::<Result<_, _>>()?;
+
+    let mut roots = RootCertStore::empty();
+    for ca in rustls_pemfile::certs(&mut &*client_ca) {
+        roots.add(ca?)?;
+    }
+
+    let verifier = WebPkiClientVerifier::builder(Arc::new(roots)).build()?;
+
+    let mut config = quinn::rustls::ServerConfig::builder()
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(cert, key)?;
+
+    config.alpn_protocols = vec![QUIC_ALPN.to_vec()];
+
+    let config = QuicServerConfig::try_from(config)?;
+
+    Ok(ServerConfig::with_crypto(Arc::new(config)))
+}
+
+async fn listen_task(conn: Incoming, addr: SocketAddr, acl: &HashMap<String, Acl>) -> Result<(), Error> {
     let conn = conn.await?;
 
-    // bridge quic client connection to tcp connection to database.
-    let mut upstream = TcpStream::connect(addr).await?;
+    let remote = conn.remote_address();
+    let fingerprint = peer_fingerprint(&conn);
 
     // the proxy does not multiplex over streams from a quic client connection but it's not a hard
     // requirement.
@@ -128,25 +255,53 @@ async fn listen_task(conn: Incoming, addr: SocketAddr) -> Result<(), Error> {
     // additional bidirectional stream.
     let (mut tx, mut rx) = conn.accept_bi().await?;
 
+    // read the first message from client and check it against acl before connecting to database.
+    let startup = read_startup(&mut rx).await?;
+
+    let (user, database) = match parse_startup(&startup[4..]) {
+        Startup::Login { user, database } => (user, database),
+        // cancel request carries no user and is authorized by the secret key from database.
+        Startup::Cancel => ("", ""),
+        Startup::Unsupported => return reject(&mut tx, remote, "unsupported startup message").await,
+    };
+
+    if !acl.is_empty() {
+        let allowed = fingerprint
+            .as_ref()
+            .and_then(|fingerprint| acl.get(fingerprint))
+            .is_some_and(|acl| user.is_empty() || acl.is_allowed(user, database));
+
+        if !allowed {
+            warn!(
+                "Proxy rejected client {remote} with fingerprint {:?} for user {user:?} on database {database:?}",
+                fingerprint
+            );
+            return reject(&mut tx, remote, "access denied by proxy").await;
+        }
+    }
+
+    info!("Proxy accepted client {remote} with fingerprint {fingerprint:?} for user {user:?} on database {database:?}");
+
+    // bridge quic client connection to tcp connection to database.
+    let mut upstream = TcpStream::connect(addr).await?;
+
+    write_all(&mut upstream, &startup).await?;
+
+    let res = copy(tx, rx, upstream).await;
+
+    info!("Proxy closed client {remote}");
+
+    res
+}
+
+async fn copy(mut tx: SendStream, mut rx: RecvStream, mut upstream: TcpStream) -> Result<(), Error> {
     // loop and copy bytes between the quic stream and tcp socket.
 
     let mut buf = [0; 4096];
 
     loop {
         match rx.read(&mut buf).select(upstream.ready(Interest::READABLE)).await {
-            SelectOutput::A(Ok(Some(len))) => {
-                let mut off = 0;
-                while off != len {
-                    match upstream.write(&buf[off..len]) {
-                        Ok(0) => return Ok(()),
-                        Ok(n) => off += n,
-                        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
-                            upstream.ready(Interest::WRITABLE).await?;
-                        }
-                        Err(e) => return Err(e.into()),
-                    }
-                }
-            }
+            SelectOutput::A(Ok(Some(len))) => write_all(&mut upstream, &buf[..len]).await?,
             SelectOutput::B(Ok(_)) => 'inner: loop {
                 match upstream.read(&mut buf) {
                     Ok(0) => return Ok(()),
@@ -162,6 +317,136 @@ async fn listen_task(conn: Incoming, addr: SocketAddr) -> Result<(), Error> {
     }
 }
 
+async fn write_all(upstream: &mut TcpStream, buf: &[u8]) -> io::Result<()> {
+    let mut off = 0;
+    while off != buf.len() {
+        match upstream.write(&buf[off..]) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => off += n,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                upstream.ready(Interest::WRITABLE).await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+// SHA-256 fingerprint of client's end entity certificate in lower case hex form.
+fn peer_fingerprint(conn: &Connection) -> Option<String> {
+    let certs = conn.peer_identity()?.downcast::<Vec<CertificateDer<'static>>>().ok()?;
+    let cert = certs.first()?;
+    Some(Sha256::digest(cert).iter().map(|b| format!("{b:02x}")).collect())
+}
+
+fn normalize_fingerprint(fingerprint: &str) -> String {
+    fingerprint
+        .chars()
+        .filter(|c| *c != ':')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+// postgres limits the size of startup message to 10000 bytes.
+const MAX_STARTUP_LEN: usize = 10000;
+
+// read length prefixed startup message. the returned buffer includes the length.
+async fn read_startup(rx: &mut RecvStream) -> Result<Vec<u8>, Error> {
+    let mut len = [0; 4];
+    rx.read_exact(&mut len).await?;
+
+    let len = u32::from_be_bytes(len) as usize;
+    if !(8..=MAX_STARTUP_LEN).contains(&len) {
+        return Err("invalid startup message length".into());
+    }
+
+    let mut buf = vec![0; len];
+    buf[..4].copy_from_slice(&(len as u32).to_be_bytes());
+    rx.read_exact(&mut buf[4..]).await?;
+
+    Ok(buf)
+}
+
+#[derive(Debug, PartialEq)]
+enum Startup<'a> {
+    Login { user: &'a str, database: &'a str },
+    Cancel,
+    Unsupported,
+}
+
+const PROTOCOL_VERSION: u32 = 196608;
+const CANCEL_REQUEST_CODE: u32 = 80877102;
+
+// parse startup message body without the length prefix.
+fn parse_startup(body: &[u8]) -> Startup<'_> {
+    let Some((version, mut params)) = body.split_first_chunk::<4>() else {
+        return Startup::Unsupported;
+    };
+
+    match u32::from_be_bytes(*version) {
+        CANCEL_REQUEST_CODE => return Startup::Cancel,
+        PROTOCOL_VERSION => {}
+        _ => return Startup::Unsupported,
+    }
+
+    let mut next = || {
+        let end = params.iter().position(|b| *b == 0)?;
+        let s = core::str::from_utf8(&params[..end]).ok()?;
+        params = &params[end + 1..];
+        Some(s)
+    };
+
+    let (mut user, mut database) = (None, None);
+
+    loop {
+        match next() {
+            Some("") => break,
+            Some(key) => {
+                let Some(value) = next() else {
+                    return Startup::Unsupported;
+                };
+                match key {
+                    "user" => user = Some(value),
+                    "database" => database = Some(value),
+                    _ => {}
+                }
+            }
+            None => return Startup::Unsupported,
+        }
+    }
+
+    match user {
+        // database defaults to user name.
+        Some(user) => Startup::Login {
+            user,
+            database: database.unwrap_or(user),
+        },
+        None => Startup::Unsupported,
+    }
+}
+
+// send error response to client and close the stream.
+async fn reject(tx: &mut SendStream, remote: SocketAddr, msg: &str) -> Result<(), Error> {
+    let mut fields = Vec::new();
+    for (field, value) in [(b'S', "FATAL"), (b'V', "FATAL"), (b'C', "28000"), (b'M', msg)] {
+        fields.push(field);
+        fields.extend_from_slice(value.as_bytes());
+        fields.push(0);
+    }
+    fields.push(0);
+
+    let mut buf = vec![b'E'];
+    buf.extend_from_slice(&(fields.len() as u32 + 4).to_be_bytes());
+    buf.extend_from_slice(&fields);
+
+    tx.write_all(&buf).await?;
+    tx.finish()?;
+
+    info!("Proxy closed client {remote}: {msg}");
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -173,6 +458,54 @@ mod test {
             .upstream_addr(addr)
             .listen_addr(addr)
             .white_list(vec![addr])
-            .white_list([addr]);
+            .white_list([addr])
+            .acl("AB:cd:01", Acl::new().database("db").user("user"));
+    }
+
+    #[test]
+    fn acl() {
+        assert_eq!(normalize_fingerprint("AB:cd:01"), "abcd01");
+
+        let acl = Acl::new();
+        assert!(acl.is_allowed("user", "db"));
+
+        let acl = Acl::new().database("db").user("user");
+        assert!(acl.is_allowed("user", "db"));
+        assert!(!acl.is_allowed("user", "db2"));
+        assert!(!acl.is_allowed("user2", "db"));
+    }
+
+    #[test]
+    fn startup() {
+        let mut buf = xitca_io::bytes::BytesMut::new();
+        postgres_protocol::message::frontend::startup_message([("user", "foo"), ("database", "bar")], &mut buf)
+            .unwrap();
+        assert_eq!(
+            parse_startup(&buf[4..]),
+            Startup::Login {
+                user: "foo",
+                database: "bar"
+            }
+        );
+
+        buf.clear();
+        postgres_protocol::message::frontend::startup_message([("user", "foo")], &mut buf).unwrap();
+        assert_eq!(
+            parse_startup(&buf[4..]),
+            Startup::Login {
+                user: "foo",
+                database: "foo"
+            }
+        );
+
+        buf.clear();
+        postgres_protocol::message::frontend::cancel_request(1, 2, &mut buf);
+        assert_eq!(parse_startup(&buf[4..]), Startup::Cancel);
+
+        buf.clear();
+        postgres_protocol::message::frontend::ssl_request(&mut buf);
+        assert_eq!(parse_startup(&buf[4..]), Startup::Unsupported);
+
+        assert_eq!(parse_startup(&[0, 3, 0, 0, b'u']), Startup::Unsupported);
     }
 }
@@ -1,9 +1,15 @@
 mod base;
+mod bulk;
+mod execute;
 mod row_stream;
 mod simple;
+mod typed;
 
 pub(crate) mod decode;
 pub(crate) mod encode;
 
 pub use base::RowStream;
-pub use simple::RowSimpleStream;
+pub use execute::{CommandTag, ExecuteResult};
+pub use row_stream::RowMapStream;
+pub use simple::{RowSimpleStream, SimpleResultSet, SimpleResultSets};
+pub use typed::RowStreamOwned;
@@ -9,10 +9,12 @@ use crate::{
     error::Error,
     iter::{slice_iter, AsyncLendingIterator},
     row::Row,
-    statement::Statement,
+    statement::{Statement, StatementNamed},
     BorrowToSql, ToSql,
 };
 
+use super::RowStreamOwned;
+
 use super::row_stream::GenericRowStream;
 
 impl Client {
@@ -112,7 +114,46 @@ impl Client {
         async { res?.try_into_row_affected().await }
     }
 
-    fn send_encode<I>(&self, stmt: &Statement, params: I) -> Result<Response, Error>
+    /// Executes a [StatementNamed], returning a stream of the resulting rows.
+    ///
+    /// The statement is prepared on current connection on first use and cached for later use.
+    /// See [Client::prepare_named] for detail.
+    ///
+    /// # Panics
+    ///
+    /// Panics if given params slice length does not match the parameter count of the statement.
+    pub async fn query_named(
+        &self,
+        stmt: &StatementNamed,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<RowStreamOwned, Error> {
+        let stmt = self.prepare_named(stmt).await?;
+        let mut res = self.send_encode(&stmt, slice_iter(params))?;
+        match res.recv().await? {
+            backend::Message::BindComplete => Ok(RowStreamOwned {
+                col: stmt.into_columns(),
+                res,
+                ranges: Vec::new(),
+            }),
+            _ => Err(Error::unexpected()),
+        }
+    }
+
+    /// Executes a [StatementNamed], returning the number of rows modified.
+    ///
+    /// The statement is prepared on current connection on first use and cached for later use.
+    /// See [Client::prepare_named] for detail.
+    ///
+    /// # Panics
+    ///
+    /// Panics if given params slice length does not match the parameter count of the statement.
+    pub async fn execute_named(&self, stmt: &StatementNamed, params: &[&(dyn ToSql + Sync)]) -> Result<u64, Error> {
+        let stmt = self.prepare_named(stmt).await?;
+        let res = self.send_encode(&stmt, slice_iter(params))?;
+        res.try_into_row_affected().await
+    }
+
+    pub(super) fn send_encode<I>(&self, stmt: &Statement, params: I) -> Result<Response, Error>
     where
         I: IntoIterator,
         I::IntoIter: ExactSizeIterator,
@@ -120,7 +161,23 @@ impl Client {
     {
         let params = params.into_iter();
         stmt.params_assert(&params);
-        self.tx.send_with(|buf| super::encode::encode(buf, stmt, params))
+
+        #[cfg(feature = "tracing")]
+        let trace = crate::trace::QueryTrace::new(stmt.name(), params.len(), self.slow_query);
+
+        #[cfg_attr(not(feature = "tracing"), allow(unused_mut))]
+        let mut res = self.tx.send_query_with(|buf, sync| {
+            if sync {
+                super::encode::encode(buf, stmt, params)
+            } else {
+                super::encode::encode_maybe_sync::<_, false>(buf, stmt, params)
+            }
+        })?;
+
+        #[cfg(feature = "tracing")]
+        res.set_trace(trace);
+
+        Ok(res)
     }
 }
 
@@ -146,6 +203,7 @@ impl Response {
             match self.recv().await? {
                 backend::Message::RowDescription(_)
                 | backend::Message::DataRow(_)
+                | backend::Message::CommandComplete(_)
                 | backend::Message::EmptyQueryResponse => {}
                 backend::Message::ReadyForQuery(_) => return Ok(()),
                 _ => return Err(Error::unexpected()),
@@ -158,7 +216,10 @@ impl Response {
 pub type RowStream<'a> = GenericRowStream<&'a [Column]>;
 
 impl<'a> AsyncLendingIterator for RowStream<'a> {
-    type Ok<'i> = Row<'i> where Self: 'i;
+    type Ok<'i>
+        = Row<'i>
+    where
+        Self: 'i;
     type Err = Error;
 
     async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
@@ -0,0 +1,176 @@
+use core::fmt::Write;
+
+use postgres_protocol::message::{backend, frontend};
+use postgres_types::IsNull;
+use xitca_io::bytes::{BufMut, BytesMut};
+
+use crate::{client::Client, error::Error, BorrowToSql, ToSql, Type};
+
+// max number of parameters a single statement can bind.
+const MAX_PARAMS: usize = u16::MAX as usize;
+
+// row count from where binary COPY is used instead of multi-row VALUES statements.
+const COPY_THRESHOLD: usize = 1024;
+
+impl Client {
+    /// Insert multiple rows into given table and return the number of rows inserted.
+    ///
+    /// When the row count is small rows are inserted with multi-row `INSERT INTO .. VALUES` statements. Rows are
+    /// split into multiple statements automatically so every statement stays under postgres's limit of 65535
+    /// parameters. For large row count `COPY .. FROM STDIN BINARY` is used to stream all rows in one round trip.
+    ///
+    /// `table` and `columns` are written into the query as is. They must be trusted input and quoted properly
+    /// when needed.
+    ///
+    /// Multiple statements are not executed atomically. Wrap the call in a transaction if all or nothing insert
+    /// is desired.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_postgres::{Client, Error, ToSql};
+    ///
+    /// async fn insert_many(client: &Client) -> Result<(), Error> {
+    ///     let users = [(1i32, "foo"), (2, "bar")];
+    ///     let rows = users.iter().map(|(id, name)| [id as &(dyn ToSql + Sync), name]);
+    ///     let inserted = client.insert_many("users", &["id", "name"], rows).await?;
+    ///     assert_eq!(inserted, 2);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `columns` is empty or it's length does not match the number of parameters of any given row.
+    pub async fn insert_many<I, R>(&self, table: &str, columns: &[&str], rows: I) -> Result<u64, Error>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator,
+        R::Item: BorrowToSql,
+    {
+        let len = columns.len();
+        assert!(
+            len > 0 && len <= MAX_PARAMS,
+            "column count must be in range of 1..={MAX_PARAMS}"
+        );
+
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                let row = row.into_iter().collect::<Vec<_>>();
+                assert_eq!(row.len(), len, "expected {len} parameters but got {}", row.len());
+                row
+            })
+            .collect::<Vec<_>>();
+
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let columns = columns.join(", ");
+
+        if rows.len() >= COPY_THRESHOLD {
+            return self.copy_in_binary(table, &columns, len, &rows).await;
+        }
+
+        let mut rows_affected = 0;
+        let mut stmt = None;
+
+        for chunk in rows.chunks(MAX_PARAMS / len) {
+            // full chunks share the same statement. only the last chunk may need a different one.
+            let stmt = match stmt {
+                Some((n, ref stmt)) if n == chunk.len() => stmt,
+                _ => {
+                    let query = values_query(table, &columns, len, chunk.len());
+                    &stmt.insert((chunk.len(), self.prepare(&query, &[]).await?)).1
+                }
+            };
+
+            let params = chunk
+                .iter()
+                .flatten()
+                .map(BorrowToSql::borrow_to_sql)
+                .collect::<Vec<_>>();
+
+            rows_affected += self.execute_raw(stmt.as_ref(), params).await?;
+        }
+
+        Ok(rows_affected)
+    }
+
+    async fn copy_in_binary<P>(&self, table: &str, columns: &str, len: usize, rows: &[Vec<P>]) -> Result<u64, Error>
+    where
+        P: BorrowToSql,
+    {
+        // a single row insert is prepared to resolve the parameter types from server.
+        let stmt = self.prepare(&values_query(table, columns, len, 1), &[]).await?;
+        let data = encode_copy_binary(stmt.as_ref().params(), rows)?;
+        drop(stmt);
+
+        let query = format!("COPY {table} ({columns}) FROM STDIN BINARY");
+
+        let mut res = self.tx.send_with(|buf| {
+            frontend::query(&query, buf)?;
+            frontend::CopyData::new(data)?.write(buf);
+            frontend::copy_done(buf);
+            Ok(())
+        })?;
+
+        // when server fails to enter copy mode the CopyData and CopyDone messages are ignored and error is
+        // received here.
+        match res.recv().await? {
+            backend::Message::CopyInResponse(_) => {}
+            _ => return Err(Error::unexpected()),
+        }
+
+        res.try_into_row_affected().await
+    }
+}
+
+fn values_query(table: &str, columns: &str, len: usize, rows: usize) -> String {
+    let mut query = format!("INSERT INTO {table} ({columns}) VALUES ");
+    for row in 0..rows {
+        if row > 0 {
+            query.push_str(", ");
+        }
+        query.push('(');
+        for col in 0..len {
+            if col > 0 {
+                query.push_str(", ");
+            }
+            let _ = write!(query, "${}", row * len + col + 1);
+        }
+        query.push(')');
+    }
+    query
+}
+
+// see https://www.postgresql.org/docs/current/sql-copy.html#id-1.9.3.55.9.4 for binary format.
+fn encode_copy_binary<P>(types: &[Type], rows: &[Vec<P>]) -> Result<BytesMut, Error>
+where
+    P: BorrowToSql,
+{
+    let mut buf = BytesMut::new();
+
+    // signature, flags field and header extension length.
+    buf.put_slice(b"PGCOPY\n\xff\r\n\0");
+    buf.put_i32(0);
+    buf.put_i32(0);
+
+    for row in rows {
+        buf.put_i16(types.len() as i16);
+        for (param, ty) in row.iter().zip(types) {
+            let idx = buf.len();
+            buf.put_i32(0);
+            let len = match ToSql::to_sql_checked(param.borrow_to_sql(), ty, &mut buf).map_err(|_| Error::todo())? {
+                IsNull::Yes => -1,
+                IsNull::No => (buf.len() - idx - 4) as i32,
+            };
+            buf[idx..idx + 4].copy_from_slice(&len.to_be_bytes());
+        }
+    }
+
+    // file trailer.
+    buf.put_i16(-1);
+
+    Ok(buf)
+}
@@ -2,7 +2,12 @@ use postgres_protocol::message::frontend;
 use postgres_types::{BorrowToSql, IsNull};
 use xitca_io::bytes::BytesMut;
 
-use crate::{error::Error, statement::Statement};
+use crate::{
+    column::Column,
+    error::Error,
+    statement::{Format, Statement},
+    Type,
+};
 
 pub(crate) fn encode<I>(buf: &mut BytesMut, stmt: &Statement, params: I) -> Result<(), Error>
 where
@@ -21,7 +26,15 @@ where
     I: ExactSizeIterator,
     I::Item: BorrowToSql,
 {
-    encode_bind(stmt, params, "", buf)?;
+    encode_bind(
+        stmt.name(),
+        stmt.params(),
+        stmt.param_formats(),
+        stmt.columns(),
+        params,
+        "",
+        buf,
+    )?;
     frontend::execute("", 0, buf).map_err(|_| Error::todo())?;
     if SYNC_MODE {
         frontend::sync(buf);
@@ -29,26 +42,69 @@ where
     Ok(())
 }
 
-fn encode_bind<I>(stmt: &Statement, params: I, portal: &str, buf: &mut BytesMut) -> Result<(), Error>
+// encode unnamed statement with explicit parameter types and execute it with unnamed portal in one go.
+// portal is described so the types of columns can be resolved from server's response.
+pub(crate) fn encode_typed<I>(buf: &mut BytesMut, stmt: &str, types: &[Type], params: I) -> Result<(), Error>
+where
+    I: ExactSizeIterator,
+    I::Item: BorrowToSql,
+{
+    frontend::parse("", stmt, types.iter().map(Type::oid), buf)?;
+    encode_bind("", types, &[], &[], params, "", buf)?;
+    frontend::describe(b'P', "", buf)?;
+    frontend::execute("", 0, buf).map_err(|_| Error::todo())?;
+    frontend::sync(buf);
+    Ok(())
+}
+
+fn encode_bind<I>(
+    stmt: &str,
+    types: &[Type],
+    param_formats: &[Format],
+    columns: &[Column],
+    params: I,
+    portal: &str,
+    buf: &mut BytesMut,
+) -> Result<(), Error>
 where
     I: ExactSizeIterator,
     I::Item: BorrowToSql,
 {
+    // a single format code applies to all parameters and columns.
+    let formats = param_formats
+        .iter()
+        .map(|f| f.code())
+        .chain(param_formats.is_empty().then_some(Format::Binary.code()));
+
+    let binary = columns.iter().all(|col| col.format() == Format::Binary);
+    let result_formats = columns
+        .iter()
+        .filter(|_| !binary)
+        .map(|col| col.format().code())
+        .chain(binary.then_some(Format::Binary.code()));
+
     let mut error_idx = 0;
     let r = frontend::bind(
         portal,
-        stmt.name(),
-        Some(1),
-        params.zip(stmt.params()).enumerate(),
-        |(idx, (param, ty)), buf| match param.borrow_to_sql().to_sql_checked(ty, buf) {
-            Ok(IsNull::No) => Ok(postgres_protocol::IsNull::No),
-            Ok(IsNull::Yes) => Ok(postgres_protocol::IsNull::Yes),
-            Err(e) => {
-                error_idx = idx;
-                Err(e)
+        stmt,
+        formats,
+        params.zip(types).enumerate(),
+        |(idx, (param, ty)), buf| {
+            // text format parameter is encoded as string and parsed by database according to it's type.
+            let ty = match param_formats.get(idx).or(param_formats.first()) {
+                Some(Format::Text) => &Type::TEXT,
+                _ => ty,
+            };
+            match param.borrow_to_sql().to_sql_checked(ty, buf) {
+                Ok(IsNull::No) => Ok(postgres_protocol::IsNull::No),
+                Ok(IsNull::Yes) => Ok(postgres_protocol::IsNull::Yes),
+                Err(e) => {
+                    error_idx = idx;
+                    Err(e)
+                }
             }
         },
-        Some(1),
+        result_formats,
         buf,
     );
 
@@ -0,0 +1,145 @@
+use core::{future::Future, ops::Range};
+
+use postgres_protocol::message::backend;
+
+use crate::{
+    client::Client,
+    column::Column,
+    driver::codec::Response,
+    error::Error,
+    iter::{slice_iter, AsyncLendingIterator},
+    row::Row,
+    statement::Statement,
+    ToSql,
+};
+
+impl Client {
+    /// Executes a statement, returning a combined result of the resulting rows and the command tag.
+    ///
+    /// This is useful for statement with `RETURNING` clause where both the returned rows and the number of rows
+    /// modified are desired. With [Client::execute] the rows are ignored and with [Client::query] the command tag
+    /// is ignored.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_postgres::{AsyncLendingIterator, Client, Error};
+    ///
+    /// async fn execute_returning(client: &Client) -> Result<(), Error> {
+    ///     let stmt = client.prepare("UPDATE users SET age = age + 1 RETURNING id", &[]).await?;
+    ///     let mut res = client.execute_returning(stmt.as_ref(), &[]).await?;
+    ///
+    ///     while let Some(row) = res.try_next().await? {
+    ///         let _id: i32 = row.get(0);
+    ///     }
+    ///
+    ///     let tag = res.finish().await?;
+    ///     assert_eq!(tag.kind(), "UPDATE");
+    ///     let _rows_affected = tag.rows_affected();
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if given params slice length does not match the length of [Statement::params].
+    pub fn execute_returning<'a>(
+        &self,
+        stmt: &'a Statement,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> impl Future<Output = Result<ExecuteResult<'a>, Error>> + Send + 'a {
+        let res = self.send_encode(stmt, slice_iter(params));
+        async {
+            let mut res = res?;
+            match res.recv().await? {
+                backend::Message::BindComplete => Ok(ExecuteResult {
+                    res,
+                    col: stmt.columns(),
+                    ranges: Vec::new(),
+                    tag: None,
+                    finished: false,
+                }),
+                _ => Err(Error::unexpected()),
+            }
+        }
+    }
+}
+
+/// Command tag of a completed statement. For example: `INSERT 0 1` or `SELECT 2`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandTag {
+    tag: Box<str>,
+}
+
+impl CommandTag {
+    pub(super) fn try_from_body(body: &backend::CommandCompleteBody) -> Result<Self, Error> {
+        body.tag()
+            .map(|tag| Self { tag: Box::from(tag) })
+            .map_err(|_| Error::todo())
+    }
+
+    /// Returns the raw command tag.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns the kind of command. For example: `INSERT`, `UPDATE` or `SELECT`.
+    pub fn kind(&self) -> &str {
+        self.tag.split(' ').next().unwrap_or_default()
+    }
+
+    /// Returns the number of rows affected by command. 0 is returned for command without row count.
+    pub fn rows_affected(&self) -> u64 {
+        self.tag.rsplit(' ').next().unwrap().parse().unwrap_or(0)
+    }
+}
+
+/// A stream of table rows with the command tag available after all rows are iterated.
+pub struct ExecuteResult<'a> {
+    res: Response,
+    col: &'a [Column],
+    ranges: Vec<Range<usize>>,
+    tag: Option<CommandTag>,
+    finished: bool,
+}
+
+impl ExecuteResult<'_> {
+    /// Returns the command tag. [Option::None] is returned when the rows are not fully iterated yet or the
+    /// statement is an empty query.
+    #[inline]
+    pub fn tag(&self) -> Option<&CommandTag> {
+        self.tag.as_ref()
+    }
+
+    /// Drain the remaining rows and return the command tag.
+    ///
+    /// # Errors
+    /// [Error] is returned when the statement is an empty query that has no command tag.
+    pub async fn finish(mut self) -> Result<CommandTag, Error> {
+        while self.try_next().await?.is_some() {}
+        self.tag.ok_or_else(Error::unexpected)
+    }
+}
+
+impl AsyncLendingIterator for ExecuteResult<'_> {
+    type Ok<'i>
+        = Row<'i>
+    where
+        Self: 'i;
+    type Err = Error;
+
+    async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
+        while !self.finished {
+            match self.res.recv().await? {
+                backend::Message::DataRow(body) => return Row::try_new(self.col, body, &mut self.ranges).map(Some),
+                backend::Message::CommandComplete(body) => self.tag = Some(CommandTag::try_from_body(&body)?),
+                backend::Message::EmptyQueryResponse | backend::Message::PortalSuspended => {}
+                backend::Message::ReadyForQuery(_) => self.finished = true,
+                _ => return Err(Error::unexpected()),
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -1,9 +1,133 @@
-use core::ops::Range;
-
-use crate::driver::codec::Response;
-
-pub struct GenericRowStream<C> {
-    pub(crate) res: Response,
-    pub(crate) col: C,
-    pub(crate) ranges: Vec<Range<usize>>,
-}
+use core::{
+    future::Future,
+    ops::Range,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_core::stream::Stream;
+
+use crate::{
+    column::Column,
+    driver::codec::Response,
+    error::Error,
+    iter::AsyncLendingIterator,
+    row::{Row, RowSimple},
+};
+
+pub struct GenericRowStream<C> {
+    pub(crate) res: Response,
+    pub(crate) col: C,
+    pub(crate) ranges: Vec<Range<usize>>,
+}
+
+macro_rules! row_stream_impl {
+    ($col: ty, $row: ident) => {
+        impl<'a> GenericRowStream<$col> {
+            /// map every row with given function and collect the outputs into [Vec].
+            pub async fn map_collect<F, T>(mut self, mut func: F) -> Result<Vec<T>, Error>
+            where
+                F: FnMut($row<'_>) -> Result<T, Error>,
+            {
+                let mut vec = Vec::new();
+                while let Some(row) = self.try_next().await? {
+                    vec.push(func(row)?);
+                }
+                Ok(vec)
+            }
+
+            /// convert every row to owned type with it's [TryFrom] impl and collect them into [Vec].
+            pub async fn collect_into_vec<T>(self) -> Result<Vec<T>, Error>
+            where
+                T: for<'r> TryFrom<$row<'r>, Error = Error>,
+            {
+                self.map_collect(|row| T::try_from(row)).await
+            }
+
+            /// convert to a boxed [Stream] with rows mapped by given function. stream ends after the first error.
+            pub fn map_stream<F, T>(self, func: F) -> RowMapStream<'a, T>
+            where
+                F: FnMut($row<'_>) -> Result<T, Error> + Send + 'a,
+                T: Send + 'a,
+            {
+                fn step<'a, F, T>(mut stream: GenericRowStream<$col>, mut func: F) -> Next<'a, T>
+                where
+                    F: FnMut($row<'_>) -> Result<T, Error> + Send + 'a,
+                    T: Send + 'a,
+                {
+                    Next(Box::pin(async move {
+                        match stream.try_next().await {
+                            Ok(Some(row)) => {
+                                let item = func(row);
+                                let next = item.is_ok().then(|| step(stream, func));
+                                Some((item, next))
+                            }
+                            Ok(None) => None,
+                            Err(e) => Some((Err(e), None)),
+                        }
+                    }))
+                }
+
+                RowMapStream {
+                    next: Some(step(self, func)),
+                }
+            }
+        }
+    };
+}
+
+row_stream_impl!(&'a [Column], Row);
+row_stream_impl!(Box<[Column]>, Row);
+row_stream_impl!(Vec<Column>, RowSimple);
+
+// future resolving to the next item and the future of item after it.
+#[allow(clippy::type_complexity)]
+struct Next<'a, T>(Pin<Box<dyn Future<Output = Option<(Result<T, Error>, Option<Next<'a, T>>)>> + Send + 'a>>);
+
+/// A boxed [Stream] of rows mapped to owned type. Produced by `map_stream` method of row streams like
+/// [RowStream](crate::RowStream) for interop with stream combinators.
+///
+/// # Examples
+/// ```rust
+/// use core::future::poll_fn;
+/// use core::pin::Pin;
+///
+/// use futures_core::stream::Stream;
+/// use xitca_postgres::{Client, Error};
+///
+/// async fn names(client: &Client) -> Result<(), Error> {
+///     let stmt = client.prepare("SELECT name FROM users", &[]).await?;
+///     let mut stream = client
+///         .query(stmt.as_ref(), &[])
+///         .await?
+///         .map_stream(|row| row.try_get::<String>(0));
+///     while let Some(name) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+///         println!("{}", name?);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct RowMapStream<'a, T> {
+    next: Option<Next<'a, T>>,
+}
+
+impl<T> Stream for RowMapStream<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let Some(next) = this.next.as_mut() else {
+            return Poll::Ready(None);
+        };
+        match ready!(next.0.as_mut().poll(cx)) {
+            Some((item, next)) => {
+                this.next = next;
+                Poll::Ready(Some(item))
+            }
+            None => {
+                this.next = None;
+                Poll::Ready(None)
+            }
+        }
+    }
+}
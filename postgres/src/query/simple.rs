@@ -1,14 +1,14 @@
-use core::future::Future;
+use core::{future::Future, ops::Range};
 
 use fallible_iterator::FallibleIterator;
 use postgres_protocol::message::{backend, frontend};
 
 use crate::{
     client::Client, column::Column, driver::codec::Response, error::Error, iter::AsyncLendingIterator, row::RowSimple,
-    Type,
+    statement::Format, Type,
 };
 
-use super::row_stream::GenericRowStream;
+use super::{execute::CommandTag, row_stream::GenericRowStream};
 
 impl Client {
     #[inline]
@@ -20,6 +20,41 @@ impl Client {
         })
     }
 
+    /// Executes a batch of statements with simple query protocol and exposes the result set of every statement
+    /// separately. Useful for executing migration scripts or psql style batches where the columns, rows and command
+    /// tag of each statement are needed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_postgres::{AsyncLendingIterator, Client, Error};
+    ///
+    /// async fn batch(client: &Client) -> Result<(), Error> {
+    ///     let batch = "CREATE TABLE foo (id INT); INSERT INTO foo VALUES (1); SELECT id FROM foo";
+    ///     let mut sets = client.query_simple_sets(batch)?;
+    ///
+    ///     while let Some(mut set) = sets.try_next().await? {
+    ///         println!("columns: {}", set.columns().len());
+    ///         while let Some(row) = set.try_next().await? {
+    ///             let _id: Option<&str> = row.try_get(0)?;
+    ///         }
+    ///         // command tag is available after all rows of result set are iterated.
+    ///         println!("tag: {:?}", set.tag());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn query_simple_sets(&self, stmt: &str) -> Result<SimpleResultSets, Error> {
+        self.send_encode_simple(stmt).map(|res| SimpleResultSets {
+            res,
+            col: Vec::new(),
+            ranges: Vec::new(),
+            tag: None,
+            set_finished: true,
+            finished: false,
+        })
+    }
+
     pub fn execute_simple(&self, stmt: &str) -> impl Future<Output = Result<u64, Error>> {
         let res = self.send_encode_simple(stmt);
         async { res?.try_into_row_affected().await }
@@ -34,22 +69,16 @@ impl Client {
 pub type RowSimpleStream = GenericRowStream<Vec<Column>>;
 
 impl AsyncLendingIterator for RowSimpleStream {
-    type Ok<'i> = RowSimple<'i> where Self: 'i;
+    type Ok<'i>
+        = RowSimple<'i>
+    where
+        Self: 'i;
     type Err = Error;
 
     async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
         loop {
             match self.res.recv().await? {
-                backend::Message::RowDescription(body) => {
-                    self.col = body
-                        .fields()
-                        // text type is used to match RowSimple::try_get's implementation
-                        // where column's pg type is always assumed as Option<&str>.
-                        // (no runtime pg type check so this does not really matter. it's
-                        // better to keep the type consistent though)
-                        .map(|f| Ok(Column::new(f.name(), Type::TEXT)))
-                        .collect::<Vec<_>>()?;
-                }
+                backend::Message::RowDescription(body) => self.col = columns(body)?,
                 backend::Message::DataRow(body) => {
                     return RowSimple::try_new(&self.col, body, &mut self.ranges).map(Some);
                 }
@@ -61,3 +90,127 @@ impl AsyncLendingIterator for RowSimpleStream {
         }
     }
 }
+
+fn columns(body: backend::RowDescriptionBody) -> Result<Vec<Column>, Error> {
+    body.fields()
+        // text type is used to match RowSimple::try_get's implementation
+        // where column's pg type is always assumed as Option<&str>.
+        // (no runtime pg type check so this does not really matter. it's
+        // better to keep the type consistent though)
+        .map(|f| Ok(Column::new(f.name(), Type::TEXT).with_format(Format::Text)))
+        .collect::<Vec<_>>()
+        .map_err(Into::into)
+}
+
+/// A stream of result sets of a batch of statements executed with simple query protocol. Produced by
+/// [Client::query_simple_sets].
+///
+/// Every statement of the batch yields one [SimpleResultSet]. Rows of a result set not iterated are skipped when
+/// advancing to the next one.
+pub struct SimpleResultSets {
+    res: Response,
+    col: Vec<Column>,
+    ranges: Vec<Range<usize>>,
+    tag: Option<CommandTag>,
+    set_finished: bool,
+    finished: bool,
+}
+
+impl AsyncLendingIterator for SimpleResultSets {
+    type Ok<'i>
+        = SimpleResultSet<'i>
+    where
+        Self: 'i;
+    type Err = Error;
+
+    async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
+        // skip remaining rows of previous result set.
+        while !self.set_finished {
+            match self.res.recv().await? {
+                backend::Message::DataRow(_) => {}
+                backend::Message::CommandComplete(body) => {
+                    self.tag = Some(CommandTag::try_from_body(&body)?);
+                    self.set_finished = true;
+                }
+                _ => return Err(Error::unexpected()),
+            }
+        }
+
+        while !self.finished {
+            match self.res.recv().await? {
+                backend::Message::RowDescription(body) => {
+                    self.col = columns(body)?;
+                    self.tag = None;
+                    self.set_finished = false;
+                    return Ok(Some(SimpleResultSet { sets: self }));
+                }
+                // statement without rows. e.g: CREATE TABLE.
+                backend::Message::CommandComplete(body) => {
+                    self.col.clear();
+                    self.tag = Some(CommandTag::try_from_body(&body)?);
+                    return Ok(Some(SimpleResultSet { sets: self }));
+                }
+                backend::Message::EmptyQueryResponse => {}
+                backend::Message::ReadyForQuery(_) => self.finished = true,
+                _ => return Err(Error::unexpected()),
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Result set of a single statement in batch. iterate it for rows of the statement.
+pub struct SimpleResultSet<'a> {
+    sets: &'a mut SimpleResultSets,
+}
+
+impl SimpleResultSet<'_> {
+    /// Returns the columns of result set. empty for statement that returns no rows.
+    #[inline]
+    pub fn columns(&self) -> &[Column] {
+        &self.sets.col
+    }
+
+    /// Returns the command tag of statement. [Option::None] is returned when the rows are not fully iterated yet.
+    #[inline]
+    pub fn tag(&self) -> Option<&CommandTag> {
+        if self.sets.set_finished {
+            self.sets.tag.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Drain the remaining rows and return the command tag.
+    pub async fn finish(mut self) -> Result<CommandTag, Error> {
+        while self.try_next().await?.is_some() {}
+        self.sets.tag.clone().ok_or_else(Error::unexpected)
+    }
+}
+
+impl AsyncLendingIterator for SimpleResultSet<'_> {
+    type Ok<'i>
+        = RowSimple<'i>
+    where
+        Self: 'i;
+    type Err = Error;
+
+    async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
+        let sets = &mut *self.sets;
+        if !sets.set_finished {
+            match sets.res.recv().await? {
+                backend::Message::DataRow(body) => {
+                    return RowSimple::try_new(&sets.col, body, &mut sets.ranges).map(Some)
+                }
+                backend::Message::CommandComplete(body) => {
+                    sets.tag = Some(CommandTag::try_from_body(&body)?);
+                    sets.set_finished = true;
+                }
+                _ => return Err(Error::unexpected()),
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,197 @@
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::message::backend;
+
+use std::sync::Arc;
+
+use crate::{
+    client::Client,
+    column::Column,
+    driver::codec::Response,
+    error::Error,
+    iter::{slice_iter, AsyncLendingIterator},
+    row::Row,
+    BorrowToSql, ToSql, Type,
+};
+
+use super::row_stream::GenericRowStream;
+
+impl Client {
+    /// Executes a statement with explicitly typed parameters, returning a stream of the resulting rows.
+    ///
+    /// Unlike [Client::query] no prepared [Statement](crate::statement::Statement) is needed. The statement
+    /// is parsed, bound and executed in one round trip and the parameter types are taken from given params
+    /// instead of being inferred by server.
+    ///
+    /// This is useful for one-off queries where the cost of a separate prepare is not desired.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_postgres::{AsyncLendingIterator, Client, Error, Type};
+    ///
+    /// async fn query_typed(client: &Client) -> Result<(), Error> {
+    ///     let mut stream = client
+    ///         .query_typed("SELECT name FROM users WHERE id = $1", &[(&996i32, Type::INT4)])
+    ///         .await?;
+    ///
+    ///     while let Some(row) = stream.try_next().await? {
+    ///         let _name: &str = row.get(0);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub async fn query_typed(
+        &self,
+        stmt: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<RowStreamOwned, Error> {
+        self.query_typed_raw(stmt, params.iter().map(|(p, t)| (*p as &dyn ToSql, t.clone())))
+            .await
+    }
+
+    /// The maximally flexible version of [Client::query_typed].
+    pub async fn query_typed_raw<P, I>(&self, stmt: &str, params: I) -> Result<RowStreamOwned, Error>
+    where
+        P: BorrowToSql,
+        I: IntoIterator<Item = (P, Type)>,
+    {
+        let (params, types): (Vec<_>, Vec<_>) = params.into_iter().unzip();
+
+        let res = self
+            .tx
+            .send_with(|buf| super::encode::encode_typed(buf, stmt, &types, params.into_iter()))?;
+
+        self.typed_row_stream(res).await
+    }
+
+    /// Executes a statement with the unnamed statement, returning a stream of the resulting rows.
+    ///
+    /// Unlike [Client::prepare] no named statement is created on database and nothing has to be closed afterward.
+    /// Parameter types are inferred by database when the sql query is first seen by current connection and
+    /// they are cached for later use. Following calls with the same sql query parse, bind and execute the unnamed
+    /// statement in one round trip.
+    ///
+    /// This is useful for queries executed rarely or with many different sql texts where keeping a named
+    /// statement for each of them is a waste of database resources. For hot queries named statement is still
+    /// faster as it's only parsed once. See [Client::prepare_named] for caching named statement.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_postgres::{AsyncLendingIterator, Client, Error};
+    ///
+    /// async fn query_unnamed(client: &Client) -> Result<(), Error> {
+    ///     let mut stream = client
+    ///         .query_unnamed("SELECT name FROM users WHERE id = $1", &[&996i32])
+    ///         .await?;
+    ///
+    ///     while let Some(row) = stream.try_next().await? {
+    ///         let _name: &str = row.get(0);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if given params slice length does not match the count of parameters of sql query.
+    #[inline]
+    pub async fn query_unnamed(&self, stmt: &str, params: &[&(dyn ToSql + Sync)]) -> Result<RowStreamOwned, Error> {
+        self.query_unnamed_raw(stmt, slice_iter(params)).await
+    }
+
+    /// The maximally flexible version of [Client::query_unnamed].
+    ///
+    /// # Panics
+    ///
+    /// Panics if given params' [ExactSizeIterator::len] does not match the count of parameters of sql query.
+    pub async fn query_unnamed_raw<I>(&self, stmt: &str, params: I) -> Result<RowStreamOwned, Error>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: BorrowToSql,
+    {
+        let params = params.into_iter();
+
+        let types = match self.cached_statements.get_unnamed(stmt) {
+            Some(types) => types,
+            // query without parameter does not need type inference.
+            None if params.len() == 0 => Arc::from([]),
+            None => {
+                let desc = self.describe(stmt, &[]).await?;
+                self.cached_statements.insert_unnamed(stmt, desc.params())
+            }
+        };
+
+        assert_eq!(
+            types.len(),
+            params.len(),
+            "expected {} parameters but got {}",
+            types.len(),
+            params.len()
+        );
+
+        let res = self
+            .tx
+            .send_with(|buf| super::encode::encode_typed(buf, stmt, &types, params))?;
+
+        self.typed_row_stream(res).await
+    }
+
+    async fn typed_row_stream(&self, mut res: Response) -> Result<RowStreamOwned, Error> {
+        match res.recv().await? {
+            backend::Message::ParseComplete => {}
+            _ => return Err(Error::unexpected()),
+        }
+
+        match res.recv().await? {
+            backend::Message::BindComplete => {}
+            _ => return Err(Error::unexpected()),
+        }
+
+        let col = match res.recv().await? {
+            backend::Message::RowDescription(body) => {
+                let mut col = Vec::new();
+                let mut it = body.fields();
+                while let Some(field) = it.next().map_err(|_| Error::todo())? {
+                    let ty = self.get_type(field.type_oid()).await?;
+                    col.push(Column::new(field.name(), ty));
+                }
+                col
+            }
+            backend::Message::NoData => Vec::new(),
+            _ => return Err(Error::unexpected()),
+        };
+
+        Ok(RowStreamOwned {
+            res,
+            col: col.into_boxed_slice(),
+            ranges: Vec::new(),
+        })
+    }
+}
+
+/// A stream of table rows with columns owned by itself.
+pub type RowStreamOwned = GenericRowStream<Box<[Column]>>;
+
+impl AsyncLendingIterator for RowStreamOwned {
+    type Ok<'i>
+        = Row<'i>
+    where
+        Self: 'i;
+    type Err = Error;
+
+    async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
+        loop {
+            match self.res.recv().await? {
+                backend::Message::DataRow(body) => return Row::try_new(&self.col, body, &mut self.ranges).map(Some),
+                backend::Message::EmptyQueryResponse
+                | backend::Message::CommandComplete(_)
+                | backend::Message::PortalSuspended => {}
+                backend::Message::ReadyForQuery(_) => return Ok(None),
+                _ => return Err(Error::unexpected()),
+            }
+        }
+    }
+}
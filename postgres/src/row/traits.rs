@@ -36,5 +36,7 @@ impl RowIndexAndType for &str {
                     .enumerate()
                     .find_map(|(idx, col)| col.name().eq_ignore_ascii_case(self).then(|| (idx, col.r#type())))
             })
+            // fall back to ordinal index when no column name matches. e.g: "0" for the first column.
+            .or_else(|| self.parse::<usize>().ok()?._from_columns(col))
     }
 }
@@ -9,6 +9,7 @@ use crate::{
     column::Column,
     error::{Error, InvalidColumnIndex, WrongType},
     from_sql::FromSqlExt,
+    statement::Format,
     Type,
 };
 
@@ -86,6 +87,24 @@ impl<'a, C> GenericRow<'a, C> {
         self.columns().len()
     }
 
+    /// Determines if the row contains given column.
+    ///
+    /// The column can be specified either by its numeric index in the row, or by its column name.
+    pub fn contains(&self, idx: impl RowIndexAndType) -> bool {
+        idx._from_columns(self.columns).is_some()
+    }
+
+    /// Returns an iterator over column names and their raw values. `None` value represents a null pg value.
+    ///
+    /// Raw value is in format of column and can be decoded according to the type of column. Column of [Row] is in
+    /// binary format by default. For [RowSimple] it's in text format. See [Column::format] for detail.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&str, Option<&[u8]>)> {
+        self.columns
+            .iter()
+            .zip(self.ranges.iter())
+            .map(|(col, range)| (col.name(), self.body.buffer().get(range.clone())))
+    }
+
     // Get the raw bytes for the column at the given range.
     fn col_buffer(&self, idx: usize) -> (&Range<usize>, &Bytes) {
         (&self.ranges[idx], self.body.buffer_bytes())
@@ -100,6 +119,12 @@ impl<'a, C> GenericRow<'a, C> {
             ._from_columns(self.columns)
             .ok_or_else(|| InvalidColumnIndex(idx.to_string()))?;
 
+        // value of text format column is decoded as text regardless of column type.
+        let ty = match self.columns[idx].format() {
+            Format::Text => &Type::TEXT,
+            Format::Binary => ty,
+        };
+
         if !ty_check(ty) {
             return Err(Error::from(WrongType::new::<T>(ty.clone())));
         }
@@ -134,6 +159,37 @@ impl Row<'_> {
         FromSqlExt::from_sql_nullable_ext(ty, self.col_buffer(idx)).map_err(Into::into)
     }
 
+    /// Deserializes a nullable value from the row. Null pg value is returned as `None` and types not
+    /// implementing [FromSqlExt] for null value can be used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds or if the value cannot be converted to the specified type.
+    #[inline]
+    pub fn get_opt<'s, T>(&'s self, idx: impl RowIndexAndType + fmt::Display) -> Option<T>
+    where
+        T: FromSqlExt<'s>,
+    {
+        self.try_get_opt(idx)
+            .unwrap_or_else(|e| panic!("error retrieving column {idx}: {e}"))
+    }
+
+    /// Like `Row::get_opt`, but returns a `Result` rather than panicking.
+    pub fn try_get_opt<'s, T>(&'s self, idx: impl RowIndexAndType + fmt::Display) -> Result<Option<T>, Error>
+    where
+        T: FromSqlExt<'s>,
+    {
+        let (idx, ty) = self.get_idx_ty::<T>(idx, T::accepts)?;
+        let (range, buf) = self.col_buffer(idx);
+        // see GenericRow::try_new for null pg value representation.
+        if range.start > range.end {
+            return Ok(None);
+        }
+        FromSqlExt::from_sql_nullable_ext(ty, (range, buf))
+            .map(Some)
+            .map_err(Into::into)
+    }
+
     #[doc(hidden)]
     /// hidden api for get row data with [FromSql] trait implementation.
     pub fn get_raw<'s, T>(&'s self, idx: impl RowIndexAndType + fmt::Display) -> T
@@ -177,6 +233,7 @@ impl RowSimple<'_> {
 
 fn _try_get_usize(row: Row) {
     let _ = row.try_get::<u32>(0);
+    let _ = row.try_get_opt::<xitca_unsafe_collection::bytes::BytesStr>("test");
     let _ = row.try_get::<&str>("test");
     let _ = row.try_get_raw::<String>(String::from("get_raw").as_str());
 }
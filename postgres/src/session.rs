@@ -26,13 +26,17 @@ pub enum TargetSessionAttrs {
 #[allow(clippy::needless_pass_by_ref_mut)] // dumb clippy
 #[cold]
 #[inline(never)]
-pub(super) async fn prepare_session<Io>(drv: &mut GenericDriver<Io>, cfg: &Config) -> Result<(), Error>
+pub(super) async fn prepare_session<Io>(
+    drv: &mut GenericDriver<Io>,
+    cfg: &Config,
+    host: Option<&str>,
+) -> Result<(), Error>
 where
     Io: AsyncIo + Send,
 {
     let mut buf = BytesMut::new();
 
-    auth(drv, cfg, &mut buf).await?;
+    auth(drv, cfg, host, &mut buf).await?;
 
     loop {
         match drv.recv().await? {
@@ -74,7 +78,12 @@ where
 
 #[cold]
 #[inline(never)]
-async fn auth<Io>(drv: &mut GenericDriver<Io>, cfg: &Config, buf: &mut BytesMut) -> Result<(), Error>
+async fn auth<Io>(
+    drv: &mut GenericDriver<Io>,
+    cfg: &Config,
+    _host: Option<&str>,
+    buf: &mut BytesMut,
+) -> Result<(), Error>
 where
     Io: AsyncIo + Send,
 {
@@ -96,6 +105,9 @@ where
     let msg = buf.split();
     drv.send(msg).await?;
 
+    #[cfg(feature = "gssapi")]
+    let mut gss_ctx = None;
+
     loop {
         match drv.recv().await? {
             backend::Message::AuthenticationOk => return Ok(()),
@@ -137,12 +149,11 @@ where
                         } else {
                             // server ask for channel binding but no tls_server_end_point can be
                             // found.
-                            return Err(Error::todo());
+                            return Err(AuthenticationError::ChannelBindingUnavailable.into());
                         }
                     }
                     (false, true) => (sasl::ChannelBinding::unrequested(), sasl::SCRAM_SHA_256),
-                    // TODO: return "unsupported SASL mechanism" error.
-                    (false, false) => return Err(Error::todo()),
+                    (false, false) => return Err(AuthenticationError::UnsupportedSaslMechanism.into()),
                 };
 
                 let mut scram = sasl::ScramSha256::new(pass, channel_binding);
@@ -158,15 +169,52 @@ where
                         let msg = buf.split();
                         drv.send(msg).await?;
                     }
-                    _ => return Err(Error::todo()),
+                    backend::Message::ErrorResponse(body) => return Err(AuthenticationError::from_response(&body)),
+                    _ => return Err(Error::unexpected()),
                 }
 
                 match drv.recv().await? {
                     backend::Message::AuthenticationSaslFinal(body) => scram.finish(body.data())?,
-                    _ => return Err(Error::todo()),
+                    backend::Message::ErrorResponse(body) => return Err(AuthenticationError::from_response(&body)),
+                    _ => return Err(Error::unexpected()),
+                }
+            }
+            backend::Message::AuthenticationGss => {
+                #[cfg(feature = "gssapi")]
+                {
+                    let ctx = gss_ctx.insert(crate::gssapi::init_context(cfg, _host)?);
+                    if let Some(token) = ctx.step(None).map_err(AuthenticationError::Gssapi)? {
+                        send_gss_response(drv, &token, buf).await?;
+                    }
+                }
+
+                #[cfg(not(feature = "gssapi"))]
+                {
+                    return Err(crate::error::FeatureError::Gssapi.into());
+                }
+            }
+            backend::Message::AuthenticationGssContinue(_body) => {
+                #[cfg(feature = "gssapi")]
+                {
+                    let ctx = gss_ctx.as_mut().ok_or_else(Error::unexpected)?;
+                    if let Some(token) = ctx.step(Some(_body.data())).map_err(AuthenticationError::Gssapi)? {
+                        send_gss_response(drv, &token, buf).await?;
+                    }
+                }
+
+                #[cfg(not(feature = "gssapi"))]
+                {
+                    return Err(Error::unexpected());
                 }
             }
-            backend::Message::ErrorResponse(_) => return Err(Error::from(AuthenticationError::WrongPassWord)),
+            backend::Message::AuthenticationSspi => return Err(AuthenticationError::UnsupportedMethod("SSPI").into()),
+            backend::Message::AuthenticationKerberosV5 => {
+                return Err(AuthenticationError::UnsupportedMethod("Kerberos V5").into())
+            }
+            backend::Message::AuthenticationScmCredential => {
+                return Err(AuthenticationError::UnsupportedMethod("SCM credential").into())
+            }
+            backend::Message::ErrorResponse(body) => return Err(AuthenticationError::from_response(&body)),
             _ => {}
         }
     }
@@ -180,3 +228,14 @@ where
     let msg = buf.split();
     drv.send(msg).await
 }
+
+#[cfg(feature = "gssapi")]
+async fn send_gss_response<Io>(drv: &mut GenericDriver<Io>, token: &[u8], buf: &mut BytesMut) -> Result<(), Error>
+where
+    Io: AsyncIo + Send,
+{
+    // GSSResponse message shares the same format with SASLResponse message.
+    frontend::sasl_response(token, buf)?;
+    let msg = buf.split();
+    drv.send(msg).await
+}
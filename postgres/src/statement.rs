@@ -2,6 +2,11 @@
 
 use core::ops::Deref;
 
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
 use postgres_protocol::message::frontend;
 
 use super::{client::Client, column::Column, Type};
@@ -42,6 +47,18 @@ where
         self.statement.take().unwrap()
     }
 
+    /// See [Statement::with_param_formats].
+    pub fn with_param_formats(mut self, formats: &[Format]) -> Self {
+        self.statement = self.statement.take().map(|stmt| stmt.with_param_formats(formats));
+        self
+    }
+
+    /// See [Statement::with_result_formats].
+    pub fn with_result_formats(mut self, formats: &[Format]) -> Self {
+        self.statement = self.statement.take().map(|stmt| stmt.with_result_formats(formats));
+        self
+    }
+
     fn cancel(&mut self) {
         if let Some(statement) = self.statement.take() {
             let _ = self.client.tx.send_with(|buf| {
@@ -53,11 +70,33 @@ where
     }
 }
 
+/// Format of parameter and column value transferred between client and database.
+///
+/// [Format::Binary] is the default. [Format::Text] is useful for types only supporting text output(types of some
+/// extensions for example) and for debugging. See [Statement::with_param_formats] and
+/// [Statement::with_result_formats] for detail.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    #[default]
+    Binary,
+}
+
+impl Format {
+    pub(crate) const fn code(self) -> i16 {
+        match self {
+            Self::Text => 0,
+            Self::Binary => 1,
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct Statement {
     name: Box<str>,
     params: Box<[Type]>,
     columns: Box<[Column]>,
+    param_formats: Box<[Format]>,
 }
 
 impl Statement {
@@ -66,9 +105,83 @@ impl Statement {
             name: name.into_boxed_str(),
             params: params.into_boxed_slice(),
             columns: columns.into_boxed_slice(),
+            param_formats: Box::new([]),
         }
     }
 
+    /// Set formats of statement's parameters when it's bound with parameters. A single format applies to all
+    /// parameters. Otherwise one format for each parameter in order.
+    ///
+    /// Parameter in [Format::Text] is sent as it's text representation and database parses it according to
+    /// parameter type. String types(`&str`, `String` etc) must be used as parameter value.
+    ///
+    /// # Panics
+    /// When count of formats is not 1 and not equal to count of parameters.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_postgres::{statement::Format, Client, Error};
+    ///
+    /// async fn text_param(client: &Client) -> Result<(), Error> {
+    ///     let stmt = client
+    ///         .prepare("SELECT $1::int4 + 1", &[])
+    ///         .await?
+    ///         .with_param_formats(&[Format::Text]);
+    ///     let _ = client.query(stmt.as_ref(), &[&"995"]).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_param_formats(mut self, formats: &[Format]) -> Self {
+        Self::formats_assert(formats.len(), self.params.len(), "parameters");
+        self.param_formats = formats.into();
+        self
+    }
+
+    /// Set formats of statement's result columns. A single format applies to all columns. Otherwise one format for
+    /// each column in order.
+    ///
+    /// Value of column in [Format::Text] is it's text representation produced by database and can be obtained as
+    /// string type(`&str`, `String` etc) from row regardless of column type. See [Column::format].
+    ///
+    /// # Panics
+    /// When count of formats is not 1 and not equal to count of columns.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_postgres::{statement::Format, AsyncLendingIterator, Client, Error};
+    ///
+    /// async fn text_result(client: &Client) -> Result<(), Error> {
+    ///     let stmt = client
+    ///         .prepare("SELECT 996::int4, now()", &[])
+    ///         .await?
+    ///         .with_result_formats(&[Format::Text]);
+    ///     let mut stream = client.query(stmt.as_ref(), &[]).await?;
+    ///     while let Some(row) = stream.try_next().await? {
+    ///         let num = row.get::<&str>(0);
+    ///         let now = row.get::<&str>(1);
+    ///         println!("{num} {now}");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_result_formats(mut self, formats: &[Format]) -> Self {
+        Self::formats_assert(formats.len(), self.columns.len(), "columns");
+        self.columns = core::mem::take(&mut self.columns)
+            .into_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(i, col)| col.with_format(formats.get(i).copied().unwrap_or(formats[0])))
+            .collect();
+        self
+    }
+
+    fn formats_assert(formats: usize, len: usize, target: &str) {
+        assert!(
+            formats == 1 || formats == len,
+            "expected 1 or {len} formats for {target} but got {formats}"
+        );
+    }
+
     pub(crate) fn params_assert(&self, params: &impl ExactSizeIterator) {
         assert_eq!(
             self.params().len(),
@@ -95,6 +208,14 @@ impl Statement {
         &self.columns
     }
 
+    pub(crate) fn param_formats(&self) -> &[Format] {
+        &self.param_formats
+    }
+
+    pub(crate) fn into_columns(self) -> Box<[Column]> {
+        self.columns
+    }
+
     /// Convert self to a drop guarded statement which would cancel on drop.
     pub fn into_guarded<C>(self, client: C) -> StatementGuarded<C>
     where
@@ -106,3 +227,158 @@ impl Statement {
         }
     }
 }
+
+/// Metadata of a statement described by database. See [Client::describe] for detail.
+#[derive(Clone, Debug)]
+pub struct Description {
+    params: Box<[Type]>,
+    columns: Box<[Column]>,
+}
+
+impl Description {
+    pub(crate) fn new(params: Vec<Type>, columns: Vec<Column>) -> Self {
+        Self {
+            params: params.into_boxed_slice(),
+            columns: columns.into_boxed_slice(),
+        }
+    }
+
+    /// Returns the types of the statement's parameters.
+    #[inline]
+    pub fn params(&self) -> &[Type] {
+        &self.params
+    }
+
+    /// Returns information about the columns returned when the statement is queried.
+    #[inline]
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+}
+
+/// A logical statement identified by its sql query and parameter types.
+///
+/// Unlike [Statement] it's not bound to any connection. It's lazily prepared on the connection it's executed on
+/// and the prepared [Statement] is cached by the connection for later use. This makes it possible to hold a
+/// statement and run it on any connection of a pool without preparing it on every connection manually.
+///
+/// # Examples
+/// ```rust
+/// use xitca_postgres::{statement::StatementNamed, Client, Error, Type};
+///
+/// async fn query_named(client: &Client) -> Result<(), Error> {
+///     let stmt = StatementNamed::new("SELECT name FROM users WHERE id = $1", &[Type::INT4]);
+///
+///     // statement is prepared on first use and cached by client.
+///     let _ = client.query_named(&stmt, &[&996i32]).await?;
+///     // cached statement is used.
+///     let _ = client.query_named(&stmt, &[&251i32]).await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StatementNamed {
+    stmt: Arc<str>,
+    types: Arc<[Type]>,
+}
+
+impl StatementNamed {
+    pub fn new(stmt: &str, types: &[Type]) -> Self {
+        Self {
+            stmt: Arc::from(stmt),
+            types: Arc::from(types),
+        }
+    }
+
+    /// Returns the sql query of the statement.
+    #[inline]
+    pub fn stmt(&self) -> &str {
+        &self.stmt
+    }
+
+    /// Returns the parameter types of the statement.
+    #[inline]
+    pub fn types(&self) -> &[Type] {
+        &self.types
+    }
+}
+
+/// Cache of [Statement] prepared from [StatementNamed] on a single connection.
+/// It also caches parameter types of sql queries executed as unnamed statement.
+#[derive(Default)]
+pub(crate) struct StatementCache {
+    statements: Mutex<HashMap<StatementNamed, Statement>>,
+    unnamed: Mutex<HashMap<Box<str>, Arc<[Type]>>>,
+}
+
+impl StatementCache {
+    pub(crate) fn get(&self, stmt: &StatementNamed) -> Option<Statement> {
+        self.statements.lock().unwrap().get(stmt).cloned()
+    }
+
+    pub(crate) fn remove(&self, stmt: &StatementNamed) -> Option<Statement> {
+        self.statements.lock().unwrap().remove(stmt)
+    }
+
+    pub(crate) fn get_unnamed(&self, stmt: &str) -> Option<Arc<[Type]>> {
+        self.unnamed.lock().unwrap().get(stmt).cloned()
+    }
+
+    pub(crate) fn insert_unnamed(&self, stmt: &str, types: &[Type]) -> Arc<[Type]> {
+        let types = Arc::<[Type]>::from(types);
+        self.unnamed.lock().unwrap().insert(Box::from(stmt), types.clone());
+        types
+    }
+
+    // insert prepared statement to cache and return the cached one. when the same statement is already cached
+    // (concurrent preparing of it for example) the given statement is returned as duplicate for cancellation.
+    pub(crate) fn insert(&self, named: &StatementNamed, stmt: Statement) -> (Statement, Option<Statement>) {
+        let mut statements = self.statements.lock().unwrap();
+        match statements.get(named) {
+            Some(cached) => (cached.clone(), Some(stmt)),
+            None => {
+                statements.insert(named.clone(), stmt.clone());
+                (stmt, None)
+            }
+        }
+    }
+
+    pub(crate) fn take(&mut self) -> HashMap<StatementNamed, Statement> {
+        core::mem::take(self.statements.get_mut().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        let stmt = || {
+            Statement::new(
+                String::from("s"),
+                vec![Type::INT4, Type::TEXT],
+                vec![Column::new("a", Type::INT4), Column::new("b", Type::INT4)],
+            )
+        };
+
+        let formats = |stmt: &Statement| stmt.columns().iter().map(Column::format).collect::<Vec<_>>();
+
+        assert_eq!(formats(&stmt()), [Format::Binary, Format::Binary]);
+        assert_eq!(
+            formats(&stmt().with_result_formats(&[Format::Text])),
+            [Format::Text, Format::Text]
+        );
+        assert_eq!(
+            formats(&stmt().with_result_formats(&[Format::Binary, Format::Text])),
+            [Format::Binary, Format::Text]
+        );
+
+        let stmt = stmt().with_param_formats(&[Format::Text, Format::Binary]);
+        assert_eq!(stmt.param_formats(), [Format::Text, Format::Binary]);
+
+        let res = std::panic::catch_unwind(|| stmt.with_param_formats(&[Format::Text; 3]));
+        assert!(res.is_err());
+    }
+}
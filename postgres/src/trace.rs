@@ -0,0 +1,71 @@
+//! per query instrumentation with [tracing]. enabled by `tracing` crate feature.
+
+use core::time::Duration;
+
+use std::time::Instant;
+
+use postgres_protocol::message::backend;
+use tracing::{debug, debug_span, warn, Span};
+
+use super::error::Error;
+
+// trace of a single query lives with it's response. event is emitted when the response is fully received, failed
+// or dropped before completion.
+pub(crate) struct QueryTrace {
+    span: Span,
+    start: Instant,
+    rows: u64,
+    slow_query: Option<Duration>,
+    finished: bool,
+}
+
+impl QueryTrace {
+    pub(crate) fn new(statement: &str, params: usize, slow_query: Option<Duration>) -> Self {
+        Self {
+            span: debug_span!("query", statement, params),
+            start: Instant::now(),
+            rows: 0,
+            slow_query,
+            finished: false,
+        }
+    }
+
+    pub(crate) fn on_message(&mut self, res: &Result<backend::Message, Error>) {
+        if self.finished {
+            return;
+        }
+
+        match res {
+            Ok(backend::Message::DataRow(_)) => self.rows += 1,
+            Ok(backend::Message::ReadyForQuery(_)) => self.finish(None),
+            Err(e) => self.finish(Some(e)),
+            _ => {}
+        }
+    }
+
+    fn finish(&mut self, err: Option<&Error>) {
+        self.finished = true;
+
+        let elapsed = self.start.elapsed();
+        let rows = self.rows;
+        let _guard = self.span.enter();
+
+        match err {
+            Some(e) => debug!(rows, ?elapsed, error = %e, "query failed"),
+            None => debug!(rows, ?elapsed, "query finished"),
+        }
+
+        if self.slow_query.is_some_and(|threshold| elapsed >= threshold) {
+            warn!(rows, ?elapsed, "slow query");
+        }
+    }
+}
+
+impl Drop for QueryTrace {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _guard = self.span.enter();
+            debug!(rows = self.rows, elapsed = ?self.start.elapsed(), "query response dropped before finish");
+        }
+    }
+}
@@ -51,6 +51,10 @@ impl Transaction<'_> {
         self.client.query_raw(stmt, params).await
     }
 
+    pub(crate) fn client(&self) -> &Client {
+        self.client
+    }
+
     pub async fn commit(mut self) -> Result<(), Error> {
         let res = self.client.send_encode_simple("COMMIT")?;
         self.state = State::Finish;
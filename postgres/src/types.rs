@@ -0,0 +1,30 @@
+//! extended types for encoding/decoding rust types to/from postgres values.
+//!
+//! Types from external crates are supported with according feature flags and the crates are re-exported from
+//! this module:
+//! - `chrono`: date and time types from [chrono](https://docs.rs/chrono)
+//! - `rust_decimal`: numeric type from [rust_decimal](https://docs.rs/rust_decimal)
+//! - `time`: date and time types from [time](https://docs.rs/time)
+//! - `uuid`: uuid type from [uuid](https://docs.rs/uuid)
+
+mod interval;
+
+#[cfg(feature = "json")]
+mod json;
+
+pub use postgres_types::{IsNull, Kind};
+pub use xitca_io::bytes::{Bytes, BytesMut};
+
+pub use self::interval::Interval;
+
+#[cfg(feature = "json")]
+pub use self::json::Json;
+
+#[cfg(feature = "chrono")]
+pub use chrono;
+#[cfg(feature = "rust_decimal")]
+pub use rust_decimal;
+#[cfg(feature = "time")]
+pub use time;
+#[cfg(feature = "uuid")]
+pub use uuid;
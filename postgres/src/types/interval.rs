@@ -0,0 +1,70 @@
+use core::ops::Range;
+
+use postgres_types::{accepts, to_sql_checked, IsNull};
+use xitca_io::bytes::{BufMut, Bytes, BytesMut};
+
+use crate::{
+    from_sql::{FromSqlError, FromSqlExt},
+    FromSql, ToSql, Type,
+};
+
+/// postgres interval type.
+///
+/// Interval is stored in separate units of months, days and microseconds as they can not be converted to each
+/// other without context. e.g: the number of days in a month.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub microseconds: i64,
+}
+
+impl Interval {
+    pub const fn new(months: i32, days: i32, microseconds: i64) -> Self {
+        Self {
+            months,
+            days,
+            microseconds,
+        }
+    }
+}
+
+impl<'a> FromSql<'a> for Interval {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, FromSqlError> {
+        let raw = <&[u8; 16]>::try_from(raw).map_err(|_| "invalid interval length")?;
+        let (microseconds, rest) = raw.split_at(8);
+        let (days, months) = rest.split_at(4);
+        Ok(Self {
+            months: i32::from_be_bytes(months.try_into().unwrap()),
+            days: i32::from_be_bytes(days.try_into().unwrap()),
+            microseconds: i64::from_be_bytes(microseconds.try_into().unwrap()),
+        })
+    }
+
+    accepts!(INTERVAL);
+}
+
+impl<'a> FromSqlExt<'a> for Interval {
+    #[inline]
+    fn from_sql_nullable_ext(ty: &Type, (range, buf): (&Range<usize>, &'a Bytes)) -> Result<Self, FromSqlError> {
+        <Self as FromSql>::from_sql_nullable(ty, buf.get(range.clone()))
+    }
+
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        <Self as FromSql>::accepts(ty)
+    }
+}
+
+impl ToSql for Interval {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, FromSqlError> {
+        out.put_i64(self.microseconds);
+        out.put_i32(self.days);
+        out.put_i32(self.months);
+        Ok(IsNull::No)
+    }
+
+    accepts!(INTERVAL);
+
+    to_sql_checked!();
+}
@@ -0,0 +1,119 @@
+use core::{
+    fmt,
+    ops::{Deref, DerefMut, Range},
+};
+
+use std::io::Read;
+
+use postgres_types::{accepts, to_sql_checked, IsNull};
+use serde::{Deserialize, Serialize};
+use xitca_io::bytes::{BufMut, Bytes, BytesMut};
+
+use crate::{
+    from_sql::{FromSqlError, FromSqlExt},
+    FromSql, ToSql, Type,
+};
+
+/// wrapper type for serializing/deserializing serde types to/from json and jsonb values.
+///
+/// # Examples
+/// ```rust
+/// use serde_json::Value;
+/// use xitca_postgres::{row::Row, types::Json, Client, Error, Type};
+///
+/// // any type implementing serde::Serialize can be used as query parameter.
+/// async fn insert(cli: &Client, info: &Value) -> Result<(), Error> {
+///     let stmt = cli.prepare("INSERT INTO foo (info) VALUES ($1)", &[Type::JSONB]).await?;
+///     cli.execute(stmt.as_ref(), &[&Json(info)]).await?;
+///     Ok(())
+/// }
+///
+/// // any type implementing serde::Deserialize can be parsed from row. borrowed types like &str are
+/// // deserialized with zero copy from row data.
+/// fn parse(row: Row<'_>) {
+///     let Json(info) = row.get::<Json<Value>>("info");
+///     println!("{info}");
+/// }
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Json<T>(pub T);
+
+impl<T> Json<T> {
+    /// take ownership of inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> fmt::Debug for Json<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<'a, T> FromSql<'a> for Json<T>
+where
+    T: Deserialize<'a>,
+{
+    fn from_sql(ty: &Type, mut raw: &'a [u8]) -> Result<Self, FromSqlError> {
+        if *ty == Type::JSONB {
+            let mut version = [0];
+            raw.read_exact(&mut version)?;
+            if version[0] != 1 {
+                return Err("unsupported JSONB encoding version".into());
+            }
+        }
+        serde_json::from_slice(raw).map(Json).map_err(Into::into)
+    }
+
+    accepts!(JSON, JSONB);
+}
+
+impl<'a, T> FromSqlExt<'a> for Json<T>
+where
+    T: Deserialize<'a>,
+{
+    #[inline]
+    fn from_sql_nullable_ext(ty: &Type, (range, buf): (&Range<usize>, &'a Bytes)) -> Result<Self, FromSqlError> {
+        <Self as FromSql>::from_sql_nullable(ty, buf.get(range.clone()))
+    }
+
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        <Self as FromSql>::accepts(ty)
+    }
+}
+
+impl<T> ToSql for Json<T>
+where
+    T: Serialize + fmt::Debug,
+{
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, FromSqlError> {
+        if *ty == Type::JSONB {
+            out.put_u8(1);
+        }
+        serde_json::to_writer(out.writer(), &self.0)?;
+        Ok(IsNull::No)
+    }
+
+    accepts!(JSON, JSONB);
+
+    to_sql_checked!();
+}
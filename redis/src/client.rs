@@ -0,0 +1,114 @@
+use super::{
+    cmd::{Cmd, Pipeline, ToArg},
+    driver::DriverTx,
+    error::Error,
+    value::Value,
+};
+
+/// client of redis server.
+///
+/// Commands sent from the same client are pipelined automatically. Client can be shared between
+/// multiple tasks through smart pointer like [std::sync::Arc] and commands sent concurrently share the
+/// same connection without waiting for each other.
+pub struct Client {
+    tx: DriverTx,
+}
+
+impl Client {
+    pub(crate) fn new(tx: DriverTx) -> Self {
+        Self { tx }
+    }
+
+    // a lossy hint of running state of io driver. an io driver shutdown can happen
+    // at the same time this api is called.
+    pub fn closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// send command and wait for it's response.
+    ///
+    /// [Value::Error] responded by server is transformed into [ServerError](crate::error::ServerError).
+    pub async fn query(&self, cmd: &Cmd) -> Result<Value, Error> {
+        let mut res = self.tx.send_with(|buf| cmd.encode(buf), 1)?;
+        res.recv().await?.into_result()
+    }
+
+    /// send all commands in pipeline in one go and wait for their responses.
+    ///
+    /// Responses are returned in the same order as commands are added to pipeline. Error responded for
+    /// individual command is kept as [Value::Error] in the returned values.
+    pub async fn pipeline(&self, pipe: &Pipeline) -> Result<Vec<Value>, Error> {
+        if pipe.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut res = self.tx.send_with(|buf| pipe.encode(buf), pipe.len())?;
+
+        let mut values = Vec::with_capacity(pipe.len());
+        for _ in 0..pipe.len() {
+            values.push(res.recv().await?);
+        }
+
+        Ok(values)
+    }
+
+    /// subscribe to channels. messages published to channels are emitted from [Driver](crate::Driver).
+    ///
+    /// # Note
+    /// When using RESP2 protocol the connection enters subscribed mode where only pub/sub related commands
+    /// and `PING` can be sent until all subscriptions are canceled. Consider use dedicated connection for
+    /// pub/sub or enable RESP3 with [Config::resp3](crate::Config::resp3).
+    pub fn subscribe<I>(&self, channels: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: ToArg,
+    {
+        self.send_subscribe("SUBSCRIBE", channels)
+    }
+
+    /// subscribe to channels matching given patterns. See [Client::subscribe] for detail.
+    pub fn psubscribe<I>(&self, patterns: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: ToArg,
+    {
+        self.send_subscribe("PSUBSCRIBE", patterns)
+    }
+
+    /// unsubscribe from channels. all channels are unsubscribed when channels are empty.
+    pub fn unsubscribe<I>(&self, channels: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: ToArg,
+    {
+        self.send_subscribe("UNSUBSCRIBE", channels)
+    }
+
+    /// unsubscribe from patterns. all patterns are unsubscribed when patterns are empty.
+    pub fn punsubscribe<I>(&self, patterns: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: ToArg,
+    {
+        self.send_subscribe("PUNSUBSCRIBE", patterns)
+    }
+
+    /// publish message to channel and return the count of clients received the message.
+    pub async fn publish(&self, channel: impl ToArg, message: impl ToArg) -> Result<i64, Error> {
+        let cmd = Cmd::new("PUBLISH").arg(channel).arg(message);
+        match self.query(&cmd).await? {
+            Value::Integer(count) => Ok(count),
+            _ => Err(crate::error::UnexpectedValue.into()),
+        }
+    }
+
+    // confirmation of pub/sub commands are out of band messages and emitted from driver.
+    fn send_subscribe<I>(&self, name: &str, args: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: ToArg,
+    {
+        let cmd = Cmd::new(name).args(args);
+        self.tx.send_subscribe_with(|buf| cmd.encode(buf))
+    }
+}
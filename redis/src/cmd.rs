@@ -0,0 +1,210 @@
+//! command and pipeline types.
+
+use std::borrow::Cow;
+
+use xitca_io::bytes::{BufMut, Bytes, BytesMut};
+
+/// type can be used as argument of redis command.
+pub trait ToArg {
+    fn to_arg(&self) -> Cow<'_, [u8]>;
+}
+
+impl<T> ToArg for &T
+where
+    T: ToArg + ?Sized,
+{
+    #[inline]
+    fn to_arg(&self) -> Cow<'_, [u8]> {
+        T::to_arg(self)
+    }
+}
+
+impl ToArg for str {
+    #[inline]
+    fn to_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl ToArg for String {
+    #[inline]
+    fn to_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl ToArg for [u8] {
+    #[inline]
+    fn to_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<const N: usize> ToArg for [u8; N] {
+    #[inline]
+    fn to_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl ToArg for Vec<u8> {
+    #[inline]
+    fn to_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl ToArg for Bytes {
+    #[inline]
+    fn to_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+macro_rules! num_impl {
+    ($($ty: ty),*) => {
+        $(
+            impl ToArg for $ty {
+                #[inline]
+                fn to_arg(&self) -> Cow<'_, [u8]> {
+                    Cow::Owned(self.to_string().into_bytes())
+                }
+            }
+        )*
+    };
+}
+
+num_impl!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// redis command.
+///
+/// # Examples
+/// ```rust
+/// use xitca_redis::Cmd;
+///
+/// let cmd = Cmd::new("SET").arg("foo").arg("bar").arg("EX").arg(60);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cmd {
+    count: usize,
+    args: BytesMut,
+}
+
+impl Cmd {
+    /// construct a new command with given name.
+    pub fn new(name: impl ToArg) -> Self {
+        Self {
+            count: 0,
+            args: BytesMut::new(),
+        }
+        .arg(name)
+    }
+
+    /// append argument to command.
+    pub fn arg(mut self, arg: impl ToArg) -> Self {
+        self.push_arg(arg);
+        self
+    }
+
+    /// append multiple arguments to command.
+    pub fn args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: ToArg,
+    {
+        for arg in args {
+            self.push_arg(arg);
+        }
+        self
+    }
+
+    fn push_arg(&mut self, arg: impl ToArg) {
+        let arg = arg.to_arg();
+        self.count += 1;
+        self.args.put_u8(b'$');
+        self.args.extend_from_slice(arg.len().to_string().as_bytes());
+        self.args.extend_from_slice(b"\r\n");
+        self.args.extend_from_slice(&arg);
+        self.args.extend_from_slice(b"\r\n");
+    }
+
+    pub(crate) fn encode(&self, buf: &mut BytesMut) {
+        buf.put_u8(b'*');
+        buf.extend_from_slice(self.count.to_string().as_bytes());
+        buf.extend_from_slice(b"\r\n");
+        buf.extend_from_slice(&self.args);
+    }
+}
+
+/// a collection of commands sent to server in one go.
+///
+/// # Examples
+/// ```rust
+/// use xitca_redis::{Cmd, Pipeline};
+///
+/// let pipe = Pipeline::new()
+///     .cmd(Cmd::new("INCR").arg("counter"))
+///     .cmd(Cmd::new("GET").arg("counter"));
+/// assert_eq!(pipe.len(), 2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline {
+    count: usize,
+    buf: BytesMut,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// append command to pipeline.
+    pub fn cmd(mut self, cmd: Cmd) -> Self {
+        self.push(&cmd);
+        self
+    }
+
+    /// append command to pipeline by reference.
+    pub fn push(&mut self, cmd: &Cmd) {
+        self.count += 1;
+        cmd.encode(&mut self.buf);
+    }
+
+    /// count of commands in pipeline.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub(crate) fn encode(&self, buf: &mut BytesMut) {
+        buf.extend_from_slice(&self.buf);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        let mut buf = BytesMut::new();
+        Cmd::new("SET").arg("foo").arg(b"b\r\nar").arg(-1).encode(&mut buf);
+        assert_eq!(
+            &buf[..],
+            b"*4\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$5\r\nb\r\nar\r\n$2\r\n-1\r\n"
+        );
+
+        let mut buf = BytesMut::new();
+        Pipeline::new()
+            .cmd(Cmd::new("PING"))
+            .cmd(Cmd::new("DEL").args(["a", "b"]))
+            .encode(&mut buf);
+        assert_eq!(
+            &buf[..],
+            b"*1\r\n$4\r\nPING\r\n*3\r\n$3\r\nDEL\r\n$1\r\na\r\n$1\r\nb\r\n"
+        );
+    }
+}
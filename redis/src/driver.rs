@@ -0,0 +1,150 @@
+pub(crate) mod codec;
+pub(crate) mod generic;
+
+pub(crate) use generic::DriverTx;
+
+use core::{
+    future::{Future, IntoFuture},
+    pin::Pin,
+};
+
+use xitca_io::{
+    bytes::BytesMut,
+    io::{AsyncIo, AsyncIoDyn},
+    net::TcpStream,
+};
+
+#[cfg(unix)]
+use xitca_io::net::UnixStream;
+
+use super::{
+    client::Client,
+    cmd::Cmd,
+    config::{Config, Host},
+    error::Error,
+    value::Value,
+};
+
+use self::generic::GenericDriver;
+
+pub(super) async fn connect(cfg: &Config) -> Result<(Client, Driver), Error> {
+    match cfg.host {
+        Host::Tcp(ref host) => {
+            let io = TcpStream::connect((host.as_str(), cfg.port)).await?;
+            let _ = io.set_nodelay(true);
+            let (mut drv, tx) = GenericDriver::new(io, cfg.resp3);
+            prepare_session(&mut drv, cfg).await?;
+            Ok((Client::new(tx), Driver::Tcp(drv)))
+        }
+        #[cfg(unix)]
+        Host::Unix(ref path) => {
+            let io = UnixStream::connect(path).await?;
+            let (mut drv, tx) = GenericDriver::new(io, cfg.resp3);
+            prepare_session(&mut drv, cfg).await?;
+            Ok((Client::new(tx), Driver::Unix(drv)))
+        }
+    }
+}
+
+pub(super) async fn connect_io<Io>(io: Io, cfg: &Config) -> Result<(Client, Driver), Error>
+where
+    Io: AsyncIo + Send + 'static,
+{
+    let (mut drv, tx) = GenericDriver::new(Box::new(io) as _, cfg.resp3);
+    prepare_session(&mut drv, cfg).await?;
+    Ok((Client::new(tx), Driver::Dynamic(drv)))
+}
+
+// authenticate and select database before handing connection to client.
+async fn prepare_session<Io>(drv: &mut GenericDriver<Io>, cfg: &Config) -> Result<(), Error>
+where
+    Io: AsyncIo + Send,
+{
+    let mut cmds = Vec::new();
+
+    if cfg.resp3 {
+        let mut hello = Cmd::new("HELLO").arg(3);
+        if let Some(ref password) = cfg.password {
+            hello = hello
+                .arg("AUTH")
+                .arg(cfg.user.as_deref().unwrap_or("default"))
+                .arg(password);
+        }
+        cmds.push(hello);
+    } else if let Some(ref password) = cfg.password {
+        let auth = match cfg.user {
+            Some(ref user) => Cmd::new("AUTH").arg(user).arg(password),
+            None => Cmd::new("AUTH").arg(password),
+        };
+        cmds.push(auth);
+    }
+
+    if cfg.db != 0 {
+        cmds.push(Cmd::new("SELECT").arg(cfg.db));
+    }
+
+    for cmd in cmds {
+        let mut buf = BytesMut::new();
+        cmd.encode(&mut buf);
+        drv.send(buf).await?;
+        drv.recv().await?.into_result()?;
+    }
+
+    Ok(())
+}
+
+/// async driver of [Client].
+/// it handles IO and emit server sent out of band value that do not belong to any command. For example
+/// pub/sub message.
+///
+/// # Examples:
+/// ```rust
+/// use std::future::IntoFuture;
+/// use xitca_redis::{pubsub::Message, Driver};
+///
+/// // drive the client and listen to pub/sub message at the same time.
+/// fn drive_with_pubsub(mut drv: Driver) {
+///     tokio::spawn(async move {
+///         while let Ok(Some(value)) = drv.try_next().await {
+///             if let Some(msg) = Message::from_value(value) {
+///                 // *Note:
+///                 // handle message must be non-blocking to prevent starvation of driver.
+///             }
+///         }
+///     });
+/// }
+///
+/// // drive client without handling pub/sub message.
+/// fn drive_only(drv: Driver) {
+///     tokio::spawn(drv.into_future());
+/// }
+/// ```
+pub enum Driver {
+    Tcp(GenericDriver<TcpStream>),
+    Dynamic(GenericDriver<Box<dyn AsyncIoDyn + Send>>),
+    #[cfg(unix)]
+    Unix(GenericDriver<UnixStream>),
+}
+
+impl Driver {
+    /// drive io and wait for next out of band value sent from server.
+    ///
+    /// `Ok(None)` is returned when [Client] is dropped and all pending commands are finished.
+    pub async fn try_next(&mut self) -> Result<Option<Value>, Error> {
+        match self {
+            Self::Tcp(ref mut drv) => drv.try_next().await,
+            Self::Dynamic(ref mut drv) => drv.try_next().await,
+            #[cfg(unix)]
+            Self::Unix(ref mut drv) => drv.try_next().await,
+        }
+    }
+}
+
+impl IntoFuture for Driver {
+    type Output = ();
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(mut self) -> Self::IntoFuture {
+        Box::pin(async move { while self.try_next().await.ok().flatten().is_some() {} })
+    }
+}
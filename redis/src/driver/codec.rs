@@ -0,0 +1,48 @@
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::{
+    error::{DriverDownReceiving, Error},
+    value::Value,
+};
+
+pub(super) fn request_pair(msg_count: usize) -> (ResponseSender, Response) {
+    let (tx, rx) = unbounded_channel();
+    (ResponseSender { tx, msg_count }, Response { rx })
+}
+
+pub(crate) struct Response {
+    rx: UnboundedReceiver<Value>,
+}
+
+impl Response {
+    pub(crate) async fn recv(&mut self) -> Result<Value, Error> {
+        self.rx.recv().await.ok_or_else(|| DriverDownReceiving.into())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ResponseSender {
+    tx: UnboundedSender<Value>,
+    msg_count: usize,
+}
+
+pub(super) enum SenderState {
+    Continue,
+    Finish,
+}
+
+impl ResponseSender {
+    pub(super) fn send(&mut self, value: Value) -> SenderState {
+        debug_assert!(self.msg_count > 0);
+
+        let _ = self.tx.send(value);
+
+        self.msg_count -= 1;
+
+        if self.msg_count == 0 {
+            SenderState::Finish
+        } else {
+            SenderState::Continue
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use core::{future::poll_fn, pin::Pin};
+
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::Notify;
+use xitca_io::{
+    bytes::{Buf, BufRead, BytesMut},
+    io::{AsyncIo, Interest},
+};
+use xitca_unsafe_collection::futures::{Select as _, SelectOutput};
+
+use crate::{
+    error::{DriverDown, Error, ProtocolError},
+    value::Value,
+};
+
+use super::codec::{request_pair, Response, ResponseSender, SenderState};
+
+type PagedBytesMut = xitca_unsafe_collection::bytes::PagedBytesMut<4096>;
+
+pub(crate) struct DriverTx(Arc<SharedState>);
+
+impl Drop for DriverTx {
+    fn drop(&mut self) {
+        self.0.guarded.lock().unwrap().closed = true;
+        self.0.notify.notify_one();
+    }
+}
+
+impl DriverTx {
+    pub(crate) fn is_closed(&self) -> bool {
+        Arc::strong_count(&self.0) == 1
+    }
+
+    // send request and expect msg_count of values responded from server.
+    pub(crate) fn send_with<F>(&self, func: F, msg_count: usize) -> Result<Response, Error>
+    where
+        F: FnOnce(&mut BytesMut),
+    {
+        debug_assert!(msg_count > 0);
+
+        let mut inner = self.0.guarded.lock().unwrap();
+
+        if inner.closed {
+            return Err(DriverDown.into());
+        }
+
+        func(&mut inner.buf);
+        let (tx, rx) = request_pair(msg_count);
+        inner.res.push_back(tx);
+        self.0.notify.notify_one();
+
+        Ok(rx)
+    }
+
+    // send pub/sub request where server responds with out of band messages.
+    pub(crate) fn send_subscribe_with<F>(&self, func: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut BytesMut),
+    {
+        let mut inner = self.0.guarded.lock().unwrap();
+
+        if inner.closed {
+            return Err(DriverDown.into());
+        }
+
+        func(&mut inner.buf);
+        // RESP3 pub/sub messages are push values and connection does not enter subscribed mode.
+        if !inner.resp3 {
+            inner.subscribed = true;
+        }
+        self.0.notify.notify_one();
+
+        Ok(())
+    }
+}
+
+pub(crate) struct SharedState {
+    guarded: Mutex<State>,
+    notify: Notify,
+}
+
+struct State {
+    closed: bool,
+    resp3: bool,
+    // connection is in RESP2 subscribed mode where server sent pub/sub messages are arrays.
+    subscribed: bool,
+    buf: BytesMut,
+    res: VecDeque<ResponseSender>,
+}
+
+impl State {
+    // check if value is out of band message not belong to any request.
+    fn is_async(&mut self, value: &Value) -> bool {
+        let values = match value {
+            Value::Push(_) => return true,
+            Value::Array(values) if self.subscribed => values,
+            _ => return self.res.is_empty(),
+        };
+
+        match values.first().and_then(Value::as_bytes) {
+            Some(b"message" | b"pmessage" | b"smessage" | b"subscribe" | b"psubscribe" | b"ssubscribe") => true,
+            Some(b"unsubscribe" | b"punsubscribe" | b"sunsubscribe") => {
+                // no active subscription left and connection is back to normal mode.
+                if values.get(2).and_then(Value::as_integer) == Some(0) {
+                    self.subscribed = false;
+                }
+                true
+            }
+            _ => self.res.is_empty(),
+        }
+    }
+}
+
+pub struct GenericDriver<Io> {
+    pub(crate) io: Io,
+    pub(crate) read_buf: PagedBytesMut,
+    pub(crate) state: DriverState,
+    pub(crate) shared_state: Arc<SharedState>,
+    write_state: WriteState,
+}
+
+// in case driver is dropped without closing the shared state
+impl<Io> Drop for GenericDriver<Io> {
+    fn drop(&mut self) {
+        self.shared_state.guarded.lock().unwrap().closed = true;
+    }
+}
+
+enum WriteState {
+    Waiting,
+    WantWrite,
+    WantFlush,
+}
+
+pub(crate) enum DriverState {
+    Running,
+    Closing(Option<io::Error>),
+}
+
+impl<Io> GenericDriver<Io>
+where
+    Io: AsyncIo + Send,
+{
+    pub(crate) fn new(io: Io, resp3: bool) -> (Self, DriverTx) {
+        let state = Arc::new(SharedState {
+            guarded: Mutex::new(State {
+                closed: false,
+                resp3,
+                subscribed: false,
+                buf: BytesMut::new(),
+                res: VecDeque::new(),
+            }),
+            notify: Notify::new(),
+        });
+
+        (
+            Self {
+                io,
+                read_buf: PagedBytesMut::new(),
+                state: DriverState::Running,
+                shared_state: state.clone(),
+                write_state: WriteState::Waiting,
+            },
+            DriverTx(state),
+        )
+    }
+
+    fn want_write(&self) -> bool {
+        !matches!(self.write_state, WriteState::Waiting)
+    }
+
+    // send message directly through io. used before driver starts running.
+    pub(crate) async fn send(&mut self, msg: BytesMut) -> Result<(), Error> {
+        self.shared_state.guarded.lock().unwrap().buf.extend_from_slice(&msg);
+        self.write_state = WriteState::WantWrite;
+        loop {
+            self.try_write()?;
+            if !self.want_write() {
+                return Ok(());
+            }
+            self.io.ready(Interest::WRITABLE).await?;
+        }
+    }
+
+    // receive value directly from io. used before driver starts running.
+    pub(crate) async fn recv(&mut self) -> Result<Value, Error> {
+        loop {
+            if let Some(value) = Value::parse(self.read_buf.get_mut())? {
+                return Ok(value);
+            }
+            self.io.ready(Interest::READABLE).await?;
+            self.try_read()?;
+        }
+    }
+
+    pub(crate) async fn try_next(&mut self) -> Result<Option<Value>, Error> {
+        loop {
+            if let Some(msg) = self.try_decode()? {
+                return Ok(Some(msg));
+            }
+
+            let mut interest = if self.want_write() {
+                const { Interest::READABLE.add(Interest::WRITABLE) }
+            } else {
+                Interest::READABLE
+            };
+
+            let ready = match self.state {
+                DriverState::Running => 'inner: loop {
+                    match self
+                        .shared_state
+                        .notify
+                        .notified()
+                        .select(self.io.ready(interest))
+                        .await
+                    {
+                        SelectOutput::A(_) => {
+                            self.write_state = WriteState::WantWrite;
+                            interest = interest.add(Interest::WRITABLE);
+                            continue 'inner;
+                        }
+                        SelectOutput::B(ready) => break ready?,
+                    }
+                },
+                DriverState::Closing(ref mut e) => {
+                    if !interest.is_writable() && self.shared_state.guarded.lock().unwrap().res.is_empty() {
+                        // no interest to write to io and all response have been finished so
+                        // shutdown io and exit.
+                        poll_fn(|cx| Pin::new(&mut self.io).poll_shutdown(cx)).await?;
+                        return e.take().map(|e| Err(e.into())).transpose();
+                    }
+                    self.io.ready(interest).await?
+                }
+            };
+
+            if ready.is_readable() {
+                self.try_read()?;
+            }
+
+            if ready.is_writable() {
+                if let Err(e) = self.try_write() {
+                    // when write error occur the driver would go into half close state(read only).
+                    // clearing write_buf would drop all pending requests in it and hint the driver
+                    // no future Interest::WRITABLE should be passed to AsyncIo::ready method.
+                    let mut inner = self.shared_state.guarded.lock().unwrap();
+                    inner.buf.clear();
+                    // close shared state early so driver tx can observe the shutdown in first hand
+                    inner.closed = true;
+                    self.write_state = WriteState::Waiting;
+                    self.state = DriverState::Closing(Some(e));
+                }
+            }
+        }
+    }
+
+    fn try_read(&mut self) -> Result<(), Error> {
+        self.read_buf.do_io(&mut self.io).map_err(Into::into)
+    }
+
+    fn try_write(&mut self) -> io::Result<()> {
+        loop {
+            match self.write_state {
+                WriteState::WantFlush => {
+                    match io::Write::flush(&mut self.io) {
+                        Ok(_) => self.write_state = WriteState::Waiting,
+                        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                        Err(e) => return Err(e),
+                    }
+                    break;
+                }
+                WriteState::WantWrite => {
+                    let mut inner = self.shared_state.guarded.lock().unwrap();
+
+                    if inner.closed && matches!(self.state, DriverState::Running) {
+                        self.state = DriverState::Closing(None);
+                    }
+
+                    if inner.buf.is_empty() {
+                        self.write_state = WriteState::WantFlush;
+                        continue;
+                    }
+
+                    match io::Write::write(&mut self.io, &inner.buf) {
+                        Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                        Ok(n) => {
+                            inner.buf.advance(n);
+
+                            if inner.buf.is_empty() {
+                                self.write_state = WriteState::WantFlush;
+                            }
+                        }
+                        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                        Err(e) => return Err(e),
+                    }
+                }
+                WriteState::Waiting => unreachable!("try_write must be called when WriteState is waiting"),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn try_decode(&mut self) -> Result<Option<Value>, Error> {
+        while let Some(value) = Value::parse(self.read_buf.get_mut())? {
+            let mut inner = self.shared_state.guarded.lock().unwrap();
+
+            if inner.is_async(&value) {
+                return Ok(Some(value));
+            }
+
+            let Some(front) = inner.res.front_mut() else {
+                return Err(ProtocolError("server respond out of bound").into());
+            };
+            if let SenderState::Finish = front.send(value) {
+                inner.res.pop_front();
+            }
+        }
+        Ok(None)
+    }
+}
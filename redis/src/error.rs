@@ -0,0 +1,195 @@
+use core::{
+    convert::Infallible,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use std::{error, io};
+
+/// public facing error type. providing basic format and display based error handling.
+///
+/// for typed based error handling runtime type cast is needed with the help of other
+/// public error types offered by this module.
+///
+/// # Example
+/// ```rust
+/// use xitca_redis::error::{Error, ServerError};
+///
+/// fn is_server_error(e: Error) -> bool {
+///     // downcast error to ServerError type to check if error is responded by redis server.
+///     e.downcast_ref::<ServerError>().is_some()
+/// }
+/// ```
+pub struct Error(Box<dyn error::Error + Send + Sync>);
+
+impl Deref for Error {
+    type Target = dyn error::Error + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl DerefMut for Error {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut *self.0
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// error indicate [Client]'s [Driver] is dropped and can't be accessed anymore when sending request to driver.
+///
+/// command related to this error has not been sent to server and it's safe to retry operation if desired.
+///
+/// [Client]: crate::Client
+/// [Driver]: crate::Driver
+#[derive(Debug, Default)]
+pub struct DriverDown;
+
+impl fmt::Display for DriverDown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Driver is dropped and unaccessible.")
+    }
+}
+
+impl error::Error for DriverDown {}
+
+impl From<DriverDown> for Error {
+    fn from(e: DriverDown) -> Self {
+        Self(Box::new(e))
+    }
+}
+
+/// error indicate [Client]'s [Driver] is dropped and can't be accessed anymore when receiving response
+/// from server.
+///
+/// command related to this error may or may not executed successfully and it should not be retried blindly.
+///
+/// [Client]: crate::Client
+/// [Driver]: crate::Driver
+#[derive(Debug)]
+pub struct DriverDownReceiving;
+
+impl fmt::Display for DriverDownReceiving {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Driver is dropped and unaccessible. Response data is lost and unrecoverable.")
+    }
+}
+
+impl error::Error for DriverDownReceiving {}
+
+impl From<DriverDownReceiving> for Error {
+    fn from(e: DriverDownReceiving) -> Self {
+        Self(Box::new(e))
+    }
+}
+
+/// error responded by redis server. For example: `WRONGTYPE Operation against a key holding the wrong kind
+/// of value`
+#[derive(Debug)]
+pub struct ServerError(pub String);
+
+impl ServerError {
+    /// error code of server error. it's the first word of error message by convention. For example:
+    /// `WRONGTYPE`.
+    pub fn code(&self) -> &str {
+        self.0.split(' ').next().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for ServerError {}
+
+impl From<ServerError> for Error {
+    fn from(e: ServerError) -> Self {
+        Self(Box::new(e))
+    }
+}
+
+/// error indicate data sent from server violates RESP protocol.
+#[derive(Debug)]
+pub struct ProtocolError(pub(crate) &'static str);
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RESP protocol error: {}", self.0)
+    }
+}
+
+impl error::Error for ProtocolError {}
+
+impl From<ProtocolError> for Error {
+    fn from(e: ProtocolError) -> Self {
+        Self(Box::new(e))
+    }
+}
+
+/// error indicate invalid [Config](crate::Config).
+#[derive(Debug)]
+pub struct ConfigError(pub(crate) &'static str);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid config: {}", self.0)
+    }
+}
+
+impl error::Error for ConfigError {}
+
+impl From<ConfigError> for Error {
+    fn from(e: ConfigError) -> Self {
+        Self(Box::new(e))
+    }
+}
+
+/// error indicate server responded with unexpected value.
+#[derive(Debug)]
+pub struct UnexpectedValue;
+
+impl fmt::Display for UnexpectedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unexpected value responded by server")
+    }
+}
+
+impl error::Error for UnexpectedValue {}
+
+impl From<UnexpectedValue> for Error {
+    fn from(e: UnexpectedValue) -> Self {
+        Self(Box::new(e))
+    }
+}
+
+impl From<Infallible> for Error {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self(Box::new(e))
+    }
+}
@@ -0,0 +1,203 @@
+#![forbid(unsafe_code)]
+
+//! An async redis client on top of [xitca-io](https://docs.rs/xitca-io).
+
+mod client;
+mod config;
+mod driver;
+
+pub mod cmd;
+pub mod error;
+pub mod pubsub;
+pub mod value;
+
+pub use self::{
+    client::Client,
+    cmd::{Cmd, Pipeline},
+    config::{Config, Host},
+    driver::Driver,
+    error::Error,
+    value::Value,
+};
+
+use xitca_io::io::AsyncIo;
+
+#[derive(Debug)]
+pub struct Redis<C> {
+    cfg: C,
+}
+
+impl<C> Redis<C>
+where
+    Config: TryFrom<C>,
+    Error: From<<Config as TryFrom<C>>::Error>,
+{
+    pub fn new(cfg: C) -> Self {
+        Self { cfg }
+    }
+
+    /// Connect to server. The returned values are [Client] and a detached async task
+    /// that drives the client communication to server and it needs to spawn on an async runtime.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// use std::future::IntoFuture;
+    /// use xitca_redis::{Cmd, Redis};
+    /// # async fn connect() {
+    /// let (client, driver) = Redis::new("redis://localhost:6379").connect().await.unwrap();
+    ///
+    /// // spawn driver as async task.
+    /// tokio::spawn(driver.into_future());
+    ///
+    /// // use client for query.
+    /// let value = client.query(&Cmd::new("GET").arg("foo")).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn connect(self) -> Result<(Client, Driver), Error> {
+        let cfg = Config::try_from(self.cfg)?;
+        driver::connect(&cfg).await
+    }
+
+    /// Connect to server with an already established Io type.
+    /// Io type must impl [AsyncIo] trait to instruct the client and driver how to transmit
+    /// data through the Io.
+    pub async fn connect_io<Io>(self, io: Io) -> Result<(Client, Driver), Error>
+    where
+        Io: AsyncIo + Send + 'static,
+    {
+        let cfg = Config::try_from(self.cfg)?;
+        driver::connect_io(io, &cfg).await
+    }
+}
+
+fn _assert_send<F: Send>(_: F) {}
+fn _assert_send2<F: Send>() {}
+
+fn _assert_connect_send() {
+    _assert_send(crate::Redis::new("redis://localhost").connect());
+}
+
+fn _assert_driver_send() {
+    _assert_send2::<Driver>();
+}
+
+#[cfg(test)]
+mod test {
+    use std::future::IntoFuture;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use xitca_io::bytes::Bytes;
+
+    use super::*;
+
+    // a mock server respond to commands with pre defined responses in order.
+    async fn mock(responses: &'static [(&'static [u8], &'static [u8])]) -> Config {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            for (req, res) in responses {
+                while !buf.starts_with(req) {
+                    assert!(req.starts_with(&buf) || buf.len() < req.len(), "unexpected request");
+                    stream.read_buf(&mut buf).await.unwrap();
+                }
+                buf.drain(..req.len());
+                stream.write_all(res).await.unwrap();
+            }
+        });
+
+        let mut cfg = Config::new();
+        cfg.host("127.0.0.1").port(port);
+        cfg
+    }
+
+    #[tokio::test]
+    async fn query() {
+        let mut cfg = mock(&[
+            (b"*2\r\n$6\r\nSELECT\r\n$1\r\n1\r\n", b"+OK\r\n"),
+            (b"*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n", b"$3\r\nbar\r\n"),
+            (b"*1\r\n$4\r\nINCR\r\n", b"-ERR wrong number of arguments\r\n"),
+            (
+                b"*1\r\n$4\r\nPING\r\n*2\r\n$4\r\nINCR\r\n$1\r\na\r\n",
+                b"+PONG\r\n-WRONGTYPE bad\r\n",
+            ),
+        ])
+        .await;
+
+        cfg.db(1);
+
+        let (cli, drv) = Redis::new(cfg).connect().await.unwrap();
+        let handle = tokio::spawn(drv.into_future());
+
+        let value = cli.query(&Cmd::new("GET").arg("foo")).await.unwrap();
+        assert_eq!(value, Value::BulkString(Bytes::from_static(b"bar")));
+
+        let err = cli.query(&Cmd::new("INCR")).await.unwrap_err();
+        let err = err.downcast_ref::<error::ServerError>().unwrap();
+        assert_eq!(err.code(), "ERR");
+
+        let pipe = Pipeline::new().cmd(Cmd::new("PING")).cmd(Cmd::new("INCR").arg("a"));
+        let values = cli.pipeline(&pipe).await.unwrap();
+        assert_eq!(
+            values,
+            [Value::SimpleString("PONG".into()), Value::Error("WRONGTYPE bad".into())]
+        );
+
+        drop(cli);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn pubsub() {
+        let mut cfg = mock(&[
+            (
+                b"*5\r\n$5\r\nHELLO\r\n$1\r\n3\r\n$4\r\nAUTH\r\n$7\r\ndefault\r\n$4\r\npass\r\n",
+                b"%1\r\n+server\r\n+redis\r\n",
+            ),
+            (
+                b"*2\r\n$9\r\nSUBSCRIBE\r\n$4\r\nchan\r\n",
+                b">3\r\n+subscribe\r\n+chan\r\n:1\r\n>3\r\n+message\r\n+chan\r\n$2\r\nhi\r\n",
+            ),
+            (b"*1\r\n$4\r\nPING\r\n", b"+PONG\r\n"),
+            (
+                b"*4\r\n$6\r\nLRANGE\r\n$4\r\nlist\r\n$1\r\n0\r\n$2\r\n-1\r\n",
+                b"*2\r\n$7\r\nmessage\r\n$1\r\nx\r\n",
+            ),
+        ])
+        .await;
+
+        cfg.password("pass").resp3();
+
+        let (cli, mut drv) = Redis::new(cfg).connect().await.unwrap();
+
+        cli.subscribe(["chan"]).unwrap();
+
+        let value = drv.try_next().await.unwrap().unwrap();
+        assert!(pubsub::Message::from_value(value).is_none());
+
+        let value = drv.try_next().await.unwrap().unwrap();
+        let msg = pubsub::Message::from_value(value).unwrap();
+        assert_eq!(msg.channel, "chan");
+        assert_eq!(msg.payload, "hi");
+
+        let handle = tokio::spawn(drv.into_future());
+
+        let value = cli.query(&Cmd::new("PING")).await.unwrap();
+        assert_eq!(value.as_str(), Some("PONG"));
+
+        // array reply looks like RESP2 pub/sub message is not treated as out of band message with RESP3.
+        let value = cli
+            .query(&Cmd::new("LRANGE").arg("list").arg("0").arg("-1"))
+            .await
+            .unwrap();
+        assert_eq!(value.as_array().unwrap()[0].as_str(), Some("message"));
+
+        drop(cli);
+        handle.await.unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+//! pub/sub message type.
+
+use xitca_io::bytes::Bytes;
+
+use crate::value::Value;
+
+/// message published to subscribed channel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    /// channel the message published to.
+    pub channel: Bytes,
+    /// pattern matched the channel. only present for message received through [Client::psubscribe].
+    ///
+    /// [Client::psubscribe]: crate::Client::psubscribe
+    pub pattern: Option<Bytes>,
+    /// payload of message.
+    pub payload: Bytes,
+}
+
+impl Message {
+    /// try to construct message from out of band value emitted by [Driver](crate::Driver).
+    ///
+    /// `None` is returned when value is not a pub/sub message. For example: confirmation of subscription.
+    pub fn from_value(value: Value) -> Option<Self> {
+        let (Value::Push(values) | Value::Array(values)) = value else {
+            return None;
+        };
+
+        let mut values = values.into_iter().map(|value| match value {
+            Value::BulkString(bytes) => Some(bytes),
+            Value::SimpleString(str) => Some(Bytes::from(str)),
+            _ => None,
+        });
+
+        let kind = values.next()??;
+
+        match kind.as_ref() {
+            b"message" | b"smessage" => {
+                let channel = values.next()??;
+                let payload = values.next()??;
+                Some(Self {
+                    channel,
+                    pattern: None,
+                    payload,
+                })
+            }
+            b"pmessage" => {
+                let pattern = values.next()??;
+                let channel = values.next()??;
+                let payload = values.next()??;
+                Some(Self {
+                    channel,
+                    pattern: Some(pattern),
+                    payload,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bulk(str: &'static str) -> Value {
+        Value::BulkString(Bytes::from_static(str.as_bytes()))
+    }
+
+    #[test]
+    fn from_value() {
+        let msg = Message::from_value(Value::Push(vec![bulk("message"), bulk("chan"), bulk("hi")])).unwrap();
+        assert_eq!(msg.channel, "chan");
+        assert_eq!(msg.pattern, None);
+        assert_eq!(msg.payload, "hi");
+
+        let msg = Message::from_value(Value::Array(vec![
+            bulk("pmessage"),
+            bulk("ch*"),
+            bulk("chan"),
+            bulk("hi"),
+        ]))
+        .unwrap();
+        assert_eq!(msg.pattern.as_deref(), Some(&b"ch*"[..]));
+
+        assert!(Message::from_value(Value::Push(vec![bulk("subscribe"), bulk("chan"), Value::Integer(1)])).is_none());
+        assert!(Message::from_value(Value::Push(vec![bulk("message"), bulk("chan")])).is_none());
+        assert!(Message::from_value(Value::Nil).is_none());
+    }
+}
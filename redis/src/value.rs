@@ -0,0 +1,351 @@
+//! RESP2 and RESP3 protocol value type.
+
+use xitca_io::bytes::{Bytes, BytesMut};
+
+use crate::error::{Error, ProtocolError, ServerError};
+
+// max nesting level of aggregate value.
+const MAX_DEPTH: usize = 64;
+
+/// value responded by redis server.
+///
+/// RESP2 null bulk string and null array are both represented as [Value::Nil].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Nil,
+    SimpleString(String),
+    Error(String),
+    Integer(i64),
+    BulkString(Bytes),
+    Array(Vec<Value>),
+    /// RESP3 only.
+    Double(f64),
+    /// RESP3 only.
+    Boolean(bool),
+    /// RESP3 only.
+    BigNumber(String),
+    /// RESP3 only. format is a three bytes type hint. For example: `txt` or `mkd`.
+    VerbatimString {
+        format: String,
+        text: Bytes,
+    },
+    /// RESP3 only.
+    Map(Vec<(Value, Value)>),
+    /// RESP3 only.
+    Set(Vec<Value>),
+    /// RESP3 only. out of band data sent by server. For example: pub/sub message.
+    Push(Vec<Value>),
+}
+
+impl Value {
+    /// true when value is nil.
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Self::Nil)
+    }
+
+    /// get bytes of bulk string, simple string or verbatim string value.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::BulkString(bytes) | Self::VerbatimString { text: bytes, .. } => Some(bytes),
+            Self::SimpleString(str) => Some(str.as_bytes()),
+            _ => None,
+        }
+    }
+
+    /// get string of bulk string, simple string or verbatim string value when it's valid utf-8.
+    pub fn as_str(&self) -> Option<&str> {
+        self.as_bytes().and_then(|bytes| core::str::from_utf8(bytes).ok())
+    }
+
+    /// get integer value. bulk string and simple string would be parsed as integer.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(int) => Some(*int),
+            _ => self.as_str().and_then(|str| str.parse().ok()),
+        }
+    }
+
+    /// get inner values of array, set and push value.
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(values) | Self::Set(values) | Self::Push(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// transform error value to [Error]. other values are returned as is.
+    pub fn into_result(self) -> Result<Self, Error> {
+        match self {
+            Self::Error(msg) => Err(ServerError(msg).into()),
+            value => Ok(value),
+        }
+    }
+
+    /// try to parse one complete value from buffer. bytes of parsed value are removed from buffer.
+    ///
+    /// `Ok(None)` is returned when buffer does not contain a complete value.
+    pub fn parse(buf: &mut BytesMut) -> Result<Option<Self>, Error> {
+        match frame_len(buf, 0, 0)? {
+            Some(len) => {
+                let frame = buf.split_to(len).freeze();
+                decode(&frame, &mut 0).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+// find index of CRLF started from pos. return index of CR.
+fn line(buf: &[u8], pos: usize) -> Option<usize> {
+    buf.get(pos..)?
+        .windows(2)
+        .position(|w| w == b"\r\n")
+        .map(|idx| pos + idx)
+}
+
+fn parse_int(buf: &[u8]) -> Result<i64, Error> {
+    core::str::from_utf8(buf)
+        .ok()
+        .and_then(|str| str.parse().ok())
+        .ok_or_else(|| ProtocolError("invalid integer").into())
+}
+
+// length prefix of aggregate and blob value. -1 is treated as null.
+fn parse_len(buf: &[u8]) -> Result<Option<usize>, Error> {
+    match parse_int(buf)? {
+        -1 => Ok(None),
+        len => usize::try_from(len)
+            .map(Some)
+            .map_err(|_| ProtocolError("invalid length").into()),
+    }
+}
+
+// check if buffer contains a complete value started from pos and return the end index of it.
+fn frame_len(buf: &[u8], pos: usize, depth: usize) -> Result<Option<usize>, Error> {
+    if depth > MAX_DEPTH {
+        return Err(ProtocolError("value nested too deep").into());
+    }
+
+    let Some(&tag) = buf.get(pos) else {
+        return Ok(None);
+    };
+
+    let Some(end) = line(buf, pos + 1) else {
+        return Ok(None);
+    };
+
+    let next = end + 2;
+
+    match tag {
+        b'+' | b'-' | b':' | b',' | b'#' | b'(' | b'_' => Ok(Some(next)),
+        b'$' | b'!' | b'=' => match parse_len(&buf[pos + 1..end])? {
+            None => Ok(Some(next)),
+            Some(len) => {
+                let end = next
+                    .checked_add(len)
+                    .and_then(|end| end.checked_add(2))
+                    .ok_or(ProtocolError("invalid length"))?;
+                match buf.get(end - 2..end) {
+                    Some(b"\r\n") => Ok(Some(end)),
+                    Some(_) => Err(ProtocolError("blob value not terminated by CRLF").into()),
+                    None => Ok(None),
+                }
+            }
+        },
+        b'*' | b'~' | b'>' | b'%' | b'|' => {
+            let Some(mut count) = parse_len(&buf[pos + 1..end])? else {
+                return Ok(Some(next));
+            };
+
+            if matches!(tag, b'%' | b'|') {
+                count = count.checked_mul(2).ok_or(ProtocolError("invalid length"))?;
+            }
+
+            let mut next = next;
+            for _ in 0..count {
+                match frame_len(buf, next, depth + 1)? {
+                    Some(end) => next = end,
+                    None => return Ok(None),
+                }
+            }
+
+            // attribute is followed by the value it describes.
+            if tag == b'|' {
+                return frame_len(buf, next, depth + 1);
+            }
+
+            Ok(Some(next))
+        }
+        _ => Err(ProtocolError("unknown value type").into()),
+    }
+}
+
+// decode a complete value from frame. the frame must be checked by frame_len beforehand.
+fn decode(frame: &Bytes, pos: &mut usize) -> Result<Value, Error> {
+    let tag = frame[*pos];
+    let end = line(frame, *pos + 1).expect("frame must be complete");
+    let line = &frame[*pos + 1..end];
+    *pos = end + 2;
+
+    let string = |line: &[u8]| {
+        String::from_utf8(line.to_vec()).map_err(|_| Error::from(ProtocolError("string is not valid utf-8")))
+    };
+
+    let value = match tag {
+        b'+' => Value::SimpleString(string(line)?),
+        b'-' => Value::Error(string(line)?),
+        b':' => Value::Integer(parse_int(line)?),
+        b'_' => Value::Nil,
+        b',' => {
+            let double = core::str::from_utf8(line)
+                .ok()
+                .and_then(|str| match str {
+                    "inf" => Some(f64::INFINITY),
+                    "-inf" => Some(f64::NEG_INFINITY),
+                    str => str.parse().ok(),
+                })
+                .ok_or(ProtocolError("invalid double"))?;
+            Value::Double(double)
+        }
+        b'#' => match line {
+            b"t" => Value::Boolean(true),
+            b"f" => Value::Boolean(false),
+            _ => return Err(ProtocolError("invalid boolean").into()),
+        },
+        b'(' => Value::BigNumber(string(line)?),
+        b'$' | b'!' | b'=' => {
+            let Some(len) = parse_len(line)? else {
+                return Ok(Value::Nil);
+            };
+            let blob = frame.slice(*pos..*pos + len);
+            *pos += len + 2;
+            match tag {
+                b'$' => Value::BulkString(blob),
+                b'!' => Value::Error(string(&blob)?),
+                _ => {
+                    if blob.len() < 4 || blob[3] != b':' {
+                        return Err(ProtocolError("invalid verbatim string").into());
+                    }
+                    Value::VerbatimString {
+                        format: string(&blob[..3])?,
+                        text: blob.slice(4..),
+                    }
+                }
+            }
+        }
+        b'%' | b'|' => {
+            let Some(count) = parse_len(line)? else {
+                return Ok(Value::Nil);
+            };
+            let mut map = Vec::with_capacity(count);
+            for _ in 0..count {
+                let key = decode(frame, pos)?;
+                let value = decode(frame, pos)?;
+                map.push((key, value));
+            }
+            // attribute is not exposed and the following value is returned.
+            if tag == b'|' {
+                return decode(frame, pos);
+            }
+            Value::Map(map)
+        }
+        _ => {
+            let Some(count) = parse_len(line)? else {
+                return Ok(Value::Nil);
+            };
+            let values = (0..count).map(|_| decode(frame, pos)).collect::<Result<_, _>>()?;
+            match tag {
+                b'*' => Value::Array(values),
+                b'~' => Value::Set(values),
+                _ => Value::Push(values),
+            }
+        }
+    };
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(buf: &[u8]) -> Option<Value> {
+        let mut buf = BytesMut::from(buf);
+        let value = Value::parse(&mut buf).unwrap();
+        if value.is_some() {
+            assert!(buf.is_empty());
+        }
+        value
+    }
+
+    #[test]
+    fn resp2() {
+        assert_eq!(parse(b"+OK\r\n"), Some(Value::SimpleString("OK".into())));
+        assert_eq!(parse(b"-ERR bad\r\n"), Some(Value::Error("ERR bad".into())));
+        assert_eq!(parse(b":-996\r\n"), Some(Value::Integer(-996)));
+        assert_eq!(parse(b"$-1\r\n"), Some(Value::Nil));
+        assert_eq!(parse(b"*-1\r\n"), Some(Value::Nil));
+        assert_eq!(
+            parse(b"$5\r\nhe\r\no\r\n"),
+            Some(Value::BulkString(Bytes::from_static(b"he\r\no")))
+        );
+        assert_eq!(
+            parse(b"*2\r\n$3\r\nfoo\r\n:1\r\n"),
+            Some(Value::Array(vec![
+                Value::BulkString(Bytes::from_static(b"foo")),
+                Value::Integer(1)
+            ]))
+        );
+
+        assert_eq!(parse(b""), None);
+        assert_eq!(parse(b"+OK\r"), None);
+        assert_eq!(parse(b"$5\r\nhe"), None);
+        assert_eq!(parse(b"*2\r\n$3\r\nfoo\r\n"), None);
+
+        assert!(Value::parse(&mut BytesMut::from(&b"$3\r\nfooo\r\n"[..])).is_err());
+        assert!(Value::parse(&mut BytesMut::from(&b"?\r\n"[..])).is_err());
+
+        // huge length waits for more data without overflowing.
+        assert_eq!(parse(b"$9223372036854775807\r\nfoo"), None);
+    }
+
+    #[test]
+    fn resp3() {
+        assert_eq!(parse(b"_\r\n"), Some(Value::Nil));
+        assert_eq!(parse(b",1.5\r\n"), Some(Value::Double(1.5)));
+        assert_eq!(parse(b",-inf\r\n"), Some(Value::Double(f64::NEG_INFINITY)));
+        assert_eq!(parse(b"#t\r\n"), Some(Value::Boolean(true)));
+        assert_eq!(parse(b"(123\r\n"), Some(Value::BigNumber("123".into())));
+        assert_eq!(parse(b"!3\r\nERR\r\n"), Some(Value::Error("ERR".into())));
+        assert_eq!(
+            parse(b"=7\r\ntxt:abc\r\n"),
+            Some(Value::VerbatimString {
+                format: "txt".into(),
+                text: Bytes::from_static(b"abc")
+            })
+        );
+        assert_eq!(
+            parse(b"%1\r\n+key\r\n:1\r\n"),
+            Some(Value::Map(vec![(Value::SimpleString("key".into()), Value::Integer(1))]))
+        );
+        assert_eq!(parse(b"~1\r\n#f\r\n"), Some(Value::Set(vec![Value::Boolean(false)])));
+        assert_eq!(
+            parse(b">2\r\n+message\r\n+chan\r\n"),
+            Some(Value::Push(vec![
+                Value::SimpleString("message".into()),
+                Value::SimpleString("chan".into())
+            ]))
+        );
+        assert_eq!(parse(b"|1\r\n+ttl\r\n:3\r\n:1\r\n"), Some(Value::Integer(1)));
+        assert_eq!(parse(b"|1\r\n+ttl\r\n:3\r\n"), None);
+    }
+
+    #[test]
+    fn accessor() {
+        let value = Value::BulkString(Bytes::from_static(b"42"));
+        assert_eq!(value.as_str(), Some("42"));
+        assert_eq!(value.as_integer(), Some(42));
+        assert!(Value::Error("ERR".into()).into_result().is_err());
+        assert!(Value::Nil.into_result().unwrap().is_nil());
+    }
+}
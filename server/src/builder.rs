@@ -1,4 +1,4 @@
-use std::{collections::HashMap, future::Future, pin::Pin, time::Duration};
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc, time::Duration};
 
 #[cfg(not(target_family = "wasm"))]
 use std::{io, net};
@@ -6,10 +6,38 @@ use std::{io, net};
 use xitca_io::net::Stream;
 
 use crate::{
+    filter::ConnectionFilter,
     net::AsListener,
     server::{IntoServiceObj, Server, ServerFuture, ServiceObj},
 };
 
+/// Builder of server.
+///
+/// A single server can host multiple listeners. Every listener is registered with a unique name and it's own
+/// service. The name is used for logging and for matching inherited listeners on upgrade.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_io::net::TcpStream;
+/// # use xitca_server::Builder;
+/// # use xitca_service::fn_service;
+/// # #[tokio::main]
+/// # async fn main() -> std::io::Result<()> {
+/// let mut server = Builder::new()
+///     // public facing listener with big backlog.
+///     .listener_backlog("app", 4096)
+///     .bind("app", "127.0.0.1:0", fn_service(|_io: TcpStream| async { Ok::<_, ()>(()) }))?
+///     // internal listener with small backlog and different service.
+///     .listener_backlog("metrics", 16)
+///     .bind("metrics", "127.0.0.1:0", fn_service(|_io: TcpStream| async { Ok::<_, ()>(()) }))?
+///     .build();
+///
+/// let handle = server.handle()?;
+/// tokio::spawn(server);
+/// # handle.stop(true);
+/// # Ok(())
+/// # }
+/// ```
 pub struct Builder {
     pub(crate) server_threads: usize,
     pub(crate) worker_threads: usize,
@@ -17,9 +45,14 @@ pub struct Builder {
     pub(crate) listeners: HashMap<String, Vec<Box<dyn AsListener>>>,
     pub(crate) factories: HashMap<String, ServiceObj>,
     pub(crate) enable_signal: bool,
+    pub(crate) enable_upgrade: bool,
     pub(crate) shutdown_timeout: Duration,
     pub(crate) on_worker_start: Box<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>,
+    pub(crate) connection_filter: Option<Arc<dyn ConnectionFilter>>,
+    pub(crate) max_connections: Option<usize>,
+    pub(crate) max_connections_per_worker: Option<usize>,
     backlog: u32,
+    listener_backlog: HashMap<String, u32>,
 }
 
 impl Default for Builder {
@@ -38,9 +71,14 @@ impl Builder {
             listeners: HashMap::new(),
             factories: HashMap::new(),
             enable_signal: true,
+            enable_upgrade: false,
             shutdown_timeout: Duration::from_secs(30),
             on_worker_start: Box::new(|| Box::pin(async {})),
+            connection_filter: None,
+            max_connections: None,
+            max_connections_per_worker: None,
             backlog: 2048,
+            listener_backlog: HashMap::new(),
         }
     }
 
@@ -96,6 +134,38 @@ impl Builder {
         self
     }
 
+    /// Enable zero downtime binary upgrade.
+    ///
+    /// When enabled, receiving `SIGUSR2` signal or calling [ServerHandle::upgrade] would spawn a new process of
+    /// current executable with the same arguments. Listeners are passed to the new process through file
+    /// descriptor inheritance and current server would graceful shutdown after the new process starts running.
+    /// When the new process exits or is not running in 30 seconds it's killed and current server keeps running.
+    ///
+    /// The new process would pick up inherited listeners in [Builder::bind] and [Builder::bind_unix] where listener
+    /// with the same name is inherited instead of binding a new one. It calls [Builder::inherit_listeners] so it must
+    /// be called before binding.
+    ///
+    /// Upgrade through [ServerHandle::upgrade] is always available on unix system regardless this setting. The new
+    /// process still has to enable upgrade or call [Builder::inherit_listeners] to pick up listeners.
+    ///
+    /// [ServerHandle::upgrade]: crate::ServerHandle::upgrade
+    #[cfg(unix)]
+    pub fn enable_upgrade(mut self) -> Self {
+        self.enable_upgrade = true;
+        self.inherit_listeners()
+    }
+
+    /// Pick up listeners inherited from upgrading parent process(and systemd socket activation when `systemd`
+    /// feature is enabled) in [Builder::bind] and [Builder::bind_unix] by name.
+    ///
+    /// Environment variables of inherited file descriptors are read and removed here. It should be called before
+    /// spawning other threads that access environment variables. Only the first call in a process has effect.
+    #[cfg(unix)]
+    pub fn inherit_listeners(self) -> Self {
+        crate::inherit::init();
+        self
+    }
+
     /// Timeout for graceful workers shutdown in seconds.
     ///
     /// After receiving a stop signal, workers have this much time to finish serving requests.
@@ -107,11 +177,72 @@ impl Builder {
         self
     }
 
+    /// Set default backlog of listeners bound by server.
+    ///
+    /// Default set to 2048.
     pub fn backlog(mut self, num: u32) -> Self {
         self.backlog = num;
         self
     }
 
+    /// Set backlog of listener with given name. Override the default value set by [Builder::backlog].
+    ///
+    /// Must be called before binding the named listener. Listeners passed through [Builder::listen] or
+    /// inherited from parent process are not affected.
+    pub fn listener_backlog<N>(mut self, name: N, num: u32) -> Self
+    where
+        N: AsRef<str>,
+    {
+        self.listener_backlog.insert(name.as_ref().to_string(), num);
+        self
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn backlog_of(&self, name: &str) -> u32 {
+        self.listener_backlog.get(name).copied().unwrap_or(self.backlog)
+    }
+
+    /// Set filter for accepted connections. Filter runs right after a connection is accepted and before it's
+    /// passed to service. Rejected connection is closed immediately.
+    ///
+    /// See [filter](crate::filter) module for detail.
+    pub fn connection_filter<F>(mut self, filter: F) -> Self
+    where
+        F: ConnectionFilter,
+    {
+        self.connection_filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Set max number of concurrent connections served by all workers.
+    ///
+    /// When limit is reached workers pause accepting new connections until existing ones are closed. Pending
+    /// connections are left in listener's backlog. Pausing is counted by
+    /// [WorkerStats::accept_paused_total](crate::stats::WorkerStats::accept_paused_total).
+    ///
+    /// Default to no limit.
+    ///
+    /// # Panics:
+    /// When received 0 as max connections.
+    pub fn max_connections(mut self, num: usize) -> Self {
+        assert_ne!(num, 0, "Max connections must be higher than 0");
+        self.max_connections = Some(num);
+        self
+    }
+
+    /// Set max number of concurrent connections served by **each** worker. It works together with
+    /// [Builder::max_connections] and accepting is paused when either limit is reached.
+    ///
+    /// Default to no limit.
+    ///
+    /// # Panics:
+    /// When received 0 as max connections.
+    pub fn max_connections_per_worker(mut self, num: usize) -> Self {
+        assert_ne!(num, 0, "Max connections per worker must be higher than 0");
+        self.max_connections_per_worker = Some(num);
+        self
+    }
+
     #[doc(hidden)]
     /// Async callback called when worker thread is spawned.
     ///
@@ -150,8 +281,13 @@ impl Builder {
 
     pub fn build(self) -> ServerFuture {
         let enable_signal = self.enable_signal;
+        let enable_upgrade = self.enable_upgrade;
         match Server::new(self) {
-            Ok(server) => ServerFuture::Init { server, enable_signal },
+            Ok(server) => ServerFuture::Init {
+                server,
+                enable_signal,
+                enable_upgrade,
+            },
             Err(e) => ServerFuture::Error(e),
         }
     }
@@ -180,12 +316,17 @@ impl Builder {
         F: IntoServiceObj<St>,
         St: TryFrom<Stream> + 'static,
     {
+        #[cfg(unix)]
+        if let Some(crate::inherit::InheritedListener::Tcp(listener)) = crate::inherit::take(name.as_ref())? {
+            return Ok(self.listen(name, listener, service));
+        }
+
         let listener = net::TcpListener::bind(addr)?;
         listener.set_nonblocking(true)?;
 
         let socket = socket2::SockRef::from(&listener);
         socket.set_reuse_address(true)?;
-        socket.listen(self.backlog as _)?;
+        socket.listen(self.backlog_of(name.as_ref()) as _)?;
 
         Ok(self.listen(name, listener, service))
     }
@@ -200,6 +341,10 @@ impl Builder {
         F: IntoServiceObj<St>,
         St: TryFrom<Stream> + 'static,
     {
+        if let Some(crate::inherit::InheritedListener::Unix(listener)) = crate::inherit::take(name.as_ref())? {
+            return Ok(self.listen(name, listener, service));
+        }
+
         // The path must not exist when we try to bind.
         // Try to remove it to avoid bind error.
         if let Err(e) = std::fs::remove_file(path.as_ref()) {
@@ -210,6 +355,7 @@ impl Builder {
         }
 
         let listener = std::os::unix::net::UnixListener::bind(path)?;
+        socket2::SockRef::from(&listener).listen(self.backlog_of(name.as_ref()) as _)?;
 
         Ok(self.listen(name, listener, service))
     }
@@ -238,7 +384,7 @@ impl Builder {
 
         self = self._bind(name.as_ref(), addr, service)?;
 
-        let builder = xitca_io::net::QuicListenerBuilder::new(addr, config).backlog(self.backlog);
+        let builder = xitca_io::net::QuicListenerBuilder::new(addr, config).backlog(self.backlog_of(name.as_ref()));
 
         self.listeners
             .get_mut(name.as_ref())
@@ -266,7 +412,7 @@ impl Builder {
             .next()
             .ok_or_else(|| io::Error::new(io::ErrorKind::AddrNotAvailable, "Can not parse SocketAddr"))?;
 
-        let listener = xitca_io::net::QuicListenerBuilder::new(addr, config).backlog(self.backlog);
+        let listener = xitca_io::net::QuicListenerBuilder::new(addr, config).backlog(self.backlog_of(name.as_ref()));
 
         Ok(self.listen(name, listener, service))
     }
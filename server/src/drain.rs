@@ -0,0 +1,127 @@
+//! worker local signal of server shutdown.
+//!
+//! Every worker thread of server has it's own [Drain]. It's started when worker stops accepting connections and
+//! graceful shutdown waits for outstanding [DrainGuard] to be dropped(bounded by shutdown timeout) together with
+//! active connections. Useful for background task living on worker thread and finishing it's work before worker
+//! exits.
+//!
+//! # Examples
+//! ```rust
+//! use xitca_server::drain::Drain;
+//!
+//! async fn background_task() {
+//!     // not running on server's worker thread.
+//!     let Some(drain) = Drain::current() else { return };
+//!
+//!     tokio::task::spawn_local(async move {
+//!         while !drain.is_draining() {
+//!             // graceful shutdown waits for guard to be dropped.
+//!             let _guard = drain.guard();
+//!             // do some work.
+//!         }
+//!     });
+//! }
+//! ```
+
+use core::cell::{Cell, RefCell};
+
+use std::rc::Rc;
+
+use tokio::sync::Notify;
+
+/// worker local handle of shutdown signal. See [module](self) level doc for detail.
+#[derive(Clone, Default)]
+pub struct Drain(Rc<Inner>);
+
+#[derive(Default)]
+struct Inner {
+    draining: Cell<bool>,
+    guards: Cell<usize>,
+    notify: Notify,
+}
+
+impl Drain {
+    /// get drain of current worker thread. return None when not called from a worker thread of server.
+    pub fn current() -> Option<Self> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// check if worker is draining.
+    pub fn is_draining(&self) -> bool {
+        self.0.draining.get()
+    }
+
+    /// wait until worker starts draining. resolve immediately when worker is already draining.
+    pub async fn wait(&self) {
+        // notified future is registered before checking state so no notification is missed.
+        let notified = self.0.notify.notified();
+        if !self.is_draining() {
+            notified.await;
+        }
+    }
+
+    /// construct a guard that keeps graceful shutdown of worker waiting until it's dropped.
+    pub fn guard(&self) -> DrainGuard {
+        self.0.guards.set(self.0.guards.get() + 1);
+        DrainGuard(self.clone())
+    }
+
+    pub(crate) fn set_current(&self) {
+        CURRENT.with(|current| *current.borrow_mut() = Some(self.clone()));
+    }
+
+    pub(crate) fn start(&self) {
+        self.0.draining.set(true);
+        self.0.notify.notify_waiters();
+    }
+
+    pub(crate) fn active_guards(&self) -> usize {
+        self.0.guards.get()
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Drain>> = const { RefCell::new(None) };
+}
+
+/// guard delaying graceful shutdown of worker. See [Drain::guard].
+pub struct DrainGuard(Drain);
+
+impl Drop for DrainGuard {
+    fn drop(&mut self) {
+        let guards = &self.0 .0.guards;
+        guards.set(guards.get() - 1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn drain() {
+        let drain = Drain::default();
+        assert!(!drain.is_draining());
+
+        let guard = drain.guard();
+        assert_eq!(drain.active_guards(), 1);
+
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let drain2 = drain.clone();
+                let waiter = tokio::task::spawn_local(async move { drain2.wait().await });
+                tokio::task::yield_now().await;
+
+                drain.start();
+                waiter.await.unwrap();
+            })
+            .await;
+        assert!(drain.is_draining());
+
+        // wait on drained resolves immediately.
+        drain.wait().await;
+
+        drop(guard);
+        assert_eq!(drain.active_guards(), 0);
+    }
+}
@@ -0,0 +1,189 @@
+//! connection filtering right after accepting and before any work of protocol handling(tls, http etc).
+//!
+//! # Examples
+//! ```rust
+//! # use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+//! # use xitca_server::{filter::IpFilter, Builder};
+//! // deny given ip address and allow at most 64 concurrent connections from the same ip address.
+//! let builder = Builder::new().connection_filter(
+//!     IpFilter::new()
+//!         .deny(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))
+//!         .max_connections_per_ip(64),
+//! );
+//!
+//! // closure can be used as simple predicate.
+//! let builder = Builder::new().connection_filter(|addr: &SocketAddr| addr.ip().is_loopback());
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+};
+
+use xitca_io::net::Stream;
+
+/// filter for accepted connection. connection is dropped without being passed to service when rejected.
+///
+/// Filter is shared between all worker threads and it must be cheap to run as it's called in accept loop.
+/// It only applies to connection with socket address. (Tcp and Udp)
+pub trait ConnectionFilter: Send + Sync + 'static {
+    /// called right after a connection is accepted. return false to reject the connection.
+    fn on_accept(&self, addr: &SocketAddr) -> bool;
+
+    /// called when an accepted connection is closed. it's not called for rejected connection.
+    fn on_close(&self, addr: &SocketAddr) {
+        let _ = addr;
+    }
+}
+
+impl<F> ConnectionFilter for F
+where
+    F: Fn(&SocketAddr) -> bool + Send + Sync + 'static,
+{
+    fn on_accept(&self, addr: &SocketAddr) -> bool {
+        (self)(addr)
+    }
+}
+
+/// peer ip address based connection filter with allow/deny lists and per ip connection cap.
+#[derive(Default)]
+pub struct IpFilter {
+    allow: HashSet<IpAddr>,
+    deny: HashSet<IpAddr>,
+    max_per_ip: Option<usize>,
+    conns: Mutex<HashMap<IpAddr, usize>>,
+}
+
+impl IpFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add ip address to allow list. when allow list is not empty only connection from ip address in it
+    /// would be accepted.
+    pub fn allow(mut self, ip: IpAddr) -> Self {
+        self.allow.insert(ip);
+        self
+    }
+
+    /// add ip address to deny list. deny list takes precedence over allow list.
+    pub fn deny(mut self, ip: IpAddr) -> Self {
+        self.deny.insert(ip);
+        self
+    }
+
+    /// set max number of concurrent connections from the same ip address.
+    ///
+    /// # Panics
+    /// panic when max is zero.
+    pub fn max_connections_per_ip(mut self, max: usize) -> Self {
+        assert_ne!(max, 0, "max connections per ip must be higher than 0");
+        self.max_per_ip = Some(max);
+        self
+    }
+}
+
+impl ConnectionFilter for IpFilter {
+    fn on_accept(&self, addr: &SocketAddr) -> bool {
+        let ip = addr.ip();
+
+        if self.deny.contains(&ip) || (!self.allow.is_empty() && !self.allow.contains(&ip)) {
+            return false;
+        }
+
+        match self.max_per_ip {
+            Some(max) => {
+                let mut conns = self.conns.lock().unwrap();
+                let count = conns.entry(ip).or_default();
+                if *count >= max {
+                    return false;
+                }
+                *count += 1;
+                true
+            }
+            None => true,
+        }
+    }
+
+    fn on_close(&self, addr: &SocketAddr) {
+        if self.max_per_ip.is_none() {
+            return;
+        }
+
+        let ip = addr.ip();
+        let mut conns = self.conns.lock().unwrap();
+        if let Some(count) = conns.get_mut(&ip) {
+            *count -= 1;
+            if *count == 0 {
+                conns.remove(&ip);
+            }
+        }
+    }
+}
+
+pub(crate) type Filter = Option<Arc<dyn ConnectionFilter>>;
+
+// guard calling ConnectionFilter::on_close when accepted connection is closed.
+pub(crate) struct FilterGuard(Option<(Arc<dyn ConnectionFilter>, SocketAddr)>);
+
+impl Drop for FilterGuard {
+    fn drop(&mut self) {
+        if let Some((filter, addr)) = self.0.take() {
+            filter.on_close(&addr);
+        }
+    }
+}
+
+// run filter against accepted stream. return None when stream is rejected.
+pub(crate) fn accept(filter: &Filter, stream: &Stream) -> Option<FilterGuard> {
+    let Some(filter) = filter else {
+        return Some(FilterGuard(None));
+    };
+
+    let addr = match stream {
+        Stream::Tcp(_, addr) => addr,
+        #[cfg(feature = "quic")]
+        Stream::Udp(_, addr) => addr,
+        #[cfg(unix)]
+        Stream::Unix(..) => return Some(FilterGuard(None)),
+    };
+
+    filter
+        .on_accept(addr)
+        .then(|| FilterGuard(Some((filter.clone(), *addr))))
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[test]
+    fn ip_filter() {
+        let addr = |n| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, n)), 8080);
+
+        let filter = IpFilter::new().deny(addr(2).ip());
+        assert!(filter.on_accept(&addr(1)));
+        assert!(!filter.on_accept(&addr(2)));
+
+        let filter = IpFilter::new()
+            .allow(addr(1).ip())
+            .allow(addr(2).ip())
+            .deny(addr(2).ip());
+        assert!(filter.on_accept(&addr(1)));
+        assert!(!filter.on_accept(&addr(2)));
+        assert!(!filter.on_accept(&addr(3)));
+
+        let filter = IpFilter::new().max_connections_per_ip(2);
+        assert!(filter.on_accept(&addr(1)));
+        assert!(filter.on_accept(&addr(1)));
+        assert!(!filter.on_accept(&addr(1)));
+        assert!(filter.on_accept(&addr(2)));
+        filter.on_close(&addr(1));
+        assert!(filter.on_accept(&addr(1)));
+        filter.on_close(&addr(2));
+        assert!(filter.conns.lock().unwrap().get(&addr(2).ip()).is_none());
+    }
+}
@@ -0,0 +1,436 @@
+//! listener inheritance between processes for zero downtime binary upgrade.
+//!
+//! On upgrade the running process clears close-on-exec flag of it's listeners and spawn a new process of
+//! current executable with the listener file descriptors exposed through [LISTEN_FDS_ENV] environment
+//! variable in `<name>:<fd>,<name>:<fd>` format. The new process would pick up the listeners when binding
+//! to the same names after reading them with [init]. Once every worker of the new process has constructed it's
+//! services it signals readiness through a socket passed with [READY_FD_ENV] environment variable and the old
+//! process would graceful shutdown afterwards. During the transition the kernel keeps queueing new connections
+//! on the shared sockets so no connection would be dropped.
+
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    future::Future,
+    io::{self, Write},
+    mem, net,
+    os::{
+        fd::{AsFd, AsRawFd, FromRawFd, OwnedFd, RawFd},
+        unix::net::{UnixListener, UnixStream},
+    },
+    process::{Child, Command},
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+
+use socket2::{SockRef, Socket, Type};
+use xitca_io::net::Listener;
+
+/// environment variable name for passing listener file descriptors to child process.
+pub(crate) const LISTEN_FDS_ENV: &str = "XITCA_LISTEN_FDS";
+
+/// environment variable name for passing readiness notify socket to child process.
+pub(crate) const READY_FD_ENV: &str = "XITCA_UPGRADE_READY_FD";
+
+/// time old process waits for readiness of new process before aborting upgrade.
+pub(crate) const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub(crate) enum InheritedListener {
+    Tcp(net::TcpListener),
+    Unix(UnixListener),
+}
+
+#[derive(Default)]
+struct Inherited {
+    listeners: HashMap<String, Vec<OwnedFd>>,
+    ready: Option<UnixStream>,
+}
+
+static INHERITED: OnceLock<Mutex<Inherited>> = OnceLock::new();
+
+/// read and clear environment variables of inherited file descriptors.
+///
+/// environment mutation is not thread safe so this must be called before server spawns any thread.
+/// only the first call has effect.
+pub(crate) fn init() {
+    INHERITED.get_or_init(|| {
+        let listen_fds = env::var(LISTEN_FDS_ENV).ok();
+        let ready_fd = env::var(READY_FD_ENV).ok();
+
+        // remove the variables so they would not leak into other child processes.
+        env::remove_var(LISTEN_FDS_ENV);
+        env::remove_var(READY_FD_ENV);
+
+        #[cfg(feature = "systemd")]
+        let systemd = crate::systemd::listen_fds();
+        #[cfg(not(feature = "systemd"))]
+        let systemd = Vec::new();
+
+        Mutex::new(collect(listen_fds.as_deref(), ready_fd.as_deref(), systemd))
+    });
+}
+
+fn collect(listen_fds: Option<&str>, ready_fd: Option<&str>, systemd: Vec<(String, RawFd)>) -> Inherited {
+    let mut inherited = Inherited::default();
+
+    // the same fd can be passed from multiple sources. only take ownership once so it's not closed twice.
+    let mut seen = HashSet::new();
+
+    if let Some(fd) = ready_fd.and_then(|fd| fd.trim().parse().ok()) {
+        seen.insert(fd);
+        match own_socket(fd, false) {
+            Ok(fd) => {
+                let stream = UnixStream::from(fd);
+                if let Err(e) = SockRef::from(&stream).set_cloexec(true) {
+                    tracing::warn!("Failed to set close-on-exec flag of readiness socket: {e}");
+                }
+                inherited.ready = Some(stream);
+            }
+            Err(e) => tracing::warn!("Ignored invalid readiness file descriptor {fd}: {e}"),
+        }
+    }
+
+    let fds = listen_fds
+        .into_iter()
+        .flat_map(parse_fds)
+        .map(|(name, fd)| (name.to_owned(), fd))
+        .chain(systemd);
+
+    for (name, fd) in fds {
+        if !seen.insert(fd) {
+            tracing::warn!("Ignored duplicate inherited file descriptor {fd} of listener {name}");
+            continue;
+        }
+
+        match own_socket(fd, true) {
+            Ok(fd) => inherited.listeners.entry(name).or_default().push(fd),
+            Err(e) => tracing::warn!("Ignored invalid inherited file descriptor {fd} of listener {name}: {e}"),
+        }
+    }
+
+    inherited
+}
+
+fn parse_fds(fds: &str) -> impl Iterator<Item = (&str, RawFd)> {
+    fds.split(',').filter_map(|pair| {
+        let (name, fd) = pair.rsplit_once(':')?;
+        let fd = fd.trim().parse().ok().filter(|fd| *fd > 2)?;
+        Some((name, fd))
+    })
+}
+
+/// validate fd is an open socket(in listening state when listening is true) and take ownership of it.
+#[allow(unsafe_code)]
+fn own_socket(fd: RawFd, listening: bool) -> io::Result<OwnedFd> {
+    if fd <= 2 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "standard io file descriptor",
+        ));
+    }
+
+    let mut stat = mem::MaybeUninit::<libc::stat>::uninit();
+    // SAFETY: fstat only writes to given stat buffer and fails with EBADF when fd is not open.
+    if unsafe { libc::fstat(fd, stat.as_mut_ptr()) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: fstat succeeded and stat buffer is initialized.
+    let stat = unsafe { stat.assume_init() };
+
+    if stat.st_mode & libc::S_IFMT != libc::S_IFSOCK {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "not a socket"));
+    }
+
+    let mut accept_conn: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>() as libc::socklen_t;
+    // SAFETY: getsockopt writes at most len bytes to accept_conn and fd is checked as open socket.
+    let res = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_ACCEPTCONN,
+            (&mut accept_conn as *mut libc::c_int).cast(),
+            &mut len,
+        )
+    };
+    if res == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    if (accept_conn != 0) != listening {
+        let msg = if listening {
+            "not a listening socket"
+        } else {
+            "unexpected listening socket"
+        };
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+    }
+
+    // SAFETY:
+    // fd is validated as open socket passed from parent process(or systemd). It's taken from environment
+    // variable once and deduplicated so it's exclusively owned here.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// notify parent process this process is ready and it can stop serving.
+/// no-op when this process is not spawned from upgrade.
+pub(crate) fn notify_ready() {
+    let ready = INHERITED
+        .get()
+        .and_then(|inherited| inherited.lock().unwrap().ready.take());
+
+    if let Some(mut ready) = ready {
+        if let Err(e) = ready.write_all(&[1]) {
+            tracing::error!("Failed to notify parent process readiness: {e}");
+        }
+    }
+}
+
+/// take one listener inherited from parent process with given name.
+pub(crate) fn take(name: &str) -> io::Result<Option<InheritedListener>> {
+    let fd = INHERITED.get().and_then(|inherited| {
+        inherited
+            .lock()
+            .unwrap()
+            .listeners
+            .get_mut(name)
+            .and_then(|fds| (!fds.is_empty()).then(|| fds.remove(0)))
+    });
+
+    fd.map(fd_to_listener).transpose()
+}
+
+/// convert an owned file descriptor of bound stream socket to listener.
+pub(crate) fn fd_to_listener(fd: OwnedFd) -> io::Result<InheritedListener> {
+    let socket = Socket::from(fd);
+
+    if socket.r#type()? != Type::STREAM {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "inherited file descriptor is not a stream socket",
+        ));
+    }
+
+    socket.set_nonblocking(true)?;
+    // flag is cleared by parent process for inheritance. restore it so listener would not leak further.
+    socket.set_cloexec(true)?;
+
+    if socket.local_addr()?.as_socket().is_some() {
+        Ok(InheritedListener::Tcp(socket.into()))
+    } else {
+        Ok(InheritedListener::Unix(socket.into()))
+    }
+}
+
+/// spawn a new process of current executable inheriting given listeners.
+///
+/// on success a future is returned. It resolves to process id of spawned process after the process signals it's
+/// readiness. When the process exits or fails to become ready in [READY_TIMEOUT] it's killed and error is
+/// returned.
+pub(crate) fn spawn_upgrade(
+    listeners: &[(String, Arc<Listener>)],
+) -> io::Result<impl Future<Output = io::Result<u32>> + Send> {
+    let fds = listeners
+        .iter()
+        .filter_map(|(name, listener)| match **listener {
+            Listener::Tcp(ref l) => Some((name, l.as_fd())),
+            Listener::Unix(ref l) => Some((name, l.as_fd())),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // socket pair is created with close-on-exec flag. only the child half is passed to new process.
+    let (ready, ready_child) = UnixStream::pair()?;
+    SockRef::from(&ready_child).set_cloexec(false)?;
+
+    let set_cloexec = |cloexec| {
+        fds.iter()
+            .try_for_each(|(_, fd)| SockRef::from(fd).set_cloexec(cloexec))
+    };
+
+    let env = fds
+        .iter()
+        .map(|(name, fd)| format!("{name}:{}", fd.as_raw_fd()))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let res = set_cloexec(false).and_then(|_| {
+        Command::new(env::current_exe()?)
+            .args(env::args_os().skip(1))
+            .env(LISTEN_FDS_ENV, env)
+            .env(READY_FD_ENV, ready_child.as_raw_fd().to_string())
+            .spawn()
+    });
+
+    // restore flag so listeners would not leak into other child processes.
+    set_cloexec(true)?;
+
+    // drop child half so reading ready half observes eof when new process exits.
+    drop(ready_child);
+
+    let child = res?;
+    tracing::info!("Spawned upgraded process: {}", child.id());
+
+    Ok(wait_ready(child, ready))
+}
+
+async fn wait_ready(mut child: Child, ready: UnixStream) -> io::Result<u32> {
+    let res = tokio::time::timeout(READY_TIMEOUT, async {
+        ready.set_nonblocking(true)?;
+        let ready = tokio::net::UnixStream::from_std(ready)?;
+        loop {
+            ready.readable().await?;
+            match ready.try_read(&mut [0]) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "upgraded process exited before ready",
+                    ))
+                }
+                Ok(_) => return Ok(()),
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    })
+    .await
+    .unwrap_or_else(|_| {
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "upgraded process is not ready in time",
+        ))
+    });
+
+    match res {
+        Ok(_) => {
+            tracing::info!("Upgraded process is ready: {}", child.id());
+            Ok(child.id())
+        }
+        Err(e) => {
+            // new process would keep serving on inherited listeners if not killed.
+            let _ = child.kill();
+            let _ = child.wait();
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let fds = parse_fds("http:3,https:4,bad,foo:x,stdio:1,a:b:5").collect::<Vec<_>>();
+        assert_eq!(fds, [("http", 3), ("https", 4), ("a:b", 5)]);
+    }
+
+    #[test]
+    fn convert() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        match fd_to_listener(OwnedFd::from(listener)).unwrap() {
+            InheritedListener::Tcp(l) => assert_eq!(l.local_addr().unwrap(), addr),
+            InheritedListener::Unix(_) => panic!("tcp listener must be converted to tcp listener"),
+        }
+
+        let udp = net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        assert!(fd_to_listener(OwnedFd::from(udp)).is_err());
+    }
+
+    #[test]
+    fn validate() {
+        use std::os::fd::IntoRawFd;
+
+        let udp = net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        assert!(own_socket(udp.as_raw_fd(), true).is_err());
+
+        let file = std::fs::File::open(env::current_exe().unwrap()).unwrap();
+        assert!(own_socket(file.as_raw_fd(), true).is_err());
+
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(own_socket(listener.as_raw_fd(), false).is_err());
+        let fd = listener.into_raw_fd();
+        assert_eq!(own_socket(fd, true).unwrap().as_raw_fd(), fd);
+
+        let (stream, _peer) = UnixStream::pair().unwrap();
+        assert!(own_socket(stream.as_raw_fd(), true).is_err());
+        let fd = stream.into_raw_fd();
+        assert_eq!(own_socket(fd, false).unwrap().as_raw_fd(), fd);
+    }
+
+    #[test]
+    fn dedup() {
+        use std::os::fd::IntoRawFd;
+
+        let fd = net::TcpListener::bind("127.0.0.1:0").unwrap().into_raw_fd();
+        let udp = net::UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        let listen_fds = format!("http:{fd},udp:{}", udp.as_raw_fd());
+        let systemd = vec![(String::from("unknown"), fd)];
+
+        let inherited = collect(Some(&listen_fds), None, systemd);
+        assert!(inherited.ready.is_none());
+        assert_eq!(inherited.listeners.len(), 1);
+        assert_eq!(inherited.listeners["http"].len(), 1);
+        assert_eq!(inherited.listeners["http"][0].as_raw_fd(), fd);
+    }
+
+    #[test]
+    fn ready_after_start() {
+        use std::io::Read;
+
+        use xitca_io::net::TcpStream;
+        use xitca_service::{fn_service, Service, ServiceExt};
+
+        struct Fail;
+
+        impl<S, E> Service<Result<S, E>> for Fail {
+            type Response = S;
+            type Error = io::Error;
+
+            async fn call(&self, _: Result<S, E>) -> Result<Self::Response, Self::Error> {
+                Err(io::Error::other("boom"))
+            }
+        }
+
+        let (ready, mut parent) = UnixStream::pair().unwrap();
+        parent.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        INHERITED.get_or_init(Default::default).lock().unwrap().ready = Some(ready);
+
+        let service = || fn_service(|_: TcpStream| async { Ok::<_, io::Error>(()) });
+
+        // worker failing to construct service stops server without notifying readiness.
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        crate::Builder::new()
+            .worker_threads(2)
+            .disable_signal()
+            .listen("test", listener, service().enclosed(Fail))
+            .build()
+            .wait()
+            .unwrap_err();
+
+        parent.set_nonblocking(true).unwrap();
+        let err = parent.read(&mut [0]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+        parent.set_nonblocking(false).unwrap();
+
+        // readiness is notified after all workers are started.
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut server = crate::Builder::new()
+            .worker_threads(2)
+            .disable_signal()
+            .listen("test", listener, service())
+            .build();
+        let handle = server.handle().unwrap();
+        let server = std::thread::spawn(move || server.wait());
+
+        let mut buf = [0];
+        assert_eq!(parent.read(&mut buf).unwrap(), 1);
+
+        handle.stop(false);
+        server.join().unwrap().unwrap();
+    }
+}
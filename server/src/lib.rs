@@ -1,13 +1,22 @@
 //! Multi-threaded server for Tcp/Udp/UnixDomain handling.
 
-#![forbid(unsafe_code)]
+#![deny(unsafe_code)]
 
 mod builder;
+#[cfg(unix)]
+mod inherit;
+mod limit;
 mod server;
 mod signals;
 mod worker;
 
+pub mod drain;
+pub mod filter;
 pub mod net;
+pub mod stats;
+
+#[cfg(all(unix, feature = "systemd"))]
+pub mod systemd;
 
 pub use builder::Builder;
 pub use server::{ServerFuture, ServerHandle};
@@ -18,7 +27,7 @@ compile_error!("io_uring can only be used on linux system");
 #[cfg(test)]
 mod test {
     use xitca_io::net::TcpStream;
-    use xitca_service::fn_service;
+    use xitca_service::{fn_service, Service, ServiceExt};
 
     #[test]
     fn test_builder() {
@@ -27,4 +36,34 @@ mod test {
             .listen("test", listener, fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }))
             .build();
     }
+
+    #[test]
+    fn start_error() {
+        struct Fail;
+
+        impl<S, E> Service<Result<S, E>> for Fail {
+            type Response = S;
+            type Error = std::io::Error;
+
+            async fn call(&self, _: Result<S, E>) -> Result<Self::Response, Self::Error> {
+                Err(std::io::Error::new(std::io::ErrorKind::AddrNotAvailable, "boom"))
+            }
+        }
+
+        let listener = std::net::TcpListener::bind("localhost:0").unwrap();
+        let service = fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }).enclosed(Fail);
+        let err = crate::builder::Builder::new()
+            .worker_threads(1)
+            .disable_signal()
+            .listen("test", listener, service)
+            .build()
+            .wait()
+            .unwrap_err();
+        assert!(err.to_string().contains("boom"));
+
+        // error of service construction is kept as source.
+        let source = std::error::Error::source(&err).unwrap();
+        let source = source.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(source.kind(), std::io::ErrorKind::AddrNotAvailable);
+    }
 }
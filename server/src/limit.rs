@@ -0,0 +1,119 @@
+use core::cell::RefCell;
+
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::trace;
+
+use crate::stats::WorkerStats;
+
+// limit of concurrent connections. accept loop is paused when limit is reached and resumed when connection
+// is closed.
+#[derive(Clone, Default)]
+pub(crate) struct Limit {
+    global: Option<Arc<Semaphore>>,
+    worker: Option<Arc<Semaphore>>,
+}
+
+impl Limit {
+    // global limit shared by all workers.
+    pub(crate) fn global(max: Option<usize>) -> Option<Arc<Semaphore>> {
+        max.map(|max| Arc::new(Semaphore::new(max)))
+    }
+
+    // construct limit of a single worker.
+    pub(crate) fn new(global: Option<Arc<Semaphore>>, worker: Option<usize>) -> Self {
+        Self {
+            global,
+            worker: worker.map(|max| Arc::new(Semaphore::new(max))),
+        }
+    }
+
+    // get limit of current worker thread. default to no limit when not called from a worker thread.
+    pub(crate) fn current() -> Self {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    pub(crate) fn set_current(self) {
+        CURRENT.with(|current| *current.borrow_mut() = self);
+    }
+
+    // wait until connection can be accepted.
+    // worker limit is acquired first so a paused worker does not hold on to global limit.
+    pub(crate) async fn acquire(&self, stats: &WorkerStats) -> LimitPermit {
+        let worker = acquire(&self.worker, stats).await;
+        let global = acquire(&self.global, stats).await;
+        LimitPermit {
+            _worker: worker,
+            _global: global,
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Limit> = RefCell::new(Limit::default());
+}
+
+// permit of accepted connection. limit is released when it's dropped.
+pub(crate) struct LimitPermit {
+    _worker: Option<OwnedSemaphorePermit>,
+    _global: Option<OwnedSemaphorePermit>,
+}
+
+async fn acquire(limit: &Option<Arc<Semaphore>>, stats: &WorkerStats) -> Option<OwnedSemaphorePermit> {
+    let limit = limit.as_ref()?;
+    match limit.clone().try_acquire_owned() {
+        Ok(permit) => Some(permit),
+        Err(_) => {
+            trace!("Max connections reached. Accepting is paused until connection is closed.");
+            stats.accept_paused();
+            // semaphore is never closed.
+            limit.clone().acquire_owned().await.ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{future::Future, pin::pin};
+
+    use xitca_unsafe_collection::futures::{NowOrPanic, Select, SelectOutput};
+
+    use super::*;
+
+    // poll future once and return true when it's pending.
+    fn is_pending(fut: impl Future) -> bool {
+        matches!(fut.select(async {}).now_or_panic(), SelectOutput::B(_))
+    }
+
+    #[test]
+    fn limit() {
+        let stats = WorkerStats::default();
+
+        let global = Limit::global(Some(2));
+        let limit = Limit::new(global.clone(), Some(1));
+        let limit2 = Limit::new(global, None);
+
+        let permit = limit.acquire(&stats).now_or_panic();
+        let permit2 = limit2.acquire(&stats).now_or_panic();
+        assert_eq!(stats.accept_paused_total(), 0);
+
+        // worker limit reached.
+        let mut fut = pin!(limit.acquire(&stats));
+        assert!(is_pending(fut.as_mut()));
+        assert_eq!(stats.accept_paused_total(), 1);
+
+        drop(permit);
+        let _permit = fut.now_or_panic();
+
+        // global limit reached.
+        let mut fut = pin!(limit2.acquire(&stats));
+        assert!(is_pending(fut.as_mut()));
+        assert_eq!(stats.accept_paused_total(), 2);
+
+        drop(permit2);
+        let _permit2 = fut.now_or_panic();
+
+        let _ = Limit::default().acquire(&stats).now_or_panic();
+    }
+}
@@ -12,17 +12,19 @@ use tracing::info;
 /// This is to delay the conversion and make it happen in server thread(s).
 /// Otherwise it could panic.
 pub trait AsListener: Send {
-    fn as_listener(&mut self) -> io::Result<Listener>;
+    /// convert to listener. name is the one listener registered with [Builder](crate::Builder) and it's used
+    /// for logging.
+    fn as_listener(&mut self, name: &str) -> io::Result<Listener>;
 }
 
 impl AsListener for Option<net::TcpListener> {
-    fn as_listener(&mut self) -> io::Result<Listener> {
+    fn as_listener(&mut self, name: &str) -> io::Result<Listener> {
         let this = self.take().unwrap();
         this.set_nonblocking(true)?;
 
         let tcp = TcpListener::from_std(this)?;
 
-        info!("Started Tcp listening on: {:?}", tcp.local_addr().ok());
+        info!("Started Tcp listener {name:?} on: {:?}", tcp.local_addr().ok());
 
         Ok(Listener::Tcp(tcp))
     }
@@ -30,13 +32,13 @@ impl AsListener for Option<net::TcpListener> {
 
 #[cfg(unix)]
 impl AsListener for Option<std::os::unix::net::UnixListener> {
-    fn as_listener(&mut self) -> io::Result<Listener> {
+    fn as_listener(&mut self, name: &str) -> io::Result<Listener> {
         let this = self.take().unwrap();
         this.set_nonblocking(true)?;
 
         let unix = UnixListener::from_std(this)?;
 
-        info!("Started Unix listening on: {:?}", unix.local_addr().ok());
+        info!("Started Unix listener {name:?} on: {:?}", unix.local_addr().ok());
 
         Ok(Listener::Unix(unix))
     }
@@ -44,10 +46,13 @@ impl AsListener for Option<std::os::unix::net::UnixListener> {
 
 #[cfg(feature = "quic")]
 impl AsListener for Option<QuicListenerBuilder> {
-    fn as_listener(&mut self) -> io::Result<Listener> {
+    fn as_listener(&mut self, name: &str) -> io::Result<Listener> {
         let udp = self.take().unwrap().build()?;
 
-        info!("Started Udp listening on: {:?}", udp.endpoint().local_addr().ok());
+        info!(
+            "Started Udp listener {name:?} on: {:?}",
+            udp.endpoint().local_addr().ok()
+        );
 
         Ok(Listener::Udp(udp))
     }
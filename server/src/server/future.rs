@@ -11,7 +11,11 @@ use super::{handle::ServerHandle, Command, Server};
 
 #[must_use = "ServerFuture must be .await/ spawn as task / consumed with ServerFuture::wait."]
 pub enum ServerFuture {
-    Init { server: Server, enable_signal: bool },
+    Init {
+        server: Server,
+        enable_signal: bool,
+        enable_upgrade: bool,
+    },
     Running(ServerFutureInner),
     Error(io::Error),
     Finished,
@@ -45,12 +49,8 @@ impl ServerFuture {
     /// ```
     pub fn handle(&mut self) -> io::Result<ServerHandle> {
         match *self {
-            Self::Init { ref server, .. } => Ok(ServerHandle {
-                tx: server.tx_cmd.clone(),
-            }),
-            Self::Running(ref inner) => Ok(ServerHandle {
-                tx: inner.server.tx_cmd.clone(),
-            }),
+            Self::Init { ref server, .. } => Ok(server.handle()),
+            Self::Running(ref inner) => Ok(inner.server.handle()),
             Self::Error(_) => match mem::take(self) {
                 Self::Error(e) => Err(e),
                 _ => unreachable!(),
@@ -68,12 +68,13 @@ impl ServerFuture {
             Self::Init {
                 mut server,
                 enable_signal,
+                enable_upgrade,
             } => {
                 let rt = server.rt.take().unwrap();
 
                 let func = move || {
                     let (mut server_fut, cmd) = rt.block_on(async {
-                        let mut server_fut = ServerFutureInner::new(server, enable_signal);
+                        let mut server_fut = ServerFutureInner::new(server, enable_signal, enable_upgrade);
                         let cmd = std::future::poll_fn(|cx| server_fut.poll_cmd(cx)).await;
                         (server_fut, cmd)
                     });
@@ -93,8 +94,7 @@ impl ServerFuture {
                     Err(_) => func(),
                 };
 
-                server_fut.handle_cmd(cmd);
-                Ok(())
+                server_fut.handle_cmd(cmd)
             }
             Self::Running(..) => panic!("ServerFuture is already polled."),
             Self::Error(e) => Err(e),
@@ -106,6 +106,12 @@ impl ServerFuture {
 pub struct ServerFutureInner {
     pub(crate) server: Server,
     pub(crate) signals: Option<SignalFuture>,
+    // number of workers still constructing their services.
+    starting: usize,
+    #[cfg(all(unix, feature = "systemd"))]
+    watchdog: Option<tokio::time::Interval>,
+    #[cfg(unix)]
+    upgrade: Option<Pin<Box<dyn Future<Output = io::Result<u32>> + Send>>>,
 }
 
 impl Default for ServerFuture {
@@ -116,15 +122,94 @@ impl Default for ServerFuture {
 
 impl ServerFutureInner {
     #[inline(never)]
-    fn new(server: Server, enable_signal: bool) -> Self {
+    fn new(server: Server, enable_signal: bool, enable_upgrade: bool) -> Self {
         Self {
+            starting: server.stats.workers().len(),
             server,
-            signals: enable_signal.then(signals::start),
+            signals: enable_signal.then(|| signals::start(enable_upgrade)),
+            #[cfg(all(unix, feature = "systemd"))]
+            watchdog: crate::systemd::watchdog_interval().map(|dur| {
+                let mut interval = tokio::time::interval(dur);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                interval
+            }),
+            #[cfg(unix)]
+            upgrade: None,
         }
     }
 
     #[inline(never)]
     fn poll_cmd(&mut self, cx: &mut Context<'_>) -> Poll<Command> {
+        loop {
+            #[cfg(unix)]
+            if let Some(upgrade) = self.upgrade.as_mut() {
+                if let Poll::Ready(res) = upgrade.as_mut().poll(cx) {
+                    self.upgrade = None;
+                    match res {
+                        Ok(_pid) => {
+                            // hand over main process to the spawned one. requires NotifyAccess=all of systemd unit.
+                            #[cfg(feature = "systemd")]
+                            if let Err(e) = crate::systemd::notify(&format!("MAINPID={_pid}")) {
+                                tracing::error!("Failed to notify systemd main pid: {e}");
+                            }
+                            return Poll::Ready(Command::GracefulStop);
+                        }
+                        Err(e) => tracing::error!("Server upgrade failed: {e}"),
+                    }
+                }
+            }
+
+            let cmd = ready!(self._poll_cmd(cx));
+
+            if matches!(cmd, Command::WorkerStarted) {
+                self.starting -= 1;
+                // readiness is sent after every worker is able to serve. a worker failing to construct it's
+                // services stops server before it and the parent process of upgrade keeps serving.
+                if self.starting == 0 {
+                    Self::notify_ready();
+                }
+                continue;
+            }
+
+            #[cfg(unix)]
+            if matches!(cmd, Command::Upgrade) {
+                if self.upgrade.is_some() {
+                    tracing::warn!("Server upgrade is already in progress.");
+                    continue;
+                }
+
+                // keep serving until spawned process is ready.
+                match self.server.upgrade() {
+                    Ok(upgrade) => self.upgrade = Some(Box::pin(upgrade)),
+                    Err(e) => tracing::error!("Server upgrade failed: {e}"),
+                }
+                continue;
+            }
+
+            return Poll::Ready(cmd);
+        }
+    }
+
+    fn notify_ready() {
+        #[cfg(all(unix, feature = "systemd"))]
+        if let Err(e) = crate::systemd::notify_ready() {
+            tracing::error!("Failed to notify systemd readiness: {e}");
+        }
+
+        #[cfg(unix)]
+        crate::inherit::notify_ready();
+    }
+
+    fn _poll_cmd(&mut self, cx: &mut Context<'_>) -> Poll<Command> {
+        #[cfg(all(unix, feature = "systemd"))]
+        if let Some(watchdog) = self.watchdog.as_mut() {
+            while watchdog.poll_tick(cx).is_ready() {
+                if let Err(e) = crate::systemd::notify_watchdog() {
+                    tracing::error!("Failed to ping systemd watchdog: {e}");
+                }
+            }
+        }
+
         if let Some(signals) = self.signals.as_mut() {
             if let Poll::Ready(sig) = Pin::new(signals).poll(cx) {
                 tracing::info!("Signal {:?} received.", sig);
@@ -137,6 +222,10 @@ impl ServerFutureInner {
                         self.signals = None;
                         return Poll::Pending;
                     }
+                    #[cfg(unix)]
+                    Signal::Usr2 => Command::Upgrade,
+                    #[cfg(not(unix))]
+                    Signal::Usr2 => return Poll::Pending,
                 };
                 return Poll::Ready(cmd);
             }
@@ -149,7 +238,12 @@ impl ServerFutureInner {
     }
 
     #[inline(never)]
-    fn handle_cmd(&mut self, cmd: Command) {
+    fn handle_cmd(&mut self, cmd: Command) -> io::Result<()> {
+        #[cfg(all(unix, feature = "systemd"))]
+        if let Err(e) = crate::systemd::notify_stopping() {
+            tracing::error!("Failed to notify systemd stopping: {e}");
+        }
+
         match cmd {
             Command::ForceStop => {
                 self.server.stop(false);
@@ -157,7 +251,16 @@ impl ServerFutureInner {
             Command::GracefulStop => {
                 self.server.stop(true);
             }
+            Command::StartFailed(e) => {
+                self.server.stop(false);
+                return Err(e);
+            }
+            Command::WorkerStarted => unreachable!("worker started command is handled in ServerFutureInner::poll_cmd"),
+            #[cfg(unix)]
+            Command::Upgrade => unreachable!("upgrade command is handled in ServerFutureInner::poll_cmd"),
         }
+
+        Ok(())
     }
 }
 
@@ -169,17 +272,25 @@ impl Future for ServerFuture {
         let this = self.as_mut().get_mut();
         match *this {
             Self::Init { .. } => match mem::take(this) {
-                Self::Init { server, enable_signal } => {
-                    self.set(Self::Running(ServerFutureInner::new(server, enable_signal)));
+                Self::Init {
+                    server,
+                    enable_signal,
+                    enable_upgrade,
+                } => {
+                    self.set(Self::Running(ServerFutureInner::new(
+                        server,
+                        enable_signal,
+                        enable_upgrade,
+                    )));
                     self.poll(cx)
                 }
                 _ => unreachable!(),
             },
             Self::Running(ref mut inner) => {
                 let cmd = ready!(inner.poll_cmd(cx));
-                inner.handle_cmd(cmd);
+                let res = inner.handle_cmd(cmd);
                 self.set(Self::Finished);
-                Poll::Ready(Ok(()))
+                Poll::Ready(res)
             }
             Self::Error(_) => match mem::take(this) {
                 Self::Error(e) => Poll::Ready(Err(e)),
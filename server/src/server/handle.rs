@@ -1,10 +1,13 @@
 use tokio::sync::mpsc::UnboundedSender;
 
+use crate::stats::ServerStats;
+
 use super::Command;
 
 #[derive(Clone)]
 pub struct ServerHandle {
     pub(super) tx: UnboundedSender<Command>,
+    pub(super) stats: ServerStats,
 }
 
 impl ServerHandle {
@@ -18,4 +21,18 @@ impl ServerHandle {
 
         let _ = self.tx.send(cmd);
     }
+
+    /// Get runtime statistics of server. See [stats](crate::stats) module for detail.
+    pub fn stats(&self) -> ServerStats {
+        self.stats.clone()
+    }
+
+    /// Upgrade xitca-server by spawning a new process of current executable inheriting listeners.
+    /// Current server would graceful stop when new process starts running.
+    ///
+    /// See [Builder::enable_upgrade](crate::Builder::enable_upgrade) for detail.
+    #[cfg(unix)]
+    pub fn upgrade(&self) {
+        let _ = self.tx.send(Command::Upgrade);
+    }
 }
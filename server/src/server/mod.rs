@@ -7,6 +7,8 @@ pub use self::{future::ServerFuture, handle::ServerHandle};
 pub(crate) use self::service::{IntoServiceObj, ServiceObj};
 
 use std::{
+    error, fmt,
+    future::Future,
     io, mem,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -20,14 +22,23 @@ use tokio::{
     sync::mpsc::{UnboundedReceiver, UnboundedSender},
 };
 
-use crate::{builder::Builder, worker};
+use crate::{
+    builder::Builder,
+    drain::Drain,
+    limit::Limit,
+    stats::{ServerStats, WorkerStats},
+    worker,
+};
 
 pub struct Server {
     is_graceful_shutdown: Arc<AtomicBool>,
     tx_cmd: UnboundedSender<Command>,
     rx_cmd: UnboundedReceiver<Command>,
     rt: Option<Runtime>,
+    stats: ServerStats,
     worker_join_handles: Vec<thread::JoinHandle<io::Result<()>>>,
+    #[cfg(unix)]
+    listeners: Vec<(String, Arc<xitca_io::net::Listener>)>,
 }
 
 impl Server {
@@ -38,6 +49,9 @@ impl Server {
             factories,
             shutdown_timeout,
             on_worker_start,
+            connection_filter,
+            max_connections,
+            max_connections_per_worker,
             ..
         } = builder;
 
@@ -48,7 +62,7 @@ impl Server {
                 .into_iter()
                 .flat_map(|(name, listeners)| {
                     listeners.into_iter().map(move |mut l| {
-                        let l = l.as_listener()?;
+                        let l = l.as_listener(&name)?;
                         Ok((name.to_owned(), Arc::new(l)))
                     })
                 })
@@ -59,9 +73,17 @@ impl Server {
 
         let is_graceful_shutdown = Arc::new(AtomicBool::new(false));
 
+        let stats = ServerStats::new(1);
+
         let on_start_fut = on_worker_start();
 
         let fut = async {
+            WorkerStats::set_current(stats.worker(0).clone());
+
+            Limit::new(Limit::global(max_connections), max_connections_per_worker).set_current();
+
+            Drain::default().set_current();
+
             on_start_fut.await;
 
             let mut handles = Vec::new();
@@ -69,9 +91,9 @@ impl Server {
 
             for (name, factory) in factories.iter() {
                 let (h, s) = factory
-                    .call((name, &listeners))
+                    .call((name, &listeners, &connection_filter))
                     .await
-                    .map_err(|_| io::Error::from(io::ErrorKind::Other))?;
+                    .map_err(|e| start_error(name, e))?;
                 handles.extend(h);
                 services.push(s);
             }
@@ -96,6 +118,9 @@ impl Server {
             factories,
             shutdown_timeout,
             on_worker_start,
+            connection_filter,
+            max_connections,
+            max_connections_per_worker,
             ..
         } = builder;
 
@@ -111,7 +136,7 @@ impl Server {
                 .into_iter()
                 .flat_map(|(name, listeners)| {
                     listeners.into_iter().map(move |mut l| {
-                        let l = l.as_listener()?;
+                        let l = l.as_listener(&name)?;
                         Ok((name.to_owned(), Arc::new(l)))
                     })
                 })
@@ -122,36 +147,66 @@ impl Server {
         // *. Server::new is most likely already inside a tokio runtime.
         let listeners = thread::scope(|s| s.spawn(|| rt.block_on(fut)).join()).unwrap()?;
 
+        #[cfg(unix)]
+        let listeners2 = listeners.clone();
+
         let is_graceful_shutdown = Arc::new(AtomicBool::new(false));
         let is_graceful_shutdown2 = is_graceful_shutdown.clone();
 
+        let stats = ServerStats::new(worker_threads);
+        let stats2 = stats.clone();
+
+        let global_limit = Limit::global(max_connections);
+
+        let (tx_cmd, rx_cmd) = tokio::sync::mpsc::unbounded_channel();
+        let tx_cmd2 = tx_cmd.clone();
+
         let worker_handles = thread::Builder::new()
             .name(String::from("xitca-server-worker-shared-scope"))
             .spawn(move || {
                 let is_graceful_shutdown = is_graceful_shutdown2;
+                let stats = stats2;
+                let tx_cmd = tx_cmd2;
 
-                // TODO: wait for startup error(including panic) and return as io::Error on call site.
-                // currently the error only show when shared scope thread is joined with handle.
+                // TODO: wait for startup panic and return as io::Error on call site.
+                // currently the panic only show when shared scope thread is joined with handle.
                 thread::scope(|scope| {
                     for idx in 0..worker_threads {
                         let thread = thread::Builder::new().name(format!("xitca-server-worker-{idx}"));
 
+                        let worker_stats = stats.worker(idx).clone();
+
                         let task = || async {
+                            WorkerStats::set_current(worker_stats);
+
+                            Limit::new(global_limit.clone(), max_connections_per_worker).set_current();
+
+                            Drain::default().set_current();
+
                             on_worker_start().await;
 
                             let mut handles = Vec::new();
                             let mut services = Vec::new();
 
                             for (name, factory) in factories.iter() {
-                                match factory.call((name, &listeners)).await {
+                                match factory.call((name, &listeners, &connection_filter)).await {
                                     Ok((h, s)) => {
                                         handles.extend(h);
                                         services.push(s);
                                     }
-                                    Err(_) => return,
+                                    Err(e) => {
+                                        // service construction failure stops server and it's surfaced as error of
+                                        // ServerFuture.
+                                        let e = start_error(name, e);
+                                        tracing::error!("{e}");
+                                        let _ = tx_cmd.send(Command::StartFailed(e));
+                                        return;
+                                    }
                                 }
                             }
 
+                            let _ = tx_cmd.send(Command::WorkerStarted);
+
                             worker::wait_for_stop(handles, services, shutdown_timeout, &is_graceful_shutdown).await;
                         };
 
@@ -180,17 +235,30 @@ impl Server {
                 })
             })?;
 
-        let (tx_cmd, rx_cmd) = tokio::sync::mpsc::unbounded_channel();
-
         Ok(Self {
             is_graceful_shutdown,
             tx_cmd,
             rx_cmd,
             rt: Some(rt),
+            stats,
             worker_join_handles: vec![worker_handles],
+            #[cfg(unix)]
+            listeners: listeners2,
         })
     }
 
+    pub(crate) fn handle(&self) -> ServerHandle {
+        ServerHandle {
+            tx: self.tx_cmd.clone(),
+            stats: self.stats.clone(),
+        }
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn upgrade(&self) -> io::Result<impl Future<Output = io::Result<u32>> + Send> {
+        crate::inherit::spawn_upgrade(&self.listeners)
+    }
+
     pub(crate) fn stop(&mut self, graceful: bool) {
         if let Some(rt) = self.rt.take() {
             self.is_graceful_shutdown.store(graceful, Ordering::SeqCst);
@@ -202,7 +270,38 @@ impl Server {
     }
 }
 
+fn start_error(name: &str, e: Box<dyn error::Error + Send + Sync>) -> io::Error {
+    io::Error::other(StartError {
+        name: name.to_owned(),
+        source: e,
+    })
+}
+
+// error of service construction kept as source so it can be downcast to it's original type.
+#[derive(Debug)]
+struct StartError {
+    name: String,
+    source: Box<dyn error::Error + Send + Sync>,
+}
+
+impl fmt::Display for StartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "service {} failed to start: {}", self.name, self.source)
+    }
+}
+
+impl error::Error for StartError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
 enum Command {
     GracefulStop,
     ForceStop,
+    StartFailed(io::Error),
+    // worker has constructed all services and starts accepting connections.
+    WorkerStarted,
+    #[cfg(unix)]
+    Upgrade,
 }
@@ -1,16 +1,19 @@
-use std::{marker::PhantomData, rc::Rc, sync::Arc};
+use std::{error, marker::PhantomData, rc::Rc, sync::Arc};
 
 use tokio::task::JoinHandle;
 use xitca_io::net::{Listener, Stream};
 use xitca_service::{ready::ReadyService, Service};
 
-use crate::worker::{self, ServiceAny};
+use crate::{
+    filter::Filter,
+    worker::{self, ServiceAny},
+};
 
 pub type ServiceObj = Box<
     dyn for<'a> xitca_service::object::ServiceObject<
-            (&'a str, &'a [(String, Arc<Listener>)]),
+            (&'a str, &'a [(String, Arc<Listener>)], &'a Filter),
             Response = (Vec<JoinHandle<()>>, ServiceAny),
-            Error = (),
+            Error = Box<dyn error::Error + Send + Sync>,
         > + Send
         + Sync,
 >;
@@ -20,25 +23,26 @@ struct Container<F, Req> {
     _t: PhantomData<fn(Req)>,
 }
 
-impl<'a, F, Req> Service<(&'a str, &'a [(String, Arc<Listener>)])> for Container<F, Req>
+impl<'a, F, Req> Service<(&'a str, &'a [(String, Arc<Listener>)], &'a Filter)> for Container<F, Req>
 where
     F: IntoServiceObj<Req>,
+    F::Error: Into<Box<dyn error::Error + Send + Sync>>,
     Req: TryFrom<Stream> + 'static,
 {
     type Response = (Vec<JoinHandle<()>>, ServiceAny);
-    type Error = ();
+    type Error = Box<dyn error::Error + Send + Sync>;
 
     async fn call(
         &self,
-        (name, listeners): (&'a str, &'a [(String, Arc<Listener>)]),
+        (name, listeners, filter): (&'a str, &'a [(String, Arc<Listener>)], &'a Filter),
     ) -> Result<Self::Response, Self::Error> {
-        let service = self.inner.call(()).await.map_err(|_| ())?;
+        let service = self.inner.call(()).await.map_err(Into::into)?;
         let service = Rc::new(service);
 
         let handles = listeners
             .iter()
             .filter(|(n, _)| n == name)
-            .map(|(_, listener)| worker::start(listener, &service))
+            .map(|(_, listener)| worker::start(listener, &service, filter))
             .collect::<Vec<_>>();
 
         Ok((handles, service as _))
@@ -59,6 +63,7 @@ where
 impl<T, Req> IntoServiceObj<Req> for T
 where
     T: Service + Send + Sync + 'static,
+    T::Error: Into<Box<dyn error::Error + Send + Sync>>,
     T::Response: ReadyService + Service<Req>,
     Req: TryFrom<Stream> + 'static,
 {
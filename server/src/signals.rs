@@ -12,11 +12,13 @@ pub(crate) enum Signal {
     Term,
     /// SIGQUIT
     Quit,
+    /// SIGUSR2
+    Usr2,
 }
 
 pub(crate) type SignalFuture = Pin<Box<dyn Future<Output = Signal> + Send>>;
 
-pub(crate) fn start() -> SignalFuture {
+pub(crate) fn start(enable_upgrade: bool) -> SignalFuture {
     #[cfg(unix)]
     {
         use std::{future::poll_fn, task::Poll};
@@ -28,8 +30,10 @@ pub(crate) fn start() -> SignalFuture {
             (unix::SignalKind::hangup(), Signal::Hup),
             (unix::SignalKind::terminate(), Signal::Term),
             (unix::SignalKind::quit(), Signal::Quit),
+            (unix::SignalKind::user_defined2(), Signal::Usr2),
         ]
         .iter()
+        .filter(|(_, sig)| enable_upgrade || *sig != Signal::Usr2)
         .filter_map(|(kind, sig)| {
             unix::signal(*kind)
                 .map(|tokio_sig| (*sig, tokio_sig))
@@ -51,6 +55,7 @@ pub(crate) fn start() -> SignalFuture {
 
     #[cfg(not(any(unix, target_family = "wasm")))]
     {
+        let _ = enable_upgrade;
         Box::pin(async {
             let _ = tokio::signal::ctrl_c().await;
             Signal::Int
@@ -59,6 +64,7 @@ pub(crate) fn start() -> SignalFuture {
 
     #[cfg(target_family = "wasm")]
     {
+        let _ = enable_upgrade;
         Box::pin(std::future::pending())
     }
 }
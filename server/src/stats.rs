@@ -0,0 +1,240 @@
+//! runtime statistics of server and it's workers.
+//!
+//! # Examples
+//! ```rust
+//! # use xitca_io::net::TcpStream;
+//! # use xitca_server::Builder;
+//! # use xitca_service::fn_service;
+//! # #[tokio::main]
+//! # async fn main() {
+//! let mut server = Builder::new()
+//!     .bind("test", "127.0.0.1:0", fn_service(|_io: TcpStream| async { Ok::<_, ()>(()) }))
+//!     .unwrap()
+//!     .build();
+//!
+//! let handle = server.handle().unwrap();
+//! tokio::spawn(server);
+//!
+//! // stats handle can be cloned and polled from any thread.
+//! let stats = handle.stats();
+//!
+//! for (idx, worker) in stats.workers().iter().enumerate() {
+//!     println!(
+//!         "worker {idx}: active {} accepted {} queued {}",
+//!         worker.active_connections(),
+//!         worker.accepted_total(),
+//!         worker.queue_depth()
+//!     );
+//! }
+//! # handle.stop(true);
+//! # }
+//! ```
+
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+use std::sync::Arc;
+
+/// a cheap to clone handle of server statistics. every counter is updated by workers in real time.
+#[derive(Clone)]
+pub struct ServerStats {
+    workers: Arc<[Arc<WorkerStats>]>,
+}
+
+impl ServerStats {
+    pub(crate) fn new(workers: usize) -> Self {
+        Self {
+            workers: (0..workers).map(|_| Arc::new(WorkerStats::default())).collect(),
+        }
+    }
+
+    pub(crate) fn worker(&self, idx: usize) -> &Arc<WorkerStats> {
+        &self.workers[idx]
+    }
+
+    /// statistics of every worker thread in order of worker index.
+    pub fn workers(&self) -> &[Arc<WorkerStats>] {
+        &self.workers
+    }
+
+    /// number of connections currently being served by all workers.
+    pub fn active_connections(&self) -> usize {
+        self.workers.iter().map(|w| w.active_connections()).sum()
+    }
+
+    /// total number of connections accepted by all workers.
+    pub fn accepted_total(&self) -> u64 {
+        self.workers.iter().map(|w| w.accepted_total()).sum()
+    }
+
+    /// total number of connections rejected by connection filter of all workers.
+    pub fn rejected_total(&self) -> u64 {
+        self.workers.iter().map(|w| w.rejected_total()).sum()
+    }
+
+    /// total number of times accepting is paused for max connections reached of all workers.
+    pub fn accept_paused_total(&self) -> u64 {
+        self.workers.iter().map(|w| w.accept_paused_total()).sum()
+    }
+
+    /// total number of bytes read from connections of all workers.
+    pub fn read_bytes(&self) -> u64 {
+        self.workers.iter().map(|w| w.read_bytes()).sum()
+    }
+
+    /// total number of bytes written to connections of all workers.
+    pub fn write_bytes(&self) -> u64 {
+        self.workers.iter().map(|w| w.write_bytes()).sum()
+    }
+}
+
+/// statistics of a single worker thread.
+#[derive(Debug, Default)]
+pub struct WorkerStats {
+    active: AtomicUsize,
+    accepted: AtomicU64,
+    rejected: AtomicU64,
+    paused: AtomicU64,
+    queued: AtomicUsize,
+    read: AtomicU64,
+    write: AtomicU64,
+}
+
+impl WorkerStats {
+    /// number of connections currently being served by worker.
+    pub fn active_connections(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// total number of connections accepted by worker.
+    pub fn accepted_total(&self) -> u64 {
+        self.accepted.load(Ordering::Relaxed)
+    }
+
+    /// total number of connections rejected by [connection filter](crate::Builder::connection_filter) of worker.
+    /// rejected connections are not counted as accepted.
+    pub fn rejected_total(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// total number of times accepting is paused for reaching max connections of server or worker. a constantly
+    /// growing count means server is saturated.
+    ///
+    /// See [Builder::max_connections](crate::Builder::max_connections) for detail.
+    pub fn accept_paused_total(&self) -> u64 {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// number of accepted connections waiting for worker to start serving them. a constantly high queue depth
+    /// means worker is overloaded.
+    pub fn queue_depth(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    /// total number of bytes read from connections of worker.
+    pub fn read_bytes(&self) -> u64 {
+        self.read.load(Ordering::Relaxed)
+    }
+
+    /// total number of bytes written to connections of worker.
+    pub fn write_bytes(&self) -> u64 {
+        self.write.load(Ordering::Relaxed)
+    }
+
+    /// record bytes read from connection. server does not look into connection io and it's up to protocol
+    /// implementations to report the bytes through [WorkerStats::current].
+    pub fn record_read(&self, bytes: usize) {
+        self.read.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// record bytes written to connection. see [WorkerStats::record_read] for detail.
+    pub fn record_write(&self, bytes: usize) {
+        self.write.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// get statistics of current worker thread. return None when not called from a worker thread of server.
+    pub fn current() -> Option<Arc<WorkerStats>> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    pub(crate) fn set_current(stats: Arc<WorkerStats>) {
+        CURRENT.with(|current| *current.borrow_mut() = Some(stats));
+    }
+
+    pub(crate) fn reject(&self) {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn accept_paused(&self) {
+        self.paused.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn accept(self: &Arc<Self>) -> ConnectionGuard {
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+        self.active.fetch_add(1, Ordering::Relaxed);
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard {
+            stats: self.clone(),
+            queued: true,
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<WorkerStats>>> = const { RefCell::new(None) };
+}
+
+// guard tracking the life cycle of accepted connection.
+pub(crate) struct ConnectionGuard {
+    stats: Arc<WorkerStats>,
+    queued: bool,
+}
+
+impl ConnectionGuard {
+    // called when worker starts serving connection.
+    pub(crate) fn start(&mut self) {
+        if core::mem::replace(&mut self.queued, false) {
+            self.stats.queued.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.start();
+        self.stats.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn connection_guard() {
+        let stats = ServerStats::new(2);
+        let worker = stats.worker(1);
+
+        let mut guard = worker.accept();
+        let guard2 = worker.accept();
+        assert_eq!(stats.active_connections(), 2);
+        assert_eq!(worker.queue_depth(), 2);
+
+        guard.start();
+        assert_eq!(worker.queue_depth(), 1);
+
+        drop(guard);
+        drop(guard2);
+        assert_eq!(stats.active_connections(), 0);
+        assert_eq!(worker.queue_depth(), 0);
+        assert_eq!(stats.accepted_total(), 2);
+        assert_eq!(stats.workers()[0].accepted_total(), 0);
+
+        worker.record_read(3);
+        worker.record_write(4);
+        assert_eq!(stats.read_bytes(), 3);
+        assert_eq!(stats.write_bytes(), 4);
+    }
+}
@@ -0,0 +1,199 @@
+//! systemd socket activation and service notification.
+//!
+//! # Socket activation
+//! After [Builder::inherit_listeners] sockets passed by systemd through `LISTEN_FDS` environment variable are picked
+//! up by [Builder::bind] and [Builder::bind_unix] when the name given to them matches the `FileDescriptorName=`
+//! option of systemd socket unit. Sockets without explicit name are named `unknown` by systemd.
+//!
+//! # Notification
+//! When service is running as `Type=notify` unit [ServerFuture] sends `READY=1` once every worker has constructed
+//! it's services and `STOPPING=1` when it starts shutting down. When `WatchdogSec=` is set keep alive pings are
+//! sent at half of the watchdog interval for as long as [ServerFuture] is running.
+//!
+//! [Builder::inherit_listeners]: crate::Builder::inherit_listeners
+//! [Builder::bind]: crate::Builder::bind
+//! [Builder::bind_unix]: crate::Builder::bind_unix
+//! [ServerFuture]: crate::ServerFuture
+
+use std::{
+    env,
+    ffi::OsStr,
+    io,
+    os::{fd::RawFd, unix::net::UnixDatagram},
+    process,
+    time::Duration,
+};
+
+const LISTEN_PID: &str = "LISTEN_PID";
+const LISTEN_FDS: &str = "LISTEN_FDS";
+const LISTEN_FDNAMES: &str = "LISTEN_FDNAMES";
+const NOTIFY_SOCKET: &str = "NOTIFY_SOCKET";
+const WATCHDOG_PID: &str = "WATCHDOG_PID";
+const WATCHDOG_USEC: &str = "WATCHDOG_USEC";
+
+// first file descriptor passed by systemd. (SD_LISTEN_FDS_START)
+const LISTEN_FDS_START: RawFd = 3;
+
+/// collect named file descriptors passed from systemd socket activation.
+///
+/// related environment variables are removed afterwards so they would not leak into child processes.
+pub(crate) fn listen_fds() -> Vec<(String, RawFd)> {
+    let pid = env::var(LISTEN_PID).ok();
+    let fds = env::var(LISTEN_FDS).ok();
+    let names = env::var(LISTEN_FDNAMES).ok();
+
+    env::remove_var(LISTEN_PID);
+    env::remove_var(LISTEN_FDS);
+    env::remove_var(LISTEN_FDNAMES);
+
+    parse_listen_fds(process::id(), pid.as_deref(), fds.as_deref(), names.as_deref())
+}
+
+fn parse_listen_fds(
+    pid: u32,
+    listen_pid: Option<&str>,
+    fds: Option<&str>,
+    names: Option<&str>,
+) -> Vec<(String, RawFd)> {
+    // file descriptors are meant for other process.
+    if listen_pid.and_then(|p| p.trim().parse::<u32>().ok()) != Some(pid) {
+        return Vec::new();
+    }
+
+    let Some(count) = fds.and_then(|fds| fds.trim().parse::<RawFd>().ok()) else {
+        return Vec::new();
+    };
+
+    let mut names = names.unwrap_or_default().split(':');
+
+    (LISTEN_FDS_START..LISTEN_FDS_START.saturating_add(count))
+        .map(|fd| {
+            let name = names.next().filter(|name| !name.is_empty()).unwrap_or("unknown");
+            (name.to_owned(), fd)
+        })
+        .collect()
+}
+
+/// Send state string to systemd service manager.
+///
+/// State string is newline separated `KEY=VALUE` pairs as described in `sd_notify(3)`.
+///
+/// Returns `Ok(false)` when service is not managed by systemd (`NOTIFY_SOCKET` is not set) and
+/// nothing is sent.
+pub fn notify(state: &str) -> io::Result<bool> {
+    send_notify(env::var_os(NOTIFY_SOCKET).as_deref(), state)
+}
+
+fn send_notify(path: Option<&OsStr>, state: &str) -> io::Result<bool> {
+    let Some(path) = path else {
+        return Ok(false);
+    };
+
+    let socket = UnixDatagram::unbound()?;
+
+    match path.as_encoded_bytes() {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        [b'@', name @ ..] => {
+            #[cfg(target_os = "android")]
+            use std::os::android::net::SocketAddrExt;
+            #[cfg(target_os = "linux")]
+            use std::os::linux::net::SocketAddrExt;
+
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &addr)?;
+        }
+        _ => {
+            socket.send_to(state.as_bytes(), path)?;
+        }
+    }
+
+    Ok(true)
+}
+
+/// Notify systemd service startup is finished.
+pub fn notify_ready() -> io::Result<bool> {
+    notify("READY=1")
+}
+
+/// Notify systemd service is beginning it's shutdown.
+pub fn notify_stopping() -> io::Result<bool> {
+    notify("STOPPING=1")
+}
+
+/// Send keep alive ping to systemd watchdog.
+pub fn notify_watchdog() -> io::Result<bool> {
+    notify("WATCHDOG=1")
+}
+
+/// Interval keep alive ping should be sent to systemd watchdog.
+///
+/// Returns `None` when watchdog is not enabled for current process. The interval is half of the
+/// configured watchdog timeout.
+pub fn watchdog_interval() -> Option<Duration> {
+    let pid = env::var(WATCHDOG_PID).ok();
+    let usec = env::var(WATCHDOG_USEC).ok()?;
+    parse_watchdog(process::id(), pid.as_deref(), &usec)
+}
+
+fn parse_watchdog(pid: u32, watchdog_pid: Option<&str>, usec: &str) -> Option<Duration> {
+    if let Some(watchdog_pid) = watchdog_pid {
+        if watchdog_pid.trim().parse::<u32>().ok()? != pid {
+            return None;
+        }
+    }
+
+    let usec = usec.trim().parse::<u64>().ok().filter(|usec| *usec > 0)?;
+
+    Some(Duration::from_micros(usec / 2))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn listen_fds() {
+        let fds = parse_listen_fds(7, Some("7"), Some("3"), Some("http::https"));
+        assert_eq!(
+            fds,
+            [
+                (String::from("http"), 3),
+                (String::from("unknown"), 4),
+                (String::from("https"), 5)
+            ]
+        );
+
+        let fds = parse_listen_fds(7, Some("7"), Some("1"), None);
+        assert_eq!(fds, [(String::from("unknown"), 3)]);
+
+        assert!(parse_listen_fds(7, Some("8"), Some("1"), None).is_empty());
+        assert!(parse_listen_fds(7, None, Some("1"), None).is_empty());
+        assert!(parse_listen_fds(7, Some("7"), None, None).is_empty());
+    }
+
+    #[test]
+    fn watchdog() {
+        assert_eq!(parse_watchdog(7, None, "2000000"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_watchdog(7, Some("7"), "2000000"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_watchdog(7, Some("8"), "2000000"), None);
+        assert_eq!(parse_watchdog(7, None, "0"), None);
+        assert_eq!(parse_watchdog(7, None, "abc"), None);
+    }
+
+    #[test]
+    fn notify_socket() {
+        let dir = env::temp_dir().join(format!("xitca-server-notify-{}", process::id()));
+        let _ = std::fs::remove_file(&dir);
+        let server = UnixDatagram::bind(&dir).unwrap();
+
+        assert!(send_notify(Some(dir.as_os_str()), "READY=1").unwrap());
+
+        let mut buf = [0; 16];
+        let n = server.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+
+        assert!(!send_notify(None, "READY=1").unwrap());
+
+        let _ = std::fs::remove_file(&dir);
+    }
+}
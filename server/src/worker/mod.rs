@@ -9,12 +9,19 @@ use tracing::{error, info};
 use xitca_io::net::{Listener, Stream};
 use xitca_service::{ready::ReadyService, Service};
 
+use crate::{
+    drain::Drain,
+    filter::{self, Filter},
+    limit::Limit,
+    stats::WorkerStats,
+};
+
 use self::shutdown::ShutdownHandle;
 
 // erase Rc<S: ReadyService<_>> type and only use it for counting the reference counter of Rc.
 pub(crate) type ServiceAny = Rc<dyn Any>;
 
-pub(crate) fn start<S, Req>(listener: &Arc<Listener>, service: &Rc<S>) -> JoinHandle<()>
+pub(crate) fn start<S, Req>(listener: &Arc<Listener>, service: &Rc<S>, filter: &Filter) -> JoinHandle<()>
 where
     S: ReadyService + Service<Req> + 'static,
     S::Ready: 'static,
@@ -22,18 +29,32 @@ where
 {
     let listener = listener.clone();
     let service = service.clone();
+    let filter = filter.clone();
+    let limit = Limit::current();
+    let stats = WorkerStats::current().unwrap_or_default();
 
     tokio::task::spawn_local(async move {
         loop {
             let ready = service.ready().await;
+            let permit = limit.acquire(&stats).await;
 
             match listener.accept().await {
                 Ok(stream) => {
+                    // filter connection before any conversion and protocol handling happen.
+                    let Some(guard) = filter::accept(&filter, &stream) else {
+                        stats.reject();
+                        continue;
+                    };
                     if let Ok(req) = TryFrom::try_from(stream) {
                         let service = service.clone();
+                        let mut conn = stats.accept();
                         tokio::task::spawn_local(async move {
+                            conn.start();
                             let _ = service.call(req).await;
+                            drop(conn);
                             drop(ready);
+                            drop(guard);
+                            drop(permit);
                         });
                     }
                 }
@@ -58,7 +79,9 @@ pub(crate) async fn wait_for_stop(
 ) {
     with_worker_name_str(|name| info!("Started {name}"));
 
-    let shutdown_handle = ShutdownHandle::new(shutdown_timeout, services, is_graceful_shutdown);
+    let drain = Drain::current().unwrap_or_default();
+
+    let shutdown_handle = ShutdownHandle::new(shutdown_timeout, services, drain.clone(), is_graceful_shutdown);
 
     for handle in handles {
         handle
@@ -66,6 +89,9 @@ pub(crate) async fn wait_for_stop(
             .unwrap_or_else(|e| with_worker_name_str(|name| error!("{name} exit on error: {e}")));
     }
 
+    // accepting is stopped. notify background tasks of worker.
+    drain.start();
+
     shutdown_handle.shutdown().await;
 }
 
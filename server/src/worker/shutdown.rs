@@ -6,11 +6,14 @@ use std::{
 
 use tracing::info;
 
+use crate::drain::Drain;
+
 use super::{with_worker_name_str, ServiceAny};
 
 pub(super) struct ShutdownHandle<'a> {
     shutdown_timeout: Duration,
     services: Vec<ServiceAny>,
+    drain: Drain,
     is_graceful_shutdown: &'a AtomicBool,
 }
 
@@ -22,10 +25,14 @@ impl Drop for ShutdownHandle<'_> {
             .into_iter()
             .fold(0, |total, service| total + Rc::strong_count(&service).saturating_sub(1));
 
-        if remaining == 0 {
+        let guards = self.drain.active_guards();
+
+        if remaining == 0 && guards == 0 {
             with_worker_name_str(|name| info!("Graceful stopped {name}"));
         } else {
-            with_worker_name_str(|name| info!("Force stopped {name}. {remaining} connections(estimate) left."));
+            with_worker_name_str(|name| {
+                info!("Force stopped {name}. {remaining} connections(estimate) and {guards} drain guards left.")
+            });
         }
     }
 }
@@ -34,11 +41,13 @@ impl<'a> ShutdownHandle<'a> {
     pub(super) fn new(
         shutdown_timeout: Duration,
         services: Vec<ServiceAny>,
+        drain: Drain,
         is_graceful_shutdown: &'a AtomicBool,
     ) -> Self {
         Self {
             shutdown_timeout,
             services,
+            drain,
             is_graceful_shutdown,
         }
     }
@@ -50,7 +59,7 @@ impl<'a> ShutdownHandle<'a> {
             while start.elapsed() < self.shutdown_timeout {
                 self.retain_active_services();
 
-                if self.services.is_empty() {
+                if self.services.is_empty() && self.drain.active_guards() == 0 {
                     return;
                 }
 
@@ -46,7 +46,9 @@ pub mod ready;
 
 pub use self::{
     async_fn::AsyncFn,
-    pipeline::{EnclosedBuilder, EnclosedFnBuilder, MapBuilder, MapErrorBuilder},
+    pipeline::{
+        AndThenBuilder, EnclosedBuilder, EnclosedFnBuilder, InjectBuilder, MapBuilder, MapErrorBuilder, ThenBuilder,
+    },
     service::{fn_build, fn_service, FnService, Service, ServiceExt},
 };
 
@@ -0,0 +1,415 @@
+use core::{fmt, time::Duration};
+
+use std::{error, sync::Mutex, time::Instant};
+
+use crate::{ready::ReadyService, service::Service};
+
+/// A middleware stop calling inner service when it keeps failing. Useful for wrapping outbound dependencies
+/// like database or upstream http service so callers fail fast instead of piling up on an unhealthy one.
+///
+/// Circuit breaker works in three states:
+/// - closed: calls pass through to inner service and their outcomes are recorded. Every `Err` output of
+///   inner service is treated as failure. The circuit opens when failures reach the configured threshold.
+/// - open: calls are rejected with [CircuitBreakerError::Open] without calling inner service until
+///   [CircuitBreaker::open_duration] elapsed.
+/// - half open: a limited number of probe calls pass through. The circuit is closed when all probes succeed
+///   and opens again when any of them fails. Calls exceeding probe limit are rejected.
+///
+/// State is not shared between services constructed from the same CircuitBreaker. (e.g: services constructed
+/// on different threads have their own circuits)
+///
+/// # Examples
+/// ```rust
+/// # use core::time::Duration;
+/// # use xitca_service::{fn_service, middleware::{CircuitBreaker, CircuitBreakerError}, Service, ServiceExt};
+/// # async fn call() {
+/// let service = fn_service(|fail: bool| async move { if fail { Err("upstream error") } else { Ok(()) } })
+///     // open circuit after 2 consecutive failures and keep it open for 10 seconds.
+///     .enclosed(CircuitBreaker::consecutive_failures(2).open_duration(Duration::from_secs(10)))
+///     .call(())
+///     .await
+///     .unwrap();
+///
+/// assert!(matches!(service.call(true).await, Err(CircuitBreakerError::Service(_))));
+/// assert!(matches!(service.call(true).await, Err(CircuitBreakerError::Service(_))));
+/// // inner service is not called when circuit is open.
+/// assert!(matches!(service.call(false).await, Err(CircuitBreakerError::Open)));
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CircuitBreaker {
+    trip: Trip,
+    open_duration: Duration,
+    half_open_probes: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Trip {
+    ConsecutiveFailures(usize),
+    ErrorRate { rate: f64, window: usize },
+}
+
+impl CircuitBreaker {
+    /// Open circuit after given number of consecutive failures.
+    ///
+    /// # Panics
+    /// When num is 0.
+    pub fn consecutive_failures(num: usize) -> Self {
+        assert_ne!(num, 0, "consecutive failures must be greater than 0");
+        Self::new(Trip::ConsecutiveFailures(num))
+    }
+
+    /// Open circuit when error rate of the last `window` number of calls reaches given rate. Circuit stays
+    /// closed before `window` number of calls are recorded.
+    ///
+    /// # Panics
+    /// When rate is not in range of `(0.0, 1.0]` or window is not in range of `1..=64`.
+    pub fn error_rate(rate: f64, window: usize) -> Self {
+        assert!(rate > 0.0 && rate <= 1.0, "error rate must be in range of (0.0, 1.0]");
+        assert!(
+            window > 0 && window <= 64,
+            "error rate window must be in range of 1..=64"
+        );
+        Self::new(Trip::ErrorRate { rate, window })
+    }
+
+    const fn new(trip: Trip) -> Self {
+        Self {
+            trip,
+            open_duration: Duration::from_secs(30),
+            half_open_probes: 1,
+        }
+    }
+
+    /// Set duration circuit stays open before entering half open state.
+    ///
+    /// Default to 30 seconds.
+    pub fn open_duration(mut self, dur: Duration) -> Self {
+        self.open_duration = dur;
+        self
+    }
+
+    /// Set number of probe calls allowed in half open state. All of them must succeed to close the circuit.
+    ///
+    /// Default to 1.
+    ///
+    /// # Panics
+    /// When num is 0.
+    pub fn half_open_probes(mut self, num: usize) -> Self {
+        assert_ne!(num, 0, "half open probes must be greater than 0");
+        self.half_open_probes = num;
+        self
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for CircuitBreaker {
+    type Response = CircuitBreakerService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| CircuitBreakerService {
+            service,
+            config: *self,
+            state: Mutex::new(State::closed()),
+        })
+    }
+}
+
+/// Error type of [CircuitBreakerService].
+pub enum CircuitBreakerError<E> {
+    /// circuit is open and inner service is not called.
+    Open,
+    /// error produced by inner service.
+    Service(E),
+}
+
+impl<E> fmt::Debug for CircuitBreakerError<E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Open => f.write_str("Open"),
+            Self::Service(ref e) => f.debug_tuple("Service").field(e).finish(),
+        }
+    }
+}
+
+impl<E> fmt::Display for CircuitBreakerError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Open => f.write_str("circuit breaker is open"),
+            Self::Service(ref e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl<E> error::Error for CircuitBreakerError<E>
+where
+    E: error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Self::Open => None,
+            Self::Service(ref e) => Some(e),
+        }
+    }
+}
+
+pub struct CircuitBreakerService<S> {
+    service: S,
+    config: CircuitBreaker,
+    state: Mutex<State>,
+}
+
+enum State {
+    Closed {
+        // number of consecutive failures.
+        failures: usize,
+        // bit ring of recent outcomes. 1 for failure.
+        outcomes: u64,
+        // number of recorded outcomes. capped at window size.
+        len: usize,
+    },
+    Open(Instant),
+    HalfOpen {
+        in_flight: usize,
+        success: usize,
+    },
+}
+
+impl State {
+    const fn closed() -> Self {
+        Self::Closed {
+            failures: 0,
+            outcomes: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<S> CircuitBreakerService<S> {
+    fn is_open(&self) -> bool {
+        match *self.state.lock().unwrap() {
+            State::Open(until) => Instant::now() < until,
+            State::HalfOpen { in_flight, .. } => in_flight >= self.config.half_open_probes,
+            State::Closed { .. } => false,
+        }
+    }
+
+    fn acquire(&self) -> Option<Permit<'_, S>> {
+        let mut state = self.state.lock().unwrap();
+        let probe = match *state {
+            State::Closed { .. } => false,
+            State::Open(until) => {
+                if Instant::now() < until {
+                    return None;
+                }
+                *state = State::HalfOpen {
+                    in_flight: 1,
+                    success: 0,
+                };
+                true
+            }
+            State::HalfOpen { ref mut in_flight, .. } => {
+                if *in_flight >= self.config.half_open_probes {
+                    return None;
+                }
+                *in_flight += 1;
+                true
+            }
+        };
+        Some(Permit { service: self, probe })
+    }
+
+    fn open(&self, state: &mut State) {
+        *state = State::Open(Instant::now() + self.config.open_duration);
+    }
+}
+
+// permit of calling inner service. in flight probe is released on drop when call is cancelled.
+struct Permit<'a, S> {
+    service: &'a CircuitBreakerService<S>,
+    probe: bool,
+}
+
+impl<S> Permit<'_, S> {
+    fn record(mut self, ok: bool) {
+        let service = self.service;
+        let mut state = service.state.lock().unwrap();
+        match (&mut *state, core::mem::take(&mut self.probe)) {
+            (State::HalfOpen { in_flight, success }, true) => {
+                *in_flight -= 1;
+                if !ok {
+                    service.open(&mut state);
+                } else {
+                    *success += 1;
+                    if *success >= service.config.half_open_probes {
+                        *state = State::closed();
+                    }
+                }
+            }
+            (
+                State::Closed {
+                    failures,
+                    outcomes,
+                    len,
+                },
+                false,
+            ) => match service.config.trip {
+                Trip::ConsecutiveFailures(max) => {
+                    *failures = if ok { 0 } else { *failures + 1 };
+                    if *failures >= max {
+                        service.open(&mut state);
+                    }
+                }
+                Trip::ErrorRate { rate, window } => {
+                    let mask = u64::MAX >> (64 - window);
+                    *outcomes = ((*outcomes << 1) | u64::from(!ok)) & mask;
+                    *len = (*len + 1).min(window);
+                    if !ok && *len == window && f64::from(outcomes.count_ones()) / window as f64 >= rate {
+                        service.open(&mut state);
+                    }
+                }
+            },
+            // state has been changed by concurrent calls. outcome is outdated.
+            _ => {}
+        }
+    }
+}
+
+impl<S> Drop for Permit<'_, S> {
+    fn drop(&mut self) {
+        if self.probe {
+            if let State::HalfOpen { ref mut in_flight, .. } = *self.service.state.lock().unwrap() {
+                *in_flight -= 1;
+            }
+        }
+    }
+}
+
+impl<S, Req> Service<Req> for CircuitBreakerService<S>
+where
+    S: Service<Req>,
+{
+    type Response = S::Response;
+    type Error = CircuitBreakerError<S::Error>;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let permit = self.acquire().ok_or(CircuitBreakerError::Open)?;
+        let res = self.service.call(req).await;
+        permit.record(res.is_ok());
+        res.map_err(CircuitBreakerError::Service)
+    }
+}
+
+/// ready state is None when circuit is open and calling the service would be rejected.
+/// It's a hint for skipping the service and the circuit may still be opened by concurrent calls
+/// before it's called.
+impl<S> ReadyService for CircuitBreakerService<S>
+where
+    S: ReadyService,
+{
+    type Ready = Option<S::Ready>;
+
+    async fn ready(&self) -> Self::Ready {
+        if self.is_open() {
+            return None;
+        }
+        Some(self.service.ready().await)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{fn_service, ServiceExt};
+
+    use super::*;
+
+    async fn index(fail: bool) -> Result<(), ()> {
+        if fail {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn consecutive_failures() {
+        let service = fn_service(index)
+            .enclosed(CircuitBreaker::consecutive_failures(2).open_duration(Duration::from_millis(10)))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |fail| service.call(fail).now_or_panic();
+
+        assert!(matches!(call(true), Err(CircuitBreakerError::Service(_))));
+        assert!(call(false).is_ok());
+        assert!(matches!(call(true), Err(CircuitBreakerError::Service(_))));
+        assert!(matches!(call(true), Err(CircuitBreakerError::Service(_))));
+        assert!(matches!(call(false), Err(CircuitBreakerError::Open)));
+        assert!(service.ready().now_or_panic().is_none());
+
+        // failed probe opens circuit again.
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(service.ready().now_or_panic().is_some());
+        assert!(matches!(call(true), Err(CircuitBreakerError::Service(_))));
+        assert!(matches!(call(false), Err(CircuitBreakerError::Open)));
+
+        // succeeded probe closes circuit.
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(call(false).is_ok());
+        assert!(matches!(call(true), Err(CircuitBreakerError::Service(_))));
+        assert!(call(false).is_ok());
+    }
+
+    #[test]
+    fn error_rate() {
+        let service = fn_service(index)
+            .enclosed(
+                CircuitBreaker::error_rate(0.5, 4)
+                    .open_duration(Duration::from_millis(10))
+                    .half_open_probes(2),
+            )
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |fail| service.call(fail).now_or_panic();
+
+        for fail in [true, true, false, false, false, true] {
+            assert_eq!(call(fail).is_ok(), !fail);
+        }
+        assert!(matches!(call(true), Err(CircuitBreakerError::Service(_))));
+        assert!(matches!(call(false), Err(CircuitBreakerError::Open)));
+
+        // all probes must succeed to close circuit.
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(call(false).is_ok());
+        assert!(call(false).is_ok());
+        assert!(call(true).is_err());
+        assert!(call(false).is_ok());
+    }
+
+    #[test]
+    fn cancelled_probe() {
+        let service = fn_service(index)
+            .enclosed(CircuitBreaker::consecutive_failures(1).open_duration(Duration::ZERO))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        assert!(service.call(true).now_or_panic().is_err());
+        // probe permit dropped without recording outcome must be released.
+        let permit = service.acquire().unwrap();
+        assert!(permit.probe);
+        drop(permit);
+        assert!(service.call(false).now_or_panic().is_ok());
+    }
+}
@@ -3,9 +3,13 @@
 //! [ServiceExt::enclosed]: crate::service::ServiceExt::enclosed
 
 mod async_fn;
+#[cfg(feature = "std")]
+mod circuit_breaker;
 mod group;
 mod unchecked_ready;
 
 pub use async_fn::AsyncFn;
+#[cfg(feature = "std")]
+pub use circuit_breaker::{CircuitBreaker, CircuitBreakerError, CircuitBreakerService};
 pub use group::Group;
 pub use unchecked_ready::UncheckedReady;
@@ -1,6 +1,44 @@
 //! trait and types making [Service] object safe.
+//!
+//! [Service::call] returns an opaque future borrowing `&self` which can not be expressed as trait object.
+//! [ServiceObject] is the bridge trait where the future is boxed and the borrow is expressed as lifetime
+//! param of [BoxFuture]. Every [Service] type implements [ServiceObject] and trait objects of
+//! [ServiceObject] implement [Service] again so erased types can be used as regular services.
+//!
+//! # Examples
+//! ```rust
+//! # use xitca_service::{fn_service, object::{BoxedServiceObject, ServiceObject}, Service, ServiceExt};
+//! # use xitca_unsafe_collection::futures::NowOrPanic;
+//! # fn build<S: Service<Response = T>, T>(s: S) -> T { s.call(()).now_or_panic().ok().unwrap() }
+//! // services with different types can be stored uniformly after erasing.
+//! let services: Vec<BoxedServiceObject<usize, usize, ()>> = vec![
+//!     build(fn_service(|req: usize| async move { Ok(req + 1) })).boxed(),
+//!     build(fn_service(|_: usize| async { Ok(996) })).boxed(),
+//! ];
+//!
+//! for service in services.iter() {
+//!     assert!(Service::call(service, 251).now_or_panic().is_ok());
+//! }
+//!
+//! // request type bound to lifetime can be expressed with higher ranked trait bound.
+//! struct Echo;
+//!
+//! impl<'r> Service<&'r str> for Echo {
+//!     type Response = &'r str;
+//!     type Error = ();
+//!
+//!     async fn call(&self, req: &'r str) -> Result<Self::Response, Self::Error> {
+//!         Ok(req)
+//!     }
+//! }
+//!
+//! let service: Box<dyn for<'r> ServiceObject<&'r str, Response = &'r str, Error = ()>> = Box::new(Echo);
+//!
+//! let req = String::from("996");
+//! assert_eq!(Service::call(&service, &req).now_or_panic(), Ok("996"));
+//! ```
 
-use alloc::boxed::Box;
+use alloc::{boxed::Box, sync::Arc};
 
 use super::{service::Service, BoxFuture};
 
@@ -43,9 +81,34 @@ where
     }
 }
 
+macro_rules! dyn_impl {
+    ($($bound: tt)*) => {
+        impl<Req, Res, Err> Service<Req> for dyn ServiceObject<Req, Response = Res, Error = Err> $($bound)* + '_ {
+            type Response = Res;
+            type Error = Err;
+
+            #[inline]
+            async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+                ServiceObject::call(self, req).await
+            }
+        }
+    };
+}
+
+// trait object impl enables smart pointers other than Box(Rc, Arc etc) to be used as Service.
+dyn_impl!();
+dyn_impl!(+ Send);
+dyn_impl!(+ Send + Sync);
+
 /// An often used type alias for boxed service object. used when Req type is not bound to any
 /// lifetime.
 pub type BoxedServiceObject<Req, Res, Err> = Box<dyn ServiceObject<Req, Response = Res, Error = Err>>;
 
 /// sync version of [BoxedServiceObject]
 pub type BoxedSyncServiceObject<Req, Res, Err> = Box<dyn ServiceObject<Req, Response = Res, Error = Err> + Send + Sync>;
+
+/// reference counted version of [BoxedServiceObject]. used when service object needs to be shared.
+pub type ArcServiceObject<Req, Res, Err> = Arc<dyn ServiceObject<Req, Response = Res, Error = Err>>;
+
+/// sync version of [ArcServiceObject]
+pub type ArcSyncServiceObject<Req, Res, Err> = Arc<dyn ServiceObject<Req, Response = Res, Error = Err> + Send + Sync>;
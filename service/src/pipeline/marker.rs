@@ -6,5 +6,9 @@ pub struct BuildMapErr;
 pub struct MapErr;
 pub struct BuildAndThen;
 pub struct AndThen;
+pub struct BuildThen;
+pub struct Then;
 pub struct BuildEnclosed;
 pub struct AsyncFn;
+pub struct BuildInject;
+pub struct Inject;
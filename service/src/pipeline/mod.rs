@@ -19,3 +19,12 @@ pub type MapBuilder<F, S> = PipelineT<F, S, marker::BuildMap>;
 
 /// Type alias for specialized [PipelineT] type.
 pub type MapErrorBuilder<F, S> = PipelineT<F, S, marker::BuildMapErr>;
+
+/// Type alias for specialized [PipelineT] type.
+pub type AndThenBuilder<F, S> = PipelineT<F, S, marker::BuildAndThen>;
+
+/// Type alias for specialized [PipelineT] type.
+pub type ThenBuilder<F, S> = PipelineT<F, S, marker::BuildThen>;
+
+/// Type alias for specialized [PipelineT] type.
+pub type InjectBuilder<F, S> = PipelineT<F, S, marker::BuildInject>;
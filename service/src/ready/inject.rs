@@ -0,0 +1,10 @@
+use crate::pipeline::{marker::Inject, PipelineT};
+
+use super::ReadyService;
+
+impl<SF, F> ReadyService for PipelineT<SF, F, Inject> {
+    type Ready = ();
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {}
+}
@@ -3,8 +3,10 @@
 mod and_then;
 mod enclosed_fn;
 mod function;
+mod inject;
 mod map;
 mod map_err;
+mod then;
 
 use core::{future::Future, ops::Deref, pin::Pin};
 
@@ -0,0 +1,15 @@
+use crate::pipeline::{marker::Then, PipelineT};
+
+use super::ReadyService;
+
+impl<S, F> ReadyService for PipelineT<S, F, Then>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.first.ready().await
+    }
+}
@@ -45,6 +45,17 @@ pub trait ServiceExt<Arg>: Service<Arg> {
         PipelineT::new(self, err)
     }
 
+    /// Mutate `Result<Self::Response, Self::Error>` output of service with given closure. Unlike
+    /// [Self::map] and [Self::map_err] both the Ok and Err branch are observable and can be converted
+    /// from one to another.
+    fn then<F, Res, Err, ResMap, ErrMap>(self, func: F) -> PipelineT<Self, F, marker::BuildThen>
+    where
+        F: Fn(Result<Res, Err>) -> Result<ResMap, ErrMap> + Clone,
+        Self: Sized,
+    {
+        PipelineT::new(self, func)
+    }
+
     /// Chain another service factory who's service takes `Self`'s `Service::Response` output as
     /// `Service::Request`.
     fn and_then<F>(self, factory: F) -> PipelineT<Self, F, marker::BuildAndThen>
@@ -54,6 +65,104 @@ pub trait ServiceExt<Arg>: Service<Arg> {
     {
         PipelineT::new(self, factory)
     }
+
+    /// Construct Self with argument derived from reference of request on every [Service::call] of output service.
+    /// Self is treated as a factory of per call service. Service construction is deferred from builder phase
+    /// to call phase and the request is passed to the constructed service afterward.
+    ///
+    /// This is useful for building per connection state from connection types like peer address of socket
+    /// where service of server is called once per connection. Self must be [Clone] as it's copied into the
+    /// output service. Wrap it in [Arc] when it's not.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_service::{fn_build, Service, ServiceExt};
+    /// // a connection type carrying it's peer address.
+    /// struct Connection {
+    ///     peer: &'static str,
+    /// }
+    ///
+    /// // service with per connection state.
+    /// struct Greeter {
+    ///     greeting: String,
+    /// }
+    ///
+    /// impl Service<Connection> for Greeter {
+    ///     type Response = String;
+    ///     type Error = ();
+    ///
+    ///     async fn call(&self, _: Connection) -> Result<Self::Response, Self::Error> {
+    ///         Ok(self.greeting.clone())
+    ///     }
+    /// }
+    ///
+    /// # async fn inject() {
+    /// // service factory with peer address as typed argument.
+    /// let service = fn_build(|peer: &'static str| async move {
+    ///     Ok(Greeter {
+    ///         greeting: format!("hello {peer}"),
+    ///     })
+    /// })
+    /// // derive the argument from connection.
+    /// .inject(|conn: &Connection| conn.peer)
+    /// .call(())
+    /// .await
+    /// .unwrap();
+    ///
+    /// let res = service.call(Connection { peer: "127.0.0.1" }).await.unwrap();
+    /// assert_eq!(res, "hello 127.0.0.1");
+    /// # }
+    /// ```
+    ///
+    /// [Arc]: https://doc.rust-lang.org/std/sync/struct.Arc.html
+    fn inject<F, Req>(self, func: F) -> PipelineT<Self, F, marker::BuildInject>
+    where
+        F: Fn(&Req) -> Arg + Clone,
+        Self: Clone + Sized,
+    {
+        PipelineT::new(self, func)
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Erase Self's type and box it as [ServiceObject] trait object.
+    ///
+    /// [ServiceObject]: crate::object::ServiceObject
+    fn boxed(self) -> crate::object::BoxedServiceObject<Arg, Self::Response, Self::Error>
+    where
+        Self: Sized + 'static,
+    {
+        alloc::boxed::Box::new(self)
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Thread safe version of [Self::boxed].
+    fn boxed_sync(self) -> crate::object::BoxedSyncServiceObject<Arg, Self::Response, Self::Error>
+    where
+        Self: Sized + Send + Sync + 'static,
+    {
+        alloc::boxed::Box::new(self)
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Erase Self's type and wrap it in [Arc] as [ServiceObject] trait object.
+    ///
+    /// [Arc]: alloc::sync::Arc
+    /// [ServiceObject]: crate::object::ServiceObject
+    fn arced(self) -> crate::object::ArcServiceObject<Arg, Self::Response, Self::Error>
+    where
+        Self: Sized + 'static,
+    {
+        alloc::sync::Arc::new(self)
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Thread safe version of [Self::arced].
+    fn arced_sync(self) -> crate::object::ArcSyncServiceObject<Arg, Self::Response, Self::Error>
+    where
+        Self: Sized + Send + Sync + 'static,
+    {
+        alloc::sync::Arc::new(self)
+    }
 }
 
 impl<S, Arg> ServiceExt<Arg> for S where S: Service<Arg> {}
@@ -62,7 +171,7 @@ impl<S, Arg> ServiceExt<Arg> for S where S: Service<Arg> {}
 mod test {
     use xitca_unsafe_collection::futures::NowOrPanic;
 
-    use crate::fn_service;
+    use crate::{fn_build, fn_service};
 
     use super::*;
 
@@ -129,6 +238,59 @@ mod test {
         assert_eq!(err, "251");
     }
 
+    #[test]
+    fn then() {
+        let service = fn_service(|_: &str| async { Err::<(), _>(()) })
+            .then(|res| match res {
+                Ok(_) => Err("996"),
+                Err(_) => Ok::<_, &str>("251"),
+            })
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call("996").now_or_panic().ok().unwrap();
+        assert_eq!(res, "251");
+    }
+
+    #[test]
+    fn and_then() {
+        let service = fn_service(index)
+            .and_then(fn_service(|s: &'static str| async move { Ok(s.len()) }))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call("996").now_or_panic().ok().unwrap();
+        assert_eq!(res, 3);
+    }
+
+    #[test]
+    fn inject() {
+        struct Slice(usize);
+
+        impl Service<&'static str> for Slice {
+            type Response = &'static str;
+            type Error = ();
+
+            async fn call(&self, req: &'static str) -> Result<Self::Response, Self::Error> {
+                Ok(&req[..self.0])
+            }
+        }
+
+        let service = fn_build(|len: usize| async move { Ok(Slice(len)) })
+            .inject(|s: &&'static str| s.len() - 1)
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call("996").now_or_panic().unwrap();
+        assert_eq!(res, "99");
+
+        let res = service.call("2510").now_or_panic().unwrap();
+        assert_eq!(res, "251");
+    }
+
     #[test]
     fn enclosed_fn() {
         async fn enclosed<S>(service: &S, req: &'static str) -> Result<&'static str, ()>
@@ -153,6 +315,25 @@ mod test {
         assert_eq!(res, "251");
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn boxed() {
+        let service = fn_service(index)
+            .enclosed(DummyMiddleware)
+            .boxed()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .boxed_sync();
+
+        let res = service.call("996").now_or_panic().unwrap();
+        assert_eq!(res, "996");
+
+        let service = fn_service(index).call(()).now_or_panic().unwrap().arced_sync();
+        let service2 = service.clone();
+        assert_eq!(service2.call("251").now_or_panic().unwrap(), "251");
+    }
+
     #[cfg(feature = "alloc")]
     #[test]
     fn enclosed_opt() {
@@ -0,0 +1,37 @@
+use core::convert::Infallible;
+
+use crate::pipeline::{
+    marker::{BuildInject, Inject},
+    PipelineT,
+};
+
+use super::Service;
+
+impl<SF, SF1> Service for PipelineT<SF, SF1, BuildInject>
+where
+    SF: Clone,
+    SF1: Clone,
+{
+    type Response = PipelineT<SF, SF1, Inject>;
+    type Error = Infallible;
+
+    async fn call(&self, _: ()) -> Result<Self::Response, Self::Error> {
+        Ok(PipelineT::new(self.first.clone(), self.second.clone()))
+    }
+}
+
+impl<SF, Req, F, Arg> Service<Req> for PipelineT<SF, F, Inject>
+where
+    F: Fn(&Req) -> Arg,
+    SF: Service<Arg>,
+    SF::Response: Service<Req, Error = SF::Error>,
+{
+    type Response = <SF::Response as Service<Req>>::Response;
+    type Error = SF::Error;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let arg = (self.second)(&req);
+        let service = self.first.call(arg).await?;
+        service.call(req).await
+    }
+}
@@ -2,9 +2,11 @@ mod and_then;
 mod enclosed;
 mod ext;
 mod function;
+mod inject;
 mod map;
 mod map_err;
 mod opt;
+mod then;
 
 pub use self::{
     ext::ServiceExt,
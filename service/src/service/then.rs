@@ -0,0 +1,34 @@
+use crate::pipeline::{
+    marker::{BuildThen, Then},
+    PipelineT,
+};
+
+use super::Service;
+
+impl<SF, Arg, SF1> Service<Arg> for PipelineT<SF, SF1, BuildThen>
+where
+    SF: Service<Arg>,
+    SF1: Clone,
+{
+    type Response = PipelineT<SF::Response, SF1, Then>;
+    type Error = SF::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let service = self.first.call(arg).await?;
+        Ok(PipelineT::new(service, self.second.clone()))
+    }
+}
+
+impl<S, Req, F, Res, Err> Service<Req> for PipelineT<S, F, Then>
+where
+    S: Service<Req>,
+    F: Fn(Result<S::Response, S::Error>) -> Result<Res, Err>,
+{
+    type Response = Res;
+    type Error = Err;
+
+    #[inline]
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        (self.second)(self.first.call(req).await)
+    }
+}
@@ -34,6 +34,7 @@ pub fn test_server<T, Req>(service: T) -> Result<TestServerHandle, Error>
 where
     T: Service + Send + Sync + 'static,
     T::Response: ReadyService + Service<Req>,
+    T::Error: Into<Error>,
     Req: TryFrom<NetStream> + 'static,
 {
     let lst = TcpListener::bind("127.0.0.1:0")?;
@@ -56,7 +57,7 @@ where
     T: Service + Send + Sync + 'static,
     T::Response: ReadyService + Service<Request<RequestExt<h1::RequestBody>>, Response = HResponse<B>> + 'static,
     <T::Response as Service<Request<RequestExt<h1::RequestBody>>>>::Error: fmt::Debug,
-    T::Error: error::Error + 'static,
+    T::Error: Into<Error>,
     B: Stream<Item = Result<Bytes, E>> + 'static,
     E: fmt::Debug + 'static,
 {
@@ -79,7 +80,7 @@ where
     T: Service + Send + Sync + 'static,
     T::Response: ReadyService + Service<Request<RequestExt<h2::RequestBody>>, Response = HResponse<B>> + 'static,
     <T::Response as Service<Request<RequestExt<h2::RequestBody>>>>::Error: fmt::Debug,
-    T::Error: error::Error + 'static,
+    T::Error: Into<Error>,
     B: Stream<Item = Result<Bytes, E>> + 'static,
     E: fmt::Debug + 'static,
 {
@@ -101,7 +102,7 @@ where
     T: Service + Send + Sync + 'static,
     T::Response: ReadyService + Service<Request<RequestExt<h3::RequestBody>>, Response = HResponse<B>> + 'static,
     <T::Response as Service<Request<RequestExt<h3::RequestBody>>>>::Error: fmt::Debug,
-    T::Error: error::Error + 'static,
+    T::Error: Into<Error>,
     B: Stream<Item = Result<Bytes, E>> + 'static,
     E: fmt::Debug + 'static,
 {
@@ -1,7 +1,11 @@
 use futures_util::StreamExt;
 use std::{
     io::{Read, Write},
-    net::TcpStream,
+    net::{SocketAddr, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
     time::Duration,
 };
 
@@ -9,14 +13,20 @@ use xitca_client::Client;
 use xitca_http::{
     body::{BoxBody, ResponseBody},
     bytes::{Bytes, BytesMut},
+    config::HttpServiceConfig,
+    disconnect::Disconnect,
+    error::BodyIncomplete,
     h1,
     http::{
         header::{self, HeaderValue, CONNECTION},
-        Method, Request, RequestExt, Response, Version,
+        Method, Request, RequestExt, Response, StatusCode, Version,
     },
+    informational::Informational,
+    timing::RequestTiming,
+    HttpServiceBuilder,
 };
-use xitca_service::fn_service;
-use xitca_test::{test_h1_server, Error};
+use xitca_service::{fn_service, Service, ServiceExt};
+use xitca_test::{test_h1_server, test_server, Error};
 
 #[tokio::test]
 async fn h1_get() -> Result<(), Error> {
@@ -243,6 +253,330 @@ async fn h1_keepalive() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h1_max_requests_per_connection() -> Result<(), Error> {
+    let service = fn_service(handle)
+        .enclosed(HttpServiceBuilder::h1().config(HttpServiceConfig::new().max_requests_per_connection(2)));
+    let mut handle = test_server::<_, (xitca_io::net::TcpStream, SocketAddr)>(service)?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+
+    let mut buf = Vec::new();
+    let mut read_response = |stream: &mut TcpStream| -> std::io::Result<String> {
+        buf.clear();
+        let mut chunk = [0; 128];
+        while !buf.ends_with(b"GET Response") {
+            let n = stream.read(&mut chunk)?;
+            assert_ne!(n, 0, "connection closed before response is received");
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        Ok(String::from_utf8_lossy(&buf).to_lowercase())
+    };
+
+    stream.write_all(SIMPLE_GET_REQ)?;
+    let res = read_response(&mut stream)?;
+    assert!(!res.contains("connection: close"));
+
+    stream.write_all(SIMPLE_GET_REQ)?;
+    let res = read_response(&mut stream)?;
+    assert!(res.contains("connection: close"));
+
+    // connection is closed by server after the last allowed request.
+    let mut chunk = [0; 128];
+    assert_eq!(stream.read(&mut chunk)?, 0);
+
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_connect_tunnel() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+
+    stream.write_all(b"CONNECT example.com:443 HTTP/1.1\r\nhost: example.com:443\r\n\r\n")?;
+
+    let mut buf = Vec::new();
+    let mut chunk = [0; 128];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk)?;
+        assert_ne!(n, 0);
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&buf).to_lowercase();
+    assert!(head.starts_with("http/1.1 200 ok"));
+    assert!(!head.contains("transfer-encoding") && !head.contains("content-length"));
+
+    // bytes sent through tunnel are echoed back as is.
+    for _ in 0..3 {
+        stream.write_all(b"tunnel")?;
+        let mut buf = [0; 6];
+        stream.read_exact(&mut buf)?;
+        assert_eq!(&buf, b"tunnel");
+    }
+
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_request_timing() -> Result<(), Error> {
+    static TIMINGS: Mutex<Vec<RequestTiming>> = Mutex::new(Vec::new());
+
+    async fn timing<S>(service: &S, req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error>
+    where
+        S: Service<Request<RequestExt<h1::RequestBody>>, Response = Response<ResponseBody>, Error = Error>,
+    {
+        let timing = req.extensions().get::<RequestTiming>().unwrap().clone();
+        let res = service.call(req).await?;
+        // handler phase ends when dispatcher receives response.
+        assert!(timing.handler().is_none());
+        TIMINGS.lock().unwrap().push(timing);
+        Ok(res)
+    }
+
+    let service = fn_service(handle)
+        .enclosed_fn(timing)
+        .enclosed(HttpServiceBuilder::h1().config(HttpServiceConfig::new().request_timing()));
+    let mut handle = test_server::<_, (xitca_io::net::TcpStream, SocketAddr)>(service)?;
+
+    let server_url = format!("http://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+
+    let mut res = c.get(&server_url).version(Version::HTTP_11).send().await?;
+    assert_eq!(res.string().await?, "GET Response");
+
+    let mut res = c
+        .post(&server_url)
+        .version(Version::HTTP_11)
+        .text("Hello,World!")
+        .send()
+        .await?;
+    assert_eq!(res.string().await?, "Hello,World!");
+
+    {
+        let timings = TIMINGS.lock().unwrap();
+        assert_eq!(timings.len(), 2);
+
+        for timing in timings.iter() {
+            assert!(timing.is_complete());
+            assert!(timing.handler().is_some());
+            assert!(timing.request_read().is_some());
+            assert!(timing.response_write().is_some());
+            assert_eq!(timing.response_body_bytes(), 12);
+        }
+
+        assert_eq!(timings[0].request_body_bytes(), 0);
+        assert_eq!(timings[1].request_body_bytes(), 12);
+    }
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_disconnect() -> Result<(), Error> {
+    static DISCONNECTED: AtomicBool = AtomicBool::new(false);
+
+    async fn handler(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+        let disconnect = req.extensions().get::<Disconnect>().unwrap().clone();
+        // request has no body. peer closing connection is observed while waiting for response.
+        disconnect.wait().await;
+        DISCONNECTED.store(true, Ordering::SeqCst);
+        Ok(Response::new(Bytes::new().into()))
+    }
+
+    let service =
+        fn_service(handler).enclosed(HttpServiceBuilder::h1().config(HttpServiceConfig::new().disconnect_signal()));
+    let mut handle = test_server::<_, (xitca_io::net::TcpStream, SocketAddr)>(service)?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!DISCONNECTED.load(Ordering::SeqCst));
+
+    drop(stream);
+
+    for _ in 0..100 {
+        if DISCONNECTED.load(Ordering::SeqCst) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(DISCONNECTED.load(Ordering::SeqCst));
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_informational() -> Result<(), Error> {
+    async fn handler(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+        let info = req.extensions().get::<Informational>().unwrap().clone();
+
+        let mut hints = Response::new(());
+        *hints.status_mut() = StatusCode::from_u16(103).unwrap();
+        hints
+            .headers_mut()
+            .insert(header::LINK, HeaderValue::from_static("</style.css>; rel=preload"));
+        assert!(info.send(hints));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let mut processing = Response::new(());
+        *processing.status_mut() = StatusCode::PROCESSING;
+        assert!(info.send(processing));
+
+        Ok(Response::new(Bytes::from("done").into()))
+    }
+
+    let service = fn_service(handler)
+        .enclosed(HttpServiceBuilder::h1().config(HttpServiceConfig::new().informational_responses()));
+    let mut handle = test_server::<_, (xitca_io::net::TcpStream, SocketAddr)>(service)?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")?;
+
+    let mut res = String::new();
+    stream.read_to_string(&mut res)?;
+
+    assert!(res.starts_with("HTTP/1.1 103 Early Hints\r\nlink: </style.css>; rel=preload\r\n\r\n"));
+    let res = &res[res.find("\r\n\r\n").unwrap() + 4..];
+    assert!(res.starts_with("HTTP/1.1 102 Processing\r\n\r\n"));
+    let res = &res[res.find("\r\n\r\n").unwrap() + 4..];
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(res.ends_with("done"));
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_incomplete_body() -> Result<(), Error> {
+    async fn handler(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+        let mut body = req.into_body();
+        let mut received = BytesMut::new();
+        let res = loop {
+            match body.next().await {
+                Some(Ok(bytes)) => received.extend_from_slice(&bytes),
+                Some(Err(e)) => break e,
+                None => panic!("request body must be incomplete"),
+            }
+        };
+
+        let incomplete = res.get_ref().and_then(|e| e.downcast_ref::<BodyIncomplete>()).unwrap();
+        assert_eq!(incomplete.received(), received.len() as u64);
+        assert_eq!(incomplete.expected(), Some(10));
+
+        Ok(Response::new(Bytes::from(received).into()))
+    }
+
+    let mut handle = test_h1_server(fn_service(handler))?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.write_all(b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 10\r\n\r\n996")?;
+    // client stops uploading in the middle of request body.
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut res = String::new();
+    stream.read_to_string(&mut res)?;
+
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(res.to_lowercase().contains("connection: close"));
+    assert!(res.ends_with("996"));
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_incomplete_body_disconnect() -> Result<(), Error> {
+    static DISCONNECTED: AtomicBool = AtomicBool::new(false);
+
+    async fn handler(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+        let disconnect = req.extensions().get::<Disconnect>().unwrap().clone();
+        // request body is not read. peer closing connection in the middle of body is observed by dispatcher.
+        disconnect.wait().await;
+        DISCONNECTED.store(true, Ordering::SeqCst);
+        Ok(Response::new(Bytes::new().into()))
+    }
+
+    let service =
+        fn_service(handler).enclosed(HttpServiceBuilder::h1().config(HttpServiceConfig::new().disconnect_signal()));
+    let mut handle = test_server::<_, (xitca_io::net::TcpStream, SocketAddr)>(service)?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.write_all(b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 10\r\n\r\n996")?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!DISCONNECTED.load(Ordering::SeqCst));
+
+    // client stops uploading in the middle of request body.
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    // dispatcher not notifying handler would block reading response.
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut res = String::new();
+    stream.read_to_string(&mut res)?;
+
+    assert!(DISCONNECTED.load(Ordering::SeqCst));
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_response_head_too_large() -> Result<(), Error> {
+    async fn handler(_: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+        let mut res = Response::new(ResponseBody::empty());
+        let value = HeaderValue::from_str(&"a".repeat(4096)).unwrap();
+        res.headers_mut().insert("x-large", value);
+        Ok(res)
+    }
+
+    let mut handle = test_h1_server(fn_service(handler))?;
+
+    let server_url = format!("http://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+    let res = c.get(&server_url).version(Version::HTTP_11).send().await?;
+    assert_eq!(res.status().as_u16(), 200);
+
+    let c = Client::builder().set_max_response_head_size(1024).finish();
+    assert!(c.get(&server_url).version(Version::HTTP_11).send().await.is_err());
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
 async fn handle(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/") | (&Method::HEAD, "/") => Ok(Response::new(Bytes::from("GET Response").into())),
@@ -282,6 +616,8 @@ async fn handle(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<Re
             res.headers_mut().insert(CONNECTION, HeaderValue::from_static("close"));
             Ok(res)
         }
+        // echo bytes from client through the tunnel.
+        (&Method::CONNECT, _) => Ok(Response::new(ResponseBody::box_stream(req.into_body()))),
         _ => todo!(),
     }
 }
@@ -34,6 +34,32 @@ async fn h2_get() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h2_keep_alive_ping() -> Result<(), Error> {
+    let mut handle = test_h2_server(fn_service(handle))?;
+
+    let server_url = format!("https://{}/", handle.ip_port_string());
+
+    let c = Client::builder()
+        .set_h2_keep_alive(Duration::from_millis(50), Duration::from_secs(1))
+        .finish();
+
+    for _ in 0..3 {
+        let mut res = c.get(&server_url).version(Version::HTTP_2).send().await?;
+        assert_eq!(res.status().as_u16(), 200);
+        let body = res.string().await?;
+        assert_eq!("GET Response", body);
+        // idle connection is pinged and kept alive.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn h2_post() -> Result<(), Error> {
     let mut handle = test_h2_server(fn_service(handle))?;
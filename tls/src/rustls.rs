@@ -9,6 +9,10 @@ use std::io;
 
 pub use rustls_crate::*;
 
+mod reload;
+
+pub use reload::TlsConfigReloader;
+
 use xitca_io::io::{AsyncIo, Interest, Ready};
 
 /// A stream managed by `rustls` crate for tls read/write.
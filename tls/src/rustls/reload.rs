@@ -0,0 +1,375 @@
+//! hot reloadable server certificates with SNI based selection.
+//!
+//! # Examples
+//! ```no_run
+//! # fn config(provider: std::sync::Arc<xitca_tls::rustls::crypto::CryptoProvider>) -> std::io::Result<()> {
+//! use std::time::Duration;
+//!
+//! use xitca_tls::rustls::TlsConfigReloader;
+//!
+//! let reloader = TlsConfigReloader::new(provider);
+//!
+//! // certificate served when client does not send SNI or no certificate matches it.
+//! reloader.load_default_pem("./cert.pem", "./key.pem")?;
+//! // certificate served for given host name. wildcard host name like "*.example.com" is supported.
+//! reloader.load_sni_pem("example.com", "./example.com/cert.pem", "./example.com/key.pem")?;
+//!
+//! // server config use reloader as certificate resolver.
+//! let config = reloader.server_config().map_err(std::io::Error::other)?;
+//!
+//! // check certificate files for change every minute and reload them when modified.
+//! reloader.watch(Duration::from_secs(60));
+//!
+//! // certificates can also be reloaded manually. e.g. from an admin api endpoint.
+//! reloader.reload()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use core::{fmt, time::Duration};
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock, Weak},
+    thread,
+    time::SystemTime,
+};
+
+use rustls_crate::{
+    crypto::CryptoProvider,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+    Error, ServerConfig,
+};
+
+/// Handle of hot reloadable certificate store.
+///
+/// Cheap to clone and all clones share the same certificates. It can be used as [ResolvesServerCert] of
+/// [ServerConfig] through [TlsConfigReloader::resolver] and certificates changes take effect on new
+/// handshakes immediately.
+#[derive(Clone)]
+pub struct TlsConfigReloader {
+    inner: Arc<Inner>,
+}
+
+impl fmt::Debug for TlsConfigReloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+struct Inner {
+    provider: Arc<CryptoProvider>,
+    certs: RwLock<Certs>,
+    sources: Mutex<Vec<Source>>,
+}
+
+impl fmt::Debug for Inner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsConfigReloader").finish_non_exhaustive()
+    }
+}
+
+#[derive(Default)]
+struct Certs {
+    default: Option<Arc<CertifiedKey>>,
+    sni: HashMap<String, Arc<CertifiedKey>>,
+}
+
+impl Certs {
+    fn resolve(&self, server_name: Option<&str>) -> Option<Arc<CertifiedKey>> {
+        server_name
+            .and_then(|name| {
+                let name = name.to_ascii_lowercase();
+                self.sni.get(&name).or_else(|| {
+                    // fallback to wildcard certificate of parent domain.
+                    let (_, parent) = name.split_once('.')?;
+                    self.sni.get(&format!("*.{parent}"))
+                })
+            })
+            .or(self.default.as_ref())
+            .cloned()
+    }
+
+    fn set(&mut self, sni: Option<String>, key: Arc<CertifiedKey>) {
+        match sni {
+            Some(sni) => {
+                self.sni.insert(sni, key);
+            }
+            None => self.default = Some(key),
+        }
+    }
+}
+
+struct Source {
+    sni: Option<String>,
+    cert: PathBuf,
+    key: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl Source {
+    fn modified(&self) -> Option<SystemTime> {
+        let cert = fs::metadata(&self.cert).and_then(|m| m.modified()).ok()?;
+        let key = fs::metadata(&self.key).and_then(|m| m.modified()).ok()?;
+        Some(cert.max(key))
+    }
+}
+
+impl TlsConfigReloader {
+    /// Construct an empty certificate store. Private keys are loaded with given crypto provider.
+    pub fn new(provider: Arc<CryptoProvider>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                provider,
+                certs: RwLock::new(Certs::default()),
+                sources: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Construct [ServerConfig] with no client auth, safe default protocol versions and current store as
+    /// certificate resolver.
+    pub fn server_config(&self) -> Result<ServerConfig, Error> {
+        ServerConfig::builder_with_provider(self.inner.provider.clone())
+            .with_safe_default_protocol_versions()
+            .map(|builder| builder.with_no_client_auth().with_cert_resolver(self.resolver()))
+    }
+
+    /// Certificate resolver backed by current store.
+    pub fn resolver(&self) -> Arc<dyn ResolvesServerCert> {
+        self.inner.clone()
+    }
+
+    /// Set certificate served when client does not send SNI or no certificate matches it.
+    pub fn set_default(&self, key: CertifiedKey) {
+        self.inner.certs.write().unwrap().set(None, Arc::new(key));
+    }
+
+    /// Set certificate served for given SNI host name.
+    ///
+    /// Host name is case insensitive and wildcard name in the form of `*.example.com` matches one level of
+    /// sub domain of `example.com`.
+    pub fn insert(&self, sni: impl Into<String>, key: CertifiedKey) {
+        let sni = sni.into().to_ascii_lowercase();
+        self.inner.certs.write().unwrap().set(Some(sni), Arc::new(key));
+    }
+
+    /// Remove certificate served for given SNI host name.
+    pub fn remove(&self, sni: &str) -> Option<Arc<CertifiedKey>> {
+        self.inner.certs.write().unwrap().sni.remove(&sni.to_ascii_lowercase())
+    }
+
+    /// Load PEM encoded certificate chain and private key files as default certificate.
+    ///
+    /// The files are remembered and loaded again on [TlsConfigReloader::reload].
+    pub fn load_default_pem(&self, cert: impl AsRef<Path>, key: impl AsRef<Path>) -> io::Result<()> {
+        self.load_pem(None, cert.as_ref(), key.as_ref())
+    }
+
+    /// Load PEM encoded certificate chain and private key files as certificate for given SNI host name.
+    ///
+    /// The files are remembered and loaded again on [TlsConfigReloader::reload].
+    pub fn load_sni_pem(
+        &self,
+        sni: impl Into<String>,
+        cert: impl AsRef<Path>,
+        key: impl AsRef<Path>,
+    ) -> io::Result<()> {
+        let sni = sni.into().to_ascii_lowercase();
+        self.load_pem(Some(sni), cert.as_ref(), key.as_ref())
+    }
+
+    fn load_pem(&self, sni: Option<String>, cert: &Path, key: &Path) -> io::Result<()> {
+        let mut source = Source {
+            sni,
+            cert: cert.to_path_buf(),
+            key: key.to_path_buf(),
+            modified: None,
+        };
+        source.modified = source.modified();
+
+        let certified = self.inner.load(&source)?;
+        self.inner.certs.write().unwrap().set(source.sni.clone(), certified);
+
+        let mut sources = self.inner.sources.lock().unwrap();
+        sources.retain(|s| s.sni != source.sni);
+        sources.push(source);
+
+        Ok(())
+    }
+
+    /// Load all certificate files again from disk.
+    ///
+    /// Reload is all or nothing. When any of the files fail to load all previous certificates are kept.
+    pub fn reload(&self) -> io::Result<()> {
+        self.inner.reload(|_| true).map(|_| ())
+    }
+
+    /// Load certificate files again from disk when their modified time changed since last load.
+    ///
+    /// Returns `true` when any certificate is reloaded.
+    pub fn reload_if_changed(&self) -> io::Result<bool> {
+        self.inner.reload(|source| source.modified() != source.modified)
+    }
+
+    /// Spawn a background thread checking certificate files for change in given interval and reload them
+    /// when modified.
+    ///
+    /// Failed reload keeps previous certificates and would be retried on next interval. The thread exits
+    /// when all handles of current store are dropped.
+    pub fn watch(&self, interval: Duration) -> thread::JoinHandle<()> {
+        let inner = Arc::downgrade(&self.inner);
+        thread::Builder::new()
+            .name(String::from("xitca-tls-reloader"))
+            .spawn(move || watch(inner, interval))
+            .expect("failed to spawn certificate reloader thread")
+    }
+}
+
+fn watch(inner: Weak<Inner>, interval: Duration) {
+    loop {
+        thread::sleep(interval);
+        match inner.upgrade() {
+            Some(inner) => {
+                let _ = inner.reload(|source| source.modified() != source.modified);
+            }
+            None => return,
+        }
+    }
+}
+
+impl Inner {
+    fn load(&self, source: &Source) -> io::Result<Arc<CertifiedKey>> {
+        let certs = CertificateDer::pem_file_iter(&source.cert)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(io::Error::other)?;
+        let key = PrivateKeyDer::from_pem_file(&source.key).map_err(io::Error::other)?;
+        let certified = CertifiedKey::from_der(certs, key, &self.provider).map_err(io::Error::other)?;
+        Ok(Arc::new(certified))
+    }
+
+    fn reload(&self, filter: impl Fn(&Source) -> bool) -> io::Result<bool> {
+        let mut sources = self.sources.lock().unwrap();
+
+        let mut loaded = Vec::new();
+        for (idx, source) in sources.iter().enumerate().filter(|(_, source)| filter(source)) {
+            // take modified time before loading so change happen during loading would be picked up next time.
+            let modified = source.modified();
+            loaded.push((idx, modified, self.load(source)?));
+        }
+
+        if loaded.is_empty() {
+            return Ok(false);
+        }
+
+        let mut certs = self.certs.write().unwrap();
+        for (idx, modified, certified) in loaded {
+            let source = &mut sources[idx];
+            source.modified = modified;
+            certs.set(source.sni.clone(), certified);
+        }
+
+        Ok(true)
+    }
+}
+
+impl ResolvesServerCert for Inner {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        self.certs.read().unwrap().resolve(client_hello.server_name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rustls_crate::{
+        sign::{Signer, SigningKey},
+        SignatureAlgorithm, SignatureScheme,
+    };
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct NoKey;
+
+    impl SigningKey for NoKey {
+        fn choose_scheme(&self, _: &[SignatureScheme]) -> Option<Box<dyn Signer>> {
+            None
+        }
+
+        fn algorithm(&self) -> SignatureAlgorithm {
+            SignatureAlgorithm::ED25519
+        }
+    }
+
+    fn key(tag: u8) -> Arc<CertifiedKey> {
+        Arc::new(CertifiedKey::new(
+            vec![CertificateDer::from(vec![tag])],
+            Arc::new(NoKey),
+        ))
+    }
+
+    fn tag(key: Option<Arc<CertifiedKey>>) -> Option<u8> {
+        key.map(|key| key.cert[0].as_ref()[0])
+    }
+
+    #[test]
+    fn resolve() {
+        let mut certs = Certs::default();
+        assert!(certs.resolve(Some("example.com")).is_none());
+
+        certs.set(None, key(0));
+        certs.set(Some(String::from("example.com")), key(1));
+        certs.set(Some(String::from("*.example.com")), key(2));
+
+        assert_eq!(tag(certs.resolve(None)), Some(0));
+        assert_eq!(tag(certs.resolve(Some("foo.com"))), Some(0));
+        assert_eq!(tag(certs.resolve(Some("Example.COM"))), Some(1));
+        assert_eq!(tag(certs.resolve(Some("www.example.com"))), Some(2));
+        assert_eq!(tag(certs.resolve(Some("a.www.example.com"))), Some(0));
+
+        certs.set(None, key(3));
+        assert_eq!(tag(certs.resolve(Some("foo.com"))), Some(3));
+    }
+
+    #[test]
+    fn load_missing_file() {
+        let reloader = TlsConfigReloader::new(Arc::new(CryptoProvider {
+            cipher_suites: Vec::new(),
+            kx_groups: Vec::new(),
+            signature_verification_algorithms: rustls_crate::crypto::WebPkiSupportedAlgorithms {
+                all: &[],
+                mapping: &[],
+            },
+            secure_random: &NoRandom,
+            key_provider: &NoKeyProvider,
+        }));
+
+        assert!(reloader
+            .load_default_pem("/not/exist/cert.pem", "/not/exist/key.pem")
+            .is_err());
+        assert!(!reloader.reload_if_changed().unwrap());
+        assert!(reloader.inner.certs.read().unwrap().resolve(None).is_none());
+    }
+
+    #[derive(Debug)]
+    struct NoRandom;
+
+    impl rustls_crate::crypto::SecureRandom for NoRandom {
+        fn fill(&self, _: &mut [u8]) -> Result<(), rustls_crate::crypto::GetRandomFailed> {
+            Err(rustls_crate::crypto::GetRandomFailed)
+        }
+    }
+
+    #[derive(Debug)]
+    struct NoKeyProvider;
+
+    impl rustls_crate::crypto::KeyProvider for NoKeyProvider {
+        fn load_private_key(&self, _: PrivateKeyDer<'static>) -> Result<Arc<dyn SigningKey>, Error> {
+            Ok(Arc::new(NoKey))
+        }
+    }
+}
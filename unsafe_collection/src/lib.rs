@@ -6,6 +6,7 @@
 pub mod bound_queue;
 pub mod fake;
 pub mod futures;
+pub mod mpsc;
 pub mod no_hash;
 pub mod small_str;
 pub mod uninit;
@@ -0,0 +1,431 @@
+//! Bounded lock-free multi-producer single-consumer ring buffer with async receiver.
+//!
+//! Producers push items into a fixed size ring without taking lock. The receiver is woken at most once
+//! per batch of items: only the first push after the receiver parked would wake it and following pushes
+//! would be observed when the receiver drains the ring.
+//!
+//! This is a standalone primitive for handing items between threads with bounded memory. It's not used by
+//! `xitca-server` where every worker accepts connections from shared listeners directly.
+
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    future::poll_fn,
+    mem::MaybeUninit,
+    sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+use std::sync::{Arc, Mutex};
+
+/// construct a bounded channel with given capacity. capacity would be rounded up to the next power of two.
+///
+/// # Panics
+/// panic when capacity is zero.
+pub fn channel<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(cap > 0, "channel capacity can not be zero");
+
+    let cap = cap.next_power_of_two();
+    let slots = (0..cap)
+        .map(|i| Slot {
+            seq: AtomicUsize::new(i),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        })
+        .collect();
+
+    let inner = Arc::new(Inner {
+        slots,
+        mask: cap - 1,
+        tail: AtomicUsize::new(0),
+        head: AtomicUsize::new(0),
+        senders: AtomicUsize::new(1),
+        rx_closed: AtomicBool::new(false),
+        parked: AtomicBool::new(false),
+        waker: Mutex::new(None),
+    });
+
+    (Sender { inner: inner.clone() }, Receiver { inner })
+}
+
+struct Slot<T> {
+    // sequence number of slot. it equals to the position when slot is ready for write and position + 1
+    // when slot is ready for read.
+    seq: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+struct Inner<T> {
+    slots: Box<[Slot<T>]>,
+    mask: usize,
+    tail: AtomicUsize,
+    // only mutated by receiver.
+    head: AtomicUsize,
+    senders: AtomicUsize,
+    rx_closed: AtomicBool,
+    parked: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+// SAFETY:
+// slot value is only accessed by the thread claimed it's position through sequence number. value is moved
+// between threads so T must be Send.
+unsafe impl<T: Send> Send for Inner<T> {}
+unsafe impl<T: Send> Sync for Inner<T> {}
+
+impl<T> Inner<T> {
+    fn push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[pos & self.mask];
+            let seq = slot.seq.load(Ordering::Acquire);
+
+            match seq.wrapping_sub(pos) as isize {
+                0 => match self.tail.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY:
+                        // position is claimed exclusively by successful cas of tail.
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.seq.store(pos.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(p) => pos = p,
+                },
+                // slot is not read yet. the ring is full.
+                diff if diff < 0 => return Err(value),
+                _ => pos = self.tail.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    // SAFETY:
+    // caller must be the only consumer of ring.
+    unsafe fn pop(&self) -> Option<T> {
+        let pos = self.head.load(Ordering::Relaxed);
+        let slot = &self.slots[pos & self.mask];
+
+        if slot.seq.load(Ordering::Acquire) != pos.wrapping_add(1) {
+            return None;
+        }
+
+        let value = (*slot.value.get()).assume_init_read();
+        slot.seq.store(pos.wrapping_add(self.mask + 1), Ordering::Release);
+        self.head.store(pos.wrapping_add(1), Ordering::Relaxed);
+        Some(value)
+    }
+
+    fn wake(&self) {
+        // pair with fence in Receiver::poll_recv. either producer observe parked receiver or receiver observe
+        // pushed item.
+        fence(Ordering::SeqCst);
+        if self.parked.swap(false, Ordering::AcqRel) {
+            if let Some(waker) = self.waker.lock().unwrap().take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        // SAFETY:
+        // exclusive access through &mut self.
+        while unsafe { self.pop() }.is_some() {}
+    }
+}
+
+/// sending half of channel. can be cloned to have multiple producers.
+pub struct Sender<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Sender<T> {
+    /// try to push item into channel.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if self.inner.rx_closed.load(Ordering::Acquire) {
+            return Err(TrySendError::Closed(value));
+        }
+        self.inner.push(value).map_err(TrySendError::Full)?;
+        self.inner.wake();
+        Ok(())
+    }
+
+    /// check if the receiving half of channel is dropped.
+    pub fn is_closed(&self) -> bool {
+        self.inner.rx_closed.load(Ordering::Acquire)
+    }
+
+    /// capacity of channel.
+    pub fn capacity(&self) -> usize {
+        self.inner.slots.len()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.inner.senders.fetch_add(1, Ordering::Relaxed);
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.inner.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.inner.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+/// receiving half of channel.
+pub struct Receiver<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// try to pop item from channel.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        // SAFETY:
+        // Receiver is not Clone and pop is only called with &mut self.
+        match unsafe { self.inner.pop() } {
+            Some(value) => Ok(value),
+            None if self.inner.senders.load(Ordering::Acquire) == 0 => {
+                // last sender may push right before dropping. check again after observing the drop.
+                unsafe { self.inner.pop() }.ok_or(TryRecvError::Disconnected)
+            }
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// poll for next item. `Poll::Ready(None)` is returned when all senders are dropped and channel is
+    /// drained.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(value) => return Poll::Ready(Some(value)),
+            Err(TryRecvError::Disconnected) => return Poll::Ready(None),
+            Err(TryRecvError::Empty) => {}
+        }
+
+        {
+            let mut waker = self.inner.waker.lock().unwrap();
+            match *waker {
+                Some(ref w) if w.will_wake(cx.waker()) => {}
+                _ => *waker = Some(cx.waker().clone()),
+            }
+        }
+        self.inner.parked.store(true, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+
+        // producers may push between previous try_recv and parking. check again to avoid lost wake up.
+        match self.try_recv() {
+            Ok(value) => {
+                self.inner.parked.store(false, Ordering::Relaxed);
+                Poll::Ready(Some(value))
+            }
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+
+    /// receive next item. `None` is returned when all senders are dropped and channel is drained.
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// receive all items currently in channel and extend them to given collection. wait for at least one
+    /// item when channel is empty. return the number of items received and zero means channel is closed.
+    pub async fn recv_batch<E>(&mut self, buf: &mut E) -> usize
+    where
+        E: Extend<T>,
+    {
+        let Some(first) = self.recv().await else {
+            return 0;
+        };
+        let mut n = 1;
+        buf.extend(Some(first));
+        while let Ok(value) = self.try_recv() {
+            buf.extend(Some(value));
+            n += 1;
+        }
+        n
+    }
+
+    /// capacity of channel.
+    pub fn capacity(&self) -> usize {
+        self.inner.slots.len()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.inner.rx_closed.store(true, Ordering::Release);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+/// error type for [Sender::try_send].
+pub enum TrySendError<T> {
+    /// channel is full. item is returned.
+    Full(T),
+    /// receiver is dropped. item is returned.
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    /// take the item failed to send.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(t) | Self::Closed(t) => t,
+        }
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("TrySendError::Full(..)"),
+            Self::Closed(_) => f.write_str("TrySendError::Closed(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("channel is full"),
+            Self::Closed(_) => f.write_str("channel is closed"),
+        }
+    }
+}
+
+impl<T> std::error::Error for TrySendError<T> {}
+
+/// error type for [Receiver::try_recv].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// channel is empty.
+    Empty,
+    /// all senders are dropped and channel is empty.
+    Disconnected,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("channel is empty"),
+            Self::Disconnected => f.write_str("channel is disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+#[cfg(test)]
+mod test {
+    use std::{rc::Rc, thread};
+
+    use crate::futures::NowOrPanic;
+
+    use super::*;
+
+    #[test]
+    fn bounded() {
+        let (tx, mut rx) = channel(3);
+        assert_eq!(tx.capacity(), 4);
+
+        for i in 0..4 {
+            tx.try_send(i).unwrap();
+        }
+        assert!(matches!(tx.try_send(4), Err(TrySendError::Full(4))));
+
+        for i in 0..4 {
+            assert_eq!(rx.try_recv(), Ok(i));
+        }
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        tx.try_send(5).unwrap();
+        drop(tx);
+        assert_eq!(rx.recv().now_or_panic(), Some(5));
+        assert_eq!(rx.recv().now_or_panic(), None);
+    }
+
+    #[test]
+    fn closed() {
+        let (tx, rx) = channel(1);
+        drop(rx);
+        assert!(tx.is_closed());
+        assert!(matches!(tx.try_send(1), Err(TrySendError::Closed(1))));
+    }
+
+    #[test]
+    fn drop_remaining() {
+        let item = Rc::new(());
+        {
+            let (tx, rx) = channel(2);
+            tx.try_send(item.clone()).unwrap();
+            tx.try_send(item.clone()).unwrap();
+            drop(rx);
+        }
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn multi_producer() {
+        const PRODUCERS: usize = 4;
+        const ITEMS: usize = 10_000;
+
+        let (tx, mut rx) = channel(64);
+
+        let handles = (0..PRODUCERS)
+            .map(|_| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in 0..ITEMS {
+                        let mut item = i;
+                        while let Err(e) = tx.try_send(item) {
+                            item = e.into_inner();
+                            thread::yield_now();
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(tx);
+
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let (count, sum) = rt.block_on(async {
+            let mut buf = Vec::new();
+            let mut count = 0;
+            loop {
+                match rx.recv_batch(&mut buf).await {
+                    0 => break,
+                    n => count += n,
+                }
+            }
+            (count, buf.iter().sum::<usize>())
+        });
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(count, PRODUCERS * ITEMS);
+        assert_eq!(sum, PRODUCERS * (ITEMS - 1) * ITEMS / 2);
+    }
+}
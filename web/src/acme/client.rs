@@ -0,0 +1,68 @@
+//! http client for talking to ACME server.
+
+use std::{io, sync::Arc};
+
+use xitca_client::Client as HttpClient;
+use xitca_tls::rustls::{crypto::ring, ClientConfig, RootCertStore};
+
+use crate::http::{
+    header::{HeaderValue, CONTENT_TYPE},
+    HeaderMap, Method, StatusCode,
+};
+
+pub(super) struct Client(HttpClient);
+
+pub(super) struct Response {
+    pub(super) status: StatusCode,
+    pub(super) headers: HeaderMap,
+    pub(super) body: Vec<u8>,
+}
+
+impl Response {
+    pub(super) fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    pub(super) fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+}
+
+impl Client {
+    pub(super) fn new() -> io::Result<Self> {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+
+        let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(io::Error::other)?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        Ok(Self(HttpClient::builder().rustls_with_config(config).finish()))
+    }
+
+    pub(super) async fn request(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<(&'static str, Vec<u8>)>,
+    ) -> io::Result<Response> {
+        let req = self.0.get(url).method(method);
+
+        let req = match body {
+            Some((content_type, body)) => req
+                .header(CONTENT_TYPE, HeaderValue::from_static(content_type))
+                .body(body),
+            None => req,
+        };
+
+        let res = req.send().await.map_err(io::Error::other)?;
+        let status = res.status();
+        let headers = res.headers().clone();
+        let body = res.body().await.map_err(io::Error::other)?;
+
+        Ok(Response { status, headers, body })
+    }
+}
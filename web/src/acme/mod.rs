@@ -0,0 +1,708 @@
+//! automatic certificate provisioning through ACME protocol (RFC 8555) with HTTP-01 challenge.
+//!
+//! Certificates are ordered from ACME server (Let's Encrypt by default), cached on disk and loaded into
+//! hot reloadable rustls certificate store. Renewal happens in background and new certificates take
+//! effect on new tls handshakes without server restart.
+//!
+//! # Examples
+//! ```no_run
+//! use xitca_web::{
+//!     acme::{Acme, AcmeConfig},
+//!     handler::handler_service,
+//!     App,
+//! };
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let acme = Acme::new(
+//!     AcmeConfig::new(["example.com", "www.example.com"])
+//!         .contact("mailto:admin@example.com")
+//!         .cache_dir("./acme"),
+//! );
+//!
+//! // server config resolve certificates from acme managed certificate store.
+//! let config = acme.server_config()?;
+//!
+//! // order/renew certificates in background thread.
+//! acme.clone().spawn();
+//!
+//! App::new()
+//!     .at("/", handler_service(|| async { "hello,world!" }))
+//!     // serve HTTP-01 challenge under /.well-known/acme-challenge/ path.
+//!     .at("/", acme.challenge())
+//!     .serve()
+//!     // ACME server validates challenge through plain http on port 80.
+//!     .bind("0.0.0.0:80")?
+//!     .bind_rustls("0.0.0.0:443", config)?
+//!     .run()
+//!     .wait()
+//! # }
+//! ```
+
+mod client;
+
+use core::{convert::Infallible, time::Duration};
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::SystemTime,
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::{
+    digest,
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use serde_json::{json, Value};
+use xitca_http::util::service::router::{PathGen, RouteGen};
+use xitca_tls::rustls::{crypto::ring::default_provider, ServerConfig};
+
+use crate::{http::Method, service::Service};
+
+use self::client::{Client, Response};
+
+pub use xitca_tls::rustls::TlsConfigReloader;
+
+/// directory url of Let's Encrypt production environment.
+pub const LETS_ENCRYPT_PRODUCTION: &str = "https://acme-v02.api.letsencrypt.org/directory";
+
+/// directory url of Let's Encrypt staging environment. certificates issued by it are not trusted and it
+/// should be used for testing.
+pub const LETS_ENCRYPT_STAGING: &str = "https://acme-staging-v02.api.letsencrypt.org/directory";
+
+const CHALLENGE_PATH: &str = "/.well-known/acme-challenge";
+
+/// configuration for ordering certificate from ACME server.
+#[derive(Clone, Debug)]
+pub struct AcmeConfig {
+    domains: Vec<String>,
+    contact: Vec<String>,
+    directory: String,
+    cache_dir: PathBuf,
+    renew_after: Duration,
+    retry_interval: Duration,
+}
+
+impl AcmeConfig {
+    /// construct a new config ordering one certificate covering all given domains.
+    ///
+    /// # Panics
+    /// when no domain is given.
+    pub fn new<I>(domains: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let domains = domains
+            .into_iter()
+            .map(|domain| domain.into().to_ascii_lowercase())
+            .collect::<Vec<_>>();
+        assert!(!domains.is_empty(), "AcmeConfig must contain at least one domain");
+
+        Self {
+            domains,
+            contact: Vec::new(),
+            directory: String::from(LETS_ENCRYPT_PRODUCTION),
+            cache_dir: PathBuf::from("./acme"),
+            renew_after: Duration::from_secs(60 * 24 * 60 * 60),
+            retry_interval: Duration::from_secs(60 * 60),
+        }
+    }
+
+    /// add contact url of ACME account. e.g. `mailto:admin@example.com`
+    pub fn contact(mut self, contact: impl Into<String>) -> Self {
+        self.contact.push(contact.into());
+        self
+    }
+
+    /// set directory url of ACME server.
+    ///
+    /// Default to [LETS_ENCRYPT_PRODUCTION].
+    pub fn directory(mut self, url: impl Into<String>) -> Self {
+        self.directory = url.into();
+        self
+    }
+
+    /// set directory where account key and certificates are stored.
+    ///
+    /// Default to `./acme`.
+    pub fn cache_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_dir = path.into();
+        self
+    }
+
+    /// set duration after issuing when certificate would be renewed.
+    ///
+    /// Default to 60 days which is 30 days before expiry of Let's Encrypt certificate.
+    pub fn renew_after(mut self, dur: Duration) -> Self {
+        self.renew_after = dur;
+        self
+    }
+
+    /// set duration to wait before retrying failed order.
+    ///
+    /// Default to 1 hour.
+    pub fn retry_interval(mut self, dur: Duration) -> Self {
+        self.retry_interval = dur;
+        self
+    }
+
+    fn cert_dir(&self) -> PathBuf {
+        self.cache_dir.join(&self.domains[0])
+    }
+
+    fn cert_path(&self) -> PathBuf {
+        self.cert_dir().join("cert.pem")
+    }
+
+    fn key_path(&self) -> PathBuf {
+        self.cert_dir().join("key.pem")
+    }
+
+    fn account_key_path(&self) -> PathBuf {
+        self.cache_dir.join("account.key")
+    }
+}
+
+/// ACME certificate manager. cheap to clone and all clones share the same state.
+#[derive(Clone)]
+pub struct Acme {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    config: AcmeConfig,
+    reloader: TlsConfigReloader,
+    challenges: Challenges,
+}
+
+type Challenges = Arc<Mutex<HashMap<String, String>>>;
+
+impl Acme {
+    /// construct a new manager with a new certificate store.
+    pub fn new(config: AcmeConfig) -> Self {
+        Self::with_reloader(config, TlsConfigReloader::new(Arc::new(default_provider())))
+    }
+
+    /// construct a new manager loading certificates into given certificate store.
+    ///
+    /// Certificate is inserted for every domain of [AcmeConfig] as SNI host name. Other certificates of
+    /// the store are not affected.
+    pub fn with_reloader(config: AcmeConfig, reloader: TlsConfigReloader) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                config,
+                reloader,
+                challenges: Default::default(),
+            }),
+        }
+    }
+
+    /// certificate store managed by current manager.
+    pub fn reloader(&self) -> &TlsConfigReloader {
+        &self.inner.reloader
+    }
+
+    /// construct rustls [ServerConfig] resolving certificates from certificate store.
+    pub fn server_config(&self) -> io::Result<ServerConfig> {
+        self.inner.reloader.server_config().map_err(io::Error::other)
+    }
+
+    /// service answering HTTP-01 challenge.
+    ///
+    /// It should be mounted on root path of plain http server listening on port 80 where challenge
+    /// is served under `/.well-known/acme-challenge/` path.
+    pub fn challenge(&self) -> AcmeChallenge {
+        AcmeChallenge {
+            challenges: self.inner.challenges.clone(),
+        }
+    }
+
+    /// load certificate from cache or order a new one when it's missing or due for renewal.
+    ///
+    /// Challenge service from [Acme::challenge] must be reachable by ACME server when ordering.
+    pub async fn provision(&self) -> io::Result<()> {
+        let config = &self.inner.config;
+
+        if !matches!(cert_age(config), Some(age) if age < config.renew_after) {
+            let (cert, key) = self.order().await?;
+            write_file(&config.key_path(), key.as_bytes(), true)?;
+            write_file(&config.cert_path(), cert.as_bytes(), false)?;
+        }
+
+        config.domains.iter().try_for_each(|domain| {
+            self.inner
+                .reloader
+                .load_sni_pem(domain, config.cert_path(), config.key_path())
+        })
+    }
+
+    /// provision certificate and renew it when it's due for renewal. this future never resolves.
+    pub async fn run(self) {
+        let config = &self.inner.config;
+        loop {
+            let wait = match self.provision().await {
+                Ok(_) => {
+                    let age = cert_age(config).unwrap_or_default();
+                    config.renew_after.saturating_sub(age).max(Duration::from_secs(60))
+                }
+                Err(e) => {
+                    tracing::error!("ACME certificate provision for {:?} failed: {e}", config.domains);
+                    config.retry_interval
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// spawn a background thread running [Acme::run].
+    pub fn spawn(self) -> thread::JoinHandle<()> {
+        thread::Builder::new()
+            .name(String::from("xitca-web-acme"))
+            .spawn(move || {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("failed to build ACME runtime")
+                    .block_on(self.run())
+            })
+            .expect("failed to spawn ACME thread")
+    }
+
+    async fn order(&self) -> io::Result<(String, String)> {
+        let config = &self.inner.config;
+
+        let key = account_key(&config.account_key_path())?;
+        let mut session = Session::new(Client::new()?, key, &config.directory).await?;
+
+        let mut account = json!({ "termsOfServiceAgreed": true });
+        if !config.contact.is_empty() {
+            account["contact"] = json!(config.contact);
+        }
+        let res = session.post(&session.directory("newAccount")?, Some(account)).await?;
+        session.kid = Some(location(&res)?);
+
+        let identifiers = config
+            .domains
+            .iter()
+            .map(|domain| json!({ "type": "dns", "value": domain }))
+            .collect::<Vec<_>>();
+        let res = session
+            .post(
+                &session.directory("newOrder")?,
+                Some(json!({ "identifiers": identifiers })),
+            )
+            .await?;
+        let order_url = location(&res)?;
+        let order = to_json(&res)?;
+
+        for auth_url in order["authorizations"].as_array().into_iter().flatten() {
+            let auth_url = as_str(auth_url, "authorization")?;
+            self.authorize(&mut session, auth_url).await?;
+        }
+
+        let key = rcgen::KeyPair::generate().map_err(io::Error::other)?;
+        let csr = rcgen::CertificateParams::new(config.domains.clone())
+            .and_then(|params| params.serialize_request(&key))
+            .map_err(io::Error::other)?;
+
+        let finalize = as_str(&order["finalize"], "finalize")?;
+        session
+            .post(finalize, Some(json!({ "csr": URL_SAFE_NO_PAD.encode(csr.der()) })))
+            .await?;
+
+        let order = session.poll(&order_url, "order").await?;
+        let cert_url = as_str(&order["certificate"], "certificate")?;
+        let res = session.post(cert_url, None).await?;
+        let cert = String::from_utf8(res.body).map_err(io::Error::other)?;
+
+        Ok((cert, key.serialize_pem()))
+    }
+
+    async fn authorize(&self, session: &mut Session, url: &str) -> io::Result<()> {
+        let auth = to_json(&session.post(url, None).await?)?;
+
+        if auth["status"] == "valid" {
+            return Ok(());
+        }
+
+        let challenge = auth["challenges"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|challenge| challenge["type"] == "http-01")
+            .ok_or_else(|| io::Error::other("ACME server does not offer http-01 challenge"))?;
+
+        let token = as_str(&challenge["token"], "token")?.to_owned();
+        let key_auth = format!("{token}.{}", session.thumbprint());
+        self.inner.challenges.lock().unwrap().insert(token.clone(), key_auth);
+
+        let res = async {
+            session
+                .post(as_str(&challenge["url"], "challenge url")?, Some(json!({})))
+                .await?;
+            session.poll(url, "authorization").await
+        }
+        .await;
+
+        self.inner.challenges.lock().unwrap().remove(&token);
+
+        res.map(|_| ())
+    }
+}
+
+/// service type answering ACME HTTP-01 challenge. see [Acme::challenge] for detail.
+pub struct AcmeChallenge {
+    challenges: Challenges,
+}
+
+impl PathGen for AcmeChallenge {
+    fn path_gen(&mut self, prefix: &str) -> String {
+        let mut prefix = String::from(prefix);
+        if prefix.ends_with('/') {
+            prefix.pop();
+        }
+
+        prefix.push_str(CHALLENGE_PATH);
+        prefix.push_str("/:token");
+
+        prefix
+    }
+}
+
+impl RouteGen for AcmeChallenge {
+    type Route<R> = R;
+
+    fn route_gen<R>(route: R) -> Self::Route<R> {
+        route
+    }
+}
+
+impl Service for AcmeChallenge {
+    type Response = service::AcmeChallengeService;
+    type Error = Infallible;
+
+    async fn call(&self, _: ()) -> Result<Self::Response, Self::Error> {
+        Ok(service::AcmeChallengeService(self.challenges.clone()))
+    }
+}
+
+mod service {
+    use crate::{
+        body::ResponseBody,
+        context::WebContext,
+        error::{Error, MatchError, RouterError},
+        http::{const_header_value::TEXT_UTF8, header::CONTENT_TYPE, WebResponse},
+        service::Service,
+    };
+
+    use super::Challenges;
+
+    pub struct AcmeChallengeService(pub(super) Challenges);
+
+    impl<'r, C, B> Service<WebContext<'r, C, B>> for AcmeChallengeService {
+        type Response = WebResponse;
+        type Error = RouterError<Error<C>>;
+
+        async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let token = ctx.req().uri().path().rsplit('/').next().unwrap_or_default();
+            let key_auth = self.0.lock().unwrap().get(token).cloned();
+            match key_auth {
+                Some(key_auth) => {
+                    let mut res = ctx.into_response(ResponseBody::from(key_auth));
+                    res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+                    Ok(res)
+                }
+                None => Err(RouterError::Match(MatchError)),
+            }
+        }
+    }
+}
+
+// ACME client session holding account key and replay nonce.
+struct Session {
+    client: Client,
+    key: EcdsaKeyPair,
+    rng: SystemRandom,
+    directory: Value,
+    nonce: Option<String>,
+    kid: Option<String>,
+}
+
+impl Session {
+    async fn new(client: Client, key: EcdsaKeyPair, directory: &str) -> io::Result<Self> {
+        let res = client.request(Method::GET, directory, None).await?;
+        let directory = to_json(&res)?;
+        Ok(Self {
+            client,
+            key,
+            rng: SystemRandom::new(),
+            directory,
+            nonce: None,
+            kid: None,
+        })
+    }
+
+    fn directory(&self, name: &str) -> io::Result<String> {
+        as_str(&self.directory[name], name).map(String::from)
+    }
+
+    fn jwk(&self) -> (String, String) {
+        // uncompressed public key point in the form of 0x04 || x || y
+        let point = self.key.public_key().as_ref();
+        (
+            URL_SAFE_NO_PAD.encode(&point[1..33]),
+            URL_SAFE_NO_PAD.encode(&point[33..65]),
+        )
+    }
+
+    // RFC 7638 jwk thumbprint.
+    fn thumbprint(&self) -> String {
+        let (x, y) = self.jwk();
+        let jwk = format!(r#"{{"crv":"P-256","kty":"EC","x":"{x}","y":"{y}"}}"#);
+        URL_SAFE_NO_PAD.encode(digest::digest(&digest::SHA256, jwk.as_bytes()))
+    }
+
+    async fn nonce(&mut self) -> io::Result<String> {
+        if let Some(nonce) = self.nonce.take() {
+            return Ok(nonce);
+        }
+        let res = self
+            .client
+            .request(Method::HEAD, &self.directory("newNonce")?, None)
+            .await?;
+        res.header("replay-nonce")
+            .map(String::from)
+            .ok_or_else(|| io::Error::other("ACME server does not return replay nonce"))
+    }
+
+    fn sign(&self, url: &str, nonce: String, payload: Option<&Value>) -> io::Result<Vec<u8>> {
+        let mut protected = json!({ "alg": "ES256", "nonce": nonce, "url": url });
+        match self.kid {
+            Some(ref kid) => protected["kid"] = json!(kid),
+            None => {
+                let (x, y) = self.jwk();
+                protected["jwk"] = json!({ "crv": "P-256", "kty": "EC", "x": x, "y": y });
+            }
+        }
+
+        let protected = URL_SAFE_NO_PAD.encode(protected.to_string());
+        // empty payload for POST-as-GET request.
+        let payload = payload
+            .map(|p| URL_SAFE_NO_PAD.encode(p.to_string()))
+            .unwrap_or_default();
+
+        let signature = self
+            .key
+            .sign(&self.rng, format!("{protected}.{payload}").as_bytes())
+            .map_err(|_| io::Error::other("failed to sign ACME request"))?;
+
+        let body = json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": URL_SAFE_NO_PAD.encode(signature),
+        });
+
+        Ok(body.to_string().into_bytes())
+    }
+
+    async fn post(&mut self, url: &str, payload: Option<Value>) -> io::Result<Response> {
+        let mut retry = true;
+        loop {
+            let nonce = self.nonce().await?;
+            let body = self.sign(url, nonce, payload.as_ref())?;
+            let res = self
+                .client
+                .request(Method::POST, url, Some(("application/jose+json", body)))
+                .await?;
+
+            self.nonce = res.header("replay-nonce").map(String::from);
+
+            if res.is_success() {
+                return Ok(res);
+            }
+
+            let err = to_json(&res).unwrap_or_default();
+            // nonce can be rejected by server. retry once with the new nonce from error response.
+            if retry && err["type"] == "urn:ietf:params:acme:error:badNonce" {
+                retry = false;
+                continue;
+            }
+
+            return Err(io::Error::other(format!(
+                "ACME request to {url} failed with status {}: {err}",
+                res.status
+            )));
+        }
+    }
+
+    // poll ACME resource until it's valid.
+    async fn poll(&mut self, url: &str, kind: &str) -> io::Result<Value> {
+        for _ in 0..30 {
+            let res = to_json(&self.post(url, None).await?)?;
+            match res["status"].as_str() {
+                Some("valid") => return Ok(res),
+                Some("invalid") => return Err(io::Error::other(format!("ACME {kind} is invalid: {res}"))),
+                _ => tokio::time::sleep(Duration::from_secs(2)).await,
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("ACME {kind} is not valid in time"),
+        ))
+    }
+}
+
+fn account_key(path: &Path) -> io::Result<EcdsaKeyPair> {
+    let rng = SystemRandom::new();
+    let pkcs8 = match fs::read(path) {
+        Ok(pkcs8) => pkcs8,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+                .map_err(|_| io::Error::other("failed to generate ACME account key"))?;
+            write_file(path, pkcs8.as_ref(), true)?;
+            pkcs8.as_ref().to_vec()
+        }
+        Err(e) => return Err(e),
+    };
+    EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &pkcs8, &rng)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+// age of cached certificate. None when certificate or key is missing.
+fn cert_age(config: &AcmeConfig) -> Option<Duration> {
+    fs::metadata(config.key_path()).ok()?;
+    let modified = fs::metadata(config.cert_path()).and_then(|m| m.modified()).ok()?;
+    Some(SystemTime::now().duration_since(modified).unwrap_or_default())
+}
+
+// write file through a temporary file so partial write would not be observed. private file is only readable and
+// writable by it's owner.
+fn write_file(path: &Path, content: &[u8], private: bool) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp = path.with_extension("tmp");
+    // temporary file left by previous failed write could have a wider permission.
+    match fs::remove_file(&tmp) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    let mut opts = fs::OpenOptions::new();
+    opts.write(true).create_new(true);
+    if private {
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut opts, 0o600);
+    }
+
+    opts.open(&tmp)?.write_all(content)?;
+    fs::rename(tmp, path)
+}
+
+fn location(res: &Response) -> io::Result<String> {
+    res.header("location")
+        .map(String::from)
+        .ok_or_else(|| io::Error::other("ACME server does not return resource location"))
+}
+
+fn to_json(res: &Response) -> io::Result<Value> {
+    serde_json::from_slice(&res.body).map_err(io::Error::other)
+}
+
+fn as_str<'a>(value: &'a Value, name: &str) -> io::Result<&'a str> {
+    value
+        .as_str()
+        .ok_or_else(|| io::Error::other(format!("ACME server response is missing {name}")))
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{StatusCode, WebRequest},
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn challenge() {
+        let acme = Acme::new(AcmeConfig::new(["example.com"]));
+        acme.inner
+            .challenges
+            .lock()
+            .unwrap()
+            .insert(String::from("token"), String::from("token.thumbprint"));
+
+        let service = App::new()
+            .at("/", acme.challenge())
+            .at("/foo", handler_service(|| async { "foo" }))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut req = WebRequest::default();
+        *req.uri_mut() = "/.well-known/acme-challenge/token".parse().unwrap();
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let mut req = WebRequest::default();
+        *req.uri_mut() = "/.well-known/acme-challenge/nope".parse().unwrap();
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn thumbprint() {
+        let dir = std::env::temp_dir().join(format!("xitca-web-acme-{}", std::process::id()));
+        let path = dir.join("account.key");
+
+        let key = account_key(&path).unwrap();
+        let public = key.public_key().as_ref().to_vec();
+        // account key is persisted and loaded again.
+        let key = account_key(&path).unwrap();
+        assert_eq!(key.public_key().as_ref(), public);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let session = Session {
+            client: Client::new().unwrap(),
+            key,
+            rng: SystemRandom::new(),
+            directory: Value::Null,
+            nonce: None,
+            kid: None,
+        };
+
+        // sha256 digest encoded in base64 url without padding.
+        assert_eq!(session.thumbprint().len(), 43);
+
+        let body = session
+            .sign("https://acme.test/new-acct", String::from("nonce"), None)
+            .unwrap();
+        let body = serde_json::from_slice::<Value>(&body).unwrap();
+        assert_eq!(body["payload"], "");
+        let protected = URL_SAFE_NO_PAD.decode(body["protected"].as_str().unwrap()).unwrap();
+        let protected = serde_json::from_slice::<Value>(&protected).unwrap();
+        assert_eq!(protected["alg"], "ES256");
+        assert_eq!(protected["jwk"]["kty"], "EC");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
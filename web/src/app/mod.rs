@@ -1,6 +1,9 @@
 mod object;
 mod router;
 
+#[cfg(feature = "__server")]
+mod task;
+
 use core::{
     convert::Infallible,
     fmt,
@@ -23,10 +26,14 @@ use crate::{
     error::{Error, RouterError},
     http::{WebRequest, WebResponse},
     middleware::eraser::TypeEraser,
+    route::StaticRouter,
     service::{ready::ReadyService, AsyncFn, EnclosedBuilder, EnclosedFnBuilder, MapBuilder, Service, ServiceExt},
 };
 
-use self::{object::WebObject, router::AppRouter};
+use self::{
+    object::WebObject,
+    router::{AppRouter, AppStaticRouter},
+};
 
 /// composed application type with router, stateful context and default middlewares.
 pub struct App<R = (), CF = ()> {
@@ -34,7 +41,7 @@ pub struct App<R = (), CF = ()> {
     ctx_builder: CF,
 }
 
-type BoxFuture<C> = Pin<Box<dyn Future<Output = Result<C, Box<dyn fmt::Debug>>>>>;
+type BoxFuture<C> = Pin<Box<dyn Future<Output = Result<C, Box<dyn error::Error + Send + Sync>>>>>;
 type CtxBuilder<C> = Box<dyn Fn() -> BoxFuture<C> + Send + Sync>;
 type DefaultWebObject<C> = WebObject<C, RequestBody, WebResponse, RouterError<Error<C>>>;
 type DefaultAppRouter<C> = AppRouter<RouteObject<(), DefaultWebObject<C>, Infallible>>;
@@ -85,6 +92,17 @@ impl App {
             ctx_builder: (),
         }
     }
+
+    /// Construct a new application instance with [StaticRouter] generated by [static_router] macro as it's only
+    /// router. Routes are fixed at compile time and no more route can be added to application.
+    ///
+    /// [static_router]: crate::static_router
+    pub fn from_static<M, R>(router: StaticRouter<M, R>) -> App<AppStaticRouter<M, R>> {
+        App {
+            router: AppStaticRouter::new(router),
+            ctx_builder: (),
+        }
+    }
 }
 
 impl<Obj, CF> App<AppRouter<Obj>, CF> {
@@ -302,6 +320,95 @@ impl<Obj, CF> App<AppRouter<Obj>, CF> {
         self
     }
 
+    /// insert a route scope with given string literal as path prefix to application. the closure receives an
+    /// empty [App] sharing the state of current application and it's routes are registered under the prefix.
+    ///
+    /// middleware enclosed inside the closure only apply to routes of the scope. It's error type must be
+    /// convertible to [Error] so it can be composed with the outer application.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_web::{
+    /// #   error::Error,
+    /// #   handler::handler_service,
+    /// #   http::{header::AUTHORIZATION, StatusCode, WebResponse},
+    /// #   service::Service,
+    /// #   App, WebContext
+    /// # };
+    /// # fn _main() -> std::io::Result<()> {
+    /// App::new()
+    ///     .at("/", handler_service(|| async { "public" }))
+    ///     // auth middleware only applies to /admin/users route.
+    ///     .at_scope("/admin", |scope| {
+    ///         scope
+    ///             .at("/users", handler_service(|| async { "admin only" }))
+    ///             .enclosed_fn(auth)
+    ///     })
+    ///     .serve()
+    ///     .bind("localhost:8080")?
+    ///     .run()
+    ///     .wait()
+    /// # }
+    ///
+    /// async fn auth<S, C, B>(service: &S, ctx: WebContext<'_, C, B>) -> Result<WebResponse, Error<C>>
+    /// where
+    ///     S: for<'r> Service<WebContext<'r, C, B>, Response = WebResponse, Error = Error<C>>,
+    /// {
+    ///     if !ctx.req().headers().contains_key(AUTHORIZATION) {
+    ///         return Err(Error::from(StatusCode::UNAUTHORIZED));
+    ///     }
+    ///     service.call(ctx).await
+    /// }
+    /// ```
+    pub fn at_scope<F, A, Obj2, C, B>(self, path: &'static str, scope: F) -> Self
+    where
+        F: FnOnce(App<AppRouter<Obj2>>) -> A,
+        A: RouteGen + Service + Send + Sync,
+        A::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<A::Route<A>, (), Object = Obj>,
+    {
+        self.at(path, scope(App::new()))
+    }
+
+    /// insert fallback service to application. it's called when no route of application matches the request
+    /// instead of responding with default 404 not found response. useful for custom not found page or serving
+    /// index.html of single page application.
+    ///
+    /// fallback service applies to the scope of application it's inserted to. a nested application or route
+    /// scope without it's own fallback service uses the fallback of outer application.
+    ///
+    /// # Panics
+    /// when fallback service is inserted more than once to the same application.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_web::{
+    /// #   handler::{handler_service, html::Html},
+    /// #   http::StatusCode,
+    /// #   App, WebContext
+    /// # };
+    /// App::new()
+    ///     .at("/", handler_service(|| async { "index" }))
+    ///     .at_scope("/api", |scope| {
+    ///         scope
+    ///             .at("/users", handler_service(|| async { "users" }))
+    ///             // unmatched request to /api/* is responded with api specific fallback.
+    ///             .at_default(handler_service(|| async { ("no such api", StatusCode::NOT_FOUND) }))
+    ///     })
+    ///     // all other unmatched request is responded with custom not found page.
+    ///     .at_default(handler_service(|| async { Html("<h1>page not found</h1>") }))
+    ///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "for type infer" }));
+    /// ```
+    pub fn at_default<F, C, B>(mut self, builder: F) -> Self
+    where
+        F: RouteGen + Service + Send + Sync,
+        F::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<F::Route<F>, (), Object = Obj>,
+    {
+        self.router = self.router.insert_default(builder);
+        self
+    }
+
     /// insert typed route service with given path to application.
     pub fn at_typed<T, C>(mut self, typed: T) -> Self
     where
@@ -377,15 +484,120 @@ impl<R, CF> App<R, CF> {
     /// Construct App with async closure which it's output would be used as state.
     /// async state is used to produce thread per core and/or non thread safe state copies.
     /// The output state is not bound to `Send` and `Sync` auto traits.
+    ///
+    /// When served with [HttpServer](crate::HttpServer) every worker constructs it's state before accepting
+    /// connection so no request is served before state is ready. Connections arrived in the mean time are queued
+    /// in listener backlog. When construction fails the server is stopped and the error is returned from awaiting
+    /// or waiting on the server.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use xitca_web::{handler::{handler_service, state::StateRef}, App};
+    /// async fn init() -> Result<usize, std::num::ParseIntError> {
+    ///     "996".parse()
+    /// }
+    ///
+    /// # fn _main() -> std::io::Result<()> {
+    /// App::new()
+    ///     .with_async_state(init)
+    ///     .at("/", handler_service(index))
+    ///     .serve()
+    ///     .bind("localhost:8080")?
+    ///     .run()
+    ///     // error of init is returned here. it's kept as source of io::Error and can be downcast to it's type.
+    ///     .wait()
+    /// # }
+    ///
+    /// async fn index(StateRef(num): StateRef<'_, usize>) -> String {
+    ///     num.to_string()
+    /// }
+    /// ```
     pub fn with_async_state<CF1, Fut, C, E>(self, builder: CF1) -> App<R, CtxBuilder<C>>
     where
         CF1: Fn() -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Result<C, E>> + 'static,
-        E: fmt::Debug + 'static,
+        E: Into<Box<dyn error::Error + Send + Sync>>,
     {
         let ctx_builder = Box::new(move || {
             let fut = builder();
-            Box::pin(async { fut.await.map_err(|e| Box::new(e) as Box<dyn fmt::Debug>) }) as _
+            Box::pin(async { fut.await.map_err(Into::into) }) as _
+        });
+
+        App {
+            router: self.router,
+            ctx_builder,
+        }
+    }
+}
+
+#[cfg(feature = "__server")]
+impl<R, C> App<R, CtxBuilder<C>>
+where
+    C: Clone + 'static,
+{
+    /// Add background task running on given interval with a clone of application state as argument.
+    ///
+    /// Task starts when App is served with [HttpServer](crate::HttpServer) and runs on the first worker thread
+    /// constructing application state. First run happens right after state is constructed. When server shuts
+    /// down no more run is scheduled and graceful shutdown waits for the run in progress(bounded by shutdown
+    /// timeout) so it's not cancelled in the middle. Task does not run when App is not served by server.
+    ///
+    /// # Panics
+    /// when period is zero.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use std::{sync::{atomic::{AtomicUsize, Ordering}, Arc}, time::Duration};
+    /// # use xitca_web::{handler::handler_service, App, WebContext};
+    /// #[derive(Clone, Default)]
+    /// struct State {
+    ///     hits: Arc<AtomicUsize>,
+    /// }
+    ///
+    /// async fn index(ctx: &WebContext<'_, State>) -> &'static str {
+    ///     ctx.state().hits.fetch_add(1, Ordering::Relaxed);
+    ///     "hello"
+    /// }
+    ///
+    /// // background task receives a clone of application state.
+    /// async fn report(state: State) {
+    ///     println!("hits in last minute: {}", state.hits.swap(0, Ordering::Relaxed));
+    /// }
+    ///
+    /// App::new()
+    ///     .at("/", handler_service(index))
+    ///     .with_state(State::default())
+    ///     .with_task(Duration::from_secs(60), report);
+    /// ```
+    pub fn with_task<F, Fut>(self, period: core::time::Duration, task: F) -> Self
+    where
+        F: Fn(C) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        use core::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        use xitca_server::drain::Drain;
+
+        assert!(!period.is_zero(), "background task period must be non-zero");
+
+        let builder = self.ctx_builder;
+        let job = Arc::new(task);
+        let started = Arc::new(AtomicBool::new(false));
+
+        let ctx_builder = Box::new(move || {
+            let fut = builder();
+            let job = job.clone();
+            let started = started.clone();
+            Box::pin(async move {
+                let state = fut.await?;
+                if let Some(drain) = Drain::current() {
+                    if !started.swap(true, Ordering::AcqRel) {
+                        tokio::task::spawn_local(task::run(period, job, state.clone(), drain));
+                    }
+                }
+                Ok(state)
+            }) as _
         });
 
         App {
@@ -398,7 +610,7 @@ impl<R, CF> App<R, CF> {
 impl<R, CF> App<R, CF>
 where
     R: Service + Send + Sync,
-    R::Error: fmt::Debug + 'static,
+    R::Error: Into<Box<dyn error::Error + Send + Sync>>,
 {
     /// Enclose App with middleware type. Middleware must impl [Service] trait.
     /// See [middleware](crate::middleware) for more.
@@ -441,14 +653,14 @@ where
 impl<R, CF> App<R, CF>
 where
     R: Service + Send + Sync,
-    R::Error: fmt::Debug + 'static,
+    R::Error: Into<Box<dyn error::Error + Send + Sync>>,
 {
     /// Finish App build. No other App method can be called afterwards.
     pub fn finish<C, ResB, SE>(
         self,
     ) -> impl Service<
         Response = impl ReadyService + Service<WebRequest, Response = WebResponse<EitherResBody<ResB>>, Error = Infallible>,
-        Error = impl fmt::Debug,
+        Error = impl fmt::Debug + Into<Box<dyn error::Error + Send + Sync>>,
     >
     where
         R::Response: ReadyService + for<'r> Service<WebContext<'r, C>, Response = WebResponse<ResB>, Error = SE>,
@@ -481,13 +693,13 @@ where
         impl<S, Arg> Service<Arg> for BoxApp<S>
         where
             S: Service<Arg>,
-            S::Error: fmt::Debug + 'static,
+            S::Error: Into<Box<dyn error::Error + Send + Sync>>,
         {
             type Response = S::Response;
-            type Error = Box<dyn fmt::Debug>;
+            type Error = Box<dyn error::Error + Send + Sync>;
 
             async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
-                self.0.call(arg).await.map_err(|e| Box::new(e) as _)
+                self.0.call(arg).await.map_err(Into::into)
             }
         }
 
@@ -504,7 +716,7 @@ where
         impl Service<
             Response = impl ReadyService
                            + Service<WebRequest, Response = WebResponse<EitherResBody<ResB>>, Error = Infallible>,
-            Error = impl fmt::Debug,
+            Error = impl fmt::Debug + Into<Box<dyn error::Error + Send + Sync>>,
         >,
     >
     where
@@ -517,6 +729,42 @@ where
     {
         crate::server::HttpServer::serve(self.finish())
     }
+
+    #[cfg(feature = "__server")]
+    /// Finish App build and serve it with [HttpServer] according to given config. No other App method
+    /// can be called afterwards.
+    ///
+    /// [ServiceConfig](crate::config::ServiceConfig) is enclosed as the outer most middleware of App and its timeouts of keep alive and request
+    /// head are applied to [HttpServer]. So is its [ExpectPolicy](crate::config::ExpectPolicy).
+    ///
+    /// [HttpServer]: crate::server::HttpServer
+    pub fn serve_with_config<C, ResB, SE>(
+        self,
+        config: crate::config::ServiceConfig,
+    ) -> crate::server::HttpServer<
+        impl Service<
+            Response = impl ReadyService
+                           + Service<WebRequest, Response = WebResponse<EitherResBody<ResB>>, Error = Infallible>,
+            Error = impl fmt::Debug + Into<Box<dyn error::Error + Send + Sync>>,
+        >,
+    >
+    where
+        R: 'static,
+        R::Response: ReadyService + for<'r> Service<WebContext<'r, C>, Response = WebResponse<ResB>, Error = SE>,
+        SE: Into<Error<C>>,
+        ResB: 'static,
+        CF: IntoCtx<Ctx = C> + 'static,
+        C: 'static,
+    {
+        let keep_alive_timeout = config.keep_alive_timeout();
+        let request_head_timeout = config.request_head_timeout();
+        let expect_policy = config.expect_policy();
+        self.enclosed(config)
+            .serve()
+            .keep_alive_timeout(keep_alive_timeout)
+            .request_head_timeout(request_head_timeout)
+            .expect_policy(expect_policy)
+    }
 }
 
 type EitherResBody<B> = Either<B, ResponseBody>;
@@ -600,8 +848,11 @@ where
 }
 
 /// object safe [App] instance. used for case where naming [App]'s type is needed.
-pub type AppObject<S> =
-    Box<dyn xitca_service::object::ServiceObject<(), Response = S, Error = Box<dyn fmt::Debug>> + Send + Sync>;
+pub type AppObject<S> = Box<
+    dyn xitca_service::object::ServiceObject<(), Response = S, Error = Box<dyn error::Error + Send + Sync>>
+        + Send
+        + Sync,
+>;
 
 #[cfg(test)]
 mod test {
@@ -727,6 +978,61 @@ mod test {
         assert_eq!(res.status().as_u16(), 405);
     }
 
+    #[test]
+    fn app_head_options() {
+        use crate::{http::header::ALLOW, route::post, test::collect_string_body};
+
+        let service = App::new()
+            .at(
+                "/",
+                get(handler_service(|| async { "get" })).post(handler_service(|| async { "post" })),
+            )
+            .at(
+                "/explicit",
+                get(handler_service(|| async { "get" }))
+                    .head(handler_service(|| async { "" }))
+                    .options(handler_service(|| async { "options" })),
+            )
+            .at("/post", post(handler_service(|| async { "post" })))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let req = |method, uri| {
+            request::Builder::default()
+                .method(method)
+                .uri(uri)
+                .body(Default::default())
+                .unwrap()
+        };
+
+        // HEAD falls back to GET handler. body is dropped by dispatcher.
+        let res = service.call(req(Method::HEAD, "/")).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "get");
+
+        let res = service.call(req(Method::HEAD, "/explicit")).now_or_panic().unwrap();
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "");
+
+        let res = service.call(req(Method::HEAD, "/post")).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 405);
+        assert_eq!(res.headers().get(ALLOW).unwrap(), "POST,OPTIONS");
+
+        // OPTIONS is answered with allowed methods.
+        let res = service.call(req(Method::OPTIONS, "/")).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+        assert_eq!(res.headers().get(ALLOW).unwrap(), "POST,GET,HEAD,OPTIONS");
+
+        let res = service.call(req(Method::OPTIONS, "/explicit")).now_or_panic().unwrap();
+        assert!(res.headers().get(ALLOW).is_none());
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "options");
+
+        let res = service.call(req(Method::DELETE, "/explicit")).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 405);
+        assert_eq!(res.headers().get(ALLOW).unwrap(), "HEAD,OPTIONS,GET");
+    }
+
     #[derive(Clone)]
     struct Foo;
 
@@ -792,4 +1098,159 @@ mod test {
 
         assert_eq!(res.status().as_u16(), 200);
     }
+
+    #[test]
+    fn app_scope() {
+        async fn handler(StateRef(state): StateRef<'_, String>, PathRef(path): PathRef<'_>) -> String {
+            assert_eq!("state", state);
+            assert_eq!("/admin/users", path);
+            state.to_string()
+        }
+
+        async fn auth<S, C>(s: &S, req: WebContext<'_, C>) -> Result<WebResponse, Error<C>>
+        where
+            S: for<'r> Service<WebContext<'r, C>, Response = WebResponse, Error = Error<C>>,
+        {
+            if req.req().headers().contains_key("x-auth") {
+                s.call(req).await
+            } else {
+                Err(Error::from(crate::http::StatusCode::UNAUTHORIZED))
+            }
+        }
+
+        let service = App::new()
+            .with_state(String::from("state"))
+            .at("/", get(handler_service(stateless_handler)))
+            .at_scope("/admin", |scope| {
+                scope.at("/users", get(handler_service(handler))).enclosed_fn(auth)
+            })
+            .finish()
+            .call(())
+            .now_or_panic()
+            .ok()
+            .unwrap();
+
+        let req = |uri: &str, auth: bool| {
+            let mut req = request::Builder::default().uri(uri);
+            if auth {
+                req = req.header("x-auth", "1");
+            }
+            req.body(Default::default()).unwrap()
+        };
+
+        let res = service.call(req("/", false)).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+
+        let res = service.call(req("/admin/users", false)).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 401);
+
+        let res = service.call(req("/admin/users", true)).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+
+        let res = service.call(req("/admin/none", true)).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 404);
+    }
+
+    #[test]
+    fn app_default() {
+        use crate::{http::StatusCode, test::collect_string_body};
+
+        async fn fallback(PathRef(path): PathRef<'_>) -> String {
+            format!("fallback {path}")
+        }
+
+        let service = App::new()
+            .at("/", get(handler_service(|| async { "index" })))
+            .at_scope("/api", |scope| {
+                scope
+                    .at("/users", get(handler_service(|| async { "users" })))
+                    .at_default(handler_service(|| async { ("api fallback", StatusCode::NOT_FOUND) }))
+            })
+            .at("/nest", App::new().at("/foo", get(handler_service(|| async { "foo" }))))
+            .at_default(handler_service(fallback))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .ok()
+            .unwrap();
+
+        let call = |method: Method, uri: &str| {
+            let req = request::Builder::default()
+                .method(method)
+                .uri(uri)
+                .body(Default::default())
+                .unwrap();
+            let res = service.call(req).now_or_panic().unwrap();
+            let status = res.status();
+            (status, collect_string_body(res.into_body()).now_or_panic().unwrap())
+        };
+
+        assert_eq!(call(Method::GET, "/"), (StatusCode::OK, "index".into()));
+        assert_eq!(call(Method::GET, "/none"), (StatusCode::OK, "fallback /none".into()));
+        assert_eq!(call(Method::GET, "/api/users"), (StatusCode::OK, "users".into()));
+        assert_eq!(
+            call(Method::GET, "/api/none"),
+            (StatusCode::NOT_FOUND, "api fallback".into())
+        );
+        // nested app without fallback uses the fallback of outer app.
+        assert_eq!(call(Method::GET, "/nest/foo"), (StatusCode::OK, "foo".into()));
+        assert_eq!(
+            call(Method::GET, "/nest/bar"),
+            (StatusCode::OK, "fallback /nest/bar".into())
+        );
+        // method mismatch is not handled by fallback.
+        assert_eq!(call(Method::POST, "/").0, StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[cfg(feature = "__server")]
+    #[test]
+    fn app_task() {
+        use std::{
+            sync::mpsc,
+            thread::{self, ThreadId},
+            time::Duration,
+        };
+
+        async fn task(tx: mpsc::Sender<ThreadId>) {
+            let _ = tx.send(thread::current().id());
+        }
+
+        let (tx, rx) = mpsc::channel();
+
+        let mut server = App::new()
+            .at(
+                "/",
+                handler_service(|_: &WebContext<'_, mpsc::Sender<ThreadId>>| async { "" }),
+            )
+            .with_state(tx)
+            .with_task(Duration::from_millis(1), task)
+            .serve()
+            .worker_threads(2)
+            .disable_signal()
+            .bind("localhost:0")
+            .unwrap()
+            .run();
+
+        let handle = server.handle().unwrap();
+        let server = thread::spawn(move || server.wait());
+
+        // task runs on one worker thread only.
+        let id = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        for _ in 0..3 {
+            assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), id);
+        }
+
+        handle.stop(true);
+        server.join().unwrap().unwrap();
+    }
+
+    #[cfg(feature = "__server")]
+    #[test]
+    #[should_panic]
+    fn app_task_zero_period() {
+        let _ = App::new()
+            .at("/", handler_service(|_: &WebContext<'_>| async { "" }))
+            .with_state(())
+            .with_task(core::time::Duration::ZERO, |_| async {});
+    }
 }
@@ -1,17 +1,24 @@
 use xitca_http::util::service::router::{IntoObject, PathGen, RouteGen, Router, RouterError, RouterMapErr, TypedRoute};
 
 use crate::{
-    error::Error,
+    error::{Error, MatchError},
+    route::StaticRouter,
     service::{ready::ReadyService, Service},
     WebContext,
 };
 
 /// application wrap around [Router] and transform it's error type into [Error]
-pub struct AppRouter<Obj>(Router<Obj>);
+pub struct AppRouter<Obj> {
+    router: Router<Obj>,
+    default: Option<Obj>,
+}
 
 impl<Obj> AppRouter<Obj> {
     pub(super) fn new() -> Self {
-        Self(Router::new())
+        Self {
+            router: Router::new(),
+            default: None,
+        }
     }
 
     pub(super) fn insert<F, Arg, Req>(mut self, path: &'static str, builder: F) -> Self
@@ -20,7 +27,21 @@ impl<Obj> AppRouter<Obj> {
         F::Response: Service<Req>,
         Req: IntoObject<F::Route<F>, Arg, Object = Obj>,
     {
-        self.0 = self.0.insert(path, builder);
+        self.router = self.router.insert(path, builder);
+        self
+    }
+
+    pub(super) fn insert_default<F, Arg, Req>(mut self, builder: F) -> Self
+    where
+        F: Service<Arg> + RouteGen + Send + Sync,
+        F::Response: Service<Req>,
+        Req: IntoObject<F::Route<F>, Arg, Object = Obj>,
+    {
+        assert!(
+            self.default.is_none(),
+            "default service can only be inserted once for one App"
+        );
+        self.default = Some(Req::into_object(F::route_gen(builder)));
         self
     }
 
@@ -28,7 +49,7 @@ impl<Obj> AppRouter<Obj> {
     where
         T: TypedRoute<M, Route = Obj>,
     {
-        self.0 = self.0.insert_typed(t);
+        self.router = self.router.insert_typed(t);
         self
     }
 }
@@ -38,7 +59,7 @@ where
     Router<Obj>: PathGen,
 {
     fn path_gen(&mut self, prefix: &str) -> String {
-        self.0.path_gen(prefix)
+        self.router.path_gen(prefix)
     }
 }
 
@@ -55,33 +76,80 @@ where
 
 impl<Arg, Obj> Service<Arg> for AppRouter<Obj>
 where
-    Router<Obj>: Service<Arg>,
+    Router<Obj>: Service<Arg, Error = Obj::Error>,
+    Obj: Service<Arg>,
+    Arg: Clone,
+{
+    type Response = RouterService<<Router<Obj> as Service<Arg>>::Response, Obj::Response>;
+    type Error = Obj::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let default = match self.default {
+            Some(ref default) => Some(default.call(arg.clone()).await?),
+            None => None,
+        };
+        self.router
+            .call(arg)
+            .await
+            .map(|service| RouterService { service, default })
+    }
+}
+
+/// application wrap around [StaticRouter] and transform it's error type into [Error]
+pub struct AppStaticRouter<M, R>(StaticRouter<M, R>);
+
+impl<M, R> AppStaticRouter<M, R> {
+    pub(super) fn new(router: StaticRouter<M, R>) -> Self {
+        Self(router)
+    }
+}
+
+impl<Arg, M, R> Service<Arg> for AppStaticRouter<M, R>
+where
+    StaticRouter<M, R>: Service<Arg>,
 {
-    type Response = RouterService<<Router<Obj> as Service<Arg>>::Response>;
-    type Error = <Router<Obj> as Service<Arg>>::Error;
+    type Response = RouterService<<StaticRouter<M, R> as Service<Arg>>::Response>;
+    type Error = <StaticRouter<M, R> as Service<Arg>>::Error;
 
     async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
-        self.0.call(arg).await.map(RouterService)
+        self.0
+            .call(arg)
+            .await
+            .map(|service| RouterService { service, default: None })
     }
 }
 
-pub struct RouterService<S>(S);
+// default service is called when no route of router or nested routers without their own default service
+// matches the request.
+pub struct RouterService<S, D = S> {
+    service: S,
+    default: Option<D>,
+}
 
-impl<'r, S, C, B, Res, E> Service<WebContext<'r, C, B>> for RouterService<S>
+impl<'r, S, D, C, B, Res, E> Service<WebContext<'r, C, B>> for RouterService<S, D>
 where
     S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = RouterError<E>>,
+    D: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = RouterError<E>>,
     E: Into<Error<C>>,
 {
     type Response = Res;
     type Error = Error<C>;
 
     #[inline]
-    async fn call(&self, req: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
-        self.0.call(req).await.map_err(Into::into)
+    async fn call(&self, mut req: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let e = match self.service.call(req.reborrow()).await {
+            Ok(res) => return Ok(res),
+            Err(e) => Error::from(e),
+        };
+
+        match self.default {
+            Some(ref default) if e.is::<MatchError>() => default.call(req).await.map_err(Into::into),
+            _ => Err(e),
+        }
     }
 }
 
-impl<S> ReadyService for RouterService<S>
+impl<S, D> ReadyService for RouterService<S, D>
 where
     S: ReadyService,
 {
@@ -89,6 +157,6 @@ where
 
     #[inline]
     async fn ready(&self) -> Self::Ready {
-        self.0.ready().await
+        self.service.ready().await
     }
 }
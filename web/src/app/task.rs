@@ -0,0 +1,27 @@
+use core::{future::Future, time::Duration};
+
+use std::sync::Arc;
+
+use tokio::time::{interval, MissedTickBehavior};
+use xitca_server::drain::Drain;
+use xitca_unsafe_collection::futures::{Select, SelectOutput};
+
+// run task on given interval until worker starts draining. task in progress is awaited by graceful shutdown.
+pub(super) async fn run<F, Fut, C>(period: Duration, task: Arc<F>, state: C, drain: Drain)
+where
+    F: Fn(C) -> Fut,
+    Fut: Future<Output = ()>,
+    C: Clone,
+{
+    let mut interval = interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        if let SelectOutput::B(_) = interval.tick().select(drain.wait()).await {
+            return;
+        }
+
+        let _guard = drain.guard();
+        task(state.clone()).await;
+    }
+}
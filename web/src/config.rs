@@ -0,0 +1,542 @@
+//! typed configuration for limits and timeouts of http service.
+
+use core::time::Duration;
+
+use tokio::time::Instant;
+
+use xitca_http::config::{DEFAULT_HEADER_LIMIT, DEFAULT_READ_BUF_LIMIT};
+
+pub use xitca_http::config::ExpectPolicy;
+
+use crate::service::Service;
+
+/// typed configuration for limits and timeouts of http service.
+///
+/// `ServiceConfig` is a middleware that enforce the limits on every request it enclosed and make itself
+/// accessible from [WebContext::service_config](crate::WebContext::service_config) so extractors and
+/// middlewares can consult it. Requests violating the limits are rejected with according status code:
+/// - header count or size over limit: `431 Request Header Fields Too Large`
+/// - body size declared by `content-length` header over limit: `413 Payload Too Large`. Or `417 Expectation Failed`
+///   for request with `Expect: 100-continue` header when [ServiceConfig::set_expectation_failed] is set.
+/// - handling time over limit: `408 Request Timeout`
+///
+/// When request timeout is set a [Deadline] is stored in request extensions and made accessible from
+/// [WebContext::deadline](crate::WebContext::deadline). Body extractors stop reading body when the deadline is
+/// reached and the remaining budget can be forwarded to downstream work like outgoing client requests.
+///
+/// Body size limit is also honored by body extractors like [Json](crate::handler::json::Json) for request
+/// without `content-length` header.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{config::ServiceConfig, handler::handler_service, route::get, App, WebContext};
+/// let config = ServiceConfig::new()
+///     .set_max_request_headers(32)
+///     .set_max_request_body_size(1024 * 1024)
+///     .set_request_timeout(Duration::from_secs(30));
+///
+/// App::new()
+///     .at("/", get(handler_service(|ctx: &WebContext<'_>| {
+///         // config can be consulted from web context.
+///         let size = ctx.service_config().max_request_body_size();
+///         async move { size.to_string() }
+///     })))
+///     .enclosed(config);
+/// ```
+///
+/// When served with [App::serve_with_config](crate::App::serve_with_config) the keep alive and request head
+/// timeouts are applied to http server too. So does the body size limit for request with `Expect: 100-continue`
+/// header which is then rejected by http server before `100 Continue` is sent and service is called.
+///
+/// # Note
+/// Header count and size are parsed with the transport level limits of http server first. Values larger than
+/// them(see [HttpServer::max_request_headers] and [HttpServer::max_read_buf_size]) have no effect.
+///
+/// [HttpServer::max_request_headers]: crate::HttpServer::max_request_headers
+/// [HttpServer::max_read_buf_size]: crate::HttpServer::max_read_buf_size
+#[derive(Clone, Copy, Debug)]
+pub struct ServiceConfig {
+    max_request_headers: usize,
+    max_request_header_size: usize,
+    max_request_body_size: usize,
+    request_timeout: Option<Duration>,
+    keep_alive_timeout: Duration,
+    request_head_timeout: Duration,
+    max_form_fields: usize,
+    max_form_field_size: usize,
+    expectation_failed: bool,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) static DEFAULT_CONFIG: ServiceConfig = ServiceConfig::new();
+
+impl ServiceConfig {
+    pub const fn new() -> Self {
+        Self {
+            max_request_headers: DEFAULT_HEADER_LIMIT,
+            max_request_header_size: DEFAULT_READ_BUF_LIMIT,
+            max_request_body_size: usize::MAX,
+            request_timeout: None,
+            keep_alive_timeout: Duration::from_secs(5),
+            request_head_timeout: Duration::from_secs(5),
+            max_form_fields: usize::MAX,
+            max_form_field_size: usize::MAX,
+            expectation_failed: false,
+        }
+    }
+
+    /// Set max number of request headers.
+    ///
+    /// Default to 64.
+    pub fn set_max_request_headers(mut self, num: usize) -> Self {
+        self.max_request_headers = num;
+        self
+    }
+
+    /// Set max size in byte unit of all request headers combined.
+    ///
+    /// Default to 1 MiB.
+    pub fn set_max_request_header_size(mut self, size: usize) -> Self {
+        self.max_request_header_size = size;
+        self
+    }
+
+    /// Set max size in byte unit the request body can be.
+    ///
+    /// Default to unlimited.
+    pub fn set_max_request_body_size(mut self, size: usize) -> Self {
+        self.max_request_body_size = size;
+        self
+    }
+
+    /// Set max duration of handling a request. Including reading request body and producing response head.
+    ///
+    /// Default to unlimited.
+    pub fn set_request_timeout(mut self, dur: Duration) -> Self {
+        self.request_timeout = Some(dur);
+        self
+    }
+
+    /// Set max duration of idle connection waiting for next request.
+    ///
+    /// Default to 5 seconds.
+    pub fn set_keep_alive_timeout(mut self, dur: Duration) -> Self {
+        self.keep_alive_timeout = dur;
+        self
+    }
+
+    /// Set max duration of reading request head.
+    ///
+    /// Default to 5 seconds.
+    pub fn set_request_head_timeout(mut self, dur: Duration) -> Self {
+        self.request_head_timeout = dur;
+        self
+    }
+
+    /// Set max number of fields in urlencoded form body. Repeated fields with the same name are counted
+    /// separately.
+    ///
+    /// Default to unlimited.
+    pub fn set_max_form_fields(mut self, num: usize) -> Self {
+        self.max_form_fields = num;
+        self
+    }
+
+    /// Set max size in byte unit of a single name or value of urlencoded form field after percent decoding.
+    ///
+    /// Default to unlimited.
+    pub fn set_max_form_field_size(mut self, size: usize) -> Self {
+        self.max_form_field_size = size;
+        self
+    }
+
+    /// Reject request with `Expect: 100-continue` header and body size over limit with `417 Expectation Failed`
+    /// instead of `413 Payload Too Large`.
+    pub fn set_expectation_failed(mut self) -> Self {
+        self.expectation_failed = true;
+        self
+    }
+
+    #[inline]
+    pub fn max_request_headers(&self) -> usize {
+        self.max_request_headers
+    }
+
+    #[inline]
+    pub fn max_request_header_size(&self) -> usize {
+        self.max_request_header_size
+    }
+
+    #[inline]
+    pub fn max_request_body_size(&self) -> usize {
+        self.max_request_body_size
+    }
+
+    #[inline]
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
+    #[inline]
+    pub fn keep_alive_timeout(&self) -> Duration {
+        self.keep_alive_timeout
+    }
+
+    #[inline]
+    pub fn request_head_timeout(&self) -> Duration {
+        self.request_head_timeout
+    }
+
+    #[inline]
+    pub fn max_form_fields(&self) -> usize {
+        self.max_form_fields
+    }
+
+    #[inline]
+    pub fn max_form_field_size(&self) -> usize {
+        self.max_form_field_size
+    }
+
+    /// [ExpectPolicy] derived from body size limit for request with `Expect: 100-continue` header.
+    pub fn expect_policy(&self) -> ExpectPolicy {
+        let max = self.max_request_body_size;
+        if max == usize::MAX {
+            ExpectPolicy::Continue
+        } else if self.expectation_failed {
+            ExpectPolicy::ExpectationFailed(max as u64)
+        } else {
+            ExpectPolicy::PayloadTooLarge(max as u64)
+        }
+    }
+}
+
+/// point in time when handling of a request must be finished.
+///
+/// Deadline is set by [ServiceConfig] middleware when request timeout is configured. Nested [ServiceConfig]
+/// middlewares can only shorten the deadline set by outer ones so a single budget bounds the whole request.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{config::ServiceConfig, handler::handler_service, route::get, App, WebContext};
+/// App::new()
+///     .at("/", get(handler_service(|ctx: &WebContext<'_>| {
+///         // remaining budget can be passed to downstream work as timeout.
+///         // e.g: xitca_client::RequestBuilder::timeout.
+///         let remaining = ctx.deadline().map(|deadline| deadline.remaining());
+///         async move { format!("{remaining:?}") }
+///     })))
+///     .enclosed(ServiceConfig::new().set_request_timeout(Duration::from_secs(30)));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+    /// construct a deadline that expires after given duration from now.
+    pub fn after(dur: Duration) -> Self {
+        Self(Instant::now() + dur)
+    }
+
+    /// construct a deadline from given point in time.
+    pub const fn at(instant: Instant) -> Self {
+        Self(instant)
+    }
+
+    /// point in time the deadline expires.
+    #[inline]
+    pub fn instant(&self) -> Instant {
+        self.0
+    }
+
+    /// remaining duration before the deadline expires. zero duration is returned when deadline is already
+    /// expired.
+    #[inline]
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    /// check if deadline is already expired.
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.0
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for ServiceConfig {
+    type Response = service::ServiceConfigService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::ServiceConfigService { service, config: *self })
+    }
+}
+
+mod service {
+    use crate::{
+        error::Error,
+        http::{
+            header::{CONTENT_LENGTH, EXPECT},
+            StatusCode, WebResponse,
+        },
+        service::{ready::ReadyService, Service},
+        WebContext,
+    };
+
+    use super::{Deadline, ServiceConfig};
+
+    pub struct ServiceConfigService<S> {
+        pub(super) service: S,
+        pub(super) config: ServiceConfig,
+    }
+
+    impl<'r, C, B, S, ResB, SE> Service<WebContext<'r, C, B>> for ServiceConfigService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = SE>,
+        SE: Into<Error<C>>,
+    {
+        type Response = WebResponse<ResB>;
+        type Error = Error<C>;
+
+        #[inline]
+        async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            self.config.enforce(&self.service, ctx).await
+        }
+    }
+
+    impl ServiceConfig {
+        // enforce limits and timeouts on given service call. shared by middlewares bundling ServiceConfig.
+        pub(crate) async fn enforce<'r, C, B, S, ResB, SE>(
+            &self,
+            service: &S,
+            mut ctx: WebContext<'r, C, B>,
+        ) -> Result<WebResponse<ResB>, Error<C>>
+        where
+            S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = SE>,
+            SE: Into<Error<C>>,
+        {
+            let config = self;
+            let headers = ctx.req().headers();
+
+            if headers.len() > config.max_request_headers
+                || headers
+                    .iter()
+                    .map(|(name, value)| name.as_str().len() + value.len())
+                    .sum::<usize>()
+                    > config.max_request_header_size
+            {
+                return Err(Error::from(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE));
+            }
+
+            let body_size = headers
+                .get(CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<usize>().ok());
+
+            if let Some(size) = body_size {
+                if headers
+                    .get(EXPECT)
+                    .is_some_and(|v| v.as_bytes().eq_ignore_ascii_case(b"100-continue"))
+                {
+                    if let Some(status) = config.expect_policy().reject(size as u64) {
+                        return Err(Error::from(status));
+                    }
+                } else if size > config.max_request_body_size {
+                    return Err(Error::from(StatusCode::PAYLOAD_TOO_LARGE));
+                }
+            }
+
+            let extensions = ctx.req_mut().extensions_mut();
+            extensions.insert(*config);
+
+            // deadline from outer middleware can only be shortened.
+            let deadline = match (config.request_timeout, extensions.get::<Deadline>()) {
+                (Some(dur), Some(outer)) => Some(*outer.min(&Deadline::after(dur))),
+                (Some(dur), None) => Some(Deadline::after(dur)),
+                (None, outer) => outer.copied(),
+            };
+
+            match deadline {
+                Some(deadline) => {
+                    extensions.insert(deadline);
+                    match tokio::time::timeout_at(deadline.instant(), service.call(ctx)).await {
+                        Ok(res) => res.map_err(Into::into),
+                        Err(_) => Err(Error::from(StatusCode::REQUEST_TIMEOUT)),
+                    }
+                }
+                None => service.call(ctx).await.map_err(Into::into),
+            }
+        }
+    }
+
+    impl<S> ReadyService for ServiceConfigService<S>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{
+            header::{HeaderName, HeaderValue, CONTENT_LENGTH, EXPECT},
+            StatusCode, WebRequest,
+        },
+        test::collect_body,
+        App, WebContext,
+    };
+
+    use super::*;
+
+    #[test]
+    fn limits() {
+        let service = App::new()
+            .at(
+                "/",
+                handler_service(|ctx: &WebContext<'_>| {
+                    let size = ctx.service_config().max_request_body_size();
+                    async move { size.to_string() }
+                }),
+            )
+            .enclosed(
+                ServiceConfig::new()
+                    .set_max_request_headers(2)
+                    .set_max_request_header_size(32)
+                    .set_max_request_body_size(8),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |headers: &[(&'static str, &'static str)]| {
+            let mut req = WebRequest::default();
+            for (name, value) in headers {
+                req.headers_mut()
+                    .append(HeaderName::from_static(name), HeaderValue::from_static(value));
+            }
+            service.call(req).now_or_panic().unwrap()
+        };
+
+        let res = call(&[]);
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = collect_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, b"8");
+
+        let res = call(&[("a", "1"), ("b", "2"), ("c", "3")]);
+        assert_eq!(res.status(), StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+
+        let res = call(&[("a", "0123456789012345678901234567890123456789")]);
+        assert_eq!(res.status(), StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+
+        let res = call(&[(CONTENT_LENGTH.as_str(), "9")]);
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn expect() {
+        let config = ServiceConfig::new()
+            .set_max_request_body_size(8)
+            .set_expectation_failed();
+        assert_eq!(config.expect_policy(), ExpectPolicy::ExpectationFailed(8));
+        assert_eq!(ServiceConfig::new().expect_policy(), ExpectPolicy::Continue);
+
+        let service = App::new()
+            .at("/", handler_service(|| async { "" }))
+            .enclosed(config)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |len: &'static str, expect: bool| {
+            let mut req = WebRequest::default();
+            req.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from_static(len));
+            if expect {
+                req.headers_mut()
+                    .insert(EXPECT, HeaderValue::from_static("100-continue"));
+            }
+            service.call(req).now_or_panic().unwrap().status()
+        };
+
+        assert_eq!(call("8", true), StatusCode::OK);
+        assert_eq!(call("9", true), StatusCode::EXPECTATION_FAILED);
+        assert_eq!(call("9", false), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let service = App::new()
+            .at(
+                "/",
+                handler_service(|| async {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    "done"
+                }),
+            )
+            .enclosed(ServiceConfig::new().set_request_timeout(Duration::from_millis(1)))
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let res = service.call(WebRequest::default()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::REQUEST_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn deadline() {
+        use futures_util::stream;
+
+        use crate::{
+            body::{BoxBody, RequestBody},
+            bytes::Bytes,
+            error::{BodyError, Error},
+        };
+
+        async fn handler(ctx: &WebContext<'_>, body: Result<Vec<u8>, Error>) -> &'static str {
+            // inner config can not extend deadline from outer one.
+            let deadline = ctx.deadline().unwrap();
+            assert!(deadline.remaining() <= Duration::from_millis(10));
+
+            // body reading stops at deadline.
+            let err = body.unwrap_err();
+            assert_eq!(err.to_string(), StatusCode::REQUEST_TIMEOUT.to_string());
+            assert!(deadline.is_expired());
+
+            "done"
+        }
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(ServiceConfig::new().set_request_timeout(Duration::from_secs(60)))
+            .enclosed(ServiceConfig::new().set_request_timeout(Duration::from_millis(10)))
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let body = stream::pending::<Result<Bytes, BodyError>>();
+        let req = WebRequest::default().map(|ext| ext.map_body(|_: ()| RequestBody::from(BoxBody::new(body))));
+
+        let res = service.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = collect_body(res.into_body()).await.unwrap();
+        assert_eq!(body, b"done");
+    }
+}
@@ -7,6 +7,7 @@ use core::{
 
 use super::{
     body::{RequestBody, ResponseBody},
+    config::{Deadline, ServiceConfig},
     handler::FromRequest,
     http::{BorrowReq, BorrowReqMut, IntoResponse, Request, RequestExt, WebRequest, WebResponse},
 };
@@ -55,6 +56,23 @@ impl<'a, C, B> WebContext<'a, C, B> {
         }
     }
 
+    /// Get reference of [ServiceConfig] the request is served with. When no [ServiceConfig] middleware enclosing
+    /// current service the default config is returned.
+    #[inline]
+    pub fn service_config(&self) -> &ServiceConfig {
+        self.req
+            .extensions()
+            .get::<ServiceConfig>()
+            .unwrap_or(&crate::config::DEFAULT_CONFIG)
+    }
+
+    /// Get the [Deadline] of current request. Deadline is set by [ServiceConfig] middleware with request timeout
+    /// configured.
+    #[inline]
+    pub fn deadline(&self) -> Option<Deadline> {
+        self.req.extensions().get::<Deadline>().copied()
+    }
+
     /// extract typed data from WebContext. type must impl [FromRequest] trait.
     /// this is a shortcut method that avoiding explicit import of mentioned trait.
     /// # Examples
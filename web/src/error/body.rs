@@ -4,7 +4,7 @@ use std::error;
 
 use super::{error_from_service, forward_blank_bad_request};
 
-pub use xitca_http::error::BodyError;
+pub use xitca_http::error::{BodyError, BodyIncomplete};
 
 #[derive(Debug, Clone)]
 pub struct BodyOverFlow {
@@ -21,3 +21,6 @@ impl error::Error for BodyOverFlow {}
 
 error_from_service!(BodyOverFlow);
 forward_blank_bad_request!(BodyOverFlow);
+
+error_from_service!(BodyIncomplete);
+forward_blank_bad_request!(BodyIncomplete);
@@ -0,0 +1,73 @@
+use core::{convert::Infallible, fmt};
+
+use std::error;
+
+use crate::{http::WebResponse, service::Service, WebContext};
+
+use super::Error;
+
+/// error type for a field of aggregate extractor failed to extract. produced by types derived from
+/// [Extract](crate::codegen::Extract) macro.
+///
+/// response generation is forwarded to the error of field and the error can be accessed through
+/// [std::error::Error::source] or [ExtractError::into_inner].
+pub struct ExtractError<C = ()> {
+    field: &'static str,
+    error: Error<C>,
+}
+
+impl<C> ExtractError<C> {
+    /// construct a new error with name of field and the error it produced.
+    pub fn new(field: &'static str, error: Error<C>) -> Self {
+        Self { field, error }
+    }
+
+    /// name of the field failed to extract. tuple struct field is named by it's index.
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    /// error produced by the field.
+    pub fn into_inner(self) -> Error<C> {
+        self.error
+    }
+}
+
+impl<C> fmt::Debug for ExtractError<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtractError")
+            .field("field", &self.field)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<C> fmt::Display for ExtractError<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to extract field {}: {}", self.field, self.error)
+    }
+}
+
+impl<C> error::Error for ExtractError<C> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(self.error.upcast())
+    }
+}
+
+impl<'r, C> Service<WebContext<'r, C>> for ExtractError<C> {
+    type Response = WebResponse;
+    type Error = Infallible;
+
+    async fn call(&self, ctx: WebContext<'r, C>) -> Result<Self::Response, Self::Error> {
+        self.error.call(ctx).await
+    }
+}
+
+impl<C> From<ExtractError<C>> for Error<C>
+where
+    C: 'static,
+{
+    fn from(e: ExtractError<C>) -> Self {
+        Self::from_service(e)
+    }
+}
@@ -72,13 +72,19 @@
 
 mod body;
 mod extension;
+mod extract;
 mod header;
+#[cfg(feature = "params")]
+mod params;
 mod router;
 mod status;
 
 pub use body::*;
 pub use extension::*;
+pub use extract::*;
 pub use header::*;
+#[cfg(feature = "params")]
+pub use params::*;
 pub use router::*;
 pub use status::*;
 
@@ -86,6 +92,9 @@ use core::{any::Any, convert::Infallible, fmt};
 
 use std::{error, io, sync::Mutex};
 
+#[cfg(feature = "backtrace")]
+use std::backtrace::Backtrace;
+
 use crate::{
     context::WebContext,
     http::WebResponse,
@@ -146,7 +155,11 @@ use self::service_impl::ErrorService;
 ///     assert!(e.downcast_ref::<Foo>().is_some());
 /// }
 /// ```
-pub struct Error<C = ()>(Box<dyn for<'r> ErrorService<WebContext<'r, C>>>);
+pub struct Error<C = ()> {
+    inner: Box<dyn for<'r> ErrorService<WebContext<'r, C>>>,
+    #[cfg(feature = "backtrace")]
+    backtrace: Backtrace,
+}
 
 impl<C> Error<C> {
     // construct an error object from given service type.
@@ -158,13 +171,21 @@ impl<C> Error<C> {
             + Sync
             + 'static,
     {
-        Self(Box::new(s))
+        Self::new(Box::new(s))
+    }
+
+    fn new(inner: Box<dyn for<'r> ErrorService<WebContext<'r, C>>>) -> Self {
+        Self {
+            inner,
+            #[cfg(feature = "backtrace")]
+            backtrace: Backtrace::capture(),
+        }
     }
 
     /// upcast Error to trait object for advanced error handling.
     /// See [std::error::Error] for usage
     pub fn upcast(&self) -> &(dyn error::Error + 'static) {
-        let e = self.0.dyn_err();
+        let e = self.inner.dyn_err();
         // due to Rust's specialization limitation Box<dyn std::error::Error> can impl neither
         // std::error::Error nor service_impl::DynError traits. Therefore a StdError new type
         // wrapper is introduced to work around it. When upcasting the error this new type is manually
@@ -175,28 +196,79 @@ impl<C> Error<C> {
         }
         e
     }
+
+    /// check if the concrete type of error is `T`.
+    pub fn is<T>(&self) -> bool
+    where
+        T: error::Error + 'static,
+    {
+        self.upcast().is::<T>()
+    }
+
+    /// downcast error to reference of concrete type `T`. return None when type does not match.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_web::{error::{Error, MatchError}, http::StatusCode};
+    /// fn is_not_found(e: &Error) -> bool {
+    ///     e.downcast_ref::<MatchError>().is_some()
+    /// }
+    ///
+    /// assert!(!is_not_found(&Error::from(StatusCode::BAD_REQUEST)));
+    /// ```
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: error::Error + 'static,
+    {
+        self.upcast().downcast_ref()
+    }
+
+    /// iterator over error and it's chain of [error::Error::source]. the first item is the same as [Self::upcast].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_web::error::Error;
+    /// fn log(e: &Error) {
+    ///     for (depth, e) in e.chain().enumerate() {
+    ///         tracing::error!("{depth}: {e}");
+    ///     }
+    /// }
+    /// ```
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn error::Error + 'static)> {
+        core::iter::successors(Some(self.upcast()), |e| e.source())
+    }
+
+    /// backtrace captured when error is constructed. it's captured according to `RUST_BACKTRACE` and
+    /// `RUST_LIB_BACKTRACE` environment variables. See [Backtrace::capture] for detail.
+    #[cfg(feature = "backtrace")]
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
 }
 
 impl<C> fmt::Debug for Error<C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&*self.0, f)
+        fmt::Debug::fmt(&*self.inner, f)
     }
 }
 
 impl<C> fmt::Display for Error<C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Display::fmt(&*self.0, f)
+        fmt::Display::fmt(&*self.inner, f)
     }
 }
 
 impl<C> error::Error for Error<C> {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        self.0.source()
+        self.inner.source()
     }
 
     #[cfg(feature = "nightly")]
     fn provide<'a>(&'a self, request: &mut error::Request<'a>) {
-        self.0.provide(request)
+        self.inner.provide(request);
+        // inner error's own backtrace is preferred when it provides one.
+        #[cfg(feature = "backtrace")]
+        request.provide_ref::<Backtrace>(&self.backtrace);
     }
 }
 
@@ -205,7 +277,7 @@ impl<'r, C> Service<WebContext<'r, C>> for Error<C> {
     type Error = Infallible;
 
     async fn call(&self, ctx: WebContext<'r, C>) -> Result<Self::Response, Self::Error> {
-        crate::service::object::ServiceObject::call(&self.0, ctx).await
+        crate::service::object::ServiceObject::call(&self.inner, ctx).await
     }
 }
 
@@ -307,7 +379,12 @@ impl<C> From<StdErr> for Error<C> {
             return Self::from(e.clone());
         }
 
-        Self(Box::new(StdError(e)))
+        // the same goes for BodyIncomplete which is emitted by http library as inner error of io::Error.
+        if let Some(e) = BodyIncomplete::find(&*e) {
+            return Self::from(*e);
+        }
+
+        Self::new(Box::new(StdError(e)))
     }
 }
 
@@ -514,4 +591,47 @@ mod test {
 
         assert!(err.upcast().downcast_ref::<Foo>().is_some());
     }
+
+    #[test]
+    fn downcast_chain() {
+        #[derive(Debug)]
+        struct Outer(io::Error);
+
+        impl fmt::Display for Outer {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("Outer")
+            }
+        }
+
+        impl error::Error for Outer {
+            fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let err = Error::<()>::from(io::Error::from(io::ErrorKind::NotFound));
+        assert!(err.is::<io::Error>());
+        assert_eq!(err.downcast_ref::<io::Error>().unwrap().kind(), io::ErrorKind::NotFound);
+        assert!(err.downcast_ref::<ThreadJoinError>().is_none());
+        assert_eq!(err.chain().count(), 1);
+
+        let err = Error::<()>::from(Box::new(Outer(io::Error::from(io::ErrorKind::NotFound))) as StdErr);
+        assert!(err.is::<Outer>());
+        let chain = err.chain().collect::<Vec<_>>();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].to_string(), "Outer");
+        assert!(chain[1].is::<io::Error>());
+    }
+
+    #[cfg(feature = "backtrace")]
+    #[test]
+    fn backtrace() {
+        use std::backtrace::BacktraceStatus;
+
+        let err = Error::<()>::from(io::Error::from(io::ErrorKind::NotFound));
+        assert!(matches!(
+            err.backtrace().status(),
+            BacktraceStatus::Captured | BacktraceStatus::Disabled
+        ));
+    }
 }
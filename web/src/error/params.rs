@@ -0,0 +1,99 @@
+use core::{convert::Infallible, fmt};
+
+use std::error;
+
+use crate::{
+    http::{const_header_value::TEXT_UTF8, header::CONTENT_TYPE, StatusCode, WebResponse},
+    service::Service,
+    WebContext,
+};
+
+use super::error_from_service;
+
+/// error type when uri param segment is missing or failed to parse into typed value. produced by
+/// [PathParams](crate::handler::params::PathParams) and [LazyPathParams](crate::handler::params::LazyPathParams).
+///
+/// produce 400 bad request response with plain text body naming the offending segment.
+pub struct InvalidParam {
+    index: usize,
+    segment: Option<(Box<str>, Box<str>)>,
+    error: Option<Box<dyn error::Error + Send + Sync>>,
+}
+
+impl InvalidParam {
+    /// construct an error when param at given index is missing.
+    pub fn missing(index: usize) -> Self {
+        Self {
+            index,
+            segment: None,
+            error: None,
+        }
+    }
+
+    /// construct an error when param at given index with name and value failed to parse.
+    pub fn invalid<E>(index: usize, name: &str, value: &str, error: E) -> Self
+    where
+        E: Into<Box<dyn error::Error + Send + Sync>>,
+    {
+        Self {
+            index,
+            segment: Some((name.into(), value.into())),
+            error: Some(error.into()),
+        }
+    }
+
+    /// index of the offending param in order of route's path segments.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// name of the offending param. None when param is missing.
+    pub fn name(&self) -> Option<&str> {
+        self.segment.as_ref().map(|(name, _)| &**name)
+    }
+
+    /// raw value of the offending param. None when param is missing.
+    pub fn value(&self) -> Option<&str> {
+        self.segment.as_ref().map(|(_, value)| &**value)
+    }
+}
+
+impl fmt::Debug for InvalidParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvalidParam")
+            .field("index", &self.index)
+            .field("name", &self.name())
+            .field("value", &self.value())
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl fmt::Display for InvalidParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.segment, &self.error) {
+            (Some((name, value)), Some(e)) => write!(f, "uri param {name}: {value:?} is invalid: {e}"),
+            _ => write!(f, "uri param at index {} is missing", self.index),
+        }
+    }
+}
+
+impl error::Error for InvalidParam {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.error.as_ref().map(|e| &**e as _)
+    }
+}
+
+error_from_service!(InvalidParam);
+
+impl<'r, C, B> Service<WebContext<'r, C, B>> for InvalidParam {
+    type Response = WebResponse;
+    type Error = Infallible;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let mut res = ctx.into_response(self.to_string());
+        *res.status_mut() = StatusCode::BAD_REQUEST;
+        res.headers_mut().insert(CONTENT_TYPE, TEXT_UTF8);
+        Ok(res)
+    }
+}
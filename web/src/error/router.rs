@@ -7,7 +7,7 @@ use core::convert::Infallible;
 
 use crate::{
     body::ResponseBody,
-    http::{header::ALLOW, StatusCode, WebResponse},
+    http::{header::ALLOW, Method, StatusCode, WebResponse},
     service::Service,
     WebContext,
 };
@@ -24,22 +24,34 @@ impl<'r, C, B> Service<WebContext<'r, C, B>> for MethodNotAllowed {
     type Error = Infallible;
 
     async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        // OPTIONS request without explicit route is answered with allowed methods.
+        let is_options = ctx.req().method() == Method::OPTIONS;
+
         let mut res = ctx.into_response(ResponseBody::empty());
 
         let allowed = self.allowed_methods();
 
         let len = allowed.iter().fold(0, |a, m| a + m.as_str().len() + 1);
 
-        let mut methods = String::with_capacity(len);
+        let mut methods = String::with_capacity(len + Method::OPTIONS.as_str().len());
 
         for method in allowed {
             methods.push_str(method.as_str());
             methods.push(',');
         }
-        methods.pop();
+
+        if !allowed.contains(&Method::OPTIONS) {
+            methods.push_str(Method::OPTIONS.as_str());
+        } else {
+            methods.pop();
+        }
 
         res.headers_mut().insert(ALLOW, methods.parse().unwrap());
-        *res.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+        *res.status_mut() = if is_options {
+            StatusCode::OK
+        } else {
+            StatusCode::METHOD_NOT_ALLOWED
+        };
 
         Ok(res)
     }
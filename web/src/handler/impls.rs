@@ -153,6 +153,24 @@ where
     }
 }
 
+// None is treated as absence of resource and produce 404 not found error.
+impl<'r, C, B, T, Res, Err> Responder<WebContext<'r, C, B>> for Option<T>
+where
+    T: for<'r2> Responder<WebContext<'r2, C, B>, Response = Res, Error = Err>,
+    Error<C>: From<Err>,
+{
+    type Response = Res;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn respond(self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        match self {
+            Some(res) => res.respond(ctx).await.map_err(Into::into),
+            None => Err(<Error<C> as From<StatusCode>>::from(StatusCode::NOT_FOUND)),
+        }
+    }
+}
+
 impl<'r, C, B, ResB> Responder<WebContext<'r, C, B>> for WebResponse<ResB> {
     type Response = WebResponse<ResB>;
     type Error = Error<C>;
@@ -209,7 +227,10 @@ const _: () = {
 mod test {
     use xitca_unsafe_collection::futures::NowOrPanic;
 
-    use crate::http::header::{HeaderMap, HeaderValue, CONTENT_TYPE, COOKIE};
+    use crate::{
+        http::header::{HeaderMap, HeaderValue, CONTENT_TYPE, COOKIE},
+        service::Service,
+    };
 
     use super::*;
 
@@ -265,4 +286,43 @@ mod test {
             .unwrap();
         check_res(res);
     }
+
+    #[test]
+    fn respond_std_types() {
+        let mut req = WebContext::new_test(());
+        let mut req = req.as_web_ctx();
+
+        let res = Some("hello,world!").respond(req.reborrow()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let err = Option::<&'static str>::None
+            .respond(req.reborrow())
+            .now_or_panic()
+            .err()
+            .unwrap();
+        let res = err.call(req.reborrow()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let res = Ok::<_, Error<()>>(Some(Vec::from("hello,world!")))
+            .respond(req.reborrow())
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = (StatusCode::CREATED, Vec::from("hello,world!"))
+            .respond(req.reborrow())
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        let res = (StatusCode::ACCEPTED, std::borrow::Cow::Borrowed("hello,world!"))
+            .respond(req)
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+        assert_eq!(
+            res.headers().get(CONTENT_TYPE).unwrap().to_str().unwrap(),
+            "text/plain; charset=utf-8"
+        );
+    }
 }
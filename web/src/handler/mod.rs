@@ -10,6 +10,6 @@ mod sync;
 pub use types::*;
 
 #[cfg(not(target_family = "wasm"))]
-pub use sync::handler_sync_service;
+pub use sync::{handler_sync_service, handler_sync_service_with_pool, BlockingPool};
 
 pub use xitca_http::util::service::handler::{handler_service, FromRequest, Responder};
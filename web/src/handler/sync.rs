@@ -6,6 +6,13 @@ use core::{
     marker::PhantomData,
 };
 
+use std::{
+    cell::RefCell,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
 use super::{FromRequest, Responder};
 
 use xitca_service::{fn_build, FnService, Service};
@@ -17,6 +24,9 @@ use xitca_service::{fn_build, FnService, Service};
 /// compared to [handler_service] where the arguments must be types that impl [FromRequest] trait,
 /// being thread safe with `Send` trait bound and with `'static` lifetime.
 ///
+/// The function runs on blocking task thread pool of async runtime by default. When server is configured with
+/// `HttpServer::blocking_threads` it runs on the dedicated [BlockingPool] shared by all workers instead.
+///
 /// # Examples:
 /// ```rust
 /// # use xitca_web::{
@@ -38,22 +48,61 @@ use xitca_service::{fn_build, FnService, Service};
 /// ```
 ///
 /// [handler_service]: super::handler_service
-pub fn handler_sync_service<Arg, F, T, O>(
+pub fn handler_sync_service<Arg, F, T, O>(func: F) -> FnService<impl Fn(Arg) -> HandlerServiceSyncBuild<F, T, O>>
+where
+    F: Closure<T> + Send + Clone,
+{
+    fn_build(move |_| {
+        ready(Ok(HandlerServiceSync {
+            func: func.clone(),
+            pool: BlockingPool::current(),
+            _p: PhantomData,
+        }))
+    })
+}
+
+/// [handler_sync_service] running given function on a dedicated [BlockingPool] instead of the blocking task
+/// thread pool of async runtime.
+///
+/// This is useful for isolating cpu heavy handlers(image processing, password hashing etc) from other blocking
+/// tasks like file io. A saturated pool would only slow down the handlers sharing it.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_web::{
+/// #     handler::{handler_service, handler_sync_service_with_pool, uri::UriOwn, BlockingPool},
+/// #     App,
+/// #     WebContext
+/// # };
+/// // a pool with 2 threads shared by handlers.
+/// let pool = BlockingPool::new(2);
+///
+/// App::new()
+///     .at("/hash", handler_sync_service_with_pool(pool.clone(), |_: UriOwn| "hashed"))
+///     .at("/resize", handler_sync_service_with_pool(pool, |_: UriOwn| "resized"))
+///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "" }));
+/// ```
+pub fn handler_sync_service_with_pool<Arg, F, T, O>(
+    pool: BlockingPool,
     func: F,
-) -> FnService<impl Fn(Arg) -> Ready<Result<HandlerServiceSync<F, T, O>, Infallible>>>
+) -> FnService<impl Fn(Arg) -> HandlerServiceSyncBuild<F, T, O>>
 where
     F: Closure<T> + Send + Clone,
 {
     fn_build(move |_| {
         ready(Ok(HandlerServiceSync {
             func: func.clone(),
+            pool: Some(pool.clone()),
             _p: PhantomData,
         }))
     })
 }
 
+type HandlerServiceSyncBuild<F, T, O> = Ready<Result<HandlerServiceSync<F, T, O>, Infallible>>;
+
 pub struct HandlerServiceSync<F, T, O> {
     func: F,
+    pool: Option<BlockingPool>,
     _p: PhantomData<(T, O)>,
 }
 
@@ -74,11 +123,91 @@ where
     async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
         let extract = T::Type::<'_>::from_request(&req).await?;
         let func = self.func.clone();
-        let res = tokio::task::spawn_blocking(move || func.call(extract)).await.unwrap();
+        let res = match self.pool {
+            Some(ref pool) => pool.run(move || func.call(extract)).await,
+            None => tokio::task::spawn_blocking(move || func.call(extract)).await.unwrap(),
+        };
         res.respond(req).await.map_err(Into::into)
     }
 }
 
+type Job = Box<dyn FnOnce() + Send>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<BlockingPool>> = const { RefCell::new(None) };
+}
+
+/// A dedicated thread pool for running blocking functions.
+///
+/// Pool is cheap to clone and all clones share the same threads. Threads are shut down when all clones
+/// of the pool are dropped.
+#[derive(Clone)]
+pub struct BlockingPool {
+    tx: mpsc::Sender<Job>,
+}
+
+impl BlockingPool {
+    /// Construct a new pool with given number of threads.
+    ///
+    /// # Panics:
+    /// When received 0 as number of threads.
+    pub fn new(threads: usize) -> Self {
+        assert_ne!(threads, 0, "BlockingPool must have at least one thread");
+
+        let (tx, rx) = mpsc::channel::<Job>();
+        let rx = Arc::new(Mutex::new(rx));
+
+        for idx in 0..threads {
+            let rx = rx.clone();
+            thread::Builder::new()
+                .name(format!("xitca-web-blocking-{idx}"))
+                .spawn(move || loop {
+                    // lock is released before running the job.
+                    let job = rx.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => return,
+                    }
+                })
+                .expect("failed to spawn BlockingPool thread");
+        }
+
+        Self { tx }
+    }
+
+    // set pool as default of current thread. handler_sync_service constructed on the thread would run on it.
+    pub(crate) fn set_current(self) {
+        CURRENT.with(|pool| *pool.borrow_mut() = Some(self));
+    }
+
+    fn current() -> Option<Self> {
+        CURRENT.with(|pool| pool.borrow().clone())
+    }
+
+    /// Run given function on the pool and wait for its output asynchronously.
+    ///
+    /// # Panics:
+    /// When given function panics the panic is propagated to the caller.
+    pub async fn run<F, R>(&self, func: F) -> R
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        self.tx
+            .send(Box::new(move || {
+                let _ = tx.send(panic::catch_unwind(AssertUnwindSafe(func)));
+            }))
+            .expect("BlockingPool threads must be alive as long as pool handle");
+
+        match rx.await.expect("BlockingPool job must not be dropped before running") {
+            Ok(res) => res,
+            Err(e) => panic::resume_unwind(e),
+        }
+    }
+}
+
 #[doc(hidden)]
 /// sync version of xitca_service::AsyncClosure trait.
 pub trait Closure<Arg> {
@@ -113,3 +242,73 @@ closure_impl! { A, B, C, D, E, F }
 closure_impl! { A, B, C, D, E, F, G }
 closure_impl! { A, B, C, D, E, F, G, H }
 closure_impl! { A, B, C, D, E, F, G, H, I }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        handler::uri::UriOwn,
+        http::{request, StatusCode},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn pool() {
+        let pool = BlockingPool::new(2);
+        let name = pool.run(|| thread::current().name().map(String::from)).await;
+        assert!(name.unwrap().starts_with("xitca-web-blocking-"));
+
+        let pool2 = pool.clone();
+        let res = tokio::spawn(async move { pool2.run(|| panic!("panic in pool")).await }).await;
+        assert!(res.unwrap_err().is_panic());
+
+        // panic is caught and pool keeps working.
+        assert_eq!(pool.run(|| 996).await, 996);
+    }
+
+    #[tokio::test]
+    async fn current_pool() {
+        BlockingPool::new(1).set_current();
+
+        let res = App::new()
+            .at(
+                "/",
+                handler_sync_service(|_: UriOwn| thread::current().name().unwrap().to_string()),
+            )
+            .finish()
+            .call(())
+            .await
+            .unwrap()
+            .call(request::Builder::default().uri("/").body(Default::default()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            collect_string_body(res.into_body()).await.unwrap(),
+            "xitca-web-blocking-0"
+        );
+    }
+
+    #[tokio::test]
+    async fn handler() {
+        let pool = BlockingPool::new(1);
+
+        let res = App::new()
+            .at(
+                "/",
+                handler_sync_service_with_pool(pool, |uri: UriOwn| uri.path().to_string()),
+            )
+            .finish()
+            .call(())
+            .await
+            .unwrap()
+            .call(request::Builder::default().uri("/").body(Default::default()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(collect_string_body(res.into_body()).await.unwrap(), "/");
+    }
+}
@@ -0,0 +1,309 @@
+//! type responder for file download.
+
+use core::fmt;
+
+use std::{io, path::PathBuf};
+
+pub use http_file::runtime::{AsyncFs, ChunkRead, Meta};
+
+use http_file::{serve_file, ServeError};
+
+use crate::{
+    body::ResponseBody,
+    context::WebContext,
+    error::Error,
+    handler::Responder,
+    http::{header::CONTENT_DISPOSITION, HeaderValue, WebResponse},
+};
+
+/// Response with file streamed as body and `content-disposition` header for downloading it.
+///
+/// `content-type` header is guessed from extension of file name when not set explicitly. `Range`,
+/// `If-Modified-Since` and `If-Unmodified-Since` request headers are honored the same way as
+/// [ServeDir](crate::service::file::ServeDir).
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #     handler::{
+/// #         attachment::{Attachment, ChunkRead, Meta},
+/// #         handler_service,
+/// #     },
+/// #     App, WebContext,
+/// # };
+/// async fn download() -> std::io::Result<Attachment<impl ChunkRead + Meta + 'static>> {
+///     // file would be downloaded as "report-2024.csv" by browser.
+///     Attachment::open("./reports/2024.csv")
+///         .await
+///         .map(|file| file.filename("report-2024.csv"))
+/// }
+///
+/// App::new().at("/report", handler_service(download))
+/// # .at("/nah", handler_service(|_: &WebContext<'_>| async { "for type infer" }));
+/// ```
+pub struct Attachment<F> {
+    file: F,
+    filename: String,
+    content_type: Option<HeaderValue>,
+    inline: bool,
+    chunk_size: usize,
+}
+
+impl<F> fmt::Debug for Attachment<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Attachment")
+            .field("filename", &self.filename)
+            .field("content_type", &self.content_type)
+            .field("inline", &self.inline)
+            .finish()
+    }
+}
+
+#[cfg(feature = "file")]
+impl Attachment<<http_file::runtime::TokioFs as AsyncFs>::File> {
+    /// open file from given path with default file system. file name of path is used as download file name.
+    pub async fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Self::open_with(&http_file::runtime::TokioFs, path).await
+    }
+}
+
+impl<F> Attachment<F>
+where
+    F: ChunkRead + Meta,
+{
+    /// construct a new attachment from an opened file and it's download file name.
+    ///
+    /// File name is trimmed to it's last path segment.
+    pub fn new(filename: impl Into<String>, file: F) -> Self {
+        Self {
+            file,
+            filename: String::new(),
+            content_type: None,
+            inline: false,
+            chunk_size: 4096,
+        }
+        .filename(filename)
+    }
+
+    /// open file from given path with given async file system. file name of path is used as download file name.
+    pub async fn open_with<FS>(fs: &FS, path: impl Into<PathBuf>) -> io::Result<Self>
+    where
+        FS: AsyncFs<File = F>,
+    {
+        let path = path.into();
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        fs.open(path).await.map(|file| Self::new(filename, file))
+    }
+}
+
+impl<F> Attachment<F> {
+    /// override download file name. non ascii file name is encoded according to RFC 5987 with an ascii fallback
+    /// for legacy user agents.
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        let mut filename = filename.into();
+        if let Some(idx) = filename.rfind(['/', '\\']) {
+            filename.drain(..=idx);
+        }
+        self.filename = filename;
+        self
+    }
+
+    /// override `content-type` header value guessed from file name.
+    pub fn content_type(mut self, value: HeaderValue) -> Self {
+        self.content_type = Some(value);
+        self
+    }
+
+    /// hint user agent to display file inline instead of downloading it.
+    pub fn inline(mut self) -> Self {
+        self.inline = true;
+        self
+    }
+
+    /// hint for chunk size of file streaming. see [ServeDir::chunk_size](http_file::ServeDir::chunk_size) for detail.
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        self.chunk_size = size;
+        self
+    }
+}
+
+impl<'r, C, B, F> Responder<WebContext<'r, C, B>> for Attachment<F>
+where
+    F: ChunkRead + Meta + 'static,
+{
+    type Response = WebResponse;
+    type Error = Error<C>;
+
+    async fn respond(self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let content_type = self.content_type.unwrap_or_else(|| {
+            let mime = mime_guess::from_path(&self.filename)
+                .first_raw()
+                .unwrap_or("application/octet-stream");
+            HeaderValue::from_static(mime)
+        });
+
+        let (parts, body) = match serve_file(ctx.req(), self.file, content_type, self.chunk_size).await {
+            Ok(res) => {
+                let (mut parts, body) = res.into_parts();
+                let value = content_disposition(self.inline, &self.filename);
+                parts.headers.insert(CONTENT_DISPOSITION, value);
+                (parts, ResponseBody::box_stream(body))
+            }
+            Err(ServeError::Io(e)) => return Err(Error::from(e)),
+            Err(e) => (e.into_response().into_parts().0, ResponseBody::none()),
+        };
+
+        let mut res = ctx.into_response(body);
+        *res.status_mut() = parts.status;
+        res.headers_mut().extend(parts.headers);
+        Ok(res)
+    }
+}
+
+fn content_disposition(inline: bool, filename: &str) -> HeaderValue {
+    let mut value = String::from(if inline { "inline" } else { "attachment" });
+
+    if !filename.is_empty() {
+        let fallback = filename
+            .chars()
+            .map(|c| match c {
+                '"' | '\\' => '_',
+                c if c.is_ascii() && !c.is_ascii_control() => c,
+                _ => '_',
+            })
+            .collect::<String>();
+
+        value.push_str("; filename=\"");
+        value.push_str(&fallback);
+        value.push('"');
+
+        if fallback != filename {
+            value.push_str("; filename*=UTF-8''");
+            for b in filename.bytes() {
+                match b {
+                    // attr-char of RFC 5987.
+                    b'a'..=b'z'
+                    | b'A'..=b'Z'
+                    | b'0'..=b'9'
+                    | b'!'
+                    | b'#'
+                    | b'$'
+                    | b'&'
+                    | b'+'
+                    | b'-'
+                    | b'.'
+                    | b'^'
+                    | b'_'
+                    | b'`'
+                    | b'|'
+                    | b'~' => value.push(b as char),
+                    b => {
+                        use core::fmt::Write;
+                        let _ = write!(value, "%{b:02X}");
+                    }
+                }
+            }
+        }
+    }
+
+    HeaderValue::try_from(value).expect("content-disposition value must be visible ascii")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn disposition() {
+        assert_eq!(content_disposition(false, ""), "attachment");
+        assert_eq!(content_disposition(true, "a.txt"), "inline; filename=\"a.txt\"");
+        assert_eq!(
+            content_disposition(false, "say \"hi\".txt"),
+            "attachment; filename=\"say _hi_.txt\"; filename*=UTF-8''say%20%22hi%22.txt"
+        );
+        assert_eq!(
+            content_disposition(false, "€ rates.pdf"),
+            "attachment; filename=\"_ rates.pdf\"; filename*=UTF-8''%E2%82%AC%20rates.pdf"
+        );
+    }
+
+    #[test]
+    fn filename() {
+        let attachment = Attachment::<()> {
+            file: (),
+            filename: String::new(),
+            content_type: None,
+            inline: false,
+            chunk_size: 4096,
+        };
+        assert_eq!(attachment.filename("../etc\\passwd").filename, "passwd");
+    }
+
+    #[cfg(feature = "file")]
+    #[tokio::test]
+    async fn respond() {
+        use crate::{
+            http::{
+                header::{CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE},
+                StatusCode,
+            },
+            test::collect_body,
+        };
+
+        let expected = std::fs::read("Cargo.toml").unwrap();
+
+        let mut ctx = WebContext::new_test(());
+        let res = Attachment::open("Cargo.toml")
+            .await
+            .unwrap()
+            .respond(ctx.as_web_ctx())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(CONTENT_DISPOSITION).unwrap(),
+            "attachment; filename=\"Cargo.toml\""
+        );
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/x-toml");
+        assert_eq!(
+            res.headers().get(CONTENT_LENGTH).unwrap(),
+            &HeaderValue::from(expected.len())
+        );
+        assert_eq!(collect_body(res.into_body()).await.unwrap(), expected);
+
+        let mut ctx = WebContext::new_test(());
+        ctx.req
+            .headers_mut()
+            .insert(RANGE, HeaderValue::from_static("bytes=0-8"));
+        let res = Attachment::open("Cargo.toml")
+            .await
+            .unwrap()
+            .filename("manifest")
+            .respond(ctx.as_web_ctx())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/octet-stream");
+        assert_eq!(
+            res.headers().get(CONTENT_RANGE).unwrap().to_str().unwrap(),
+            format!("bytes 0-8/{}", expected.len())
+        );
+        assert_eq!(collect_body(res.into_body()).await.unwrap(), &expected[..9]);
+
+        let mut ctx = WebContext::new_test(());
+        ctx.req
+            .headers_mut()
+            .insert(RANGE, HeaderValue::from_static("bytes=999999-"));
+        let res = Attachment::open("Cargo.toml")
+            .await
+            .unwrap()
+            .respond(ctx.as_web_ctx())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert!(res.headers().get(CONTENT_DISPOSITION).is_none());
+    }
+}
@@ -4,11 +4,11 @@ use core::{cmp, convert::Infallible, future::poll_fn, pin::pin};
 
 use crate::{
     body::{BodyStream, BoxBody, ResponseBody},
-    bytes::{Bytes, BytesMut},
+    bytes::{BufMut, Bytes, BytesMut},
     context::WebContext,
-    error::{BodyOverFlow, Error},
+    error::{BodyIncomplete, BodyOverFlow, Error},
     handler::{FromRequest, Responder},
-    http::{IntoResponse, WebResponse},
+    http::{IntoResponse, StatusCode, WebResponse},
 };
 
 use super::header::{self, HeaderRef};
@@ -43,30 +43,28 @@ macro_rules! from_bytes_impl {
             type Error = Error<C>;
 
             async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
-                let limit = HeaderRef::<'a, { header::CONTENT_LENGTH }>::from_request(ctx)
-                    .await
-                    .ok()
-                    .and_then(|header| header.to_str().ok().and_then(|s| s.parse().ok()))
-                    // when content length is 0 the http library should be producing an immediate
-                    // yielding streaming body which result in an empty body collection type.
-                    .map(|len| cmp::min(len, LIMIT))
-                    .unwrap_or_else(|| LIMIT);
-
-                let body = ctx.take_body_ref();
+                let mut buf = <$type>::new();
+                collect(ctx, LIMIT, &mut buf).await?;
+                Ok((buf, Limit))
+            }
+        }
 
-                let mut body = pin!(body);
+        impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for PartialBody<$type>
+        where
+            B: BodyStream + Default,
+        {
+            type Type<'b> = PartialBody<$type>;
+            type Error = Error<C>;
 
+            async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
                 let mut buf = <$type>::new();
-
-                while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
-                    let chunk = chunk.map_err(Into::into)?;
-                    buf.extend_from_slice(chunk.as_ref());
-                    if limit > 0 && buf.len() > limit {
-                        return Err(Error::from(BodyOverFlow { limit }));
-                    }
+                match collect(ctx, 0, &mut buf).await {
+                    Ok(_) => Ok(PartialBody(buf, None)),
+                    Err(e) => match e.downcast_ref::<BodyIncomplete>() {
+                        Some(incomplete) => Ok(PartialBody(buf, Some(*incomplete))),
+                        None => Err(e),
+                    },
                 }
-
-                Ok((buf, Limit))
             }
         }
 
@@ -108,8 +106,97 @@ where
     }
 }
 
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for PartialBody<Bytes>
+where
+    B: BodyStream + Default,
+{
+    type Type<'b> = PartialBody<Bytes>;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        <PartialBody<BytesMut>>::from_request(ctx)
+            .await
+            .map(|PartialBody(bytes, incomplete)| PartialBody(bytes.into(), incomplete))
+    }
+}
+
 from_bytes_impl!(Bytes);
 
+/// extractor for collecting request body that tolerates the body being terminated before it's finished.
+///
+/// When client disconnects in the middle of uploading the bytes received so far are extracted along with a
+/// [BodyIncomplete] error describing how much of the body is received. This is useful for resumable upload
+/// where client continues uploading from where it's stopped. Other body errors are returned as is.
+///
+/// Body size is only limited by [ServiceConfig](crate::config::ServiceConfig) and `Content-Length` header.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{bytes::Bytes, handler::{handler_service, body::PartialBody}, App, WebContext};
+/// async fn upload(PartialBody(body, incomplete): PartialBody<Bytes>) -> String {
+///     match incomplete {
+///         // persist partial upload and let client resume from offset.
+///         Some(incomplete) => format!("resume from {}", incomplete.received()),
+///         None => format!("uploaded {} bytes", body.len()),
+///     }
+/// }
+///
+/// App::new()
+///     .at("/upload", handler_service(upload))
+///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "for type infer" }));
+/// ```
+pub struct PartialBody<T>(pub T, pub Option<BodyIncomplete>);
+
+// collect request body into buffer.
+async fn collect<C, B, T>(ctx: &WebContext<'_, C, B>, limit: usize, buf: &mut T) -> Result<(), Error<C>>
+where
+    B: BodyStream + Default,
+    T: BufMut,
+{
+    // body size limit from service config is applied as upper bound.
+    let limit = match (limit, ctx.service_config().max_request_body_size()) {
+        (0, usize::MAX) => 0,
+        (0, max) => max,
+        (limit, max) => cmp::min(limit, max),
+    };
+
+    let limit = HeaderRef::<'_, { header::CONTENT_LENGTH }>::from_request(ctx)
+        .await
+        .ok()
+        .and_then(|header| header.to_str().ok().and_then(|s| s.parse().ok()))
+        // when content length is 0 the http library should be producing an immediate
+        // yielding streaming body which result in an empty body collection type.
+        .map(|len| cmp::min(len, limit))
+        .unwrap_or(limit);
+
+    // body reading is bounded by request deadline when there is one.
+    let deadline = ctx.deadline();
+
+    let body = ctx.take_body_ref();
+
+    let mut body = pin!(body);
+
+    let mut len = 0;
+
+    loop {
+        let next = poll_fn(|cx| body.as_mut().poll_next(cx));
+        let chunk = match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.instant(), next)
+                .await
+                .map_err(|_| Error::from(StatusCode::REQUEST_TIMEOUT))?,
+            None => next.await,
+        };
+        let Some(chunk) = chunk else { return Ok(()) };
+        let chunk = chunk.map_err(Into::into)?;
+        len += chunk.as_ref().len();
+        buf.put_slice(chunk.as_ref());
+        if limit > 0 && len > limit {
+            return Err(Error::from(BodyOverFlow { limit }));
+        }
+    }
+}
+
 macro_rules! responder_impl {
     ($type: ty) => {
         impl<'r, C, B> Responder<WebContext<'r, C, B>> for $type {
@@ -162,3 +249,67 @@ impl<'r, C, B> Responder<WebContext<'r, C, B>> for BoxBody {
         Responder::<WebContext<'r, C, B>>::map(ResponseBody::stream(self), res)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::io;
+
+    use futures_util::stream;
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::{BoxBody, RequestBody},
+        error::BodyError,
+        handler::handler_service,
+        http::WebRequest,
+        service::Service,
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    async fn upload(PartialBody(body, incomplete): PartialBody<Bytes>) -> String {
+        let incomplete = incomplete.map(|i| (i.received(), i.expected()));
+        format!("{}:{incomplete:?}", body.len())
+    }
+
+    async fn full(_: Bytes) -> &'static str {
+        unreachable!("incomplete body must be rejected")
+    }
+
+    #[test]
+    fn partial_body() {
+        let service = App::new()
+            .at("/upload", handler_service(upload))
+            .at("/full", handler_service(full))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |path: &'static str, incomplete: bool| {
+            let mut items: Vec<Result<Bytes, BodyError>> =
+                vec![Ok(Bytes::from_static(b"996")), Ok(Bytes::from_static(b"251"))];
+            if incomplete {
+                let e = io::Error::new(io::ErrorKind::UnexpectedEof, BodyIncomplete::new(6, Some(10)));
+                items.push(Err(Box::new(e)));
+            }
+            let body = BoxBody::new(stream::iter(items));
+            let mut req = WebRequest::default().map(|ext| ext.map_body(|_: ()| RequestBody::from(body)));
+            *req.uri_mut() = crate::http::Uri::from_static(path);
+            service.call(req).now_or_panic().unwrap()
+        };
+
+        let res = call("/upload", false);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, "6:None");
+
+        let res = call("/upload", true);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, "6:Some((6, Some(10)))");
+
+        let res = call("/full", true);
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
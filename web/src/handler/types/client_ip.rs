@@ -0,0 +1,418 @@
+//! type extractor and middleware for client ip address behind reverse proxies.
+
+use core::{
+    fmt,
+    net::{IpAddr, SocketAddr},
+};
+
+use std::sync::Arc;
+
+use crate::{
+    context::WebContext,
+    error::Error,
+    handler::FromRequest,
+    http::header::{HeaderMap, HeaderName, FORWARDED},
+    service::Service,
+};
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const X_REAL_IP: HeaderName = HeaderName::from_static("x-real-ip");
+
+/// ip address of client resolved with [TrustPolicy].
+///
+/// When [TrustPolicy] middleware is not in use or request is not from a trusted proxy the address of peer
+/// connection is extracted.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{client_ip::{ClientIp, TrustPolicy}, handler_service},
+/// #   route::get,
+/// #   App,
+/// # };
+/// async fn handler(ClientIp(ip): ClientIp) -> String {
+///     ip.to_string()
+/// }
+///
+/// # fn _main() -> std::io::Result<()> {
+/// App::new()
+///     .at("/", get(handler_service(handler)))
+///     // trust proxies from local network and look into forwarded headers for client address.
+///     .enclosed(TrustPolicy::new().trust_proxy("127.0.0.1").trust_proxy("10.0.0.0/8"))
+///     .serve()
+///     .bind("localhost:8080")?
+///     .run()
+///     .wait()
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ClientIp(pub IpAddr);
+
+impl fmt::Display for ClientIp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for ClientIp {
+    type Type<'b> = ClientIp;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        let req = ctx.req();
+        Ok(req
+            .extensions()
+            .get::<ClientIp>()
+            .copied()
+            .unwrap_or_else(|| ClientIp(req.body().socket_addr().ip())))
+    }
+}
+
+/// headers carrying the address chain of proxies a request passed through.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ForwardedHeader {
+    /// standard `Forwarded` header defined by RFC 7239. The `for` parameter of every element is used.
+    Forwarded,
+    /// de facto standard `X-Forwarded-For` header.
+    XForwardedFor,
+    /// `X-Real-IP` header carrying a single address.
+    XRealIp,
+}
+
+/// middleware resolving [ClientIp] of request with trusted proxies.
+///
+/// Forwarded headers are only looked into when the peer connection is from a trusted proxy. The address chain
+/// of the first present header in [TrustPolicy::headers] order is walked from right to left and the first
+/// address not belonging to trusted proxies is the client. When every address of the chain is trusted the
+/// left most one is used. Walking stops at address can't be parsed(`unknown` or obfuscated identifier of
+/// `Forwarded` header for example) and the last trusted address is used.
+///
+/// Resolved [ClientIp] is stored in request extensions so that it can be extracted by handlers and consulted
+/// by other middlewares. e.g: [RateLimit](crate::middleware::rate_limit::RateLimit) enclosed by TrustPolicy
+/// limits request by it.
+///
+/// Request from trusted proxy is also marked so that forwarded scheme and host are used by
+/// [url building](crate::middleware::base_url) helpers of [WebContext].
+#[derive(Clone)]
+pub struct TrustPolicy {
+    proxies: Arc<[Cidr]>,
+    headers: Arc<[ForwardedHeader]>,
+}
+
+impl Default for TrustPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrustPolicy {
+    /// construct a new policy trusting no proxy. `Forwarded` and `X-Forwarded-For` headers are checked in
+    /// order when proxy is trusted.
+    pub fn new() -> Self {
+        Self {
+            proxies: Arc::new([]),
+            headers: Arc::new([ForwardedHeader::Forwarded, ForwardedHeader::XForwardedFor]),
+        }
+    }
+
+    /// trust proxy with given address or CIDR range. e.g: `"127.0.0.1"`, `"10.0.0.0/8"` or `"fd00::/8"`.
+    ///
+    /// # Panics
+    /// when given string is not a valid ip address or CIDR range.
+    pub fn trust_proxy(mut self, cidr: &str) -> Self {
+        let cidr = Cidr::parse(cidr).unwrap_or_else(|| panic!("{cidr} is not a valid ip address or CIDR range"));
+        let mut proxies = self.proxies.to_vec();
+        proxies.push(cidr);
+        self.proxies = proxies.into();
+        self
+    }
+
+    /// set headers to look into and their order of preference. The first header present in request is used
+    /// and the rest are ignored.
+    pub fn headers(mut self, headers: impl IntoIterator<Item = ForwardedHeader>) -> Self {
+        self.headers = headers.into_iter().collect();
+        self
+    }
+
+    /// resolve client ip address from given peer address and request headers.
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+
+        let Some(chain) = self.headers.iter().find_map(|header| forwarded_chain(*header, headers)) else {
+            return peer;
+        };
+
+        let mut client = peer;
+        for addr in chain.iter().rev() {
+            match *addr {
+                Some(addr) => {
+                    client = addr;
+                    if !self.is_trusted(addr) {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        }
+        client
+    }
+
+    fn is_trusted(&self, addr: IpAddr) -> bool {
+        let addr = addr.to_canonical();
+        self.proxies.iter().any(|cidr| cidr.contains(addr))
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for TrustPolicy {
+    type Response = service::TrustPolicyService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::TrustPolicyService {
+            service,
+            policy: self.clone(),
+        })
+    }
+}
+
+mod service {
+    use crate::{http::WebResponse, middleware::base_url::TrustedProxy, service::ready::ReadyService};
+
+    use super::*;
+
+    pub struct TrustPolicyService<S> {
+        pub(super) service: S,
+        pub(super) policy: TrustPolicy,
+    }
+
+    impl<'r, C, B, S, ResB, SE> Service<WebContext<'r, C, B>> for TrustPolicyService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = SE>,
+    {
+        type Response = WebResponse<ResB>;
+        type Error = SE;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let req = ctx.req();
+            let peer = req.body().socket_addr().ip();
+            let ip = self.policy.resolve(peer, req.headers());
+            let ext = ctx.req_mut().extensions_mut();
+            ext.insert(ClientIp(ip));
+            if self.policy.is_trusted(peer) {
+                ext.insert(TrustedProxy);
+            }
+            self.service.call(ctx).await
+        }
+    }
+
+    impl<S> ReadyService for TrustPolicyService<S>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    fn parse(s: &str) -> Option<Self> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+            None => (s.parse::<IpAddr>().ok()?, None),
+        };
+
+        let addr = addr.to_canonical();
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+
+        (prefix <= max).then_some(Self { addr, prefix })
+    }
+
+    fn contains(&self, addr: IpAddr) -> bool {
+        match (self.addr, addr) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+// collect address chain from all occurrences of given header in order. None element represents address that can
+// not be parsed.
+fn forwarded_chain(header: ForwardedHeader, headers: &HeaderMap) -> Option<Vec<Option<IpAddr>>> {
+    let name = match header {
+        ForwardedHeader::Forwarded => FORWARDED,
+        ForwardedHeader::XForwardedFor => X_FORWARDED_FOR,
+        ForwardedHeader::XRealIp => X_REAL_IP,
+    };
+
+    let mut values = headers.get_all(name).iter().peekable();
+    values.peek()?;
+
+    let mut chain = Vec::new();
+
+    for value in values {
+        let Ok(value) = value.to_str() else {
+            chain.push(None);
+            continue;
+        };
+
+        match header {
+            ForwardedHeader::Forwarded => chain.extend(value.split(',').map(forwarded_for)),
+            ForwardedHeader::XForwardedFor => chain.extend(value.split(',').map(parse_node)),
+            ForwardedHeader::XRealIp => chain.push(parse_node(value)),
+        }
+    }
+
+    Some(chain)
+}
+
+// extract address from `for` parameter of a Forwarded element. e.g: `for=192.0.2.60;proto=http;by=203.0.113.43`
+fn forwarded_for(element: &str) -> Option<IpAddr> {
+    element.split(';').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("for")
+            .then(|| parse_node(value.trim().trim_matches('"')))
+    })?
+}
+
+// parse node in form of `192.0.2.60`, `192.0.2.60:4711`, `2001:db8::1`, `[2001:db8::1]` or `[2001:db8::1]:4711`
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim();
+
+    if let Some(rest) = node.strip_prefix('[') {
+        let (addr, _) = rest.split_once(']')?;
+        return addr.parse().ok();
+    }
+
+    node.parse()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{header::HeaderValue, WebRequest},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    fn headers(pairs: &[(HeaderName, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(name.clone(), HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn cidr() {
+        let cidr = Cidr::parse("10.0.0.0/8").unwrap();
+        assert!(cidr.contains(ip("10.1.2.3")));
+        assert!(!cidr.contains(ip("11.0.0.1")));
+        assert!(!cidr.contains(ip("::1")));
+
+        assert!(Cidr::parse("0.0.0.0/0").unwrap().contains(ip("8.8.8.8")));
+        assert!(Cidr::parse("fd00::/8").unwrap().contains(ip("fd12::1")));
+        assert!(Cidr::parse("::1").unwrap().contains(ip("::1")));
+
+        assert!(Cidr::parse("10.0.0.0/33").is_none());
+        assert!(Cidr::parse("foo").is_none());
+    }
+
+    #[test]
+    fn resolve() {
+        let policy = TrustPolicy::new().trust_proxy("10.0.0.0/8");
+        let peer = ip("10.0.0.1");
+
+        // untrusted peer.
+        let h = headers(&[(X_FORWARDED_FOR, "1.1.1.1")]);
+        assert_eq!(policy.resolve(ip("2.2.2.2"), &h), ip("2.2.2.2"));
+
+        // spoofed address in front of chain is ignored.
+        let h = headers(&[(X_FORWARDED_FOR, "6.6.6.6, 1.1.1.1, 10.0.0.2")]);
+        assert_eq!(policy.resolve(peer, &h), ip("1.1.1.1"));
+
+        // multiple header occurrences form one chain.
+        let h = headers(&[(X_FORWARDED_FOR, "1.1.1.1"), (X_FORWARDED_FOR, "10.0.0.2")]);
+        assert_eq!(policy.resolve(peer, &h), ip("1.1.1.1"));
+
+        // all trusted.
+        let h = headers(&[(X_FORWARDED_FOR, "10.0.0.3, 10.0.0.2")]);
+        assert_eq!(policy.resolve(peer, &h), ip("10.0.0.3"));
+
+        // Forwarded header is preferred by default.
+        let h = headers(&[
+            (X_FORWARDED_FOR, "3.3.3.3"),
+            (
+                FORWARDED,
+                r#"for=1.1.1.1;proto=http, For="[2001:db8::1]:4711";by=10.0.0.2"#,
+            ),
+        ]);
+        assert_eq!(policy.resolve(peer, &h), ip("2001:db8::1"));
+
+        // unknown address stops walking.
+        let h = headers(&[(FORWARDED, "for=1.1.1.1, for=unknown, for=10.0.0.2")]);
+        assert_eq!(policy.resolve(peer, &h), ip("10.0.0.2"));
+
+        let policy = policy.headers([ForwardedHeader::XRealIp]);
+        let h = headers(&[(X_REAL_IP, "1.1.1.1:80"), (X_FORWARDED_FOR, "3.3.3.3")]);
+        assert_eq!(policy.resolve(peer, &h), ip("1.1.1.1"));
+
+        let h = headers(&[(X_FORWARDED_FOR, "3.3.3.3")]);
+        assert_eq!(policy.resolve(peer, &h), peer);
+    }
+
+    #[test]
+    fn extract() {
+        async fn handler(ClientIp(ip): ClientIp) -> String {
+            ip.to_string()
+        }
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(TrustPolicy::new().trust_proxy("127.0.0.1"))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut req = WebRequest::default();
+        req.headers_mut()
+            .insert(X_FORWARDED_FOR, HeaderValue::from_static("1.1.1.1"));
+        *req.body_mut().socket_addr_mut() = "127.0.0.1:8080".parse().unwrap();
+
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "1.1.1.1");
+    }
+}
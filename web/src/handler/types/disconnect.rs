@@ -0,0 +1,82 @@
+//! type extractor for signal of peer closing connection.
+//!
+//! [Disconnect] extractor yields signal of current request. Long running handler can observe it and abort it's
+//! work when peer closes the connection.
+//!
+//! The signal is only triggered for Http/1 connection with
+//! [HttpServer::disconnect_signal](crate::HttpServer::disconnect_signal) enabled. Otherwise a signal that is
+//! never triggered is extracted.
+//!
+//! # Examples
+//! ```rust
+//! # use xitca_web::{
+//! #   handler::{disconnect::Disconnect, handler_service},
+//! #   route::get,
+//! #   App,
+//! # };
+//! async fn expensive_query() -> &'static str {
+//!     "done"
+//! }
+//!
+//! async fn handler(disconnect: Disconnect) -> &'static str {
+//!     tokio::select! {
+//!         res = expensive_query() => res,
+//!         // peer is gone and response would not be received by anyone.
+//!         _ = disconnect.wait() => "",
+//!     }
+//! }
+//!
+//! # fn _main() -> std::io::Result<()> {
+//! App::new()
+//!     .at("/", get(handler_service(handler)))
+//!     .serve()
+//!     .disconnect_signal()
+//!     .bind("localhost:8080")?
+//!     .run()
+//!     .wait()
+//! # }
+//! ```
+
+pub use xitca_http::disconnect::Disconnect;
+
+use crate::{context::WebContext, error::Error, handler::FromRequest};
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for Disconnect {
+    type Type<'b> = Disconnect;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        Ok(ctx.req().extensions().get::<Disconnect>().cloned().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{handler::handler_service, http::WebRequest, route::get, service::Service, App};
+
+    use super::*;
+
+    #[test]
+    fn extract() {
+        async fn handler(disconnect: Disconnect) -> &'static str {
+            if disconnect.is_disconnected() {
+                "gone"
+            } else {
+                "alive"
+            }
+        }
+
+        let service = App::new()
+            .at("/", get(handler_service(handler)))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(WebRequest::default()).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+    }
+}
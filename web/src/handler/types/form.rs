@@ -26,6 +26,37 @@ pub const DEFAULT_LIMIT: usize = 1024 * 1024;
 /// Object larger than limit would be treated as error.
 ///
 /// Default limit is [DEFAULT_LIMIT] in bytes.
+///
+/// Besides plain fields the extractor supports:
+/// - repeated fields with the same name deserialized into sequence types like [Vec]. field name with `[]` suffix
+///   is treated the same as name without it. e.g: `tag=a&tag[]=b` -> `tag: vec!["a", "b"]`
+/// - missing checkbox field deserialized as `false` with `#[serde(default, deserialize_with = "checkbox")]`.
+///   See [checkbox] for detail.
+///
+/// Max number of fields and max size of field can be configured with [ServiceConfig::set_max_form_fields] and
+/// [ServiceConfig::set_max_form_field_size].
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::handler::form::{checkbox, Form};
+/// #[derive(serde::Deserialize)]
+/// struct Post {
+///     title: String,
+///     // repeated fields. e.g: tags=a&tags=b or tags[]=a&tags[]=b
+///     #[serde(default)]
+///     tags: Vec<String>,
+///     // html checkbox is only sent when it's checked.
+///     #[serde(default, deserialize_with = "checkbox")]
+///     draft: bool,
+/// }
+///
+/// async fn handler(Form(post): Form<Post>) -> String {
+///     format!("{} {:?} {}", post.title, post.tags, post.draft)
+/// }
+/// ```
+///
+/// [ServiceConfig::set_max_form_fields]: crate::config::ServiceConfig::set_max_form_fields
+/// [ServiceConfig::set_max_form_field_size]: crate::config::ServiceConfig::set_max_form_field_size
 pub struct Form<T, const LIMIT: usize = DEFAULT_LIMIT>(pub T);
 
 impl<T, const LIMIT: usize> fmt::Debug for Form<T, LIMIT>
@@ -65,7 +96,10 @@ where
     async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
         HeaderRef::<'a, { header::CONTENT_TYPE }>::from_request(ctx).await?;
         let (bytes, _) = <(BytesMut, Limit<LIMIT>)>::from_request(ctx).await?;
-        serde_urlencoded::from_bytes(&bytes).map(Form).map_err(Into::into)
+        let config = ctx.service_config();
+        de::from_bytes(&bytes, config.max_form_fields(), config.max_form_field_size())
+            .map(Form)
+            .map_err(Into::into)
     }
 }
 
@@ -73,6 +107,8 @@ where
 /// it lowers the deserialization to handler function where zero copy deserialize can happen.
 pub struct LazyForm<T, const LIMIT: usize = DEFAULT_LIMIT> {
     bytes: Vec<u8>,
+    max_fields: usize,
+    max_field_size: usize,
     _form: PhantomData<T>,
 }
 
@@ -81,7 +117,7 @@ impl<T, const LIMIT: usize> LazyForm<T, LIMIT> {
     where
         T: Deserialize<'de>,
     {
-        serde_urlencoded::from_bytes(&self.bytes).map_err(Into::into)
+        de::from_bytes(&self.bytes, self.max_fields, self.max_field_size).map_err(Into::into)
     }
 }
 
@@ -96,8 +132,11 @@ where
     async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
         HeaderRef::<'a, { header::CONTENT_TYPE }>::from_request(ctx).await?;
         let (bytes, _) = <(Vec<u8>, Limit<LIMIT>)>::from_request(ctx).await?;
+        let config = ctx.service_config();
         Ok(LazyForm {
             bytes,
+            max_fields: config.max_form_fields(),
+            max_field_size: config.max_form_field_size(),
             _form: PhantomData,
         })
     }
@@ -137,6 +176,365 @@ impl<T> Form<T> {
 error_from_service!(serde_urlencoded::ser::Error);
 forward_blank_bad_request!(serde_urlencoded::ser::Error);
 
+/// serde helper for deserializing html checkbox field into bool.
+///
+/// Checked checkbox is sent with it's value(`on` by default) and unchecked one is not sent at all. Use it with
+/// `#[serde(default, deserialize_with = "checkbox")]` so missing field is deserialized as `false` and present
+/// field as `true` unless it's value is one of `off`, `false` or `0`.
+pub fn checkbox<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    let value = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+    Ok(!matches!(value.as_ref(), "off" | "false" | "0"))
+}
+
+mod de {
+    use std::{borrow::Cow, collections::HashMap};
+
+    use serde::de::{
+        value::{Error, MapDeserializer, SeqDeserializer},
+        Deserialize, Deserializer, Error as _, IntoDeserializer, Visitor,
+    };
+
+    type Pairs<'de> = Vec<(Cow<'de, str>, Cow<'de, str>)>;
+
+    pub(super) fn from_bytes<'de, T>(input: &'de [u8], max_fields: usize, max_field_size: usize) -> Result<T, Error>
+    where
+        T: Deserialize<'de>,
+    {
+        let mut pairs = Vec::new();
+
+        for (key, value) in form_urlencoded::parse(input) {
+            if pairs.len() == max_fields {
+                return Err(Error::custom("too many form fields"));
+            }
+            if key.len() > max_field_size || value.len() > max_field_size {
+                return Err(Error::custom("form field too large"));
+            }
+            pairs.push((key, value));
+        }
+
+        T::deserialize(FormDeserializer(pairs))
+    }
+
+    struct FormDeserializer<'de>(Pairs<'de>);
+
+    impl<'de> FormDeserializer<'de> {
+        // group values of repeated fields by name while keeping the order of first occurrence.
+        fn group(self) -> Vec<(Cow<'de, str>, Vec<Cow<'de, str>>)> {
+            let mut groups = Vec::<(Cow<'de, str>, Vec<Cow<'de, str>>)>::new();
+            let mut index = HashMap::<_, usize>::new();
+
+            for (key, value) in self.0 {
+                let key = trim_brackets(key);
+                match index.get(&key) {
+                    Some(&idx) => groups[idx].1.push(value),
+                    None => {
+                        index.insert(key.clone(), groups.len());
+                        groups.push((key, vec![value]));
+                    }
+                }
+            }
+
+            groups
+        }
+    }
+
+    fn trim_brackets(key: Cow<'_, str>) -> Cow<'_, str> {
+        match key {
+            Cow::Borrowed(key) => Cow::Borrowed(key.strip_suffix("[]").unwrap_or(key)),
+            Cow::Owned(mut key) => {
+                if key.ends_with("[]") {
+                    key.truncate(key.len() - 2);
+                }
+                Cow::Owned(key)
+            }
+        }
+    }
+
+    impl<'de> Deserializer<'de> for FormDeserializer<'de> {
+        type Error = Error;
+
+        fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.deserialize_map(visitor)
+        }
+
+        fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            let groups = self.group().into_iter().map(|(k, v)| (Part(k), Values(v)));
+            MapDeserializer::new(groups).deserialize_map(visitor)
+        }
+
+        fn deserialize_struct<V>(
+            self,
+            _: &'static str,
+            _: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.deserialize_map(visitor)
+        }
+
+        // sequence of key value pairs. e.g: Vec<(String, String)>
+        fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            let pairs = self.0.into_iter().map(|(k, v)| (Part(k), Part(v)));
+            MapDeserializer::new(pairs).deserialize_seq(visitor)
+        }
+
+        fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            if self.0.is_empty() {
+                visitor.visit_unit()
+            } else {
+                Err(Error::invalid_type(serde::de::Unexpected::Map, &visitor))
+            }
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf option
+            unit_struct newtype_struct tuple tuple_struct identifier ignored_any enum
+        }
+    }
+
+    // values of a field. sequence types take all values and others take the last one.
+    struct Values<'de>(Vec<Cow<'de, str>>);
+
+    impl<'de> Values<'de> {
+        fn last(mut self) -> Part<'de> {
+            Part(self.0.pop().unwrap_or_default())
+        }
+    }
+
+    impl<'de> IntoDeserializer<'de, Error> for Values<'de> {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self::Deserializer {
+            self
+        }
+    }
+
+    macro_rules! forward_to_last {
+        ($($method: ident),*) => {
+            $(
+                fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+                where
+                    V: Visitor<'de>,
+                {
+                    self.last().$method(visitor)
+                }
+            )*
+        };
+    }
+
+    impl<'de> Deserializer<'de> for Values<'de> {
+        type Error = Error;
+
+        fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            match self.0.len() {
+                1 => self.last().deserialize_any(visitor),
+                _ => self.deserialize_seq(visitor),
+            }
+        }
+
+        fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            SeqDeserializer::new(self.0.into_iter().map(Part)).deserialize_seq(visitor)
+        }
+
+        fn deserialize_tuple<V>(self, _: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.deserialize_seq(visitor)
+        }
+
+        fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            visitor.visit_some(self)
+        }
+
+        fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_tuple_struct<V>(self, _: &'static str, _: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.deserialize_seq(visitor)
+        }
+
+        fn deserialize_enum<V>(
+            self,
+            name: &'static str,
+            variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.last().deserialize_enum(name, variants, visitor)
+        }
+
+        fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.last().deserialize_unit_struct(name, visitor)
+        }
+
+        fn deserialize_struct<V>(
+            self,
+            name: &'static str,
+            fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.last().deserialize_struct(name, fields, visitor)
+        }
+
+        forward_to_last!(
+            deserialize_bool,
+            deserialize_i8,
+            deserialize_i16,
+            deserialize_i32,
+            deserialize_i64,
+            deserialize_u8,
+            deserialize_u16,
+            deserialize_u32,
+            deserialize_u64,
+            deserialize_f32,
+            deserialize_f64,
+            deserialize_char,
+            deserialize_str,
+            deserialize_string,
+            deserialize_bytes,
+            deserialize_byte_buf,
+            deserialize_unit,
+            deserialize_map,
+            deserialize_identifier,
+            deserialize_ignored_any
+        );
+    }
+
+    // a single name or value of form field.
+    struct Part<'de>(Cow<'de, str>);
+
+    impl<'de> IntoDeserializer<'de, Error> for Part<'de> {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self::Deserializer {
+            self
+        }
+    }
+
+    macro_rules! forward_parsed {
+        ($($ty: ident => $method: ident),*) => {
+            $(
+                fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+                where
+                    V: Visitor<'de>,
+                {
+                    match self.0.parse::<$ty>() {
+                        Ok(val) => val.into_deserializer().$method(visitor),
+                        Err(e) => Err(Error::custom(e)),
+                    }
+                }
+            )*
+        };
+    }
+
+    impl<'de> Deserializer<'de> for Part<'de> {
+        type Error = Error;
+
+        fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            match self.0 {
+                Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+                Cow::Owned(value) => visitor.visit_string(value),
+            }
+        }
+
+        fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            visitor.visit_some(self)
+        }
+
+        fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_enum<V>(
+            self,
+            name: &'static str,
+            variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            IntoDeserializer::<Error>::into_deserializer(self.0).deserialize_enum(name, variants, visitor)
+        }
+
+        fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            SeqDeserializer::new(core::iter::once(self)).deserialize_seq(visitor)
+        }
+
+        forward_parsed!(
+            bool => deserialize_bool,
+            u8 => deserialize_u8,
+            u16 => deserialize_u16,
+            u32 => deserialize_u32,
+            u64 => deserialize_u64,
+            i8 => deserialize_i8,
+            i16 => deserialize_i16,
+            i32 => deserialize_i32,
+            i64 => deserialize_i64,
+            f32 => deserialize_f32,
+            f64 => deserialize_f64
+        );
+
+        serde::forward_to_deserialize_any! {
+            char str string bytes byte_buf unit unit_struct tuple tuple_struct map struct identifier ignored_any
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use xitca_unsafe_collection::futures::NowOrPanic;
@@ -151,6 +549,51 @@ mod test {
         age: u8,
     }
 
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Post<'a> {
+        title: &'a str,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default)]
+        ids: Vec<u32>,
+        #[serde(default, deserialize_with = "checkbox")]
+        draft: bool,
+        #[serde(default, deserialize_with = "checkbox")]
+        pinned: bool,
+        rating: Option<u8>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let post = de::from_bytes::<Post>(
+            b"title=hello&tags=a&tags[]=b%20c&ids[]=1&ids[]=2&pinned=on",
+            usize::MAX,
+            usize::MAX,
+        )
+        .unwrap();
+        assert_eq!(
+            post,
+            Post {
+                title: "hello",
+                tags: vec!["a".into(), "b c".into()],
+                ids: vec![1, 2],
+                draft: false,
+                pinned: true,
+                rating: None,
+            }
+        );
+
+        let post = de::from_bytes::<Post>(b"title=hello&draft=off&rating=5", usize::MAX, usize::MAX).unwrap();
+        assert!(!post.draft);
+        assert_eq!(post.rating, Some(5));
+
+        let pairs = de::from_bytes::<Vec<(String, String)>>(b"a=1&a=2", usize::MAX, usize::MAX).unwrap();
+        assert_eq!(pairs, [("a".into(), "1".into()), ("a".into(), "2".into())]);
+
+        assert!(de::from_bytes::<Post>(b"title=hello&tags=a&tags=b", 2, usize::MAX).is_err());
+        assert!(de::from_bytes::<Post>(b"title=hello", usize::MAX, 4).is_err());
+    }
+
     #[test]
     fn extract_and_respond() {
         let mut ctx = WebContext::new_test(&());
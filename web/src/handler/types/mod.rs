@@ -1,13 +1,19 @@
 pub mod body;
+pub mod client_ip;
 pub mod extension;
 pub mod header;
 pub mod html;
 pub mod path;
 pub mod redirect;
+pub mod sse;
 pub mod state;
+pub mod stream;
 pub mod text;
 pub mod uri;
 
+#[cfg(feature = "__server")]
+pub mod disconnect;
+
 #[cfg(feature = "params")]
 pub mod params;
 
@@ -26,5 +32,8 @@ pub mod cookie;
 #[cfg(feature = "multipart")]
 pub mod multipart;
 
+#[cfg(feature = "file-raw")]
+pub mod attachment;
+
 #[cfg(feature = "websocket")]
 pub mod websocket;
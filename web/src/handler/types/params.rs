@@ -1,6 +1,6 @@
 //! type extractor for uri params.
 
-use core::{marker::PhantomData, ops::Deref};
+use core::{marker::PhantomData, ops::Deref, str::FromStr};
 
 use serde::{
     de::{self, Deserializer, Error as DeError, Visitor},
@@ -9,7 +9,11 @@ use serde::{
 
 use xitca_http::util::service::router;
 
-use crate::{context::WebContext, error::Error, handler::FromRequest};
+use crate::{
+    context::WebContext,
+    error::{Error, InvalidParam},
+    handler::FromRequest,
+};
 
 #[derive(Debug)]
 pub struct Params<T>(pub T);
@@ -78,6 +82,106 @@ impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for ParamsRef<'a> {
     }
 }
 
+/// typed uri params extractor. parse params in order of route's path segments into tuple of types implementing
+/// [FromStr]. custom parsing and validation can be added by implementing [FromStr] for a new type.
+///
+/// unlike [Params] it does not go through serde and failed parsing produces [InvalidParam] error with 400 bad
+/// request response naming the offending segment. trailing params not covered by the tuple are ignored.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::{handler_service, params::PathParams}, App, WebContext};
+/// async fn handler(PathParams((name, age)): PathParams<(String, u32)>) -> String {
+///     format!("{name} is {age} years old")
+/// }
+///
+/// App::new()
+///     .at("/:name/:age", handler_service(handler))
+///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "for type infer" }));
+/// ```
+#[derive(Debug)]
+pub struct PathParams<T>(pub T);
+
+impl<'a, 'r, T, C, B> FromRequest<'a, WebContext<'r, C, B>> for PathParams<T>
+where
+    T: FromParams,
+{
+    type Type<'b> = PathParams<T>;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        T::from_params(ctx.req().body().params())
+            .map(PathParams)
+            .map_err(Into::into)
+    }
+}
+
+/// lazy typed uri params extractor. it lowers the parsing of [PathParams] to handler function where it can be
+/// skipped when not needed.
+pub struct LazyPathParams<'a, T> {
+    params: &'a router::Params,
+    _params: PhantomData<T>,
+}
+
+impl<T> LazyPathParams<'_, T>
+where
+    T: FromParams,
+{
+    pub fn parse<C>(&self) -> Result<T, Error<C>> {
+        T::from_params(self.params).map_err(Into::into)
+    }
+}
+
+impl<'a, 'r, C, B, T> FromRequest<'a, WebContext<'r, C, B>> for LazyPathParams<'a, T> {
+    type Type<'b> = LazyPathParams<'b, T>;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        Ok(LazyPathParams {
+            params: ctx.req().body().params(),
+            _params: PhantomData,
+        })
+    }
+}
+
+/// trait for parsing uri params into typed value. implemented for tuples of types implementing [FromStr].
+pub trait FromParams: Sized {
+    fn from_params(params: &router::Params) -> Result<Self, InvalidParam>;
+}
+
+macro_rules! from_params_impl {
+    ($($ty: ident),+) => {
+        impl<$($ty),+> FromParams for ($($ty,)+)
+        where
+            $(
+                $ty: FromStr,
+                $ty::Err: Into<Box<dyn std::error::Error + Send + Sync>>,
+            )+
+        {
+            fn from_params(params: &router::Params) -> Result<Self, InvalidParam> {
+                let mut iter = params.iter().enumerate();
+                Ok(($(
+                    {
+                        let (idx, (name, value)) = iter.next().ok_or_else(|| InvalidParam::missing(params.len()))?;
+                        $ty::from_str(value).map_err(|e| InvalidParam::invalid(idx, name, value, e))?
+                    },
+                )+))
+            }
+        }
+    };
+}
+
+from_params_impl!(A);
+from_params_impl!(A, B);
+from_params_impl!(A, B, C);
+from_params_impl!(A, B, C, D);
+from_params_impl!(A, B, C, D, E);
+from_params_impl!(A, B, C, D, E, F);
+from_params_impl!(A, B, C, D, E, F, G);
+from_params_impl!(A, B, C, D, E, F, G, H);
+
 macro_rules! unsupported_type {
     ($trait_fn:ident, $name:expr) => {
         fn $trait_fn<V>(self, _: V) -> Result<V::Value, Self::Error>
@@ -519,7 +623,7 @@ mod tests {
     use xitca_unsafe_collection::futures::NowOrPanic;
 
     use crate::{
-        http::{Request, RequestExt, Uri},
+        http::{Request, RequestExt, StatusCode, Uri},
         service::{fn_service, Service},
         test::collect_string_body,
         App,
@@ -798,4 +902,88 @@ mod tests {
 
         assert_eq!(s, "such dead much unoriginal");
     }
+
+    #[derive(Debug)]
+    struct Even(u32);
+
+    impl FromStr for Even {
+        type Err = &'static str;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s.parse::<u32>() {
+                Ok(n) if n % 2 == 0 => Ok(Even(n)),
+                _ => Err("not an even number"),
+            }
+        }
+    }
+
+    async fn handler4(PathParams((name, Even(n))): PathParams<(String, Even)>) -> String {
+        format!("{name}{n}")
+    }
+
+    async fn handler5(lazy: LazyPathParams<'_, (u8, u8)>) -> &'static str {
+        assert!(lazy.parse::<()>().is_err());
+        "lazy"
+    }
+
+    #[test]
+    fn typed_extract() {
+        let service = App::new()
+            .at("/typed/:name/:num", handler_service(handler4))
+            .at("/lazy/:a", handler_service(handler5))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |uri: &'static str| {
+            let mut req = crate::http::WebRequest::default();
+            *req.uri_mut() = Uri::from_static(uri);
+            let res = service.call(req).now_or_panic().unwrap();
+            let status = res.status();
+            let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+            (status, body)
+        };
+
+        let (status, body) = call("/typed/doge/2");
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "doge2");
+
+        let (status, body) = call("/typed/doge/3");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, "uri param num: \"3\" is invalid: not an even number");
+
+        let (status, body) = call("/lazy/1");
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "lazy");
+    }
+
+    #[test]
+    fn typed_extract_errors() {
+        let service = Router::new()
+            .insert("/:id/:age", fn_service(handler))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut req = crate::http::WebRequest::default();
+        *req.uri_mut() = Uri::from_static("/996/abc");
+
+        let res = service.call(req).now_or_panic().unwrap();
+        let params = res.body().params();
+
+        let (id,) = <(u32,)>::from_params(params).unwrap();
+        assert_eq!(id, 996);
+
+        let e = <(u32, u32)>::from_params(params).unwrap_err();
+        assert_eq!(e.index(), 1);
+        assert_eq!(e.name(), Some("age"));
+        assert_eq!(e.value(), Some("abc"));
+        assert!(std::error::Error::source(&e).is_some());
+
+        let e = <(u32, String, String)>::from_params(params).unwrap_err();
+        assert_eq!(e.index(), 2);
+        assert_eq!(e.name(), None);
+        assert_eq!(e.to_string(), "uri param at index 2 is missing");
+    }
 }
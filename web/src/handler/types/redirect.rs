@@ -20,6 +20,7 @@ use crate::{
 pub struct Redirect {
     status: StatusCode,
     location: Result<HeaderValue, StatusCode>,
+    absolute: bool,
 }
 
 macro_rules! variants {
@@ -41,15 +42,31 @@ impl Redirect {
         Self {
             status,
             location: uri.try_into().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR),
+            absolute: false,
         }
     }
+
+    /// resolve location starting with `/` to absolute url with [WebContext::url_for] when responding.
+    /// location is kept as is when Redirect is used in [Responder::map].
+    pub fn absolute(mut self) -> Self {
+        self.absolute = true;
+        self
+    }
 }
 
 impl<'r, C, B> Responder<WebContext<'r, C, B>> for Redirect {
     type Response = WebResponse;
     type Error = Error<C>;
 
-    async fn respond(self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+    async fn respond(mut self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        if self.absolute {
+            if let Ok(location) = &self.location {
+                if let Some(path) = location.to_str().ok().filter(|path| path.starts_with('/')) {
+                    self.location =
+                        HeaderValue::try_from(ctx.url_for(path)).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            }
+        }
         let res = ctx.into_response(ResponseBody::empty());
         Responder::<WebContext<'r, C, B>>::map(self, res)
     }
@@ -0,0 +1,360 @@
+//! server sent events responder and type extractor.
+//!
+//! [Sse] turns a [Stream] of [Event] into `text/event-stream` response. [LastEventId] extracts `Last-Event-ID`
+//! header sent by client reconnecting to event stream so it can resume from where it left.
+//!
+//! # Examples
+//! ```rust
+//! # use std::{convert::Infallible, time::Duration};
+//! # use futures_core::stream::Stream;
+//! # use xitca_web::{
+//! #     handler::{handler_service, sse::{Event, LastEventId, Sse}},
+//! #     route::get,
+//! #     App, WebContext,
+//! # };
+//! async fn events(LastEventId(id): LastEventId<'_>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+//!     // resume from the event after last one received by client.
+//!     let start = id.and_then(|id| id.parse::<u64>().ok()).map(|id| id + 1).unwrap_or(0);
+//!
+//!     let events = futures_util::stream::iter((start..start + 3).map(|id| {
+//!         Ok(Event::default()
+//!             .id(id.to_string())
+//!             .event("tick")
+//!             .data(format!("tick {id}")))
+//!     }));
+//!
+//!     // send a comment to keep connection alive when no event is sent in 15 seconds.
+//!     Sse::new(events).keep_alive(Duration::from_secs(15))
+//! }
+//!
+//! App::new()
+//!     .at("/events", get(handler_service(events)))
+//!     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "for type infer" }));
+//! ```
+
+use core::{
+    fmt::{self, Write},
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use tokio::time::{sleep, Instant, Sleep};
+
+use crate::{
+    body::ResponseBody,
+    bytes::Bytes,
+    context::WebContext,
+    error::{BodyError, Error, InvalidHeaderValue},
+    handler::{FromRequest, Responder},
+    http::{
+        header::{HeaderName, HeaderValue, CACHE_CONTROL, CONTENT_TYPE},
+        WebResponse,
+    },
+};
+
+const LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+
+/// a single event of event stream.
+///
+/// Multi-line data and comment are split into multiple fields and reassembled by client.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::handler::sse::Event;
+/// // event named "update" with id. client sends the id as `Last-Event-ID` header when reconnecting.
+/// let event = Event::default().id("42").event("update").data("line 1\nline 2");
+///
+/// // instruct client to wait 5 seconds before reconnecting.
+/// let event = Event::default().retry(Duration::from_secs(5));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Event {
+    id: Option<String>,
+    event: Option<String>,
+    data: Option<String>,
+    retry: Option<Duration>,
+    comment: Option<String>,
+}
+
+impl Event {
+    /// set id of event. client remembers the id of last received event and sends it with `Last-Event-ID`
+    /// header when reconnecting.
+    ///
+    /// # Panics
+    /// When id contains line break or null character.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        let id = id.into();
+        assert!(
+            !id.contains(['\r', '\n', '\0']),
+            "event id must not contain line break or null"
+        );
+        self.id = Some(id);
+        self
+    }
+
+    /// set name of event. client dispatches event to listener of the name. unnamed event is dispatched as
+    /// `message` event.
+    ///
+    /// # Panics
+    /// When name contains line break.
+    pub fn event(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        assert!(!name.contains(['\r', '\n']), "event name must not contain line break");
+        self.event = Some(name);
+        self
+    }
+
+    /// set data of event.
+    pub fn data(mut self, data: impl Into<String>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    /// set reconnection time of client in milliseconds precision.
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// set comment of event. comment is ignored by client.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    fn encode(&self) -> Bytes {
+        let mut buf = String::new();
+
+        if let Some(ref comment) = self.comment {
+            write_field(&mut buf, "", comment);
+        }
+        if let Some(ref event) = self.event {
+            write_field(&mut buf, "event", event);
+        }
+        if let Some(ref data) = self.data {
+            write_field(&mut buf, "data", data);
+        }
+        if let Some(ref id) = self.id {
+            write_field(&mut buf, "id", id);
+        }
+        if let Some(retry) = self.retry {
+            let _ = writeln!(buf, "retry: {}", retry.as_millis());
+        }
+
+        buf.push('\n');
+        Bytes::from(buf)
+    }
+}
+
+// write field line for every line of value.
+fn write_field(buf: &mut String, name: &str, mut value: &str) {
+    loop {
+        let end = value.find(['\r', '\n']).unwrap_or(value.len());
+        buf.push_str(name);
+        buf.push_str(": ");
+        buf.push_str(&value[..end]);
+        buf.push('\n');
+
+        if end == value.len() {
+            return;
+        }
+
+        let skip = if value[end..].starts_with("\r\n") { 2 } else { 1 };
+        value = &value[end + skip..];
+    }
+}
+
+pin_project! {
+    /// `text/event-stream` response body from a [Stream] of [Event].
+    ///
+    /// See [module](self) level doc for example.
+    pub struct Sse<S> {
+        #[pin]
+        stream: S,
+        keep_alive: Option<Duration>,
+        sleep: Option<Pin<Box<Sleep>>>,
+    }
+}
+
+impl<S> fmt::Debug for Sse<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sse").field("keep_alive", &self.keep_alive).finish()
+    }
+}
+
+impl<S> Sse<S> {
+    /// Construct a new event stream response from given stream.
+    pub const fn new(stream: S) -> Self {
+        Self {
+            stream,
+            keep_alive: None,
+            sleep: None,
+        }
+    }
+
+    /// Send a comment when no event is sent for given duration. It keeps idle connection from being closed by
+    /// proxies and client.
+    pub fn keep_alive(mut self, dur: Duration) -> Self {
+        self.keep_alive = Some(dur);
+        self
+    }
+}
+
+impl<S, E> Stream for Sse<S>
+where
+    S: Stream<Item = Result<Event, E>>,
+    E: Into<BodyError>,
+{
+    type Item = Result<Bytes, BodyError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        match this.stream.poll_next(cx) {
+            Poll::Ready(Some(res)) => {
+                if let (Some(dur), Some(sleep)) = (*this.keep_alive, this.sleep.as_mut()) {
+                    sleep.as_mut().reset(Instant::now() + dur);
+                }
+                Poll::Ready(Some(res.map(|event| event.encode()).map_err(Into::into)))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => {
+                let Some(dur) = *this.keep_alive else {
+                    return Poll::Pending;
+                };
+                let sleep = this.sleep.get_or_insert_with(|| Box::pin(sleep(dur)));
+                ready!(sleep.as_mut().poll(cx));
+                sleep.as_mut().reset(Instant::now() + dur);
+                Poll::Ready(Some(Ok(Bytes::from_static(b":\n\n"))))
+            }
+        }
+    }
+}
+
+impl<'r, C, B, S, E> Responder<WebContext<'r, C, B>> for Sse<S>
+where
+    S: Stream<Item = Result<Event, E>> + 'static,
+    E: Into<BodyError>,
+{
+    type Response = WebResponse;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn respond(self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let res = ctx.into_response(ResponseBody::empty());
+        Responder::<WebContext<'r, C, B>>::map(self, res)
+    }
+
+    fn map(self, res: Self::Response) -> Result<Self::Response, Self::Error> {
+        let mut res = res.map(|_| ResponseBody::box_stream(self));
+        res.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+        res.headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        Ok(res)
+    }
+}
+
+/// type extractor for `Last-Event-ID` header of reconnecting event stream request.
+///
+/// None is extracted when header is absent which is the case for client connecting for the first time.
+///
+/// on failure [InvalidHeaderValue] error would be returned when header value is not valid UTF-8, which would
+/// generate a "400 BadRequest" http response.
+#[derive(Debug)]
+pub struct LastEventId<'a>(pub Option<&'a str>);
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for LastEventId<'a> {
+    type Type<'b> = LastEventId<'b>;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        match ctx.req().headers().get(LAST_EVENT_ID) {
+            Some(value) => core::str::from_utf8(value.as_bytes())
+                .map(|id| LastEventId(Some(id)))
+                .map_err(|_| Error::from_service(InvalidHeaderValue(LAST_EVENT_ID))),
+            None => Ok(LastEventId(None)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::convert::Infallible;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service, http::WebRequest, route::get, service::Service, test::collect_string_body, App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn encode() {
+        let event = Event::default()
+            .comment("hello")
+            .event("update")
+            .data("line 1\nline 2\r\nline 3\rline 4")
+            .id("42")
+            .retry(Duration::from_secs(3));
+
+        assert_eq!(
+            event.encode(),
+            ": hello\nevent: update\ndata: line 1\ndata: line 2\ndata: line 3\ndata: line 4\nid: 42\nretry: 3000\n\n"
+        );
+
+        assert_eq!(Event::default().data("").encode(), "data: \n\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_id() {
+        let _ = Event::default().id("4\n2");
+    }
+
+    #[test]
+    fn respond() {
+        async fn handler(LastEventId(id): LastEventId<'_>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+            let start = id.map(|id| id.parse::<u32>().unwrap() + 1).unwrap_or(0);
+            let events = (start..start + 2).map(|id| Ok(Event::default().id(id.to_string()).data("996")));
+            Sse::new(futures_util::stream::iter(events))
+        }
+
+        let service = App::new()
+            .at("/", get(handler_service(handler)))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(WebRequest::default()).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/event-stream");
+        assert_eq!(res.headers().get(CACHE_CONTROL).unwrap(), "no-cache");
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, "data: 996\nid: 0\n\ndata: 996\nid: 1\n\n");
+
+        let mut req = WebRequest::default();
+        req.headers_mut().insert(LAST_EVENT_ID, HeaderValue::from_static("1"));
+        let res = service.call(req).now_or_panic().unwrap();
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, "data: 996\nid: 2\n\ndata: 996\nid: 3\n\n");
+    }
+
+    #[tokio::test]
+    async fn keep_alive() {
+        let mut sse =
+            core::pin::pin!(Sse::new(futures_util::stream::pending::<Result<Event, Infallible>>())
+                .keep_alive(Duration::from_millis(1)));
+
+        let chunk = core::future::poll_fn(|cx| sse.as_mut().poll_next(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), ":\n\n");
+    }
+}
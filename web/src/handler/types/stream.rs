@@ -0,0 +1,190 @@
+//! response generator for streaming body.
+
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use xitca_http::body::exact_body_hint;
+
+use crate::{
+    body::ResponseBody,
+    bytes::Bytes,
+    context::WebContext,
+    error::{BodyError, Error},
+    handler::Responder,
+    http::{header::CONTENT_TYPE, HeaderValue, WebResponse},
+};
+
+pin_project! {
+    /// Response body from a [Stream] with optional content type and content length hint.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use xitca_web::{bytes::Bytes, handler::{handler_service, stream::StreamBody}, http::HeaderValue, App};
+    /// async fn handler() -> StreamBody<impl futures_core::stream::Stream<Item = Result<Bytes, Infallible>>> {
+    ///     let body = b"hello,world!";
+    ///     let stream = futures_util::stream::once(async { Ok(Bytes::from_static(body)) });
+    ///     StreamBody::new(stream)
+    ///         .content_type(HeaderValue::from_static("text/plain; charset=utf-8"))
+    ///         .content_length(body.len())
+    /// }
+    ///
+    /// App::new().at("/", handler_service(handler))
+    /// # .at("/nah", handler_service(|_: &xitca_web::WebContext<'_>| async { "for type infer" }));
+    /// ```
+    pub struct StreamBody<S> {
+        #[pin]
+        stream: S,
+        content_type: Option<HeaderValue>,
+        len: Option<usize>,
+    }
+}
+
+impl<S> fmt::Debug for StreamBody<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamBody")
+            .field("content_type", &self.content_type)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<S> StreamBody<S> {
+    /// Construct a new StreamBody from given stream.
+    ///
+    /// Without content length hint response body is sent with chunked transfer encoding (for Http/1.1).
+    pub const fn new(stream: S) -> Self {
+        Self {
+            stream,
+            content_type: None,
+            len: None,
+        }
+    }
+
+    /// Set `content-type` header value of response.
+    pub fn content_type(mut self, value: HeaderValue) -> Self {
+        self.content_type = Some(value);
+        self
+    }
+
+    /// Set exact length of response body in bytes. It's used for generating `content-length` header.
+    ///
+    /// Stream must produce exactly the hinted count of bytes. Otherwise connection would be closed
+    /// abnormally.
+    pub fn content_length(mut self, len: usize) -> Self {
+        self.len = Some(len);
+        self
+    }
+}
+
+impl<S> Stream for StreamBody<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().stream.poll_next(cx)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.len {
+            Some(len) => exact_body_hint(len),
+            // size hint of inner stream counts items rather than bytes.
+            None => (0, None),
+        }
+    }
+}
+
+impl<'r, C, B, S, T, E> Responder<WebContext<'r, C, B>> for StreamBody<S>
+where
+    S: Stream<Item = Result<T, E>> + 'static,
+    T: Into<Bytes>,
+    E: Into<BodyError>,
+{
+    type Response = WebResponse;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn respond(mut self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let content_type = self.content_type.take();
+        let res = ctx.into_response(ResponseBody::box_stream(self));
+        Ok(with_content_type(res, content_type))
+    }
+
+    fn map(mut self, res: Self::Response) -> Result<Self::Response, Self::Error> {
+        let content_type = self.content_type.take();
+        let res = res.map(|_| ResponseBody::box_stream(self));
+        Ok(with_content_type(res, content_type))
+    }
+}
+
+fn with_content_type(mut res: WebResponse, content_type: Option<HeaderValue>) -> WebResponse {
+    if let Some(value) = content_type {
+        res.headers_mut().insert(CONTENT_TYPE, value);
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use core::convert::Infallible;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::BodyStream,
+        handler::handler_service,
+        http::{const_header_value::TEXT_UTF8, WebRequest},
+        service::Service,
+        test::collect_body,
+        App,
+    };
+
+    use super::*;
+
+    fn stream() -> impl Stream<Item = Result<Bytes, Infallible>> {
+        xitca_http::body::Once::new(Bytes::from_static(b"996"))
+    }
+
+    #[test]
+    fn size_hint() {
+        let body = StreamBody::new(futures_util::stream::iter([Ok::<_, Infallible>(Bytes::from_static(
+            b"996",
+        ))]));
+        assert_eq!(body.size_hint(), (0, None));
+
+        let body = StreamBody::new(stream()).content_length(5);
+        assert_eq!(body.size_hint(), exact_body_hint(5));
+    }
+
+    #[test]
+    fn respond() {
+        async fn handler() -> StreamBody<impl BodyStream<Chunk = Bytes>> {
+            StreamBody::new(stream()).content_type(TEXT_UTF8)
+        }
+
+        let res = App::new()
+            .at("/", handler_service(handler))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(WebRequest::default())
+            .now_or_panic()
+            .unwrap();
+
+        assert_eq!(res.status().as_u16(), 200);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), TEXT_UTF8);
+
+        let body = collect_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, b"996");
+    }
+}
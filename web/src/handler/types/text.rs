@@ -52,6 +52,10 @@ text_utf8!(Box<str>);
 text_utf8!(std::borrow::Cow<'static, str>);
 
 /// text responder and service type that would extend [`CONTENT_TYPE`] header with [`TEXT_UTF8`] value to [`WebResponse`].
+///
+/// Response of `&'static str` and other sized text types carries only [`CONTENT_TYPE`] header with a sized body.
+/// It's eligible for http/1 encoding fast path where pre-computed response head is written without iterating
+/// headers.
 #[derive(Clone)]
 pub struct Text<T>(pub T);
 
@@ -99,3 +103,31 @@ where
         self.clone().respond(ctx).await
     }
 }
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use xitca_http::body::BodySize;
+
+    use crate::{handler::handler_service, http::Request, App};
+
+    use super::*;
+
+    #[test]
+    fn static_str() {
+        let res = App::new()
+            .at("/", handler_service(|| async { "996" }))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(Request::default())
+            .now_or_panic()
+            .unwrap();
+
+        assert_eq!(res.headers().len(), 1);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), TEXT_UTF8);
+        assert_eq!(BodySize::from_stream(res.body()), BodySize::Sized(3));
+    }
+}
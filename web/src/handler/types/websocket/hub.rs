@@ -0,0 +1,337 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use http_ws::{CloseCode, Message as WsMessage, ResponseSender};
+use tokio::{sync::mpsc, task::JoinHandle};
+use xitca_unsafe_collection::bytes::BytesStr;
+
+use crate::bytes::Bytes;
+
+/// Topic based broadcast hub for websocket connections.
+///
+/// Every connection registered with [Hub::member] gets a bounded message buffer. Messages broadcasted to a
+/// topic are queued to the buffer of every member joined the topic without waiting. When a member's buffer is
+/// full the member is considered a slow client and evicted from the hub. An evicted member receives a close
+/// message with [CloseCode::Policy] after it's buffered messages are sent.
+///
+/// Hub is cheap to clone and can be shared between threads. e.g: as application state.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{
+/// #       handler_service,
+/// #       state::StateRef,
+/// #       websocket::{Hub, Message, WebSocket},
+/// #   },
+/// #   route::get,
+/// #   App,
+/// # };
+/// async fn handler(mut ws: WebSocket, StateRef(hub): StateRef<'_, Hub>) -> WebSocket {
+///     // register connection and join topic.
+///     let member = hub.member(ws.msg_sender());
+///     member.join("lobby");
+///
+///     ws.on_msg(move |_, msg| {
+///         // fan out text message to all members of topic.
+///         if let Message::Text(txt) = msg {
+///             member.hub().text("lobby", txt);
+///         }
+///         Box::pin(async {})
+///     });
+///
+///     // member is dropped when connection is closed and it leaves all joined topics.
+///     ws
+/// }
+///
+/// # fn _main() -> std::io::Result<()> {
+/// App::new()
+///     .with_state(Hub::new().buffer(64))
+///     .at("/ws", get(handler_service(handler)))
+///     .serve()
+///     .bind("localhost:8080")?
+///     .run()
+///     .wait()
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Hub {
+    inner: Arc<Mutex<Inner>>,
+    buffer: usize,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_id: u64,
+    topics: HashMap<Box<str>, HashSet<u64>>,
+    members: HashMap<u64, Entry>,
+}
+
+struct Entry {
+    tx: mpsc::Sender<WsMessage>,
+    topics: HashSet<Box<str>>,
+}
+
+impl Inner {
+    fn remove(&mut self, id: u64) {
+        if let Some(entry) = self.members.remove(&id) {
+            for topic in entry.topics {
+                self.leave(&topic, id);
+            }
+        }
+    }
+
+    fn leave(&mut self, topic: &str, id: u64) {
+        if let Some(ids) = self.topics.get_mut(topic) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.topics.remove(topic);
+            }
+        }
+    }
+}
+
+impl Default for Hub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hub {
+    /// construct a new hub with default buffer size of 32 messages per member.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner::default())),
+            buffer: 32,
+        }
+    }
+
+    /// set max number of buffered messages per member. member is evicted when it's buffer is full.
+    ///
+    /// # Panics
+    /// when 0 is passed as argument.
+    pub fn buffer(mut self, size: usize) -> Self {
+        assert!(size > 0, "buffer size MUST be none 0");
+        self.buffer = size;
+        self
+    }
+
+    /// register a websocket connection to hub with it's message sender.
+    ///
+    /// Messages are forwarded to connection by a task spawned with [tokio::task::spawn_local]. It must be called
+    /// inside the context of xitca-web's server. e.g: in handler function.
+    pub fn member(&self, tx: &ResponseSender) -> Member {
+        let (hub_tx, mut rx) = mpsc::channel(self.buffer);
+
+        let id = {
+            let mut inner = self.inner.lock().unwrap();
+            let id = inner.next_id;
+            inner.next_id += 1;
+            inner.members.insert(
+                id,
+                Entry {
+                    tx: hub_tx,
+                    topics: HashSet::new(),
+                },
+            );
+            id
+        };
+
+        let tx = tx.downgrade();
+
+        let task = tokio::task::spawn_local(async move {
+            while let Some(msg) = rx.recv().await {
+                match tx.upgrade() {
+                    Some(tx) if tx.send(msg).await.is_ok() => {}
+                    _ => return,
+                }
+            }
+
+            // channel is only closed by hub when member is evicted. the forwarding task is aborted when member
+            // is dropped.
+            if let Some(tx) = tx.upgrade() {
+                let _ = tx.send(WsMessage::Close(Some(CloseCode::Policy.into()))).await;
+            }
+        });
+
+        Member {
+            id,
+            hub: self.clone(),
+            task,
+        }
+    }
+
+    /// broadcast text message to all members of given topic. return the number of members the message is queued to.
+    pub fn text(&self, topic: &str, txt: impl Into<BytesStr>) -> usize {
+        let txt = txt.into().into_inner();
+        self.broadcast(topic, || WsMessage::Text(txt.clone()))
+    }
+
+    /// broadcast binary message to all members of given topic. return the number of members the message is queued
+    /// to.
+    pub fn binary(&self, topic: &str, bin: impl Into<Bytes>) -> usize {
+        let bin = bin.into();
+        self.broadcast(topic, || WsMessage::Binary(bin.clone()))
+    }
+
+    /// get the number of members joined given topic.
+    pub fn members(&self, topic: &str) -> usize {
+        self.inner.lock().unwrap().topics.get(topic).map_or(0, HashSet::len)
+    }
+
+    fn broadcast(&self, topic: &str, msg: impl Fn() -> WsMessage) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+        let Inner { topics, members, .. } = &mut *inner;
+
+        let Some(ids) = topics.get(topic) else {
+            return 0;
+        };
+
+        let mut sent = 0;
+        let mut evict = Vec::new();
+
+        for id in ids {
+            // full buffer means slow client and closed buffer means connection is gone. evict member in both cases.
+            match members.get(id).map(|entry| entry.tx.try_send(msg())) {
+                Some(Ok(_)) => sent += 1,
+                _ => evict.push(*id),
+            }
+        }
+
+        for id in evict {
+            inner.remove(id);
+        }
+
+        sent
+    }
+}
+
+/// A websocket connection registered to [Hub]. Member leaves all joined topics when dropped.
+pub struct Member {
+    id: u64,
+    hub: Hub,
+    task: JoinHandle<()>,
+}
+
+impl Member {
+    /// join given topic. return false when member is already evicted from hub.
+    pub fn join(&self, topic: &str) -> bool {
+        let mut inner = self.hub.inner.lock().unwrap();
+        let Some(entry) = inner.members.get_mut(&self.id) else {
+            return false;
+        };
+        entry.topics.insert(Box::from(topic));
+        inner.topics.entry(Box::from(topic)).or_default().insert(self.id);
+        true
+    }
+
+    /// leave given topic.
+    pub fn leave(&self, topic: &str) {
+        let mut inner = self.hub.inner.lock().unwrap();
+        if let Some(entry) = inner.members.get_mut(&self.id) {
+            entry.topics.remove(topic);
+            inner.leave(topic, self.id);
+        }
+    }
+
+    /// check if member is evicted from hub.
+    pub fn is_evicted(&self) -> bool {
+        !self.hub.inner.lock().unwrap().members.contains_key(&self.id)
+    }
+
+    /// get a reference of the hub member registered to.
+    pub fn hub(&self) -> &Hub {
+        &self.hub
+    }
+}
+
+impl Drop for Member {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.hub.inner.lock().unwrap().remove(self.id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{future::poll_fn, pin::pin};
+
+    use futures_core::stream::Stream;
+    use http_ws::{RequestStream, ResponseStream};
+    use tokio::task::LocalSet;
+
+    use crate::body::RequestBody;
+
+    use super::*;
+
+    fn response_pair() -> (ResponseStream, ResponseSender) {
+        RequestStream::new(RequestBody::default()).response_stream()
+    }
+
+    async fn next(stream: &mut ResponseStream) -> Bytes {
+        let mut stream = pin!(stream);
+        poll_fn(|cx| stream.as_mut().poll_next(cx)).await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn broadcast() {
+        LocalSet::new()
+            .run_until(async {
+                let hub = Hub::new();
+
+                let (mut rx1, tx1) = response_pair();
+                let (mut rx2, tx2) = response_pair();
+
+                let member1 = hub.member(&tx1);
+                let member2 = hub.member(&tx2);
+
+                assert!(member1.join("a"));
+                assert!(member2.join("a"));
+                assert!(member2.join("b"));
+
+                assert_eq!(hub.members("a"), 2);
+                assert_eq!(hub.text("a", "hi"), 2);
+                assert_eq!(hub.binary("b", Bytes::from_static(b"96")), 1);
+                assert_eq!(hub.text("c", "hi"), 0);
+
+                assert_eq!(next(&mut rx1).await, &b"\x81\x02hi"[..]);
+                assert_eq!(next(&mut rx2).await, &b"\x81\x02hi"[..]);
+                assert_eq!(next(&mut rx2).await, &b"\x82\x0296"[..]);
+
+                member2.leave("a");
+                assert_eq!(hub.members("a"), 1);
+
+                drop(member2);
+                assert_eq!(hub.members("b"), 0);
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn evict_slow_member() {
+        LocalSet::new()
+            .run_until(async {
+                let hub = Hub::new().buffer(1);
+
+                let (mut rx, tx) = response_pair();
+
+                let member = hub.member(&tx);
+                member.join("a");
+
+                // forwarding task does not run until yield so the second message overflows buffer.
+                assert_eq!(hub.text("a", "1"), 1);
+                assert_eq!(hub.text("a", "2"), 0);
+
+                assert!(member.is_evicted());
+                assert!(!member.join("a"));
+                assert_eq!(hub.members("a"), 0);
+
+                assert_eq!(next(&mut rx).await, &b"\x81\x011"[..]);
+                // close frame with policy violation code.
+                assert_eq!(next(&mut rx).await, &b"\x88\x02\x03\xf0"[..]);
+            })
+            .await
+    }
+}
@@ -31,8 +31,12 @@ use crate::{
     service::Service,
 };
 
+mod hub;
+
 pub use http_ws::{ResponseSender, ResponseWeakSender};
 
+pub use self::hub::{Hub, Member};
+
 /// simplified websocket message type.
 /// for more variant of message please reference [http_ws::Message] type.
 #[derive(Debug, Eq, PartialEq)]
@@ -0,0 +1,504 @@
+//! locale negotiation and localized message formatting.
+//!
+//! [I18n] middleware negotiates a [Locale] for every request it enclosed from the following sources in order:
+//! - query parameter. (when [I18n::query] is set)
+//! - cookie. (when [I18n::cookie] is set)
+//! - `Accept-Language` header weighted by it's quality value.
+//! - default locale.
+//!
+//! Negotiated [Locale] is stored in request extensions and can be extracted by handlers, error handlers and
+//! middlewares. Messages are formatted from [Catalog] attached to [I18n]. A [MessageCatalog] is provided for
+//! simple use case and complex ones like [Fluent](https://projectfluent.org/) bundles can be integrated by
+//! implementing [Catalog] trait.
+//!
+//! # Examples
+//! ```rust
+//! # use xitca_web::{handler::handler_service, i18n::{I18n, Locale, MessageCatalog}, route::get, App};
+//! # fn _main() -> std::io::Result<()> {
+//! let catalog = MessageCatalog::new()
+//!     .add_resource("en", "hello = Hello, { $name }!")
+//!     .add_resource("zh-CN", "hello = 你好, { $name }!");
+//!
+//! App::new()
+//!     .at("/", get(handler_service(|locale: Locale| async move {
+//!         locale.format("hello", &[("name", "xitca")])
+//!     })))
+//!     .enclosed(I18n::new("en").locale("zh-CN").query("lang").cookie("lang").catalog(catalog))
+//!     .serve()
+//!     .bind("localhost:8080")?
+//!     .run()
+//!     .wait()
+//! # }
+//! ```
+//!
+//! # Fluent integration
+//! ```rust
+//! # use xitca_web::i18n::Catalog;
+//! // a type wrapping fluent bundles of all supported locales.
+//! struct FluentCatalog {
+//!     // bundles: HashMap<String, fluent::FluentBundle<fluent::FluentResource>>
+//! }
+//!
+//! impl Catalog for FluentCatalog {
+//!     fn format(&self, locale: &str, id: &str, args: &[(&str, &str)]) -> Option<String> {
+//!         // let bundle = self.bundles.get(locale)?;
+//!         // let pattern = bundle.get_message(id)?.value()?;
+//!         // let args = fluent::FluentArgs::from_iter(args.iter().copied());
+//!         // Some(bundle.format_pattern(pattern, Some(&args), &mut vec![]).into_owned())
+//!         None
+//!     }
+//! }
+//! ```
+
+use core::fmt;
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    context::WebContext,
+    error::{Error, ExtensionNotFound},
+    handler::FromRequest,
+    http::{
+        header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE, COOKIE},
+        intern::intern,
+        WebResponse,
+    },
+    service::{ready::ReadyService, Service},
+};
+
+/// Source of localized messages.
+pub trait Catalog: Send + Sync + 'static {
+    /// format message with given id and named arguments for locale. None when message is not found.
+    fn format(&self, locale: &str, id: &str, args: &[(&str, &str)]) -> Option<String>;
+}
+
+/// A simple in memory [Catalog].
+///
+/// Messages are added in subset of Fluent syntax:
+/// - `id = value` for message. Indented lines following it are joined to value with new line.
+/// - `{ $name }` placeable for named argument. Unknown argument is rendered as it's name.
+/// - lines start with `#` are comments.
+///
+/// Selectors, terms and attributes of Fluent syntax are not supported.
+#[derive(Default)]
+pub struct MessageCatalog {
+    messages: HashMap<String, HashMap<String, String>>,
+}
+
+impl MessageCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add single message for locale.
+    pub fn add(mut self, locale: &str, id: impl Into<String>, value: impl Into<String>) -> Self {
+        self.messages
+            .entry(locale.to_ascii_lowercase())
+            .or_default()
+            .insert(id.into(), value.into());
+        self
+    }
+
+    /// add messages for locale from resource text. See [MessageCatalog] for supported syntax.
+    pub fn add_resource(mut self, locale: &str, resource: &str) -> Self {
+        let messages = self.messages.entry(locale.to_ascii_lowercase()).or_default();
+        let mut last = None;
+
+        for line in resource.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with(char::is_whitespace) {
+                if let Some(value) = last.as_ref().and_then(|id| messages.get_mut(id)) {
+                    if !value.is_empty() {
+                        value.push('\n');
+                    }
+                    value.push_str(line.trim());
+                }
+                continue;
+            }
+
+            last = line.split_once('=').map(|(id, value)| {
+                let id = id.trim().to_owned();
+                messages.insert(id.clone(), value.trim().to_owned());
+                id
+            });
+        }
+
+        self
+    }
+}
+
+impl Catalog for MessageCatalog {
+    fn format(&self, locale: &str, id: &str, args: &[(&str, &str)]) -> Option<String> {
+        let pattern = self.messages.get(&locale.to_ascii_lowercase())?.get(id)?;
+
+        let mut res = String::with_capacity(pattern.len());
+        let mut rest = pattern.as_str();
+
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            res.push_str(&rest[..start]);
+            let placeable = rest[start + 1..start + end].trim();
+            match placeable.strip_prefix('$') {
+                Some(name) => match args.iter().find(|(n, _)| *n == name) {
+                    Some((_, value)) => res.push_str(value),
+                    None => res.push_str(name),
+                },
+                // string literal placeable. e.g: { "{" }
+                None => res.push_str(placeable.trim_matches('"')),
+            }
+            rest = &rest[start + end + 1..];
+        }
+
+        res.push_str(rest);
+        Some(res)
+    }
+}
+
+/// Middleware for negotiating [Locale] of request. See [module](self) level doc for detail.
+///
+/// Negotiated locale is written to `Content-Language` header of response when it's not already set.
+#[derive(Clone)]
+pub struct I18n {
+    shared: Shared,
+}
+
+#[derive(Clone)]
+struct Shared {
+    // supported locales. the first one is default.
+    locales: Vec<String>,
+    query: Option<String>,
+    cookie: Option<String>,
+    catalog: Option<Arc<dyn Catalog>>,
+}
+
+impl I18n {
+    /// construct middleware with default locale used when negotiation failed.
+    pub fn new(default: impl Into<String>) -> Self {
+        Self {
+            shared: Shared {
+                locales: vec![default.into()],
+                query: None,
+                cookie: None,
+                catalog: None,
+            },
+        }
+    }
+
+    /// add supported locale. e.g: `en-US`, `zh-CN`
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.shared.locales.push(locale.into());
+        self
+    }
+
+    /// negotiate locale from query parameter with given name. e.g: `/?lang=en-US`
+    pub fn query(mut self, name: impl Into<String>) -> Self {
+        self.shared.query = Some(name.into());
+        self
+    }
+
+    /// negotiate locale from cookie with given name.
+    pub fn cookie(mut self, name: impl Into<String>) -> Self {
+        self.shared.cookie = Some(name.into());
+        self
+    }
+
+    /// set [Catalog] for formatting localized messages with [Locale].
+    pub fn catalog<C>(mut self, catalog: C) -> Self
+    where
+        C: Catalog,
+    {
+        self.shared.catalog = Some(Arc::new(catalog));
+        self
+    }
+}
+
+impl Shared {
+    // find index of supported locale matching given language tag. exact match is preferred over match of
+    // primary language subtag. e.g: `en-GB` matches `en-US` when `en-GB` and `en` are not supported.
+    fn find(&self, tag: &str) -> Option<usize> {
+        let tag = tag.trim();
+        if tag.is_empty() || tag == "*" {
+            return None;
+        }
+        self.locales
+            .iter()
+            .position(|locale| locale.eq_ignore_ascii_case(tag))
+            .or_else(|| {
+                let primary = primary_subtag(tag);
+                self.locales
+                    .iter()
+                    .position(|locale| primary_subtag(locale).eq_ignore_ascii_case(primary))
+            })
+    }
+
+    fn negotiate<B>(&self, req: &crate::http::Request<B>) -> usize {
+        if let Some(name) = self.query.as_deref() {
+            let found = req
+                .uri()
+                .query()
+                .into_iter()
+                .flat_map(|query| query.split('&'))
+                .filter_map(|pair| pair.split_once('='))
+                .find_map(|(k, v)| (k == name).then(|| self.find(v)).flatten());
+            if let Some(idx) = found {
+                return idx;
+            }
+        }
+
+        if let Some(name) = self.cookie.as_deref() {
+            let found = req
+                .headers()
+                .get_all(COOKIE)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(';'))
+                .filter_map(|pair| pair.trim().split_once('='))
+                .find_map(|(k, v)| (k == name).then(|| self.find(v.trim_matches('"'))).flatten());
+            if let Some(idx) = found {
+                return idx;
+            }
+        }
+
+        let mut tags = req
+            .headers()
+            .get_all(ACCEPT_LANGUAGE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|tag| {
+                let mut parts = tag.split(';');
+                let tag = parts.next()?.trim();
+                let q = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (q > 0.0).then_some((tag, q))
+            })
+            .collect::<Vec<_>>();
+
+        // stable sort keeps header order for tags with the same quality value.
+        tags.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        tags.into_iter().find_map(|(tag, _)| self.find(tag)).unwrap_or(0)
+    }
+}
+
+fn primary_subtag(tag: &str) -> &str {
+    tag.split(['-', '_']).next().unwrap_or(tag)
+}
+
+/// Negotiated locale of request. It can be extracted from request after [I18n] middleware and
+/// messages can be formatted from it's [Catalog].
+#[derive(Clone)]
+pub struct Locale {
+    idx: usize,
+    shared: Arc<Shared>,
+}
+
+impl fmt::Debug for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Locale").field(&self.as_str()).finish()
+    }
+}
+
+impl Locale {
+    /// language tag of negotiated locale.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.shared.locales[self.idx]
+    }
+
+    /// check if negotiated locale is the default one.
+    #[inline]
+    pub fn is_default(&self) -> bool {
+        self.idx == 0
+    }
+
+    /// format message with given id. See [Locale::format] for detail.
+    pub fn message(&self, id: &str) -> String {
+        self.format(id, &[])
+    }
+
+    /// format message with given id and named arguments. When message is not found in catalog for negotiated
+    /// locale the default locale is tried and the id itself is returned as fallback.
+    pub fn format(&self, id: &str, args: &[(&str, &str)]) -> String {
+        self.shared
+            .catalog
+            .as_ref()
+            .and_then(|catalog| {
+                catalog
+                    .format(self.as_str(), id, args)
+                    .or_else(|| catalog.format(&self.shared.locales[0], id, args))
+            })
+            .unwrap_or_else(|| id.to_owned())
+    }
+}
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for Locale {
+    type Type<'b> = Locale;
+    type Error = Error<C>;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        ctx.req()
+            .extensions()
+            .get::<Locale>()
+            .cloned()
+            .ok_or_else(|| Error::from_service(ExtensionNotFound::from_type::<Locale>()))
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for I18n {
+    type Response = service::I18nService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::I18nService {
+            service,
+            shared: Arc::new(self.shared.clone()),
+        })
+    }
+}
+
+mod service {
+    use super::*;
+
+    pub struct I18nService<S> {
+        pub(super) service: S,
+        pub(super) shared: Arc<Shared>,
+    }
+
+    impl<'r, C, B, S, ResB, SE> Service<WebContext<'r, C, B>> for I18nService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = SE>,
+    {
+        type Response = WebResponse<ResB>;
+        type Error = SE;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let locale = Locale {
+                idx: self.shared.negotiate(ctx.req()),
+                shared: self.shared.clone(),
+            };
+
+            let value = intern(locale.as_str()).ok();
+            ctx.req_mut().extensions_mut().insert(locale);
+
+            let mut res = self.service.call(ctx).await?;
+            if let Some(value) = value {
+                res.headers_mut().entry(CONTENT_LANGUAGE).or_insert(value);
+            }
+            Ok(res)
+        }
+    }
+
+    impl<S> ReadyService for I18nService<S>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{header::HeaderValue, Request, Uri},
+        test::collect_body,
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn negotiate() {
+        let i18n = I18n::new("en")
+            .locale("en-GB")
+            .locale("zh-CN")
+            .query("lang")
+            .cookie("lang");
+        let shared = &i18n.shared;
+
+        let negotiate = |uri: &'static str, headers: &[(&'static str, &'static str)]| {
+            let mut req = Request::new(());
+            *req.uri_mut() = Uri::from_static(uri);
+            for (name, value) in headers {
+                req.headers_mut().append(*name, HeaderValue::from_static(value));
+            }
+            shared.locales[shared.negotiate(&req)].as_str()
+        };
+
+        assert_eq!(negotiate("/", &[]), "en");
+        assert_eq!(negotiate("/?foo=bar&lang=zh-cn", &[]), "zh-CN");
+        assert_eq!(negotiate("/?lang=fr", &[("cookie", "a=b; lang=en-GB")]), "en-GB");
+        assert_eq!(
+            negotiate("/", &[("accept-language", "fr;q=0.9, zh-TW;q=0.8, en-GB;q=0.5")]),
+            "zh-CN"
+        );
+        assert_eq!(negotiate("/", &[("accept-language", "en-US, en-GB;q=0")]), "en");
+        assert_eq!(negotiate("/", &[("accept-language", "*, fr")]), "en");
+    }
+
+    #[test]
+    fn catalog() {
+        let catalog = MessageCatalog::new()
+            .add_resource(
+                "en",
+                "# comment\nhello = Hello, { $name }!\nmulti =\n    line1\n    line2\nbrace = { \"{\" }",
+            )
+            .add("zh-CN", "hello", "你好, { $name }!");
+
+        assert_eq!(catalog.format("en", "hello", &[("name", "a")]).unwrap(), "Hello, a!");
+        assert_eq!(catalog.format("en", "hello", &[]).unwrap(), "Hello, name!");
+        assert_eq!(catalog.format("en", "multi", &[]).unwrap(), "line1\nline2");
+        assert_eq!(catalog.format("en", "brace", &[]).unwrap(), "{");
+        assert_eq!(catalog.format("zh-cn", "hello", &[("name", "a")]).unwrap(), "你好, a!");
+        assert!(catalog.format("fr", "hello", &[]).is_none());
+    }
+
+    #[test]
+    fn extract() {
+        let catalog = MessageCatalog::new()
+            .add("en", "hello", "hello")
+            .add("en", "bye", "bye")
+            .add("zh-CN", "hello", "你好");
+
+        let service = App::new()
+            .at(
+                "/",
+                handler_service(|locale: Locale| async move {
+                    format!(
+                        "{} {} {}",
+                        locale.message("hello"),
+                        locale.message("bye"),
+                        locale.message("none")
+                    )
+                }),
+            )
+            .enclosed(I18n::new("en").locale("zh-CN").catalog(catalog))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut req = Request::default();
+        req.headers_mut()
+            .insert(ACCEPT_LANGUAGE, HeaderValue::from_static("zh-CN,zh;q=0.9"));
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.headers().get(CONTENT_LANGUAGE).unwrap(), "zh-CN");
+        let body = collect_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, "你好 bye none".as_bytes());
+    }
+}
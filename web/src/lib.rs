@@ -262,12 +262,22 @@ mod context;
 mod server;
 
 pub mod body;
+pub mod config;
 pub mod error;
 pub mod handler;
 pub mod middleware;
 pub mod service;
 pub mod test;
 
+#[cfg(feature = "openapi")]
+pub mod openapi;
+
+#[cfg(feature = "acme")]
+pub mod acme;
+
+#[cfg(feature = "i18n")]
+pub mod i18n;
+
 #[cfg(feature = "codegen")]
 pub mod codegen {
     //! macro code generation module.
@@ -306,6 +316,49 @@ pub mod codegen {
     /// ```
     pub use xitca_codegen::State;
 
+    /// Derive macro for struct that all fields are extractors. The struct can be used as a single aggregate handler
+    /// argument instead of a long tuple of extractors.
+    ///
+    /// Fields are extracted in order of declaration and every field's extractor must have
+    /// [Error](crate::error::Error) as it's error type. When a field fails to extract the error is wrapped in
+    /// [ExtractError](crate::error::ExtractError) carrying the name of field (index for tuple struct) while response
+    /// generation is still forwarded to the error of field.
+    ///
+    /// Struct can have at most one lifetime param which would be used as the lifetime of borrowed extractors.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use xitca_web::{
+    /// #   codegen::Extract,
+    /// #   handler::{handler_service, header::{self, HeaderRef}, path::PathRef, state::StateRef},
+    /// #   App, WebContext,
+    /// # };
+    /// // all fields are extractors.
+    /// #[derive(Extract)]
+    /// struct Args<'a> {
+    ///     path: PathRef<'a>,
+    ///     host: HeaderRef<'a, { header::HOST }>,
+    ///     state: StateRef<'a, String>,
+    /// }
+    ///
+    /// // receive the struct as one handler argument.
+    /// async fn index(args: Args<'_>) -> String {
+    ///     format!("{} from {:?} with {}", args.path.0, args.host.to_str(), args.state)
+    /// }
+    ///
+    /// # async fn nah(_: &WebContext<'_, String>) -> &'static str {
+    /// #   // needed to infer the body type of request
+    /// #   ""
+    /// # }
+    /// # fn app() {
+    /// App::new()
+    ///     .with_state(String::from("state"))
+    ///     .at("/", handler_service(index))
+    /// #   .at("/nah", handler_service(nah));
+    /// # }
+    /// ```
+    pub use xitca_codegen::Extract;
+
     pub use xitca_codegen::route;
 
     pub use xitca_codegen::error_impl;
@@ -355,6 +408,14 @@ pub mod route {
     //! # }
     //! ```
     pub use xitca_http::util::service::route::{connect, delete, get, head, options, patch, post, put, trace, Route};
+
+    mod content_type;
+    mod static_router;
+    mod vhost;
+
+    pub use content_type::{on_content_type, ContentTypeRouter};
+    pub use static_router::StaticRouter;
+    pub use vhost::Vhost;
 }
 
 pub use app::{App, AppObject, NestApp};
@@ -0,0 +1,370 @@
+//! absolute url building from current request.
+//!
+//! [WebContext::url_for] and [WebContext::url] build absolute url from components inferred from request:
+//!
+//! - scheme: [BaseUrl::scheme] > trusted forwarded headers > scheme of request uri (Http/2 and Http/3) > `http`
+//! - host: [BaseUrl::host] > trusted forwarded headers > authority of request uri > `Host` header
+//! - path: [BaseUrl::path] prefixed to the given path.
+//!
+//! `Forwarded` header's `proto` and `host` parameters and `X-Forwarded-Proto` and `X-Forwarded-Host` headers are
+//! only looked into when request comes from a proxy trusted by
+//! [TrustPolicy](crate::handler::client_ip::TrustPolicy) middleware. In case of multiple values the first one
+//! (added by proxy closest to client) is used.
+//!
+//! # Examples
+//! ```rust
+//! # use xitca_web::{
+//! #   handler::{client_ip::TrustPolicy, handler_service, redirect::Redirect},
+//! #   middleware::base_url::BaseUrl,
+//! #   route::get,
+//! #   App, WebContext,
+//! # };
+//! // link to next page of current listing.
+//! async fn list(ctx: &WebContext<'_>) -> String {
+//!     ctx.url_for("/items?page=2")
+//! }
+//!
+//! App::new()
+//!     .at("/items", get(handler_service(list)))
+//!     // relative location is resolved to absolute url. e.g: https://example.com/api/items
+//!     .at("/", Redirect::see_other("/items").absolute())
+//!     // app is served under /api path by reverse proxy that strip the prefix.
+//!     .enclosed(BaseUrl::new().path("/api"))
+//!     // trust proxy from local network so it's forwarded headers are used for scheme and host.
+//!     .enclosed(TrustPolicy::new().trust_proxy("10.0.0.0/8"));
+//! ```
+
+use std::sync::Arc;
+
+use crate::{
+    context::WebContext,
+    http::{
+        header::{HeaderMap, HeaderName, FORWARDED, HOST},
+        uri::Authority,
+        WebRequest,
+    },
+    service::Service,
+};
+
+const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+
+/// middleware for configuring components of absolute url built from request. see [module](self) level doc for
+/// detail.
+#[derive(Clone, Debug, Default)]
+pub struct BaseUrl {
+    scheme: Option<Arc<str>>,
+    host: Option<Arc<str>>,
+    path: Option<Arc<str>>,
+}
+
+impl BaseUrl {
+    /// construct a new middleware where all components are inferred from request.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// set fixed scheme of url. e.g: `"https"`
+    ///
+    /// # Panics
+    /// when given string is not a valid scheme.
+    pub fn scheme(mut self, scheme: &str) -> Self {
+        assert!(is_scheme(scheme), "{scheme} is not a valid scheme");
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    /// set fixed host (with optional port) of url. e.g: `"example.com"` or `"localhost:8080"`
+    ///
+    /// # Panics
+    /// when given string is not a valid host.
+    pub fn host(mut self, host: &str) -> Self {
+        assert!(is_host(host), "{host} is not a valid host");
+        self.host = Some(host.into());
+        self
+    }
+
+    /// set base path prefixed to path of url. e.g: `"/api"`
+    pub fn path(mut self, path: &str) -> Self {
+        let path = path.trim_end_matches('/');
+        self.path = (!path.is_empty()).then(|| {
+            if path.starts_with('/') {
+                path.into()
+            } else {
+                format!("/{path}").into()
+            }
+        });
+        self
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for BaseUrl {
+    type Response = service::BaseUrlService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::BaseUrlService {
+            service,
+            base: self.clone(),
+        })
+    }
+}
+
+mod service {
+    use crate::{http::WebResponse, service::ready::ReadyService};
+
+    use super::*;
+
+    pub struct BaseUrlService<S> {
+        pub(super) service: S,
+        pub(super) base: BaseUrl,
+    }
+
+    impl<'r, C, B, S, ResB, SE> Service<WebContext<'r, C, B>> for BaseUrlService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = SE>,
+    {
+        type Response = WebResponse<ResB>;
+        type Error = SE;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            ctx.req_mut().extensions_mut().insert(self.base.clone());
+            self.service.call(ctx).await
+        }
+    }
+
+    impl<S> ReadyService for BaseUrlService<S>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+// marker inserted into request extensions by TrustPolicy middleware when request comes from trusted proxy.
+#[derive(Clone, Copy)]
+pub(crate) struct TrustedProxy;
+
+pub(crate) fn scheme(req: &WebRequest<()>) -> &str {
+    let base = req.extensions().get::<BaseUrl>();
+    base.and_then(|base| base.scheme.as_deref())
+        .or_else(|| forwarded(req, "proto", X_FORWARDED_PROTO).filter(|s| is_scheme(s)))
+        .or_else(|| req.uri().scheme_str())
+        .unwrap_or("http")
+}
+
+pub(crate) fn host(req: &WebRequest<()>) -> Option<&str> {
+    let base = req.extensions().get::<BaseUrl>();
+    base.and_then(|base| base.host.as_deref())
+        .or_else(|| forwarded(req, "host", X_FORWARDED_HOST).filter(|s| is_host(s)))
+        .or_else(|| req.uri().authority().map(Authority::as_str))
+        .or_else(|| req.headers().get(HOST)?.to_str().ok().filter(|s| is_host(s)))
+}
+
+pub(crate) fn url_for(req: &WebRequest<()>, path: &str) -> String {
+    let base = req
+        .extensions()
+        .get::<BaseUrl>()
+        .and_then(|base| base.path.as_deref())
+        .unwrap_or("");
+
+    let mut url = String::new();
+
+    if let Some(host) = host(req) {
+        url.push_str(scheme(req));
+        url.push_str("://");
+        url.push_str(host);
+    }
+
+    url.push_str(base);
+
+    if !path.starts_with('/') {
+        url.push('/');
+    }
+    url.push_str(path);
+
+    url
+}
+
+// look up parameter of the first Forwarded element and fall back to given X-Forwarded-* header.
+fn forwarded<'a>(req: &'a WebRequest<()>, key: &str, fallback: HeaderName) -> Option<&'a str> {
+    req.extensions().get::<TrustedProxy>()?;
+    forwarded_param(req.headers(), key).or_else(|| first_value(req.headers(), fallback))
+}
+
+fn forwarded_param<'a>(headers: &'a HeaderMap, key: &str) -> Option<&'a str> {
+    let element = first_value(headers, FORWARDED)?;
+    element.split(';').find_map(|pair| {
+        let (k, v) = pair.split_once('=')?;
+        k.trim().eq_ignore_ascii_case(key).then(|| v.trim().trim_matches('"'))
+    })
+}
+
+fn first_value(headers: &HeaderMap, name: HeaderName) -> Option<&str> {
+    let value = headers.get(name)?.to_str().ok()?;
+    let value = value.split(',').next()?.trim();
+    (!value.is_empty()).then_some(value)
+}
+
+fn is_scheme(scheme: &str) -> bool {
+    let mut bytes = scheme.bytes();
+    bytes.next().is_some_and(|b| b.is_ascii_alphabetic())
+        && bytes.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
+}
+
+fn is_host(host: &str) -> bool {
+    !host.contains('@') && Authority::try_from(host).is_ok()
+}
+
+impl<C, B> WebContext<'_, C, B> {
+    /// scheme of current request. see [module](crate::middleware::base_url) level doc for detail.
+    #[inline]
+    pub fn scheme(&self) -> &str {
+        scheme(self.req())
+    }
+
+    /// host (with optional port) of current request. see [module](crate::middleware::base_url) level doc for
+    /// detail.
+    #[inline]
+    pub fn host(&self) -> Option<&str> {
+        host(self.req())
+    }
+
+    /// build absolute url from given path (with optional query). base path configured by [BaseUrl] middleware
+    /// is prefixed to the path. When host can not be determined the url is relative to host.
+    /// see [module](crate::middleware::base_url) level doc for detail.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_web::WebContext;
+    /// fn next_page(ctx: &WebContext<'_>, page: usize) -> String {
+    ///     // e.g: https://example.com/items?page=2
+    ///     ctx.url_for(&format!("/items?page={page}"))
+    /// }
+    /// ```
+    #[inline]
+    pub fn url_for(&self, path: &str) -> String {
+        url_for(self.req(), path)
+    }
+
+    /// build absolute url of current request. see [WebContext::url_for] for detail.
+    pub fn url(&self) -> String {
+        let uri = self.req().uri();
+        let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+        url_for(self.req(), path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::{client_ip::TrustPolicy, handler_service, redirect::Redirect},
+        http::{
+            header::{HeaderValue, LOCATION},
+            Uri,
+        },
+        route::get,
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn infer() {
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+
+        *ctx.req_mut().uri_mut() = Uri::from_static("/foo?bar=1");
+        assert_eq!(ctx.scheme(), "http");
+        assert_eq!(ctx.host(), None);
+        assert_eq!(ctx.url(), "/foo?bar=1");
+        assert_eq!(ctx.url_for("baz"), "/baz");
+
+        ctx.req_mut()
+            .headers_mut()
+            .insert(HOST, HeaderValue::from_static("example.com:8080"));
+        assert_eq!(ctx.url(), "http://example.com:8080/foo?bar=1");
+
+        // forwarded headers from untrusted peer are ignored.
+        ctx.req_mut()
+            .headers_mut()
+            .insert(X_FORWARDED_PROTO, HeaderValue::from_static("https"));
+        ctx.req_mut().headers_mut().insert(
+            FORWARDED,
+            HeaderValue::from_static("for=1.1.1.1;host=a.com, host=b.com"),
+        );
+        assert_eq!(ctx.url_for("/"), "http://example.com:8080/");
+
+        ctx.req_mut().extensions_mut().insert(TrustedProxy);
+        assert_eq!(ctx.url_for("/"), "https://a.com/");
+
+        *ctx.req_mut().uri_mut() = Uri::from_static("https://h2.com/foo");
+        ctx.req_mut().headers_mut().clear();
+        assert_eq!(ctx.url(), "https://h2.com/foo");
+
+        ctx.req_mut()
+            .extensions_mut()
+            .insert(BaseUrl::new().scheme("http").host("c.com").path("api/"));
+        assert_eq!(ctx.url(), "http://c.com/api/foo");
+        assert_eq!(ctx.url_for("/items?page=2"), "http://c.com/api/items?page=2");
+    }
+
+    #[test]
+    fn invalid() {
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+
+        ctx.req_mut().extensions_mut().insert(TrustedProxy);
+        ctx.req_mut()
+            .headers_mut()
+            .insert(X_FORWARDED_PROTO, HeaderValue::from_static("1ttp"));
+        ctx.req_mut()
+            .headers_mut()
+            .insert(X_FORWARDED_HOST, HeaderValue::from_static("evil.com/path"));
+        ctx.req_mut()
+            .headers_mut()
+            .insert(HOST, HeaderValue::from_static("user@example.com"));
+        assert_eq!(ctx.scheme(), "http");
+        assert_eq!(ctx.host(), None);
+    }
+
+    #[test]
+    fn middleware() {
+        async fn url(ctx: &WebContext<'_>) -> String {
+            ctx.url()
+        }
+
+        let service = App::new()
+            .at("/", Redirect::see_other("/foo").absolute())
+            .at("/foo", get(handler_service(url)))
+            .enclosed(BaseUrl::new().path("/api"))
+            .enclosed(TrustPolicy::new().trust_proxy("0.0.0.0/0"))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut req = WebRequest::default();
+        req.headers_mut().insert(HOST, HeaderValue::from_static("example.com"));
+        req.headers_mut()
+            .insert(X_FORWARDED_PROTO, HeaderValue::from_static("https"));
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status().as_u16(), 303);
+        assert_eq!(res.headers().get(LOCATION).unwrap(), "https://example.com/api/foo");
+
+        let mut req = WebRequest::default();
+        *req.uri_mut() = Uri::from_static("/foo");
+        req.headers_mut().insert(HOST, HeaderValue::from_static("example.com"));
+        let res = service.call(req).now_or_panic().unwrap();
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, "http://example.com/api/foo");
+    }
+}
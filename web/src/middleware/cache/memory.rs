@@ -0,0 +1,171 @@
+use core::time::Duration;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use super::{CacheStore, CachedResponse};
+
+/// In process least recently used cache store.
+///
+/// Store is shared between cloned instances and thread safe. When total size of cached responses exceeds
+/// capacity the least recently used responses are evicted.
+#[derive(Clone)]
+pub struct MemoryStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    capacity: usize,
+    size: usize,
+    tick: u64,
+    entries: HashMap<String, Entry>,
+    // access order of entries. smallest tick is the least recently used.
+    order: BTreeMap<u64, String>,
+}
+
+struct Entry {
+    res: CachedResponse,
+    expires: Instant,
+    tick: u64,
+}
+
+impl MemoryStore {
+    /// Construct a new store with given capacity in bytes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                capacity,
+                size: 0,
+                tick: 0,
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// Total size of cached responses in bytes.
+    pub fn size(&self) -> usize {
+        self.inner.lock().unwrap().size
+    }
+}
+
+impl Inner {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.tick);
+        self.size -= entry.res.size();
+        Some(entry)
+    }
+}
+
+impl CacheStore for MemoryStore {
+    async fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut inner = self.inner.lock().unwrap();
+
+        let tick = inner.next_tick();
+        let Inner { entries, order, .. } = &mut *inner;
+
+        let entry = entries.get_mut(key)?;
+        if entry.expires > Instant::now() {
+            let key = order.remove(&entry.tick).expect("order of entry must present");
+            order.insert(tick, key);
+            entry.tick = tick;
+            return Some(entry.res.clone());
+        }
+
+        inner.remove(key);
+        None
+    }
+
+    async fn set(&self, key: &str, res: CachedResponse, ttl: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.remove(key);
+
+        let size = res.size();
+        if size > inner.capacity {
+            return;
+        }
+
+        while inner.size + size > inner.capacity {
+            let (_, key) = inner.order.pop_first().expect("order of entry must present");
+            let entry = inner.entries.remove(&key).expect("entry must present");
+            inner.size -= entry.res.size();
+        }
+
+        let tick = inner.next_tick();
+        inner.size += size;
+        inner.order.insert(tick, key.to_owned());
+        inner.entries.insert(
+            key.to_owned(),
+            Entry {
+                res,
+                expires: Instant::now() + ttl,
+                tick,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        bytes::Bytes,
+        http::{HeaderMap, StatusCode},
+    };
+
+    use super::*;
+
+    fn res(body: &'static str) -> CachedResponse {
+        CachedResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(body.as_bytes()),
+            vary: Vec::new(),
+            created: std::time::SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn lru() {
+        let store = MemoryStore::new(8);
+        let ttl = Duration::from_secs(60);
+
+        store.set("a", res("123"), ttl).now_or_panic();
+        store.set("b", res("456"), ttl).now_or_panic();
+        assert_eq!(store.size(), 6);
+
+        // access a so b is the least recently used.
+        assert!(store.get("a").now_or_panic().is_some());
+
+        store.set("c", res("789"), ttl).now_or_panic();
+        assert_eq!(store.size(), 6);
+        assert!(store.get("b").now_or_panic().is_none());
+        assert_eq!(store.get("a").now_or_panic().unwrap().body(), "123");
+        assert_eq!(store.get("c").now_or_panic().unwrap().body(), "789");
+
+        // replace existing entry.
+        store.set("c", res("0"), ttl).now_or_panic();
+        assert_eq!(store.size(), 4);
+
+        // larger than capacity.
+        store.set("d", res("123456789"), ttl).now_or_panic();
+        assert!(store.get("d").now_or_panic().is_none());
+        assert_eq!(store.size(), 4);
+
+        // expired.
+        store.set("e", res("e"), Duration::ZERO).now_or_panic();
+        assert!(store.get("e").now_or_panic().is_none());
+        assert_eq!(store.size(), 4);
+    }
+}
@@ -0,0 +1,762 @@
+//! http response cache middleware.
+//!
+//! Responses to `GET` and `HEAD` requests are cached in a [CacheStore] according to `Cache-Control` and
+//! `Vary` headers. Cached responses are served without calling the enclosed service until they expire.
+//!
+//! # Caching rules
+//! - request with `Cache-Control: no-store` bypasses the cache entirely.
+//! - request with `Cache-Control: no-cache` or `max-age=0` skips cache lookup and refreshes cached response.
+//! - request with `Authorization` header is only cached when response is marked `public` or has `s-maxage`.
+//! - response with `no-store`, `no-cache` or `private` directive, `Set-Cookie` header or `Vary: *` is not cached.
+//! - only heuristically cacheable status codes (`200`, `203`, `204`, `300`, `301`, `308`, `404`, `405`, `410`,
+//!   `414` and `501`) are cached.
+//! - time to live of cached response is the smaller one between configured [Cache::ttl] and `s-maxage`
+//!   (or `max-age` when `s-maxage` is absent) of response.
+//!
+//! One cache key is shared by all variants of a response distinguished by `Vary` header. Request not matching
+//! the variant in cache is treated as cache miss and the cached variant is replaced by the new response.
+
+mod memory;
+
+#[cfg(feature = "cache-redis")]
+mod redis;
+
+pub use self::memory::MemoryStore;
+
+#[cfg(feature = "cache-redis")]
+pub use self::redis::RedisStore;
+
+use core::{future::Future, time::Duration};
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    bytes::{Buf, BufMut, Bytes},
+    http::{
+        header::{HeaderMap, HeaderName, HeaderValue, AGE, AUTHORIZATION, CACHE_CONTROL, SET_COOKIE, VARY},
+        StatusCode,
+    },
+    service::Service,
+};
+
+/// Storage of cached responses.
+///
+/// Cache is best effort by nature. Errors happen inside store should be treated as cache miss and skipped
+/// storing.
+pub trait CacheStore {
+    /// Get cached response by key. Expired response must not be returned.
+    fn get(&self, key: &str) -> impl Future<Output = Option<CachedResponse>>;
+
+    /// Store response by key for given duration. Existing response with the same key is replaced.
+    fn set(&self, key: &str, res: CachedResponse, ttl: Duration) -> impl Future<Output = ()>;
+}
+
+/// Response stored in [CacheStore].
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    vary: Vec<(HeaderName, Option<HeaderValue>)>,
+    created: SystemTime,
+}
+
+impl CachedResponse {
+    /// Status code of cached response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Headers of cached response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Body of cached response.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Approximate size of cached response in bytes.
+    pub fn size(&self) -> usize {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| name.as_str().len() + value.len())
+            .sum::<usize>();
+        headers + self.body.len()
+    }
+
+    /// Encode response to bytes. Useful for [CacheStore] persist response to external storage.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.size() + 64);
+
+        buf.put_u8(ENCODE_VERSION);
+        buf.put_u16(self.status.as_u16());
+        let created = self.created.duration_since(UNIX_EPOCH).unwrap_or_default();
+        buf.put_u64(created.as_secs());
+
+        buf.put_u32(self.headers.len() as u32);
+        for (name, value) in self.headers.iter() {
+            put_slice(&mut buf, name.as_str().as_bytes());
+            put_slice(&mut buf, value.as_bytes());
+        }
+
+        buf.put_u32(self.vary.len() as u32);
+        for (name, value) in self.vary.iter() {
+            put_slice(&mut buf, name.as_str().as_bytes());
+            match value {
+                Some(value) => {
+                    buf.put_u8(1);
+                    put_slice(&mut buf, value.as_bytes());
+                }
+                None => buf.put_u8(0),
+            }
+        }
+
+        buf.extend_from_slice(&self.body);
+
+        buf
+    }
+
+    /// Decode response from bytes produced by [CachedResponse::encode].
+    ///
+    /// Returns `None` when bytes are malformed.
+    pub fn decode(mut buf: &[u8]) -> Option<Self> {
+        if get_u8(&mut buf)? != ENCODE_VERSION {
+            return None;
+        }
+
+        let status = StatusCode::from_u16(get_u16(&mut buf)?).ok()?;
+        let created = UNIX_EPOCH + Duration::from_secs(get_u64(&mut buf)?);
+
+        let len = get_u32(&mut buf)?;
+        let mut headers = HeaderMap::with_capacity(len as usize);
+        for _ in 0..len {
+            let name = HeaderName::from_bytes(get_slice(&mut buf)?).ok()?;
+            let value = HeaderValue::from_bytes(get_slice(&mut buf)?).ok()?;
+            headers.append(name, value);
+        }
+
+        let len = get_u32(&mut buf)?;
+        let mut vary = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            let name = HeaderName::from_bytes(get_slice(&mut buf)?).ok()?;
+            let value = match get_u8(&mut buf)? {
+                0 => None,
+                _ => Some(HeaderValue::from_bytes(get_slice(&mut buf)?).ok()?),
+            };
+            vary.push((name, value));
+        }
+
+        Some(Self {
+            status,
+            headers,
+            body: Bytes::copy_from_slice(buf),
+            vary,
+            created,
+        })
+    }
+
+    fn vary_matches(&self, headers: &HeaderMap) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| headers.get(name) == value.as_ref())
+    }
+
+    fn age(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(self.created)
+            .map(|dur| dur.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+const ENCODE_VERSION: u8 = 1;
+
+fn put_slice(buf: &mut Vec<u8>, slice: &[u8]) {
+    buf.put_u32(slice.len() as u32);
+    buf.extend_from_slice(slice);
+}
+
+fn get_u8(buf: &mut &[u8]) -> Option<u8> {
+    (buf.remaining() >= 1).then(|| buf.get_u8())
+}
+
+fn get_u16(buf: &mut &[u8]) -> Option<u16> {
+    (buf.remaining() >= 2).then(|| buf.get_u16())
+}
+
+fn get_u32(buf: &mut &[u8]) -> Option<u32> {
+    (buf.remaining() >= 4).then(|| buf.get_u32())
+}
+
+fn get_u64(buf: &mut &[u8]) -> Option<u64> {
+    (buf.remaining() >= 8).then(|| buf.get_u64())
+}
+
+fn get_slice<'a>(buf: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = get_u32(buf)? as usize;
+    if buf.len() < len {
+        return None;
+    }
+    let (slice, rest) = buf.split_at(len);
+    *buf = rest;
+    Some(slice)
+}
+
+/// builder for http response cache middleware.
+///
+/// # Type mutation
+/// `Cache` would mutate response body type from `B` to [ResponseBody]. Service enclosed by it must be able
+/// to handle it's mutation or utilize [TypeEraser] to erase the mutation.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{handler::handler_service, middleware::cache::Cache, route::get, App, WebContext};
+/// App::new()
+///     .at("/", get(handler_service(|| async { "hello,world!" })))
+///     # .at("/infer", handler_service(|_: &WebContext<'_>| async{ "infer type" }))
+///     // cache responses up to 64KiB in size for at most 30 seconds.
+///     .enclosed(Cache::new().max_object_size(64 * 1024).ttl(Duration::from_secs(30)));
+/// ```
+///
+/// [ResponseBody]: crate::body::ResponseBody
+/// [TypeEraser]: crate::middleware::eraser::TypeEraser
+#[derive(Clone)]
+pub struct Cache<St = MemoryStore> {
+    store: St,
+    max_object_size: usize,
+    ttl: Duration,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cache {
+    /// Construct a cache middleware with [MemoryStore] of 32MiB capacity.
+    pub fn new() -> Self {
+        Self::with_store(MemoryStore::new(32 * 1024 * 1024))
+    }
+}
+
+impl<St> Cache<St> {
+    /// Construct a cache middleware with given [CacheStore].
+    ///
+    /// Store is cloned for every service instance built from the middleware.
+    pub fn with_store(store: St) -> Self {
+        Self {
+            store,
+            max_object_size: 1024 * 1024,
+            ttl: Duration::from_secs(60),
+        }
+    }
+
+    /// Set max size in bytes of response body that can be cached. Default to 1MiB.
+    ///
+    /// Response with larger body is passed through without caching.
+    pub fn max_object_size(mut self, size: usize) -> Self {
+        self.max_object_size = size;
+        self
+    }
+
+    /// Set max time to live of cached response. Default to 60 seconds.
+    ///
+    /// Response with shorter freshness lifetime from it's `Cache-Control` header is cached for shorter
+    /// duration.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl<St, S, E> Service<Result<S, E>> for Cache<St>
+where
+    St: Clone,
+{
+    type Response = service::CacheService<S, St>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::CacheService {
+            service,
+            store: self.store.clone(),
+            max_object_size: self.max_object_size,
+            ttl: self.ttl,
+        })
+    }
+}
+
+#[derive(Default)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    private: bool,
+    public: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+}
+
+impl CacheControl {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let mut this = Self::default();
+
+        let directives = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+
+        for directive in directives {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+
+            let secs = || value.and_then(|v| v.parse().ok());
+
+            if name.eq_ignore_ascii_case("no-store") {
+                this.no_store = true;
+            } else if name.eq_ignore_ascii_case("no-cache") {
+                this.no_cache = true;
+            } else if name.eq_ignore_ascii_case("private") {
+                this.private = true;
+            } else if name.eq_ignore_ascii_case("public") {
+                this.public = true;
+            } else if name.eq_ignore_ascii_case("max-age") {
+                this.max_age = secs();
+            } else if name.eq_ignore_ascii_case("s-maxage") {
+                this.s_maxage = secs();
+            }
+        }
+
+        this
+    }
+}
+
+// freshness lifetime of response. None when response can not be cached.
+fn response_ttl(
+    status: StatusCode,
+    headers: &HeaderMap,
+    authorized: bool,
+    ttl: Duration,
+) -> Option<(Duration, Vec<HeaderName>)> {
+    if !matches!(
+        status.as_u16(),
+        200 | 203 | 204 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+    ) {
+        return None;
+    }
+
+    if headers.contains_key(SET_COOKIE) {
+        return None;
+    }
+
+    let cc = CacheControl::from_headers(headers);
+
+    if cc.no_store || cc.no_cache || cc.private || (authorized && !cc.public && cc.s_maxage.is_none()) {
+        return None;
+    }
+
+    let ttl = match cc.s_maxage.or(cc.max_age) {
+        Some(secs) => ttl.min(Duration::from_secs(secs)),
+        None => ttl,
+    };
+
+    if ttl.is_zero() {
+        return None;
+    }
+
+    let mut vary = Vec::new();
+    for value in headers.get_all(VARY) {
+        for name in value.to_str().ok()?.split(',') {
+            let name = name.trim();
+            if name == "*" {
+                return None;
+            }
+            if !name.is_empty() {
+                vary.push(HeaderName::from_bytes(name.as_bytes()).ok()?);
+            }
+        }
+    }
+
+    Some((ttl, vary))
+}
+
+mod service {
+    use core::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use std::collections::VecDeque;
+
+    use futures_core::stream::Stream;
+
+    use crate::{
+        body::ResponseBody,
+        bytes::BytesMut,
+        context::WebContext,
+        error::{BodyError, Error},
+        http::{header::HOST, Method, WebResponse},
+        service::{ready::ReadyService, Service},
+    };
+
+    use super::*;
+
+    pub struct CacheService<S, St> {
+        pub(super) service: S,
+        pub(super) store: St,
+        pub(super) max_object_size: usize,
+        pub(super) ttl: Duration,
+    }
+
+    impl<'r, C, B, S, St, ResB, T, E> Service<WebContext<'r, C, B>> for CacheService<S, St>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = Error<C>>,
+        St: CacheStore,
+        ResB: Stream<Item = Result<T, E>> + 'static,
+        T: Into<Bytes>,
+        E: Into<BodyError>,
+    {
+        type Response = WebResponse;
+        type Error = Error<C>;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let req = ctx.req();
+
+            let cc = CacheControl::from_headers(req.headers());
+
+            if !matches!(*req.method(), Method::GET | Method::HEAD) || cc.no_store {
+                return self
+                    .service
+                    .call(ctx)
+                    .await
+                    .map(|res| res.map(ResponseBody::box_stream));
+            }
+
+            let key = cache_key(req);
+
+            if !cc.no_cache && cc.max_age != Some(0) {
+                if let Some(cached) = self.store.get(&key).await {
+                    if cached.vary_matches(req.headers()) {
+                        let age = cached.age();
+                        let mut res = ctx.into_response(cached.body);
+                        *res.status_mut() = cached.status;
+                        *res.headers_mut() = cached.headers;
+                        res.headers_mut().insert(AGE, HeaderValue::from(age));
+                        return Ok(res);
+                    }
+                }
+            }
+
+            // request headers are consumed by service. keep a copy for extracting vary header values.
+            let headers = ctx.req().headers().clone();
+            let authorized = headers.contains_key(AUTHORIZATION);
+
+            let res = self.service.call(ctx.reborrow()).await?;
+
+            let Some((ttl, vary)) = response_ttl(res.status(), res.headers(), authorized, self.ttl) else {
+                return Ok(res.map(ResponseBody::box_stream));
+            };
+
+            if matches!(res.body().size_hint(), (_, Some(up)) if up > self.max_object_size) {
+                return Ok(res.map(ResponseBody::box_stream));
+            }
+
+            let (parts, body) = res.into_parts();
+
+            let mut body = Box::pin(body);
+            let mut chunks = VecDeque::new();
+            let mut len = 0;
+
+            while let Some(chunk) = core::future::poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+                let chunk = chunk.map_err(Into::into)?.into();
+                len += chunk.len();
+                chunks.push_back(chunk);
+
+                // body too large. pass through collected chunks and the rest of body.
+                if len > self.max_object_size {
+                    let body = ResponseBody::box_stream(Buffered { chunks, body });
+                    return Ok(WebResponse::from_parts(parts, body));
+                }
+            }
+
+            let body = match chunks.len() {
+                0 => Bytes::new(),
+                1 => chunks.pop_front().unwrap(),
+                _ => {
+                    let mut buf = BytesMut::with_capacity(len);
+                    chunks.into_iter().for_each(|chunk| buf.extend_from_slice(&chunk));
+                    buf.freeze()
+                }
+            };
+
+            let cached = CachedResponse {
+                status: parts.status,
+                headers: parts.headers.clone(),
+                body: body.clone(),
+                vary: vary
+                    .into_iter()
+                    .map(|name| {
+                        let value = headers.get(&name).cloned();
+                        (name, value)
+                    })
+                    .collect(),
+                created: SystemTime::now(),
+            };
+
+            self.store.set(&key, cached, ttl).await;
+
+            Ok(WebResponse::from_parts(parts, ResponseBody::bytes(body)))
+        }
+    }
+
+    impl<S, St> ReadyService for CacheService<S, St>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+
+    fn cache_key<B>(req: &crate::http::WebRequest<B>) -> String {
+        let host = req
+            .headers()
+            .get(HOST)
+            .and_then(|host| host.to_str().ok())
+            .or_else(|| req.uri().authority().map(|a| a.as_str()))
+            .unwrap_or_default();
+        let path = req.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/");
+        format!("{} {host}{path}", req.method())
+    }
+
+    // body partially collected by cache middleware.
+    struct Buffered<B> {
+        chunks: VecDeque<Bytes>,
+        body: Pin<Box<B>>,
+    }
+
+    impl<B, T, E> Stream for Buffered<B>
+    where
+        B: Stream<Item = Result<T, E>>,
+        T: Into<Bytes>,
+    {
+        type Item = Result<Bytes, E>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let this = self.get_mut();
+            match this.chunks.pop_front() {
+                Some(chunk) => Poll::Ready(Some(Ok(chunk))),
+                None => this
+                    .body
+                    .as_mut()
+                    .poll_next(cx)
+                    .map(|res| res.map(|res| res.map(Into::into))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{
+        convert::Infallible,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use std::sync::Arc;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::ResponseBody,
+        error::Error,
+        handler::{handler_service, stream::StreamBody},
+        http::{header::ACCEPT_ENCODING, WebRequest, WebResponse},
+        test::collect_body,
+        App, WebContext,
+    };
+
+    use super::*;
+
+    fn res(status: u16) -> CachedResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=5"));
+        CachedResponse {
+            status: StatusCode::from_u16(status).unwrap(),
+            headers,
+            body: Bytes::from_static(b"996"),
+            vary: vec![(ACCEPT_ENCODING, Some(HeaderValue::from_static("gzip"))), (AGE, None)],
+            created: UNIX_EPOCH + Duration::from_secs(251),
+        }
+    }
+
+    #[test]
+    fn encode_decode() {
+        let res = res(200);
+        let buf = res.encode();
+        let res2 = CachedResponse::decode(&buf).unwrap();
+
+        assert_eq!(res2.status, res.status);
+        assert_eq!(res2.headers, res.headers);
+        assert_eq!(res2.body, res.body);
+        assert_eq!(res2.vary, res.vary);
+        assert_eq!(res2.created, res.created);
+
+        assert!(CachedResponse::decode(&buf[..buf.len() - res.body.len() - 1]).is_none());
+        assert!(CachedResponse::decode(&[]).is_none());
+    }
+
+    #[test]
+    fn ttl() {
+        let ttl = Duration::from_secs(60);
+        let ok = StatusCode::OK;
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(response_ttl(ok, &headers, false, ttl).unwrap().0, ttl);
+        assert!(response_ttl(StatusCode::INTERNAL_SERVER_ERROR, &headers, false, ttl).is_none());
+        assert!(response_ttl(ok, &headers, true, ttl).is_none());
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("public, max-age=5"));
+        assert_eq!(response_ttl(ok, &headers, true, ttl).unwrap().0, Duration::from_secs(5));
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=5, s-maxage=10"));
+        assert_eq!(
+            response_ttl(ok, &headers, false, ttl).unwrap().0,
+            Duration::from_secs(10)
+        );
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=0"));
+        assert!(response_ttl(ok, &headers, false, ttl).is_none());
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("private"));
+        assert!(response_ttl(ok, &headers, false, ttl).is_none());
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("No-Store"));
+        assert!(response_ttl(ok, &headers, false, ttl).is_none());
+
+        headers.remove(CACHE_CONTROL);
+        headers.insert(VARY, HeaderValue::from_static("accept-encoding, origin"));
+        let (_, vary) = response_ttl(ok, &headers, false, ttl).unwrap();
+        assert_eq!(vary, [ACCEPT_ENCODING, HeaderName::from_static("origin")]);
+
+        headers.insert(VARY, HeaderValue::from_static("*"));
+        assert!(response_ttl(ok, &headers, false, ttl).is_none());
+    }
+
+    #[test]
+    fn middleware() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let count2 = count.clone();
+
+        let service = App::new()
+            .at(
+                "/",
+                handler_service(move |ctx: &WebContext<'_>| {
+                    let n = count2.fetch_add(1, Ordering::Relaxed);
+                    let no_store = ctx.req().uri().query() == Some("no-store");
+                    async move {
+                        let mut res: WebResponse = WebResponse::new(ResponseBody::from(n.to_string()));
+                        if no_store {
+                            res.headers_mut()
+                                .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+                        }
+                        res.headers_mut()
+                            .insert(VARY, HeaderValue::from_static("accept-encoding"));
+                        Ok::<_, Error>(res)
+                    }
+                }),
+            )
+            .enclosed(Cache::new().max_object_size(16))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |uri: &str, headers: &[(HeaderName, &'static str)]| {
+            let mut req = WebRequest::default();
+            *req.uri_mut() = uri.parse().unwrap();
+            for (name, value) in headers {
+                req.headers_mut().insert(name, HeaderValue::from_static(value));
+            }
+            let res = service.call(req).now_or_panic().unwrap();
+            let age = res.headers().contains_key(AGE);
+            let body = collect_body(res.into_body()).now_or_panic().unwrap();
+            (String::from_utf8(body).unwrap(), age)
+        };
+
+        assert_eq!(call("/", &[]), ("0".into(), false));
+        assert_eq!(call("/", &[]), ("0".into(), true));
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+
+        // vary mismatch.
+        assert_eq!(call("/", &[(ACCEPT_ENCODING, "gzip")]), ("1".into(), false));
+        assert_eq!(call("/", &[(ACCEPT_ENCODING, "gzip")]), ("1".into(), true));
+
+        // request forcing revalidation.
+        assert_eq!(
+            call("/", &[(ACCEPT_ENCODING, "gzip"), (CACHE_CONTROL, "no-cache")]),
+            ("2".into(), false)
+        );
+        assert_eq!(call("/", &[(ACCEPT_ENCODING, "gzip")]), ("2".into(), true));
+
+        // response not cacheable.
+        assert_eq!(call("/?no-store", &[]), ("3".into(), false));
+        assert_eq!(call("/?no-store", &[]), ("4".into(), false));
+    }
+
+    #[test]
+    fn max_object_size() {
+        use futures_util::StreamExt;
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let count2 = count.clone();
+        let polled = Arc::new(AtomicUsize::new(0));
+        let polled2 = polled.clone();
+
+        let service = App::new()
+            .at(
+                "/",
+                handler_service(move || {
+                    count2.fetch_add(1, Ordering::Relaxed);
+                    let polled = polled2.clone();
+                    async move {
+                        // stream without size hint is collected by cache until it exceeds max object size.
+                        let chunks =
+                            ["996", "251", "007"].map(|s| Ok::<_, Infallible>(Bytes::from_static(s.as_bytes())));
+                        let body = StreamBody::new(futures_util::stream::iter(chunks).inspect(move |_| {
+                            polled.fetch_add(1, Ordering::Relaxed);
+                        }));
+                        assert_eq!(futures_core::Stream::size_hint(&body), (0, None));
+                        body
+                    }
+                }),
+            )
+            .enclosed(Cache::new().max_object_size(4))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for _ in 0..2 {
+            polled.store(0, Ordering::Relaxed);
+            let res = service.call(WebRequest::default()).now_or_panic().unwrap();
+            assert!(!res.headers().contains_key(AGE));
+            // collecting stops at the second chunk where max object size is exceeded.
+            assert_eq!(polled.load(Ordering::Relaxed), 2);
+            let body = collect_body(res.into_body()).now_or_panic().unwrap();
+            assert_eq!(body, b"996251007");
+        }
+
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+}
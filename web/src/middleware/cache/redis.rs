@@ -0,0 +1,159 @@
+use core::{future::IntoFuture, time::Duration};
+
+use tokio::sync::OnceCell;
+use xitca_redis::{Client, Cmd, Config, Error, Redis, Value};
+
+use super::{CacheStore, CachedResponse};
+
+/// Cache store backed by redis server.
+///
+/// Connection to redis server is established lazily on first use and each cloned instance owns it's own
+/// connection. Errors from redis are treated as cache miss.
+pub struct RedisStore {
+    cfg: Config,
+    prefix: String,
+    client: OnceCell<Client>,
+}
+
+impl Clone for RedisStore {
+    fn clone(&self) -> Self {
+        Self {
+            cfg: self.cfg.clone(),
+            prefix: self.prefix.clone(),
+            client: OnceCell::new(),
+        }
+    }
+}
+
+impl RedisStore {
+    /// Construct a new store with redis connection url. For example: `redis://127.0.0.1:6379`
+    pub fn new(url: &str) -> Result<Self, Error> {
+        Config::try_from(url).map(Self::with_config)
+    }
+
+    /// Construct a new store with given redis connection config.
+    pub fn with_config(cfg: Config) -> Self {
+        Self {
+            cfg,
+            prefix: String::from("xitca-web:cache:"),
+            client: OnceCell::new(),
+        }
+    }
+
+    /// Set prefix of keys stored in redis. Default to `xitca-web:cache:`
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    async fn client(&self) -> Option<&Client> {
+        self.client
+            .get_or_try_init(|| async {
+                let (client, driver) = Redis::new(self.cfg.clone()).connect().await?;
+                tokio::spawn(driver.into_future());
+                Ok::<_, Error>(client)
+            })
+            .await
+            .ok()
+    }
+}
+
+impl CacheStore for RedisStore {
+    async fn get(&self, key: &str) -> Option<CachedResponse> {
+        let client = self.client().await?;
+        let cmd = Cmd::new("GET").arg(format!("{}{key}", self.prefix));
+        match client.query(&cmd).await.ok()? {
+            Value::BulkString(buf) => CachedResponse::decode(&buf),
+            _ => None,
+        }
+    }
+
+    async fn set(&self, key: &str, res: CachedResponse, ttl: Duration) {
+        let Some(client) = self.client().await else {
+            return;
+        };
+        let cmd = Cmd::new("SET")
+            .arg(format!("{}{key}", self.prefix))
+            .arg(res.encode())
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64);
+        let _ = client.query(&cmd).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+        time::UNIX_EPOCH,
+    };
+
+    use crate::{
+        bytes::Bytes,
+        http::{HeaderMap, StatusCode},
+    };
+
+    use super::*;
+
+    fn arg(buf: &mut Vec<u8>, arg: &[u8]) {
+        buf.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buf.extend_from_slice(arg);
+        buf.extend_from_slice(b"\r\n");
+    }
+
+    #[tokio::test]
+    async fn store() {
+        let res = CachedResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(b"996"),
+            vary: Vec::new(),
+            created: UNIX_EPOCH + Duration::from_secs(251),
+        };
+        let value = res.encode();
+
+        let mut set = b"*5\r\n".to_vec();
+        for a in [&b"SET"[..], b"xitca-web:cache:foo", &value, b"PX", b"60000"] {
+            arg(&mut set, a);
+        }
+
+        let mut get = b"*2\r\n".to_vec();
+        arg(&mut get, b"GET");
+        arg(&mut get, b"xitca-web:cache:foo");
+
+        let mut bulk = Vec::new();
+        arg(&mut bulk, &value);
+
+        // a mock server respond to commands with pre defined responses in order.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let responses = [
+            (set, b"+OK\r\n".to_vec()),
+            (get.clone(), bulk),
+            (get, b"$-1\r\n".to_vec()),
+        ];
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            for (req, res) in responses {
+                let mut buf = vec![0; req.len()];
+                stream.read_exact(&mut buf).unwrap();
+                assert_eq!(buf, req);
+                stream.write_all(&res).unwrap();
+            }
+        });
+
+        let store = RedisStore::new(&format!("redis://127.0.0.1:{port}")).unwrap();
+
+        store.set("foo", res, Duration::from_secs(60)).await;
+
+        let res = store.get("foo").await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.body().as_ref(), b"996");
+
+        assert!(store.get("foo").await.is_none());
+
+        handle.join().unwrap();
+    }
+}
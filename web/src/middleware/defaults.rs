@@ -0,0 +1,470 @@
+//! bundle of commonly used middlewares.
+
+use core::time::Duration;
+
+#[cfg(feature = "logger")]
+use tracing::Level;
+
+use crate::{
+    config::ServiceConfig,
+    http::header::{HeaderName, HeaderValue},
+    service::Service,
+};
+
+use super::security_headers::SecurityHeaders;
+
+/// header name used for propagating request id by default.
+pub const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Middleware bundling commonly needed middlewares into one enclosure.
+///
+/// Default enabled pieces are(from outer to inner):
+/// - request id: reuse request id from `x-request-id` request header or generate a new one. The id is stored
+///   in request extensions as [RequestId] and added to response headers.
+/// - logger: log request and error with [`Level::INFO`] verbosity. Only available with `logger` feature.
+///   (see [Logger](crate::middleware::Logger) for detail).
+/// - catch panic: convert panic to `500 Internal Server Error` response.
+///   (see [CatchUnwind](crate::middleware::CatchUnwind) for detail).
+/// - limits and timeouts: 30 seconds request timeout and 2 MiB request body size limit.
+///   (see [ServiceConfig] for detail).
+/// - security headers: add security related headers to response.
+///   (see [SecurityHeaders] for detail).
+///
+/// Every piece can be toggled or configured with according builder method.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{
+/// #   handler::{extension::ExtensionRef, handler_service},
+/// #   middleware::defaults::{Defaults, RequestId},
+/// #   route::get,
+/// #   App, WebContext};
+/// async fn handler(ExtensionRef(id): ExtensionRef<'_, RequestId>) -> String {
+///     format!("request id: {id}")
+/// }
+///
+/// App::new()
+///     .at("/", get(handler_service(handler)))
+///     # .at("/infer", handler_service(|_: &WebContext<'_>| async{ "infer type" }))
+///     .enclosed(
+///         Defaults::new()
+///             .request_timeout(Duration::from_secs(10))
+///             .disable_security_headers(),
+///     );
+/// ```
+///
+/// # Note
+/// Response headers are only added to successful response of enclosed services. Error is passed through as is
+/// and would be converted to response by outer services.
+#[derive(Clone)]
+pub struct Defaults {
+    request_id: Option<HeaderName>,
+    #[cfg(feature = "logger")]
+    logger: Option<Level>,
+    catch_panic: bool,
+    config: Option<ServiceConfig>,
+    security_headers: Option<SecurityHeaders>,
+}
+
+impl Default for Defaults {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Defaults {
+    /// construct middleware with all pieces enabled. See [Defaults] for detail.
+    pub fn new() -> Self {
+        Self {
+            request_id: Some(X_REQUEST_ID),
+            #[cfg(feature = "logger")]
+            logger: Some(Level::INFO),
+            catch_panic: true,
+            config: Some(
+                ServiceConfig::new()
+                    .set_request_timeout(Duration::from_secs(30))
+                    .set_max_request_body_size(2 * 1024 * 1024),
+            ),
+            security_headers: Some(SecurityHeaders::new()),
+        }
+    }
+
+    /// construct middleware with all pieces disabled. Pieces can then be enabled individually.
+    pub fn empty() -> Self {
+        Self {
+            request_id: None,
+            #[cfg(feature = "logger")]
+            logger: None,
+            catch_panic: false,
+            config: None,
+            security_headers: None,
+        }
+    }
+
+    /// enable request id with given header name. e.g: `x-correlation-id`
+    pub fn request_id(mut self, name: HeaderName) -> Self {
+        self.request_id = Some(name);
+        self
+    }
+
+    /// disable request id.
+    pub fn disable_request_id(mut self) -> Self {
+        self.request_id = None;
+        self
+    }
+
+    /// enable logger with given [Level] of verbosity.
+    /// would try to initialize global trace dispatcher when middleware is constructed.
+    #[cfg(feature = "logger")]
+    pub fn logger(mut self, level: Level) -> Self {
+        self.logger = Some(level);
+        self
+    }
+
+    /// disable logger.
+    #[cfg(feature = "logger")]
+    pub fn disable_logger(mut self) -> Self {
+        self.logger = None;
+        self
+    }
+
+    /// enable catch panic.
+    pub fn catch_panic(mut self) -> Self {
+        self.catch_panic = true;
+        self
+    }
+
+    /// disable catch panic.
+    pub fn disable_catch_panic(mut self) -> Self {
+        self.catch_panic = false;
+        self
+    }
+
+    /// enable limits and timeouts with given [ServiceConfig]. replace the default ones.
+    pub fn service_config(mut self, config: ServiceConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// set max duration of handling a request. enable limits and timeouts when it's disabled.
+    pub fn request_timeout(mut self, dur: Duration) -> Self {
+        self.config = Some(self.config.unwrap_or_default().set_request_timeout(dur));
+        self
+    }
+
+    /// set max size in byte unit the request body can be. enable limits and timeouts when it's disabled.
+    pub fn max_request_body_size(mut self, size: usize) -> Self {
+        self.config = Some(self.config.unwrap_or_default().set_max_request_body_size(size));
+        self
+    }
+
+    /// disable limits and timeouts.
+    pub fn disable_limits(mut self) -> Self {
+        self.config = None;
+        self
+    }
+
+    /// enable security headers with given [SecurityHeaders]. replace the default ones.
+    pub fn security_headers(mut self, headers: SecurityHeaders) -> Self {
+        self.security_headers = Some(headers);
+        self
+    }
+
+    /// disable security headers.
+    pub fn disable_security_headers(mut self) -> Self {
+        self.security_headers = None;
+        self
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for Defaults {
+    type Response = service::DefaultsService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        #[cfg(feature = "logger")]
+        if let Some(level) = self.logger {
+            if let Err(e) = tracing_subscriber::fmt().with_max_level(level).try_init() {
+                // the most likely case is trace dispatcher has already been set by user. log the warning and move on.
+                tracing::warn!("failed to initialize global trace dispatcher: {}", e);
+            }
+        }
+
+        res.map(|service| service::DefaultsService {
+            service,
+            defaults: self.clone(),
+        })
+    }
+}
+
+/// request id of current request. stored in request extensions by [Defaults] middleware.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequestId(HeaderValue);
+
+impl RequestId {
+    // max length of request id accepted from request header.
+    const MAX_LEN: usize = 128;
+
+    fn from_header(value: &HeaderValue) -> Option<Self> {
+        (!value.is_empty() && value.len() <= Self::MAX_LEN && value.to_str().is_ok()).then(|| Self(value.clone()))
+    }
+
+    fn generate() -> Self {
+        use std::{
+            hash::{BuildHasher, RandomState},
+            sync::{
+                atomic::{AtomicU64, Ordering},
+                OnceLock,
+            },
+        };
+
+        static SEED: OnceLock<u64> = OnceLock::new();
+        static COUNT: AtomicU64 = AtomicU64::new(0);
+
+        let seed = *SEED.get_or_init(|| RandomState::new().hash_one(std::process::id()));
+        let count = COUNT.fetch_add(1, Ordering::Relaxed);
+
+        let value = format!("{seed:016x}{count:016x}");
+        Self(HeaderValue::try_from(value).unwrap())
+    }
+
+    /// request id as string slice.
+    pub fn as_str(&self) -> &str {
+        // RequestId is constructed from visible ascii only.
+        self.0.to_str().unwrap()
+    }
+}
+
+impl core::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+mod service {
+    use xitca_unsafe_collection::futures::CatchUnwind;
+
+    use core::panic::AssertUnwindSafe;
+
+    use crate::{
+        error::{Error, ThreadJoinError},
+        http::WebResponse,
+        service::{ready::ReadyService, Service},
+        WebContext,
+    };
+
+    use super::*;
+
+    pub struct DefaultsService<S> {
+        pub(super) service: S,
+        pub(super) defaults: Defaults,
+    }
+
+    impl<S> DefaultsService<S> {
+        async fn call_inner<'r, C, B, ResB, SE>(&self, ctx: WebContext<'r, C, B>) -> Result<WebResponse<ResB>, Error<C>>
+        where
+            S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = SE>,
+            SE: Into<Error<C>>,
+        {
+            let fut = async {
+                match self.defaults.config {
+                    Some(ref config) => config.enforce(&self.service, ctx).await,
+                    None => self.service.call(ctx).await.map_err(Into::into),
+                }
+            };
+
+            let mut res = if self.defaults.catch_panic {
+                CatchUnwind::new(AssertUnwindSafe(fut))
+                    .await
+                    .map_err(|e| Error::from(ThreadJoinError::new(e)))?
+            } else {
+                fut.await
+            }?;
+
+            if let Some(ref headers) = self.defaults.security_headers {
+                headers.apply(res.headers_mut());
+            }
+
+            Ok(res)
+        }
+
+        #[cfg(feature = "logger")]
+        async fn call_logged<'r, C, B, ResB, SE>(
+            &self,
+            ctx: WebContext<'r, C, B>,
+            id: Option<&RequestId>,
+        ) -> Result<WebResponse<ResB>, Error<C>>
+        where
+            S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = SE>,
+            SE: Into<Error<C>>,
+        {
+            use tracing::{event, span, Instrument};
+
+            let Some(level) = self.defaults.logger else {
+                return self.call_inner(ctx).await;
+            };
+
+            macro_rules! span2 {
+                ($lvl:expr, $name:expr, $($fields:tt)*) => {
+                    match $lvl {
+                        Level::TRACE => span!(Level::TRACE, $name, $($fields)*),
+                        Level::DEBUG => span!(Level::DEBUG, $name, $($fields)*),
+                        Level::INFO => span!(Level::INFO, $name, $($fields)*),
+                        Level::WARN => span!(Level::WARN, $name, $($fields)*),
+                        Level::ERROR => span!(Level::ERROR, $name, $($fields)*),
+                    }
+                }
+            }
+
+            let req = ctx.req();
+            let span = span2!(
+                level,
+                "request",
+                method = %req.method(),
+                uri = %req.uri(),
+                request_id = id.map(RequestId::as_str)
+            );
+
+            async {
+                event!(target: "on_request", Level::INFO, "serving request");
+                match self.call_inner(ctx).await {
+                    Ok(res) => {
+                        event!(target: "on_response", Level::INFO, status = %res.status(), "sending response");
+                        Ok(res)
+                    }
+                    Err(e) => {
+                        event!(target: "on_error", Level::WARN, "{}", e);
+                        Err(e)
+                    }
+                }
+            }
+            .instrument(span)
+            .await
+        }
+    }
+
+    impl<'r, C, B, S, ResB, SE> Service<WebContext<'r, C, B>> for DefaultsService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = SE>,
+        SE: Into<Error<C>>,
+    {
+        type Response = WebResponse<ResB>;
+        type Error = Error<C>;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let id = self.defaults.request_id.as_ref().map(|name| {
+                let id = ctx
+                    .req()
+                    .headers()
+                    .get(name)
+                    .and_then(RequestId::from_header)
+                    .unwrap_or_else(RequestId::generate);
+                ctx.req_mut().extensions_mut().insert(id.clone());
+                (name, id)
+            });
+
+            #[cfg(feature = "logger")]
+            let res = self.call_logged(ctx, id.as_ref().map(|(_, id)| id)).await;
+            #[cfg(not(feature = "logger"))]
+            let res = self.call_inner(ctx).await;
+
+            res.map(|mut res| {
+                if let Some((name, id)) = id {
+                    res.headers_mut().entry(name).or_insert(id.0);
+                }
+                res
+            })
+        }
+    }
+
+    impl<S> ReadyService for DefaultsService<S>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::{extension::ExtensionRef, handler_service},
+        http::{
+            header::{CONTENT_LENGTH, X_FRAME_OPTIONS},
+            StatusCode, Uri, WebRequest,
+        },
+        route::get,
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        async fn handler(ExtensionRef(id): ExtensionRef<'_, RequestId>) -> String {
+            id.to_string()
+        }
+
+        async fn panic() -> &'static str {
+            panic!("");
+        }
+
+        let service = App::new()
+            .at("/", get(handler_service(handler)))
+            .at("/panic", get(handler_service(panic)))
+            // request timeout needs tokio runtime. replace default limits with body size limit only.
+            .enclosed(Defaults::new().disable_limits().max_request_body_size(8))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(WebRequest::default()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().contains_key(X_FRAME_OPTIONS));
+        let id = res.headers().get(X_REQUEST_ID).unwrap().clone();
+        assert_eq!(id.len(), 32);
+
+        let res = service.call(WebRequest::default()).now_or_panic().unwrap();
+        assert_ne!(res.headers().get(X_REQUEST_ID), Some(&id));
+
+        let mut req = WebRequest::default();
+        req.headers_mut().insert(X_REQUEST_ID, HeaderValue::from_static("996"));
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.headers().get(X_REQUEST_ID).unwrap(), "996");
+
+        let mut req = WebRequest::default();
+        req.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from_static("16"));
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let mut req = WebRequest::default();
+        *req.uri_mut() = Uri::from_static("/panic");
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn empty() {
+        let service = App::new()
+            .at("/", get(handler_service(|| async { "" })))
+            .enclosed(Defaults::empty())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(WebRequest::default()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res.headers().contains_key(X_FRAME_OPTIONS));
+        assert!(!res.headers().contains_key(X_REQUEST_ID));
+    }
+}
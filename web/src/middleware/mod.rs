@@ -293,6 +293,8 @@
 //! [`RequestBody`]: crate::body::RequestBody
 //! [`WebResponse<B>`]: crate::http::WebResponse
 
+#[cfg(feature = "cache")]
+pub mod cache;
 #[cfg(any(feature = "compress-br", feature = "compress-gz", feature = "compress-de"))]
 pub mod compress;
 #[cfg(any(feature = "compress-br", feature = "compress-gz", feature = "compress-de"))]
@@ -303,9 +305,16 @@ pub mod rate_limit;
 pub mod sync;
 #[cfg(feature = "tower-http-compat")]
 pub mod tower_http_compat;
+#[cfg(feature = "webhook")]
+pub mod webhook;
 
+pub mod base_url;
+pub mod defaults;
 pub mod eraser;
 pub mod limit;
+pub mod normalize_path;
+pub mod security_headers;
+pub mod server_timing;
 
 #[cfg(feature = "logger")]
 mod logger;
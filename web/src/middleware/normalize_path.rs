@@ -0,0 +1,256 @@
+//! request path normalization middleware.
+
+use std::borrow::Cow;
+
+use crate::{
+    error::Error,
+    http::{uri::PathAndQuery, StatusCode, Uri},
+    service::{ready::ReadyService, Service},
+    WebContext,
+};
+
+/// Middleware for normalizing request path before it reaches router.
+///
+/// Normalization includes:
+/// - collapse duplicate slashes. e.g: `//foo///bar` -> `/foo/bar`
+/// - resolve `.` and `..` segments (including their percent encoded form). `..` can not escape root path.
+///   e.g: `/foo/./bar/../../../baz` -> `/baz`
+/// - handle percent encoded slash (`%2F`) according to [`EncodedSlash`] policy.
+///
+/// Trailing slash of path is preserved by default and can be trimmed with [`NormalizePath::trim_trailing_slash`].
+/// Query string of uri is never touched.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::normalize_path::{EncodedSlash, NormalizePath}, App, WebContext};
+/// App::new()
+///     .at("/foo/bar", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///     // request to "//foo/./bar/" would be routed to "/foo/bar"
+///     .enclosed(NormalizePath::new().trim_trailing_slash().encoded_slash(EncodedSlash::Reject));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct NormalizePath {
+    trim_trailing_slash: bool,
+    encoded_slash: EncodedSlash,
+}
+
+/// Policy for handling percent encoded slash (`%2F`) in request path.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EncodedSlash {
+    /// keep encoded slash as is. It would be treated as part of path segment.
+    #[default]
+    Keep,
+    /// decode encoded slash to `/` before normalizing. It would be treated as path separator.
+    Decode,
+    /// reject request containing encoded slash with `400 Bad Request` response.
+    Reject,
+}
+
+impl Default for NormalizePath {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NormalizePath {
+    pub const fn new() -> Self {
+        Self {
+            trim_trailing_slash: false,
+            encoded_slash: EncodedSlash::Keep,
+        }
+    }
+
+    /// Remove trailing slash from normalized path. e.g: `/foo/` -> `/foo`.
+    /// root path `/` is not affected.
+    pub const fn trim_trailing_slash(mut self) -> Self {
+        self.trim_trailing_slash = true;
+        self
+    }
+
+    /// Set policy for handling percent encoded slash. Default to [`EncodedSlash::Keep`].
+    pub const fn encoded_slash(mut self, policy: EncodedSlash) -> Self {
+        self.encoded_slash = policy;
+        self
+    }
+
+    // return Ok(None) when path is already normalized.
+    fn normalize(&self, path: &str) -> Result<Option<String>, ()> {
+        let origin = path;
+        let path = match self.encoded_slash {
+            EncodedSlash::Keep => Cow::Borrowed(path),
+            EncodedSlash::Decode if contains_encoded_slash(path) => {
+                Cow::Owned(path.replace("%2F", "/").replace("%2f", "/"))
+            }
+            EncodedSlash::Decode => Cow::Borrowed(path),
+            EncodedSlash::Reject if contains_encoded_slash(path) => return Err(()),
+            EncodedSlash::Reject => Cow::Borrowed(path),
+        };
+
+        let mut segments = Vec::new();
+        let mut trailing_slash = false;
+
+        for segment in path.split('/') {
+            trailing_slash = true;
+            match segment {
+                "" => {}
+                s if is_dot(s) => {}
+                s if is_dot_dot(s) => {
+                    segments.pop();
+                }
+                s => {
+                    segments.push(s);
+                    trailing_slash = false;
+                }
+            }
+        }
+
+        let mut normalized = String::with_capacity(path.len());
+        for segment in segments.iter() {
+            normalized.push('/');
+            normalized.push_str(segment);
+        }
+
+        if normalized.is_empty() || (trailing_slash && !self.trim_trailing_slash) {
+            normalized.push('/');
+        }
+
+        Ok((normalized != origin).then_some(normalized))
+    }
+}
+
+fn contains_encoded_slash(path: &str) -> bool {
+    path.as_bytes()
+        .windows(3)
+        .any(|w| w[0] == b'%' && w[1] == b'2' && (w[2] == b'F' || w[2] == b'f'))
+}
+
+fn is_dot(segment: &str) -> bool {
+    segment == "." || segment.eq_ignore_ascii_case("%2e")
+}
+
+fn is_dot_dot(segment: &str) -> bool {
+    match segment.len() {
+        2 => segment == "..",
+        4 => segment.eq_ignore_ascii_case(".%2e") || segment.eq_ignore_ascii_case("%2e."),
+        6 => segment.eq_ignore_ascii_case("%2e%2e"),
+        _ => false,
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for NormalizePath {
+    type Response = service::NormalizePathService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::NormalizePathService {
+            service,
+            normalize: *self,
+        })
+    }
+}
+
+mod service {
+    use super::*;
+
+    pub struct NormalizePathService<S> {
+        pub(super) service: S,
+        pub(super) normalize: NormalizePath,
+    }
+
+    impl<'r, S, C, B> Service<WebContext<'r, C, B>> for NormalizePathService<S>
+    where
+        S: Service<WebContext<'r, C, B>>,
+        S::Error: Into<Error<C>>,
+    {
+        type Response = S::Response;
+        type Error = Error<C>;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let uri = ctx.req().uri();
+            let normalized = self
+                .normalize
+                .normalize(uri.path())
+                .map_err(|_| Error::from(StatusCode::BAD_REQUEST))?;
+
+            if let Some(mut path) = normalized {
+                if let Some(query) = uri.query() {
+                    path.push('?');
+                    path.push_str(query);
+                }
+                let mut parts = uri.clone().into_parts();
+                parts.path_and_query = Some(PathAndQuery::try_from(path).map_err(|_| StatusCode::BAD_REQUEST)?);
+                *ctx.req_mut().uri_mut() = Uri::from_parts(parts).map_err(|_| StatusCode::BAD_REQUEST)?;
+            }
+
+            self.service.call(ctx).await.map_err(Into::into)
+        }
+    }
+
+    impl<S> ReadyService for NormalizePathService<S>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{handler::handler_service, http::Request, App};
+
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        let n = NormalizePath::new();
+        assert_eq!(n.normalize("/foo/bar"), Ok(None));
+        assert_eq!(n.normalize("/foo/bar/"), Ok(None));
+        assert_eq!(n.normalize("/"), Ok(None));
+        assert_eq!(n.normalize(""), Ok(Some("/".into())));
+        assert_eq!(n.normalize("//foo///bar"), Ok(Some("/foo/bar".into())));
+        assert_eq!(n.normalize("/foo/./bar/."), Ok(Some("/foo/bar/".into())));
+        assert_eq!(n.normalize("/foo/bar/../baz"), Ok(Some("/foo/baz".into())));
+        assert_eq!(n.normalize("/../../foo"), Ok(Some("/foo".into())));
+        assert_eq!(n.normalize("/foo/%2e%2E/../bar"), Ok(Some("/bar".into())));
+        assert_eq!(n.normalize("/foo%2Fbar"), Ok(None));
+
+        let n = NormalizePath::new().trim_trailing_slash();
+        assert_eq!(n.normalize("/foo/bar/"), Ok(Some("/foo/bar".into())));
+        assert_eq!(n.normalize("/foo/.."), Ok(Some("/".into())));
+        assert_eq!(n.normalize("/"), Ok(None));
+
+        let n = NormalizePath::new().encoded_slash(EncodedSlash::Decode);
+        assert_eq!(n.normalize("/foo%2Fbar%2f..%2Fbaz"), Ok(Some("/foo/baz".into())));
+
+        let n = NormalizePath::new().encoded_slash(EncodedSlash::Reject);
+        assert_eq!(n.normalize("/foo%2fbar"), Err(()));
+    }
+
+    #[test]
+    fn route() {
+        let service = App::new()
+            .at("/foo/bar", handler_service(|| async { "" }))
+            .enclosed(NormalizePath::new().encoded_slash(EncodedSlash::Reject))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut req = Request::default();
+        *req.uri_mut() = Uri::from_static("//foo/./baz/../bar?foo=bar");
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let mut req = Request::default();
+        *req.uri_mut() = Uri::from_static("/foo%2Fbar");
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
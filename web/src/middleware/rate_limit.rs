@@ -8,6 +8,10 @@ use crate::service::Service;
 
 /// builder for client ip address based rate limiting middleware.
 ///
+/// When enclosed by [TrustPolicy](crate::handler::client_ip::TrustPolicy) middleware the resolved
+/// [ClientIp](crate::handler::client_ip::ClientIp) is used as rate limit key. Otherwise forwarded headers are
+/// trusted as is.
+///
 /// # Examples
 /// ```rust
 /// # use xitca_web::{handler::handler_service, middleware::rate_limit::RateLimit, route::get, App, WebContext};
@@ -65,6 +69,7 @@ mod service {
     use crate::{
         body::ResponseBody,
         error::Error,
+        handler::client_ip::ClientIp,
         http::WebResponse,
         service::{ready::ReadyService, Service},
         WebContext,
@@ -83,9 +88,13 @@ mod service {
         type Error = Error<C>;
 
         async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
-            let headers = ctx.req().headers();
-            let addr = ctx.req().body().socket_addr();
-            let snap = self.rate_limit.rate_limit(headers, addr).map_err(Error::from_service)?;
+            let req = ctx.req();
+            // prefer client address resolved by TrustPolicy middleware.
+            let snap = match req.extensions().get::<ClientIp>() {
+                Some(ClientIp(ip)) => self.rate_limit.rate_limit_ip(*ip),
+                None => self.rate_limit.rate_limit(req.headers(), req.body().socket_addr()),
+            }
+            .map_err(Error::from_service)?;
             self.service.call(ctx).await.map(|mut res| {
                 snap.extend_response(&mut res);
                 res
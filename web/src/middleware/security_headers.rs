@@ -0,0 +1,317 @@
+//! security related response headers middleware.
+
+use core::time::Duration;
+
+use crate::{
+    http::header::{
+        HeaderMap, HeaderName, HeaderValue, CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY_REPORT_ONLY,
+        REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+    },
+    service::Service,
+};
+
+/// Middleware for adding security related headers to response.
+///
+/// Default headers are:
+/// - `X-Content-Type-Options: nosniff`
+/// - `X-Frame-Options: DENY`
+/// - `Referrer-Policy: strict-origin-when-cross-origin`
+/// - `Strict-Transport-Security: max-age=31536000; includeSubDomains`
+///
+/// Headers are only added when response does not have them already. Nesting SecurityHeaders in route scope
+/// would override the global ones enclosing App.
+///
+/// Error produced by enclosed services is passed through as is and the headers are not applied to it's
+/// response.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::handler_service,
+/// #   middleware::security_headers::{ContentSecurityPolicy, FrameOptions, SecurityHeaders},
+/// #   route::get,
+/// #   service::ServiceExt,
+/// #   App, WebContext};
+/// App::new()
+///     .at("/", get(handler_service(|_: &WebContext<'_>| async { "hello,world!" })))
+///     // per route override.
+///     .at(
+///         "/embed",
+///         get(handler_service(|_: &WebContext<'_>| async { "embed" }))
+///             .enclosed(SecurityHeaders::new().frame_options(FrameOptions::SameOrigin)),
+///     )
+///     // global headers.
+///     .enclosed(
+///         SecurityHeaders::new().content_security_policy(
+///             ContentSecurityPolicy::new()
+///                 .directive("default-src", ["'self'"])
+///                 .directive("img-src", ["'self'", "data:"]),
+///         ),
+///     );
+/// ```
+#[derive(Clone)]
+pub struct SecurityHeaders {
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+/// value of `X-Frame-Options` header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FrameOptions {
+    Deny,
+    SameOrigin,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecurityHeaders {
+    /// construct middleware with default headers. See [SecurityHeaders] for detail.
+    pub fn new() -> Self {
+        Self {
+            headers: vec![
+                (X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+                (X_FRAME_OPTIONS, HeaderValue::from_static("DENY")),
+                (
+                    REFERRER_POLICY,
+                    HeaderValue::from_static("strict-origin-when-cross-origin"),
+                ),
+                (
+                    STRICT_TRANSPORT_SECURITY,
+                    HeaderValue::from_static("max-age=31536000; includeSubDomains"),
+                ),
+            ],
+        }
+    }
+
+    /// construct middleware without any header.
+    pub fn empty() -> Self {
+        Self { headers: Vec::new() }
+    }
+
+    /// set `X-Frame-Options` header.
+    pub fn frame_options(self, opt: FrameOptions) -> Self {
+        let value = match opt {
+            FrameOptions::Deny => "DENY",
+            FrameOptions::SameOrigin => "SAMEORIGIN",
+        };
+        self.header(X_FRAME_OPTIONS, HeaderValue::from_static(value))
+    }
+
+    /// set `Referrer-Policy` header. e.g: `no-referrer`
+    ///
+    /// # Panics
+    /// When policy is not a valid header value.
+    pub fn referrer_policy(self, policy: &str) -> Self {
+        self.header(REFERRER_POLICY, HeaderValue::from_str(policy).unwrap())
+    }
+
+    /// set `Strict-Transport-Security` header with given max age and optional directives.
+    pub fn hsts(self, max_age: Duration, include_sub_domains: bool, preload: bool) -> Self {
+        let mut value = format!("max-age={}", max_age.as_secs());
+        if include_sub_domains {
+            value.push_str("; includeSubDomains");
+        }
+        if preload {
+            value.push_str("; preload");
+        }
+        self.header(STRICT_TRANSPORT_SECURITY, HeaderValue::try_from(value).unwrap())
+    }
+
+    /// set `Content-Security-Policy` header. `Content-Security-Policy-Report-Only` header is set instead when
+    /// [ContentSecurityPolicy::report_only] is enabled.
+    pub fn content_security_policy(self, csp: ContentSecurityPolicy) -> Self {
+        let name = if csp.report_only {
+            CONTENT_SECURITY_POLICY_REPORT_ONLY
+        } else {
+            CONTENT_SECURITY_POLICY
+        };
+        self.header(name, csp.finish())
+    }
+
+    /// set arbitrary header. existing header with the same name is replaced.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.retain(|(n, _)| *n != name);
+        self.headers.push((name, value));
+        self
+    }
+
+    /// remove header with given name. e.g: removing `Strict-Transport-Security` header for plain http server.
+    pub fn remove(mut self, name: HeaderName) -> Self {
+        self.headers.retain(|(n, _)| *n != name);
+        self
+    }
+
+    // add headers to response when they are absent.
+    pub(crate) fn apply(&self, headers: &mut HeaderMap) {
+        for (name, value) in self.headers.iter() {
+            if !headers.contains_key(name) {
+                headers.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// builder type for `Content-Security-Policy` header value.
+#[derive(Clone, Debug, Default)]
+pub struct ContentSecurityPolicy {
+    directives: Vec<String>,
+    report_only: bool,
+}
+
+impl ContentSecurityPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add directive with given sources. e.g: `directive("script-src", ["'self'", "https://example.com"])`
+    /// directive with the same name is replaced.
+    pub fn directive<'a>(mut self, name: &str, sources: impl IntoIterator<Item = &'a str>) -> Self {
+        self.directives
+            .retain(|d| d.split(' ').next().is_some_and(|n| !n.eq_ignore_ascii_case(name)));
+        let mut directive = String::from(name);
+        for source in sources {
+            directive.push(' ');
+            directive.push_str(source);
+        }
+        self.directives.push(directive);
+        self
+    }
+
+    /// use `Content-Security-Policy-Report-Only` header for monitoring policy violations without enforcing.
+    pub fn report_only(mut self) -> Self {
+        self.report_only = true;
+        self
+    }
+
+    /// # Panics
+    /// When directives contain invalid header value.
+    fn finish(self) -> HeaderValue {
+        HeaderValue::try_from(self.directives.join("; ")).expect("invalid Content-Security-Policy directive")
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for SecurityHeaders {
+    type Response = service::SecurityHeadersService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::SecurityHeadersService {
+            service,
+            headers: self.clone(),
+        })
+    }
+}
+
+mod service {
+    use crate::{
+        http::WebResponse,
+        service::{ready::ReadyService, Service},
+        WebContext,
+    };
+
+    use super::*;
+
+    pub struct SecurityHeadersService<S> {
+        pub(super) service: S,
+        pub(super) headers: SecurityHeaders,
+    }
+
+    impl<'r, C, B, S, ResB> Service<WebContext<'r, C, B>> for SecurityHeadersService<S>
+    where
+        S: Service<WebContext<'r, C, B>, Response = WebResponse<ResB>>,
+    {
+        type Response = WebResponse<ResB>;
+        type Error = S::Error;
+
+        async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            self.service.call(ctx).await.map(|mut res| {
+                self.headers.apply(res.headers_mut());
+                res
+            })
+        }
+    }
+
+    impl<S> ReadyService for SecurityHeadersService<S>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{StatusCode, Uri, WebRequest},
+        route::get,
+        service::ServiceExt,
+        App, WebContext,
+    };
+
+    use super::*;
+
+    #[test]
+    fn headers() {
+        let service = App::new()
+            .at("/", get(handler_service(|_: &WebContext<'_>| async { "" })))
+            .at(
+                "/embed",
+                get(handler_service(|_: &WebContext<'_>| async { "" }))
+                    .enclosed(SecurityHeaders::empty().frame_options(FrameOptions::SameOrigin)),
+            )
+            .enclosed(
+                SecurityHeaders::new()
+                    .remove(STRICT_TRANSPORT_SECURITY)
+                    .content_security_policy(
+                        ContentSecurityPolicy::new()
+                            .directive("default-src", ["'none'"])
+                            .directive("img-src", ["'self'", "data:"])
+                            .directive("default-src", ["'self'"]),
+                    ),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |uri| {
+            let mut req = WebRequest::default();
+            *req.uri_mut() = Uri::from_static(uri);
+            service.call(req).now_or_panic().unwrap()
+        };
+
+        let res = call("/");
+        assert_eq!(res.status(), StatusCode::OK);
+        let headers = res.headers();
+        assert_eq!(headers.get(X_CONTENT_TYPE_OPTIONS).unwrap(), "nosniff");
+        assert_eq!(headers.get(X_FRAME_OPTIONS).unwrap(), "DENY");
+        assert!(headers.get(STRICT_TRANSPORT_SECURITY).is_none());
+        assert_eq!(
+            headers.get(CONTENT_SECURITY_POLICY).unwrap(),
+            "img-src 'self' data:; default-src 'self'"
+        );
+
+        let res = call("/embed");
+        assert_eq!(res.headers().get(X_FRAME_OPTIONS).unwrap(), "SAMEORIGIN");
+        assert_eq!(res.headers().get(X_CONTENT_TYPE_OPTIONS).unwrap(), "nosniff");
+    }
+
+    #[test]
+    fn hsts() {
+        let headers = SecurityHeaders::empty()
+            .hsts(Duration::from_secs(60), false, true)
+            .headers;
+        assert_eq!(headers[0].1, "max-age=60; preload");
+    }
+}
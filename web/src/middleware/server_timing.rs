@@ -0,0 +1,328 @@
+//! `Server-Timing` response header middleware.
+
+use core::{fmt::Write, time::Duration};
+
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use crate::{
+    http::header::{HeaderName, HeaderValue},
+    service::Service,
+};
+
+/// `Server-Timing` header name.
+pub const SERVER_TIMING: HeaderName = HeaderName::from_static("server-timing");
+
+/// Middleware collecting timing metrics contributed by handlers and emitting them as `Server-Timing` response
+/// header.
+///
+/// A [ServerTiming] handle is stored in request extensions and can be extracted with
+/// [ExtensionRef](crate::handler::extension::ExtensionRef) for recording metrics. A `total` metric of the
+/// time spent by enclosed services is added by default.
+///
+/// Metrics are only emitted with successful response of enclosed services. Error is passed through as is.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{
+/// #   handler::{extension::ExtensionRef, handler_service},
+/// #   middleware::server_timing::{ServerTiming, ServerTimingHeader},
+/// #   route::get,
+/// #   App, WebContext};
+/// async fn handler(ExtensionRef(timing): ExtensionRef<'_, ServerTiming>) -> &'static str {
+///     {
+///         // record duration of scope with a guard.
+///         let _guard = timing.start("db").desc("Database");
+///         // query database.
+///     }
+///
+///     // record duration directly.
+///     timing.record("cache", Duration::from_millis(3));
+///
+///     "hello,world!"
+/// }
+///
+/// App::new()
+///     .at("/", get(handler_service(handler)))
+///     # .at("/infer", handler_service(|_: &WebContext<'_>| async{ "infer type" }))
+///     // response header would be like: Server-Timing: db;desc="Database";dur=1.2, cache;dur=3, total;dur=1.5
+///     .enclosed(ServerTimingHeader::new());
+/// ```
+#[derive(Clone, Copy)]
+pub struct ServerTimingHeader {
+    total: bool,
+}
+
+impl Default for ServerTimingHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServerTimingHeader {
+    pub const fn new() -> Self {
+        Self { total: true }
+    }
+
+    /// disable `total` metric of the time spent by enclosed services.
+    pub fn disable_total(mut self) -> Self {
+        self.total = false;
+        self
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for ServerTimingHeader {
+    type Response = service::ServerTimingService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::ServerTimingService {
+            service,
+            total: self.total,
+        })
+    }
+}
+
+/// handle for recording timing metrics of current request. stored in request extensions by
+/// [ServerTimingHeader] middleware.
+#[derive(Clone, Debug, Default)]
+pub struct ServerTiming {
+    metrics: Arc<Mutex<Vec<Metric>>>,
+}
+
+#[derive(Debug)]
+struct Metric {
+    name: Cow<'static, str>,
+    dur: Option<Duration>,
+    desc: Option<Cow<'static, str>>,
+}
+
+impl ServerTiming {
+    /// record a metric with given name and duration.
+    ///
+    /// # Panics
+    /// When name is not a valid http token.
+    pub fn record(&self, name: impl Into<Cow<'static, str>>, dur: Duration) {
+        self.push(name.into(), Some(dur), None);
+    }
+
+    /// record a metric with given name, duration and description.
+    ///
+    /// # Panics
+    /// When name is not a valid http token.
+    pub fn record_with_desc(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        dur: Duration,
+        desc: impl Into<Cow<'static, str>>,
+    ) {
+        self.push(name.into(), Some(dur), Some(desc.into()));
+    }
+
+    /// start timing a metric with given name. The duration is recorded when returned guard is dropped.
+    ///
+    /// # Panics
+    /// When name is not a valid http token.
+    pub fn start(&self, name: impl Into<Cow<'static, str>>) -> TimingGuard<'_> {
+        let name = name.into();
+        assert!(is_token(&name), "server timing metric name must be valid http token");
+        TimingGuard {
+            timing: self,
+            name: Some(name),
+            desc: None,
+            start: Instant::now(),
+        }
+    }
+
+    fn push(&self, name: Cow<'static, str>, dur: Option<Duration>, desc: Option<Cow<'static, str>>) {
+        assert!(is_token(&name), "server timing metric name must be valid http token");
+        self.metrics.lock().unwrap().push(Metric { name, dur, desc });
+    }
+
+    fn header_value(&self) -> Option<HeaderValue> {
+        let metrics = self.metrics.lock().unwrap();
+
+        if metrics.is_empty() {
+            return None;
+        }
+
+        let mut value = String::new();
+
+        for (i, metric) in metrics.iter().enumerate() {
+            if i != 0 {
+                value.push_str(", ");
+            }
+            value.push_str(&metric.name);
+            if let Some(ref desc) = metric.desc {
+                value.push_str(";desc=\"");
+                for c in desc.chars().filter(|c| !c.is_control()) {
+                    if c == '"' || c == '\\' {
+                        value.push('\\');
+                    }
+                    value.push(c);
+                }
+                value.push('"');
+            }
+            if let Some(dur) = metric.dur {
+                // duration in milliseconds.
+                let _ = write!(value, ";dur={}", dur.as_micros() as f64 / 1000.0);
+            }
+        }
+
+        // non ascii description would be rejected here and the header is skipped.
+        HeaderValue::try_from(value).ok()
+    }
+}
+
+/// guard for recording duration of a metric started by [ServerTiming::start].
+pub struct TimingGuard<'a> {
+    timing: &'a ServerTiming,
+    name: Option<Cow<'static, str>>,
+    desc: Option<Cow<'static, str>>,
+    start: Instant,
+}
+
+impl TimingGuard<'_> {
+    /// set description of the metric.
+    pub fn desc(mut self, desc: impl Into<Cow<'static, str>>) -> Self {
+        self.desc = Some(desc.into());
+        self
+    }
+}
+
+impl Drop for TimingGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(name) = self.name.take() {
+            self.timing.push(name, Some(self.start.elapsed()), self.desc.take());
+        }
+    }
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+mod service {
+    use crate::{
+        http::WebResponse,
+        service::{ready::ReadyService, Service},
+        WebContext,
+    };
+
+    use super::*;
+
+    pub struct ServerTimingService<S> {
+        pub(super) service: S,
+        pub(super) total: bool,
+    }
+
+    impl<'r, C, B, S, ResB> Service<WebContext<'r, C, B>> for ServerTimingService<S>
+    where
+        S: Service<WebContext<'r, C, B>, Response = WebResponse<ResB>>,
+    {
+        type Response = WebResponse<ResB>;
+        type Error = S::Error;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let start = Instant::now();
+
+            let timing = ServerTiming::default();
+            ctx.req_mut().extensions_mut().insert(timing.clone());
+
+            let mut res = self.service.call(ctx).await?;
+
+            if self.total {
+                timing.record("total", start.elapsed());
+            }
+
+            if let Some(value) = timing.header_value() {
+                res.headers_mut().append(SERVER_TIMING, value);
+            }
+
+            Ok(res)
+        }
+    }
+
+    impl<S> ReadyService for ServerTimingService<S>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::{extension::ExtensionRef, handler_service},
+        http::WebRequest,
+        route::get,
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn header() {
+        async fn handler(ExtensionRef(timing): ExtensionRef<'_, ServerTiming>) -> &'static str {
+            drop(timing.start("db").desc("Data\"base"));
+            timing.record("cache", Duration::from_micros(1500));
+            ""
+        }
+
+        let res = App::new()
+            .at("/", get(handler_service(handler)))
+            .enclosed(ServerTimingHeader::new())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(WebRequest::default())
+            .now_or_panic()
+            .unwrap();
+
+        let value = res.headers().get(SERVER_TIMING).unwrap().to_str().unwrap();
+        let metrics = value.split(", ").collect::<Vec<_>>();
+        assert_eq!(metrics.len(), 3);
+        assert!(metrics[0].starts_with("db;desc=\"Data\\\"base\";dur="));
+        assert_eq!(metrics[1], "cache;dur=1.5");
+        assert!(metrics[2].starts_with("total;dur="));
+    }
+
+    #[test]
+    fn no_metric() {
+        let res = App::new()
+            .at("/", get(handler_service(|| async { "" })))
+            .enclosed(ServerTimingHeader::new().disable_total())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(WebRequest::default())
+            .now_or_panic()
+            .unwrap();
+
+        assert!(!res.headers().contains_key(SERVER_TIMING));
+    }
+
+    #[test]
+    fn token() {
+        assert!(is_token("db-query_1"));
+        assert!(!is_token(""));
+        assert!(!is_token("db query"));
+        assert!(!is_token("db;"));
+    }
+}
@@ -0,0 +1,380 @@
+//! webhook signature verification middleware.
+
+use core::time::Duration;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::hmac;
+
+use crate::{
+    http::header::{HeaderMap, HeaderName},
+    service::Service,
+};
+
+/// Middleware for verifying HMAC-SHA256 signature of webhook request.
+///
+/// Request body is streamed into a single buffer while the signature is computed. When the signature matches
+/// the one carried by request header the buffer becomes the new request body and downstream extractors(like
+/// [Json](crate::handler::json::Json)) can consume it without reading from network again. Request with missing
+/// or mismatched signature is rejected with `401 Unauthorized` response before reaching enclosed service.
+///
+/// Body size limit of [ServiceConfig](crate::config::ServiceConfig) is honored when reading the body.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::webhook::VerifySignature, route::post, App, WebContext};
+/// App::new()
+///     .at("/github", post(handler_service(|_: &WebContext<'_>, body: String| async move { body })))
+///     // verify `X-Hub-Signature-256` header from github.
+///     .enclosed(VerifySignature::github(b"secret"));
+/// ```
+#[derive(Clone)]
+pub struct VerifySignature {
+    key: hmac::Key,
+    header: HeaderName,
+    scheme: Scheme,
+}
+
+#[derive(Clone)]
+enum Scheme {
+    Hex { prefix: Option<String> },
+    Base64 { prefix: Option<String> },
+    Stripe { tolerance: Duration },
+}
+
+impl VerifySignature {
+    /// verify hex encoded HMAC-SHA256 signature of request body carried by given header.
+    pub fn hmac_sha256(secret: &[u8], header: HeaderName) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+            header,
+            scheme: Scheme::Hex { prefix: None },
+        }
+    }
+
+    /// github webhook scheme. signature is carried by `X-Hub-Signature-256` header in form of
+    /// `sha256=<hex signature>`.
+    pub fn github(secret: &[u8]) -> Self {
+        Self::hmac_sha256(secret, HeaderName::from_static("x-hub-signature-256")).prefix("sha256=")
+    }
+
+    /// shopify webhook scheme. signature is carried by `X-Shopify-Hmac-Sha256` header in base64 encoding.
+    pub fn shopify(secret: &[u8]) -> Self {
+        Self::hmac_sha256(secret, HeaderName::from_static("x-shopify-hmac-sha256")).base64()
+    }
+
+    /// stripe webhook scheme. signature is carried by `Stripe-Signature` header in form of
+    /// `t=<timestamp>,v1=<hex signature>` and signed payload is `<timestamp>.<body>`. Request with timestamp
+    /// older than 5 minutes is rejected. See [VerifySignature::tolerance] for changing it.
+    pub fn stripe(secret: &[u8]) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+            header: HeaderName::from_static("stripe-signature"),
+            scheme: Scheme::Stripe {
+                tolerance: Duration::from_secs(300),
+            },
+        }
+    }
+
+    /// strip given prefix from header value before decoding signature. e.g: `sha256=`
+    ///
+    /// No op for [VerifySignature::stripe] scheme.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        match self.scheme {
+            Scheme::Hex { prefix: ref mut p } | Scheme::Base64 { prefix: ref mut p } => *p = Some(prefix.into()),
+            Scheme::Stripe { .. } => {}
+        }
+        self
+    }
+
+    /// decode signature in standard base64 encoding instead of hex.
+    ///
+    /// No op for [VerifySignature::stripe] scheme.
+    pub fn base64(mut self) -> Self {
+        if let Scheme::Hex { ref mut prefix } = self.scheme {
+            self.scheme = Scheme::Base64 { prefix: prefix.take() };
+        }
+        self
+    }
+
+    /// set max age of signed timestamp for [VerifySignature::stripe] scheme.
+    ///
+    /// No op for other schemes.
+    pub fn tolerance(mut self, dur: Duration) -> Self {
+        if let Scheme::Stripe { ref mut tolerance } = self.scheme {
+            *tolerance = dur;
+        }
+        self
+    }
+
+    // parse signed payload prefix and candidate signatures from request headers.
+    fn parse(&self, headers: &HeaderMap) -> Option<(Option<String>, Vec<Vec<u8>>)> {
+        let value = headers.get(&self.header)?.to_str().ok()?.trim();
+        match self.scheme {
+            Scheme::Hex { ref prefix } => {
+                let value = strip_prefix(value, prefix.as_deref())?;
+                Some((None, vec![decode_hex(value)?]))
+            }
+            Scheme::Base64 { ref prefix } => {
+                let value = strip_prefix(value, prefix.as_deref())?;
+                Some((None, vec![STANDARD.decode(value).ok()?]))
+            }
+            Scheme::Stripe { tolerance } => {
+                let mut timestamp = None;
+                let mut signatures = Vec::new();
+                for pair in value.split(',') {
+                    match pair.trim().split_once('=')? {
+                        ("t", t) => timestamp = Some(t),
+                        ("v1", sig) => signatures.extend(decode_hex(sig)),
+                        _ => {}
+                    }
+                }
+                let timestamp = timestamp?;
+                // timestamp overflowing system time is rejected.
+                let signed = UNIX_EPOCH.checked_add(Duration::from_secs(timestamp.parse().ok()?))?;
+                let age = SystemTime::now().duration_since(signed).unwrap_or_default();
+                (age <= tolerance && !signatures.is_empty()).then(|| (Some(format!("{timestamp}.")), signatures))
+            }
+        }
+    }
+}
+
+fn strip_prefix<'a>(value: &'a str, prefix: Option<&str>) -> Option<&'a str> {
+    match prefix {
+        Some(prefix) => value.strip_prefix(prefix),
+        None => Some(value),
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    fn digit(b: u8) -> Option<u8> {
+        match b {
+            b'0'..=b'9' => Some(b - b'0'),
+            b'a'..=b'f' => Some(b - b'a' + 10),
+            b'A'..=b'F' => Some(b - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    let chunks = value.as_bytes().chunks_exact(2);
+    if !chunks.remainder().is_empty() {
+        return None;
+    }
+    chunks
+        .map(|pair| Some(digit(pair[0])? << 4 | digit(pair[1])?))
+        .collect()
+}
+
+impl<S, E> Service<Result<S, E>> for VerifySignature {
+    type Response = service::VerifySignatureService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::VerifySignatureService {
+            service,
+            verify: self.clone(),
+        })
+    }
+}
+
+mod service {
+    use core::{future::poll_fn, pin::pin};
+
+    use crate::{
+        body::BodyStream,
+        bytes::{Bytes, BytesMut},
+        error::{BodyOverFlow, Error},
+        http::StatusCode,
+        service::{ready::ReadyService, Service},
+        WebContext,
+    };
+
+    use super::*;
+
+    pub struct VerifySignatureService<S> {
+        pub(super) service: S,
+        pub(super) verify: VerifySignature,
+    }
+
+    impl<'r, C, B, S, Res, SE> Service<WebContext<'r, C, B>> for VerifySignatureService<S>
+    where
+        B: BodyStream + From<Bytes> + Default,
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = SE>,
+        SE: Into<Error<C>>,
+    {
+        type Response = Res;
+        type Error = Error<C>;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let (prefix, signatures) = self
+                .verify
+                .parse(ctx.req().headers())
+                .ok_or(Error::from(StatusCode::UNAUTHORIZED))?;
+
+            let limit = ctx.service_config().max_request_body_size();
+            let deadline = ctx.deadline();
+
+            // signed payload is prefix followed by body. prefix is split off after verification.
+            let prefix_len = prefix.as_ref().map_or(0, String::len);
+            let mut buf = BytesMut::from(prefix.unwrap_or_default().as_bytes());
+            let mut body = pin!(ctx.take_body_mut());
+
+            loop {
+                let next = poll_fn(|cx| body.as_mut().poll_next(cx));
+                let chunk = match deadline {
+                    Some(deadline) => tokio::time::timeout_at(deadline.instant(), next)
+                        .await
+                        .map_err(|_| Error::from(StatusCode::REQUEST_TIMEOUT))?,
+                    None => next.await,
+                };
+                let Some(chunk) = chunk else { break };
+                let chunk = chunk.map_err(Into::into)?;
+                let chunk = chunk.as_ref();
+                if buf.len() - prefix_len + chunk.len() > limit {
+                    return Err(Error::from(BodyOverFlow { limit }));
+                }
+                buf.extend_from_slice(chunk);
+            }
+
+            if !signatures
+                .iter()
+                .any(|sig| hmac::verify(&self.verify.key, &buf, sig).is_ok())
+            {
+                return Err(Error::from(StatusCode::UNAUTHORIZED));
+            }
+
+            *ctx.body_get_mut() = B::from(buf.split_off(prefix_len).freeze());
+
+            self.service.call(ctx).await.map_err(Into::into)
+        }
+    }
+
+    impl<S> ReadyService for VerifySignatureService<S>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        bytes::Bytes,
+        handler::handler_service,
+        http::{header::HeaderValue, StatusCode, WebRequest},
+        test::collect_body,
+        App, WebContext,
+    };
+
+    use super::*;
+
+    fn sign(payload: &[u8]) -> Vec<u8> {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, b"secret");
+        hmac::sign(&key, payload).as_ref().to_vec()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let mut headers = HeaderMap::new();
+        let github = VerifySignature::github(b"secret");
+        assert!(github.parse(&headers).is_none());
+
+        headers.insert("x-hub-signature-256", HeaderValue::from_static("sha256=0aFf"));
+        assert_eq!(github.parse(&headers).unwrap().1, vec![vec![0x0a, 0xff]]);
+
+        headers.insert("x-hub-signature-256", HeaderValue::from_static("0aff"));
+        assert!(github.parse(&headers).is_none());
+
+        let shopify = VerifySignature::shopify(b"secret");
+        headers.insert("x-shopify-hmac-sha256", HeaderValue::from_static("Cv8="));
+        assert_eq!(shopify.parse(&headers).unwrap().1, vec![vec![0x0a, 0xff]]);
+
+        let stripe = VerifySignature::stripe(b"secret");
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let value = format!("t={now},v1=0a,v0=ff,v1=ff");
+        headers.insert("stripe-signature", HeaderValue::try_from(value).unwrap());
+        let (prefix, signatures) = stripe.parse(&headers).unwrap();
+        assert_eq!(prefix.unwrap(), format!("{now}."));
+        assert_eq!(signatures, vec![vec![0x0a], vec![0xff]]);
+
+        headers.insert("stripe-signature", HeaderValue::from_static("t=1,v1=0a"));
+        assert!(stripe.parse(&headers).is_none());
+
+        headers.insert(
+            "stripe-signature",
+            HeaderValue::from_static("t=18446744073709551615,v1=00"),
+        );
+        assert!(stripe.parse(&headers).is_none());
+    }
+
+    #[test]
+    fn verify_body() {
+        let service = App::new()
+            .at(
+                "/",
+                handler_service(|_: &WebContext<'_>, body: String| async move { body }),
+            )
+            .enclosed(VerifySignature::github(b"secret"))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |body: &'static [u8], signature: String| {
+            let mut req = WebRequest::default().map(|ext| ext.map_body(|_: ()| RequestBody::from(Bytes::from(body))));
+            req.headers_mut()
+                .insert("x-hub-signature-256", HeaderValue::try_from(signature).unwrap());
+            service.call(req).now_or_panic().unwrap()
+        };
+
+        let res = call(b"hello,world!", format!("sha256={}", hex(&sign(b"hello,world!"))));
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = collect_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, b"hello,world!");
+
+        let res = call(b"hello,world?", format!("sha256={}", hex(&sign(b"hello,world!"))));
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn verify_stripe_body() {
+        let service = App::new()
+            .at(
+                "/",
+                handler_service(|_: &WebContext<'_>, body: String| async move { body }),
+            )
+            .enclosed(VerifySignature::stripe(b"secret"))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let signature = hex(&sign(format!("{now}.hello,world!").as_bytes()));
+
+        let mut req =
+            WebRequest::default().map(|ext| ext.map_body(|_: ()| RequestBody::from(Bytes::from("hello,world!"))));
+        req.headers_mut().insert(
+            "stripe-signature",
+            HeaderValue::try_from(format!("t={now},v1={signature}")).unwrap(),
+        );
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = collect_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, b"hello,world!");
+    }
+}
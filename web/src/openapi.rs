@@ -0,0 +1,655 @@
+//! OpenAPI document generation from typed handler functions.
+//!
+//! Handler arguments implementing [ExtractDoc] and handler outputs implementing [RespondDoc] contribute
+//! schema information to an [OpenApi] document. Types carrying user data (like [Json], [Query] and [Params])
+//! describe it through [JsonSchema] trait while other extractors/responders are silently skipped.
+//!
+//! # Example
+//! ```rust
+//! # #[cfg(feature = "params")]
+//! # fn _main() -> std::io::Result<()> {
+//! use xitca_web::{
+//!     handler::{handler_service, json::Json, params::Params},
+//!     http::Method,
+//!     openapi::{swagger_ui, JsonSchema, OpenApi},
+//!     route::get,
+//!     App,
+//! };
+//!
+//! #[derive(serde::Serialize, JsonSchema)]
+//! struct User {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! async fn user(Params(id): Params<u64>) -> Json<User> {
+//!     Json(User { id, name: String::from("xitca") })
+//! }
+//!
+//! // collect schema from handler function signature.
+//! let doc = OpenApi::new("users", "0.1.0")
+//!     .operation(Method::GET, "/users/:id", &user)
+//!     .finish();
+//!
+//! App::new()
+//!     .at("/users/:id", get(handler_service(user)))
+//!     // serve generated document and swagger ui page viewing it.
+//!     .at("/openapi.json", Json(doc))
+//!     .at("/docs", swagger_ui("/openapi.json"))
+//!     .serve()
+//!     .bind("localhost:8080")?
+//!     .run()
+//!     .wait()
+//! # }
+//! ```
+
+use schemars::{
+    generate::{SchemaGenerator, SchemaSettings},
+    Schema,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    body::{RequestBody, ResponseBody},
+    bytes::{Bytes, BytesMut},
+    context::WebContext,
+    handler::{
+        body::Body,
+        extension::{ExtensionOwn, ExtensionRef, ExtensionsRef},
+        header::HeaderRef,
+        html::Html,
+        json::{Json, LazyJson},
+        path::{PathOwn, PathRef},
+        redirect::Redirect,
+        state::{StateOwn, StateRef},
+        text::Text,
+        uri::{UriOwn, UriRef},
+    },
+    http::{Method, RequestExt, StatusCode, WebRequest, WebResponse},
+    service::AsyncFn,
+};
+
+#[cfg(feature = "params")]
+use crate::handler::params::{LazyParams, Params, ParamsRef};
+
+#[cfg(feature = "urlencoded")]
+use crate::handler::{
+    form::{Form, LazyForm},
+    query::{LazyQuery, Query},
+};
+
+pub use schemars::JsonSchema;
+
+/// OpenAPI document builder.
+pub struct OpenApi {
+    generator: SchemaGenerator,
+    info: Map<String, Value>,
+    paths: Map<String, Value>,
+}
+
+impl OpenApi {
+    /// construct a new document with given title and version of api.
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        let mut info = Map::new();
+        info.insert("title".into(), Value::String(title.into()));
+        info.insert("version".into(), Value::String(version.into()));
+        Self {
+            generator: SchemaSettings::openapi3().into_generator(),
+            info,
+            paths: Map::new(),
+        }
+    }
+
+    /// set description of the api.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.info
+            .insert("description".into(), Value::String(description.into()));
+        self
+    }
+
+    /// document an operation with given http method and route path. route path shares the same syntax of
+    /// [App::at](crate::App::at) where `:name` and `*name` params are translated to OpenAPI's `{name}`.
+    ///
+    /// handler function's argument types contribute request parameters and body while it's output type
+    /// contributes response.
+    pub fn operation<F, T>(mut self, method: Method, path: &str, _: &F) -> Self
+    where
+        F: AsyncFn<T>,
+        T: ExtractDoc,
+        F::Output: RespondDoc,
+    {
+        let (path, params) = translate_path(path);
+
+        let mut op = Operation {
+            generator: &mut self.generator,
+            params,
+            object: Map::new(),
+        };
+
+        T::doc(&mut op);
+        F::Output::doc(&mut op);
+
+        let Operation { params, mut object, .. } = op;
+
+        // path params are required by OpenAPI. add the ones not described by handler arguments.
+        for name in params {
+            push_param(&mut object, "path", &name, json!({ "type": "string" }), true);
+        }
+
+        if !object.contains_key("responses") {
+            object.insert("responses".into(), json!({ "default": { "description": "" } }));
+        }
+
+        let item = self
+            .paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .unwrap();
+        item.insert(method.as_str().to_ascii_lowercase(), Value::Object(object));
+
+        self
+    }
+
+    /// finish document build and produce it as json value.
+    pub fn finish(mut self) -> Value {
+        let schemas = self.generator.take_definitions(true);
+        json!({
+            "openapi": "3.0.3",
+            "info": self.info,
+            "paths": self.paths,
+            "components": { "schemas": schemas },
+        })
+    }
+}
+
+/// an OpenAPI operation object under construction.
+pub struct Operation<'a> {
+    generator: &'a mut SchemaGenerator,
+    // path param names not yet described.
+    params: Vec<String>,
+    object: Map<String, Value>,
+}
+
+impl Operation<'_> {
+    /// generate schema for given type. named types are registered as components and referenced.
+    pub fn schema_for<T>(&mut self) -> Schema
+    where
+        T: JsonSchema + ?Sized,
+    {
+        self.generator.subschema_for::<T>()
+    }
+
+    /// add request body of given content type and schema.
+    pub fn request_body(&mut self, content_type: &str, schema: Schema) {
+        self.object.insert(
+            "requestBody".into(),
+            json!({
+                "required": true,
+                "content": { content_type: { "schema": schema } }
+            }),
+        );
+    }
+
+    /// add response of given status code. content type and schema are optional.
+    pub fn response(&mut self, status: StatusCode, content: Option<(&str, Schema)>) {
+        let mut res = Map::new();
+        res.insert(
+            "description".into(),
+            Value::String(status.canonical_reason().unwrap_or_default().into()),
+        );
+        if let Some((content_type, schema)) = content {
+            res.insert("content".into(), json!({ content_type: { "schema": schema } }));
+        }
+        self.object
+            .entry("responses")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .unwrap()
+            .insert(status.as_str().into(), Value::Object(res));
+    }
+
+    /// add parameters at given location("path", "query", "header" or "cookie") from type's schema.
+    ///
+    /// struct type's fields are treated as individual parameters. For path location non struct type is
+    /// treated as the first undescribed path param.
+    pub fn parameters<T>(&mut self, location: &str)
+    where
+        T: JsonSchema + ?Sized,
+    {
+        let schema = SchemaSettings::openapi3()
+            .with(|s| {
+                s.inline_subschemas = true;
+                s.meta_schema = None;
+            })
+            .into_generator()
+            .into_root_schema_for::<T>();
+
+        match schema.get("properties").and_then(Value::as_object) {
+            Some(props) => {
+                let required = schema
+                    .get("required")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+
+                for (name, prop) in props {
+                    let required = location == "path" || required.iter().any(|r| r.as_str() == Some(name.as_str()));
+                    self.params.retain(|p| p != name);
+                    push_param(&mut self.object, location, name, prop.clone(), required);
+                }
+            }
+            None if location == "path" && !self.params.is_empty() => {
+                let name = self.params.remove(0);
+                let mut schema = schema.to_value();
+                if let Some(obj) = schema.as_object_mut() {
+                    obj.remove("title");
+                }
+                push_param(&mut self.object, location, &name, schema, true);
+            }
+            None => {}
+        }
+    }
+}
+
+fn push_param(object: &mut Map<String, Value>, location: &str, name: &str, schema: Value, required: bool) {
+    object
+        .entry("parameters")
+        .or_insert_with(|| Value::Array(Vec::new()))
+        .as_array_mut()
+        .unwrap()
+        .push(json!({
+            "name": name,
+            "in": location,
+            "required": required,
+            "schema": schema,
+        }));
+}
+
+// translate xitca-router path syntax to OpenAPI path template and collect path param names.
+fn translate_path(path: &str) -> (String, Vec<String>) {
+    let mut params = Vec::new();
+    let path = path
+        .split('/')
+        .map(|seg| match seg.strip_prefix(':').or_else(|| seg.strip_prefix('*')) {
+            Some(name) if !name.is_empty() => {
+                params.push(name.to_owned());
+                format!("{{{name}}}")
+            }
+            _ => seg.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    (path, params)
+}
+
+/// trait for handler argument types contributing to OpenAPI operation.
+pub trait ExtractDoc {
+    /// describe extractor type to operation. default to no-op.
+    fn doc(op: &mut Operation<'_>) {
+        let _ = op;
+    }
+}
+
+/// trait for handler output types contributing to OpenAPI operation.
+pub trait RespondDoc {
+    /// describe responder type to operation. default to no-op.
+    fn doc(op: &mut Operation<'_>) {
+        let _ = op;
+    }
+}
+
+macro_rules! extract_doc_impl {
+    ($($arg: ident),*) => {
+        impl<$($arg,)*> ExtractDoc for ($($arg,)*)
+        where
+            $($arg: ExtractDoc,)*
+        {
+            fn doc(_op: &mut Operation<'_>) {
+                $($arg::doc(_op);)*
+            }
+        }
+    };
+}
+
+extract_doc_impl! {}
+extract_doc_impl! { A }
+extract_doc_impl! { A, B }
+extract_doc_impl! { A, B, C }
+extract_doc_impl! { A, B, C, D }
+extract_doc_impl! { A, B, C, D, E }
+extract_doc_impl! { A, B, C, D, E, F }
+extract_doc_impl! { A, B, C, D, E, F, G }
+extract_doc_impl! { A, B, C, D, E, F, G, H }
+extract_doc_impl! { A, B, C, D, E, F, G, H, I }
+
+impl<T> ExtractDoc for Option<T>
+where
+    T: ExtractDoc,
+{
+    fn doc(op: &mut Operation<'_>) {
+        T::doc(op)
+    }
+}
+
+impl<T, E> ExtractDoc for Result<T, E>
+where
+    T: ExtractDoc,
+{
+    fn doc(op: &mut Operation<'_>) {
+        T::doc(op)
+    }
+}
+
+impl<T, const LIMIT: usize> ExtractDoc for Json<T, LIMIT>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        let schema = op.schema_for::<T>();
+        op.request_body("application/json", schema);
+    }
+}
+
+impl<T, const LIMIT: usize> ExtractDoc for LazyJson<T, LIMIT>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        Json::<T, LIMIT>::doc(op)
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl<T> ExtractDoc for Query<T>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        op.parameters::<T>("query")
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl<T> ExtractDoc for LazyQuery<'_, T>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        op.parameters::<T>("query")
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl<T, const LIMIT: usize> ExtractDoc for Form<T, LIMIT>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        let schema = op.schema_for::<T>();
+        op.request_body("application/x-www-form-urlencoded", schema);
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl<T, const LIMIT: usize> ExtractDoc for LazyForm<T, LIMIT>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        Form::<T, LIMIT>::doc(op)
+    }
+}
+
+#[cfg(feature = "params")]
+impl<T> ExtractDoc for Params<T>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        op.parameters::<T>("path")
+    }
+}
+
+#[cfg(feature = "params")]
+impl<T> ExtractDoc for LazyParams<'_, T>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        op.parameters::<T>("path")
+    }
+}
+
+macro_rules! blank_extract_doc {
+    ($($ty: ty),* $(,)?) => {
+        $(impl ExtractDoc for $ty {})*
+    };
+}
+
+blank_extract_doc!(
+    Method,
+    &Method,
+    RequestExt<()>,
+    &RequestExt<()>,
+    WebRequest<()>,
+    &WebRequest<()>,
+    core::net::SocketAddr,
+    &core::net::SocketAddr,
+    PathRef<'_>,
+    PathOwn,
+    UriRef<'_>,
+    UriOwn,
+    ExtensionsRef<'_>,
+    String,
+    Vec<u8>,
+    Bytes,
+    BytesMut,
+);
+
+#[cfg(feature = "params")]
+blank_extract_doc!(ParamsRef<'_>);
+
+impl<C, B> ExtractDoc for &WebContext<'_, C, B> {}
+
+impl<B> ExtractDoc for Body<B> {}
+
+impl<const HEADER_NAME: usize> ExtractDoc for HeaderRef<'_, HEADER_NAME> {}
+
+impl<S> ExtractDoc for StateRef<'_, S> where S: ?Sized {}
+
+impl<S> ExtractDoc for StateOwn<S> {}
+
+impl<T> ExtractDoc for ExtensionRef<'_, T> {}
+
+impl<T> ExtractDoc for ExtensionOwn<T> {}
+
+impl<T, E> RespondDoc for Result<T, E>
+where
+    T: RespondDoc,
+{
+    fn doc(op: &mut Operation<'_>) {
+        T::doc(op)
+    }
+}
+
+impl<T> RespondDoc for Json<T>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        let schema = op.schema_for::<T>();
+        op.response(StatusCode::OK, Some(("application/json", schema)));
+    }
+}
+
+impl RespondDoc for serde_json::Value {
+    fn doc(op: &mut Operation<'_>) {
+        let schema = op.schema_for::<serde_json::Value>();
+        op.response(StatusCode::OK, Some(("application/json", schema)));
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl<T> RespondDoc for Form<T>
+where
+    T: JsonSchema,
+{
+    fn doc(op: &mut Operation<'_>) {
+        let schema = op.schema_for::<T>();
+        op.response(StatusCode::OK, Some(("application/x-www-form-urlencoded", schema)));
+    }
+}
+
+impl<T> RespondDoc for Html<T> {
+    fn doc(op: &mut Operation<'_>) {
+        let schema = op.schema_for::<str>();
+        op.response(StatusCode::OK, Some(("text/html", schema)));
+    }
+}
+
+impl<T> RespondDoc for Text<T> {
+    fn doc(op: &mut Operation<'_>) {
+        let schema = op.schema_for::<str>();
+        op.response(StatusCode::OK, Some(("text/plain", schema)));
+    }
+}
+
+macro_rules! text_respond_doc {
+    ($($ty: ty),* $(,)?) => {
+        $(
+            impl RespondDoc for $ty {
+                fn doc(op: &mut Operation<'_>) {
+                    Text::<$ty>::doc(op)
+                }
+            }
+        )*
+    };
+}
+
+text_respond_doc!(&'static str, String, Box<str>, std::borrow::Cow<'static, str>);
+
+impl RespondDoc for StatusCode {}
+
+impl RespondDoc for () {}
+
+impl RespondDoc for Redirect {}
+
+impl RespondDoc for WebResponse {}
+
+impl<B> RespondDoc for ResponseBody<B> {}
+
+impl RespondDoc for RequestBody {}
+
+/// generate a Swagger UI html page rendering OpenAPI document from given url.
+///
+/// page assets are loaded from public cdn on client side.
+pub fn swagger_ui(spec_url: &str) -> Html<String> {
+    Html(format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8" />
+<meta name="viewport" content="width=device-width, initial-scale=1" />
+<title>Swagger UI</title>
+<link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+<div id="swagger-ui"></div>
+<script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+<script>
+window.onload = () => {{
+  window.ui = SwaggerUIBundle({{ url: "{spec_url}", dom_id: "#swagger-ui" }});
+}};
+</script>
+</body>
+</html>"##
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(serde::Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Post {
+        title: String,
+        content: Option<String>,
+    }
+
+    #[derive(serde::Serialize, JsonSchema)]
+    struct Id {
+        id: u64,
+    }
+
+    async fn create(_: PathRef<'_>, _: Json<Post>) -> Json<Id> {
+        Json(Id { id: 996 })
+    }
+
+    async fn plain(_: &WebContext<'_>) -> &'static str {
+        "plain"
+    }
+
+    #[test]
+    fn document() {
+        let doc = OpenApi::new("test", "0.1.0")
+            .operation(Method::POST, "/posts/:user/*rest", &create)
+            .operation(Method::GET, "/", &plain)
+            .finish();
+
+        let op = &doc["paths"]["/posts/{user}/{rest}"]["post"];
+        assert_eq!(
+            op["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Post"
+        );
+        assert_eq!(
+            op["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Id"
+        );
+        assert_eq!(op["parameters"].as_array().unwrap().len(), 2);
+        assert_eq!(op["parameters"][0]["name"], "user");
+        assert_eq!(op["parameters"][1]["name"], "rest");
+
+        assert!(doc["components"]["schemas"]["Post"]["properties"]["title"].is_object());
+
+        let op = &doc["paths"]["/"]["get"];
+        assert_eq!(
+            op["responses"]["200"]["content"]["text/plain"]["schema"]["type"],
+            "string"
+        );
+    }
+
+    #[cfg(feature = "params")]
+    #[test]
+    fn path_params() {
+        async fn single(_: Params<u64>) -> StatusCode {
+            StatusCode::OK
+        }
+
+        #[derive(serde::Deserialize, JsonSchema)]
+        #[allow(dead_code)]
+        struct Multi {
+            user: String,
+            post: u32,
+        }
+
+        async fn multi(_: Params<Multi>) -> StatusCode {
+            StatusCode::OK
+        }
+
+        let doc = OpenApi::new("test", "0.1.0")
+            .operation(Method::GET, "/:id", &single)
+            .operation(Method::GET, "/:user/:post", &multi)
+            .finish();
+
+        let params = &doc["paths"]["/{id}"]["get"]["parameters"];
+        assert_eq!(params[0]["name"], "id");
+        assert_eq!(params[0]["schema"]["type"], "integer");
+        assert_eq!(params.as_array().unwrap().len(), 1);
+
+        let params = doc["paths"]["/{user}/{post}"]["get"]["parameters"].as_array().unwrap();
+        assert_eq!(params.len(), 2);
+        assert!(params.iter().all(|p| p["required"] == true));
+    }
+}
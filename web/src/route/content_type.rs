@@ -0,0 +1,443 @@
+use std::sync::Arc;
+
+use xitca_http::util::service::router::{PathGen, RouteGen, RouterError};
+
+use crate::{
+    context::WebContext,
+    error::Error,
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    service::{ready::ReadyService, Service},
+};
+
+/// construct a new [ContentTypeRouter] with no route registered.
+pub const fn on_content_type() -> ContentTypeRouter<()> {
+    ContentTypeRouter {
+        guards: Vec::new(),
+        route: (),
+        fallback: (),
+    }
+}
+
+/// a type routing multiple [Service] types against the `Content-Type` header or custom header predicates of
+/// request. Guards are checked in the order of registration and the first matching one wins.
+///
+/// Media types are matched against the essence of `Content-Type` header case insensitively and parameters like
+/// `charset` are ignored. `type/*` pattern matches all sub types of given type. Request with unmatched media type
+/// is rejected with `415 Unsupported Media Type` unless a fallback service is set.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, route::{on_content_type, post}, App};
+/// # fn _main() -> std::io::Result<()> {
+/// App::new()
+///     .at(
+///         "/ingest",
+///         post(
+///             on_content_type()
+///                 .json(handler_service(|| async { "json" }))
+///                 .csv(handler_service(|| async { "csv" }))
+///                 // route with custom header predicate.
+///                 .when(|headers| headers.contains_key("x-legacy"), handler_service(|| async { "legacy" })),
+///         ),
+///     )
+///     .serve()
+///     .bind("localhost:8080")?
+///     .run()
+///     .wait()
+/// # }
+/// ```
+pub struct ContentTypeRouter<R, F = ()> {
+    guards: Vec<Guard>,
+    route: R,
+    fallback: F,
+}
+
+macro_rules! mime_method {
+    ($method: ident, $mime: literal) => {
+        #[doc = concat!("register given [Service] type to `", $mime, "` media type.")]
+        /// Act as shortcut of [ContentTypeRouter::mime].
+        pub fn $method<R1>(self, route: R1) -> ContentTypeRouter<ContentTypeRoute<R1, R>, F> {
+            self.mime($mime, route)
+        }
+    };
+}
+
+impl<R, F> ContentTypeRouter<R, F> {
+    /// register given [Service] type to media type pattern.
+    ///
+    /// # Panics
+    /// panic when pattern is not in form of `type/subtype` or `type/*` or it's already registered.
+    pub fn mime<R1>(self, pattern: &'static str, route: R1) -> ContentTypeRouter<ContentTypeRoute<R1, R>, F> {
+        let (ty, sub) = pattern
+            .split_once('/')
+            .unwrap_or_else(|| panic!("{pattern} is not a valid media type pattern"));
+        assert!(
+            !ty.is_empty() && !sub.is_empty(),
+            "{pattern} is not a valid media type pattern"
+        );
+        if self
+            .guards
+            .iter()
+            .any(|g| matches!(g, Guard::Mime(m) if m.eq_ignore_ascii_case(pattern)))
+        {
+            panic!("{pattern} media type already exists. ContentTypeRouter can not contain duplicate media types.");
+        }
+        self.push(Guard::Mime(pattern), route)
+    }
+
+    /// register given [Service] type to a custom predicate on request headers.
+    pub fn when<P, R1>(self, predicate: P, route: R1) -> ContentTypeRouter<ContentTypeRoute<R1, R>, F>
+    where
+        P: Fn(&HeaderMap) -> bool + Send + Sync + 'static,
+    {
+        self.push(Guard::Fn(Arc::new(predicate)), route)
+    }
+
+    mime_method!(json, "application/json");
+    mime_method!(form, "application/x-www-form-urlencoded");
+    mime_method!(multipart, "multipart/form-data");
+    mime_method!(csv, "text/csv");
+    mime_method!(text, "text/plain");
+
+    /// set fallback [Service] type for request with no matching guard. by default a `415 Unsupported Media Type`
+    /// error is returned.
+    pub fn fallback<F1>(self, fallback: F1) -> ContentTypeRouter<R, Fallback<F1>> {
+        ContentTypeRouter {
+            guards: self.guards,
+            route: self.route,
+            fallback: Fallback(fallback),
+        }
+    }
+
+    fn push<R1>(mut self, guard: Guard, route: R1) -> ContentTypeRouter<ContentTypeRoute<R1, R>, F> {
+        self.guards.push(guard);
+        ContentTypeRouter {
+            guards: self.guards,
+            route: ContentTypeRoute {
+                route,
+                next: self.route,
+            },
+            fallback: self.fallback,
+        }
+    }
+}
+
+impl<R, F> PathGen for ContentTypeRouter<R, F> {}
+
+impl<R, F> RouteGen for ContentTypeRouter<R, F> {
+    type Route<R1> = R1;
+
+    fn route_gen<R1>(route: R1) -> Self::Route<R1> {
+        route
+    }
+}
+
+impl<Arg, R> Service<Arg> for ContentTypeRouter<R>
+where
+    R: Service<Arg>,
+{
+    type Response = ContentTypeRouterService<R::Response>;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let route = self.route.call(arg).await?;
+        Ok(ContentTypeRouterService {
+            guards: self.guards.clone().into_boxed_slice(),
+            route,
+            fallback: (),
+        })
+    }
+}
+
+impl<Arg, R, F> Service<Arg> for ContentTypeRouter<R, Fallback<F>>
+where
+    R: Service<Arg>,
+    F: Service<Arg, Error = R::Error>,
+    Arg: Clone,
+{
+    type Response = ContentTypeRouterService<R::Response, Fallback<F::Response>>;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let route = self.route.call(arg.clone()).await?;
+        let fallback = self.fallback.0.call(arg).await?;
+        Ok(ContentTypeRouterService {
+            guards: self.guards.clone().into_boxed_slice(),
+            route,
+            fallback: Fallback(fallback),
+        })
+    }
+}
+
+pub struct ContentTypeRouterService<R, F = ()> {
+    guards: Box<[Guard]>,
+    route: R,
+    fallback: F,
+}
+
+impl<R, F> ContentTypeRouterService<R, F> {
+    // guards are registered in reverse order of route nesting. map the first matched guard to the position of
+    // route in nesting.
+    fn find(&self, headers: &HeaderMap) -> Option<usize> {
+        let essence = headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or_default().trim());
+        self.guards
+            .iter()
+            .position(|guard| guard.matches(essence, headers))
+            .map(|idx| self.guards.len() - 1 - idx)
+    }
+}
+
+impl<'r, C, B, R, Res, E> Service<WebContext<'r, C, B>> for ContentTypeRouterService<R>
+where
+    R: Service<(usize, WebContext<'r, C, B>), Response = Res, Error = E>,
+    E: Into<Error<C>>,
+{
+    type Response = Res;
+    type Error = RouterError<Error<C>>;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        match self.find(ctx.req().headers()) {
+            Some(idx) => self
+                .route
+                .call((idx, ctx))
+                .await
+                .map_err(|e| RouterError::Service(e.into())),
+            None => Err(RouterError::Service(Error::from(StatusCode::UNSUPPORTED_MEDIA_TYPE))),
+        }
+    }
+}
+
+impl<'r, C, B, R, F, Res, E, FE> Service<WebContext<'r, C, B>> for ContentTypeRouterService<R, Fallback<F>>
+where
+    R: Service<(usize, WebContext<'r, C, B>), Response = Res, Error = E>,
+    F: Service<WebContext<'r, C, B>, Response = Res, Error = FE>,
+    E: Into<Error<C>>,
+    FE: Into<Error<C>>,
+{
+    type Response = Res;
+    type Error = RouterError<Error<C>>;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let res = match self.find(ctx.req().headers()) {
+            Some(idx) => self.route.call((idx, ctx)).await.map_err(Into::into),
+            None => self.fallback.0.call(ctx).await.map_err(Into::into),
+        };
+        res.map_err(RouterError::Service)
+    }
+}
+
+impl<R, F> ReadyService for ContentTypeRouterService<R, F> {
+    type Ready = ();
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {}
+}
+
+#[derive(Clone)]
+enum Guard {
+    Mime(&'static str),
+    Fn(Arc<dyn Fn(&HeaderMap) -> bool + Send + Sync>),
+}
+
+impl Guard {
+    fn matches(&self, essence: Option<&str>, headers: &HeaderMap) -> bool {
+        match *self {
+            Self::Mime(pattern) => essence.is_some_and(|essence| mime_matches(pattern, essence)),
+            Self::Fn(ref predicate) => predicate(headers),
+        }
+    }
+}
+
+fn mime_matches(pattern: &str, essence: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(ty) => essence
+            .split_once('/')
+            .is_some_and(|(t, sub)| !sub.is_empty() && t.eq_ignore_ascii_case(ty)),
+        None => essence.eq_ignore_ascii_case(pattern),
+    }
+}
+
+/// a linked list of [Service] types registered to [ContentTypeRouter].
+pub struct ContentTypeRoute<R, N> {
+    route: R,
+    next: N,
+}
+
+impl<Arg, R> Service<Arg> for ContentTypeRoute<R, ()>
+where
+    R: Service<Arg>,
+{
+    type Response = ContentTypeRouteService<R::Response, ()>;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let route = self.route.call(arg).await?;
+        Ok(ContentTypeRouteService { route, next: () })
+    }
+}
+
+impl<Arg, R, R1, N1> Service<Arg> for ContentTypeRoute<R, ContentTypeRoute<R1, N1>>
+where
+    R: Service<Arg>,
+    ContentTypeRoute<R1, N1>: Service<Arg, Error = R::Error>,
+    Arg: Clone,
+{
+    type Response = ContentTypeRouteService<R::Response, <ContentTypeRoute<R1, N1> as Service<Arg>>::Response>;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let route = self.route.call(arg.clone()).await?;
+        let next = self.next.call(arg).await?;
+        Ok(ContentTypeRouteService { route, next })
+    }
+}
+
+pub struct ContentTypeRouteService<R, N> {
+    route: R,
+    next: N,
+}
+
+impl<R, Req> Service<(usize, Req)> for ContentTypeRouteService<R, ()>
+where
+    R: Service<Req>,
+{
+    type Response = R::Response;
+    type Error = R::Error;
+
+    #[inline]
+    async fn call(&self, (_, req): (usize, Req)) -> Result<Self::Response, Self::Error> {
+        self.route.call(req).await
+    }
+}
+
+impl<R, R1, N1, Req> Service<(usize, Req)> for ContentTypeRouteService<R, ContentTypeRouteService<R1, N1>>
+where
+    R: Service<Req>,
+    ContentTypeRouteService<R1, N1>: Service<(usize, Req), Response = R::Response, Error = R::Error>,
+{
+    type Response = R::Response;
+    type Error = R::Error;
+
+    #[inline]
+    async fn call(&self, (idx, req): (usize, Req)) -> Result<Self::Response, Self::Error> {
+        match idx {
+            0 => self.route.call(req).await,
+            idx => self.next.call((idx - 1, req)).await,
+        }
+    }
+}
+
+/// user provided fallback [Service] type of [ContentTypeRouter].
+pub struct Fallback<F>(F);
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{header::HeaderValue, Method, Request, WebRequest},
+        route::post,
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    fn req(content_type: Option<&'static str>) -> WebRequest {
+        let mut req = Request::default();
+        *req.method_mut() = Method::POST;
+        if let Some(ct) = content_type {
+            req.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(ct));
+        }
+        req
+    }
+
+    #[test]
+    fn mime() {
+        assert!(mime_matches("application/json", "Application/JSON"));
+        assert!(!mime_matches("application/json", "application/jsonx"));
+        assert!(mime_matches("text/*", "text/csv"));
+        assert!(!mime_matches("text/*", "text/"));
+        assert!(!mime_matches("text/*", "textual/csv"));
+    }
+
+    #[test]
+    fn content_type_route() {
+        let service = App::new()
+            .at(
+                "/",
+                post(
+                    on_content_type()
+                        .json(handler_service(|| async { "json" }))
+                        .csv(handler_service(|| async { "csv" }))
+                        .mime("text/*", handler_service(|| async { "text" }))
+                        .when(
+                            |headers| headers.contains_key("x-legacy"),
+                            handler_service(|| async { "legacy" }),
+                        ),
+                ),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for (ct, body) in [
+            ("application/json", "json"),
+            ("application/json; charset=utf-8", "json"),
+            ("text/csv", "csv"),
+            ("text/plain", "text"),
+        ] {
+            let res = service.call(req(Some(ct))).now_or_panic().unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), body);
+        }
+
+        let mut legacy = req(Some("application/xml"));
+        legacy.headers_mut().insert("x-legacy", HeaderValue::from_static("1"));
+        let res = service.call(legacy).now_or_panic().unwrap();
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "legacy");
+
+        let res = service.call(req(Some("application/xml"))).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let res = service.call(req(None)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[test]
+    fn content_type_fallback() {
+        let service = App::new()
+            .at(
+                "/",
+                on_content_type()
+                    .form(handler_service(|| async { "form" }))
+                    .fallback(handler_service(|| async { "fallback" })),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service
+            .call(req(Some("application/x-www-form-urlencoded")))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "form");
+
+        let res = service.call(req(None)).now_or_panic().unwrap();
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "fallback");
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_mime() {
+        let _ = on_content_type()
+            .json(handler_service(|| async { "foo" }))
+            .mime("Application/Json", handler_service(|| async { "bar" }));
+    }
+}
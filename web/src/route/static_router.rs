@@ -0,0 +1,390 @@
+use core::marker::PhantomData;
+
+use xitca_http::util::service::router::{MatchError, PathGen, RouteGen, RouterError};
+
+use crate::{
+    http::{BorrowReq, Uri},
+    service::{ready::ReadyService, Service},
+};
+
+/// a router with compile time route table generated by [static_router] macro.
+///
+/// Unlike the default radix tree router of [App] the paths of StaticRouter are matched with a rust `match`
+/// expression against string literals. Matching is zero allocation and the table is fixed at compile time.
+/// In trade StaticRouter only supports exact static paths. Path parameters, wildcards and nested routers are
+/// not supported.
+///
+/// StaticRouter can be used as the only router of application with [App::from_static] or registered to
+/// [App::at] for serving a sub path.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, route::get, static_router, App};
+/// # fn _main() -> std::io::Result<()> {
+/// App::from_static(static_router! {
+///     "/" => get(handler_service(|| async { "index" })),
+///     "/health" => handler_service(|| async { "ok" }),
+/// })
+/// .serve()
+/// .bind("localhost:8080")?
+/// .run()
+/// .wait()
+/// # }
+/// ```
+///
+/// [static_router]: crate::static_router
+/// [App]: crate::App
+/// [App::at]: crate::App::at
+/// [App::from_static]: crate::App::from_static
+pub struct StaticRouter<M, R> {
+    paths: &'static [&'static str],
+    matcher: M,
+    prefix: Option<usize>,
+    route: R,
+}
+
+// route service registered to StaticRouter with it's error type mapped to RouterError.
+type Routed<F> = <F as RouteGen>::Route<F>;
+
+impl<M> StaticRouter<M, ()>
+where
+    M: Fn(&str) -> Option<usize>,
+{
+    #[doc(hidden)]
+    /// construct router with paths and matcher function returning the index of matched path. used by
+    /// [static_router](crate::static_router) macro.
+    ///
+    /// # Panics
+    /// panic when path is not started with `/` or duplicate path found.
+    pub fn new(paths: &'static [&'static str], matcher: M) -> Self {
+        for (idx, path) in paths.iter().enumerate() {
+            assert!(path.starts_with('/'), "static route path {path} must start with /");
+            if paths[..idx].contains(path) {
+                panic!("{path} route already exists. StaticRouter can not contain duplicate paths.");
+            }
+        }
+
+        Self {
+            paths,
+            matcher,
+            prefix: None,
+            route: (),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn route<F>(self, route: F) -> StaticRouter<M, StaticRoute<Routed<F>, StaticRouteEnd<Routed<F>>>>
+    where
+        F: RouteGen,
+    {
+        StaticRouter {
+            paths: self.paths,
+            matcher: self.matcher,
+            prefix: self.prefix,
+            route: StaticRoute {
+                route: F::route_gen(route),
+                next: StaticRouteEnd(PhantomData),
+            },
+        }
+    }
+}
+
+impl<M, R, N> StaticRouter<M, StaticRoute<R, N>> {
+    #[doc(hidden)]
+    pub fn route<F>(self, route: F) -> StaticRouter<M, StaticRoute<Routed<F>, StaticRoute<R, N>>>
+    where
+        F: RouteGen,
+    {
+        StaticRouter {
+            paths: self.paths,
+            matcher: self.matcher,
+            prefix: self.prefix,
+            route: StaticRoute {
+                route: F::route_gen(route),
+                next: self.route,
+            },
+        }
+    }
+}
+
+impl<M, R> PathGen for StaticRouter<M, R> {
+    fn path_gen(&mut self, prefix: &str) -> String {
+        let mut path = String::from(prefix);
+        if path.ends_with("/*") {
+            path.pop();
+        }
+
+        if path.ends_with('/') {
+            path.pop();
+        }
+
+        *self.prefix.get_or_insert(0) += path.len();
+
+        path.push_str("/*");
+
+        path
+    }
+}
+
+impl<M, R> RouteGen for StaticRouter<M, R> {
+    type Route<R1> = R1;
+
+    fn route_gen<R1>(route: R1) -> Self::Route<R1> {
+        route
+    }
+}
+
+impl<Arg, M, R> Service<Arg> for StaticRouter<M, R>
+where
+    M: Clone,
+    R: Service<Arg>,
+{
+    type Response = StaticRouterService<M, R::Response>;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let route = self.route.call(arg).await?;
+        Ok(StaticRouterService {
+            len: self.paths.len(),
+            matcher: self.matcher.clone(),
+            prefix: self.prefix,
+            route,
+        })
+    }
+}
+
+pub struct StaticRouterService<M, R> {
+    len: usize,
+    matcher: M,
+    prefix: Option<usize>,
+    route: R,
+}
+
+impl<M, R, Req, Res, E> Service<Req> for StaticRouterService<M, R>
+where
+    M: Fn(&str) -> Option<usize>,
+    R: Service<(usize, Req), Response = Res, Error = RouterError<E>>,
+    Req: BorrowReq<Uri>,
+{
+    type Response = Res;
+    type Error = RouterError<E>;
+
+    #[inline]
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let mut path = req.borrow().path();
+
+        if let Some(prefix) = self.prefix {
+            path = &path[prefix..];
+        }
+
+        match (self.matcher)(path) {
+            // routes are nested in reverse order of path declaration.
+            Some(idx) => self.route.call((self.len - 1 - idx, req)).await,
+            None => Err(RouterError::Match(MatchError)),
+        }
+    }
+}
+
+impl<M, R> ReadyService for StaticRouterService<M, R> {
+    type Ready = ();
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {}
+}
+
+/// a linked list of [Service] types registered to [StaticRouter].
+pub struct StaticRoute<R, N> {
+    route: R,
+    next: N,
+}
+
+impl<Arg, R, N> Service<Arg> for StaticRoute<R, N>
+where
+    R: Service<Arg>,
+    N: Service<Arg, Error = R::Error>,
+    Arg: Clone,
+{
+    type Response = StaticRouteService<R::Response, N::Response>;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let route = self.route.call(arg.clone()).await?;
+        let next = self.next.call(arg).await?;
+        Ok(StaticRouteService { route, next })
+    }
+}
+
+pub struct StaticRouteService<R, N> {
+    route: R,
+    next: N,
+}
+
+impl<R, N, Req> Service<(usize, Req)> for StaticRouteService<R, N>
+where
+    R: Service<Req>,
+    N: Service<(usize, Req), Response = R::Response, Error = R::Error>,
+{
+    type Response = R::Response;
+    type Error = R::Error;
+
+    #[inline]
+    async fn call(&self, (idx, req): (usize, Req)) -> Result<Self::Response, Self::Error> {
+        match idx {
+            0 => self.route.call(req).await,
+            idx => self.next.call((idx - 1, req)).await,
+        }
+    }
+}
+
+/// end of [StaticRoute] list.
+pub struct StaticRouteEnd<R>(PhantomData<fn(R)>);
+
+impl<Arg, R> Service<Arg> for StaticRouteEnd<R>
+where
+    R: Service<Arg>,
+{
+    type Response = StaticRouteEndService<R::Response>;
+    type Error = R::Error;
+
+    async fn call(&self, _: Arg) -> Result<Self::Response, Self::Error> {
+        Ok(StaticRouteEndService(PhantomData))
+    }
+}
+
+pub struct StaticRouteEndService<R>(PhantomData<fn(R)>);
+
+impl<R, Req> Service<(usize, Req)> for StaticRouteEndService<R>
+where
+    R: Service<Req>,
+{
+    type Response = R::Response;
+    type Error = R::Error;
+
+    async fn call(&self, _: (usize, Req)) -> Result<Self::Response, Self::Error> {
+        unreachable!("route index must be in range of registered paths")
+    }
+}
+
+/// generate a [StaticRouter](crate::route::StaticRouter) from a list of string literal paths and route services.
+/// paths are compiled into a `match` expression.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, route::{get, post}, static_router};
+/// let router = static_router! {
+///     "/" => get(handler_service(|| async { "index" })),
+///     "/login" => get(handler_service(|| async { "login page" })).post(handler_service(|| async { "login" })),
+/// };
+/// ```
+///
+/// # Panics
+/// panic when path is not started with `/` or duplicate path found.
+#[macro_export]
+macro_rules! static_router {
+    ($($path: literal => $route: expr),+ $(,)?) => {{
+        // duplicate path is reported by StaticRouter::new.
+        #[allow(unreachable_patterns)]
+        fn matcher(path: &str) -> ::core::option::Option<usize> {
+            $crate::static_router!(@match path, (0), [], $($path,)+)
+        }
+        $crate::route::StaticRouter::new(&[$($path),+], matcher)$(.route($route))+
+    }};
+    (@match $p: ident, ($($idx: tt)*), [$($arms: tt)*], $path: literal, $($rest: literal,)*) => {
+        $crate::static_router!(
+            @match $p,
+            ($($idx)* + 1),
+            [$($arms)* $path => ::core::option::Option::Some($($idx)*),],
+            $($rest,)*
+        )
+    };
+    (@match $p: ident, ($($idx: tt)*), [$($arms: tt)*],) => {
+        match $p {
+            $($arms)*
+            _ => ::core::option::Option::None,
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::handler_service,
+        http::{Method, Request, StatusCode, WebRequest},
+        route::{get, post},
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    fn req(method: Method, uri: &'static str) -> WebRequest {
+        let mut req = Request::default();
+        *req.method_mut() = method;
+        *req.uri_mut() = Uri::from_static(uri);
+        req
+    }
+
+    #[test]
+    fn static_app() {
+        let service = App::from_static(crate::static_router! {
+            "/" => get(handler_service(|| async { "index" })),
+            "/foo" => handler_service(|| async { "foo" }),
+            "/bar" => post(handler_service(|| async { "bar" })),
+        })
+        .finish()
+        .call(())
+        .now_or_panic()
+        .unwrap();
+
+        for (uri, body) in [("/", "index"), ("/foo", "foo")] {
+            let res = service.call(req(Method::GET, uri)).now_or_panic().unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), body);
+        }
+
+        let res = service.call(req(Method::POST, "/bar")).now_or_panic().unwrap();
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "bar");
+
+        let res = service.call(req(Method::GET, "/bar")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let res = service.call(req(Method::GET, "/foo/")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn static_nest() {
+        let service = App::new()
+            .at("/", handler_service(|| async { "index" }))
+            .at(
+                "/api",
+                crate::static_router! {
+                    "/foo" => handler_service(|| async { "foo" }),
+                    "/bar" => handler_service(|| async { "bar" }),
+                },
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for (uri, body) in [("/", "index"), ("/api/foo", "foo"), ("/api/bar", "bar")] {
+            let res = service.call(req(Method::GET, uri)).now_or_panic().unwrap();
+            assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), body);
+        }
+
+        let res = service.call(req(Method::GET, "/api/baz")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_path() {
+        crate::static_router! {
+            "/foo" => handler_service(|| async { "foo" }),
+            "/foo" => handler_service(|| async { "bar" }),
+        };
+    }
+}
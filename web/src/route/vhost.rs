@@ -0,0 +1,547 @@
+use core::{cmp::Reverse, fmt, marker::PhantomData};
+
+use xitca_http::util::service::router::{MatchError, PathGen, RouteGen, RouterError};
+
+use crate::{
+    http::{header::HOST, BorrowReq, HeaderMap, Uri},
+    service::{ready::ReadyService, Service},
+};
+
+/// a type routing multiple [Service] types against the host of request. host is extracted from uri authority
+/// (`:authority` pseudo header of http/2 and http/3) and fallback to `Host` header.
+///
+/// Host patterns are in form of:
+/// - exact host. e.g: `example.com`
+/// - wildcard subdomain. e.g: `*.example.com` matches `foo.example.com` and `foo.bar.example.com` but not
+///   `example.com`.
+///
+/// Port of request host is ignored and host is matched case insensitively. When multiple patterns match the same
+/// host the most specific one wins: exact host takes precedence over wildcard and longer wildcard takes precedence
+/// over shorter one.
+///
+/// Vhost is a routed service and it can be registered to [App] with [App::at]. Nested [App] with it's own
+/// application state and routes can be registered as host service for multi-tenant server.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, route::{get, Vhost}, App, NestApp, WebContext};
+/// // application for api host sharing the state of root application.
+/// fn api() -> NestApp<String> {
+///     App::new().at("/users", get(handler_service(|_: &WebContext<'_, String>| async { "api" })))
+/// }
+///
+/// App::new()
+///     .with_state(String::from("root"))
+///     .at(
+///         "/",
+///         Vhost::new()
+///             .host("api.example.com", api())
+///             // application for every tenant subdomain with it's own state.
+///             .host(
+///                 "*.example.com",
+///                 App::new()
+///                     .with_state(996usize)
+///                     .at("/", handler_service(|_: &WebContext<'_, usize>| async { "tenant" })),
+///             )
+///             // fallback service for request with unmatched host.
+///             .fallback(handler_service(|_: &WebContext<'_, String>| async { "index" })),
+///     );
+/// ```
+///
+/// [App]: crate::App
+/// [App::at]: crate::App::at
+pub struct Vhost<R, F = NotFound> {
+    hosts: Vec<HostPattern>,
+    route: R,
+    fallback: F,
+}
+
+impl Vhost<()> {
+    /// construct a new Vhost with no host registered.
+    pub const fn new() -> Self {
+        Self {
+            hosts: Vec::new(),
+            route: (),
+            fallback: NotFound,
+        }
+    }
+}
+
+impl Default for Vhost<()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> Vhost<(), F> {
+    /// register given [Service] type to host pattern.
+    ///
+    /// # Panics
+    /// panic when host pattern is empty.
+    pub fn host<R1>(mut self, pattern: &'static str, route: R1) -> Vhost<HostRoute<R1, HostRouteEnd<R1>>, F> {
+        self.hosts.push(HostPattern::new(pattern));
+        Vhost {
+            hosts: self.hosts,
+            route: HostRoute {
+                route,
+                next: HostRouteEnd(PhantomData),
+            },
+            fallback: self.fallback,
+        }
+    }
+}
+
+impl<R, N, F> Vhost<HostRoute<R, N>, F> {
+    /// register given [Service] type to host pattern.
+    ///
+    /// # Panics
+    /// panic when host pattern is empty or already registered.
+    pub fn host<R1>(mut self, pattern: &'static str, route: R1) -> Vhost<HostRoute<R1, HostRoute<R, N>>, F> {
+        let pattern = HostPattern::new(pattern);
+        if self.hosts.contains(&pattern) {
+            panic!("{pattern} host already exists. Vhost can not contain duplicate hosts.");
+        }
+        self.hosts.push(pattern);
+        Vhost {
+            hosts: self.hosts,
+            route: HostRoute {
+                route,
+                next: self.route,
+            },
+            fallback: self.fallback,
+        }
+    }
+
+    /// set fallback [Service] type for request with unmatched host. by default a not found error is
+    /// returned.
+    pub fn fallback<F1>(self, fallback: F1) -> Vhost<HostRoute<R, N>, Fallback<F1>> {
+        Vhost {
+            hosts: self.hosts,
+            route: self.route,
+            fallback: Fallback(fallback),
+        }
+    }
+}
+
+impl<R, F> Vhost<R, F> {
+    // hosts are registered in reverse order of route nesting. map them to the position of route in
+    // nesting and sort them by specificity.
+    fn sorted_hosts(&self) -> Box<[(HostPattern, usize)]> {
+        let len = self.hosts.len();
+        let mut hosts = self
+            .hosts
+            .iter()
+            .enumerate()
+            .map(|(idx, host)| (*host, len - 1 - idx))
+            .collect::<Vec<_>>();
+        hosts.sort_by_key(|(host, _)| Reverse(host.specificity()));
+        hosts.into_boxed_slice()
+    }
+}
+
+impl<R, F> PathGen for Vhost<R, F>
+where
+    R: PathGen,
+    F: PathGen,
+{
+    fn path_gen(&mut self, prefix: &str) -> String {
+        let fallback = self.fallback.path_gen(prefix);
+        let route = self.route.path_gen(prefix);
+        wildcard_path(route, fallback)
+    }
+}
+
+impl<R, F> RouteGen for Vhost<R, F>
+where
+    R: PathGen,
+    F: PathGen,
+{
+    type Route<R1> = R1;
+
+    fn route_gen<R1>(route: R1) -> Self::Route<R1> {
+        route
+    }
+}
+
+impl<Arg, R> Service<Arg> for Vhost<R, NotFound>
+where
+    R: Service<Arg>,
+{
+    type Response = VhostService<R::Response, NotFoundService<R::Response>>;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let route = self.route.call(arg).await?;
+        Ok(VhostService {
+            hosts: self.sorted_hosts(),
+            route,
+            fallback: NotFoundService(PhantomData),
+        })
+    }
+}
+
+impl<Arg, R, F> Service<Arg> for Vhost<R, Fallback<F>>
+where
+    R: Service<Arg>,
+    F: Service<Arg, Error = R::Error>,
+    Arg: Clone,
+{
+    type Response = VhostService<R::Response, Fallback<F::Response>>;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let route = self.route.call(arg.clone()).await?;
+        let fallback = self.fallback.0.call(arg).await?;
+        Ok(VhostService {
+            hosts: self.sorted_hosts(),
+            route,
+            fallback: Fallback(fallback),
+        })
+    }
+}
+
+pub struct VhostService<R, F> {
+    hosts: Box<[(HostPattern, usize)]>,
+    route: R,
+    fallback: F,
+}
+
+impl<R, F, Req, Res, E> Service<Req> for VhostService<R, F>
+where
+    R: Service<(usize, Req), Response = Res, Error = E>,
+    F: Service<Req, Response = Res, Error = RouterError<E>>,
+    Req: BorrowReq<Uri> + BorrowReq<HeaderMap>,
+{
+    type Response = Res;
+    type Error = RouterError<E>;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let idx = request_host(&req).and_then(|host| {
+            self.hosts
+                .iter()
+                .find_map(|(pattern, idx)| pattern.matches(host).then_some(*idx))
+        });
+
+        match idx {
+            Some(idx) => self.route.call((idx, req)).await.map_err(RouterError::Service),
+            None => self.fallback.call(req).await,
+        }
+    }
+}
+
+impl<R, F> ReadyService for VhostService<R, F> {
+    type Ready = ();
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {}
+}
+
+// host services share the same path. when any of them is a nested router prefer it's wildcard path so all
+// sub paths can be routed to Vhost.
+fn wildcard_path(a: String, b: String) -> String {
+    if b.ends_with("/*") {
+        b
+    } else {
+        a
+    }
+}
+
+fn request_host<Req>(req: &Req) -> Option<&str>
+where
+    Req: BorrowReq<Uri> + BorrowReq<HeaderMap>,
+{
+    let host = match BorrowReq::<Uri>::borrow(req).host() {
+        Some(host) => host,
+        None => {
+            let host = BorrowReq::<HeaderMap>::borrow(req).get(HOST)?.to_str().ok()?;
+            // strip port from host. ipv6 address is in form of "[::1]:8080".
+            match host.rfind(':') {
+                Some(i) if !host[i..].contains(']') => &host[..i],
+                _ => host,
+            }
+        }
+    };
+    Some(host.strip_suffix('.').unwrap_or(host))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct HostPattern {
+    host: &'static str,
+    wildcard: bool,
+}
+
+impl HostPattern {
+    fn new(pattern: &'static str) -> Self {
+        let (host, wildcard) = match pattern.strip_prefix("*.") {
+            Some(host) => (host, true),
+            None => (pattern, false),
+        };
+        assert!(!host.is_empty(), "Vhost host pattern can not be empty");
+        Self { host, wildcard }
+    }
+
+    fn matches(&self, host: &str) -> bool {
+        if !self.wildcard {
+            return host.eq_ignore_ascii_case(self.host);
+        }
+
+        host.len() > self.host.len() + 1 && {
+            let (sub, domain) = host.split_at(host.len() - self.host.len());
+            sub.ends_with('.') && domain.eq_ignore_ascii_case(self.host)
+        }
+    }
+
+    fn specificity(&self) -> (bool, usize) {
+        (!self.wildcard, self.host.len())
+    }
+}
+
+impl fmt::Display for HostPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.wildcard {
+            f.write_str("*.")?;
+        }
+        f.write_str(self.host)
+    }
+}
+
+/// a linked list of [Service] types registered to [Vhost].
+pub struct HostRoute<R, N> {
+    route: R,
+    next: N,
+}
+
+impl<R, N> PathGen for HostRoute<R, N>
+where
+    R: PathGen,
+    N: PathGen,
+{
+    fn path_gen(&mut self, prefix: &str) -> String {
+        let next = self.next.path_gen(prefix);
+        let route = self.route.path_gen(prefix);
+        wildcard_path(route, next)
+    }
+}
+
+impl<Arg, R, N> Service<Arg> for HostRoute<R, N>
+where
+    R: Service<Arg>,
+    N: Service<Arg, Error = R::Error>,
+    Arg: Clone,
+{
+    type Response = HostRouteService<R::Response, N::Response>;
+    type Error = R::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let route = self.route.call(arg.clone()).await?;
+        let next = self.next.call(arg).await?;
+        Ok(HostRouteService { route, next })
+    }
+}
+
+pub struct HostRouteService<R, N> {
+    route: R,
+    next: N,
+}
+
+impl<R, N, Req> Service<(usize, Req)> for HostRouteService<R, N>
+where
+    R: Service<Req>,
+    N: Service<(usize, Req), Response = R::Response, Error = R::Error>,
+{
+    type Response = R::Response;
+    type Error = R::Error;
+
+    #[inline]
+    async fn call(&self, (idx, req): (usize, Req)) -> Result<Self::Response, Self::Error> {
+        match idx {
+            0 => self.route.call(req).await,
+            idx => self.next.call((idx - 1, req)).await,
+        }
+    }
+}
+
+/// end of [HostRoute] list.
+pub struct HostRouteEnd<R>(PhantomData<fn(R)>);
+
+impl<R> PathGen for HostRouteEnd<R> {}
+
+impl<Arg, R> Service<Arg> for HostRouteEnd<R>
+where
+    R: Service<Arg>,
+{
+    type Response = HostRouteEndService<R::Response>;
+    type Error = R::Error;
+
+    async fn call(&self, _: Arg) -> Result<Self::Response, Self::Error> {
+        Ok(HostRouteEndService(PhantomData))
+    }
+}
+
+pub struct HostRouteEndService<R>(PhantomData<fn(R)>);
+
+impl<R, Req> Service<(usize, Req)> for HostRouteEndService<R>
+where
+    R: Service<Req>,
+{
+    type Response = R::Response;
+    type Error = R::Error;
+
+    async fn call(&self, _: (usize, Req)) -> Result<Self::Response, Self::Error> {
+        unreachable!("host index must be in range of registered routes")
+    }
+}
+
+/// user provided fallback [Service] type of [Vhost].
+pub struct Fallback<F>(F);
+
+impl<F> PathGen for Fallback<F>
+where
+    F: PathGen,
+{
+    fn path_gen(&mut self, prefix: &str) -> String {
+        self.0.path_gen(prefix)
+    }
+}
+
+impl<F, Req> Service<Req> for Fallback<F>
+where
+    F: Service<Req>,
+{
+    type Response = F::Response;
+    type Error = RouterError<F::Error>;
+
+    #[inline]
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        self.0.call(req).await.map_err(RouterError::Service)
+    }
+}
+
+/// default fallback of [Vhost] producing not found error.
+pub struct NotFound;
+
+impl PathGen for NotFound {}
+
+pub struct NotFoundService<R>(PhantomData<fn(R)>);
+
+impl<R, Req> Service<Req> for NotFoundService<R>
+where
+    R: Service<(usize, Req)>,
+{
+    type Response = R::Response;
+    type Error = RouterError<R::Error>;
+
+    async fn call(&self, _: Req) -> Result<Self::Response, Self::Error> {
+        Err(RouterError::Match(MatchError))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::{handler_service, state::StateRef},
+        http::{header::HeaderValue, Request, StatusCode, WebRequest},
+        test::collect_string_body,
+        App, WebContext,
+    };
+
+    use super::*;
+
+    fn req(host: &str) -> WebRequest {
+        let mut req = Request::default();
+        req.headers_mut().insert(HOST, HeaderValue::from_str(host).unwrap());
+        req
+    }
+
+    #[test]
+    fn pattern() {
+        let p = HostPattern::new("example.com");
+        assert!(p.matches("example.com"));
+        assert!(p.matches("EXAMPLE.com"));
+        assert!(!p.matches("foo.example.com"));
+
+        let p = HostPattern::new("*.example.com");
+        assert!(p.matches("foo.example.com"));
+        assert!(p.matches("foo.bar.Example.com"));
+        assert!(!p.matches("example.com"));
+        assert!(!p.matches(".example.com"));
+        assert!(!p.matches("fooexample.com"));
+    }
+
+    #[test]
+    fn vhost_app() {
+        async fn state(StateRef(state): StateRef<'_, &'static str>) -> &'static str {
+            state
+        }
+
+        let service = App::new()
+            .with_state("index")
+            .at(
+                "/",
+                Vhost::new()
+                    .host(
+                        "*.example.com",
+                        App::new().with_state("tenant").at("/", handler_service(state)),
+                    )
+                    .host(
+                        "api.example.com",
+                        App::new().at(
+                            "/api",
+                            handler_service(|_: &WebContext<'_, &'static str>| async { "api" }),
+                        ),
+                    )
+                    .fallback(handler_service(state)),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for (host, body) in [
+            ("api.example.com", "api"),
+            ("api.example.com:8080", "api"),
+            ("foo.example.com", "tenant"),
+            ("example.com", "index"),
+            ("[::1]:8080", "index"),
+        ] {
+            let mut req = req(host);
+            if body == "api" {
+                *req.uri_mut() = Uri::from_static("/api");
+            }
+            let res = service.call(req).now_or_panic().unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), body);
+        }
+
+        let mut req = Request::default();
+        *req.uri_mut() = Uri::from_static("http://api.example.com/api");
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "api");
+
+        let res = service.call(self::req("api.example.com")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn vhost_nest() {
+        let service = App::new()
+            .at(
+                "/",
+                Vhost::new()
+                    .host("foo.com", handler_service(|| async { "foo" }))
+                    .host("bar.com", handler_service(|| async { "bar" })),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(req("bar.com")).now_or_panic().unwrap();
+        assert_eq!(collect_string_body(res.into_body()).now_or_panic().unwrap(), "bar");
+
+        let res = service.call(req("baz.com")).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fmt, future::Future, sync::Arc, time::Duration};
+use std::{error, fmt, future::Future, pin::Pin, sync::Arc, time::Duration};
 
 use futures_core::stream::Stream;
 use xitca_http::{
@@ -6,7 +6,7 @@ use xitca_http::{
     config::{HttpServiceConfig, DEFAULT_HEADER_LIMIT, DEFAULT_READ_BUF_LIMIT, DEFAULT_WRITE_BUF_LIMIT},
     HttpServiceBuilder,
 };
-use xitca_server::{Builder, ServerFuture};
+use xitca_server::{filter::ConnectionFilter, Builder, ServerFuture};
 use xitca_service::ServiceExt;
 
 use crate::{
@@ -25,8 +25,13 @@ pub struct HttpServer<
     service: Arc<S>,
     builder: Builder,
     config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
+    on_worker_start: OnWorkerStart,
+    #[cfg(not(target_family = "wasm"))]
+    blocking_pool: Option<crate::handler::BlockingPool>,
 }
 
+type OnWorkerStart = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
 impl<S> HttpServer<S>
 where
     S: Send + Sync + 'static,
@@ -36,6 +41,9 @@ where
             service: Arc::new(service),
             builder: Builder::new(),
             config: HttpServiceConfig::default(),
+            on_worker_start: Arc::new(|| Box::pin(async {})),
+            #[cfg(not(target_family = "wasm"))]
+            blocking_pool: None,
         }
     }
 }
@@ -69,12 +77,27 @@ where
 
     /// Set max number of threads for each worker's blocking task thread pool.
     ///
-    /// One thread pool is set up **per worker**; not shared across workers.
+    /// One thread pool is set up **per worker**; not shared across workers. For a thread pool dedicated to
+    /// certain handlers see [BlockingPool](crate::handler::BlockingPool).
     pub fn worker_max_blocking_threads(mut self, num: usize) -> Self {
         self.builder = self.builder.worker_max_blocking_threads(num);
         self
     }
 
+    /// Set number of threads for a [BlockingPool](crate::handler::BlockingPool) shared by all workers.
+    ///
+    /// When set [handler_sync_service](crate::handler::handler_sync_service) runs on this dedicated pool instead of
+    /// worker's blocking task thread pool. It keeps cpu heavy sync handlers from starving other blocking tasks like
+    /// file io.
+    ///
+    /// # Panics:
+    /// When received 0 as number of threads.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn blocking_threads(mut self, num: usize) -> Self {
+        self.blocking_pool = Some(crate::handler::BlockingPool::new(num));
+        self.set_on_worker_start()
+    }
+
     /// Disable signal listening.
     ///
     /// `tokio::signal` is used for listening and it only functions in tokio runtime 1.x.
@@ -89,6 +112,33 @@ where
         self
     }
 
+    /// Set filter for accepted connections. Filter runs before any tls and http handling happen.
+    ///
+    /// See [xitca_server::filter] module for detail.
+    pub fn connection_filter<F>(mut self, filter: F) -> Self
+    where
+        F: ConnectionFilter,
+    {
+        self.builder = self.builder.connection_filter(filter);
+        self
+    }
+
+    /// Set max number of concurrent connections served by all workers. Accepting is paused when limit is reached.
+    ///
+    /// See [Builder::max_connections](xitca_server::Builder::max_connections) for detail.
+    pub fn max_connections(mut self, num: usize) -> Self {
+        self.builder = self.builder.max_connections(num);
+        self
+    }
+
+    /// Set max number of concurrent connections served by each worker. Accepting is paused when limit is reached.
+    ///
+    /// See [Builder::max_connections_per_worker](xitca_server::Builder::max_connections_per_worker) for detail.
+    pub fn max_connections_per_worker(mut self, num: usize) -> Self {
+        self.builder = self.builder.max_connections_per_worker(num);
+        self
+    }
+
     /// Disable vectored write even when IO is able to perform it.
     ///
     /// This is beneficial when dealing with small size of response body.
@@ -115,6 +165,66 @@ where
         self
     }
 
+    /// Change policy for Http/1 request with `Expect: 100-continue` header.
+    ///
+    /// Request advertising body size over the limit of policy is rejected before it's body is read.
+    /// See [ExpectPolicy](crate::config::ExpectPolicy) for detail.
+    pub fn expect_policy(mut self, policy: crate::config::ExpectPolicy) -> Self {
+        self.config = self.config.expect_policy(policy);
+        self
+    }
+
+    /// Change max size in bytes of response body a Http/2 stream can send before yielding to other
+    /// streams on the same connection.
+    ///
+    /// See [HttpServiceConfig::h2_fairness_window](xitca_http::config::HttpServiceConfig::h2_fairness_window)
+    /// for detail.
+    pub fn h2_fairness_window(mut self, size: usize) -> Self {
+        self.config = self.config.h2_fairness_window(size);
+        self
+    }
+
+    /// Change limits of Http/1 request head. Request exceeding limit is rejected with
+    /// `431 Request Header Fields Too Large`.
+    ///
+    /// See [HeaderLimits](xitca_http::config::HeaderLimits) for detail.
+    pub fn header_limits(mut self, limits: xitca_http::config::HeaderLimits) -> Self {
+        self.config = self.config.header_limits(limits);
+        self
+    }
+
+    /// Change counters of Http/1 request rejected for exceeding limit of request head.
+    ///
+    /// See [HeaderLimitMetrics](xitca_http::config::HeaderLimitMetrics) for detail.
+    pub fn header_limit_metrics(mut self, metrics: &'static xitca_http::config::HeaderLimitMetrics) -> Self {
+        self.config = self.config.header_limit_metrics(metrics);
+        self
+    }
+
+    /// Change casing of header names in Http/1 response head. For working with legacy clients expecting
+    /// specific casing.
+    ///
+    /// See [HeaderCase](xitca_http::config::HeaderCase) for detail.
+    pub fn header_case(mut self, case: xitca_http::config::HeaderCase) -> Self {
+        self.config = self.config.header_case(case);
+        self
+    }
+
+    /// Enable peer disconnect signal for Http/1 connection. Handlers can observe peer closing connection with
+    /// [Disconnect](crate::handler::disconnect::Disconnect) extractor.
+    pub fn disconnect_signal(mut self) -> Self {
+        self.config = self.config.disconnect_signal();
+        self
+    }
+
+    /// Enable informational(1xx) responses for Http/1.1 connection. Handlers can send interim responses like
+    /// `103 Early Hints` ahead of final response with [Informational](xitca_http::informational::Informational)
+    /// from request extensions.
+    pub fn informational_responses(mut self) -> Self {
+        self.config = self.config.informational_responses();
+        self
+    }
+
     /// Change tls accept timeout for Http/1 and Http/2 connection.
     ///
     /// Connection can not finish tls handshake for this duration would be closed.
@@ -162,7 +272,27 @@ where
         FS: Fn() -> Fut + Send + Sync + 'static,
         Fut: Future + Send + 'static,
     {
-        self.builder = self.builder.on_worker_start(on_start);
+        self.on_worker_start = Arc::new(move || {
+            let fut = on_start();
+            Box::pin(async {
+                fut.await;
+            })
+        });
+        self.set_on_worker_start()
+    }
+
+    // worker start callback of server builder is composed from user callback and blocking pool setup.
+    fn set_on_worker_start(mut self) -> Self {
+        let on_start = self.on_worker_start.clone();
+        #[cfg(not(target_family = "wasm"))]
+        let pool = self.blocking_pool.clone();
+        self.builder = self.builder.on_worker_start(move || {
+            #[cfg(not(target_family = "wasm"))]
+            if let Some(pool) = pool.clone() {
+                pool.set_current();
+            }
+            on_start()
+        });
         self
     }
 
@@ -172,7 +302,7 @@ where
         A: std::net::ToSocketAddrs,
         S: Service + 'static,
         S::Response: ReadyService + Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>> + 'static,
-        S::Error: fmt::Debug,
+        S::Error: Into<Box<dyn error::Error + Send + Sync>>,
         <S::Response as Service<Request<RequestExt<RequestBody>>>>::Error: fmt::Debug,
 
         ResB: Stream<Item = Result<Bytes, BE>> + 'static,
@@ -188,7 +318,7 @@ where
     where
         S: Service + 'static,
         S::Response: ReadyService + Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>> + 'static,
-        S::Error: fmt::Debug,
+        S::Error: Into<Box<dyn error::Error + Send + Sync>>,
         <S::Response as Service<Request<RequestExt<RequestBody>>>>::Error: fmt::Debug,
 
         ResB: Stream<Item = Result<Bytes, BE>> + 'static,
@@ -209,7 +339,7 @@ where
     where
         S: Service + 'static,
         S::Response: ReadyService + Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>> + 'static,
-        S::Error: fmt::Debug,
+        S::Error: Into<Box<dyn error::Error + Send + Sync>>,
         <S::Response as Service<Request<RequestExt<RequestBody>>>>::Error: fmt::Debug,
 
         ResB: Stream<Item = Result<Bytes, BE>> + 'static,
@@ -266,7 +396,7 @@ where
     where
         S: Service + 'static,
         S::Response: ReadyService + Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>> + 'static,
-        S::Error: fmt::Debug,
+        S::Error: Into<Box<dyn error::Error + Send + Sync>>,
         <S::Response as Service<Request<RequestExt<RequestBody>>>>::Error: fmt::Debug,
 
         ResB: Stream<Item = Result<Bytes, BE>> + 'static,
@@ -297,7 +427,7 @@ where
     where
         S: Service + 'static,
         S::Response: ReadyService + Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>> + 'static,
-        S::Error: fmt::Debug,
+        S::Error: Into<Box<dyn error::Error + Send + Sync>>,
         <S::Response as Service<Request<RequestExt<RequestBody>>>>::Error: fmt::Debug,
 
         ResB: Stream<Item = Result<Bytes, BE>> + 'static,
@@ -322,6 +452,9 @@ where
             config: self
                 .config
                 .mutate_const_generic::<HEADER_LIMIT2, READ_BUF_LIMIT2, WRITE_BUF_LIMIT2>(),
+            on_worker_start: self.on_worker_start,
+            #[cfg(not(target_family = "wasm"))]
+            blocking_pool: self.blocking_pool,
         }
     }
 }
@@ -57,6 +57,37 @@ where
             inner: _ServeDir::with_fs(path, fs),
         }
     }
+
+    /// serve pre-compressed `.br` sibling file of requested file when client accepts br encoding.
+    /// useful for immutable assets compressed ahead of time and avoid the cost of compressing on the fly.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use xitca_web::{
+    /// #     handler::{handler_service},
+    /// #     service::file::ServeDir,
+    /// #     App, WebContext
+    /// # };
+    /// App::new()
+    ///     // request to /assets/app.js would be served with ./static/app.js.br or ./static/app.js.gz file
+    ///     // depending on request's Accept-Encoding header. fall back to ./static/app.js when no sibling
+    ///     // file matches.
+    ///     .at("/assets", ServeDir::new("static").precompressed_br().precompressed_gzip())
+    ///     # .at("/bar", handler_service(|_: &WebContext<'_>| async { "used for inferring types!" }));
+    /// ```
+    ///
+    /// See [http_file::ServeDir::precompressed_br] for detail.
+    pub fn precompressed_br(mut self) -> Self {
+        self.inner.precompressed_br();
+        self
+    }
+
+    /// serve pre-compressed `.gz` sibling file of requested file when client accepts gzip encoding.
+    /// See [ServeDir::precompressed_br] for detail.
+    pub fn precompressed_gzip(mut self) -> Self {
+        self.inner.precompressed_gzip();
+        self
+    }
 }
 
 impl<F> PathGen for ServeDir<F>